@@ -0,0 +1,26 @@
+-- First-class work sessions opened/closed by the daemon.
+-- Captures and activities link to the session they were recorded in.
+CREATE TABLE sessions (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    device TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at TIMESTAMPTZ,
+    app_summary JSONB NOT NULL DEFAULT '[]'
+);
+
+CREATE INDEX idx_sessions_user ON sessions (user_id, started_at DESC);
+
+ALTER TABLE captures
+    ADD COLUMN IF NOT EXISTS session_id BIGINT REFERENCES sessions(id) ON DELETE SET NULL;
+
+ALTER TABLE activities
+    ADD COLUMN IF NOT EXISTS session_id BIGINT REFERENCES sessions(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_captures_session
+    ON captures (session_id, captured_at DESC)
+    WHERE session_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_activities_session
+    ON activities (session_id, timestamp DESC)
+    WHERE session_id IS NOT NULL;
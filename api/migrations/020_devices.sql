@@ -0,0 +1,23 @@
+-- Machines running the daemon for a user. Registered when the daemon enrolls
+-- its API token and refreshed on every authenticated daemon request.
+CREATE TABLE devices (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    device_uid TEXT NOT NULL,           -- stable client-generated identifier
+    name TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ,
+    UNIQUE (user_id, device_uid)
+);
+
+CREATE INDEX idx_devices_user ON devices (user_id, last_seen_at DESC);
+
+ALTER TABLE captures
+    ADD COLUMN IF NOT EXISTS device_id BIGINT REFERENCES devices(id) ON DELETE SET NULL;
+
+ALTER TABLE activities
+    ADD COLUMN IF NOT EXISTS device_id BIGINT REFERENCES devices(id) ON DELETE SET NULL;
+
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS device_id BIGINT REFERENCES devices(id) ON DELETE SET NULL;
@@ -0,0 +1,18 @@
+-- Interval ids are only unique per daemon install: two machines (or one machine
+-- after a restart) can report the same interval. Scope interval lookups by
+-- device so captures and activities from different machines never group together.
+
+DROP INDEX IF EXISTS idx_captures_interval;
+DROP INDEX IF EXISTS idx_activities_interval;
+
+CREATE INDEX IF NOT EXISTS idx_captures_device_interval
+    ON captures (user_id, device_id, interval_id, captured_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_activities_device_interval
+    ON activities (user_id, device_id, interval_id, timestamp DESC);
+
+ALTER TABLE captures
+    ADD CONSTRAINT captures_interval_id_nonnegative CHECK (interval_id >= 0);
+
+ALTER TABLE activities
+    ADD CONSTRAINT activities_interval_id_nonnegative CHECK (interval_id >= 0);
@@ -0,0 +1,25 @@
+-- Partition captures and activities by month.
+-- Both are TimescaleDB hypertables, so "partitions" are chunks: this only changes
+-- the interval used for chunks created from now on. TimescaleDB creates each
+-- month's chunk on the first insert into that month.
+SELECT set_chunk_time_interval('captures', INTERVAL '1 month');
+SELECT set_chunk_time_interval('activities', INTERVAL '1 month');
+
+-- Monthly archive jobs: export a month's rows and media to storage, then drop it.
+CREATE TABLE capture_archives (
+    id BIGSERIAL PRIMARY KEY,
+    month DATE NOT NULL UNIQUE,             -- first day of the archived month (UTC)
+    status TEXT NOT NULL DEFAULT 'pending', -- pending, exporting, completed, failed
+    archive_path TEXT,                      -- storage prefix holding the export
+    capture_count BIGINT NOT NULL DEFAULT 0,
+    activity_count BIGINT NOT NULL DEFAULT 0,
+    attempts INT NOT NULL DEFAULT 0,
+    lease_until TIMESTAMPTZ,
+    error TEXT,
+    requested_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_capture_archives_claimable ON capture_archives (requested_at)
+    WHERE status IN ('pending', 'exporting');
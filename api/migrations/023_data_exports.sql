@@ -0,0 +1,22 @@
+-- User data exports (takeout): a zip of everything we store for a user
+CREATE TABLE data_exports (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending', -- pending, building, completed, failed, expired
+    archive_path TEXT,                      -- storage path of the zip
+    size_bytes BIGINT,
+    attempts INT NOT NULL DEFAULT 0,
+    lease_until TIMESTAMPTZ,
+    error TEXT,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_data_exports_user ON data_exports (user_id, requested_at DESC);
+CREATE INDEX idx_data_exports_claimable ON data_exports (requested_at)
+    WHERE status IN ('pending', 'building');
+
+-- At most one export in flight per user
+CREATE UNIQUE INDEX idx_data_exports_one_active ON data_exports (user_id)
+    WHERE status IN ('pending', 'building');
@@ -0,0 +1,2 @@
+-- Captures the user wants kept but never sent to the content agent / LLM
+ALTER TABLE captures ADD COLUMN excluded_from_agent BOOLEAN NOT NULL DEFAULT FALSE;
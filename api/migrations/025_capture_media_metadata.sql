@@ -0,0 +1,8 @@
+-- Media properties recorded at ingest / first frame-worker pass so later
+-- consumers don't need to ffprobe the original again
+ALTER TABLE captures
+    ADD COLUMN duration_secs DOUBLE PRECISION,
+    ADD COLUMN width INT,
+    ADD COLUMN height INT,
+    ADD COLUMN codec TEXT,
+    ADD COLUMN file_size_bytes BIGINT;
@@ -0,0 +1,3 @@
+-- SHA-256 of the uploaded bytes (hex), verified at ingest and kept for
+-- later integrity audits of stored objects
+ALTER TABLE captures ADD COLUMN sha256 TEXT;
@@ -0,0 +1,19 @@
+-- Discrepancies between stored objects and the captures table, found by the
+-- storage audit worker. One open row per (kind, path); rows not seen again in
+-- a later run are resolved automatically.
+CREATE TABLE storage_audit (
+    id BIGSERIAL PRIMARY KEY,
+    kind TEXT NOT NULL
+        CHECK (kind IN ('orphaned_object', 'missing_object', 'missing_thumbnail', 'size_mismatch')),
+    path TEXT NOT NULL,
+    capture_id BIGINT,
+    captured_at TIMESTAMPTZ,
+    detail TEXT,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+    resolution TEXT
+);
+
+CREATE UNIQUE INDEX idx_storage_audit_open ON storage_audit (kind, path) WHERE resolved_at IS NULL;
+CREATE INDEX idx_storage_audit_last_seen ON storage_audit (last_seen_at DESC);
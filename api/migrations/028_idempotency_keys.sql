@@ -0,0 +1,15 @@
+-- Idempotency-Key claims for publish and upload endpoints. A row is claimed
+-- before the handler runs; successful responses are stored so a retried
+-- request gets the original result instead of running again.
+CREATE TABLE idempotency_keys (
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    status_code SMALLINT,
+    response_body JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    PRIMARY KEY (user_id, key)
+);
+
+CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys (created_at);
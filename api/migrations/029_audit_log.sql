@@ -0,0 +1,16 @@
+-- Security-relevant account actions (logins, API token generation, publishes,
+-- deletions, settings changes), shown to users at GET /me/audit-log.
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- 'web' (session cookie), 'daemon' (API token) or 'system'
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    target TEXT,
+    detail JSONB,
+    ip TEXT,
+    user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_log_user_created ON audit_log (user_id, created_at DESC, id DESC);
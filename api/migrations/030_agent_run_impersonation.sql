@@ -0,0 +1,4 @@
+-- Admin who started the run on the user's behalf (POST /admin/agent/run);
+-- NULL for runs the user or the scheduler started.
+ALTER TABLE agent_runs
+    ADD COLUMN triggered_by_admin BIGINT REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,4 @@
+-- Subscription tier, selecting the capture ingestion quotas in config [quotas]
+ALTER TABLE users
+    ADD COLUMN tier TEXT NOT NULL DEFAULT 'free'
+        CHECK (tier IN ('free', 'pro'));
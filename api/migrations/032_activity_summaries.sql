@@ -0,0 +1,24 @@
+-- Per-interval activity rollups computed by the daemon (POST /activity/summary).
+-- One row per device and 5-minute interval replaces the raw click rows, while
+-- keeping what the agent uses: the main app, how often the user switched apps
+-- and clicked, and how long they were actually active.
+
+CREATE TABLE activity_summaries (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    device_id BIGINT REFERENCES devices(id) ON DELETE SET NULL,
+    session_id BIGINT REFERENCES sessions(id) ON DELETE SET NULL,
+    interval_id BIGINT NOT NULL CHECK (interval_id >= 0),
+    period_start TIMESTAMPTZ NOT NULL,
+    period_end TIMESTAMPTZ NOT NULL,
+    top_app TEXT,
+    app_switches INT NOT NULL DEFAULT 0 CHECK (app_switches >= 0),
+    clicks INT NOT NULL DEFAULT 0 CHECK (clicks >= 0),
+    keypresses INT NOT NULL DEFAULT 0 CHECK (keypresses >= 0),
+    active_secs INT NOT NULL DEFAULT 0 CHECK (active_secs >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (period_end > period_start)
+);
+
+CREATE INDEX idx_activity_summaries_user_period
+    ON activity_summaries (user_id, period_start DESC);
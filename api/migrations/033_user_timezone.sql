@@ -0,0 +1,4 @@
+-- IANA time zone used for the user's day buckets (storage paths, browse and
+-- session day filters) and for the times shown to the content agent
+ALTER TABLE users
+    ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
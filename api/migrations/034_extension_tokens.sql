@@ -0,0 +1,23 @@
+-- Tokens for the companion browser extension. Unlike the daemon API token they
+-- carry a scope and can only call the endpoints that scope allows (today just
+-- POST /extension/tab). Only the SHA-256 of each token is stored.
+CREATE TABLE extension_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    scope TEXT NOT NULL DEFAULT 'tab_context' CHECK (scope IN ('tab_context')),
+    name TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_extension_tokens_user ON extension_tokens (user_id, created_at DESC);
+
+-- Sites whose tabs are never recorded (matched on the host and its subdomains)
+ALTER TABLE users
+    ADD COLUMN blocked_domains TEXT[] NOT NULL DEFAULT '{}';
+
+-- Page URL for TabContext activities (query string and fragment stripped)
+ALTER TABLE activities
+    ADD COLUMN url TEXT;
@@ -0,0 +1,17 @@
+-- Corrections made to already-posted tweets. X can't edit a tweet through the
+-- API, so a correction deletes the posted tweet and posts the new text; each
+-- row keeps what was replaced.
+CREATE TABLE tweet_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    tweet_collateral_id BIGINT NOT NULL REFERENCES tweet_collateral(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    previous_text TEXT NOT NULL,
+    previous_tweet_id TEXT NOT NULL,
+    new_text TEXT NOT NULL,
+    new_tweet_id TEXT NOT NULL,
+    -- False when X refused the delete and the old tweet may still be up
+    previous_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_tweet_revisions_tweet ON tweet_revisions (tweet_collateral_id, created_at DESC);
@@ -0,0 +1,5 @@
+-- Tweets composed as a reply to, or a quote of, an existing tweet. The URL is
+-- what the user pasted; the tweet ID is parsed from it when publishing.
+ALTER TABLE tweet_collateral
+    ADD COLUMN in_reply_to_url TEXT,
+    ADD COLUMN quote_tweet_url TEXT;
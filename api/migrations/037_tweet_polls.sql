@@ -0,0 +1,9 @@
+-- Tweets that carry a poll. X allows 2-4 options of at most 25 characters,
+-- open for 5 minutes to 7 days; a poll can't be combined with media or a quote.
+ALTER TABLE tweet_collateral
+    ADD COLUMN poll_options TEXT[],
+    ADD COLUMN poll_duration INT,
+    ADD CONSTRAINT tweet_collateral_poll_complete
+        CHECK ((poll_options IS NULL) = (poll_duration IS NULL)),
+    ADD CONSTRAINT tweet_collateral_poll_duration
+        CHECK (poll_duration BETWEEN 5 AND 10080);
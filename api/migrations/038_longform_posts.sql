@@ -0,0 +1,20 @@
+-- Long-form posts for X accounts with a subscription that allows posts past
+-- 280 characters. Published through their own path so the tweet limits and
+-- media handling stay untouched.
+CREATE TABLE longform_posts (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- For the user's own organization; not posted
+    title TEXT,
+    body TEXT NOT NULL,
+    rationale TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    publish_status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (publish_status IN ('pending', 'posting', 'posted', 'failed', 'dismissed')),
+    publish_error TEXT,
+    posted_at TIMESTAMPTZ,
+    tweet_id TEXT,
+    dismissed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_longform_posts_user ON longform_posts (user_id, created_at DESC);
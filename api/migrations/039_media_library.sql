@@ -0,0 +1,29 @@
+-- Reusable media the user uploads themselves (logos, banners, pre-made
+-- screenshots), kept apart from daemon captures so it never counts against
+-- capture quotas or shows up for the content agent. Objects live under
+-- library/user_<id>/ in storage.
+CREATE TABLE media_folders (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+CREATE TABLE media_assets (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Deleting a folder moves its assets back to the top level
+    folder_id BIGINT REFERENCES media_folders(id) ON DELETE SET NULL,
+    name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    gcs_path TEXT NOT NULL UNIQUE,
+    file_size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_media_assets_user ON media_assets (user_id, folder_id, created_at DESC);
+
+-- Library images attached to a tweet, posted after its capture images
+ALTER TABLE tweet_collateral
+    ADD COLUMN library_asset_ids BIGINT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,9 @@
+-- Branding watermark composited onto images and videos when they're published.
+-- The logo is a media library image; deleting it switches branding off.
+ALTER TABLE users
+    ADD COLUMN branding_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN branding_asset_id BIGINT REFERENCES media_assets(id) ON DELETE SET NULL,
+    ADD COLUMN branding_position TEXT NOT NULL DEFAULT 'bottom_right'
+        CHECK (branding_position IN ('top_left', 'top_right', 'bottom_left', 'bottom_right')),
+    ADD COLUMN branding_opacity REAL NOT NULL DEFAULT 0.8
+        CHECK (branding_opacity > 0 AND branding_opacity <= 1);
@@ -0,0 +1,5 @@
+-- Dry runs (POST /agent/run with dry_run, or AGENT_DRY_RUN for the scheduler)
+-- run the full pipeline but save no collateral. They never move the window
+-- cursor for real runs.
+ALTER TABLE agent_runs
+    ADD COLUMN dry_run BOOLEAN NOT NULL DEFAULT FALSE;
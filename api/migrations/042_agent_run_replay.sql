@@ -0,0 +1,4 @@
+-- Re-runs over a historical window (POST /agent/run with window_start and
+-- window_end). Like dry runs, they never move the window cursor.
+ALTER TABLE agent_runs
+    ADD COLUMN replay BOOLEAN NOT NULL DEFAULT FALSE;
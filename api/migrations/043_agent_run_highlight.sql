@@ -0,0 +1,4 @@
+-- Focused runs over a clip the daemon flagged as a highlight
+-- (POST /agent/highlight). Like replays, they never move the window cursor.
+ALTER TABLE agent_runs
+    ADD COLUMN highlight BOOLEAN NOT NULL DEFAULT FALSE;
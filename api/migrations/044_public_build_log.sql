@@ -0,0 +1,7 @@
+-- Public build log (GET /public/:username/log): posted tweets plus captures the
+-- user picked, shown only once the user opts in.
+ALTER TABLE users ADD COLUMN public_log_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE captures ADD COLUMN in_public_log BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_captures_public_log ON captures (user_id, captured_at DESC)
+    WHERE in_public_log;
@@ -0,0 +1,17 @@
+-- Expiring, revocable links to a single capture (POST /captures/:id/share).
+-- Anyone with the link can view the capture at GET /shared/:token until it
+-- expires or is revoked. Only the SHA-256 of each token is stored.
+CREATE TABLE capture_shares (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- captures is a hypertable, so no foreign key; lookups join on it
+    capture_id BIGINT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    view_count INTEGER NOT NULL DEFAULT 0,
+    last_viewed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_capture_shares_capture ON capture_shares (user_id, capture_id, created_at DESC);
@@ -0,0 +1,17 @@
+-- Review comments on tweet collateral (POST /content/:id/comments). Replies
+-- point at a top-level comment; resolving the top-level comment resolves its
+-- thread. `mentions` holds the lowercased @handles found in the body.
+CREATE TABLE collateral_comments (
+    id BIGSERIAL PRIMARY KEY,
+    tweet_collateral_id BIGINT NOT NULL REFERENCES tweet_collateral(id) ON DELETE CASCADE,
+    parent_id BIGINT REFERENCES collateral_comments(id) ON DELETE CASCADE,
+    author_user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    mentions TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_collateral_comments_tweet ON collateral_comments (tweet_collateral_id, created_at);
+CREATE INDEX idx_collateral_comments_unresolved ON collateral_comments (tweet_collateral_id)
+    WHERE parent_id IS NULL AND resolved_at IS NULL;
@@ -0,0 +1,24 @@
+-- Nightly daily-summary export to a notes app (Notion, a WebDAV vault, or a
+-- folder under the server's vault root). One destination per user; secrets are
+-- encrypted like OAuth tokens. last_exported_date is the user's local date of
+-- the last day written, so each day is exported once.
+CREATE TABLE summary_exports (
+    user_id BIGINT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    destination TEXT NOT NULL CHECK (destination IN ('notion', 'webdav', 'local')),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    notion_token TEXT,
+    notion_parent_page_id TEXT,
+    webdav_url TEXT,
+    webdav_username TEXT,
+    webdav_password TEXT,
+    -- Relative to user_<id>/ under workers.daily_summary.vault_root
+    local_dir TEXT,
+    last_exported_date DATE,
+    last_error TEXT,
+    lease_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_summary_exports_enabled ON summary_exports (last_exported_date)
+    WHERE enabled;
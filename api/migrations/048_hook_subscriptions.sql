@@ -0,0 +1,19 @@
+-- REST hook subscriptions for no-code tools (Zapier, Make). A subscription
+-- receives one POST per new event; cursor_id is the last tweet_collateral id
+-- delivered, so deliveries resume where they stopped after a failure.
+CREATE TABLE hook_subscriptions (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event TEXT NOT NULL CHECK (event IN ('new_collateral')),
+    target_url TEXT NOT NULL,
+    cursor_id BIGINT NOT NULL DEFAULT 0,
+    failures INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    disabled_at TIMESTAMPTZ,
+    lease_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, event, target_url)
+);
+
+CREATE INDEX idx_hook_subscriptions_active ON hook_subscriptions (id)
+    WHERE disabled_at IS NULL;
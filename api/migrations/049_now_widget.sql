@@ -0,0 +1,4 @@
+-- "Now building" widget (GET /public/:username/now.json): latest posted tweets
+-- and posting streak, shown only once the user opts in. Separate from the
+-- public build log so a user can embed one without publishing the other.
+ALTER TABLE users ADD COLUMN now_widget_enabled BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,10 @@
+-- Which kinds of data the agent may read for each user (GET/PUT
+-- /me/agent-data-policy). Screenshots, video recordings and activity text
+-- (app, window and URL events plus activity summaries) are switched
+-- separately. Each run records the policy it ran under.
+ALTER TABLE users
+    ADD COLUMN agent_allow_images BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN agent_allow_video BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN agent_allow_activity BOOLEAN NOT NULL DEFAULT TRUE;
+
+ALTER TABLE agent_runs ADD COLUMN data_policy JSONB;
@@ -0,0 +1,11 @@
+-- Compliance filter on generated copy: tweets whose text mentions profanity,
+-- slurs, a confidential codename or a competitor are saved with needs_review
+-- set and the reason attached. Codenames and competitors are per user
+-- (GET/PUT /me/copy-filter).
+ALTER TABLE tweet_collateral
+    ADD COLUMN needs_review BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN review_reason TEXT;
+
+ALTER TABLE users
+    ADD COLUMN confidential_terms TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN competitor_terms TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,25 @@
+-- Tracked links for tweets (POST /content/:id/links). GET /l/:slug redirects
+-- to the target and logs the click; GET /content/:id/clicks aggregates them.
+-- Clicks keep only coarse data: the referring host and a user agent class.
+CREATE TABLE tracked_links (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tweet_collateral_id BIGINT NOT NULL REFERENCES tweet_collateral(id) ON DELETE CASCADE,
+    slug TEXT NOT NULL UNIQUE,
+    target_url TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_tracked_links_tweet ON tracked_links (tweet_collateral_id);
+
+CREATE TABLE link_clicks (
+    id BIGSERIAL PRIMARY KEY,
+    link_id BIGINT NOT NULL REFERENCES tracked_links(id) ON DELETE CASCADE,
+    clicked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Host of the Referer header, if any
+    referrer TEXT,
+    -- 'desktop', 'mobile', 'bot' or 'other'
+    agent TEXT NOT NULL
+);
+
+CREATE INDEX idx_link_clicks_link ON link_clicks (link_id, clicked_at);
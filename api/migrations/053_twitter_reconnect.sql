@@ -0,0 +1,5 @@
+-- Set when X rejects a user's refresh token (invalid_grant: app access was
+-- revoked or the token expired), so publishing stops failing silently. Cleared
+-- whenever new tokens are stored at login or on refresh.
+ALTER TABLE users
+    ADD COLUMN needs_reconnect BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,15 @@
+-- Server-handled OAuth callback (GET /auth/twitter/callback). Each login
+-- remembers which device started it with its PKCE verifier; daemon logins end
+-- on a page showing a one-time enrollment code that the daemon trades for its
+-- API token at POST /auth/daemon/enroll. Only the code's hash is stored.
+ALTER TABLE oauth_states
+    ADD COLUMN device TEXT NOT NULL DEFAULT 'web';
+
+CREATE TABLE enrollment_codes (
+    code_hash TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_enrollment_codes_expires ON enrollment_codes (expires_at);
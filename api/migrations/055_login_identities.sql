@@ -0,0 +1,29 @@
+-- GitHub and Google logins. An account no longer has to come from X: a login
+-- identity (provider plus the provider's user id) can own it instead, and X
+-- becomes an integration linked from /me/integrations. Accounts without X
+-- have no twitter_* columns or tokens.
+CREATE TABLE login_identities (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,
+    provider_user_id TEXT NOT NULL,
+    -- GitHub login or Google email, shown as the account handle until X is linked
+    username TEXT NOT NULL,
+    email TEXT,
+    name TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (provider, provider_user_id),
+    UNIQUE (user_id, provider)
+);
+
+ALTER TABLE users
+    ALTER COLUMN twitter_id DROP NOT NULL,
+    ALTER COLUMN twitter_username DROP NOT NULL,
+    ALTER COLUMN access_token DROP NOT NULL,
+    ALTER COLUMN token_expires_at DROP NOT NULL;
+
+-- Pending logins remember their provider; linking X to a signed-in account
+-- remembers the account
+ALTER TABLE oauth_states
+    ADD COLUMN provider TEXT NOT NULL DEFAULT 'twitter',
+    ADD COLUMN link_user_id BIGINT REFERENCES users(id) ON DELETE CASCADE;
@@ -0,0 +1,13 @@
+-- Email sign-in links. A link signs in the account owning the address's
+-- `email` login identity, creating one on first use; links sent from a
+-- signed-in session (link_user_id) verify the address and attach it to that
+-- account instead. Only the token's hash is stored.
+CREATE TABLE email_login_tokens (
+    token_hash TEXT PRIMARY KEY,
+    email TEXT NOT NULL,
+    link_user_id BIGINT REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_email_login_tokens_expires ON email_login_tokens (expires_at);
@@ -0,0 +1,14 @@
+-- Per-user notification preferences (GET/PUT /me/notifications), checked by
+-- every sender before it notifies. `muted` lists the channel/event pairs the
+-- user switched off as 'channel.event' (e.g. 'push.content'); anything not
+-- listed is on, so channels and event types added later start on. Quiet hours
+-- are local times in the user's zone and may wrap past midnight. No row means
+-- everything on and no quiet hours.
+CREATE TABLE notification_preferences (
+    user_id BIGINT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    muted TEXT[] NOT NULL DEFAULT '{}',
+    quiet_hours_start TIME,
+    quiet_hours_end TIME,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((quiet_hours_start IS NULL) = (quiet_hours_end IS NULL))
+);
@@ -0,0 +1,6 @@
+-- Language the agent writes drafts in (GET/PUT /me/language), as a code from
+-- services::language. A run can be started in another language
+-- (POST /agent/run `language`); each run records the one it used.
+ALTER TABLE users ADD COLUMN draft_language TEXT NOT NULL DEFAULT 'en';
+
+ALTER TABLE agent_runs ADD COLUMN language TEXT;
@@ -0,0 +1,27 @@
+-- A/B copy experiments (PUT /tweets/:id/experiment). A pending draft carries
+-- two copy variants, each labelled with the style it tries ("question hook",
+-- "result first", ...); the draft's text becomes variant A, which is what gets
+-- posted. Once the post is 24 hours old the metrics sync stores its public
+-- metrics, and GET /insights/experiments compares styles across measured
+-- experiments.
+CREATE TABLE copy_experiments (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tweet_collateral_id BIGINT NOT NULL UNIQUE REFERENCES tweet_collateral(id) ON DELETE CASCADE,
+    variant_a TEXT NOT NULL,
+    style_a TEXT NOT NULL,
+    variant_b TEXT NOT NULL,
+    style_b TEXT NOT NULL,
+    like_count BIGINT,
+    retweet_count BIGINT,
+    reply_count BIGINT,
+    quote_count BIGINT,
+    measured_at TIMESTAMPTZ,
+    last_error TEXT,
+    lease_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_copy_experiments_user ON copy_experiments (user_id, measured_at);
+CREATE INDEX idx_copy_experiments_unmeasured ON copy_experiments (tweet_collateral_id)
+    WHERE measured_at IS NULL;
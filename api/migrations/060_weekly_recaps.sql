@@ -0,0 +1,20 @@
+-- Weekly "week in review" thread drafts. Users without a row get the default
+-- schedule (Fridays from 17:00 local); a row is created the first time a recap
+-- is claimed or the schedule is changed (PUT /me/weekly-recap). weekday is ISO
+-- (1 = Monday). last_recap_on is the user's local date of the last recap, so
+-- each week is composed once.
+CREATE TABLE weekly_recaps (
+    user_id BIGINT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    weekday SMALLINT NOT NULL DEFAULT 5 CHECK (weekday BETWEEN 1 AND 7),
+    hour SMALLINT NOT NULL DEFAULT 17 CHECK (hour BETWEEN 0 AND 23),
+    last_recap_on DATE,
+    last_thread_id BIGINT REFERENCES tweet_threads(id) ON DELETE SET NULL,
+    last_error TEXT,
+    lease_until TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Recap threads are drafts like any other, marked so the next recap doesn't
+-- summarize the last one
+ALTER TABLE tweet_threads ADD COLUMN weekly_recap_on DATE;
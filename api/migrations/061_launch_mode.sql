@@ -0,0 +1,4 @@
+-- Launch mode (POST /me/launch-mode): until this time the daemon is told to
+-- screenshot more often, the scheduler runs the agent after a shorter idle gap,
+-- and the agent prioritizes metrics dashboards and analytics screens.
+ALTER TABLE users ADD COLUMN launch_mode_until TIMESTAMPTZ;
@@ -0,0 +1,30 @@
+-- Build, test and benchmark results read from terminal captures. The
+-- dev_signals worker OCRs captures taken while a terminal app was focused and
+-- stores what it recognizes ("cargo build finished in 19.3s", "42 passed,
+-- 1 failed") so the agent can quote real numbers. captures is a hypertable, so
+-- capture_id isn't a foreign key; archives drop a month's rows with its captures.
+CREATE TABLE dev_signals (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    capture_id BIGINT NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('build', 'test', 'benchmark')),
+    -- cargo, pytest, vite, ...; NULL when the output doesn't say
+    tool TEXT,
+    -- Build or test run outcome; NULL for benchmarks
+    success BOOLEAN,
+    passed INT,
+    failed INT,
+    duration_secs DOUBLE PRECISION,
+    -- Benchmark name and its (mean or median) time
+    bench_name TEXT,
+    bench_value DOUBLE PRECISION,
+    bench_unit TEXT,
+    -- The OCR'd line the signal came from
+    excerpt TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_dev_signals_user_time ON dev_signals (user_id, captured_at DESC);
+
+ALTER TABLE captures ADD COLUMN dev_signals_scanned_at TIMESTAMPTZ;
@@ -0,0 +1,19 @@
+-- Projects inferred from window titles: repo names in editor, terminal and
+-- GitHub titles, and GitHub/GitLab tabs from the extension. Activity ingestion
+-- upserts a row per project and adds the foreground time it saw; the agent can
+-- tag collateral with one of them.
+CREATE TABLE user_projects (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Lowercased name, so "CleoApp" and "cleoapp" are one project
+    key TEXT NOT NULL,
+    -- Name as first seen
+    name TEXT NOT NULL,
+    last_branch TEXT,
+    active_secs BIGINT NOT NULL DEFAULT 0,
+    first_seen_at TIMESTAMPTZ NOT NULL,
+    last_seen_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (user_id, key)
+);
+
+ALTER TABLE tweet_collateral ADD COLUMN project TEXT;
@@ -0,0 +1,13 @@
+-- Per-project publishing defaults. Agent drafts tagged with a project get its
+-- hashtags appended while the tweet still fits, publishing a project's content
+-- is refused while a different X account than twitter_username is connected,
+-- and auto_generate = FALSE keeps the agent away from captures taken while the
+-- project was in the foreground.
+ALTER TABLE user_projects
+    ADD COLUMN hashtags TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN twitter_username TEXT,
+    ADD COLUMN auto_generate BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- Project inferred from a ForegroundSwitch's window title (user_projects.key),
+-- so captures can be matched to the project that was in the foreground
+ALTER TABLE activities ADD COLUMN project_key TEXT;
@@ -0,0 +1,44 @@
+-- Which captures each tweet uses, kept in step with tweet_collateral's
+-- image_capture_ids and video_clip.source_capture_id by a trigger, so every
+-- write path (agent, editor, threads) is covered. captures is a hypertable and
+-- can't be the target of a foreign key; the archive worker checks this table
+-- before dropping captures instead.
+CREATE TABLE tweet_captures (
+    tweet_id BIGINT NOT NULL REFERENCES tweet_collateral(id) ON DELETE CASCADE,
+    capture_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    PRIMARY KEY (tweet_id, capture_id)
+);
+
+CREATE INDEX idx_tweet_captures_capture ON tweet_captures (capture_id);
+
+CREATE FUNCTION sync_tweet_captures() RETURNS trigger AS $$
+BEGIN
+    DELETE FROM tweet_captures WHERE tweet_id = NEW.id;
+    INSERT INTO tweet_captures (tweet_id, capture_id, user_id)
+    SELECT DISTINCT NEW.id, ids.capture_id, NEW.user_id
+    FROM (
+        SELECT unnest(COALESCE(NEW.image_capture_ids, '{}')) AS capture_id
+        UNION ALL
+        SELECT (NEW.video_clip->>'source_capture_id')::BIGINT
+        WHERE jsonb_typeof(NEW.video_clip->'source_capture_id') = 'number'
+    ) ids
+    WHERE ids.capture_id IS NOT NULL;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER tweet_collateral_sync_captures
+    AFTER INSERT OR UPDATE OF image_capture_ids, video_clip ON tweet_collateral
+    FOR EACH ROW EXECUTE FUNCTION sync_tweet_captures();
+
+INSERT INTO tweet_captures (tweet_id, capture_id, user_id)
+SELECT DISTINCT t.id, ids.capture_id, t.user_id
+FROM tweet_collateral t
+CROSS JOIN LATERAL (
+    SELECT unnest(COALESCE(t.image_capture_ids, '{}')) AS capture_id
+    UNION ALL
+    SELECT (t.video_clip->>'source_capture_id')::BIGINT
+    WHERE jsonb_typeof(t.video_clip->'source_capture_id') = 'number'
+) ids
+WHERE ids.capture_id IS NOT NULL;
@@ -0,0 +1,9 @@
+-- Text search for GET /content?q=. The filter is a case-insensitive substring
+-- match (ILIKE '%q%'), which trigram GIN indexes can serve. The from/to date
+-- filters use the existing (user_id, created_at DESC) indexes.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_tweet_collateral_text_trgm ON tweet_collateral USING gin (text gin_trgm_ops);
+CREATE INDEX idx_tweet_threads_title_trgm ON tweet_threads USING gin (title gin_trgm_ops);
+CREATE INDEX idx_longform_posts_title_trgm ON longform_posts USING gin (title gin_trgm_ops);
+CREATE INDEX idx_longform_posts_body_trgm ON longform_posts USING gin (body gin_trgm_ops);
@@ -0,0 +1,14 @@
+-- Saved views for GET /content (POST /me/views): a named filter and sort the
+-- web app can switch between, like "Needs review" or "Top performers".
+-- filters holds the /content query parameters (status, project, q, from, to,
+-- has_media); dates are kept as written and resolved when the view is used.
+CREATE TABLE content_views (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    filters JSONB NOT NULL DEFAULT '{}',
+    sort TEXT NOT NULL DEFAULT 'created_at',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_content_views_user_name ON content_views (user_id, lower(name));
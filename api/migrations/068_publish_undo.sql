@@ -0,0 +1,14 @@
+-- Undo window for publishing. With publish_undo_secs set (PUT /me/publish-undo),
+-- POST /tweets/:id/publish marks the tweet 'queued' until publish_at instead of
+-- posting; DELETE /tweets/:id/publish puts it back to 'pending' before then,
+-- and the publish queue worker posts it once the window has passed.
+ALTER TABLE users
+    ADD COLUMN publish_undo_secs INT NOT NULL DEFAULT 0
+        CHECK (publish_undo_secs BETWEEN 0 AND 300);
+
+ALTER TABLE tweet_collateral
+    ADD COLUMN publish_at TIMESTAMPTZ;
+
+CREATE INDEX idx_tweet_collateral_publish_due
+    ON tweet_collateral (publish_at)
+    WHERE publish_status = 'queued';
@@ -0,0 +1,5 @@
+-- Why a draft was dismissed (DELETE /tweets/:id, POST /tweets/bulk). Listed by
+-- GET /tweets/dismissed, and recent reasons are shown to the agent so it stops
+-- writing the kind of draft the user keeps rejecting.
+ALTER TABLE tweet_collateral
+    ADD COLUMN dismiss_reason TEXT;
@@ -0,0 +1,4 @@
+-- Code-signing status the daemon reports in X-Device-Integrity ('notarized',
+-- 'signed', ...). NULL for daemons that don't send it.
+ALTER TABLE devices
+    ADD COLUMN integrity TEXT;
@@ -0,0 +1,464 @@
+//! Replay harness for the collateral agent
+//!
+//! Each fixture in `fixtures/agent/` records a window (captures, activity and
+//! the frames extracted from them) plus the tool calls a model made while
+//! reviewing it. `ReplayModel` stands in for the LLM and hands those calls
+//! back one turn at a time in the provider's shape; they run through the same
+//! handlers the live agent registers, and the collateral that survives dedupe
+//! is checked against the fixture's expectations. No API keys or database are
+//! needed, so fixtures can't use GetMoreContext.
+
+use super::*;
+use serde_json::json;
+use sqlx::postgres::PgPoolOptions;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    #[serde(default)]
+    timezone: Option<String>,
+    captures: Vec<FixtureCapture>,
+    #[serde(default)]
+    activities: Vec<FixtureActivity>,
+    /// Drafts and posts from earlier runs, for dedupe
+    #[serde(default)]
+    recent_tweets: Vec<FixtureRecentTweet>,
+    calls: Vec<FixtureCall>,
+    expect: Expected,
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureCapture {
+    id: i64,
+    media_type: String,
+    captured_at: DateTime<Utc>,
+    #[serde(default)]
+    device: Option<String>,
+    /// Frame offsets in seconds from `captured_at`
+    frames: Vec<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureActivity {
+    timestamp: DateTime<Utc>,
+    event_type: String,
+    #[serde(default)]
+    application: Option<String>,
+    #[serde(default)]
+    window: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureRecentTweet {
+    text: String,
+    #[serde(default)]
+    capture_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FixtureCall {
+    name: String,
+    arguments: serde_json::Value,
+    /// Substring the tool result must contain. Without it the call must succeed.
+    #[serde(default)]
+    expect_result: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Expected {
+    completed: bool,
+    /// Substrings the initial user prompt must contain
+    #[serde(default)]
+    prompt_contains: Vec<String>,
+    /// Substrings that must not reach the prompt (e.g. injected instructions)
+    #[serde(default)]
+    prompt_excludes: Vec<String>,
+    tweets: Vec<ExpectedTweet>,
+    #[serde(default)]
+    threads: usize,
+    /// Tweets dropped by dedupe
+    #[serde(default)]
+    dropped: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExpectedTweet {
+    text: String,
+    #[serde(default)]
+    image_capture_ids: Vec<i64>,
+    #[serde(default)]
+    video_capture_id: Option<i64>,
+    #[serde(default)]
+    thread_position: Option<i32>,
+    #[serde(default)]
+    poll: bool,
+}
+
+/// Stands in for the LLM: returns the recorded calls in order, shaped like a
+/// provider tool call (arguments as a JSON string)
+struct ReplayModel {
+    calls: VecDeque<FixtureCall>,
+    issued: usize,
+}
+
+impl ReplayModel {
+    fn new(calls: Vec<FixtureCall>) -> Self {
+        Self {
+            calls: calls.into(),
+            issued: 0,
+        }
+    }
+
+    fn next_turn(&mut self) -> Option<(FixtureCall, serde_json::Value)> {
+        let call = self.calls.pop_front()?;
+        self.issued += 1;
+        let value = json!({
+            "id": format!("call_{}", self.issued),
+            "type": "function",
+            "function": {
+                "name": call.name,
+                "arguments": call.arguments.to_string(),
+            },
+        });
+        Some((call, value))
+    }
+}
+
+/// Route a call to its handler, accepting the same names as the turn loop
+async fn dispatch_tool(
+    ctx: &Mutex<AgentContext>,
+    name: &str,
+    args: serde_json::Value,
+) -> reson_agentic::error::Result<String> {
+    let is = |tool_name: &str, struct_name: &str| name == tool_name || name == struct_name;
+    if is(WriteTweet::tool_name(), "WriteTweet") {
+        handle_write_tweet(ctx, args).await
+    } else if is(WriteThread::tool_name(), "WriteThread") {
+        handle_write_thread(ctx, args).await
+    } else if is(MarkComplete::tool_name(), "MarkComplete") {
+        handle_mark_complete(ctx, args).await
+    } else if is(ViewFrames::tool_name(), "ViewFrames") {
+        handle_view_frames(ctx, args).await
+    } else if is(AdvanceFrames::tool_name(), "AdvanceFrames") {
+        handle_advance_frames(ctx, args).await
+    } else if is(ExpandFrame::tool_name(), "ExpandFrame") {
+        handle_expand_frame(ctx, args).await
+    } else {
+        panic!("tool {} can't be replayed without a database", name)
+    }
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/agent")
+}
+
+fn load_fixture(path: &Path) -> Fixture {
+    let raw =
+        std::fs::read_to_string(path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e));
+    serde_json::from_str(&raw).unwrap_or_else(|e| panic!("parse {}: {}", path.display(), e))
+}
+
+fn build_timeline(fixture: &Fixture) -> Vec<TimelineFrame> {
+    let mut timeline: Vec<TimelineFrame> = fixture
+        .captures
+        .iter()
+        .flat_map(|capture| {
+            capture
+                .frames
+                .iter()
+                .enumerate()
+                .map(move |(index, offset)| TimelineFrame {
+                    capture_id: capture.id,
+                    frame_index: index,
+                    timestamp: capture.captured_at
+                        + Duration::milliseconds((offset * 1000.0) as i64),
+                    phash: String::new(),
+                    frame_path: format!("frames/user_1/{}/{:04}.jpg", capture.id, index),
+                    source_media_type: capture.media_type.clone(),
+                    device_id: None,
+                    device: capture.device.clone(),
+                })
+        })
+        .collect();
+    timeline.sort_by_key(|frame| frame.timestamp);
+    timeline
+}
+
+fn build_prompt(fixture: &Fixture, tz: Tz, total_frames: usize) -> String {
+    let activity_summary = fixture
+        .activities
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            let record = ActivityRecord {
+                id: i as i64,
+                timestamp: a.timestamp,
+                event_type: a.event_type.clone(),
+                application: a.application.clone(),
+                window: a.window.clone(),
+                url: a.url.clone(),
+                device: None,
+            };
+            activity_line(&record, tz)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let capture_summary = fixture
+        .captures
+        .iter()
+        .map(|c| {
+            let record = CaptureRecord {
+                id: c.id,
+                media_type: c.media_type.clone(),
+                content_type: String::new(),
+                gcs_path: String::new(),
+                captured_at: c.captured_at,
+                device_id: None,
+                device: c.device.clone(),
+            };
+            capture_line(&record, tz)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    build_user_prompt(
+        &fixture.window_start.with_timezone(&tz).to_string(),
+        &fixture.window_end.with_timezone(&tz).to_string(),
+        &activity_summary,
+        "",
+        &capture_summary,
+        "",
+        &[],
+        total_frames,
+    )
+}
+
+/// Replay one fixture, returning a description of every mismatch
+async fn replay(path: &Path) -> Vec<String> {
+    let fixture = load_fixture(path);
+    let mut failures = Vec::new();
+    let tz = fixture
+        .timezone
+        .as_deref()
+        .and_then(services::timezone::parse)
+        .unwrap_or(Tz::UTC);
+    let timeline = build_timeline(&fixture);
+
+    let prompt = build_prompt(&fixture, tz, timeline.len());
+    for needle in &fixture.expect.prompt_contains {
+        if !prompt.contains(needle.as_str()) {
+            failures.push(format!("prompt is missing {:?}", needle));
+        }
+    }
+    for needle in &fixture.expect.prompt_excludes {
+        if prompt.contains(needle.as_str()) {
+            failures.push(format!("prompt contains {:?}", needle));
+        }
+    }
+
+    let ctx = Mutex::new(AgentContext {
+        // Never connected: no replayable tool touches the database, and capture ids
+        // from the timeline skip WriteTweet's ownership lookup
+        db: PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .expect("lazy pool"),
+        gcs: None,
+        user_id: 1,
+        window_start: fixture.window_start,
+        window_end: fixture.window_end,
+        tweets: Vec::new(),
+        threads: Vec::new(),
+        completed: false,
+        next_thread_id: 1,
+        nudges: None,
+        dismissals: Vec::new(),
+        frame_window: Some(FrameWindow {
+            timeline,
+            summaries: Vec::new(),
+            current_offset: 0,
+        }),
+        local_storage_path: None,
+        buckets: Buckets {
+            media: String::new(),
+            derived: String::new(),
+        },
+        timezone: tz,
+        data_policy: AgentDataPolicy::default(),
+        language: language::default_language(),
+        launch_mode: false,
+        projects: Vec::new(),
+    });
+
+    let mut model = ReplayModel::new(fixture.calls.clone());
+    for turn in 0..MAX_TURNS {
+        if ctx.lock().await.completed {
+            break;
+        }
+        let Some((call, value)) = model.next_turn() else {
+            break;
+        };
+        let result = match dispatch_tool(&ctx, &call.name, value).await {
+            Ok(result) => result,
+            Err(e) => format!("Tool execution failed: {}", e),
+        };
+        match &call.expect_result {
+            Some(expected) if !result.contains(expected.as_str()) => failures.push(format!(
+                "turn {} ({}): expected result containing {:?}, got {:?}",
+                turn + 1,
+                call.name,
+                expected,
+                result
+            )),
+            None if ["Tool error", "Error", "Tool execution failed"]
+                .iter()
+                .any(|prefix| result.starts_with(prefix)) =>
+            {
+                failures.push(format!(
+                    "turn {} ({}) failed: {}",
+                    turn + 1,
+                    call.name,
+                    result
+                ))
+            }
+            _ => {}
+        }
+    }
+    if !model.calls.is_empty() {
+        failures.push(format!(
+            "{} recorded calls were never replayed",
+            model.calls.len()
+        ));
+    }
+
+    let mut guard = ctx.lock().await;
+    if guard.completed != fixture.expect.completed {
+        failures.push(format!(
+            "completed = {}, expected {}",
+            guard.completed, fixture.expect.completed
+        ));
+    }
+
+    let recent: Vec<RecentTweet> = fixture
+        .recent_tweets
+        .iter()
+        .map(|t| RecentTweet {
+            text: t.text.clone(),
+            capture_ids: t.capture_ids.clone(),
+        })
+        .collect();
+    let (threads, tweets, dropped) = dedupe_generated_tweets(
+        std::mem::take(&mut guard.threads),
+        std::mem::take(&mut guard.tweets),
+        &recent,
+        tweet_dedupe_max_hamming_distance(),
+    );
+
+    if threads.len() != fixture.expect.threads {
+        failures.push(format!(
+            "{} threads, expected {}",
+            threads.len(),
+            fixture.expect.threads
+        ));
+    }
+    if dropped != fixture.expect.dropped {
+        failures.push(format!(
+            "dedupe dropped {}, expected {}",
+            dropped, fixture.expect.dropped
+        ));
+    }
+    if tweets.len() != fixture.expect.tweets.len() {
+        failures.push(format!(
+            "{} tweets, expected {}: {:?}",
+            tweets.len(),
+            fixture.expect.tweets.len(),
+            tweets.iter().map(|t| &t.text).collect::<Vec<_>>()
+        ));
+    }
+    for (i, (tweet, expected)) in tweets.iter().zip(&fixture.expect.tweets).enumerate() {
+        let actual = (
+            tweet.text.as_str(),
+            &tweet.image_capture_ids,
+            tweet.video_clip.as_ref().map(|v| v.source_capture_id),
+            tweet.thread_position,
+            tweet.poll.is_some(),
+        );
+        let wanted = (
+            expected.text.as_str(),
+            &expected.image_capture_ids,
+            expected.video_capture_id,
+            expected.thread_position,
+            expected.poll,
+        );
+        if actual != wanted {
+            failures.push(format!(
+                "tweet {}: got {:?}, expected {:?}",
+                i + 1,
+                actual,
+                wanted
+            ));
+        }
+    }
+
+    failures
+}
+
+#[tokio::test]
+async fn test_golden_fixtures() {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(fixtures_dir())
+        .expect("fixtures/agent exists")
+        .map(|entry| entry.expect("dir entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no agent fixtures found");
+
+    let mut report = Vec::new();
+    for path in &paths {
+        for failure in replay(path).await {
+            report.push(format!("{}: {}", path.display(), failure));
+        }
+    }
+    assert!(report.is_empty(), "\n{}", report.join("\n"));
+}
+
+#[test]
+fn test_replay_calls_reach_handlers_as_arguments() {
+    let mut model = ReplayModel::new(vec![FixtureCall {
+        name: "AdvanceFrames".into(),
+        arguments: json!({ "summary": "Refactored the parser" }),
+        expect_result: None,
+    }]);
+    let (_, value) = model.next_turn().expect("one call");
+    let args: AdvanceFrames =
+        serde_json::from_value(extract_tool_arguments(value)).expect("schema matches");
+    assert_eq!(args.summary, "Refactored the parser");
+    assert!(model.next_turn().is_none());
+}
+
+#[test]
+fn test_write_tweet_drops_unowned_captures() {
+    let mut tweet: WriteTweet = serde_json::from_value(json!({
+        "text": "Flat memory on big exports",
+        "image_capture_ids": [102, 999, 999],
+        "video_capture_id": 555,
+        "video_timestamp": "0:02",
+        "media_options": [{ "image_capture_ids": [888] }, { "video_capture_id": 101 }],
+        "rationale": "Before/after graph",
+    }))
+    .expect("schema matches");
+    let owned: HashSet<i64> = [101, 102].into_iter().collect();
+
+    let mut dropped = drop_unowned_captures(&mut tweet, &owned);
+    dropped.sort();
+    assert_eq!(dropped, [555, 888, 999]);
+    assert_eq!(tweet.image_capture_ids, Some(vec![102]));
+    assert_eq!(tweet.video_capture_id, None);
+    assert_eq!(tweet.video_timestamp, None);
+    let options = tweet.media_options.expect("one option left");
+    assert_eq!(options.len(), 1);
+    assert_eq!(options[0].video_capture_id, Some(101));
+}
@@ -0,0 +1,318 @@
+//! Monthly archive background worker
+//!
+//! Processes jobs queued via POST /admin/archives: exports a month of captures and
+//! activities (rows as JSONL plus the original media) under `archives/YYYY-MM/`, then
+//! deletes the month from the hypertables and removes the media, thumbnails and frames.
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::path::PathBuf;
+
+use crate::domain::archives;
+use crate::frames::{FrameManifest, get_frames_dir};
+use crate::storage::{self, Buckets};
+use crate::thumbnails;
+
+const MAX_ATTEMPTS: i32 = 3;
+const BATCH_SIZE: i64 = 500;
+
+/// Manifest written last, so its presence marks a complete export
+#[derive(Debug, Serialize)]
+struct ArchiveManifest {
+    month: String,
+    exported_at: DateTime<Utc>,
+    capture_count: i64,
+    activity_count: i64,
+    capture_files: Vec<String>,
+    activity_files: Vec<String>,
+    media_prefix: String,
+}
+
+/// Start the archive worker.
+/// Archiving is rare and heavy, so only one job runs at a time.
+pub async fn run_archive_worker(
+    pool: PgPool,
+    gcs: Option<google_cloud_storage::client::Storage>,
+    local_storage_path: Option<PathBuf>,
+    buckets: Buckets,
+) {
+    let poll_interval_secs = archive_poll_interval_secs();
+    let lease_secs = archive_lease_secs();
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(poll_interval_secs));
+
+    println!(
+        "[archive] Worker starting ({}s poll, {}s lease)",
+        poll_interval_secs, lease_secs
+    );
+
+    loop {
+        interval.tick().await;
+        crate::services::supervisor::heartbeat("archive");
+
+        let job = match archives::claim_archive_job(&pool, lease_secs, MAX_ATTEMPTS).await {
+            Ok(Some(job)) => job,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("[archive] Claim error: {}", e);
+                continue;
+            }
+        };
+
+        let label = month_label(job.month);
+        println!("[archive] Archiving {}", label);
+
+        match archive_month(
+            &pool,
+            gcs.as_ref(),
+            local_storage_path.as_ref(),
+            &buckets,
+            job.month,
+        )
+        .await
+        {
+            Ok((archive_path, capture_count, activity_count)) => {
+                if let Err(e) = archives::complete_archive(
+                    &pool,
+                    job.id,
+                    &archive_path,
+                    capture_count,
+                    activity_count,
+                )
+                .await
+                {
+                    eprintln!("[archive] Failed to mark {} completed: {}", label, e);
+                }
+                println!(
+                    "[archive] Archived {}: {} captures, {} activities -> {}",
+                    label, capture_count, activity_count, archive_path
+                );
+            }
+            Err(e) => {
+                eprintln!("[archive] Failed {}: {}", label, e);
+                if let Err(e) =
+                    archives::fail_archive(&pool, job.id, &e.to_string(), MAX_ATTEMPTS).await
+                {
+                    eprintln!("[archive] Failed to record error for {}: {}", label, e);
+                }
+            }
+        }
+    }
+}
+
+/// Export, drop, then clean up storage for one month.
+/// Returns (archive prefix, capture count, activity count).
+async fn archive_month(
+    pool: &PgPool,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    month: NaiveDate,
+) -> Result<(String, i64, i64), Box<dyn std::error::Error + Send + Sync>> {
+    let (start, end) = month_bounds(month)?;
+    let prefix = format!("archives/{}", month_label(month));
+    let media_prefix = format!("{}/media", prefix);
+
+    // Captures: one JSONL file per batch, copying each capture's media alongside
+    let mut capture_files = Vec::new();
+    let mut capture_count = 0i64;
+    let mut media_paths: Vec<(i64, String, Option<String>)> = Vec::new();
+    let mut after_id = 0i64;
+    loop {
+        let batch = archives::fetch_capture_batch(pool, start, end, after_id, BATCH_SIZE).await?;
+        let Some(last) = batch.last() else { break };
+        after_id = last.id;
+
+        let mut lines = String::new();
+        for capture in &batch {
+            lines.push_str(&serde_json::to_string(&capture.row)?);
+            lines.push('\n');
+
+            match storage::download_capture(gcs, local_storage_path, buckets, &capture.gcs_path)
+                .await
+            {
+                Ok(data) => {
+                    let dest = format!("{}/{}", media_prefix, capture.gcs_path);
+                    storage::upload_data(gcs, local_storage_path, buckets, &dest, &data).await?;
+                }
+                // Media already gone (e.g. a capture deleted from storage by hand) -
+                // keep the row, there's nothing to copy
+                Err(e) => eprintln!(
+                    "[archive] Missing media for capture {} ({}): {}",
+                    capture.id, capture.gcs_path, e
+                ),
+            }
+            media_paths.push((
+                capture.id,
+                capture.gcs_path.clone(),
+                capture.thumbnail_path.clone(),
+            ));
+        }
+
+        let file = format!("{}/captures-{:05}.jsonl", prefix, capture_files.len());
+        storage::upload_data(gcs, local_storage_path, buckets, &file, lines.as_bytes()).await?;
+        capture_files.push(file);
+        capture_count += batch.len() as i64;
+    }
+
+    // Activities
+    let mut activity_files = Vec::new();
+    let mut activity_count = 0i64;
+    let mut after_id = 0i64;
+    loop {
+        let batch = archives::fetch_activity_batch(pool, start, end, after_id, BATCH_SIZE).await?;
+        let Some(last) = batch.last() else { break };
+        after_id = last.id;
+
+        let mut lines = String::new();
+        for activity in &batch {
+            lines.push_str(&serde_json::to_string(&activity.row)?);
+            lines.push('\n');
+        }
+
+        let file = format!("{}/activities-{:05}.jsonl", prefix, activity_files.len());
+        storage::upload_data(gcs, local_storage_path, buckets, &file, lines.as_bytes()).await?;
+        activity_files.push(file);
+        activity_count += batch.len() as i64;
+    }
+
+    let manifest = ArchiveManifest {
+        month: month_label(month),
+        exported_at: Utc::now(),
+        capture_count,
+        activity_count,
+        capture_files,
+        activity_files,
+        media_prefix,
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest)?;
+    storage::upload_data(
+        gcs,
+        local_storage_path,
+        buckets,
+        &format!("{}/manifest.json", prefix),
+        manifest_json.as_bytes(),
+    )
+    .await?;
+
+    // Export is durable - drop the month in one transaction
+    let mut tx = pool.begin().await?;
+    let kept = archives::drop_month(&mut tx, start, end).await?;
+    tx.commit().await?;
+    if !kept.is_empty() {
+        println!(
+            "[archive] Keeping {} capture(s) still used by drafts in {}",
+            kept.len(),
+            month_label(month)
+        );
+    }
+
+    // Rows are gone, so storage cleanup failures only leak objects; log and move on
+    for (_, gcs_path, thumbnail_path) in media_paths.iter().filter(|(id, ..)| !kept.contains(id)) {
+        for path in
+            capture_storage_paths(gcs, local_storage_path, buckets, gcs_path, thumbnail_path).await
+        {
+            if let Err(e) = storage::delete_object(gcs, local_storage_path, buckets, &path).await {
+                eprintln!("[archive] Failed to delete {}: {}", path, e);
+            }
+        }
+        // Resized thumbnails only exist for widths someone requested; most are missing
+        for path in thumbnail_path
+            .iter()
+            .flat_map(|t| thumbnails::resized_thumbnail_paths(t))
+        {
+            let _ = storage::delete_object(gcs, local_storage_path, buckets, &path).await;
+        }
+    }
+
+    Ok((prefix, capture_count, activity_count))
+}
+
+/// Every storage object belonging to a capture: original, thumbnail, frames and manifest
+async fn capture_storage_paths(
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    gcs_path: &str,
+    thumbnail_path: &Option<String>,
+) -> Vec<String> {
+    let mut paths = vec![gcs_path.to_string()];
+    paths.extend(thumbnail_path.clone());
+
+    let frames_dir = get_frames_dir(gcs_path);
+    let manifest_path = format!("{}/manifest.json", frames_dir);
+    let manifest = storage::download_capture(gcs, local_storage_path, buckets, &manifest_path)
+        .await
+        .ok()
+        .and_then(|data| serde_json::from_slice::<FrameManifest>(&data).ok());
+    if let Some(manifest) = manifest {
+        paths.extend(
+            manifest
+                .frames
+                .iter()
+                .map(|f| format!("{}/{}", frames_dir, f.filename)),
+        );
+        paths.push(manifest_path);
+    }
+    paths
+}
+
+/// [start, end) of a month in UTC
+fn month_bounds(
+    month: NaiveDate,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), Box<dyn std::error::Error + Send + Sync>> {
+    let first = month.with_day(1).ok_or("invalid month")?;
+    let next = first
+        .checked_add_months(Months::new(1))
+        .ok_or("month out of range")?;
+    let start = first.and_hms_opt(0, 0, 0).ok_or("invalid month")?.and_utc();
+    let end = next.and_hms_opt(0, 0, 0).ok_or("invalid month")?.and_utc();
+    Ok((start, end))
+}
+
+/// "2025-01" for 2025-01-01
+pub fn month_label(month: NaiveDate) -> String {
+    format!("{:04}-{:02}", month.year(), month.month())
+}
+
+/// Parse "YYYY-MM" to the first day of that month
+pub fn parse_month(s: &str) -> Option<NaiveDate> {
+    let (year, month) = s.trim().split_once('-')?;
+    if year.len() != 4 || month.len() != 2 {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)
+}
+
+/// Months younger than this can't be archived (ARCHIVE_MIN_AGE_MONTHS)
+pub fn archive_min_age_months() -> u32 {
+    crate::config::get().workers.archive.min_age_months
+}
+
+pub fn archive_poll_interval_secs() -> u64 {
+    crate::config::get().workers.archive.poll_interval_secs
+}
+
+fn archive_lease_secs() -> i64 {
+    crate::config::get().workers.archive.lease_secs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_month() {
+        assert_eq!(parse_month("2025-03"), NaiveDate::from_ymd_opt(2025, 3, 1));
+        assert_eq!(parse_month("2025-13"), None);
+        assert_eq!(parse_month("2025-3"), None);
+        assert_eq!(parse_month("march"), None);
+    }
+
+    #[test]
+    fn test_month_bounds_wraps_year() {
+        let (start, end) = month_bounds(NaiveDate::from_ymd_opt(2024, 12, 1).unwrap()).unwrap();
+        assert_eq!(start.to_rfc3339(), "2024-12-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2025-01-01T00:00:00+00:00");
+    }
+}
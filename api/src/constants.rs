@@ -1,11 +1,26 @@
 //! Application constants
 
-/// GCS bucket name for multimedia storage
-pub const BUCKET_NAME: &str = "cleo_multimedia_data";
+/// Default GCS bucket for multimedia storage (override with GCS_BUCKET)
+pub const DEFAULT_BUCKET_NAME: &str = "cleo_multimedia_data";
 
 /// Maximum upload size for captures (200 MB)
 pub const MAX_CAPTURE_UPLOAD_SIZE: usize = 200 * 1024 * 1024;
 
+/// Maximum upload size for media library images (5 MB, X's image limit)
+pub const MAX_MEDIA_LIBRARY_UPLOAD_SIZE: usize = 5 * 1024 * 1024;
+
+/// Maximum file parts in one /captures/batch request
+pub const MAX_CAPTURE_PARTS: usize = 64;
+
+/// Default body limit for every other route (1 MB)
+pub const MAX_JSON_BODY_SIZE: usize = 1024 * 1024;
+
+/// Body limit for daemon activity batches (4 MB)
+pub const MAX_ACTIVITY_BODY_SIZE: usize = 4 * 1024 * 1024;
+
+/// Maximum events in one /activity batch
+pub const MAX_ACTIVITIES_PER_BATCH: usize = 5_000;
+
 /// Signed URL expiry time in seconds (15 minutes)
 pub const SIGNED_URL_EXPIRY_SECS: u32 = 15 * 60;
 
@@ -0,0 +1,763 @@
+//! Daily summary export background worker
+//!
+//! For users who set a destination via PUT /me/summary-export, writes a note for
+//! each finished local day: the apps with the most active time, a spread of the
+//! day's captures with thumbnails, and the tweets drafted and posted. Notes go to
+//! a new Notion page under the configured parent page, to a WebDAV folder (e.g.
+//! an Obsidian vault synced over WebDAV), or to the user's folder under
+//! `workers.daily_summary.vault_root`.
+//!
+//! A day is exported once the user's local clock passes `export_hour`. Days
+//! missed while the worker was down aren't backfilled, and days with nothing in
+//! them are marked done without writing a note.
+
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use chrono_tz::Tz;
+use reqwest::{Client, Method, RequestBuilder};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::domain::summary_exports::{self, AppTime, DayCapture, DayTweet, SummaryExport};
+use crate::services::{crypto, timezone};
+use crate::storage::{self, Buckets};
+
+type ExportError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Apps listed under "Top apps"
+const TOP_APPS: i64 = 5;
+/// Captures (with thumbnails) per note, spread across the day
+const MAX_CAPTURES: usize = 12;
+/// Folder next to the notes that holds thumbnails
+const ATTACHMENTS_DIR: &str = "attachments";
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Children Notion accepts per request
+const NOTION_BLOCK_BATCH: usize = 100;
+/// Characters Notion accepts per rich text object
+const NOTION_TEXT_LIMIT: usize = 2000;
+
+/// Everything written for one day
+struct DaySummary {
+    date: NaiveDate,
+    tz: Tz,
+    apps: Vec<AppTime>,
+    /// Captures taken that day, of which `captures` is a sample
+    capture_count: usize,
+    captures: Vec<SummaryCapture>,
+    drafted: Vec<DayTweet>,
+    posted: Vec<DayTweet>,
+}
+
+struct SummaryCapture {
+    capture: DayCapture,
+    /// Thumbnail JPEG, if one was generated and could be read
+    thumbnail: Option<Vec<u8>>,
+}
+
+impl DaySummary {
+    fn is_empty(&self) -> bool {
+        self.apps.is_empty()
+            && self.capture_count == 0
+            && self.drafted.is_empty()
+            && self.posted.is_empty()
+    }
+
+    fn title(&self) -> String {
+        format!("Cleo daily summary: {}", self.date.format("%A, %B %-d, %Y"))
+    }
+
+    fn local_time(&self, at: DateTime<Utc>) -> String {
+        at.with_timezone(&self.tz).format("%H:%M").to_string()
+    }
+}
+
+/// Start the daily summary export worker
+pub async fn run_daily_summary_worker(
+    pool: PgPool,
+    gcs: Option<google_cloud_storage::client::Storage>,
+    local_storage_path: Option<PathBuf>,
+    buckets: Buckets,
+) {
+    let poll_interval_secs = daily_summary_poll_interval_secs();
+    let config = &crate::config::get().workers.daily_summary;
+    // WebDAV URLs are checked when saved; don't let a redirect take us elsewhere
+    let http = Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .expect("HTTP client");
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(poll_interval_secs));
+
+    println!(
+        "[daily_summary] Worker starting ({}s poll, {}s lease, export after {:02}:00 local)",
+        poll_interval_secs, config.lease_secs, config.export_hour
+    );
+
+    loop {
+        interval.tick().await;
+        crate::services::supervisor::heartbeat("daily_summary");
+
+        let now = Utc::now();
+        let candidates = match summary_exports::list_candidates(&pool, now.date_naive()).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("[daily_summary] List error: {}", e);
+                continue;
+            }
+        };
+
+        for candidate in candidates {
+            let tz = timezone::parse(&candidate.timezone).unwrap_or(Tz::UTC);
+            let Some(day) = due_day(now, tz, config.export_hour) else {
+                continue;
+            };
+            if candidate.last_exported_date.is_some_and(|d| d >= day) {
+                continue;
+            }
+
+            let export = match summary_exports::claim_export(
+                &pool,
+                candidate.user_id,
+                day,
+                config.lease_secs,
+            )
+            .await
+            {
+                Ok(Some(export)) => export,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("[daily_summary] Claim error: {}", e);
+                    continue;
+                }
+            };
+
+            let result = export_day(
+                &pool,
+                &http,
+                gcs.as_ref(),
+                local_storage_path.as_ref(),
+                &buckets,
+                &export,
+                day,
+                tz,
+            )
+            .await;
+            match result {
+                Ok(written) => {
+                    if let Err(e) =
+                        summary_exports::complete_export(&pool, export.user_id, day).await
+                    {
+                        eprintln!("[daily_summary] Complete error: {}", e);
+                    } else if written {
+                        println!(
+                            "[daily_summary] Exported {} for user {} to {}",
+                            day, export.user_id, export.destination
+                        );
+                    }
+                }
+                Err(e) => {
+                    eprintln!(
+                        "[daily_summary] Export of {} for user {} failed: {}",
+                        day, export.user_id, e
+                    );
+                    if let Err(e) =
+                        summary_exports::fail_export(&pool, export.user_id, &e.to_string()).await
+                    {
+                        eprintln!("[daily_summary] Fail error: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The local day to export at `now`: yesterday, once the local clock passes `export_hour`
+fn due_day(now: DateTime<Utc>, tz: Tz, export_hour: u32) -> Option<NaiveDate> {
+    let local = now.with_timezone(&tz);
+    if local.hour() < export_hour {
+        return None;
+    }
+    local.date_naive().pred_opt()
+}
+
+/// Build and write one day's note. Returns false if the day was empty.
+#[allow(clippy::too_many_arguments)]
+async fn export_day(
+    pool: &PgPool,
+    http: &Client,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    export: &SummaryExport,
+    day: NaiveDate,
+    tz: Tz,
+) -> Result<bool, ExportError> {
+    let summary = build_summary(
+        pool,
+        gcs,
+        local_storage_path,
+        buckets,
+        export.user_id,
+        day,
+        tz,
+    )
+    .await?;
+    if summary.is_empty() {
+        return Ok(false);
+    }
+
+    match export.destination.as_str() {
+        "notion" => write_notion(http, export, &summary).await?,
+        "webdav" => write_webdav(http, export, &summary).await?,
+        "local" => write_local(export, &summary).await?,
+        other => return Err(format!("unknown destination {:?}", other).into()),
+    }
+    Ok(true)
+}
+
+async fn build_summary(
+    pool: &PgPool,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    user_id: i64,
+    day: NaiveDate,
+    tz: Tz,
+) -> Result<DaySummary, ExportError> {
+    let (start, end) = timezone::day_bounds(day, tz);
+    let apps = summary_exports::top_apps(pool, user_id, start, end, TOP_APPS).await?;
+    let day_captures = summary_exports::list_day_captures(pool, user_id, start, end).await?;
+    let tweets = summary_exports::list_day_tweets(pool, user_id, start, end).await?;
+
+    let capture_count = day_captures.len();
+    let picked = spread(capture_count, MAX_CAPTURES);
+    let mut captures = Vec::with_capacity(picked.len());
+    for capture in day_captures
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| picked.contains(i))
+        .map(|(_, c)| c)
+    {
+        // A missing thumbnail leaves the capture listed without an image
+        let thumbnail = match &capture.thumbnail_path {
+            Some(path) => {
+                match storage::download_capture(gcs, local_storage_path, buckets, path).await {
+                    Ok(data) => Some(data),
+                    Err(e) => {
+                        eprintln!(
+                            "[daily_summary] Thumbnail {} for capture {} unavailable: {}",
+                            path, capture.id, e
+                        );
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        captures.push(SummaryCapture { capture, thumbnail });
+    }
+
+    let (posted, drafted): (Vec<_>, Vec<_>) = tweets
+        .into_iter()
+        .partition(|t| t.posted_at.is_some_and(|p| p >= start && p < end));
+
+    Ok(DaySummary {
+        date: day,
+        tz,
+        apps,
+        capture_count,
+        captures,
+        drafted,
+        posted,
+    })
+}
+
+/// Indices of at most `n` items spread evenly over `len`, first and last included
+fn spread(len: usize, n: usize) -> Vec<usize> {
+    if len <= n {
+        return (0..len).collect();
+    }
+    if n <= 1 {
+        return (0..n).collect();
+    }
+    (0..n).map(|i| i * (len - 1) / (n - 1)).collect()
+}
+
+/// "2h 05m", "45m", or "<1m"
+pub fn format_duration(secs: i64) -> String {
+    let minutes = secs / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "<1m".to_string(),
+        (0, m) => format!("{}m", m),
+        (h, m) => format!("{}h {:02}m", h, m),
+    }
+}
+
+fn tweet_url(tweet_id: &str) -> String {
+    format!("https://x.com/i/status/{}", tweet_id)
+}
+
+/// File name of a capture's thumbnail in the attachments folder
+fn attachment_name(date: NaiveDate, capture_id: i64) -> String {
+    format!("{}-{}.jpg", date, capture_id)
+}
+
+fn note_name(date: NaiveDate) -> String {
+    format!("{}.md", date)
+}
+
+/// Markdown note for the day; thumbnails are linked from `attachments/`
+fn render_markdown(summary: &DaySummary) -> String {
+    let mut md = format!("# {}\n", summary.title());
+
+    if !summary.apps.is_empty() {
+        md.push_str("\n## Top apps\n\n");
+        for app in &summary.apps {
+            md.push_str(&format!(
+                "- **{}**: {}\n",
+                app.app,
+                format_duration(app.active_secs)
+            ));
+        }
+    }
+
+    if summary.capture_count > 0 {
+        md.push_str("\n## Captures\n\n");
+        if summary.capture_count > summary.captures.len() {
+            md.push_str(&format!(
+                "{} of {} captures.\n\n",
+                summary.captures.len(),
+                summary.capture_count
+            ));
+        }
+        for c in &summary.captures {
+            md.push_str(&format!(
+                "- {} {}\n",
+                summary.local_time(c.capture.captured_at),
+                c.capture.media_type
+            ));
+            if c.thumbnail.is_some() {
+                md.push_str(&format!(
+                    "  ![capture {}]({}/{})\n",
+                    c.capture.id,
+                    ATTACHMENTS_DIR,
+                    attachment_name(summary.date, c.capture.id)
+                ));
+            }
+        }
+    }
+
+    for (heading, tweets) in [
+        ("Drafted tweets", &summary.drafted),
+        ("Posted tweets", &summary.posted),
+    ] {
+        if tweets.is_empty() {
+            continue;
+        }
+        md.push_str(&format!("\n## {}\n\n", heading));
+        for tweet in tweets {
+            let at = tweet.posted_at.unwrap_or(tweet.created_at);
+            match &tweet.tweet_id {
+                Some(id) if tweet.posted_at.is_some() => md.push_str(&format!(
+                    "- {} ([view]({}))\n",
+                    summary.local_time(at),
+                    tweet_url(id)
+                )),
+                _ => md.push_str(&format!("- {}\n", summary.local_time(at))),
+            }
+            for line in tweet.text.lines() {
+                md.push_str(&format!("  > {}\n", line));
+            }
+        }
+    }
+
+    md
+}
+
+/// A user's folder under the vault root: `user_<id>/<local_dir>`.
+/// None if `local_dir` could climb out of it.
+pub fn vault_dir(root: &Path, user_id: i64, local_dir: Option<&str>) -> Option<PathBuf> {
+    let user_root = root.join(format!("user_{}", user_id));
+    let Some(local_dir) = local_dir else {
+        return Some(user_root);
+    };
+    Path::new(local_dir)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then(|| user_root.join(local_dir))
+}
+
+async fn write_local(export: &SummaryExport, summary: &DaySummary) -> Result<(), ExportError> {
+    let root = crate::config::get()
+        .workers
+        .daily_summary
+        .vault_root
+        .as_ref()
+        .ok_or("local exports are disabled (no vault_root configured)")?;
+    let dir = vault_dir(root, export.user_id, export.local_dir.as_deref())
+        .ok_or("invalid local folder")?;
+
+    let attachments = dir.join(ATTACHMENTS_DIR);
+    tokio::fs::create_dir_all(&attachments).await?;
+    for c in &summary.captures {
+        if let Some(data) = &c.thumbnail {
+            let name = attachment_name(summary.date, c.capture.id);
+            tokio::fs::write(attachments.join(name), data).await?;
+        }
+    }
+    tokio::fs::write(dir.join(note_name(summary.date)), render_markdown(summary)).await?;
+    Ok(())
+}
+
+async fn write_webdav(
+    http: &Client,
+    export: &SummaryExport,
+    summary: &DaySummary,
+) -> Result<(), ExportError> {
+    let base = export
+        .webdav_url
+        .as_deref()
+        .ok_or("WebDAV URL missing")?
+        .trim_end_matches('/');
+    let username = export.webdav_username.as_deref().unwrap_or_default();
+    let password = match &export.webdav_password {
+        Some(p) => Some(crypto::cipher().decrypt(p)?),
+        None => None,
+    };
+    let request = |method: Method, url: String| {
+        http.request(method, url)
+            .basic_auth(username, password.as_deref())
+    };
+
+    let attachments = format!("{}/{}", base, ATTACHMENTS_DIR);
+    if summary.captures.iter().any(|c| c.thumbnail.is_some()) {
+        // 405 means the collection already exists
+        let mkcol = Method::from_bytes(b"MKCOL").expect("valid method");
+        let resp = request(mkcol, format!("{}/", attachments)).send().await?;
+        if !resp.status().is_success() && resp.status().as_u16() != 405 {
+            return Err(format!("WebDAV MKCOL failed: {}", resp.status()).into());
+        }
+    }
+    for c in &summary.captures {
+        if let Some(data) = &c.thumbnail {
+            let url = format!(
+                "{}/{}",
+                attachments,
+                attachment_name(summary.date, c.capture.id)
+            );
+            webdav_put(request(Method::PUT, url), "image/jpeg", data.clone()).await?;
+        }
+    }
+    let url = format!("{}/{}", base, note_name(summary.date));
+    webdav_put(
+        request(Method::PUT, url),
+        "text/markdown; charset=utf-8",
+        render_markdown(summary).into_bytes(),
+    )
+    .await
+}
+
+async fn webdav_put(
+    request: RequestBuilder,
+    content_type: &str,
+    body: Vec<u8>,
+) -> Result<(), ExportError> {
+    let resp = request
+        .header(reqwest::header::CONTENT_TYPE, content_type)
+        .body(body)
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(format!("WebDAV PUT failed: {}", resp.status()).into());
+    }
+    Ok(())
+}
+
+async fn write_notion(
+    http: &Client,
+    export: &SummaryExport,
+    summary: &DaySummary,
+) -> Result<(), ExportError> {
+    let token = crypto::cipher().decrypt(
+        export
+            .notion_token
+            .as_deref()
+            .ok_or("Notion token missing")?,
+    )?;
+    let parent = export
+        .notion_parent_page_id
+        .as_deref()
+        .ok_or("Notion parent page missing")?;
+
+    let mut uploads = HashMap::new();
+    for c in &summary.captures {
+        if let Some(data) = &c.thumbnail {
+            let name = attachment_name(summary.date, c.capture.id);
+            uploads.insert(
+                c.capture.id,
+                notion_upload(http, &token, &name, data).await?,
+            );
+        }
+    }
+
+    let blocks = notion_blocks(summary, &uploads);
+    let mut batches = blocks.chunks(NOTION_BLOCK_BATCH);
+    let page = notion_call(
+        notion_request(http, Method::POST, "/pages", &token).json(&json!({
+            "parent": { "page_id": parent },
+            "properties": {
+                "title": { "title": [rich_text(&summary.title(), None)] }
+            },
+            "children": batches.next().unwrap_or_default(),
+        })),
+    )
+    .await?;
+    let page_id = page["id"].as_str().ok_or("Notion page id missing")?;
+    for batch in batches {
+        notion_call(
+            notion_request(
+                http,
+                Method::PATCH,
+                &format!("/blocks/{}/children", page_id),
+                &token,
+            )
+            .json(&json!({ "children": batch })),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+fn notion_request(http: &Client, method: Method, path: &str, token: &str) -> RequestBuilder {
+    http.request(method, format!("{}{}", NOTION_API, path))
+        .bearer_auth(token)
+        .header("Notion-Version", NOTION_VERSION)
+}
+
+async fn notion_call(request: RequestBuilder) -> Result<Value, ExportError> {
+    let resp = request.send().await?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        return Err(format!("Notion API error {}: {}", status, text).into());
+    }
+    Ok(resp.json().await?)
+}
+
+/// Upload a thumbnail with Notion's file upload API, returning the upload id
+async fn notion_upload(
+    http: &Client,
+    token: &str,
+    name: &str,
+    data: &[u8],
+) -> Result<String, ExportError> {
+    let created = notion_call(
+        notion_request(http, Method::POST, "/file_uploads", token)
+            .json(&json!({ "filename": name, "content_type": "image/jpeg" })),
+    )
+    .await?;
+    let id = created["id"]
+        .as_str()
+        .ok_or("Notion file upload id missing")?
+        .to_string();
+
+    let part = reqwest::multipart::Part::bytes(data.to_vec())
+        .file_name(name.to_string())
+        .mime_str("image/jpeg")?;
+    notion_call(
+        notion_request(
+            http,
+            Method::POST,
+            &format!("/file_uploads/{}/send", id),
+            token,
+        )
+        .multipart(reqwest::multipart::Form::new().part("file", part)),
+    )
+    .await?;
+    Ok(id)
+}
+
+fn rich_text(text: &str, link: Option<&str>) -> Value {
+    let content: String = text.chars().take(NOTION_TEXT_LIMIT).collect();
+    match link {
+        Some(url) => {
+            json!({ "type": "text", "text": { "content": content, "link": { "url": url } } })
+        }
+        None => json!({ "type": "text", "text": { "content": content } }),
+    }
+}
+
+fn notion_block(kind: &str, text: Vec<Value>) -> Value {
+    json!({ "object": "block", "type": kind, kind: { "rich_text": text } })
+}
+
+/// The same note as render_markdown, as Notion blocks
+fn notion_blocks(summary: &DaySummary, uploads: &HashMap<i64, String>) -> Vec<Value> {
+    let mut blocks = Vec::new();
+
+    if !summary.apps.is_empty() {
+        blocks.push(notion_block("heading_2", vec![rich_text("Top apps", None)]));
+        for app in &summary.apps {
+            blocks.push(notion_block(
+                "bulleted_list_item",
+                vec![rich_text(
+                    &format!("{}: {}", app.app, format_duration(app.active_secs)),
+                    None,
+                )],
+            ));
+        }
+    }
+
+    if summary.capture_count > 0 {
+        blocks.push(notion_block("heading_2", vec![rich_text("Captures", None)]));
+        if summary.capture_count > summary.captures.len() {
+            blocks.push(notion_block(
+                "paragraph",
+                vec![rich_text(
+                    &format!(
+                        "{} of {} captures.",
+                        summary.captures.len(),
+                        summary.capture_count
+                    ),
+                    None,
+                )],
+            ));
+        }
+        for c in &summary.captures {
+            blocks.push(notion_block(
+                "bulleted_list_item",
+                vec![rich_text(
+                    &format!(
+                        "{} {}",
+                        summary.local_time(c.capture.captured_at),
+                        c.capture.media_type
+                    ),
+                    None,
+                )],
+            ));
+            if let Some(upload_id) = uploads.get(&c.capture.id) {
+                blocks.push(json!({
+                    "object": "block",
+                    "type": "image",
+                    "image": { "type": "file_upload", "file_upload": { "id": upload_id } },
+                }));
+            }
+        }
+    }
+
+    for (heading, tweets) in [
+        ("Drafted tweets", &summary.drafted),
+        ("Posted tweets", &summary.posted),
+    ] {
+        if tweets.is_empty() {
+            continue;
+        }
+        blocks.push(notion_block("heading_2", vec![rich_text(heading, None)]));
+        for tweet in tweets {
+            let at = summary.local_time(tweet.posted_at.unwrap_or(tweet.created_at));
+            let mut label = vec![rich_text(&at, None)];
+            if let (Some(id), Some(_)) = (&tweet.tweet_id, tweet.posted_at) {
+                label.push(rich_text(" ", None));
+                label.push(rich_text("view", Some(&tweet_url(id))));
+            }
+            blocks.push(notion_block("paragraph", label));
+            blocks.push(notion_block("quote", vec![rich_text(&tweet.text, None)]));
+        }
+    }
+
+    blocks
+}
+
+pub fn daily_summary_poll_interval_secs() -> u64 {
+    crate::config::get()
+        .workers
+        .daily_summary
+        .poll_interval_secs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_due_day_waits_for_export_hour() {
+        let tz = timezone::parse("America/New_York").unwrap();
+        // 05:30 UTC is 01:30 in New York on October 16th
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 5, 30, 0).unwrap();
+        assert_eq!(due_day(now, tz, 2), None);
+        assert_eq!(due_day(now, tz, 1), NaiveDate::from_ymd_opt(2026, 10, 15));
+    }
+
+    #[test]
+    fn test_spread_keeps_first_and_last() {
+        assert_eq!(spread(3, 12), vec![0, 1, 2]);
+        assert_eq!(spread(100, 3), vec![0, 49, 99]);
+        assert_eq!(spread(100, 12).len(), 12);
+    }
+
+    #[test]
+    fn test_vault_dir_stays_under_root() {
+        let root = Path::new("/vaults");
+        assert_eq!(
+            vault_dir(root, 7, Some("Notes/Cleo")),
+            Some(PathBuf::from("/vaults/user_7/Notes/Cleo"))
+        );
+        assert_eq!(
+            vault_dir(root, 7, None),
+            Some(PathBuf::from("/vaults/user_7"))
+        );
+        assert_eq!(vault_dir(root, 7, Some("../user_8")), None);
+        assert_eq!(vault_dir(root, 7, Some("/etc")), None);
+        assert_eq!(vault_dir(root, 7, Some("Notes/../../user_8")), None);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let tz = timezone::parse("America/New_York").unwrap();
+        let at = |h, m| Utc.with_ymd_and_hms(2026, 10, 15, h, m, 0).unwrap();
+        let summary = DaySummary {
+            date: NaiveDate::from_ymd_opt(2026, 10, 15).unwrap(),
+            tz,
+            apps: vec![AppTime {
+                app: "Xcode".to_string(),
+                active_secs: 2 * 3600 + 5 * 60,
+            }],
+            capture_count: 40,
+            captures: vec![SummaryCapture {
+                capture: DayCapture {
+                    id: 7,
+                    media_type: "image".to_string(),
+                    captured_at: at(13, 14),
+                    thumbnail_path: Some("thumbnails/user_1/2026-10-15/7.jpg".to_string()),
+                },
+                thumbnail: Some(vec![0xff, 0xd8]),
+            }],
+            drafted: vec![],
+            posted: vec![DayTweet {
+                text: "Shipped it\nfinally".to_string(),
+                tweet_id: Some("123".to_string()),
+                created_at: at(12, 0),
+                posted_at: Some(at(18, 2)),
+            }],
+        };
+
+        assert_eq!(
+            render_markdown(&summary),
+            "# Cleo daily summary: Thursday, October 15, 2026\n\
+             \n## Top apps\n\n\
+             - **Xcode**: 2h 05m\n\
+             \n## Captures\n\n\
+             1 of 40 captures.\n\n\
+             - 09:14 image\n  \
+             ![capture 7](attachments/2026-10-15-7.jpg)\n\
+             \n## Posted tweets\n\n\
+             - 14:02 ([view](https://x.com/i/status/123))\n  \
+             > Shipped it\n  \
+             > finally\n"
+        );
+    }
+}
@@ -0,0 +1,514 @@
+//! Dev signal extraction background worker
+//!
+//! OCRs captures taken while a terminal app was focused (with the `tesseract`
+//! CLI) and stores the build results, test counts and benchmark numbers it
+//! recognizes as dev_signals, which the content agent lists next to the
+//! captures so drafts can quote real numbers. Screenshots are read at full
+//! resolution; for recordings the last frame is used, since that's where the
+//! final output sits.
+
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::domain::dev_signals::{self, DevSignal, ScanCapture};
+use crate::storage::{self, Buckets};
+
+/// Lowercase app names that count as terminals
+pub const TERMINAL_APPS: &[&str] = &[
+    "terminal",
+    "iterm2",
+    "ghostty",
+    "warp",
+    "alacritty",
+    "kitty",
+    "wezterm",
+    "hyper",
+    "tabby",
+    "rio",
+];
+/// Longest OCR'd line kept as a signal's excerpt
+const MAX_EXCERPT_CHARS: usize = 200;
+
+/// Whether the `tesseract` CLI can be run; the worker isn't started without it
+pub async fn tesseract_available() -> bool {
+    Command::new("tesseract")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .is_ok_and(|status| status.success())
+}
+
+pub async fn run_dev_signals_worker(
+    pool: sqlx::PgPool,
+    gcs: Option<google_cloud_storage::client::Storage>,
+    local_storage_path: Option<std::path::PathBuf>,
+    buckets: Buckets,
+) {
+    let poll_interval_secs = dev_signals_poll_interval_secs();
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(poll_interval_secs));
+    println!(
+        "[dev_signals] Worker starting ({}s poll)",
+        poll_interval_secs
+    );
+
+    loop {
+        interval.tick().await;
+        crate::services::supervisor::heartbeat("dev_signals");
+
+        let captures =
+            match dev_signals::claim_captures(&pool, dev_signals_batch_size(), TERMINAL_APPS).await
+            {
+                Ok(captures) => captures,
+                Err(e) => {
+                    eprintln!("[dev_signals] Claim error: {}", e);
+                    continue;
+                }
+            };
+
+        for capture in captures.iter().filter(|c| c.terminal) {
+            match scan_capture(
+                &pool,
+                gcs.as_ref(),
+                local_storage_path.as_ref(),
+                &buckets,
+                capture,
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(count) => println!("[dev_signals] Capture {}: {} signal(s)", capture.id, count),
+                // Captures are marked scanned when claimed, so a failure isn't retried
+                Err(e) => eprintln!("[dev_signals] Failed capture {}: {}", capture.id, e),
+            }
+        }
+    }
+}
+
+/// OCR one capture and store its signals, returning how many were found
+async fn scan_capture(
+    pool: &sqlx::PgPool,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&std::path::PathBuf>,
+    buckets: &Buckets,
+    capture: &ScanCapture,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let data =
+        storage::download_capture(gcs, local_storage_path, buckets, &capture.gcs_path).await?;
+
+    let temp_dir = std::env::temp_dir().join(format!("cleo_ocr_{}", rand::random::<u64>()));
+    tokio::fs::create_dir_all(&temp_dir).await?;
+    let text = ocr_capture(&temp_dir, &capture.media_type, &data).await;
+    if let Err(e) = tokio::fs::remove_dir_all(&temp_dir).await {
+        eprintln!(
+            "[dev_signals] Failed to cleanup temp dir {:?}: {}",
+            temp_dir, e
+        );
+    }
+
+    let signals = parse_signals(&text?);
+    for signal in &signals {
+        dev_signals::insert_signal(
+            pool,
+            capture.user_id,
+            capture.id,
+            capture.captured_at,
+            signal,
+        )
+        .await?;
+    }
+    Ok(signals.len())
+}
+
+async fn ocr_capture(
+    temp_dir: &Path,
+    media_type: &str,
+    data: &[u8],
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let image_path = if media_type == "video" {
+        let input_path = temp_dir.join("input.mp4");
+        tokio::fs::write(&input_path, data).await?;
+        let frame_path = temp_dir.join("last.png");
+        extract_last_frame(&input_path, &frame_path).await?;
+        frame_path
+    } else {
+        let image_path = temp_dir.join("capture");
+        tokio::fs::write(&image_path, data).await?;
+        image_path
+    };
+
+    let output = Command::new("tesseract")
+        .arg(&image_path)
+        .arg("stdout")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(format!(
+            "tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Write a recording's last frame at full resolution
+async fn extract_last_frame(
+    input_path: &Path,
+    frame_path: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let threads = crate::config::get().workers.ffmpeg_threads.to_string();
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-nostdin"])
+        .args(["-threads", &threads])
+        // Decode only the last second; -update keeps overwriting the same file
+        .args(["-sseof", "-1"])
+        .arg("-i")
+        .arg(input_path)
+        .args(["-an", "-sn", "-update", "1", "-y"])
+        .arg(frame_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg last frame extraction failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Build, test and benchmark results recognized in OCR'd terminal text, one
+/// per matching line. Lines repeated on screen are kept once.
+pub fn parse_signals(text: &str) -> Vec<DevSignal> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let mut signals: Vec<DevSignal> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let lower = line.to_lowercase();
+        let previous = i.checked_sub(1).map(|j| lines[j]);
+        let Some(mut signal) = parse_build(&lower)
+            .or_else(|| parse_tests(&lower))
+            .or_else(|| parse_benchmark(line, &lower, previous))
+        else {
+            continue;
+        };
+        signal.excerpt = line.chars().take(MAX_EXCERPT_CHARS).collect();
+        if !signals.contains(&signal) {
+            signals.push(signal);
+        }
+    }
+    signals
+}
+
+fn signal(kind: &str, tool: Option<&str>, success: Option<bool>) -> DevSignal {
+    DevSignal {
+        kind: kind.to_string(),
+        tool: tool.map(str::to_string),
+        success,
+        passed: None,
+        failed: None,
+        duration_secs: None,
+        bench_name: None,
+        bench_value: None,
+        bench_unit: None,
+        excerpt: String::new(),
+    }
+}
+
+/// Compiler and bundler results: cargo, vite, webpack, gradle, tsc, make
+fn parse_build(lower: &str) -> Option<DevSignal> {
+    let timed = |tool: &str, success: bool, marker: &str| {
+        let duration = parse_duration(after(lower, marker)?)?;
+        Some(DevSignal {
+            duration_secs: Some(duration),
+            ..signal("build", Some(tool), Some(success))
+        })
+    };
+
+    if lower.starts_with("finished") && lower.contains("target(s) in ") {
+        return timed("cargo", true, "target(s) in ");
+    }
+    if lower.starts_with("error: could not compile") {
+        return Some(signal("build", Some("cargo"), Some(false)));
+    }
+    if lower.contains("compiled successfully in ") {
+        return timed("webpack", true, "compiled successfully in ");
+    }
+    if lower.contains("build successful in ") {
+        return timed("gradle", true, "build successful in ");
+    }
+    if lower.contains("build failed in ") {
+        return timed("gradle", false, "build failed in ");
+    }
+    if lower.contains("built in ") {
+        return timed("vite", true, "built in ");
+    }
+    if lower.starts_with("found ") && lower.contains(" error") {
+        let errors = count_before(lower, " error")?;
+        return Some(signal("build", Some("tsc"), Some(errors == 0)));
+    }
+    if lower.starts_with("make: ***") || (lower.starts_with("make[") && lower.contains("***")) {
+        return Some(signal("build", Some("make"), Some(false)));
+    }
+    None
+}
+
+/// Test run summaries: cargo test, pytest, jest, vitest, go test
+fn parse_tests(lower: &str) -> Option<DevSignal> {
+    let counted = |tool: Option<&str>, success: Option<bool>| {
+        let passed = count_before(lower, " passed");
+        let failed = count_before(lower, " failed");
+        if passed.is_none() && failed.is_none() {
+            return None;
+        }
+        Some(DevSignal {
+            success: success.or(Some(failed.unwrap_or(0) == 0)),
+            passed,
+            failed,
+            ..signal("test", tool, None)
+        })
+    };
+
+    if lower.starts_with("test result:") {
+        let mut s = counted(Some("cargo"), Some(lower.contains("test result: ok")))?;
+        s.duration_secs = after(lower, "finished in ").and_then(parse_duration);
+        return Some(s);
+    }
+    if lower.starts_with('=') && lower.contains(" in ") {
+        let mut s = counted(Some("pytest"), None)?;
+        s.duration_secs = lower
+            .rsplit_once(" in ")
+            .and_then(|(_, d)| parse_duration(d));
+        return Some(s);
+    }
+    if let Some(rest) = lower.strip_prefix("tests") {
+        let tool = if rest.starts_with(':') {
+            "jest"
+        } else {
+            "vitest"
+        };
+        return counted(Some(tool), None);
+    }
+
+    // go test: "ok  <package>  0.123s" / "FAIL  <package>  0.456s"
+    match lower.split_whitespace().collect::<Vec<_>>()[..] {
+        [status @ ("ok" | "fail"), package, duration]
+            if package.contains('/') || package.contains('.') =>
+        {
+            Some(DevSignal {
+                duration_secs: Some(parse_duration(duration)?),
+                ..signal("test", Some("go"), Some(status == "ok"))
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Benchmark results: criterion, go test -bench, hyperfine. `previous` is the
+/// line above, which holds the benchmark name when it isn't on this line.
+fn parse_benchmark(line: &str, lower: &str, previous: Option<&str>) -> Option<DevSignal> {
+    let bench = |name: &str, tool: &str, value: &str, unit: &str| {
+        let name = name.trim();
+        Some(DevSignal {
+            bench_name: (!name.is_empty()).then(|| name.to_string()),
+            bench_value: Some(value.parse().ok()?),
+            bench_unit: Some(unit.to_string()),
+            ..signal("benchmark", Some(tool), None)
+        })
+    };
+
+    // criterion: "<name>  time:   [26.029 µs 26.251 µs 26.505 µs]" (the
+    // middle figure is the estimate)
+    let criterion = line.find("time:").and_then(|idx| {
+        let estimates = line[idx..].split_once('[')?.1.split_once(']')?.0;
+        let [_, _, value, unit, _, _] = estimates.split_whitespace().collect::<Vec<_>>()[..] else {
+            return None;
+        };
+        let name = match line[..idx].trim() {
+            "" => previous.unwrap_or_default(),
+            name => name,
+        };
+        bench(name, "criterion", value, unit)
+    });
+    if criterion.is_some() {
+        return criterion;
+    }
+
+    // go test -bench: "BenchmarkParse-8   1000000   1234 ns/op"
+    if lower.starts_with("benchmark") && lower.contains(" ns/op") {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let unit_at = tokens.iter().position(|t| *t == "ns/op")?;
+        return bench(tokens[0], "go", tokens.get(unit_at.checked_sub(1)?)?, "ns");
+    }
+
+    // hyperfine: "Benchmark 1: cargo build" then "Time (mean ± σ):  19.3 ms ± 0.4 ms"
+    if lower.starts_with("time (mean") {
+        let mut tokens = line.split_once("):")?.1.split_whitespace();
+        let (value, unit) = (tokens.next()?, tokens.next()?);
+        let name = previous
+            .and_then(|p| p.split_once(": "))
+            .map(|(_, command)| command)
+            .unwrap_or_default();
+        return bench(name, "hyperfine", value, unit);
+    }
+    None
+}
+
+/// Text following the first occurrence of `marker`
+fn after<'a>(text: &'a str, marker: &str) -> Option<&'a str> {
+    text.find(marker).map(|idx| &text[idx + marker.len()..])
+}
+
+/// The number immediately before an occurrence of `word`, e.g. 42 in
+/// "test result: failed. 42 passed"
+fn count_before(text: &str, word: &str) -> Option<i32> {
+    text.match_indices(word).find_map(|(idx, _)| {
+        let before = text[..idx].trim_end();
+        let digits_start = before.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        before[digits_start..].parse().ok()
+    })
+}
+
+/// Seconds in a duration at the start of `text`: "19.32s", "1m 04s", "1234ms",
+/// "3.2 secs"
+fn parse_duration(text: &str) -> Option<f64> {
+    let mut rest = text.trim_start();
+    let mut total = None;
+    loop {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        if number_len == 0 {
+            break;
+        }
+        let Ok(value) = rest[..number_len].parse::<f64>() else {
+            break;
+        };
+        let unit_start = rest[number_len..].trim_start();
+        let unit_len = unit_start
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(unit_start.len());
+        let secs = match &unit_start[..unit_len] {
+            "ms" => value / 1000.0,
+            "s" | "sec" | "secs" | "seconds" => value,
+            "m" | "min" | "mins" | "minutes" => value * 60.0,
+            "h" => value * 3600.0,
+            _ => break,
+        };
+        total = Some(total.unwrap_or(0.0) + secs);
+        rest = unit_start[unit_len..].trim_start();
+    }
+    total
+}
+
+pub fn dev_signals_poll_interval_secs() -> u64 {
+    crate::config::get().workers.dev_signals.poll_interval_secs
+}
+
+fn dev_signals_batch_size() -> i64 {
+    crate::config::get().workers.dev_signals.batch_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_builds_and_tests() {
+        let text = "   Compiling api v0.1.0\n\
+                    Finished `release` profile [optimized] target(s) in 1m 04s\n\
+                    test result: FAILED. 41 passed; 1 failed; 0 ignored; finished in 3.21s\n\
+                    ===== 40 passed, 2 failed in 0.87s =====\n\
+                    Tests:       40 passed, 40 total\n\
+                    \u{2713} built in 812ms\n\
+                    ok  github.com/acme/api/parse  0.123s\n\
+                    Finished `release` profile [optimized] target(s) in 1m 04s";
+        let signals = parse_signals(text);
+        let summary: Vec<_> = signals
+            .iter()
+            .map(|s| {
+                (
+                    s.kind.as_str(),
+                    s.tool.as_deref(),
+                    s.success,
+                    s.passed,
+                    s.failed,
+                    s.duration_secs,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("build", Some("cargo"), Some(true), None, None, Some(64.0)),
+                (
+                    "test",
+                    Some("cargo"),
+                    Some(false),
+                    Some(41),
+                    Some(1),
+                    Some(3.21)
+                ),
+                (
+                    "test",
+                    Some("pytest"),
+                    Some(false),
+                    Some(40),
+                    Some(2),
+                    Some(0.87)
+                ),
+                ("test", Some("jest"), Some(true), Some(40), None, None),
+                ("build", Some("vite"), Some(true), None, None, Some(0.812)),
+                ("test", Some("go"), Some(true), None, None, Some(0.123)),
+            ]
+        );
+        assert!(signals[0].excerpt.starts_with("Finished `release`"));
+    }
+
+    #[test]
+    fn parses_benchmarks() {
+        let text = "fib 20                  time:   [26.029 µs 26.251 µs 26.505 µs]\n\
+                    BenchmarkParse-8   1000000   1234 ns/op\n\
+                    Benchmark 1: cargo build\n\
+                    Time (mean ± σ):     19.3 ms ±   0.4 ms    [User: 12.1 ms, System: 6.2 ms]";
+        let benches: Vec<_> = parse_signals(text)
+            .into_iter()
+            .map(|s| {
+                format!(
+                    "{} {}: {} {}",
+                    s.tool.unwrap(),
+                    s.bench_name.unwrap(),
+                    s.bench_value.unwrap(),
+                    s.bench_unit.unwrap()
+                )
+            })
+            .collect();
+        assert_eq!(
+            benches,
+            [
+                "criterion fib 20: 26.251 µs",
+                "go BenchmarkParse-8: 1234 ns",
+                "hyperfine cargo build: 19.3 ms",
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_prose() {
+        assert!(parse_signals("this CLI is built in Rust\nfound 3 bugs today").is_empty());
+        assert_eq!(parse_duration("2m 5.5s,"), Some(125.5));
+        assert_eq!(parse_duration("Rust"), None);
+    }
+}
@@ -6,31 +6,151 @@
 use chrono::{DateTime, Utc};
 use sqlx::{Executor, Postgres};
 
-/// Insert an activity record
+/// Insert an activity record. `project_key` is the project inferred from a
+/// ForegroundSwitch's window title (services::projects).
+#[allow(clippy::too_many_arguments)]
 pub async fn insert_activity<'e, E>(
     executor: E,
     user_id: i64,
     timestamp: DateTime<Utc>,
     interval_id: i64,
+    session_id: Option<i64>,
+    device_id: Option<i64>,
     event_type: &str,
     application: Option<&str>,
     window: Option<&str>,
+    project_key: Option<&str>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query!(
+        r#"
+        INSERT INTO activities (user_id, timestamp, interval_id, session_id, device_id, event_type, application, "window", project_key)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+        user_id,
+        timestamp,
+        interval_id,
+        session_id,
+        device_id,
+        event_type,
+        application,
+        window,
+        project_key,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// The device's latest ForegroundSwitch in `[since, before)`, as
+/// (timestamp, application, window)
+pub async fn last_foreground_switch<'e, E>(
+    executor: E,
+    user_id: i64,
+    device_id: Option<i64>,
+    since: DateTime<Utc>,
+    before: DateTime<Utc>,
+) -> Result<Option<(DateTime<Utc>, String, String)>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT "timestamp", COALESCE(application, ''), COALESCE("window", '')
+        FROM activities
+        WHERE user_id = $1
+          AND device_id IS NOT DISTINCT FROM $2
+          AND event_type = 'ForegroundSwitch'
+          AND "timestamp" >= $3
+          AND "timestamp" < $4
+        ORDER BY "timestamp" DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(device_id)
+    .bind(since)
+    .bind(before)
+    .fetch_optional(executor)
+    .await
+}
+
+/// A per-interval activity rollup reported by the daemon
+#[derive(Debug)]
+pub struct NewActivitySummary<'a> {
+    pub user_id: i64,
+    pub device_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub interval_id: i64,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub top_app: Option<&'a str>,
+    pub app_switches: i32,
+    pub clicks: i32,
+    pub keypresses: i32,
+    pub active_secs: i32,
+}
+
+/// Insert an activity summary record
+pub async fn insert_summary<'e, E>(
+    executor: E,
+    summary: NewActivitySummary<'_>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO activity_summaries (user_id, device_id, session_id, interval_id, period_start,
+                                        period_end, top_app, app_switches, clicks, keypresses, active_secs)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        "#,
+    )
+    .bind(summary.user_id)
+    .bind(summary.device_id)
+    .bind(summary.session_id)
+    .bind(summary.interval_id)
+    .bind(summary.period_start)
+    .bind(summary.period_end)
+    .bind(summary.top_app)
+    .bind(summary.app_switches)
+    .bind(summary.clicks)
+    .bind(summary.keypresses)
+    .bind(summary.active_secs)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Insert a TabContext activity reported by the browser extension
+pub async fn insert_tab_activity<'e, E>(
+    executor: E,
+    user_id: i64,
+    timestamp: DateTime<Utc>,
+    interval_id: i64,
+    host: &str,
+    title: &str,
+    url: &str,
 ) -> Result<(), sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
 {
     sqlx::query(
         r#"
-        INSERT INTO activities (user_id, timestamp, interval_id, event_type, application, "window")
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO activities (user_id, timestamp, interval_id, event_type, application, "window", url)
+        VALUES ($1, $2, $3, 'TabContext', $4, $5, $6)
         "#,
     )
     .bind(user_id)
     .bind(timestamp)
     .bind(interval_id)
-    .bind(event_type)
-    .bind(application)
-    .bind(window)
+    .bind(host)
+    .bind(title)
+    .bind(url)
     .execute(executor)
     .await?;
 
@@ -0,0 +1,293 @@
+//! Archives domain - DB queries for monthly capture archival
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct Archive {
+    pub id: i64,
+    pub month: NaiveDate,
+    pub status: String,
+    pub archive_path: Option<String>,
+    pub capture_count: i64,
+    pub activity_count: i64,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Claimed archive job
+#[derive(Debug, sqlx::FromRow)]
+pub struct ArchiveJob {
+    pub id: i64,
+    pub month: NaiveDate,
+}
+
+/// Capture row exported to an archive; `row` is the full row as JSON
+#[derive(Debug, sqlx::FromRow)]
+pub struct ArchivedCapture {
+    pub id: i64,
+    pub gcs_path: String,
+    pub thumbnail_path: Option<String>,
+    pub row: serde_json::Value,
+}
+
+/// Activity row exported to an archive
+#[derive(Debug, sqlx::FromRow)]
+pub struct ArchivedActivity {
+    pub id: i64,
+    pub row: serde_json::Value,
+}
+
+/// Queue a month for archival. Re-requesting a failed month re-queues it;
+/// pending, running and completed jobs are returned unchanged.
+pub async fn request_archive<'e, E>(
+    executor: E,
+    month: NaiveDate,
+    requested_by: i64,
+) -> Result<Archive, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO capture_archives (month, requested_by)
+        VALUES ($1, $2)
+        ON CONFLICT (month) DO UPDATE SET
+            status = CASE WHEN capture_archives.status = 'failed' THEN 'pending' ELSE capture_archives.status END,
+            attempts = CASE WHEN capture_archives.status = 'failed' THEN 0 ELSE capture_archives.attempts END,
+            error = CASE WHEN capture_archives.status = 'failed' THEN NULL ELSE capture_archives.error END
+        RETURNING id, month, status, archive_path, capture_count, activity_count,
+                  attempts, error, requested_at, completed_at
+        "#,
+    )
+    .bind(month)
+    .bind(requested_by)
+    .fetch_one(executor)
+    .await
+}
+
+/// List archive jobs, newest month first
+pub async fn list_archives<'e, E>(executor: E) -> Result<Vec<Archive>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, month, status, archive_path, capture_count, activity_count,
+               attempts, error, requested_at, completed_at
+        FROM capture_archives
+        ORDER BY month DESC
+        "#,
+    )
+    .fetch_all(executor)
+    .await
+}
+
+/// Claim one pending (or lease-expired) archive job
+pub async fn claim_archive_job<'e, E>(
+    executor: E,
+    lease_secs: i64,
+    max_attempts: i32,
+) -> Result<Option<ArchiveJob>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        UPDATE capture_archives
+        SET status = 'exporting',
+            attempts = attempts + 1,
+            lease_until = NOW() + ($1 * INTERVAL '1 second')
+        WHERE id = (
+            SELECT id FROM capture_archives
+            WHERE (status = 'pending' OR (status = 'exporting' AND lease_until < NOW()))
+              AND attempts < $2
+            ORDER BY requested_at ASC
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, month
+        "#,
+    )
+    .bind(lease_secs)
+    .bind(max_attempts)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Mark an archive job completed
+pub async fn complete_archive<'e, E>(
+    executor: E,
+    archive_id: i64,
+    archive_path: &str,
+    capture_count: i64,
+    activity_count: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE capture_archives
+        SET status = 'completed', archive_path = $2, capture_count = $3, activity_count = $4,
+            lease_until = NULL, error = NULL, completed_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(archive_id)
+    .bind(archive_path)
+    .bind(capture_count)
+    .bind(activity_count)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed attempt; the job is retried until it runs out of attempts
+pub async fn fail_archive<'e, E>(
+    executor: E,
+    archive_id: i64,
+    error: &str,
+    max_attempts: i32,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE capture_archives
+        SET status = CASE WHEN attempts >= $3 THEN 'failed' ELSE 'pending' END,
+            error = $2, lease_until = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(archive_id)
+    .bind(error)
+    .bind(max_attempts)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Fetch a batch of captures in [start, end) with id > after_id, ordered by id
+pub async fn fetch_capture_batch<'e, E>(
+    executor: E,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<ArchivedCapture>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT c.id, c.gcs_path, c.thumbnail_path, to_jsonb(c) AS row
+        FROM captures c
+        WHERE c.captured_at >= $1 AND c.captured_at < $2 AND c.id > $3
+        ORDER BY c.id ASC
+        LIMIT $4
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Fetch a batch of activities in [start, end) with id > after_id, ordered by id
+pub async fn fetch_activity_batch<'e, E>(
+    executor: E,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<ArchivedActivity>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT a.id, to_jsonb(a) AS row
+        FROM activities a
+        WHERE a."timestamp" >= $1 AND a."timestamp" < $2 AND a.id > $3
+        ORDER BY a.id ASC
+        LIMIT $4
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Delete a month of captures, activities and dev signals, then drop any chunks left empty.
+/// Captures still used by a draft (unposted, undismissed tweet) are kept, and so are the
+/// capture chunks holding them; returns their ids so the caller keeps their media too.
+/// Run inside a transaction so a failure leaves the month intact.
+pub async fn drop_month(
+    conn: &mut sqlx::PgConnection,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<i64>, sqlx::Error> {
+    let kept: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT c.id
+        FROM captures c
+        JOIN tweet_captures tc ON tc.capture_id = c.id
+        JOIN tweet_collateral t ON t.id = tc.tweet_id
+        WHERE c.captured_at >= $1 AND c.captured_at < $2
+          AND t.posted_at IS NULL AND t.dismissed_at IS NULL
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "DELETE FROM captures WHERE captured_at >= $1 AND captured_at < $2 AND id <> ALL($3)",
+    )
+    .bind(start)
+    .bind(end)
+    .bind(&kept)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(r#"DELETE FROM activities WHERE "timestamp" >= $1 AND "timestamp" < $2"#)
+        .bind(start)
+        .bind(end)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DELETE FROM dev_signals WHERE captured_at >= $1 AND captured_at < $2")
+        .bind(start)
+        .bind(end)
+        .execute(&mut *conn)
+        .await?;
+
+    // Monthly chunks fall entirely inside the range and are dropped; older weekly
+    // chunks straddling the boundary stay (already emptied of this month's rows).
+    // Dropping a chunk drops every row in it, kept captures included.
+    let tables: &[&str] = if kept.is_empty() {
+        &["captures", "activities"]
+    } else {
+        &["activities"]
+    };
+    for table in tables {
+        sqlx::query("SELECT drop_chunks($1::regclass, older_than => $3, newer_than => $2)")
+            .bind(table)
+            .bind(start)
+            .bind(end)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(kept)
+}
@@ -0,0 +1,92 @@
+//! Audit log domain - DB queries for the per-user audit trail
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub detail: Option<serde_json::Value>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct AuditEntryWithTotal {
+    #[sqlx(flatten)]
+    entry: AuditEntry,
+    total_count: i64,
+}
+
+/// A new audit log row
+#[derive(Debug)]
+pub struct NewAuditEntry<'a> {
+    pub user_id: i64,
+    pub actor: &'a str,
+    pub action: &'a str,
+    pub target: Option<&'a str>,
+    pub detail: Option<serde_json::Value>,
+    pub ip: Option<&'a str>,
+    pub user_agent: Option<&'a str>,
+}
+
+/// Append an entry to a user's audit log
+pub async fn insert_entry<'e, E>(executor: E, entry: NewAuditEntry<'_>) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO audit_log (user_id, actor, action, target, detail, ip, user_agent)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(entry.user_id)
+    .bind(entry.actor)
+    .bind(entry.action)
+    .bind(entry.target)
+    .bind(entry.detail)
+    .bind(entry.ip)
+    .bind(entry.user_agent)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// List a user's audit log, newest first, with the total count
+pub async fn list_entries_paginated<'e, E>(
+    executor: E,
+    user_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<AuditEntry>, i64), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let rows: Vec<AuditEntryWithTotal> = sqlx::query_as(
+        r#"
+        SELECT id, actor, action, target, detail, ip, user_agent, created_at,
+               COUNT(*) OVER() AS total_count
+        FROM audit_log
+        WHERE user_id = $1
+        ORDER BY created_at DESC, id DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(executor)
+    .await?;
+
+    let total = rows.first().map(|r| r.total_count).unwrap_or(0);
+    Ok((rows.into_iter().map(|r| r.entry).collect(), total))
+}
@@ -0,0 +1,124 @@
+//! Capture shares domain - DB queries for expiring public links to captures
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct CaptureShare {
+    pub id: i64,
+    pub capture_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub view_count: i32,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+}
+
+/// The capture behind an active share link
+#[derive(Debug, sqlx::FromRow)]
+pub struct SharedCapture {
+    pub user_id: i64,
+    pub gcs_path: String,
+}
+
+/// Store a new share by its token hash
+pub async fn insert_share<'e, E>(
+    executor: E,
+    user_id: i64,
+    capture_id: i64,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<CaptureShare, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO capture_shares (user_id, capture_id, token_hash, expires_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, capture_id, created_at, expires_at, revoked_at, view_count, last_viewed_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(capture_id)
+    .bind(token_hash)
+    .bind(expires_at)
+    .fetch_one(executor)
+    .await
+}
+
+/// List the shares of one of the user's captures, newest first
+pub async fn list_shares<'e, E>(
+    executor: E,
+    user_id: i64,
+    capture_id: i64,
+) -> Result<Vec<CaptureShare>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, capture_id, created_at, expires_at, revoked_at, view_count, last_viewed_at
+        FROM capture_shares
+        WHERE user_id = $1 AND capture_id = $2
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(capture_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Revoke a share. Returns false if the user has no such active share.
+pub async fn revoke_share<'e, E>(
+    executor: E,
+    user_id: i64,
+    capture_id: i64,
+    id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE capture_shares SET revoked_at = NOW()
+        WHERE id = $1 AND user_id = $2 AND capture_id = $3 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(capture_id)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Look up an unexpired, unrevoked share by token hash and count the view
+pub async fn view_share<'e, E>(
+    executor: E,
+    token_hash: &str,
+) -> Result<Option<SharedCapture>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        WITH share AS (
+            UPDATE capture_shares
+            SET view_count = view_count + 1, last_viewed_at = NOW()
+            WHERE token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            RETURNING user_id, capture_id
+        )
+        SELECT c.user_id, c.gcs_path
+        FROM share s
+        JOIN captures c ON c.id = s.capture_id AND c.user_id = s.user_id
+        "#,
+    )
+    .bind(token_hash)
+    .fetch_optional(executor)
+    .await
+}
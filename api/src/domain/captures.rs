@@ -6,6 +6,8 @@
 use chrono::{DateTime, Utc};
 use sqlx::{Executor, Postgres};
 
+use super::projects::CAPTURE_PROJECT_KEY;
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct CaptureMedia {
     pub gcs_path: String,
@@ -14,6 +16,8 @@ pub struct CaptureMedia {
 
 #[derive(Debug, sqlx::FromRow)]
 pub struct CaptureThumbnail {
+    pub media_type: String,
+    pub gcs_path: String,
     pub thumbnail_path: Option<String>,
 }
 
@@ -24,6 +28,17 @@ pub struct CaptureRow {
     pub content_type: String,
     pub captured_at: DateTime<Utc>,
     pub thumbnail_path: Option<String>,
+    pub device_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub excluded_from_agent: bool,
+    pub in_public_log: bool,
+    pub duration_secs: Option<f64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub codec: Option<String>,
+    pub file_size_bytes: Option<i64>,
+    /// Unposted, undismissed tweets that use this capture
+    pub used_in_drafts: i64,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -52,7 +67,7 @@ where
     .await
 }
 
-/// Get capture thumbnail path
+/// Get capture thumbnail path, plus the original for resizing
 pub async fn get_capture_thumbnail<'e, E>(
     executor: E,
     capture_id: i64,
@@ -63,7 +78,7 @@ where
 {
     sqlx::query_as(
         r#"
-        SELECT thumbnail_path FROM captures
+        SELECT media_type, gcs_path, thumbnail_path FROM captures
         WHERE id = $1 AND user_id = $2
         "#,
     )
@@ -73,6 +88,37 @@ where
     .await
 }
 
+#[derive(Debug, sqlx::FromRow)]
+pub struct CaptureThumbnailWithId {
+    pub id: i64,
+    pub media_type: String,
+    pub gcs_path: String,
+    pub thumbnail_path: Option<String>,
+}
+
+/// Get thumbnail paths for several of the user's captures, in the order requested
+pub async fn get_capture_thumbnails<'e, E>(
+    executor: E,
+    user_id: i64,
+    capture_ids: &[i64],
+) -> Result<Vec<CaptureThumbnailWithId>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT c.id, c.media_type, c.gcs_path, c.thumbnail_path
+        FROM unnest($2::bigint[]) WITH ORDINALITY AS req(id, ord)
+        JOIN captures c ON c.id = req.id AND c.user_id = $1
+        ORDER BY req.ord
+        "#,
+    )
+    .bind(user_id)
+    .bind(capture_ids)
+    .fetch_all(executor)
+    .await
+}
+
 /// Check whether a media path (capture or thumbnail) belongs to the user
 pub async fn user_owns_media_path<'e, E>(
     executor: E,
@@ -108,12 +154,24 @@ pub struct CaptureRowWithTotal {
     pub content_type: String,
     pub captured_at: DateTime<Utc>,
     pub thumbnail_path: Option<String>,
+    pub device_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub excluded_from_agent: bool,
+    pub in_public_log: bool,
+    pub duration_secs: Option<f64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub codec: Option<String>,
+    pub file_size_bytes: Option<i64>,
+    pub used_in_drafts: i64,
     pub total_count: i64,
 }
 
 /// Browse captures with optional filters and pagination, returning total count in same query
 /// Uses window function to avoid race condition between SELECT and COUNT
 /// If include_ids is provided, those captures are always included (for showing selected items)
+/// Ties on captured_at are broken by device so captures from different machines don't interleave
+/// `project` keeps captures taken while that project was in the foreground
 #[allow(clippy::too_many_arguments)]
 pub async fn browse_captures_with_count<'e, E>(
     executor: E,
@@ -121,6 +179,8 @@ pub async fn browse_captures_with_count<'e, E>(
     start_time: Option<DateTime<Utc>>,
     end_time: Option<DateTime<Utc>>,
     media_type: Option<&str>,
+    device_id: Option<i64>,
+    project: Option<&str>,
     limit: i64,
     offset: i64,
     _include_ids: Option<&[i64]>,
@@ -132,25 +192,36 @@ where
     // the executor. For transaction use, caller should handle include_ids separately.
     // The main query provides the core functionality.
 
-    let rows: Vec<CaptureRowWithTotal> = sqlx::query_as(
+    let rows: Vec<CaptureRowWithTotal> = sqlx::query_as(&format!(
         r#"
-        SELECT id, media_type, content_type, captured_at, thumbnail_path,
+        SELECT id, media_type, content_type, captured_at, thumbnail_path, device_id, session_id,
+               excluded_from_agent, in_public_log, duration_secs, width, height, codec,
+               file_size_bytes,
+               (SELECT COUNT(*) FROM tweet_captures tc
+                JOIN tweet_collateral t ON t.id = tc.tweet_id
+                WHERE tc.capture_id = c.id AND t.posted_at IS NULL AND t.dismissed_at IS NULL
+               ) AS used_in_drafts,
                COUNT(*) OVER() as total_count
-        FROM captures
+        FROM captures c
         WHERE user_id = $1
           AND ($2::timestamptz IS NULL OR captured_at >= $2)
           AND ($3::timestamptz IS NULL OR captured_at <= $3)
           AND ($4::text IS NULL OR media_type = $4)
-        ORDER BY captured_at DESC
-        LIMIT $5 OFFSET $6
+          AND ($5::bigint IS NULL OR device_id = $5)
+          AND ($8::text IS NULL OR {} = lower($8))
+        ORDER BY captured_at DESC, device_id NULLS LAST, id DESC
+        LIMIT $6 OFFSET $7
         "#,
-    )
+        CAPTURE_PROJECT_KEY
+    ))
     .bind(user_id)
     .bind(start_time)
     .bind(end_time)
     .bind(media_type)
+    .bind(device_id)
     .bind(limit)
     .bind(offset)
+    .bind(project)
     .fetch_all(executor)
     .await?;
 
@@ -166,47 +237,111 @@ where
             content_type: r.content_type,
             captured_at: r.captured_at,
             thumbnail_path: r.thumbnail_path,
+            device_id: r.device_id,
+            session_id: r.session_id,
+            excluded_from_agent: r.excluded_from_agent,
+            in_public_log: r.in_public_log,
+            duration_secs: r.duration_secs,
+            width: r.width,
+            height: r.height,
+            codec: r.codec,
+            file_size_bytes: r.file_size_bytes,
+            used_in_drafts: r.used_in_drafts,
         })
         .collect();
 
     Ok((captures, total))
 }
 
-#[derive(Debug, sqlx::FromRow)]
-pub struct InsertedCapture {
-    pub id: i64,
+/// Set whether a capture is withheld from the content agent.
+/// Returns false if the capture doesn't exist or belongs to another user.
+pub async fn set_capture_excluded<'e, E>(
+    executor: E,
+    capture_id: i64,
+    user_id: i64,
+    excluded: bool,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE captures SET excluded_from_agent = $3
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(capture_id)
+    .bind(user_id)
+    .bind(excluded)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Set whether a capture appears in the user's public build log.
+/// Returns false if the capture doesn't exist or belongs to another user.
+pub async fn set_capture_in_public_log<'e, E>(
+    executor: E,
+    capture_id: i64,
+    user_id: i64,
+    included: bool,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE captures SET in_public_log = $3
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(capture_id)
+    .bind(user_id)
+    .bind(included)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
 }
 
 /// Insert a new capture record
+#[allow(clippy::too_many_arguments)]
 pub async fn insert_capture<'e, E>(
     executor: E,
     interval_id: i64,
+    session_id: Option<i64>,
+    device_id: Option<i64>,
     user_id: i64,
     media_type: &str,
     content_type: &str,
     gcs_path: &str,
     captured_at: DateTime<Utc>,
+    file_size_bytes: i64,
+    sha256: &str,
 ) -> Result<i64, sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
 {
-    let result: InsertedCapture = sqlx::query_as(
+    sqlx::query_scalar!(
         r#"
-        INSERT INTO captures (interval_id, user_id, media_type, content_type, gcs_path, captured_at)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO captures (interval_id, session_id, device_id, user_id, media_type, content_type, gcs_path, captured_at, file_size_bytes, sha256)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
         RETURNING id
         "#,
+        interval_id,
+        session_id,
+        device_id,
+        user_id,
+        media_type,
+        content_type,
+        gcs_path,
+        captured_at,
+        file_size_bytes,
+        sha256,
     )
-    .bind(interval_id)
-    .bind(user_id)
-    .bind(media_type)
-    .bind(content_type)
-    .bind(gcs_path)
-    .bind(captured_at)
     .fetch_one(executor)
-    .await?;
-
-    Ok(result.id)
+    .await
 }
 
 /// Verify captures belong to user
@@ -229,12 +364,64 @@ where
     Ok(result.count == capture_ids.len() as i64)
 }
 
+/// The subset of `capture_ids` that exist and belong to the user
+pub async fn owned_capture_ids<'e, E>(
+    executor: E,
+    capture_ids: &[i64],
+    user_id: i64,
+) -> Result<Vec<i64>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT id FROM captures WHERE id = ANY($1) AND user_id = $2")
+        .bind(capture_ids)
+        .bind(user_id)
+        .fetch_all(executor)
+        .await
+}
+
+/// A tweet that uses a capture, from `tweet_captures`
+#[derive(Debug, sqlx::FromRow)]
+pub struct CaptureTweet {
+    pub tweet_id: i64,
+    pub thread_id: Option<i64>,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    pub posted_at: Option<DateTime<Utc>>,
+}
+
+/// Tweets (drafts and posted, not dismissed) using a capture, newest first
+pub async fn list_capture_tweets<'e, E>(
+    executor: E,
+    capture_id: i64,
+    user_id: i64,
+) -> Result<Vec<CaptureTweet>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT t.id AS tweet_id, t.thread_id, t.text, t.created_at, t.posted_at
+        FROM tweet_captures tc
+        JOIN tweet_collateral t ON t.id = tc.tweet_id
+        WHERE tc.capture_id = $1 AND tc.user_id = $2 AND t.dismissed_at IS NULL
+        ORDER BY t.created_at DESC, t.id DESC
+        "#,
+    )
+    .bind(capture_id)
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
 /// Capture info for media upload
 #[derive(Debug, sqlx::FromRow)]
 pub struct CaptureInfo {
     pub id: i64,
     pub gcs_path: String,
     pub content_type: String,
+    /// Known once the frame worker has probed the video
+    pub duration_secs: Option<f64>,
 }
 
 /// Get single capture info for media upload
@@ -246,7 +433,7 @@ pub async fn get_capture_info<'e, E>(
 where
     E: Executor<'e, Database = Postgres>,
 {
-    sqlx::query_as("SELECT id, gcs_path, content_type FROM captures WHERE id = $1 AND user_id = $2")
+    sqlx::query_as("SELECT id, gcs_path, content_type, duration_secs FROM captures WHERE id = $1 AND user_id = $2")
         .bind(capture_id)
         .bind(user_id)
         .fetch_optional(executor)
@@ -263,7 +450,7 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let rows: Vec<CaptureInfo> = sqlx::query_as(
-        "SELECT id, gcs_path, content_type FROM captures WHERE id = ANY($1) AND user_id = $2",
+        "SELECT id, gcs_path, content_type, duration_secs FROM captures WHERE id = ANY($1) AND user_id = $2",
     )
     .bind(capture_ids)
     .bind(user_id)
@@ -272,3 +459,34 @@ where
 
     Ok(rows.into_iter().map(|r| (r.id, r)).collect())
 }
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct CaptureUsage {
+    pub captures_today: i64,
+    pub bytes_today: i64,
+    pub stored_bytes: i64,
+}
+
+/// Captures and bytes ingested since `day_start`, plus total stored bytes
+pub async fn get_capture_usage<'e, E>(
+    executor: E,
+    user_id: i64,
+    day_start: DateTime<Utc>,
+) -> Result<CaptureUsage, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FILTER (WHERE captured_at >= $2) AS captures_today,
+               COALESCE(SUM(file_size_bytes) FILTER (WHERE captured_at >= $2), 0)::BIGINT AS bytes_today,
+               COALESCE(SUM(file_size_bytes), 0)::BIGINT AS stored_bytes
+        FROM captures
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(day_start)
+    .fetch_one(executor)
+    .await
+}
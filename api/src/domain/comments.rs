@@ -0,0 +1,211 @@
+//! Collateral comments domain - DB queries for review comments on tweet collateral
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+use std::collections::HashMap;
+
+/// Longest comment body, in characters
+pub const MAX_COMMENT_LEN: usize = 2_000;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct Comment {
+    pub id: i64,
+    /// Top-level comment this one replies to
+    pub parent_id: Option<i64>,
+    pub author_username: String,
+    pub body: String,
+    pub mentions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Lowercased @handles in a comment body, deduplicated in order of appearance.
+/// Handles follow X's rules: 1 to 15 letters, digits or underscores.
+pub fn extract_mentions(body: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    let mut chars = body.char_indices().peekable();
+    let mut prev: Option<char> = None;
+    while let Some((i, c)) = chars.next() {
+        // "a@b" is an email address, not a mention
+        let starts_mention = c == '@' && !prev.is_some_and(|p| p.is_alphanumeric() || p == '_');
+        prev = Some(c);
+        if !starts_mention {
+            continue;
+        }
+        let start = i + 1;
+        let mut end = start;
+        while let Some(&(j, h)) = chars.peek() {
+            if h.is_ascii_alphanumeric() || h == '_' {
+                end = j + 1;
+                prev = Some(h);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let handle = &body[start..end];
+        if (1..=15).contains(&handle.len()) {
+            let handle = handle.to_ascii_lowercase();
+            if !mentions.contains(&handle) {
+                mentions.push(handle);
+            }
+        }
+    }
+    mentions
+}
+
+/// Add a comment to one of the user's tweets. A reply to a reply is attached to
+/// the top-level comment. Returns None if the tweet (or parent) isn't the user's.
+pub async fn insert_comment<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+    parent_id: Option<i64>,
+    body: &str,
+    mentions: &[String],
+) -> Result<Option<Comment>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        WITH parent AS (
+            SELECT COALESCE(parent_id, id) AS root_id
+            FROM collateral_comments
+            WHERE id = $3 AND tweet_collateral_id = $2
+        ),
+        inserted AS (
+            INSERT INTO collateral_comments
+                (tweet_collateral_id, parent_id, author_user_id, body, mentions)
+            SELECT t.id, (SELECT root_id FROM parent), $1, $4, $5
+            FROM tweet_collateral t
+            WHERE t.id = $2 AND t.user_id = $1
+              AND ($3::bigint IS NULL OR EXISTS (SELECT 1 FROM parent))
+            RETURNING *
+        )
+        SELECT i.id, i.parent_id,
+               COALESCE(
+                   u.twitter_username,
+                   (SELECT l.username FROM login_identities l
+                    WHERE l.user_id = u.id ORDER BY l.id LIMIT 1)
+               ) AS author_username, i.body, i.mentions,
+               i.created_at, i.resolved_at
+        FROM inserted i
+        JOIN users u ON u.id = i.author_user_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .bind(parent_id)
+    .bind(body)
+    .bind(mentions)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Comments on one of the user's tweets, oldest first
+pub async fn list_comments<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+) -> Result<Vec<Comment>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT c.id, c.parent_id,
+               COALESCE(
+                   u.twitter_username,
+                   (SELECT l.username FROM login_identities l
+                    WHERE l.user_id = u.id ORDER BY l.id LIMIT 1)
+               ) AS author_username, c.body, c.mentions,
+               c.created_at, c.resolved_at
+        FROM collateral_comments c
+        JOIN tweet_collateral t ON t.id = c.tweet_collateral_id
+        JOIN users u ON u.id = c.author_user_id
+        WHERE c.tweet_collateral_id = $2 AND t.user_id = $1
+        ORDER BY c.created_at, c.id
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Resolve or reopen a top-level comment on one of the user's tweets.
+/// Returns false if there's no such top-level comment.
+pub async fn set_resolved<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+    comment_id: i64,
+    resolved: bool,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE collateral_comments c
+        SET resolved_at = CASE WHEN $4 THEN COALESCE(c.resolved_at, NOW()) END
+        FROM tweet_collateral t
+        WHERE c.id = $3 AND c.tweet_collateral_id = $2 AND c.parent_id IS NULL
+          AND t.id = c.tweet_collateral_id AND t.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .bind(comment_id)
+    .bind(resolved)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Unresolved top-level comments per tweet, for the tweets that have any
+pub async fn unresolved_counts<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_ids: &[i64],
+) -> Result<HashMap<i64, i64>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let rows: Vec<(i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT c.tweet_collateral_id, COUNT(*)
+        FROM collateral_comments c
+        JOIN tweet_collateral t ON t.id = c.tweet_collateral_id
+        WHERE c.tweet_collateral_id = ANY($2) AND t.user_id = $1
+          AND c.parent_id IS NULL AND c.resolved_at IS NULL
+        GROUP BY c.tweet_collateral_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_ids)
+    .fetch_all(executor)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_mentions() {
+        assert_eq!(
+            extract_mentions("@Alice can you check this? cc @bob_2, @alice"),
+            vec!["alice", "bob_2"]
+        );
+        // Emails, bare @ and over-long handles aren't mentions
+        assert!(extract_mentions("mail me@example.com or @ later").is_empty());
+        assert!(extract_mentions("@abcdefghijklmnopq").is_empty());
+        assert_eq!(extract_mentions("(@carol)"), vec!["carol"]);
+    }
+}
@@ -5,13 +5,13 @@ use sqlx::types::Json;
 use sqlx::{Executor, PgPool, Postgres};
 use std::collections::HashMap;
 
-use super::twitter::{Thread, ThreadWithTweets, Tweet};
+use super::twitter::{Longform, Thread, ThreadWithTweets, Tweet, longform};
 
 /// Content item reference from UNION query
 #[derive(Debug, Clone)]
 struct ContentRef {
     id: i64,
-    content_type: String, // "tweet" or "thread"
+    content_type: String, // "tweet", "thread" or "longform"
 }
 
 /// Internal struct for batch fetching tweets with their thread_id
@@ -23,6 +23,7 @@ struct TweetWithThreadId {
     copy_options: Json<Vec<String>>,
     video_clip: Option<serde_json::Value>,
     image_capture_ids: Vec<i64>,
+    library_asset_ids: Vec<i64>,
     media_options: Json<Vec<serde_json::Value>>,
     rationale: String,
     created_at: DateTime<Utc>,
@@ -34,6 +35,13 @@ struct TweetWithThreadId {
     reply_to_tweet_id: Option<String>,
     posted_at: Option<DateTime<Utc>>,
     tweet_id: Option<String>,
+    in_reply_to_url: Option<String>,
+    quote_tweet_url: Option<String>,
+    poll_options: Option<Vec<String>>,
+    poll_duration: Option<i32>,
+    needs_review: bool,
+    review_reason: Option<String>,
+    project: Option<String>,
 }
 
 /// Parsed content status filter enum for type-safe query building
@@ -53,55 +61,165 @@ impl ContentStatusFilter {
         }
     }
 
-    /// Tweet WHERE clause fragment (for tweet_collateral table)
-    fn tweet_where(&self) -> &'static str {
+    /// Tweet filter key bound as `$2`; queries match it against posted_at/dismissed_at
+    fn tweet_param(&self) -> &'static str {
         match self {
-            ContentStatusFilter::Pending => "AND posted_at IS NULL AND dismissed_at IS NULL",
-            ContentStatusFilter::Posted => "AND posted_at IS NOT NULL",
-            ContentStatusFilter::All => "AND dismissed_at IS NULL",
+            ContentStatusFilter::Pending => "pending",
+            ContentStatusFilter::Posted => "posted",
+            ContentStatusFilter::All => "all",
         }
     }
 
-    /// Thread WHERE clause fragment (for tweet_threads table)
-    fn thread_where(&self) -> &'static str {
+    /// Thread statuses bound as `$3::text[]` (None = no filter)
+    fn thread_statuses(&self) -> Option<&'static [&'static str]> {
         match self {
-            ContentStatusFilter::Pending => "AND status IN ('draft', 'posting', 'partial_failed')",
-            ContentStatusFilter::Posted => "AND status = 'posted'",
-            ContentStatusFilter::All => "",
+            ContentStatusFilter::Pending => Some(&["draft", "posting", "partial_failed"]),
+            ContentStatusFilter::Posted => Some(&["posted"]),
+            ContentStatusFilter::All => None,
         }
     }
 }
 
-/// Count total content items (tweets + threads) with status filter
+/// Order of the content list; ties and the rest fall back to newest first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentSort {
+    #[default]
+    CreatedAt,
+    /// Most recently posted first, unposted content last
+    PostedAt,
+    /// Most likes, reposts, replies and quotes first. Only posts measured by a
+    /// copy experiment have metrics; everything else goes last.
+    Engagement,
+}
+
+impl ContentSort {
+    /// None for an unknown key
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "created_at" => Some(ContentSort::CreatedAt),
+            "posted_at" => Some(ContentSort::PostedAt),
+            "engagement" => Some(ContentSort::Engagement),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentSort::CreatedAt => "created_at",
+            ContentSort::PostedAt => "posted_at",
+            ContentSort::Engagement => "engagement",
+        }
+    }
+}
+
+/// Filters for listing and counting content
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContentFilter<'a> {
+    /// "pending", "posted" or anything else for all
+    pub status: Option<&'a str>,
+    /// Tweets and threads tagged with this project; leaves out long-form posts
+    pub project: Option<&'a str>,
+    /// Case-insensitive substring of tweet text, thread titles and tweets,
+    /// and long-form titles and bodies
+    pub q: Option<&'a str>,
+    /// Created at or after
+    pub from: Option<DateTime<Utc>>,
+    /// Created at or before
+    pub to: Option<DateTime<Utc>>,
+    /// Only content with (true) or without (false) images or video; a thread
+    /// has media when any of its tweets does, long-form posts never do
+    pub has_media: Option<bool>,
+}
+
+impl ContentFilter<'_> {
+    /// `q` as an ILIKE pattern, with LIKE wildcards in it matched literally
+    fn pattern(&self) -> Option<String> {
+        let q = self.q.map(str::trim).filter(|q| !q.is_empty())?;
+        let escaped = q
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        Some(format!("%{}%", escaped))
+    }
+}
+
+/// Count total content items (tweets + threads + long-form posts) matching `filter`
 pub async fn count_content<'e, E>(
     executor: E,
     user_id: i64,
-    status_filter: Option<&str>,
+    filter: &ContentFilter<'_>,
 ) -> Result<i64, sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
 {
-    let filter = ContentStatusFilter::from_str(status_filter);
+    let status = ContentStatusFilter::from_str(filter.status);
 
-    let query = format!(
+    sqlx::query_scalar!(
         r#"
         SELECT (
             SELECT COUNT(*) FROM tweet_collateral
-            WHERE user_id = $1 AND thread_id IS NULL {}
+            WHERE user_id = $1 AND thread_id IS NULL
+              AND (
+                  ($2::text = 'pending' AND posted_at IS NULL AND dismissed_at IS NULL)
+                  OR ($2 = 'posted' AND posted_at IS NOT NULL)
+                  OR ($2 = 'all' AND dismissed_at IS NULL)
+              )
+              AND ($4::text IS NULL OR lower(project) = lower($4))
+              AND ($5::text IS NULL OR text ILIKE $5)
+              AND ($6::timestamptz IS NULL OR created_at >= $6)
+              AND ($7::timestamptz IS NULL OR created_at <= $7)
+              AND ($8::bool IS NULL OR $8 = (
+                  COALESCE(cardinality(image_capture_ids), 0) > 0
+                  OR COALESCE(cardinality(library_asset_ids), 0) > 0
+                  OR video_clip IS NOT NULL
+              ))
         ) + (
-            SELECT COUNT(*) FROM tweet_threads
-            WHERE user_id = $1 {}
-        )
+            SELECT COUNT(*) FROM tweet_threads t
+            WHERE user_id = $1 AND ($3::text[] IS NULL OR status = ANY($3))
+              AND ($4::text IS NULL OR EXISTS (
+                  SELECT 1 FROM tweet_collateral tc
+                  WHERE tc.thread_id = t.id AND lower(tc.project) = lower($4)
+              ))
+              AND ($5::text IS NULL OR title ILIKE $5 OR EXISTS (
+                  SELECT 1 FROM tweet_collateral tc
+                  WHERE tc.thread_id = t.id AND tc.text ILIKE $5
+              ))
+              AND ($6::timestamptz IS NULL OR created_at >= $6)
+              AND ($7::timestamptz IS NULL OR created_at <= $7)
+              AND ($8::bool IS NULL OR $8 = EXISTS (
+                  SELECT 1 FROM tweet_collateral tc
+                  WHERE tc.thread_id = t.id
+                    AND (
+                        COALESCE(cardinality(tc.image_capture_ids), 0) > 0
+                        OR COALESCE(cardinality(tc.library_asset_ids), 0) > 0
+                        OR tc.video_clip IS NOT NULL
+                    )
+              ))
+        ) + (
+            SELECT COUNT(*) FROM longform_posts
+            WHERE user_id = $1 AND $4::text IS NULL
+              AND (
+                  ($2::text = 'pending' AND posted_at IS NULL AND dismissed_at IS NULL)
+                  OR ($2 = 'posted' AND posted_at IS NOT NULL)
+                  OR ($2 = 'all' AND dismissed_at IS NULL)
+              )
+              AND ($5::text IS NULL OR title ILIKE $5 OR body ILIKE $5)
+              AND ($6::timestamptz IS NULL OR created_at >= $6)
+              AND ($7::timestamptz IS NULL OR created_at <= $7)
+              AND ($8::bool IS NULL OR NOT $8)
+        ) AS "count!"
         "#,
-        filter.tweet_where(),
-        filter.thread_where()
-    );
-
-    let (count,): (i64,) = sqlx::query_as(&query)
-        .bind(user_id)
-        .fetch_one(executor)
-        .await?;
-    Ok(count)
+        user_id,
+        status.tweet_param(),
+        status.thread_statuses() as _,
+        filter.project,
+        filter.pattern(),
+        filter.from,
+        filter.to,
+        filter.has_media,
+    )
+    .fetch_one(executor)
+    .await
 }
 
 /// List content with DB-level pagination using UNION query
@@ -109,40 +227,107 @@ where
 pub async fn list_content_paginated(
     db: &PgPool,
     user_id: i64,
-    status_filter: Option<&str>,
+    filter: &ContentFilter<'_>,
+    sort: ContentSort,
     limit: i64,
     offset: i64,
 ) -> Result<(Vec<ContentItem>, i64), sqlx::Error> {
-    let filter = ContentStatusFilter::from_str(status_filter);
+    let status = ContentStatusFilter::from_str(filter.status);
 
-    // Use UNION to get content references ordered by created_at with LIMIT/OFFSET
+    // Use UNION to get content references in `sort` order with LIMIT/OFFSET
     // This is the key optimization - we paginate in the database, not in memory
-    let query = format!(
+    let refs: Vec<(i64, String)> = sqlx::query_as(
         r#"
-        SELECT id, 'tweet' as content_type, created_at
+        SELECT id, content_type FROM (
+        SELECT id, 'tweet' as content_type, created_at, posted_at,
+               (SELECT COALESCE(e.like_count, 0) + COALESCE(e.retweet_count, 0)
+                       + COALESCE(e.reply_count, 0) + COALESCE(e.quote_count, 0)
+                FROM copy_experiments e
+                WHERE e.tweet_collateral_id = tweet_collateral.id AND e.measured_at IS NOT NULL
+               ) AS engagement
         FROM tweet_collateral
-        WHERE user_id = $1 AND thread_id IS NULL {}
+        WHERE user_id = $1 AND thread_id IS NULL
+          AND (
+              ($2::text = 'pending' AND posted_at IS NULL AND dismissed_at IS NULL)
+              OR ($2 = 'posted' AND posted_at IS NOT NULL)
+              OR ($2 = 'all' AND dismissed_at IS NULL)
+          )
+          AND ($6::text IS NULL OR lower(project) = lower($6))
+          AND ($7::text IS NULL OR text ILIKE $7)
+          AND ($8::timestamptz IS NULL OR created_at >= $8)
+          AND ($9::timestamptz IS NULL OR created_at <= $9)
+          AND ($10::bool IS NULL OR $10 = (
+              COALESCE(cardinality(image_capture_ids), 0) > 0
+              OR COALESCE(cardinality(library_asset_ids), 0) > 0
+              OR video_clip IS NOT NULL
+          ))
         UNION ALL
-        SELECT id, 'thread' as content_type, created_at
-        FROM tweet_threads
-        WHERE user_id = $1 {}
-        ORDER BY created_at DESC
-        LIMIT $2 OFFSET $3
+        SELECT id, 'thread' as content_type, created_at, posted_at,
+               (SELECT SUM(COALESCE(e.like_count, 0) + COALESCE(e.retweet_count, 0)
+                           + COALESCE(e.reply_count, 0) + COALESCE(e.quote_count, 0))::BIGINT
+                FROM tweet_collateral tc
+                JOIN copy_experiments e ON e.tweet_collateral_id = tc.id
+                WHERE tc.thread_id = t.id AND e.measured_at IS NOT NULL
+               ) AS engagement
+        FROM tweet_threads t
+        WHERE user_id = $1 AND ($3::text[] IS NULL OR status = ANY($3))
+          AND ($6::text IS NULL OR EXISTS (
+              SELECT 1 FROM tweet_collateral tc
+              WHERE tc.thread_id = t.id AND lower(tc.project) = lower($6)
+          ))
+          AND ($7::text IS NULL OR title ILIKE $7 OR EXISTS (
+              SELECT 1 FROM tweet_collateral tc
+              WHERE tc.thread_id = t.id AND tc.text ILIKE $7
+          ))
+          AND ($8::timestamptz IS NULL OR created_at >= $8)
+          AND ($9::timestamptz IS NULL OR created_at <= $9)
+          AND ($10::bool IS NULL OR $10 = EXISTS (
+              SELECT 1 FROM tweet_collateral tc
+              WHERE tc.thread_id = t.id
+                AND (
+                    COALESCE(cardinality(tc.image_capture_ids), 0) > 0
+                    OR COALESCE(cardinality(tc.library_asset_ids), 0) > 0
+                    OR tc.video_clip IS NOT NULL
+                )
+          ))
+        UNION ALL
+        SELECT id, 'longform' as content_type, created_at, posted_at, NULL::BIGINT AS engagement
+        FROM longform_posts
+        WHERE user_id = $1 AND $6::text IS NULL
+          AND (
+              ($2::text = 'pending' AND posted_at IS NULL AND dismissed_at IS NULL)
+              OR ($2 = 'posted' AND posted_at IS NOT NULL)
+              OR ($2 = 'all' AND dismissed_at IS NULL)
+          )
+          AND ($7::text IS NULL OR title ILIKE $7 OR body ILIKE $7)
+          AND ($8::timestamptz IS NULL OR created_at >= $8)
+          AND ($9::timestamptz IS NULL OR created_at <= $9)
+          AND ($10::bool IS NULL OR NOT $10)
+        ) items
+        ORDER BY
+            CASE WHEN $11 = 'posted_at' THEN posted_at END DESC NULLS LAST,
+            CASE WHEN $11 = 'engagement' THEN engagement END DESC NULLS LAST,
+            created_at DESC
+        LIMIT $4 OFFSET $5
         "#,
-        filter.tweet_where(),
-        filter.thread_where()
-    );
-
-    let refs: Vec<(i64, String, DateTime<Utc>)> = sqlx::query_as(&query)
-        .bind(user_id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(db)
-        .await?;
+    )
+    .bind(user_id)
+    .bind(status.tweet_param())
+    .bind(status.thread_statuses())
+    .bind(limit)
+    .bind(offset)
+    .bind(filter.project)
+    .bind(filter.pattern())
+    .bind(filter.from)
+    .bind(filter.to)
+    .bind(filter.has_media)
+    .bind(sort.as_str())
+    .fetch_all(db)
+    .await?;
 
     let content_refs: Vec<ContentRef> = refs
         .into_iter()
-        .map(|(id, content_type, _created_at)| ContentRef { id, content_type })
+        .map(|(id, content_type)| ContentRef { id, content_type })
         .collect();
 
     // Separate tweet and thread IDs
@@ -158,6 +343,12 @@ pub async fn list_content_paginated(
         .map(|r| r.id)
         .collect();
 
+    let longform_ids: Vec<i64> = content_refs
+        .iter()
+        .filter(|r| r.content_type == "longform")
+        .map(|r| r.id)
+        .collect();
+
     // Batch fetch tweets
     let tweets: HashMap<i64, Tweet> = if !tweet_ids.is_empty() {
         let rows: Vec<Tweet> = sqlx::query_as(
@@ -168,7 +359,9 @@ pub async fn list_content_paginated(
                    COALESCE(media_options, '[]'::jsonb) as media_options,
                    rationale, created_at,
                    publish_status, publish_attempts, publish_error, publish_error_at,
-                   thread_position, reply_to_tweet_id, posted_at, tweet_id
+                   thread_position, reply_to_tweet_id, posted_at, tweet_id,
+                   in_reply_to_url, quote_tweet_url, poll_options, poll_duration, library_asset_ids,
+                   needs_review, review_reason, project
             FROM tweet_collateral
             WHERE id = ANY($1) AND user_id = $2
             "#,
@@ -209,7 +402,9 @@ pub async fn list_content_paginated(
                    COALESCE(media_options, '[]'::jsonb) as media_options,
                    rationale, created_at,
                    publish_status, publish_attempts, publish_error, publish_error_at,
-                   thread_position, reply_to_tweet_id, posted_at, tweet_id
+                   thread_position, reply_to_tweet_id, posted_at, tweet_id,
+                   in_reply_to_url, quote_tweet_url, poll_options, poll_duration, library_asset_ids,
+                   needs_review, review_reason, project
             FROM tweet_collateral
             WHERE thread_id = ANY($1) AND user_id = $2
             ORDER BY thread_id, thread_position ASC
@@ -229,6 +424,7 @@ pub async fn list_content_paginated(
                 copy_options: tweet_row.copy_options,
                 video_clip: tweet_row.video_clip,
                 image_capture_ids: tweet_row.image_capture_ids,
+                library_asset_ids: tweet_row.library_asset_ids,
                 media_options: tweet_row.media_options,
                 rationale: tweet_row.rationale,
                 created_at: tweet_row.created_at,
@@ -240,6 +436,13 @@ pub async fn list_content_paginated(
                 reply_to_tweet_id: tweet_row.reply_to_tweet_id,
                 posted_at: tweet_row.posted_at,
                 tweet_id: tweet_row.tweet_id,
+                in_reply_to_url: tweet_row.in_reply_to_url,
+                quote_tweet_url: tweet_row.quote_tweet_url,
+                poll_options: tweet_row.poll_options,
+                poll_duration: tweet_row.poll_duration,
+                needs_review: tweet_row.needs_review,
+                review_reason: tweet_row.review_reason,
+                project: tweet_row.project,
             };
             tweets_by_thread
                 .entry(tweet_row.thread_id)
@@ -259,20 +462,32 @@ pub async fn list_content_paginated(
         HashMap::new()
     };
 
+    // Batch fetch long-form posts
+    let longforms: HashMap<i64, Longform> = if !longform_ids.is_empty() {
+        longform::get_longforms_by_ids(db, &longform_ids, user_id)
+            .await?
+            .into_iter()
+            .map(|l| (l.id, l))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
     // Build final items in the correct order (from content_refs)
     let items: Vec<ContentItem> = content_refs
         .into_iter()
-        .filter_map(|r| {
-            if r.content_type == "tweet" {
-                tweets.get(&r.id).cloned().map(ContentItem::Tweet)
-            } else {
-                threads.get(&r.id).cloned().map(ContentItem::Thread)
-            }
+        .filter_map(|r| match r.content_type.as_str() {
+            "tweet" => tweets
+                .get(&r.id)
+                .cloned()
+                .map(|t| ContentItem::Tweet(Box::new(t))),
+            "longform" => longforms.get(&r.id).cloned().map(ContentItem::Longform),
+            _ => threads.get(&r.id).cloned().map(ContentItem::Thread),
         })
         .collect();
 
     // Get total count
-    let total = count_content(db, user_id, status_filter).await?;
+    let total = count_content(db, user_id, filter).await?;
 
     Ok((items, total))
 }
@@ -280,6 +495,7 @@ pub async fn list_content_paginated(
 /// Discriminated union for content items (matches route ContentItem)
 #[derive(Debug, Clone)]
 pub enum ContentItem {
-    Tweet(Tweet),
+    Tweet(Box<Tweet>),
     Thread(ThreadWithTweets),
+    Longform(Longform),
 }
@@ -0,0 +1,127 @@
+//! Saved content views domain - named filter and sort combinations for GET /content
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{Executor, Postgres};
+
+/// The GET /content filters a view applies, as query parameter values
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewFilters {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_media: Option<bool>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ContentView {
+    pub id: i64,
+    pub name: String,
+    pub filters: Json<ViewFilters>,
+    pub sort: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Save a view. Returns None if the user already has a view with that name
+/// (compared case-insensitively).
+pub async fn insert_view<'e, E>(
+    executor: E,
+    user_id: i64,
+    name: &str,
+    filters: &ViewFilters,
+    sort: &str,
+) -> Result<Option<ContentView>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO content_views (user_id, name, filters, sort)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, lower(name)) DO NOTHING
+        RETURNING id, name, filters, sort, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(Json(filters))
+    .bind(sort)
+    .fetch_optional(executor)
+    .await
+}
+
+/// List a user's views, oldest first so the order stays put as views are added
+pub async fn list_views<'e, E>(executor: E, user_id: i64) -> Result<Vec<ContentView>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, name, filters, sort, created_at
+        FROM content_views
+        WHERE user_id = $1
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Get one of the user's views
+pub async fn get_view<'e, E>(
+    executor: E,
+    user_id: i64,
+    id: i64,
+) -> Result<Option<ContentView>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, name, filters, sort, created_at
+        FROM content_views
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Number of views the user has saved
+pub async fn count_views<'e, E>(executor: E, user_id: i64) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT COUNT(*) FROM content_views WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(executor)
+        .await
+}
+
+/// Delete a view. Returns false if the user has no such view.
+pub async fn delete_view<'e, E>(executor: E, user_id: i64, id: i64) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query("DELETE FROM content_views WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,251 @@
+//! Copy experiments domain - DB queries for A/B copy variants on drafts and their measured engagement
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct CopyExperiment {
+    pub tweet_collateral_id: i64,
+    pub variant_a: String,
+    pub style_a: String,
+    pub variant_b: String,
+    pub style_b: String,
+    /// Set with the counts once the post is 24 hours old
+    pub measured_at: Option<DateTime<Utc>>,
+    pub like_count: Option<i64>,
+    pub retweet_count: Option<i64>,
+    pub reply_count: Option<i64>,
+    pub quote_count: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Both variants of an experiment: A is posted, B is the alternative
+#[derive(Debug)]
+pub struct NewExperiment<'a> {
+    pub variant_a: &'a str,
+    pub style_a: &'a str,
+    pub variant_b: &'a str,
+    pub style_b: &'a str,
+}
+
+/// A posted experiment whose 24-hour metrics are due
+#[derive(Debug, sqlx::FromRow)]
+pub struct DueExperiment {
+    pub id: i64,
+    pub user_id: i64,
+    /// X id of the posted variant A
+    pub tweet_id: String,
+}
+
+/// Public metrics of a post, 24 hours in
+#[derive(Debug, Clone, Copy)]
+pub struct Engagement {
+    pub like_count: i64,
+    pub retweet_count: i64,
+    pub reply_count: i64,
+    pub quote_count: i64,
+}
+
+const EXPERIMENT_COLUMNS: &str = "tweet_collateral_id, variant_a, style_a, variant_b, style_b, \
+     measured_at, like_count, retweet_count, reply_count, quote_count, last_error, created_at";
+
+/// Attach an experiment to one of the user's pending drafts, replacing any it
+/// already has. Returns None if the draft isn't the user's or is no longer
+/// pending.
+pub async fn upsert_experiment<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+    experiment: &NewExperiment<'_>,
+) -> Result<Option<CopyExperiment>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        r#"
+        INSERT INTO copy_experiments
+            (user_id, tweet_collateral_id, variant_a, style_a, variant_b, style_b)
+        SELECT $1, t.id, $3, $4, $5, $6
+        FROM tweet_collateral t
+        WHERE t.id = $2 AND t.user_id = $1 AND t.posted_at IS NULL AND t.dismissed_at IS NULL
+        ON CONFLICT (tweet_collateral_id) DO UPDATE SET
+            variant_a = EXCLUDED.variant_a,
+            style_a = EXCLUDED.style_a,
+            variant_b = EXCLUDED.variant_b,
+            style_b = EXCLUDED.style_b,
+            created_at = NOW()
+        RETURNING {}
+        "#,
+        EXPERIMENT_COLUMNS
+    );
+    sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(tweet_collateral_id)
+        .bind(experiment.variant_a)
+        .bind(experiment.style_a)
+        .bind(experiment.variant_b)
+        .bind(experiment.style_b)
+        .fetch_optional(executor)
+        .await
+}
+
+/// The experiment on one of the user's tweets
+pub async fn get_experiment<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+) -> Result<Option<CopyExperiment>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        "SELECT {} FROM copy_experiments WHERE user_id = $1 AND tweet_collateral_id = $2",
+        EXPERIMENT_COLUMNS
+    );
+    sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(tweet_collateral_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Drop the experiment from a draft that hasn't been posted yet. Returns false
+/// if there was none or the draft is already out.
+pub async fn delete_experiment<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        DELETE FROM copy_experiments e
+        USING tweet_collateral t
+        WHERE e.user_id = $1 AND e.tweet_collateral_id = $2
+          AND t.id = e.tweet_collateral_id AND t.posted_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// The user's measured experiments, newest first
+pub async fn list_measured<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Vec<CopyExperiment>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        r#"
+        SELECT {} FROM copy_experiments
+        WHERE user_id = $1 AND measured_at IS NOT NULL
+        ORDER BY measured_at DESC
+        "#,
+        EXPERIMENT_COLUMNS
+    );
+    sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_all(executor)
+        .await
+}
+
+/// Lease unmeasured experiments whose post has been up for 24 hours. Posts
+/// older than a week are left alone: their 24-hour numbers are long gone.
+pub async fn claim_due<'e, E>(
+    executor: E,
+    lease_secs: i64,
+    limit: i64,
+) -> Result<Vec<DueExperiment>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        UPDATE copy_experiments e
+        SET lease_until = NOW() + ($1 * INTERVAL '1 second')
+        FROM tweet_collateral t
+        WHERE t.id = e.tweet_collateral_id
+          AND e.id IN (
+              SELECT c.id FROM copy_experiments c
+              JOIN tweet_collateral p ON p.id = c.tweet_collateral_id
+              WHERE c.measured_at IS NULL
+                AND p.tweet_id IS NOT NULL
+                AND p.posted_at <= NOW() - INTERVAL '24 hours'
+                AND p.posted_at > NOW() - INTERVAL '7 days'
+                AND (c.lease_until IS NULL OR c.lease_until < NOW())
+              ORDER BY p.posted_at
+              LIMIT $2
+              FOR UPDATE OF c SKIP LOCKED
+          )
+        RETURNING e.id, e.user_id, t.tweet_id
+        "#,
+    )
+    .bind(lease_secs)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Store the post's metrics and release the lease
+pub async fn record_engagement<'e, E>(
+    executor: E,
+    experiment_id: i64,
+    engagement: Engagement,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE copy_experiments
+        SET like_count = $2, retweet_count = $3, reply_count = $4, quote_count = $5,
+            measured_at = NOW(), last_error = NULL, lease_until = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(experiment_id)
+    .bind(engagement.like_count)
+    .bind(engagement.retweet_count)
+    .bind(engagement.reply_count)
+    .bind(engagement.quote_count)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Note why metrics couldn't be read; the lease is held for `retry_secs`
+/// before the next attempt
+pub async fn record_failure<'e, E>(
+    executor: E,
+    experiment_id: i64,
+    error: &str,
+    retry_secs: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE copy_experiments
+        SET last_error = $2, lease_until = NOW() + ($3 * INTERVAL '1 second')
+        WHERE id = $1
+        "#,
+    )
+    .bind(experiment_id)
+    .bind(error)
+    .bind(retry_secs)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,185 @@
+//! Dev signals domain - DB queries for build, test and benchmark results read
+//! from terminal captures
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+/// One recognized result. `kind` is "build", "test" or "benchmark".
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct DevSignal {
+    pub kind: String,
+    pub tool: Option<String>,
+    pub success: Option<bool>,
+    pub passed: Option<i32>,
+    pub failed: Option<i32>,
+    pub duration_secs: Option<f64>,
+    pub bench_name: Option<String>,
+    pub bench_value: Option<f64>,
+    pub bench_unit: Option<String>,
+    pub excerpt: String,
+}
+
+/// A stored signal with the capture it was read from
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CaptureSignal {
+    pub capture_id: i64,
+    pub captured_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    pub signal: DevSignal,
+}
+
+/// A capture claimed for scanning
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScanCapture {
+    pub id: i64,
+    pub user_id: i64,
+    pub media_type: String,
+    pub gcs_path: String,
+    pub captured_at: DateTime<Utc>,
+    /// A terminal app was focused when the screenshot was taken (or when the
+    /// recording ended); only these are worth OCR'ing
+    pub terminal: bool,
+}
+
+/// Mark up to `limit` unscanned captures from the last day as scanned and
+/// return them. Recordings wait for frame extraction, which records their
+/// duration. `terminal_apps` are lowercase app names.
+pub async fn claim_captures<'e, E>(
+    executor: E,
+    limit: i64,
+    terminal_apps: &[&str],
+) -> Result<Vec<ScanCapture>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        WITH claimed AS (
+            SELECT id, captured_at
+            FROM captures
+            WHERE dev_signals_scanned_at IS NULL
+              AND NOT excluded_from_agent
+              AND source_capture_id IS NULL
+              AND (media_type = 'image' OR frames_extracted)
+              AND captured_at > NOW() - INTERVAL '1 day'
+              AND captured_at < NOW() - INTERVAL '1 minute'
+            ORDER BY captured_at ASC
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE captures c
+        SET dev_signals_scanned_at = NOW()
+        FROM claimed
+        WHERE c.id = claimed.id
+          AND c.captured_at = claimed.captured_at
+        RETURNING c.id, c.user_id, c.media_type, c.gcs_path, c.captured_at,
+            COALESCE(lower((
+                SELECT a.application
+                FROM activities a
+                WHERE a.user_id = c.user_id
+                  AND a.device_id IS NOT DISTINCT FROM c.device_id
+                  AND a.application IS NOT NULL
+                  AND a."timestamp" > c.captured_at - INTERVAL '1 hour'
+                  AND a."timestamp" <= c.captured_at
+                      + make_interval(secs => COALESCE(c.duration_secs, 0))
+                ORDER BY a."timestamp" DESC
+                LIMIT 1
+            )) = ANY($2), FALSE) AS terminal
+        "#,
+    )
+    .bind(limit)
+    .bind(terminal_apps)
+    .fetch_all(executor)
+    .await
+}
+
+/// Store one signal read from a capture
+pub async fn insert_signal<'e, E>(
+    executor: E,
+    user_id: i64,
+    capture_id: i64,
+    captured_at: DateTime<Utc>,
+    signal: &DevSignal,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO dev_signals
+            (user_id, capture_id, captured_at, kind, tool, success, passed, failed,
+             duration_secs, bench_name, bench_value, bench_unit, excerpt)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        "#,
+    )
+    .bind(user_id)
+    .bind(capture_id)
+    .bind(captured_at)
+    .bind(&signal.kind)
+    .bind(&signal.tool)
+    .bind(signal.success)
+    .bind(signal.passed)
+    .bind(signal.failed)
+    .bind(signal.duration_secs)
+    .bind(&signal.bench_name)
+    .bind(signal.bench_value)
+    .bind(&signal.bench_unit)
+    .bind(&signal.excerpt)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+const SIGNAL_COLUMNS: &str = "capture_id, captured_at, kind, tool, success, passed, failed, \
+     duration_secs, bench_name, bench_value, bench_unit, excerpt";
+
+/// Signals read from these captures, oldest first
+pub async fn list_for_captures<'e, E>(
+    executor: E,
+    user_id: i64,
+    capture_ids: &[i64],
+) -> Result<Vec<CaptureSignal>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(&format!(
+        "SELECT {} FROM dev_signals
+         WHERE user_id = $1 AND capture_id = ANY($2)
+         ORDER BY captured_at ASC, id ASC",
+        SIGNAL_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(capture_ids)
+    .fetch_all(executor)
+    .await
+}
+
+/// The user's latest timed, successful build with `tool` in the two weeks
+/// before `before`, as a baseline for "cut build time from X to Y"
+pub async fn last_build_before<'e, E>(
+    executor: E,
+    user_id: i64,
+    tool: &str,
+    before: DateTime<Utc>,
+) -> Result<Option<CaptureSignal>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(&format!(
+        "SELECT {} FROM dev_signals
+         WHERE user_id = $1 AND kind = 'build' AND tool = $2
+           AND success AND duration_secs IS NOT NULL
+           AND captured_at < $3 AND captured_at > $3 - INTERVAL '14 days'
+         ORDER BY captured_at DESC
+         LIMIT 1",
+        SIGNAL_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(tool)
+    .bind(before)
+    .fetch_optional(executor)
+    .await
+}
@@ -0,0 +1,115 @@
+//! Devices domain - DB queries for daemon machines
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct Device {
+    pub id: i64,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DeviceWithStats {
+    pub id: i64,
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub integrity: Option<String>,
+    pub capture_count: i64,
+    pub last_capture_at: Option<DateTime<Utc>>,
+}
+
+/// Register a device or refresh its last-seen time (and name and integrity
+/// status, when provided). Revoked devices are returned unchanged so the caller
+/// can reject them.
+pub async fn upsert_device<'e, E>(
+    executor: E,
+    user_id: i64,
+    device_uid: &str,
+    name: Option<&str>,
+    integrity: Option<&str>,
+) -> Result<Device, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as!(
+        Device,
+        r#"
+        INSERT INTO devices (user_id, device_uid, name, integrity)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, device_uid) DO UPDATE SET
+            last_seen_at = CASE WHEN devices.revoked_at IS NULL THEN NOW() ELSE devices.last_seen_at END,
+            name = CASE WHEN devices.revoked_at IS NULL THEN COALESCE(EXCLUDED.name, devices.name) ELSE devices.name END,
+            integrity = CASE WHEN devices.revoked_at IS NULL THEN COALESCE(EXCLUDED.integrity, devices.integrity) ELSE devices.integrity END
+        RETURNING id, revoked_at
+        "#,
+        user_id,
+        device_uid,
+        name,
+        integrity,
+    )
+    .fetch_one(executor)
+    .await
+}
+
+/// List a user's devices with capture stats, most recently seen first
+pub async fn list_devices<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Vec<DeviceWithStats>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT d.id, d.name, d.created_at, d.last_seen_at, d.revoked_at, d.integrity,
+               COUNT(c.id) AS capture_count,
+               MAX(c.captured_at) AS last_capture_at
+        FROM devices d
+        LEFT JOIN captures c ON c.device_id = d.id AND c.user_id = d.user_id
+        WHERE d.user_id = $1
+        GROUP BY d.id
+        ORDER BY d.last_seen_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Revoke a device and clear the user's API token. The token is what the
+/// daemon on that machine holds, so it can't carry on by dropping X-Device-ID
+/// or sending a new one; the user's other daemons have to sign in again.
+/// Returns false if the device doesn't exist or belongs to another user.
+pub async fn revoke_device<'e, E>(
+    executor: E,
+    device_id: i64,
+    user_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        WITH revoked AS (
+            UPDATE devices
+            SET revoked_at = COALESCE(revoked_at, NOW())
+            WHERE id = $1 AND user_id = $2
+            RETURNING user_id
+        )
+        UPDATE users SET api_token = NULL, updated_at = NOW()
+        WHERE id IN (SELECT user_id FROM revoked)
+        "#,
+    )
+    .bind(device_id)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,73 @@
+//! Email login tokens domain - DB queries for one-time sign-in links
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+/// A redeemed sign-in link
+#[derive(Debug, sqlx::FromRow)]
+pub struct EmailLoginToken {
+    pub email: String,
+    /// Account the address is being added to, for links sent while signed in
+    pub link_user_id: Option<i64>,
+}
+
+/// Store a link token by its hash
+pub async fn insert_token<'e, E>(
+    executor: E,
+    token_hash: &str,
+    email: &str,
+    link_user_id: Option<i64>,
+    expires_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO email_login_tokens (token_hash, email, link_user_id, expires_at)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(token_hash)
+    .bind(email)
+    .bind(link_user_id)
+    .bind(expires_at)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Use up a link token. Returns None if it is unknown, used or expired.
+pub async fn redeem_token<'e, E>(
+    executor: E,
+    token_hash: &str,
+) -> Result<Option<EmailLoginToken>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    // Atomic DELETE + RETURNING: a link works once even if opened twice
+    sqlx::query_as(
+        r#"
+        DELETE FROM email_login_tokens
+        WHERE token_hash = $1 AND expires_at > NOW()
+        RETURNING email, link_user_id
+        "#,
+    )
+    .bind(token_hash)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Drop expired tokens
+pub async fn delete_expired<'e, E>(executor: E) -> Result<u64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query("DELETE FROM email_login_tokens WHERE expires_at <= NOW()")
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected())
+}
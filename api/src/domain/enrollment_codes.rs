@@ -0,0 +1,60 @@
+//! Enrollment codes domain - DB queries for one-time daemon enrollment codes
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+/// Store a code by its hash
+pub async fn insert_code<'e, E>(
+    executor: E,
+    user_id: i64,
+    code_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO enrollment_codes (code_hash, user_id, expires_at)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(code_hash)
+    .bind(user_id)
+    .bind(expires_at)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Use up a code. Returns its user, or None if it is unknown, used or expired.
+pub async fn redeem_code<'e, E>(executor: E, code_hash: &str) -> Result<Option<i64>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    // Atomic DELETE + RETURNING: a code works once even under concurrent redeems
+    sqlx::query_scalar(
+        r#"
+        DELETE FROM enrollment_codes
+        WHERE code_hash = $1 AND expires_at > NOW()
+        RETURNING user_id
+        "#,
+    )
+    .bind(code_hash)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Drop expired codes
+pub async fn delete_expired<'e, E>(executor: E) -> Result<u64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query("DELETE FROM enrollment_codes WHERE expires_at <= NOW()")
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected())
+}
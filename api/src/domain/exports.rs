@@ -0,0 +1,303 @@
+//! Exports domain - DB queries for user data exports (takeout)
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DataExport {
+    pub id: i64,
+    pub status: String,
+    pub archive_path: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Claimed export job
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExportJob {
+    pub id: i64,
+    pub user_id: i64,
+}
+
+/// Expired export whose archive should be removed from storage
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExpiredExport {
+    pub id: i64,
+    pub archive_path: String,
+}
+
+/// One exported table: `rows` is a JSON array of the user's rows
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExportedRows {
+    pub rows: serde_json::Value,
+}
+
+/// Capture media to include in an export
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExportedMedia {
+    pub id: i64,
+    pub gcs_path: String,
+    pub frames_extracted: bool,
+}
+
+/// Queue an export, or return the user's export that is already in flight
+pub async fn request_export<'e, E>(executor: E, user_id: i64) -> Result<DataExport, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        WITH inserted AS (
+            INSERT INTO data_exports (user_id)
+            VALUES ($1)
+            ON CONFLICT (user_id) WHERE status IN ('pending', 'building') DO NOTHING
+            RETURNING id, status, archive_path, size_bytes, error,
+                      requested_at, completed_at, expires_at
+        )
+        SELECT * FROM inserted
+        UNION ALL
+        SELECT id, status, archive_path, size_bytes, error,
+               requested_at, completed_at, expires_at
+        FROM data_exports
+        WHERE user_id = $1 AND status IN ('pending', 'building')
+          AND NOT EXISTS (SELECT 1 FROM inserted)
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(executor)
+    .await
+}
+
+/// Get one of the user's exports
+pub async fn get_export<'e, E>(
+    executor: E,
+    export_id: i64,
+    user_id: i64,
+) -> Result<Option<DataExport>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, status, archive_path, size_bytes, error,
+               requested_at, completed_at, expires_at
+        FROM data_exports
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(export_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// List the user's recent exports, newest first
+pub async fn list_exports<'e, E>(executor: E, user_id: i64) -> Result<Vec<DataExport>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, status, archive_path, size_bytes, error,
+               requested_at, completed_at, expires_at
+        FROM data_exports
+        WHERE user_id = $1
+        ORDER BY requested_at DESC
+        LIMIT 10
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Claim one pending (or lease-expired) export job
+pub async fn claim_export_job<'e, E>(
+    executor: E,
+    lease_secs: i64,
+    max_attempts: i32,
+) -> Result<Option<ExportJob>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        UPDATE data_exports
+        SET status = 'building',
+            attempts = attempts + 1,
+            lease_until = NOW() + ($1 * INTERVAL '1 second')
+        WHERE id = (
+            SELECT id FROM data_exports
+            WHERE (status = 'pending' OR (status = 'building' AND lease_until < NOW()))
+              AND attempts < $2
+            ORDER BY requested_at ASC
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, user_id
+        "#,
+    )
+    .bind(lease_secs)
+    .bind(max_attempts)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Mark an export completed; the archive is kept for `ttl_secs`
+pub async fn complete_export<'e, E>(
+    executor: E,
+    export_id: i64,
+    archive_path: &str,
+    size_bytes: i64,
+    ttl_secs: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE data_exports
+        SET status = 'completed', archive_path = $2, size_bytes = $3,
+            lease_until = NULL, error = NULL, completed_at = NOW(),
+            expires_at = NOW() + ($4 * INTERVAL '1 second')
+        WHERE id = $1
+        "#,
+    )
+    .bind(export_id)
+    .bind(archive_path)
+    .bind(size_bytes)
+    .bind(ttl_secs)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed attempt; the job is retried until it runs out of attempts
+pub async fn fail_export<'e, E>(
+    executor: E,
+    export_id: i64,
+    error: &str,
+    max_attempts: i32,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE data_exports
+        SET status = CASE WHEN attempts >= $3 THEN 'failed' ELSE 'pending' END,
+            error = $2, lease_until = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(export_id)
+    .bind(error)
+    .bind(max_attempts)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Mark completed exports past their expiry as expired, returning their archives
+pub async fn expire_exports<'e, E>(executor: E) -> Result<Vec<ExpiredExport>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        UPDATE data_exports
+        SET status = 'expired'
+        WHERE status = 'completed' AND expires_at < NOW() AND archive_path IS NOT NULL
+        RETURNING id, archive_path
+        "#,
+    )
+    .fetch_all(executor)
+    .await
+}
+
+/// Fetch all of a user's rows from a table as a JSON array.
+/// `table` must be one of the fixed export table names, never user input.
+pub async fn fetch_user_rows<'e, E>(
+    executor: E,
+    table: &'static str,
+    user_id: i64,
+) -> Result<serde_json::Value, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        "SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.id), '[]'::jsonb) AS rows
+         FROM {} t WHERE t.user_id = $1",
+        table
+    );
+    let row: ExportedRows = sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_one(executor)
+        .await?;
+    Ok(row.rows)
+}
+
+/// The user's profile, without OAuth or API tokens
+pub async fn fetch_profile<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<serde_json::Value, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let row: ExportedRows = sqlx::query_as(
+        r#"
+        SELECT to_jsonb(u) - 'access_token' - 'refresh_token' - 'api_token' AS rows
+        FROM users u
+        WHERE u.id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(executor)
+    .await?;
+    Ok(row.rows)
+}
+
+/// All of a user's capture media paths, oldest first
+pub async fn list_user_media<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Vec<ExportedMedia>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, gcs_path, COALESCE(frames_extracted, FALSE) AS frames_extracted
+        FROM captures
+        WHERE user_id = $1
+        ORDER BY captured_at ASC, id ASC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// All of a user's media library paths, oldest first
+pub async fn list_library_media<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Vec<String>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "SELECT gcs_path FROM media_assets WHERE user_id = $1 ORDER BY created_at ASC, id ASC",
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
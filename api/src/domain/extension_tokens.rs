@@ -0,0 +1,109 @@
+//! Extension tokens domain - DB queries for browser extension credentials
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExtensionToken {
+    pub id: i64,
+    pub scope: String,
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Owner and scope of an active token
+#[derive(Debug, sqlx::FromRow)]
+pub struct TokenGrant {
+    pub user_id: i64,
+    pub scope: String,
+}
+
+/// Store a new token by its hash
+pub async fn insert_token<'e, E>(
+    executor: E,
+    user_id: i64,
+    token_hash: &str,
+    scope: &str,
+    name: Option<&str>,
+) -> Result<ExtensionToken, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO extension_tokens (user_id, token_hash, scope, name)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, scope, name, created_at, last_used_at, revoked_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(token_hash)
+    .bind(scope)
+    .bind(name)
+    .fetch_one(executor)
+    .await
+}
+
+/// List a user's tokens, newest first
+pub async fn list_tokens<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Vec<ExtensionToken>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, scope, name, created_at, last_used_at, revoked_at
+        FROM extension_tokens
+        WHERE user_id = $1
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Revoke a token. Returns false if the user has no such active token.
+pub async fn revoke_token<'e, E>(executor: E, user_id: i64, id: i64) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE extension_tokens SET revoked_at = NOW()
+        WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Look up an active token by hash and mark it used
+pub async fn use_token<'e, E>(
+    executor: E,
+    token_hash: &str,
+) -> Result<Option<TokenGrant>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        UPDATE extension_tokens SET last_used_at = NOW()
+        WHERE token_hash = $1 AND revoked_at IS NULL
+        RETURNING user_id, scope
+        "#,
+    )
+    .bind(token_hash)
+    .fetch_optional(executor)
+    .await
+}
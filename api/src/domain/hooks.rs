@@ -0,0 +1,248 @@
+//! Hooks domain - DB queries for polling triggers and REST hook subscriptions
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+/// A generated tweet, as delivered to triggers
+#[derive(Debug, sqlx::FromRow)]
+pub struct NewCollateral {
+    pub id: i64,
+    pub text: String,
+    pub rationale: String,
+    pub thread_id: Option<i64>,
+    pub thread_position: Option<i32>,
+    pub image_capture_ids: Vec<i64>,
+    pub has_video: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct HookSubscription {
+    pub id: i64,
+    pub event: String,
+    pub target_url: String,
+    pub failures: i32,
+    pub last_error: Option<String>,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Subscription leased by the delivery worker
+#[derive(Debug, sqlx::FromRow)]
+pub struct ClaimedSubscription {
+    pub id: i64,
+    pub user_id: i64,
+    pub target_url: String,
+    pub cursor_id: i64,
+}
+
+/// The user's generated tweets with ids after `since`, oldest first.
+/// Without `since`, the most recent `limit` tweets, newest first.
+pub async fn list_new_collateral<'e, E>(
+    executor: E,
+    user_id: i64,
+    since: Option<i64>,
+    limit: i64,
+) -> Result<Vec<NewCollateral>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, text, rationale, thread_id, thread_position,
+               COALESCE(image_capture_ids, '{}') AS image_capture_ids,
+               video_clip IS NOT NULL AS has_video, created_at
+        FROM tweet_collateral
+        WHERE user_id = $1 AND ($2::bigint IS NULL OR id > $2)
+        ORDER BY CASE WHEN $2::bigint IS NULL THEN -id ELSE id END
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(since)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Number of subscriptions the user has
+pub async fn count_subscriptions<'e, E>(executor: E, user_id: i64) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT COUNT(*) FROM hook_subscriptions WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(executor)
+        .await
+}
+
+/// Subscribe a URL to an event, starting after the user's latest tweet.
+/// Subscribing the same URL again re-enables it and resets its failures.
+pub async fn insert_subscription<'e, E>(
+    executor: E,
+    user_id: i64,
+    event: &str,
+    target_url: &str,
+) -> Result<HookSubscription, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO hook_subscriptions (user_id, event, target_url, cursor_id)
+        VALUES ($1, $2, $3,
+                (SELECT COALESCE(MAX(id), 0) FROM tweet_collateral WHERE user_id = $1))
+        ON CONFLICT (user_id, event, target_url) DO UPDATE
+        SET disabled_at = NULL, failures = 0, last_error = NULL
+        RETURNING id, event, target_url, failures, last_error, disabled_at, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(event)
+    .bind(target_url)
+    .fetch_one(executor)
+    .await
+}
+
+/// The user's subscriptions, oldest first
+pub async fn list_subscriptions<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Vec<HookSubscription>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, event, target_url, failures, last_error, disabled_at, created_at
+        FROM hook_subscriptions
+        WHERE user_id = $1
+        ORDER BY id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Remove one of the user's subscriptions. Returns false if there was none.
+pub async fn delete_subscription<'e, E>(
+    executor: E,
+    user_id: i64,
+    subscription_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query("DELETE FROM hook_subscriptions WHERE id = $1 AND user_id = $2")
+        .bind(subscription_id)
+        .bind(user_id)
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lease active subscriptions with undelivered tweets
+pub async fn claim_subscriptions<'e, E>(
+    executor: E,
+    lease_secs: i64,
+    limit: i64,
+) -> Result<Vec<ClaimedSubscription>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        UPDATE hook_subscriptions
+        SET lease_until = NOW() + ($1 * INTERVAL '1 second')
+        WHERE id IN (
+            SELECT s.id FROM hook_subscriptions s
+            WHERE s.disabled_at IS NULL
+              AND (s.lease_until IS NULL OR s.lease_until < NOW())
+              AND EXISTS (
+                  SELECT 1 FROM tweet_collateral t
+                  WHERE t.user_id = s.user_id AND t.id > s.cursor_id
+              )
+            ORDER BY s.id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, user_id, target_url, cursor_id
+        "#,
+    )
+    .bind(lease_secs)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Record delivery up to `cursor_id` and release the lease
+pub async fn finish_delivery<'e, E>(
+    executor: E,
+    subscription_id: i64,
+    cursor_id: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE hook_subscriptions
+        SET cursor_id = GREATEST(cursor_id, $2), failures = 0, last_error = NULL,
+            lease_until = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(subscription_id)
+    .bind(cursor_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed delivery after advancing past whatever did get through.
+/// The lease is kept as a retry delay; `max_failures` in a row disables the subscription.
+pub async fn fail_delivery<'e, E>(
+    executor: E,
+    subscription_id: i64,
+    cursor_id: i64,
+    error: &str,
+    max_failures: i32,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE hook_subscriptions
+        SET cursor_id = GREATEST(cursor_id, $2), failures = failures + 1, last_error = $3,
+            disabled_at = CASE WHEN failures + 1 >= $4 THEN NOW() END
+        WHERE id = $1
+        "#,
+    )
+    .bind(subscription_id)
+    .bind(cursor_id)
+    .bind(error)
+    .bind(max_failures)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Drop a subscription the receiver has gone away from (HTTP 410)
+pub async fn remove_subscription<'e, E>(
+    executor: E,
+    subscription_id: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("DELETE FROM hook_subscriptions WHERE id = $1")
+        .bind(subscription_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
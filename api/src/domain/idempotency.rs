@@ -0,0 +1,130 @@
+//! Idempotency domain - DB queries for Idempotency-Key claims
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct IdempotencyRecord {
+    pub endpoint: String,
+    pub status_code: Option<i16>,
+    pub response_body: Option<serde_json::Value>,
+}
+
+/// Claim a key for a request. Returns true when the caller should run the request;
+/// otherwise the existing record decides what to do. Unfinished claims older than
+/// `stale_before` are taken over (the original request died mid-flight).
+pub async fn claim_key<'e, E>(
+    executor: E,
+    user_id: i64,
+    key: &str,
+    endpoint: &str,
+    stale_before: DateTime<Utc>,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let claimed: Option<String> = sqlx::query_scalar(
+        r#"
+        INSERT INTO idempotency_keys (user_id, key, endpoint)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, key) DO UPDATE SET created_at = NOW()
+            WHERE idempotency_keys.completed_at IS NULL
+              AND idempotency_keys.created_at < $4
+              AND idempotency_keys.endpoint = EXCLUDED.endpoint
+        RETURNING key
+        "#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(endpoint)
+    .bind(stale_before)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(claimed.is_some())
+}
+
+/// Look up an existing claim
+pub async fn get_key<'e, E>(
+    executor: E,
+    user_id: i64,
+    key: &str,
+) -> Result<Option<IdempotencyRecord>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT endpoint, status_code, response_body
+        FROM idempotency_keys
+        WHERE user_id = $1 AND key = $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Store the response for a claimed key
+pub async fn complete_key<'e, E>(
+    executor: E,
+    user_id: i64,
+    key: &str,
+    status_code: i16,
+    response_body: &serde_json::Value,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE idempotency_keys
+        SET status_code = $3, response_body = $4, completed_at = NOW()
+        WHERE user_id = $1 AND key = $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(status_code)
+    .bind(response_body)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Drop an unfinished claim so the client can retry with the same key
+pub async fn release_key<'e, E>(executor: E, user_id: i64, key: &str) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        DELETE FROM idempotency_keys
+        WHERE user_id = $1 AND key = $2 AND completed_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Delete claims created before the cutoff
+pub async fn delete_expired_keys<'e, E>(
+    executor: E,
+    created_before: DateTime<Utc>,
+) -> Result<u64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query("DELETE FROM idempotency_keys WHERE created_at < $1")
+        .bind(created_before)
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,197 @@
+//! Links domain - DB queries for tracked tweet links and their clicks
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct TrackedLink {
+    pub id: i64,
+    pub slug: String,
+    pub target_url: String,
+    pub created_at: DateTime<Utc>,
+    pub clicks: i64,
+}
+
+/// Where a slug points
+#[derive(Debug, sqlx::FromRow)]
+pub struct LinkTarget {
+    pub id: i64,
+    pub target_url: String,
+}
+
+/// Clicks per day, in the user's time zone
+#[derive(Debug, sqlx::FromRow)]
+pub struct DailyClicks {
+    pub day: NaiveDate,
+    pub clicks: i64,
+}
+
+/// Clicks grouped by a label (referrer host or agent class)
+#[derive(Debug, sqlx::FromRow)]
+pub struct LabeledClicks {
+    pub label: Option<String>,
+    pub clicks: i64,
+}
+
+/// Create a tracked link on one of the user's tweets.
+/// Returns None if the tweet isn't the user's.
+pub async fn insert_link<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+    slug: &str,
+    target_url: &str,
+) -> Result<Option<TrackedLink>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO tracked_links (user_id, tweet_collateral_id, slug, target_url)
+        SELECT $1, t.id, $3, $4
+        FROM tweet_collateral t
+        WHERE t.id = $2 AND t.user_id = $1
+        RETURNING id, slug, target_url, created_at, 0::bigint AS clicks
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .bind(slug)
+    .bind(target_url)
+    .fetch_optional(executor)
+    .await
+}
+
+/// A tweet's tracked links with their click counts, oldest first
+pub async fn list_links<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+) -> Result<Vec<TrackedLink>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT l.id, l.slug, l.target_url, l.created_at,
+               (SELECT COUNT(*) FROM link_clicks c WHERE c.link_id = l.id) AS clicks
+        FROM tracked_links l
+        WHERE l.user_id = $1 AND l.tweet_collateral_id = $2
+        ORDER BY l.created_at, l.id
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Look up a slug for redirecting
+pub async fn get_target<'e, E>(executor: E, slug: &str) -> Result<Option<LinkTarget>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as("SELECT id, target_url FROM tracked_links WHERE slug = $1")
+        .bind(slug)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Log one click
+pub async fn record_click<'e, E>(
+    executor: E,
+    link_id: i64,
+    referrer: Option<&str>,
+    agent: &str,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("INSERT INTO link_clicks (link_id, referrer, agent) VALUES ($1, $2, $3)")
+        .bind(link_id)
+        .bind(referrer)
+        .bind(agent)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Clicks on a tweet's links per local day, oldest first
+pub async fn daily_clicks<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+    timezone: &str,
+) -> Result<Vec<DailyClicks>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT (c.clicked_at AT TIME ZONE $3)::date AS day, COUNT(*) AS clicks
+        FROM link_clicks c
+        JOIN tracked_links l ON l.id = c.link_id
+        WHERE l.user_id = $1 AND l.tweet_collateral_id = $2
+        GROUP BY day
+        ORDER BY day
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .bind(timezone)
+    .fetch_all(executor)
+    .await
+}
+
+/// Clicks on a tweet's links by referrer host, most first
+pub async fn clicks_by_referrer<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+) -> Result<Vec<LabeledClicks>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT c.referrer AS label, COUNT(*) AS clicks
+        FROM link_clicks c
+        JOIN tracked_links l ON l.id = c.link_id
+        WHERE l.user_id = $1 AND l.tweet_collateral_id = $2
+        GROUP BY c.referrer
+        ORDER BY clicks DESC, label
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Clicks on a tweet's links by user agent class, most first
+pub async fn clicks_by_agent<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_collateral_id: i64,
+) -> Result<Vec<LabeledClicks>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT c.agent AS label, COUNT(*) AS clicks
+        FROM link_clicks c
+        JOIN tracked_links l ON l.id = c.link_id
+        WHERE l.user_id = $1 AND l.tweet_collateral_id = $2
+        GROUP BY c.agent
+        ORDER BY clicks DESC, label
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_collateral_id)
+    .fetch_all(executor)
+    .await
+}
@@ -0,0 +1,275 @@
+//! Login identities domain - DB queries for GitHub/Google/email logins and their OAuth state
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct LoginIdentity {
+    pub provider: String,
+    pub username: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Remember a pending login's PKCE verifier under its state
+pub async fn save_login_state<'e, E>(
+    executor: E,
+    state: &str,
+    code_verifier: &str,
+    provider: &str,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO oauth_states (state, code_verifier, provider)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(state)
+    .bind(code_verifier)
+    .bind(provider)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Use up a pending login for `provider`, returning its PKCE verifier
+pub async fn take_login_state<'e, E>(
+    executor: E,
+    state: &str,
+    provider: &str,
+) -> Result<Option<String>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        r#"
+        DELETE FROM oauth_states
+        WHERE state = $1 AND provider = $2 AND created_at > NOW() - INTERVAL '10 minutes'
+        RETURNING code_verifier
+        "#,
+    )
+    .bind(state)
+    .bind(provider)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Refresh a known identity's details. Returns its user, or None if it's new.
+pub async fn update_identity<'e, E>(
+    executor: E,
+    provider: &str,
+    provider_user_id: &str,
+    username: &str,
+    email: Option<&str>,
+    name: Option<&str>,
+) -> Result<Option<i64>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        r#"
+        UPDATE login_identities SET username = $3, email = $4, name = $5
+        WHERE provider = $1 AND provider_user_id = $2
+        RETURNING user_id
+        "#,
+    )
+    .bind(provider)
+    .bind(provider_user_id)
+    .bind(username)
+    .bind(email)
+    .bind(name)
+    .fetch_optional(executor)
+    .await
+}
+
+/// The account an identity belongs to, if any
+pub async fn find_identity_user<'e, E>(
+    executor: E,
+    provider: &str,
+    provider_user_id: &str,
+) -> Result<Option<i64>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "SELECT user_id FROM login_identities WHERE provider = $1 AND provider_user_id = $2",
+    )
+    .bind(provider)
+    .bind(provider_user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Add an identity to an existing account, replacing its previous one for the
+/// same provider. Check `find_identity_user` first: an identity owned by another
+/// account fails on the unique constraint.
+pub async fn attach_identity<'e, E>(
+    executor: E,
+    user_id: i64,
+    provider: &str,
+    provider_user_id: &str,
+    username: &str,
+    email: Option<&str>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO login_identities (user_id, provider, provider_user_id, username, email)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_id, provider) DO UPDATE SET
+            provider_user_id = EXCLUDED.provider_user_id,
+            username = EXCLUDED.username,
+            email = EXCLUDED.email,
+            name = NULL,
+            created_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(provider)
+    .bind(provider_user_id)
+    .bind(username)
+    .bind(email)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Create an account owned by a new identity; returns the user id
+pub async fn create_user_with_identity<'e, E>(
+    executor: E,
+    provider: &str,
+    provider_user_id: &str,
+    username: &str,
+    email: Option<&str>,
+    name: Option<&str>,
+) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        r#"
+        WITH new_user AS (
+            INSERT INTO users DEFAULT VALUES
+            RETURNING id
+        )
+        INSERT INTO login_identities (user_id, provider, provider_user_id, username, email, name)
+        SELECT id, $1, $2, $3, $4, $5 FROM new_user
+        RETURNING user_id
+        "#,
+    )
+    .bind(provider)
+    .bind(provider_user_id)
+    .bind(username)
+    .bind(email)
+    .bind(name)
+    .fetch_one(executor)
+    .await
+}
+
+/// A user's login identities, oldest first
+pub async fn list_identities<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Vec<LoginIdentity>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT provider, username, email, created_at
+        FROM login_identities
+        WHERE user_id = $1
+        ORDER BY id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Fold a new account into an existing one: its login identities move over
+/// (where the target has none for that provider), its audit history moves with
+/// them and it is deleted. Only accounts with nothing of their own yet are
+/// merged; returns false, changing nothing, for one with content, devices,
+/// settings or anything else the delete would cascade away. Sessions and other
+/// sign-in leftovers are dropped. Run inside a transaction.
+pub async fn merge_into(
+    conn: &mut sqlx::PgConnection,
+    from_user_id: i64,
+    into_user_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let has_state: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM users WHERE id = $1 AND api_token IS NOT NULL)
+            OR EXISTS (SELECT 1 FROM captures WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM activities WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM activity_summaries WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM tweet_collateral WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM tweet_threads WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM tweet_revisions WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM longform_posts WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM collateral_comments WHERE author_user_id = $1)
+            OR EXISTS (SELECT 1 FROM media_folders WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM media_assets WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM capture_shares WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM tracked_links WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM devices WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM enrollment_codes WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM extension_tokens WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM hook_subscriptions WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM user_push_subscriptions WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM user_personas WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM user_projects WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM notification_preferences WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM copy_experiments WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM dev_signals WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM weekly_recaps WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM summary_exports WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM data_exports WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM content_views WHERE user_id = $1)
+        "#,
+    )
+    .bind(from_user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    if has_state {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE login_identities SET user_id = $2
+        WHERE user_id = $1
+          AND provider NOT IN (SELECT provider FROM login_identities WHERE user_id = $2)
+        "#,
+    )
+    .bind(from_user_id)
+    .bind(into_user_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE audit_log SET user_id = $2 WHERE user_id = $1")
+        .bind(from_user_id)
+        .bind(into_user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Agent runs over an empty account have nothing worth keeping, and their
+    // foreign key doesn't cascade
+    sqlx::query("DELETE FROM agent_runs WHERE user_id = $1")
+        .bind(from_user_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(from_user_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(true)
+}
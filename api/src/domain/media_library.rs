@@ -0,0 +1,302 @@
+//! Media library domain - DB queries for user-uploaded reusable assets
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+use std::collections::HashMap;
+
+/// Storage prefix for library objects (`library/user_<id>/...`)
+pub const PATH_PREFIX: &str = "library/";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct MediaFolder {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub asset_count: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MediaAsset {
+    pub id: i64,
+    pub folder_id: Option<i64>,
+    pub name: String,
+    pub content_type: String,
+    pub gcs_path: String,
+    pub file_size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create a folder. Returns None if the user already has one with this name.
+pub async fn insert_folder<'e, E>(
+    executor: E,
+    user_id: i64,
+    name: &str,
+) -> Result<Option<MediaFolder>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO media_folders (user_id, name)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id, name) DO NOTHING
+        RETURNING id, name, created_at, 0::BIGINT AS asset_count
+        "#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .fetch_optional(executor)
+    .await
+}
+
+/// List a user's folders by name, with how many assets each holds
+pub async fn list_folders<'e, E>(executor: E, user_id: i64) -> Result<Vec<MediaFolder>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT f.id, f.name, f.created_at, COUNT(a.id) AS asset_count
+        FROM media_folders f
+        LEFT JOIN media_assets a ON a.folder_id = f.id
+        WHERE f.user_id = $1
+        GROUP BY f.id
+        ORDER BY f.name
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Check a folder belongs to the user
+pub async fn folder_owned<'e, E>(
+    executor: E,
+    folder_id: i64,
+    user_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM media_folders WHERE id = $1 AND user_id = $2)")
+        .bind(folder_id)
+        .bind(user_id)
+        .fetch_one(executor)
+        .await
+}
+
+/// Delete a folder; its assets move to the top level
+pub async fn delete_folder<'e, E>(
+    executor: E,
+    folder_id: i64,
+    user_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query("DELETE FROM media_folders WHERE id = $1 AND user_id = $2")
+        .bind(folder_id)
+        .bind(user_id)
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Record an uploaded asset
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_asset<'e, E>(
+    executor: E,
+    user_id: i64,
+    folder_id: Option<i64>,
+    name: &str,
+    content_type: &str,
+    gcs_path: &str,
+    file_size_bytes: i64,
+) -> Result<MediaAsset, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO media_assets (user_id, folder_id, name, content_type, gcs_path, file_size_bytes)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, folder_id, name, content_type, gcs_path, file_size_bytes, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(folder_id)
+    .bind(name)
+    .bind(content_type)
+    .bind(gcs_path)
+    .bind(file_size_bytes)
+    .fetch_one(executor)
+    .await
+}
+
+/// List assets newest first, optionally only those in one folder
+pub async fn list_assets<'e, E>(
+    executor: E,
+    user_id: i64,
+    folder_id: Option<i64>,
+) -> Result<Vec<MediaAsset>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, folder_id, name, content_type, gcs_path, file_size_bytes, created_at
+        FROM media_assets
+        WHERE user_id = $1 AND ($2::BIGINT IS NULL OR folder_id = $2)
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(folder_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Get one asset
+pub async fn get_asset<'e, E>(
+    executor: E,
+    asset_id: i64,
+    user_id: i64,
+) -> Result<Option<MediaAsset>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, folder_id, name, content_type, gcs_path, file_size_bytes, created_at
+        FROM media_assets
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(asset_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Batch get assets for media upload
+pub async fn get_assets_batch<'e, E>(
+    executor: E,
+    asset_ids: &[i64],
+    user_id: i64,
+) -> Result<HashMap<i64, MediaAsset>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let rows: Vec<MediaAsset> = sqlx::query_as(
+        r#"
+        SELECT id, folder_id, name, content_type, gcs_path, file_size_bytes, created_at
+        FROM media_assets
+        WHERE id = ANY($1) AND user_id = $2
+        "#,
+    )
+    .bind(asset_ids)
+    .bind(user_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| (r.id, r)).collect())
+}
+
+/// Verify assets belong to user
+pub async fn verify_assets_owned<'e, E>(
+    executor: E,
+    asset_ids: &[i64],
+    user_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM media_assets WHERE id = ANY($1) AND user_id = $2")
+            .bind(asset_ids)
+            .bind(user_id)
+            .fetch_one(executor)
+            .await?;
+
+    Ok(count == asset_ids.len() as i64)
+}
+
+/// Rename an asset and/or move it. `folder_id: Some(None)` moves it to the top level.
+pub async fn update_asset<'e, E>(
+    executor: E,
+    asset_id: i64,
+    user_id: i64,
+    name: Option<&str>,
+    folder_id: Option<Option<i64>>,
+) -> Result<Option<MediaAsset>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        UPDATE media_assets
+        SET name = COALESCE($3, name),
+            folder_id = CASE WHEN $4 THEN $5 ELSE folder_id END
+        WHERE id = $1 AND user_id = $2
+        RETURNING id, folder_id, name, content_type, gcs_path, file_size_bytes, created_at
+        "#,
+    )
+    .bind(asset_id)
+    .bind(user_id)
+    .bind(name)
+    .bind(folder_id.is_some())
+    .bind(folder_id.flatten())
+    .fetch_optional(executor)
+    .await
+}
+
+/// Delete an asset and detach it from unposted tweets. Returns its storage path.
+pub async fn delete_asset<'e, E>(
+    executor: E,
+    asset_id: i64,
+    user_id: i64,
+) -> Result<Option<String>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        r#"
+        WITH deleted AS (
+            DELETE FROM media_assets WHERE id = $1 AND user_id = $2
+            RETURNING id, gcs_path
+        ), detached AS (
+            UPDATE tweet_collateral
+            SET library_asset_ids = array_remove(library_asset_ids, $1)
+            WHERE user_id = $2 AND posted_at IS NULL
+              AND $1 = ANY(library_asset_ids)
+              AND EXISTS (SELECT 1 FROM deleted)
+        )
+        SELECT gcs_path FROM deleted
+        "#,
+    )
+    .bind(asset_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Check whether a storage path is one of the user's library assets
+pub async fn user_owns_asset_path<'e, E>(
+    executor: E,
+    user_id: i64,
+    path: &str,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM media_assets WHERE user_id = $1 AND gcs_path = $2)",
+    )
+    .bind(user_id)
+    .bind(path)
+    .fetch_one(executor)
+    .await
+}
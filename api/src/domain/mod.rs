@@ -1,6 +1,30 @@
 pub mod activities;
+pub mod archives;
+pub mod audit_log;
+pub mod capture_shares;
 pub mod captures;
+pub mod comments;
 pub mod content;
+pub mod content_views;
+pub mod copy_experiments;
+pub mod dev_signals;
+pub mod devices;
+pub mod email_login_tokens;
+pub mod enrollment_codes;
+pub mod exports;
+pub mod extension_tokens;
+pub mod hooks;
+pub mod idempotency;
+pub mod links;
+pub mod login_identities;
+pub mod media_library;
+pub mod notification_preferences;
+pub mod projects;
+pub mod public_log;
 pub mod push;
+pub mod sessions;
+pub mod storage_audit;
+pub mod summary_exports;
 pub mod twitter;
 pub mod users;
+pub mod weekly_recaps;
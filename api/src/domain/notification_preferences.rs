@@ -0,0 +1,67 @@
+//! Notification preferences domain - DB queries for per-user channel/event toggles and quiet hours
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::NaiveTime;
+use sqlx::{Executor, Postgres};
+
+/// A user's stored preferences. The default (no row) has nothing muted and no
+/// quiet hours.
+#[derive(Debug, Clone, Default, sqlx::FromRow)]
+pub struct NotificationPreferences {
+    /// Switched-off pairs as "channel.event"
+    pub muted: Vec<String>,
+    /// Local time in the user's zone; set together with `quiet_hours_end`
+    pub quiet_hours_start: Option<NaiveTime>,
+    pub quiet_hours_end: Option<NaiveTime>,
+}
+
+/// Get the user's preferences, or None if they never changed them
+pub async fn get_preferences<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Option<NotificationPreferences>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT muted, quiet_hours_start, quiet_hours_end
+        FROM notification_preferences
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Replace the user's preferences
+pub async fn set_preferences<'e, E>(
+    executor: E,
+    user_id: i64,
+    prefs: &NotificationPreferences,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO notification_preferences (user_id, muted, quiet_hours_start, quiet_hours_end)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id) DO UPDATE SET
+            muted = EXCLUDED.muted,
+            quiet_hours_start = EXCLUDED.quiet_hours_start,
+            quiet_hours_end = EXCLUDED.quiet_hours_end,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(&prefs.muted)
+    .bind(prefs.quiet_hours_start)
+    .bind(prefs.quiet_hours_end)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
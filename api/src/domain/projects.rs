@@ -0,0 +1,171 @@
+//! Projects domain - DB queries for the per-user project list inferred from
+//! window titles (see services::projects)
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+/// A project the user has worked on
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Project {
+    pub name: String,
+    pub last_branch: Option<String>,
+    pub active_secs: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Appended to agent drafts tagged with the project (without '#')
+    pub hashtags: Vec<String>,
+    /// X account the project's content is published from (without '@')
+    pub twitter_username: Option<String>,
+    /// The agent drafts from captures taken while the project is in the foreground
+    pub auto_generate: bool,
+}
+
+/// Publishing defaults set on a project
+#[derive(Debug, Clone)]
+pub struct ProjectSettings {
+    pub hashtags: Vec<String>,
+    pub twitter_username: Option<String>,
+    pub auto_generate: bool,
+}
+
+const PROJECT_COLUMNS: &str = "name, last_branch, active_secs, first_seen_at, last_seen_at, \
+     hashtags, twitter_username, auto_generate";
+
+/// The key of the project in the foreground when capture `c` was taken: the
+/// one inferred from the device's latest ForegroundSwitch, if that was within
+/// services::projects::MAX_SLICE_SECS. For queries over `captures c`.
+pub const CAPTURE_PROJECT_KEY: &str = r#"(
+    SELECT a.project_key
+    FROM activities a
+    WHERE a.user_id = c.user_id
+      AND a.device_id IS NOT DISTINCT FROM c.device_id
+      AND a.event_type = 'ForegroundSwitch'
+      AND a."timestamp" <= c.captured_at
+      AND a."timestamp" > c.captured_at - INTERVAL '30 minutes'
+    ORDER BY a."timestamp" DESC
+    LIMIT 1
+)"#;
+
+/// Record a sighting of a project at `seen_at` and add `secs` of foreground
+/// time. The branch is only replaced by a newer sighting.
+pub async fn record_activity<'e, E>(
+    executor: E,
+    user_id: i64,
+    name: &str,
+    branch: Option<&str>,
+    seen_at: DateTime<Utc>,
+    secs: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO user_projects
+            (user_id, key, name, last_branch, active_secs, first_seen_at, last_seen_at)
+        VALUES ($1, lower($2), $2, $3, $4, $5, $5)
+        ON CONFLICT (user_id, key) DO UPDATE SET
+            last_branch = CASE
+                WHEN EXCLUDED.last_branch IS NOT NULL
+                 AND EXCLUDED.last_seen_at >= user_projects.last_seen_at
+                THEN EXCLUDED.last_branch
+                ELSE user_projects.last_branch
+            END,
+            active_secs = user_projects.active_secs + EXCLUDED.active_secs,
+            first_seen_at = LEAST(user_projects.first_seen_at, EXCLUDED.first_seen_at),
+            last_seen_at = GREATEST(user_projects.last_seen_at, EXCLUDED.last_seen_at)
+        "#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(branch)
+    .bind(secs)
+    .bind(seen_at)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// The user's projects, most recently seen first
+pub async fn list_projects<'e, E>(
+    executor: E,
+    user_id: i64,
+    limit: i64,
+) -> Result<Vec<Project>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(&format!(
+        "SELECT {} FROM user_projects
+         WHERE user_id = $1
+         ORDER BY last_seen_at DESC
+         LIMIT $2",
+        PROJECT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Replace a project's publishing defaults. Returns None if the user has no
+/// project by that name (ignoring case).
+pub async fn update_settings<'e, E>(
+    executor: E,
+    user_id: i64,
+    name: &str,
+    settings: &ProjectSettings,
+) -> Result<Option<Project>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(&format!(
+        "UPDATE user_projects
+         SET hashtags = $3, twitter_username = $4, auto_generate = $5
+         WHERE user_id = $1 AND key = lower($2)
+         RETURNING {}",
+        PROJECT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(name)
+    .bind(&settings.hashtags)
+    .bind(&settings.twitter_username)
+    .bind(settings.auto_generate)
+    .fetch_optional(executor)
+    .await
+}
+
+/// A project behind the given tweet (or any tweet in the given thread) that
+/// is linked to a different X account than the one connected, as
+/// (project name, linked username)
+pub async fn publish_account_conflict<'e, E>(
+    executor: E,
+    user_id: i64,
+    tweet_id: Option<i64>,
+    thread_id: Option<i64>,
+) -> Result<Option<(String, String)>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT p.name, p.twitter_username
+        FROM tweet_collateral tc
+        JOIN user_projects p ON p.user_id = tc.user_id AND p.key = lower(tc.project)
+        JOIN users u ON u.id = tc.user_id
+        WHERE tc.user_id = $1
+          AND (tc.id = $2 OR tc.thread_id = $3)
+          AND p.twitter_username IS NOT NULL
+          AND lower(p.twitter_username) IS DISTINCT FROM lower(u.twitter_username)
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(tweet_id)
+    .bind(thread_id)
+    .fetch_optional(executor)
+    .await
+}
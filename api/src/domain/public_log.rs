@@ -0,0 +1,235 @@
+//! Public build log domain - DB queries for the opt-in public feed and "now building" widget
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+/// A user whose build log is public
+#[derive(Debug, sqlx::FromRow)]
+pub struct LogOwner {
+    pub id: i64,
+    pub twitter_username: String,
+    pub twitter_name: Option<String>,
+}
+
+/// A posted tweet shown in the log
+#[derive(Debug, sqlx::FromRow)]
+pub struct LogTweet {
+    pub id: i64,
+    pub text: String,
+    pub tweet_id: Option<String>,
+    pub posted_at: DateTime<Utc>,
+    pub image_capture_ids: Vec<i64>,
+    pub thread_id: Option<i64>,
+}
+
+/// A capture the user added to the log, or one attached to a posted tweet
+#[derive(Debug, sqlx::FromRow)]
+pub struct LogCapture {
+    pub id: i64,
+    pub media_type: String,
+    pub content_type: String,
+    pub gcs_path: String,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Find a user by Twitter username (case-insensitive), only if their log is public
+pub async fn get_public_owner<'e, E>(
+    executor: E,
+    username: &str,
+) -> Result<Option<LogOwner>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, twitter_username, twitter_name FROM users
+        WHERE LOWER(twitter_username) = LOWER($1) AND public_log_enabled
+        "#,
+    )
+    .bind(username)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Whether the user's build log is public
+pub async fn get_enabled<'e, E>(executor: E, user_id: i64) -> Result<Option<bool>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT public_log_enabled FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Make the user's build log public or private
+pub async fn set_enabled<'e, E>(executor: E, user_id: i64, enabled: bool) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE users SET public_log_enabled = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(enabled)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Posted tweets (standalone and in threads) before `before`, newest first
+pub async fn list_posted_tweets<'e, E>(
+    executor: E,
+    user_id: i64,
+    before: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<LogTweet>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, text, tweet_id, posted_at, image_capture_ids, thread_id
+        FROM tweet_collateral
+        WHERE user_id = $1 AND posted_at IS NOT NULL AND posted_at < $2
+          AND dismissed_at IS NULL
+        ORDER BY posted_at DESC, thread_position ASC NULLS FIRST
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(before)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Captures added to the log, captured before `before`, newest first
+pub async fn list_selected_captures<'e, E>(
+    executor: E,
+    user_id: i64,
+    before: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<LogCapture>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, media_type, content_type, gcs_path, captured_at
+        FROM captures
+        WHERE user_id = $1 AND in_public_log AND captured_at < $2
+        ORDER BY captured_at DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(before)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// The user's captures with these IDs (images attached to posted tweets)
+pub async fn get_captures<'e, E>(
+    executor: E,
+    user_id: i64,
+    capture_ids: &[i64],
+) -> Result<Vec<LogCapture>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, media_type, content_type, gcs_path, captured_at
+        FROM captures
+        WHERE user_id = $1 AND id = ANY($2)
+        "#,
+    )
+    .bind(user_id)
+    .bind(capture_ids)
+    .fetch_all(executor)
+    .await
+}
+
+/// A user whose "now building" widget is public
+#[derive(Debug, sqlx::FromRow)]
+pub struct WidgetOwner {
+    pub id: i64,
+    pub twitter_username: String,
+    pub twitter_name: Option<String>,
+    pub timezone: String,
+}
+
+/// Find a user by Twitter username (case-insensitive), only if their widget is public
+pub async fn get_widget_owner<'e, E>(
+    executor: E,
+    username: &str,
+) -> Result<Option<WidgetOwner>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, twitter_username, twitter_name, timezone FROM users
+        WHERE LOWER(twitter_username) = LOWER($1) AND now_widget_enabled
+        "#,
+    )
+    .bind(username)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Whether the user's "now building" widget is public
+pub async fn get_widget_enabled<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Option<bool>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT now_widget_enabled FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Make the user's "now building" widget public or private
+pub async fn set_widget_enabled<'e, E>(
+    executor: E,
+    user_id: i64,
+    enabled: bool,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE users SET now_widget_enabled = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(enabled)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// When the user posted since `since`, newest first
+pub async fn list_posted_times<'e, E>(
+    executor: E,
+    user_id: i64,
+    since: DateTime<Utc>,
+) -> Result<Vec<DateTime<Utc>>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        r#"
+        SELECT posted_at FROM tweet_collateral
+        WHERE user_id = $1 AND posted_at >= $2 AND dismissed_at IS NULL
+        ORDER BY posted_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(since)
+    .fetch_all(executor)
+    .await
+}
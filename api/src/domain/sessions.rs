@@ -0,0 +1,172 @@
+//! Sessions domain - DB queries for daemon work sessions
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct Session {
+    pub id: i64,
+    pub device: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub app_summary: serde_json::Value,
+    pub capture_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct SessionWithTotal {
+    id: i64,
+    device: Option<String>,
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    app_summary: serde_json::Value,
+    capture_count: i64,
+    total_count: i64,
+}
+
+/// Open a new session for a user
+pub async fn open_session<'e, E>(
+    executor: E,
+    user_id: i64,
+    device_id: Option<i64>,
+    device: Option<&str>,
+) -> Result<(i64, DateTime<Utc>), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO sessions (user_id, device_id, device)
+        VALUES ($1, $2, $3)
+        RETURNING id, started_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(device_id)
+    .bind(device)
+    .fetch_one(executor)
+    .await
+}
+
+/// Close a session, summarizing foreground apps from its activities.
+/// Returns None if the session doesn't exist or belongs to another user.
+/// Closing an already-closed session is a no-op that returns the stored row.
+pub async fn close_session<'e, E>(
+    executor: E,
+    session_id: i64,
+    user_id: i64,
+) -> Result<Option<Session>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        WITH summary AS (
+            SELECT COALESCE(
+                jsonb_agg(jsonb_build_object('application', application, 'switches', switches)
+                          ORDER BY switches DESC),
+                '[]'::jsonb
+            ) AS apps
+            FROM (
+                SELECT application, COUNT(*) AS switches
+                FROM activities
+                WHERE session_id = $1
+                  AND user_id = $2
+                  AND event_type = 'ForegroundSwitch'
+                  AND application IS NOT NULL
+                GROUP BY application
+            ) per_app
+        )
+        UPDATE sessions s
+        SET ended_at = COALESCE(s.ended_at, NOW()),
+            app_summary = CASE WHEN s.ended_at IS NULL THEN summary.apps ELSE s.app_summary END
+        FROM summary
+        WHERE s.id = $1 AND s.user_id = $2
+        RETURNING s.id, s.device, s.started_at, s.ended_at, s.app_summary,
+                  (SELECT COUNT(*) FROM captures c WHERE c.session_id = s.id) AS capture_count
+        "#,
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Check whether a session belongs to the user and, when both sides are known, the device.
+/// Sessions opened before device tracking (NULL device_id) accept any device.
+pub async fn session_owned_by<'e, E>(
+    executor: E,
+    session_id: i64,
+    user_id: i64,
+    device_id: Option<i64>,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM sessions
+            WHERE id = $1 AND user_id = $2
+              AND ($3::bigint IS NULL OR device_id IS NULL OR device_id = $3)
+        ) AS "owned!"
+        "#,
+        session_id,
+        user_id,
+        device_id,
+    )
+    .fetch_one(executor)
+    .await
+}
+
+/// List a user's sessions, newest first, with total count from a window function.
+/// `started_within` limits results to sessions started in `[start, end)`.
+pub async fn list_sessions_paginated<'e, E>(
+    executor: E,
+    user_id: i64,
+    started_within: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<Session>, i64), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let rows: Vec<SessionWithTotal> = sqlx::query_as(
+        r#"
+        SELECT s.id, s.device, s.started_at, s.ended_at, s.app_summary,
+               (SELECT COUNT(*) FROM captures c WHERE c.session_id = s.id) AS capture_count,
+               COUNT(*) OVER() AS total_count
+        FROM sessions s
+        WHERE s.user_id = $1
+          AND ($2::timestamptz IS NULL OR s.started_at >= $2)
+          AND ($3::timestamptz IS NULL OR s.started_at < $3)
+        ORDER BY s.started_at DESC
+        LIMIT $4 OFFSET $5
+        "#,
+    )
+    .bind(user_id)
+    .bind(started_within.map(|(start, _)| start))
+    .bind(started_within.map(|(_, end)| end))
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(executor)
+    .await?;
+
+    let total = rows.first().map(|r| r.total_count).unwrap_or(0);
+    let sessions = rows
+        .into_iter()
+        .map(|r| Session {
+            id: r.id,
+            device: r.device,
+            started_at: r.started_at,
+            ended_at: r.ended_at,
+            app_summary: r.app_summary,
+            capture_count: r.capture_count,
+        })
+        .collect();
+
+    Ok((sessions, total))
+}
@@ -0,0 +1,231 @@
+//! Storage audit domain - DB queries for storage/captures reconciliation
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, PgConnection, Postgres};
+
+/// Advisory lock key so only one replica audits at a time
+pub const AUDIT_LOCK_KEY: i64 = 0x636c_656f_6175_6474;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct Finding {
+    pub id: i64,
+    pub kind: String,
+    pub path: String,
+    pub capture_id: Option<i64>,
+    pub captured_at: Option<DateTime<Utc>>,
+    pub detail: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolution: Option<String>,
+}
+
+/// Capture fields needed to check its stored objects
+#[derive(Debug, sqlx::FromRow)]
+pub struct AuditedCapture {
+    pub id: i64,
+    pub captured_at: DateTime<Utc>,
+    pub gcs_path: String,
+    pub thumbnail_path: Option<String>,
+    pub file_size_bytes: Option<i64>,
+}
+
+/// Page through all captures by id
+pub async fn fetch_capture_batch<'e, E>(
+    executor: E,
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<AuditedCapture>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, captured_at, gcs_path, thumbnail_path, file_size_bytes
+        FROM captures
+        WHERE id > $1
+        ORDER BY id
+        LIMIT $2
+        "#,
+    )
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Record a discrepancy, refreshing last_seen_at if it is already open
+pub async fn record_finding<'e, E>(
+    executor: E,
+    kind: &str,
+    path: &str,
+    capture: Option<(i64, DateTime<Utc>)>,
+    detail: Option<&str>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO storage_audit (kind, path, capture_id, captured_at, detail)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (kind, path) WHERE resolved_at IS NULL
+        DO UPDATE SET last_seen_at = NOW(), detail = EXCLUDED.detail
+        "#,
+    )
+    .bind(kind)
+    .bind(path)
+    .bind(capture.map(|(id, _)| id))
+    .bind(capture.map(|(_, at)| at))
+    .bind(detail)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Resolve open findings that a run started at `run_started_at` no longer saw
+pub async fn resolve_stale_findings<'e, E>(
+    executor: E,
+    run_started_at: DateTime<Utc>,
+) -> Result<u64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE storage_audit
+        SET resolved_at = NOW(), resolution = 'no longer detected'
+        WHERE resolved_at IS NULL AND last_seen_at < $1
+        "#,
+    )
+    .bind(run_started_at)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Mark a finding resolved
+pub async fn resolve_finding<'e, E>(
+    executor: E,
+    finding_id: i64,
+    resolution: &str,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "UPDATE storage_audit SET resolved_at = NOW(), resolution = $2 WHERE id = $1 AND resolved_at IS NULL",
+    )
+    .bind(finding_id)
+    .bind(resolution)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Open findings of the given kinds, oldest first
+pub async fn list_open_findings<'e, E>(
+    executor: E,
+    kinds: &[&str],
+) -> Result<Vec<Finding>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, kind, path, capture_id, captured_at, detail,
+               first_seen_at, last_seen_at, resolved_at, resolution
+        FROM storage_audit
+        WHERE resolved_at IS NULL AND kind = ANY($1)
+        ORDER BY id
+        "#,
+    )
+    .bind(kinds)
+    .fetch_all(executor)
+    .await
+}
+
+/// Findings for the admin view, newest first
+pub async fn list_findings<'e, E>(
+    executor: E,
+    include_resolved: bool,
+    limit: i64,
+) -> Result<Vec<Finding>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, kind, path, capture_id, captured_at, detail,
+               first_seen_at, last_seen_at, resolved_at, resolution
+        FROM storage_audit
+        WHERE $1 OR resolved_at IS NULL
+        ORDER BY last_seen_at DESC, id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(include_resolved)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Clear a capture's thumbnail so the thumbnail worker regenerates it
+pub async fn reset_thumbnail<'e, E>(
+    executor: E,
+    capture_id: i64,
+    captured_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE captures
+        SET thumbnail_path = NULL, thumbnail_attempts = 0
+        WHERE id = $1 AND captured_at = $2
+        "#,
+    )
+    .bind(capture_id)
+    .bind(captured_at)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Whether any capture still references `path` as its media or thumbnail
+pub async fn path_referenced<'e, E>(executor: E, path: &str) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM captures WHERE gcs_path = $1 OR thumbnail_path = $1)",
+    )
+    .bind(path)
+    .fetch_one(executor)
+    .await
+}
+
+/// Take the audit lock on this connection; false if another replica holds it
+pub async fn try_lock_audit(conn: &mut PgConnection) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+        .bind(AUDIT_LOCK_KEY)
+        .fetch_one(conn)
+        .await
+}
+
+/// Release the audit lock taken by `try_lock_audit`
+pub async fn unlock_audit(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(AUDIT_LOCK_KEY)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,323 @@
+//! Summary exports domain - DB queries for the nightly daily-summary export
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Executor, Postgres};
+
+/// A user's export destination. Secrets are stored encrypted (services::crypto).
+#[derive(Debug, sqlx::FromRow)]
+pub struct SummaryExport {
+    pub user_id: i64,
+    /// notion, webdav or local
+    pub destination: String,
+    pub enabled: bool,
+    pub notion_token: Option<String>,
+    pub notion_parent_page_id: Option<String>,
+    pub webdav_url: Option<String>,
+    pub webdav_username: Option<String>,
+    pub webdav_password: Option<String>,
+    pub local_dir: Option<String>,
+    pub last_exported_date: Option<NaiveDate>,
+    pub last_error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Destination settings from PUT /me/summary-export, secrets already encrypted
+#[derive(Debug)]
+pub struct NewSummaryExport<'a> {
+    pub destination: &'a str,
+    pub enabled: bool,
+    pub notion_token: Option<&'a str>,
+    pub notion_parent_page_id: Option<&'a str>,
+    pub webdav_url: Option<&'a str>,
+    pub webdav_username: Option<&'a str>,
+    pub webdav_password: Option<&'a str>,
+    pub local_dir: Option<&'a str>,
+}
+
+/// An enabled export that may have a finished day to write
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExportCandidate {
+    pub user_id: i64,
+    pub timezone: String,
+    pub last_exported_date: Option<NaiveDate>,
+}
+
+/// Active time in one app over the day
+#[derive(Debug, sqlx::FromRow)]
+pub struct AppTime {
+    pub app: String,
+    pub active_secs: i64,
+}
+
+/// A capture taken during the day
+#[derive(Debug, sqlx::FromRow)]
+pub struct DayCapture {
+    pub id: i64,
+    pub media_type: String,
+    pub captured_at: DateTime<Utc>,
+    pub thumbnail_path: Option<String>,
+}
+
+/// A tweet drafted or posted during the day
+#[derive(Debug, sqlx::FromRow)]
+pub struct DayTweet {
+    pub text: String,
+    pub tweet_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub posted_at: Option<DateTime<Utc>>,
+}
+
+const EXPORT_COLUMNS: &str = "user_id, destination, enabled, notion_token, notion_parent_page_id, \
+     webdav_url, webdav_username, webdav_password, local_dir, last_exported_date, last_error, updated_at";
+
+/// Get the user's export destination
+pub async fn get_export<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Option<SummaryExport>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        "SELECT {} FROM summary_exports WHERE user_id = $1",
+        EXPORT_COLUMNS
+    );
+    sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Create or replace the user's export destination.
+/// The last exported date is kept, so switching destinations doesn't re-export old days.
+pub async fn upsert_export<'e, E>(
+    executor: E,
+    user_id: i64,
+    export: NewSummaryExport<'_>,
+) -> Result<SummaryExport, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        r#"
+        INSERT INTO summary_exports (user_id, destination, enabled, notion_token,
+                                     notion_parent_page_id, webdav_url, webdav_username,
+                                     webdav_password, local_dir)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (user_id) DO UPDATE
+        SET destination = EXCLUDED.destination,
+            enabled = EXCLUDED.enabled,
+            notion_token = EXCLUDED.notion_token,
+            notion_parent_page_id = EXCLUDED.notion_parent_page_id,
+            webdav_url = EXCLUDED.webdav_url,
+            webdav_username = EXCLUDED.webdav_username,
+            webdav_password = EXCLUDED.webdav_password,
+            local_dir = EXCLUDED.local_dir,
+            last_error = NULL,
+            updated_at = NOW()
+        RETURNING {}
+        "#,
+        EXPORT_COLUMNS
+    );
+    sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(export.destination)
+        .bind(export.enabled)
+        .bind(export.notion_token)
+        .bind(export.notion_parent_page_id)
+        .bind(export.webdav_url)
+        .bind(export.webdav_username)
+        .bind(export.webdav_password)
+        .bind(export.local_dir)
+        .fetch_one(executor)
+        .await
+}
+
+/// Remove the user's export destination. Returns false if there was none.
+pub async fn delete_export<'e, E>(executor: E, user_id: i64) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query("DELETE FROM summary_exports WHERE user_id = $1")
+        .bind(user_id)
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Enabled, unleased exports whose last exported day is before `utc_today`.
+/// A user's previous local day is never after the UTC date, so this is a
+/// superset of the exports that are due; the worker checks each user's zone.
+pub async fn list_candidates<'e, E>(
+    executor: E,
+    utc_today: NaiveDate,
+) -> Result<Vec<ExportCandidate>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT s.user_id, u.timezone, s.last_exported_date
+        FROM summary_exports s
+        JOIN users u ON u.id = s.user_id
+        WHERE s.enabled
+          AND (s.last_exported_date IS NULL OR s.last_exported_date < $1)
+          AND (s.lease_until IS NULL OR s.lease_until < NOW())
+        ORDER BY s.user_id
+        "#,
+    )
+    .bind(utc_today)
+    .fetch_all(executor)
+    .await
+}
+
+/// Lease the user's export to write `day`, unless another worker holds it or
+/// the day was already written
+pub async fn claim_export<'e, E>(
+    executor: E,
+    user_id: i64,
+    day: NaiveDate,
+    lease_secs: i64,
+) -> Result<Option<SummaryExport>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        r#"
+        UPDATE summary_exports
+        SET lease_until = NOW() + ($3 * INTERVAL '1 second')
+        WHERE user_id = $1 AND enabled
+          AND (last_exported_date IS NULL OR last_exported_date < $2)
+          AND (lease_until IS NULL OR lease_until < NOW())
+        RETURNING {}
+        "#,
+        EXPORT_COLUMNS
+    );
+    sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(day)
+        .bind(lease_secs)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Record that `day` was written and release the lease
+pub async fn complete_export<'e, E>(
+    executor: E,
+    user_id: i64,
+    day: NaiveDate,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE summary_exports
+        SET last_exported_date = $2, last_error = NULL, lease_until = NULL
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(day)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed export. The lease is kept, so the day is retried once it expires.
+pub async fn fail_export<'e, E>(executor: E, user_id: i64, error: &str) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE summary_exports SET last_error = $2 WHERE user_id = $1")
+        .bind(user_id)
+        .bind(error)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Apps with the most active time in `[start, end)`, most first
+pub async fn top_apps<'e, E>(
+    executor: E,
+    user_id: i64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<AppTime>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT top_app AS app, SUM(active_secs)::BIGINT AS active_secs
+        FROM activity_summaries
+        WHERE user_id = $1 AND period_start >= $2 AND period_start < $3
+          AND top_app IS NOT NULL AND active_secs > 0
+        GROUP BY top_app
+        ORDER BY active_secs DESC, app
+        LIMIT $4
+        "#,
+    )
+    .bind(user_id)
+    .bind(start)
+    .bind(end)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Captures taken in `[start, end)`, oldest first
+pub async fn list_day_captures<'e, E>(
+    executor: E,
+    user_id: i64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<DayCapture>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, media_type, captured_at, thumbnail_path
+        FROM captures
+        WHERE user_id = $1 AND captured_at >= $2 AND captured_at < $3
+        ORDER BY captured_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(executor)
+    .await
+}
+
+/// Tweets drafted or posted in `[start, end)`, oldest first (dismissed drafts excluded)
+pub async fn list_day_tweets<'e, E>(
+    executor: E,
+    user_id: i64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<DayTweet>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT text, tweet_id, created_at, posted_at
+        FROM tweet_collateral
+        WHERE user_id = $1 AND dismissed_at IS NULL
+          AND ((created_at >= $2 AND created_at < $3)
+               OR (posted_at >= $2 AND posted_at < $3))
+        ORDER BY COALESCE(posted_at, created_at), id
+        "#,
+    )
+    .bind(user_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(executor)
+    .await
+}
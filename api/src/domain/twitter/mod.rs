@@ -7,4 +7,4 @@ pub mod queries;
 pub use models::*;
 
 // Re-export query modules
-pub use queries::{threads, tweets};
+pub use queries::{longform, threads, tweets};
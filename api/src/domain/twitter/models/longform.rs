@@ -0,0 +1,21 @@
+//! Long-form post model definitions
+
+use chrono::{DateTime, Utc};
+
+/// Longest long-form post X accepts, in characters
+pub const MAX_LONGFORM_CHARS: usize = 25_000;
+
+/// A long-form post (over the 280 character tweet limit)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Longform {
+    pub id: i64,
+    /// For the user's own organization; only the body is posted
+    pub title: Option<String>,
+    pub body: String,
+    pub rationale: String,
+    pub created_at: DateTime<Utc>,
+    pub publish_status: String,
+    pub publish_error: Option<String>,
+    pub posted_at: Option<DateTime<Utc>>,
+    pub tweet_id: Option<String>,
+}
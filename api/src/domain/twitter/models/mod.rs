@@ -1,7 +1,12 @@
 //! Twitter domain models
 
+mod longform;
 mod thread;
 mod tweet;
 
+pub use longform::{Longform, MAX_LONGFORM_CHARS};
 pub use thread::{Thread, ThreadStatus, ThreadWithTweets};
-pub use tweet::{Tweet, TweetForPosting};
+pub use tweet::{
+    DEFAULT_POLL_DURATION, DismissalFeedback, DismissedTweet, Poll, PostedTweet, Tweet,
+    TweetForPosting, TweetRevision,
+};
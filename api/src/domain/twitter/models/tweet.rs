@@ -1,8 +1,64 @@
 //! Tweet model definitions
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::types::Json;
 
+/// Most and fewest poll options X accepts
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 4;
+/// Longest poll option, in characters
+pub const MAX_POLL_OPTION_LEN: usize = 25;
+/// Shortest and longest poll, in minutes (5 minutes to 7 days)
+pub const MIN_POLL_DURATION: i32 = 5;
+pub const MAX_POLL_DURATION: i32 = 7 * 24 * 60;
+/// Duration used when none is given (1 day)
+pub const DEFAULT_POLL_DURATION: i32 = 24 * 60;
+
+/// A poll attached to a tweet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poll {
+    pub options: Vec<String>,
+    /// How long the poll stays open, in minutes
+    pub duration_minutes: i32,
+}
+
+impl Poll {
+    /// Build from the stored columns (set together or not at all)
+    pub fn from_columns(options: Option<&Vec<String>>, duration: Option<i32>) -> Option<Self> {
+        Some(Self {
+            options: options?.clone(),
+            duration_minutes: duration?,
+        })
+    }
+
+    /// Check the poll against X's limits
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&self.options.len()) {
+            return Err(format!(
+                "a poll needs {} to {} options",
+                MIN_POLL_OPTIONS, MAX_POLL_OPTIONS
+            ));
+        }
+        for option in &self.options {
+            let len = option.trim().chars().count();
+            if len == 0 || len > MAX_POLL_OPTION_LEN {
+                return Err(format!(
+                    "poll options must be 1 to {} characters",
+                    MAX_POLL_OPTION_LEN
+                ));
+            }
+        }
+        if !(MIN_POLL_DURATION..=MAX_POLL_DURATION).contains(&self.duration_minutes) {
+            return Err(format!(
+                "poll duration must be {} to {} minutes",
+                MIN_POLL_DURATION, MAX_POLL_DURATION
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// A tweet (standalone or part of a thread)
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct Tweet {
@@ -11,6 +67,8 @@ pub struct Tweet {
     pub copy_options: Json<Vec<String>>,
     pub video_clip: Option<serde_json::Value>,
     pub image_capture_ids: Vec<i64>,
+    /// Media library images, posted after the capture images
+    pub library_asset_ids: Vec<i64>,
     pub media_options: Json<Vec<serde_json::Value>>,
     pub rationale: String,
     pub created_at: DateTime<Utc>,
@@ -18,6 +76,18 @@ pub struct Tweet {
     pub reply_to_tweet_id: Option<String>,
     pub posted_at: Option<DateTime<Utc>>,
     pub tweet_id: Option<String>,
+    /// Tweet this one replies to, as pasted by the user
+    pub in_reply_to_url: Option<String>,
+    /// Tweet this one quotes
+    pub quote_tweet_url: Option<String>,
+    pub poll_options: Option<Vec<String>>,
+    /// Poll length in minutes
+    pub poll_duration: Option<i32>,
+    /// Set when the compliance filter flagged the text (services::copy_filter)
+    pub needs_review: bool,
+    pub review_reason: Option<String>,
+    /// Project the agent tagged the tweet with (user_projects.name)
+    pub project: Option<String>,
     pub publish_status: String,
     pub publish_attempts: i32,
     pub publish_error: Option<String>,
@@ -25,6 +95,22 @@ pub struct Tweet {
     pub publish_error_at: Option<DateTime<Utc>>,
 }
 
+/// A dismissed tweet, for the dismissed archive
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DismissedTweet {
+    #[sqlx(flatten)]
+    pub tweet: Tweet,
+    pub dismissed_at: DateTime<Utc>,
+    pub dismiss_reason: Option<String>,
+}
+
+/// A recently dismissed draft with the user's reason, fed back to the agent
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DismissalFeedback {
+    pub text: String,
+    pub reason: String,
+}
+
 /// Tweet data needed for posting (includes media info)
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct TweetForPosting {
@@ -33,9 +119,74 @@ pub struct TweetForPosting {
     #[allow(dead_code)]
     pub copy_options: Json<Vec<String>>,
     pub image_capture_ids: Vec<i64>,
+    pub library_asset_ids: Vec<i64>,
     pub video_clip: Option<serde_json::Value>,
     #[allow(dead_code)]
     pub media_options: Json<Vec<serde_json::Value>>,
     #[allow(dead_code)]
     pub rationale: String,
+    pub in_reply_to_url: Option<String>,
+    pub quote_tweet_url: Option<String>,
+    pub poll_options: Option<Vec<String>>,
+    pub poll_duration: Option<i32>,
+}
+
+impl TweetForPosting {
+    pub fn poll(&self) -> Option<Poll> {
+        Poll::from_columns(self.poll_options.as_ref(), self.poll_duration)
+    }
+}
+
+/// A posted tweet being corrected (media info for reposting)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PostedTweet {
+    #[sqlx(flatten)]
+    pub tweet: TweetForPosting,
+    /// Twitter ID of the live tweet
+    pub tweet_id: String,
+    pub thread_id: Option<i64>,
+}
+
+/// A correction to a posted tweet
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TweetRevision {
+    pub id: i64,
+    pub previous_text: String,
+    pub previous_tweet_id: String,
+    pub new_text: String,
+    pub new_tweet_id: String,
+    pub previous_deleted: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_validation() {
+        let poll = |options: &[&str], duration_minutes| Poll {
+            options: options.iter().map(|o| o.to_string()).collect(),
+            duration_minutes,
+        };
+        assert!(
+            poll(&["Tabs", "Spaces"], DEFAULT_POLL_DURATION)
+                .validate()
+                .is_ok()
+        );
+        assert!(poll(&["Only one"], 60).validate().is_err());
+        assert!(poll(&["a", "b", "c", "d", "e"], 60).validate().is_err());
+        assert!(
+            poll(&["Yes", "No, and here is a long reason"], 60)
+                .validate()
+                .is_err()
+        );
+        assert!(poll(&["Yes", " "], 60).validate().is_err());
+        assert!(poll(&["Yes", "No"], 4).validate().is_err());
+        assert!(
+            poll(&["Yes", "No"], MAX_POLL_DURATION + 1)
+                .validate()
+                .is_err()
+        );
+    }
 }
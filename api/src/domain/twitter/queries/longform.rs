@@ -0,0 +1,219 @@
+//! Long-form domain - DB queries for long-form posts
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use sqlx::{Executor, Postgres};
+
+use super::super::models::Longform;
+
+/// Create a draft
+pub async fn insert_longform<'e, E>(
+    executor: E,
+    user_id: i64,
+    title: Option<&str>,
+    body: &str,
+    rationale: &str,
+) -> Result<Longform, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        INSERT INTO longform_posts (user_id, title, body, rationale)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, title, body, rationale, created_at,
+                  publish_status, publish_error, posted_at, tweet_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(title)
+    .bind(body)
+    .bind(rationale)
+    .fetch_one(executor)
+    .await
+}
+
+/// Get a post by ID (dismissed posts excluded)
+pub async fn get_longform<'e, E>(
+    executor: E,
+    id: i64,
+    user_id: i64,
+) -> Result<Option<Longform>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, title, body, rationale, created_at,
+               publish_status, publish_error, posted_at, tweet_id
+        FROM longform_posts
+        WHERE id = $1 AND user_id = $2 AND dismissed_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Fetch posts by ID for the content listing
+pub async fn get_longforms_by_ids<'e, E>(
+    executor: E,
+    ids: &[i64],
+    user_id: i64,
+) -> Result<Vec<Longform>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, title, body, rationale, created_at,
+               publish_status, publish_error, posted_at, tweet_id
+        FROM longform_posts
+        WHERE id = ANY($1) AND user_id = $2
+        "#,
+    )
+    .bind(ids)
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Edit an unposted draft. Returns false if it was posted or doesn't exist.
+pub async fn update_longform<'e, E>(
+    executor: E,
+    id: i64,
+    user_id: i64,
+    title: Option<Option<&str>>,
+    body: Option<&str>,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE longform_posts
+        SET title = CASE WHEN $3 THEN $4 ELSE title END,
+            body = COALESCE($5, body)
+        WHERE id = $1 AND user_id = $2
+            AND posted_at IS NULL AND dismissed_at IS NULL
+            AND publish_status IN ('pending', 'failed')
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(title.is_some())
+    .bind(title.flatten())
+    .bind(body)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Claim a draft for publishing (atomic - only one publish can win)
+pub async fn set_longform_posting<'e, E>(
+    executor: E,
+    id: i64,
+    user_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE longform_posts
+        SET publish_status = 'posting',
+            publish_error = NULL
+        WHERE id = $1 AND user_id = $2
+            AND posted_at IS NULL AND dismissed_at IS NULL
+            AND publish_status IN ('pending', 'failed')
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Mark a post as published
+pub async fn mark_longform_posted<'e, E>(
+    executor: E,
+    id: i64,
+    twitter_id: &str,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE longform_posts
+        SET posted_at = NOW(),
+            tweet_id = $1,
+            publish_status = 'posted',
+            publish_error = NULL
+        WHERE id = $2 AND posted_at IS NULL
+        "#,
+    )
+    .bind(twitter_id)
+    .bind(id)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Mark a publish attempt as failed so it can be retried
+pub async fn mark_longform_publish_failed<'e, E>(
+    executor: E,
+    id: i64,
+    user_id: i64,
+    error: &str,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE longform_posts
+        SET publish_status = 'failed',
+            publish_error = $3
+        WHERE id = $1 AND user_id = $2 AND posted_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(error)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Soft-delete an unposted draft
+pub async fn dismiss_longform<'e, E>(
+    executor: E,
+    id: i64,
+    user_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE longform_posts
+        SET dismissed_at = NOW(),
+            publish_status = 'dismissed'
+        WHERE id = $1 AND user_id = $2 AND posted_at IS NULL AND dismissed_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
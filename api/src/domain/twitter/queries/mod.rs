@@ -1,4 +1,5 @@
 //! Twitter domain queries
 
+pub mod longform;
 pub mod threads;
 pub mod tweets;
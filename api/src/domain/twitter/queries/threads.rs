@@ -7,7 +7,7 @@ use chrono::{DateTime, Utc};
 use sqlx::types::Json;
 use sqlx::{Executor, Postgres, QueryBuilder};
 
-use super::super::models::{Thread, ThreadStatus, ThreadWithTweets, Tweet, TweetForPosting};
+use super::super::models::{Poll, Thread, ThreadStatus, ThreadWithTweets, Tweet, TweetForPosting};
 
 /// Parsed status filter enum for type-safe query building
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -26,12 +26,12 @@ impl ThreadStatusFilter {
         }
     }
 
-    /// Returns SQL WHERE clause fragment for filtering by thread status
-    fn where_clause(&self) -> &'static str {
+    /// Thread statuses matched by this filter, bound as `$2::text[]` (None = no filter)
+    fn statuses(&self) -> Option<&'static [&'static str]> {
         match self {
-            ThreadStatusFilter::Pending => "AND status IN ('draft', 'posting', 'partial_failed')",
-            ThreadStatusFilter::Posted => "AND status = 'posted'",
-            ThreadStatusFilter::All => "",
+            ThreadStatusFilter::Pending => Some(&["draft", "posting", "partial_failed"]),
+            ThreadStatusFilter::Posted => Some(&["posted"]),
+            ThreadStatusFilter::All => None,
         }
     }
 }
@@ -46,17 +46,18 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let filter = ThreadStatusFilter::from_str(status_filter);
-    let query = format!(
-        "SELECT COUNT(*) FROM tweet_threads WHERE user_id = $1 {}",
-        filter.where_clause()
-    );
 
-    let (count,): (i64,) = sqlx::query_as(&query)
-        .bind(user_id)
-        .fetch_one(executor)
-        .await?;
-
-    Ok(count)
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM tweet_threads
+        WHERE user_id = $1 AND ($2::text[] IS NULL OR status = ANY($2))
+        "#,
+        user_id,
+        filter.statuses() as _,
+    )
+    .fetch_one(executor)
+    .await
 }
 
 /// List all threads for a user (no pagination)
@@ -70,20 +71,21 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let filter = ThreadStatusFilter::from_str(status_filter);
-    let query = format!(
+
+    sqlx::query_as!(
+        Thread,
         r#"SELECT id, user_id, title,
-                  COALESCE(copy_options, '[]'::jsonb) as copy_options,
-                  status, created_at, posted_at, first_tweet_id
+                  COALESCE(copy_options, '[]'::jsonb) AS "copy_options!: Json<Vec<Vec<String>>>",
+                  status AS "status: ThreadStatus", created_at AS "created_at!",
+                  posted_at, first_tweet_id
            FROM tweet_threads
-           WHERE user_id = $1 {}
+           WHERE user_id = $1 AND ($2::text[] IS NULL OR status = ANY($2))
            ORDER BY created_at DESC"#,
-        filter.where_clause()
-    );
-
-    sqlx::query_as(&query)
-        .bind(user_id)
-        .fetch_all(executor)
-        .await
+        user_id,
+        filter.statuses() as _,
+    )
+    .fetch_all(executor)
+    .await
 }
 
 /// List threads for a user with pagination
@@ -98,23 +100,24 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let filter = ThreadStatusFilter::from_str(status_filter);
-    let query = format!(
+
+    sqlx::query_as!(
+        Thread,
         r#"SELECT id, user_id, title,
-                  COALESCE(copy_options, '[]'::jsonb) as copy_options,
-                  status, created_at, posted_at, first_tweet_id
+                  COALESCE(copy_options, '[]'::jsonb) AS "copy_options!: Json<Vec<Vec<String>>>",
+                  status AS "status: ThreadStatus", created_at AS "created_at!",
+                  posted_at, first_tweet_id
            FROM tweet_threads
-           WHERE user_id = $1 {}
+           WHERE user_id = $1 AND ($2::text[] IS NULL OR status = ANY($2))
            ORDER BY created_at DESC
-           LIMIT $2 OFFSET $3"#,
-        filter.where_clause()
-    );
-
-    sqlx::query_as(&query)
-        .bind(user_id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(executor)
-        .await
+           LIMIT $3 OFFSET $4"#,
+        user_id,
+        filter.statuses() as _,
+        limit,
+        offset,
+    )
+    .fetch_all(executor)
+    .await
 }
 
 /// Get a thread with its tweets
@@ -170,7 +173,9 @@ where
                COALESCE(media_options, '[]'::jsonb) as media_options,
                rationale, created_at,
                publish_status, publish_attempts, publish_error, publish_error_at,
-               thread_position, reply_to_tweet_id, posted_at, tweet_id
+               thread_position, reply_to_tweet_id, posted_at, tweet_id,
+               in_reply_to_url, quote_tweet_url, poll_options, poll_duration, library_asset_ids,
+               needs_review, review_reason, project
         FROM tweet_collateral
         WHERE thread_id = $1 AND user_id = $2
         ORDER BY thread_position ASC
@@ -441,7 +446,8 @@ where
                COALESCE(copy_options, '[]'::jsonb) as copy_options,
                image_capture_ids, video_clip,
                COALESCE(media_options, '[]'::jsonb) as media_options,
-               rationale
+               rationale, in_reply_to_url, quote_tweet_url,
+               poll_options, poll_duration, library_asset_ids
         FROM tweet_collateral
         WHERE thread_id = $1 AND user_id = $2 AND posted_at IS NULL
         ORDER BY thread_position ASC
@@ -453,6 +459,40 @@ where
     .await
 }
 
+/// Where one tweet of a thread is in publishing
+#[derive(Debug, sqlx::FromRow)]
+pub struct TweetPublishState {
+    pub id: i64,
+    pub thread_position: Option<i32>,
+    pub publish_status: String,
+    pub tweet_id: Option<String>,
+    pub reply_to_tweet_id: Option<String>,
+    pub publish_error: Option<String>,
+}
+
+/// Publish state of every tweet in a thread, in thread order
+pub async fn list_tweet_publish_states<'e, E>(
+    executor: E,
+    thread_id: i64,
+    user_id: i64,
+) -> Result<Vec<TweetPublishState>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, thread_position, publish_status, tweet_id, reply_to_tweet_id, publish_error
+        FROM tweet_collateral
+        WHERE thread_id = $1 AND user_id = $2
+        ORDER BY thread_position ASC
+        "#,
+    )
+    .bind(thread_id)
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
 /// Mark a tweet in a thread as posted
 pub async fn mark_thread_tweet_posted<'e, E>(
     executor: E,
@@ -766,61 +806,103 @@ where
     Ok(())
 }
 
-/// Verify tweet exists and is unposted
-pub async fn verify_tweet_exists_unposted<'e, E>(
-    executor: E,
-    tweet_id: i64,
-    user_id: i64,
-) -> Result<bool, sqlx::Error>
-where
-    E: Executor<'e, Database = Postgres>,
-{
-    let exists: Option<(i64,)> = sqlx::query_as(
-        "SELECT id FROM tweet_collateral WHERE id = $1 AND user_id = $2 AND posted_at IS NULL",
-    )
-    .bind(tweet_id)
-    .bind(user_id)
-    .fetch_optional(executor)
-    .await?;
-
-    Ok(exists.is_some())
+/// Changes to a tweet's collateral. `None` leaves a field unchanged; for
+/// nullable fields `Some(None)` clears it.
+#[derive(Debug, Default)]
+pub struct CollateralUpdate<'a> {
+    pub text: Option<&'a str>,
+    pub image_capture_ids: Option<&'a Vec<i64>>,
+    pub library_asset_ids: Option<&'a Vec<i64>>,
+    pub video_clip: Option<Option<serde_json::Value>>,
+    pub in_reply_to_url: Option<Option<&'a str>>,
+    pub quote_tweet_url: Option<Option<&'a str>>,
+    pub poll: Option<Option<&'a Poll>>,
+    /// Compliance filter result for new text: `Some(None)` clears the flag
+    pub review: Option<Option<&'a str>>,
+}
+
+impl CollateralUpdate<'_> {
+    fn is_empty(&self) -> bool {
+        self.text.is_none()
+            && self.image_capture_ids.is_none()
+            && self.library_asset_ids.is_none()
+            && self.video_clip.is_none()
+            && self.in_reply_to_url.is_none()
+            && self.quote_tweet_url.is_none()
+            && self.poll.is_none()
+            && self.review.is_none()
+    }
 }
 
-/// Update tweet collateral (media attachments)
+/// Update tweet collateral (text, media attachments, reply/quote links, poll)
 pub async fn update_tweet_collateral<'e, E>(
     executor: E,
     tweet_id: i64,
     user_id: i64,
-    text: Option<&str>,
-    image_capture_ids: Option<&Vec<i64>>,
-    video_clip: Option<Option<serde_json::Value>>,
+    update: CollateralUpdate<'_>,
 ) -> Result<bool, sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
 {
-    if text.is_none() && image_capture_ids.is_none() && video_clip.is_none() {
+    if update.is_empty() {
         return Ok(true);
     }
 
     let mut builder = QueryBuilder::<Postgres>::new("UPDATE tweet_collateral SET ");
     let mut separated = builder.separated(", ");
 
-    if let Some(text) = text {
+    if let Some(text) = update.text {
         separated.push("text = ").push_bind_unseparated(text);
     }
 
-    if let Some(image_capture_ids) = image_capture_ids {
+    if let Some(image_capture_ids) = update.image_capture_ids {
         separated
             .push("image_capture_ids = ")
             .push_bind_unseparated(image_capture_ids);
     }
 
-    if let Some(video_clip) = video_clip {
+    if let Some(library_asset_ids) = update.library_asset_ids {
+        separated
+            .push("library_asset_ids = ")
+            .push_bind_unseparated(library_asset_ids);
+    }
+
+    if let Some(video_clip) = update.video_clip {
         separated
             .push("video_clip = ")
             .push_bind_unseparated(video_clip);
     }
 
+    if let Some(in_reply_to_url) = update.in_reply_to_url {
+        separated
+            .push("in_reply_to_url = ")
+            .push_bind_unseparated(in_reply_to_url);
+    }
+
+    if let Some(quote_tweet_url) = update.quote_tweet_url {
+        separated
+            .push("quote_tweet_url = ")
+            .push_bind_unseparated(quote_tweet_url);
+    }
+
+    if let Some(poll) = update.poll {
+        separated
+            .push("poll_options = ")
+            .push_bind_unseparated(poll.map(|p| p.options.clone()));
+        separated
+            .push("poll_duration = ")
+            .push_bind_unseparated(poll.map(|p| p.duration_minutes));
+    }
+
+    if let Some(review_reason) = update.review {
+        separated
+            .push("needs_review = ")
+            .push_bind_unseparated(review_reason.is_some());
+        separated
+            .push("review_reason = ")
+            .push_bind_unseparated(review_reason);
+    }
+
     builder.push(" WHERE id = ");
     builder.push_bind(tweet_id);
     builder.push(" AND user_id = ");
@@ -830,3 +912,44 @@ where
 
     Ok(result.rows_affected() > 0)
 }
+
+/// A pending standalone tweet considered for thread suggestions
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ThreadCandidate {
+    pub id: i64,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    pub capture_ids: Vec<i64>,
+}
+
+/// Most recent pending tweets that aren't in a thread, with the captures they use
+pub async fn list_thread_candidates<'e, E>(
+    executor: E,
+    user_id: i64,
+    limit: i64,
+) -> Result<Vec<ThreadCandidate>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT t.id, t.text, t.created_at,
+               ARRAY(
+                   SELECT tc.capture_id FROM tweet_captures tc
+                   WHERE tc.tweet_id = t.id
+                   ORDER BY tc.capture_id
+               ) AS capture_ids
+        FROM tweet_collateral t
+        WHERE t.user_id = $1
+          AND t.thread_id IS NULL
+          AND t.posted_at IS NULL
+          AND t.dismissed_at IS NULL
+        ORDER BY t.created_at DESC, t.id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
@@ -3,9 +3,13 @@
 //! All functions use the generic Executor pattern, allowing them to work with
 //! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
 
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
 use sqlx::{Executor, Postgres};
 
-use super::super::models::{Tweet, TweetForPosting};
+use super::super::models::{
+    DismissalFeedback, DismissedTweet, PostedTweet, Tweet, TweetForPosting, TweetRevision,
+};
 
 /// Parsed status filter enum for type-safe query building
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -26,13 +30,13 @@ impl StatusFilter {
         }
     }
 
-    /// Returns SQL WHERE clause fragment for filtering by post status
-    fn where_clause(&self) -> &'static str {
+    /// Filter key bound as `$2`; queries match it against posted_at/dismissed_at
+    fn as_param(&self) -> &'static str {
         match self {
-            StatusFilter::Pending => "AND posted_at IS NULL AND dismissed_at IS NULL",
-            StatusFilter::Posted => "AND posted_at IS NOT NULL",
-            StatusFilter::Dismissed => "AND dismissed_at IS NOT NULL",
-            StatusFilter::All => "AND dismissed_at IS NULL",
+            StatusFilter::Pending => "pending",
+            StatusFilter::Posted => "posted",
+            StatusFilter::Dismissed => "dismissed",
+            StatusFilter::All => "all",
         }
     }
 }
@@ -46,21 +50,24 @@ pub async fn list_pending_tweets<'e, E>(
 where
     E: Executor<'e, Database = Postgres>,
 {
-    sqlx::query_as(
+    sqlx::query_as!(
+        Tweet,
         r#"
         SELECT id, text,
-               COALESCE(copy_options, '[]'::jsonb) as copy_options,
-               video_clip, image_capture_ids,
-               COALESCE(media_options, '[]'::jsonb) as media_options,
+               COALESCE(copy_options, '[]'::jsonb) AS "copy_options!: Json<Vec<String>>",
+               video_clip, image_capture_ids AS "image_capture_ids!",
+               COALESCE(media_options, '[]'::jsonb) AS "media_options!: Json<Vec<serde_json::Value>>",
                rationale, created_at,
                publish_status, publish_attempts, publish_error, publish_error_at,
-               thread_position, reply_to_tweet_id, posted_at, tweet_id
+               thread_position, reply_to_tweet_id, posted_at, tweet_id,
+               in_reply_to_url, quote_tweet_url, poll_options, poll_duration, library_asset_ids,
+               needs_review, review_reason, project
         FROM tweet_collateral
         WHERE user_id = $1 AND posted_at IS NULL AND dismissed_at IS NULL AND thread_id IS NULL
         ORDER BY created_at DESC
         "#,
+        user_id,
     )
-    .bind(user_id)
     .fetch_all(executor)
     .await
 }
@@ -75,17 +82,24 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let filter = StatusFilter::from_str(status_filter);
-    let query = format!(
-        "SELECT COUNT(*) FROM tweet_collateral WHERE user_id = $1 AND thread_id IS NULL {}",
-        filter.where_clause()
-    );
-
-    let (count,): (i64,) = sqlx::query_as(&query)
-        .bind(user_id)
-        .fetch_one(executor)
-        .await?;
 
-    Ok(count)
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM tweet_collateral
+        WHERE user_id = $1 AND thread_id IS NULL
+          AND (
+              ($2::text = 'pending' AND posted_at IS NULL AND dismissed_at IS NULL)
+              OR ($2 = 'posted' AND posted_at IS NOT NULL)
+              OR ($2 = 'dismissed' AND dismissed_at IS NOT NULL)
+              OR ($2 = 'all' AND dismissed_at IS NULL)
+          )
+        "#,
+        user_id,
+        filter.as_param(),
+    )
+    .fetch_one(executor)
+    .await
 }
 
 /// List pending standalone tweets with pagination
@@ -100,27 +114,34 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let filter = StatusFilter::from_str(status_filter);
-    let query = format!(
+    sqlx::query_as!(
+        Tweet,
         r#"SELECT id, text,
-                  COALESCE(copy_options, '[]'::jsonb) as copy_options,
-                  video_clip, image_capture_ids,
-                  COALESCE(media_options, '[]'::jsonb) as media_options,
+                  COALESCE(copy_options, '[]'::jsonb) AS "copy_options!: Json<Vec<String>>",
+                  video_clip, image_capture_ids AS "image_capture_ids!",
+                  COALESCE(media_options, '[]'::jsonb) AS "media_options!: Json<Vec<serde_json::Value>>",
                   rationale, created_at,
                   publish_status, publish_attempts, publish_error, publish_error_at,
-                  thread_position, reply_to_tweet_id, posted_at, tweet_id
+                  thread_position, reply_to_tweet_id, posted_at, tweet_id,
+                  in_reply_to_url, quote_tweet_url, poll_options, poll_duration, library_asset_ids,
+                  needs_review, review_reason, project
            FROM tweet_collateral
-           WHERE user_id = $1 AND thread_id IS NULL {}
+           WHERE user_id = $1 AND thread_id IS NULL
+             AND (
+                 ($2::text = 'pending' AND posted_at IS NULL AND dismissed_at IS NULL)
+                 OR ($2 = 'posted' AND posted_at IS NOT NULL)
+                 OR ($2 = 'dismissed' AND dismissed_at IS NOT NULL)
+                 OR ($2 = 'all' AND dismissed_at IS NULL)
+             )
            ORDER BY created_at DESC
-           LIMIT $2 OFFSET $3"#,
-        filter.where_clause()
-    );
-
-    sqlx::query_as(&query)
-        .bind(user_id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(executor)
-        .await
+           LIMIT $3 OFFSET $4"#,
+        user_id,
+        filter.as_param(),
+        limit,
+        offset,
+    )
+    .fetch_all(executor)
+    .await
 }
 
 /// List standalone tweets with pagination (not in a thread) for a user
@@ -136,27 +157,34 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let filter = StatusFilter::from_str(status_filter);
-    let query = format!(
+    sqlx::query_as!(
+        Tweet,
         r#"SELECT id, text,
-                  COALESCE(copy_options, '[]'::jsonb) as copy_options,
-                  video_clip, image_capture_ids,
-                  COALESCE(media_options, '[]'::jsonb) as media_options,
+                  COALESCE(copy_options, '[]'::jsonb) AS "copy_options!: Json<Vec<String>>",
+                  video_clip, image_capture_ids AS "image_capture_ids!",
+                  COALESCE(media_options, '[]'::jsonb) AS "media_options!: Json<Vec<serde_json::Value>>",
                   rationale, created_at,
                   publish_status, publish_attempts, publish_error, publish_error_at,
-                  thread_position, reply_to_tweet_id, posted_at, tweet_id
+                  thread_position, reply_to_tweet_id, posted_at, tweet_id,
+                  in_reply_to_url, quote_tweet_url, poll_options, poll_duration, library_asset_ids,
+                  needs_review, review_reason, project
            FROM tweet_collateral
-           WHERE user_id = $1 AND thread_id IS NULL {}
+           WHERE user_id = $1 AND thread_id IS NULL
+             AND (
+                 ($2::text = 'pending' AND posted_at IS NULL AND dismissed_at IS NULL)
+                 OR ($2 = 'posted' AND posted_at IS NOT NULL)
+                 OR ($2 = 'dismissed' AND dismissed_at IS NOT NULL)
+                 OR ($2 = 'all' AND dismissed_at IS NULL)
+             )
            ORDER BY created_at DESC
-           LIMIT $2 OFFSET $3"#,
-        filter.where_clause()
-    );
-
-    sqlx::query_as(&query)
-        .bind(user_id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(executor)
-        .await
+           LIMIT $3 OFFSET $4"#,
+        user_id,
+        filter.as_param(),
+        limit,
+        offset,
+    )
+    .fetch_all(executor)
+    .await
 }
 
 /// List all standalone tweets (not in a thread) for a user
@@ -170,24 +198,31 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let filter = StatusFilter::from_str(status_filter);
-    let query = format!(
+    sqlx::query_as!(
+        Tweet,
         r#"SELECT id, text,
-                  COALESCE(copy_options, '[]'::jsonb) as copy_options,
-                  video_clip, image_capture_ids,
-                  COALESCE(media_options, '[]'::jsonb) as media_options,
+                  COALESCE(copy_options, '[]'::jsonb) AS "copy_options!: Json<Vec<String>>",
+                  video_clip, image_capture_ids AS "image_capture_ids!",
+                  COALESCE(media_options, '[]'::jsonb) AS "media_options!: Json<Vec<serde_json::Value>>",
                   rationale, created_at,
                   publish_status, publish_attempts, publish_error, publish_error_at,
-                  thread_position, reply_to_tweet_id, posted_at, tweet_id
+                  thread_position, reply_to_tweet_id, posted_at, tweet_id,
+                  in_reply_to_url, quote_tweet_url, poll_options, poll_duration, library_asset_ids,
+                  needs_review, review_reason, project
            FROM tweet_collateral
-           WHERE user_id = $1 AND thread_id IS NULL {}
+           WHERE user_id = $1 AND thread_id IS NULL
+             AND (
+                 ($2::text = 'pending' AND posted_at IS NULL AND dismissed_at IS NULL)
+                 OR ($2 = 'posted' AND posted_at IS NOT NULL)
+                 OR ($2 = 'dismissed' AND dismissed_at IS NOT NULL)
+                 OR ($2 = 'all' AND dismissed_at IS NULL)
+             )
            ORDER BY created_at DESC"#,
-        filter.where_clause()
-    );
-
-    sqlx::query_as(&query)
-        .bind(user_id)
-        .fetch_all(executor)
-        .await
+        user_id,
+        filter.as_param(),
+    )
+    .fetch_all(executor)
+    .await
 }
 
 /// Get a tweet by ID (with media info for posting)
@@ -205,7 +240,8 @@ where
                COALESCE(copy_options, '[]'::jsonb) as copy_options,
                image_capture_ids, video_clip,
                COALESCE(media_options, '[]'::jsonb) as media_options,
-               rationale
+               rationale, in_reply_to_url, quote_tweet_url,
+               poll_options, poll_duration, library_asset_ids
         FROM tweet_collateral
         WHERE id = $1 AND user_id = $2 AND posted_at IS NULL AND dismissed_at IS NULL
         "#,
@@ -304,11 +340,108 @@ where
     Ok(result.rows_affected() > 0)
 }
 
-/// Soft-delete a pending tweet (sets dismissed_at instead of removing the row)
+/// Queue a pending or failed tweet to publish after `delay_secs` (the user's
+/// undo window). Returns when it will publish, or None if it can't be queued.
+pub async fn queue_tweet_publish<'e, E>(
+    executor: E,
+    tweet_id: i64,
+    user_id: i64,
+    delay_secs: i32,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        r#"
+        UPDATE tweet_collateral
+        SET publish_status = 'queued',
+            publish_at = NOW() + ($3 * INTERVAL '1 second'),
+            publish_error = NULL,
+            publish_error_at = NULL
+        WHERE id = $1
+            AND user_id = $2
+            AND posted_at IS NULL
+            AND dismissed_at IS NULL
+            AND publish_status IN ('pending', 'failed')
+        RETURNING publish_at
+        "#,
+    )
+    .bind(tweet_id)
+    .bind(user_id)
+    .bind(delay_secs)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Put a queued tweet back to pending while its undo window is still open.
+/// Returns false if it isn't queued or the publish queue already claimed it.
+pub async fn cancel_queued_publish<'e, E>(
+    executor: E,
+    tweet_id: i64,
+    user_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE tweet_collateral
+        SET publish_status = 'pending',
+            publish_at = NULL
+        WHERE id = $1
+            AND user_id = $2
+            AND publish_status = 'queued'
+            AND publish_at > NOW()
+        "#,
+    )
+    .bind(tweet_id)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Claim queued tweets whose undo window has passed, moving them to posting.
+/// Returns (tweet id, user id) pairs.
+pub async fn claim_due_publishes<'e, E>(
+    executor: E,
+    limit: i64,
+) -> Result<Vec<(i64, i64)>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        UPDATE tweet_collateral
+        SET publish_status = 'posting',
+            publish_at = NULL,
+            publish_attempts = COALESCE(publish_attempts, 0) + 1
+        WHERE id IN (
+            SELECT id FROM tweet_collateral
+            WHERE publish_status = 'queued'
+                AND publish_at <= NOW()
+                AND posted_at IS NULL
+                AND dismissed_at IS NULL
+            ORDER BY publish_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, user_id
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Soft-delete a pending tweet (sets dismissed_at instead of removing the row),
+/// keeping the user's reason if they gave one
 pub async fn delete_tweet<'e, E>(
     executor: E,
     tweet_id: i64,
     user_id: i64,
+    reason: Option<&str>,
 ) -> Result<bool, sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
@@ -317,7 +450,109 @@ where
         r#"
         UPDATE tweet_collateral
         SET dismissed_at = NOW(),
-            publish_status = 'dismissed'
+            publish_status = 'dismissed',
+            dismiss_reason = $3
+        WHERE id = $1 AND user_id = $2 AND posted_at IS NULL AND dismissed_at IS NULL
+        "#,
+    )
+    .bind(tweet_id)
+    .bind(user_id)
+    .bind(reason)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Count a user's dismissed tweets (standalone and in threads)
+pub async fn count_dismissed_tweets<'e, E>(executor: E, user_id: i64) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM tweet_collateral WHERE user_id = $1 AND dismissed_at IS NOT NULL",
+    )
+    .bind(user_id)
+    .fetch_one(executor)
+    .await
+}
+
+/// List a user's dismissed tweets, most recently dismissed first
+pub async fn list_dismissed_tweets<'e, E>(
+    executor: E,
+    user_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<DismissedTweet>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"SELECT id, text,
+                  COALESCE(copy_options, '[]'::jsonb) as copy_options,
+                  video_clip, image_capture_ids,
+                  COALESCE(media_options, '[]'::jsonb) as media_options,
+                  rationale, created_at,
+                  publish_status, publish_attempts, publish_error, publish_error_at,
+                  thread_position, reply_to_tweet_id, posted_at, tweet_id,
+                  in_reply_to_url, quote_tweet_url, poll_options, poll_duration, library_asset_ids,
+                  needs_review, review_reason, project,
+                  dismissed_at, dismiss_reason
+           FROM tweet_collateral
+           WHERE user_id = $1 AND dismissed_at IS NOT NULL
+           ORDER BY dismissed_at DESC, id DESC
+           LIMIT $2 OFFSET $3"#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(executor)
+    .await
+}
+
+/// Most recent dismissals that came with a reason, within `days`
+pub async fn list_dismissal_feedback<'e, E>(
+    executor: E,
+    user_id: i64,
+    days: i64,
+    limit: i64,
+) -> Result<Vec<DismissalFeedback>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT text, dismiss_reason AS reason
+        FROM tweet_collateral
+        WHERE user_id = $1
+          AND dismiss_reason IS NOT NULL
+          AND dismissed_at > NOW() - ($2 * INTERVAL '1 day')
+        ORDER BY dismissed_at DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(days)
+    .bind(limit)
+    .fetch_all(executor)
+    .await
+}
+
+/// Clear the compliance filter's review flag on a pending tweet. Returns false
+/// if the user has no such pending tweet.
+pub async fn approve_tweet<'e, E>(
+    executor: E,
+    tweet_id: i64,
+    user_id: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE tweet_collateral
+        SET needs_review = FALSE,
+            review_reason = NULL
         WHERE id = $1 AND user_id = $2 AND posted_at IS NULL AND dismissed_at IS NULL
         "#,
     )
@@ -328,3 +563,132 @@ where
 
     Ok(result.rows_affected() > 0)
 }
+
+/// Get a posted tweet by ID (with media info for reposting)
+pub async fn get_posted_tweet<'e, E>(
+    executor: E,
+    tweet_id: i64,
+    user_id: i64,
+) -> Result<Option<PostedTweet>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, text,
+               COALESCE(copy_options, '[]'::jsonb) as copy_options,
+               image_capture_ids, video_clip,
+               COALESCE(media_options, '[]'::jsonb) as media_options,
+               rationale, in_reply_to_url, quote_tweet_url,
+               poll_options, poll_duration, library_asset_ids, tweet_id, thread_id
+        FROM tweet_collateral
+        WHERE id = $1 AND user_id = $2 AND posted_at IS NOT NULL AND tweet_id IS NOT NULL
+        "#,
+    )
+    .bind(tweet_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Point a posted tweet at its replacement (atomic - only succeeds if the live
+/// tweet is still `previous_twitter_id`). Returns false if it changed meanwhile.
+pub async fn replace_posted_tweet<'e, E>(
+    executor: E,
+    tweet_id: i64,
+    user_id: i64,
+    previous_twitter_id: &str,
+    text: &str,
+    twitter_id: &str,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE tweet_collateral
+        SET text = $4,
+            tweet_id = $5,
+            posted_at = NOW()
+        WHERE id = $1 AND user_id = $2 AND tweet_id = $3
+        "#,
+    )
+    .bind(tweet_id)
+    .bind(user_id)
+    .bind(previous_twitter_id)
+    .bind(text)
+    .bind(twitter_id)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Record a correction in the tweet's revision history
+pub async fn insert_revision<'e, E>(
+    executor: E,
+    tweet_id: i64,
+    user_id: i64,
+    previous_text: &str,
+    previous_twitter_id: &str,
+    new_text: &str,
+    new_twitter_id: &str,
+) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO tweet_revisions
+            (tweet_collateral_id, user_id, previous_text, previous_tweet_id,
+             new_text, new_tweet_id, previous_deleted)
+        VALUES ($1, $2, $3, $4, $5, $6, FALSE)
+        RETURNING id
+        "#,
+    )
+    .bind(tweet_id)
+    .bind(user_id)
+    .bind(previous_text)
+    .bind(previous_twitter_id)
+    .bind(new_text)
+    .bind(new_twitter_id)
+    .fetch_one(executor)
+    .await
+}
+
+/// Note that the replaced tweet was deleted on Twitter
+pub async fn mark_revision_deleted<'e, E>(executor: E, revision_id: i64) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE tweet_revisions SET previous_deleted = TRUE WHERE id = $1")
+        .bind(revision_id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+/// List a tweet's corrections, newest first
+pub async fn list_revisions<'e, E>(
+    executor: E,
+    tweet_id: i64,
+    user_id: i64,
+) -> Result<Vec<TweetRevision>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT id, previous_text, previous_tweet_id, new_text, new_tweet_id,
+               previous_deleted, created_at
+        FROM tweet_revisions
+        WHERE tweet_collateral_id = $1 AND user_id = $2
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(tweet_id)
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
@@ -3,11 +3,16 @@
 //! All functions use the generic Executor pattern, allowing them to work with
 //! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
 
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::{Executor, Postgres};
 
 #[derive(Debug, sqlx::FromRow)]
 pub struct UserBasicInfo {
-    pub twitter_username: String,
+    /// X handle, or the GitHub/Google handle for accounts without X
+    pub username: String,
+    /// None until X is linked
+    pub twitter_username: Option<String>,
 }
 
 /// Get basic user info by ID
@@ -18,8 +23,367 @@ pub async fn get_user_by_id<'e, E>(
 where
     E: Executor<'e, Database = Postgres>,
 {
-    sqlx::query_as("SELECT twitter_username FROM users WHERE id = $1")
+    sqlx::query_as(
+        r#"
+        SELECT COALESCE(
+                   u.twitter_username,
+                   (SELECT l.username FROM login_identities l
+                    WHERE l.user_id = u.id ORDER BY l.id LIMIT 1)
+               ) AS username,
+               u.twitter_username
+        FROM users u WHERE u.id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Get a user's subscription tier ('free' or 'pro')
+pub async fn get_user_tier<'e, E>(executor: E, user_id: i64) -> Result<Option<String>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT tier FROM users WHERE id = $1")
         .bind(user_id)
         .fetch_optional(executor)
         .await
 }
+
+/// Get a user's IANA time zone name
+pub async fn get_user_timezone<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Option<String>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT timezone FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Set a user's IANA time zone name (validated by the caller)
+pub async fn set_user_timezone<'e, E>(
+    executor: E,
+    user_id: i64,
+    timezone: &str,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE users SET timezone = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(timezone)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Get the code of the language a user's drafts are written in
+pub async fn get_draft_language<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Option<String>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT draft_language FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Set the language a user's drafts are written in (validated by the caller)
+pub async fn set_draft_language<'e, E>(
+    executor: E,
+    user_id: i64,
+    language: &str,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE users SET draft_language = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(language)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Seconds a publish waits in the queue so the user can cancel it (0 = none)
+pub async fn get_publish_undo_secs<'e, E>(executor: E, user_id: i64) -> Result<i32, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let secs: Option<i32> = sqlx::query_scalar("SELECT publish_undo_secs FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await?;
+    Ok(secs.unwrap_or(0))
+}
+
+/// Set a user's publish undo window (validated by the caller)
+pub async fn set_publish_undo_secs<'e, E>(
+    executor: E,
+    user_id: i64,
+    secs: i32,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE users SET publish_undo_secs = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(secs)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Get when a user's launch mode ends, if it's still running
+pub async fn get_launch_mode_until<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "SELECT launch_mode_until FROM users WHERE id = $1 AND launch_mode_until > NOW()",
+    )
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+    .map(Option::flatten)
+}
+
+/// Start (Some) or end (None) a user's launch mode
+pub async fn set_launch_mode_until<'e, E>(
+    executor: E,
+    user_id: i64,
+    until: Option<DateTime<Utc>>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE users SET launch_mode_until = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(until)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Get the sites whose browser tabs are never recorded
+pub async fn get_blocked_domains<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Vec<String>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar("SELECT blocked_domains FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await
+        .map(Option::unwrap_or_default)
+}
+
+/// Replace the user's blocked domain list
+pub async fn set_blocked_domains<'e, E>(
+    executor: E,
+    user_id: i64,
+    domains: &[String],
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE users SET blocked_domains = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(domains)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Watermark settings applied to published media
+#[derive(Debug, sqlx::FromRow)]
+pub struct Branding {
+    pub enabled: bool,
+    /// Media library image used as the logo
+    pub asset_id: Option<i64>,
+    pub position: String,
+    pub opacity: f32,
+}
+
+/// Get the user's branding watermark settings
+pub async fn get_branding<'e, E>(executor: E, user_id: i64) -> Result<Option<Branding>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT branding_enabled AS enabled, branding_asset_id AS asset_id,
+               branding_position AS position, branding_opacity AS opacity
+        FROM users
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Replace the user's branding watermark settings (validated by the caller)
+pub async fn set_branding<'e, E>(
+    executor: E,
+    user_id: i64,
+    branding: &Branding,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET branding_enabled = $2, branding_asset_id = $3,
+            branding_position = $4, branding_opacity = $5
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(branding.enabled)
+    .bind(branding.asset_id)
+    .bind(&branding.position)
+    .bind(branding.opacity)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Which kinds of data the agent may read for a user
+#[derive(Debug, Clone, Copy, sqlx::FromRow, Serialize, Deserialize)]
+pub struct AgentDataPolicy {
+    /// Screenshots (captures with media_type 'image')
+    pub images: bool,
+    /// Screen recordings, including the frames extracted from them
+    pub video: bool,
+    /// App, window and URL events and activity summaries
+    pub activity: bool,
+}
+
+impl Default for AgentDataPolicy {
+    fn default() -> Self {
+        Self {
+            images: true,
+            video: true,
+            activity: true,
+        }
+    }
+}
+
+impl AgentDataPolicy {
+    /// Whether captures of this media type ('image' or 'video') may be read
+    pub fn allows_media(&self, media_type: &str) -> bool {
+        match media_type {
+            "image" => self.images,
+            "video" => self.video,
+            _ => false,
+        }
+    }
+
+    /// Capture media types the agent may read
+    pub fn media_types(&self) -> Vec<&'static str> {
+        ["image", "video"]
+            .into_iter()
+            .filter(|m| self.allows_media(m))
+            .collect()
+    }
+}
+
+/// Get the user's agent data policy
+pub async fn get_agent_data_policy<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Option<AgentDataPolicy>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT agent_allow_images AS images, agent_allow_video AS video,
+               agent_allow_activity AS activity
+        FROM users
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Replace the user's agent data policy
+pub async fn set_agent_data_policy<'e, E>(
+    executor: E,
+    user_id: i64,
+    policy: &AgentDataPolicy,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET agent_allow_images = $2, agent_allow_video = $3, agent_allow_activity = $4
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(policy.images)
+    .bind(policy.video)
+    .bind(policy.activity)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// The user's own terms for the generated-copy compliance filter
+#[derive(Debug, Default, sqlx::FromRow, Serialize, Deserialize)]
+pub struct CopyFilterTerms {
+    /// Unreleased project codenames that must not be tweeted
+    pub confidential_terms: Vec<String>,
+    pub competitor_terms: Vec<String>,
+}
+
+/// Get the user's compliance filter terms
+pub async fn get_copy_filter_terms<'e, E>(
+    executor: E,
+    user_id: i64,
+) -> Result<Option<CopyFilterTerms>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as("SELECT confidential_terms, competitor_terms FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Replace the user's compliance filter terms (normalized by the caller)
+pub async fn set_copy_filter_terms<'e, E>(
+    executor: E,
+    user_id: i64,
+    terms: &CopyFilterTerms,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE users SET confidential_terms = $2, competitor_terms = $3 WHERE id = $1")
+        .bind(user_id)
+        .bind(&terms.confidential_terms)
+        .bind(&terms.competitor_terms)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,294 @@
+//! Weekly recaps domain - DB queries for the "week in review" schedule and the threads it drafts
+//!
+//! All functions use the generic Executor pattern, allowing them to work with
+//! both `&PgPool` (for standalone queries) and `&mut PgConnection` (for transactions).
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Executor, Postgres};
+
+/// A user's recap schedule and the outcome of the last recap
+#[derive(Debug, sqlx::FromRow)]
+pub struct WeeklyRecap {
+    pub enabled: bool,
+    /// ISO weekday, 1 = Monday
+    pub weekday: i16,
+    /// Local hour the recap is composed from
+    pub hour: i16,
+    pub last_recap_on: Option<NaiveDate>,
+    pub last_thread_id: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+/// A user whose recap may be due; the worker checks their zone and schedule
+#[derive(Debug, sqlx::FromRow)]
+pub struct RecapCandidate {
+    pub user_id: i64,
+    pub timezone: String,
+    pub weekday: i16,
+    pub hour: i16,
+    pub last_recap_on: Option<NaiveDate>,
+}
+
+/// A tweet drafted or posted during the week
+#[derive(Debug, sqlx::FromRow)]
+pub struct WeekTweet {
+    pub text: String,
+    /// Why the agent picked the captured moment
+    pub rationale: String,
+    pub image_capture_ids: Vec<i64>,
+    pub created_at: DateTime<Utc>,
+    pub posted_at: Option<DateTime<Utc>>,
+}
+
+const RECAP_COLUMNS: &str = "enabled, weekday, hour, last_recap_on, last_thread_id, last_error";
+
+/// Get the user's recap settings, or None if they never changed the default
+pub async fn get_recap<'e, E>(executor: E, user_id: i64) -> Result<Option<WeeklyRecap>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        "SELECT {} FROM weekly_recaps WHERE user_id = $1",
+        RECAP_COLUMNS
+    );
+    sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Set the user's recap schedule, keeping the record of the last recap
+pub async fn set_schedule<'e, E>(
+    executor: E,
+    user_id: i64,
+    enabled: bool,
+    weekday: i16,
+    hour: i16,
+) -> Result<WeeklyRecap, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let query = format!(
+        r#"
+        INSERT INTO weekly_recaps (user_id, enabled, weekday, hour)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id) DO UPDATE SET
+            enabled = EXCLUDED.enabled,
+            weekday = EXCLUDED.weekday,
+            hour = EXCLUDED.hour,
+            updated_at = NOW()
+        RETURNING {}
+        "#,
+        RECAP_COLUMNS
+    );
+    sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(enabled)
+        .bind(weekday)
+        .bind(hour)
+        .fetch_one(executor)
+        .await
+}
+
+/// Users with recaps switched on (the default), not leased, not recapped in the
+/// last five UTC days, and with a tweet drafted or posted in the last week.
+/// `default_weekday` and `default_hour` apply to users without a row.
+pub async fn list_candidates<'e, E>(
+    executor: E,
+    utc_today: NaiveDate,
+    default_weekday: i16,
+    default_hour: i16,
+) -> Result<Vec<RecapCandidate>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT u.id AS user_id, u.timezone,
+               COALESCE(r.weekday, $2) AS weekday,
+               COALESCE(r.hour, $3) AS hour,
+               r.last_recap_on
+        FROM users u
+        LEFT JOIN weekly_recaps r ON r.user_id = u.id
+        WHERE COALESCE(r.enabled, TRUE)
+          AND (r.last_recap_on IS NULL OR r.last_recap_on <= $1 - 5)
+          AND (r.lease_until IS NULL OR r.lease_until < NOW())
+          AND EXISTS (
+              SELECT 1 FROM tweet_collateral t
+              WHERE t.user_id = u.id
+                AND COALESCE(t.posted_at, t.created_at) > NOW() - INTERVAL '7 days'
+          )
+        ORDER BY u.id
+        "#,
+    )
+    .bind(utc_today)
+    .bind(default_weekday)
+    .bind(default_hour)
+    .fetch_all(executor)
+    .await
+}
+
+/// Lease the user's recap, unless another worker holds it, the user switched
+/// recaps off, or the last recap is on or after `recapped_before`. Returns
+/// false if it couldn't be claimed.
+pub async fn claim_recap<'e, E>(
+    executor: E,
+    user_id: i64,
+    recapped_before: NaiveDate,
+    lease_secs: i64,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        INSERT INTO weekly_recaps (user_id, lease_until)
+        VALUES ($1, NOW() + ($3 * INTERVAL '1 second'))
+        ON CONFLICT (user_id) DO UPDATE SET lease_until = EXCLUDED.lease_until
+        WHERE weekly_recaps.enabled
+          AND (weekly_recaps.last_recap_on IS NULL OR weekly_recaps.last_recap_on < $2)
+          AND (weekly_recaps.lease_until IS NULL OR weekly_recaps.lease_until < NOW())
+        "#,
+    )
+    .bind(user_id)
+    .bind(recapped_before)
+    .bind(lease_secs)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Record the recap composed for the local date `day` and release the lease.
+/// `thread_id` is None when the week had nothing to recap.
+pub async fn complete_recap<'e, E>(
+    executor: E,
+    user_id: i64,
+    day: NaiveDate,
+    thread_id: Option<i64>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO weekly_recaps (user_id, last_recap_on, last_thread_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id) DO UPDATE SET
+            last_recap_on = EXCLUDED.last_recap_on,
+            last_thread_id = EXCLUDED.last_thread_id,
+            last_error = NULL,
+            lease_until = NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(day)
+    .bind(thread_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed recap. The lease is kept, so it's retried once it expires.
+pub async fn fail_recap<'e, E>(executor: E, user_id: i64, error: &str) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE weekly_recaps SET last_error = $2 WHERE user_id = $1")
+        .bind(user_id)
+        .bind(error)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Tweets drafted or posted in `[start, end)`, oldest first. Dismissed drafts
+/// and earlier recap threads are left out.
+pub async fn list_week_tweets<'e, E>(
+    executor: E,
+    user_id: i64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<WeekTweet>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as(
+        r#"
+        SELECT t.text, t.rationale, COALESCE(t.image_capture_ids, '{}') AS image_capture_ids,
+               t.created_at, t.posted_at
+        FROM tweet_collateral t
+        LEFT JOIN tweet_threads th ON th.id = t.thread_id
+        WHERE t.user_id = $1 AND t.dismissed_at IS NULL
+          AND th.weekly_recap_on IS NULL
+          AND ((t.created_at >= $2 AND t.created_at < $3)
+               OR (t.posted_at >= $2 AND t.posted_at < $3))
+        ORDER BY COALESCE(t.posted_at, t.created_at), t.id
+        "#,
+    )
+    .bind(user_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(executor)
+    .await
+}
+
+/// Create a draft thread marked as the recap for the local date `recap_on`
+/// Note: Caller manages transaction. Use with a transaction for atomicity.
+pub async fn create_recap_thread<'e, E>(
+    executor: E,
+    user_id: i64,
+    title: &str,
+    recap_on: NaiveDate,
+) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO tweet_threads (user_id, title, status, created_at, weekly_recap_on)
+        VALUES ($1, $2, 'draft', NOW(), $3)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(title)
+    .bind(recap_on)
+    .fetch_one(executor)
+    .await
+}
+
+/// Add one tweet of a recap thread
+/// Note: Caller manages transaction. Use with a transaction for atomicity.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_recap_tweet<'e, E>(
+    executor: E,
+    user_id: i64,
+    thread_id: i64,
+    position: i32,
+    text: &str,
+    image_capture_ids: &[i64],
+    rationale: &str,
+    review_reason: Option<&str>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO tweet_collateral (user_id, text, image_capture_ids, rationale, created_at,
+                                      thread_id, thread_position, needs_review, review_reason)
+        VALUES ($1, $2, $3, $4, NOW(), $5, $6, $7, $8)
+        "#,
+    )
+    .bind(user_id)
+    .bind(text)
+    .bind(image_capture_ids)
+    .bind(rationale)
+    .bind(thread_id)
+    .bind(position)
+    .bind(review_reason.is_some())
+    .bind(review_reason)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,375 @@
+//! User data export (takeout) background worker
+//!
+//! Processes jobs queued via POST /me/export: writes every table we keep for the
+//! user as JSON plus their capture media and extracted frames into a zip, uploads
+//! it under `exports/`, and sends a push notification with a download link.
+//! Finished exports expire after EXPORT_TTL_HOURS and their zips are deleted.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use std::io::Write;
+use std::path::PathBuf;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use crate::domain::exports;
+use crate::frames::{FrameManifest, get_frames_dir};
+use crate::services;
+use crate::storage::{self, Buckets};
+
+const MAX_ATTEMPTS: i32 = 3;
+/// Expiry of the signed link sent in the ready notification
+const NOTIFICATION_URL_EXPIRY_SECS: u32 = 24 * 60 * 60;
+
+/// Tables exported as `<name>.json`, each filtered by user_id
+const EXPORT_TABLES: &[(&str, &str)] = &[
+    ("captures", "captures"),
+    ("activities", "activities"),
+    ("summaries", "activity_summaries"),
+    ("sessions", "sessions"),
+    ("devices", "devices"),
+    ("tweets", "tweet_collateral"),
+    ("revisions", "tweet_revisions"),
+    ("threads", "tweet_threads"),
+    ("longform", "longform_posts"),
+    ("library", "media_assets"),
+    ("folders", "media_folders"),
+    ("agent_runs", "agent_runs"),
+    ("personas", "user_personas"),
+];
+
+const README: &str = "Cleo data export
+
+profile.json      your account (OAuth and API tokens omitted)
+captures.json     screenshot and recording metadata
+activities.json   app and window activity
+summaries.json    activity rolled up per 5-minute interval
+sessions.json     work sessions
+devices.json      machines running the Cleo daemon
+tweets.json       generated tweets, including posting state and tweet ids
+revisions.json    corrections made to posted tweets
+threads.json      generated threads
+longform.json     long-form posts
+library.json      media library images you uploaded
+folders.json      media library folders
+agent_runs.json   content agent runs
+personas.json     saved personas
+media/            original captures and library images, at the paths listed
+                  in captures.json and library.json
+frames/           frames extracted from captures for the content agent
+";
+
+#[derive(Debug, Serialize)]
+struct ExportSummary {
+    user_id: i64,
+    exported_at: chrono::DateTime<chrono::Utc>,
+    media_count: usize,
+    missing_media: Vec<String>,
+}
+
+/// Start the export worker.
+/// Exports are built one at a time; expired archives are swept every cycle.
+pub async fn run_export_worker(
+    pool: PgPool,
+    gcs: Option<google_cloud_storage::client::Storage>,
+    local_storage_path: Option<PathBuf>,
+    buckets: Buckets,
+) {
+    let poll_interval_secs = export_poll_interval_secs();
+    let lease_secs = export_lease_secs();
+    let ttl_secs = export_ttl_hours() * 60 * 60;
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(poll_interval_secs));
+
+    println!(
+        "[export] Worker starting ({}s poll, {}s lease, {}s ttl)",
+        poll_interval_secs, lease_secs, ttl_secs
+    );
+
+    loop {
+        interval.tick().await;
+        crate::services::supervisor::heartbeat("export");
+
+        sweep_expired(&pool, gcs.as_ref(), local_storage_path.as_ref(), &buckets).await;
+
+        let job = match exports::claim_export_job(&pool, lease_secs, MAX_ATTEMPTS).await {
+            Ok(Some(job)) => job,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("[export] Claim error: {}", e);
+                continue;
+            }
+        };
+
+        println!(
+            "[export] Building export {} for user {}",
+            job.id, job.user_id
+        );
+
+        match build_export(
+            &pool,
+            gcs.as_ref(),
+            local_storage_path.as_ref(),
+            &buckets,
+            &job,
+        )
+        .await
+        {
+            Ok((archive_path, size_bytes)) => {
+                if let Err(e) =
+                    exports::complete_export(&pool, job.id, &archive_path, size_bytes, ttl_secs)
+                        .await
+                {
+                    eprintln!("[export] Failed to mark export {} completed: {}", job.id, e);
+                    continue;
+                }
+                println!(
+                    "[export] Export {} for user {} ready ({} bytes)",
+                    job.id, job.user_id, size_bytes
+                );
+
+                match download_url(
+                    local_storage_path.is_some(),
+                    &buckets,
+                    job.id,
+                    &archive_path,
+                    NOTIFICATION_URL_EXPIRY_SECS,
+                )
+                .await
+                {
+                    Ok(url) => {
+                        if let Err(e) =
+                            services::push::notify_export_ready(&pool, job.user_id, &url).await
+                        {
+                            eprintln!(
+                                "[export] Failed to notify user {} of export {}: {}",
+                                job.user_id, job.id, e
+                            );
+                        }
+                    }
+                    Err(e) => eprintln!("[export] Download URL error for {}: {}", job.id, e),
+                }
+            }
+            Err(e) => {
+                eprintln!("[export] Failed export {}: {}", job.id, e);
+                if let Err(e) =
+                    exports::fail_export(&pool, job.id, &e.to_string(), MAX_ATTEMPTS).await
+                {
+                    eprintln!("[export] Failed to record error for {}: {}", job.id, e);
+                }
+            }
+        }
+    }
+}
+
+/// Build the zip in a temp dir, upload it, and return (storage path, size)
+async fn build_export(
+    pool: &PgPool,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    job: &exports::ExportJob,
+) -> Result<(String, i64), Box<dyn std::error::Error + Send + Sync>> {
+    let temp_dir = std::env::temp_dir().join(format!("cleo_export_{}", rand::random::<u64>()));
+    tokio::fs::create_dir_all(&temp_dir).await?;
+    let zip_path = temp_dir.join("export.zip");
+
+    let result = async {
+        write_zip(
+            pool,
+            gcs,
+            local_storage_path,
+            buckets,
+            job.user_id,
+            &zip_path,
+        )
+        .await?;
+
+        let data = tokio::fs::read(&zip_path).await?;
+        let archive_path = export_path(job.user_id, job.id);
+        storage::upload_data(gcs, local_storage_path, buckets, &archive_path, &data).await?;
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>((archive_path, data.len() as i64))
+    }
+    .await;
+
+    if let Err(e) = tokio::fs::remove_dir_all(&temp_dir).await {
+        eprintln!("[export] Failed to cleanup temp dir {:?}: {}", temp_dir, e);
+    }
+    result
+}
+
+async fn write_zip(
+    pool: &PgPool,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    user_id: i64,
+    zip_path: &std::path::Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let deflated = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    // Media is already compressed
+    let stored = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+
+    let mut zip = ZipWriter::new(std::fs::File::create(zip_path)?);
+
+    zip.start_file("README.txt", deflated)?;
+    zip.write_all(README.as_bytes())?;
+
+    let profile = exports::fetch_profile(pool, user_id).await?;
+    zip.start_file("profile.json", deflated)?;
+    zip.write_all(&serde_json::to_vec_pretty(&profile)?)?;
+
+    for (name, table) in EXPORT_TABLES {
+        let rows = exports::fetch_user_rows(pool, table, user_id).await?;
+        zip.start_file(format!("{}.json", name), deflated)?;
+        zip.write_all(&serde_json::to_vec_pretty(&rows)?)?;
+    }
+
+    let media = exports::list_user_media(pool, user_id).await?;
+    let mut missing_media = Vec::new();
+    for capture in &media {
+        match storage::download_capture(gcs, local_storage_path, buckets, &capture.gcs_path).await {
+            Ok(data) => {
+                zip.start_file(format!("media/{}", capture.gcs_path), stored)?;
+                zip.write_all(&data)?;
+            }
+            Err(e) => {
+                eprintln!(
+                    "[export] Missing media for capture {} ({}): {}",
+                    capture.id, capture.gcs_path, e
+                );
+                missing_media.push(capture.gcs_path.clone());
+                continue;
+            }
+        }
+
+        if !capture.frames_extracted {
+            continue;
+        }
+        let frames_dir = get_frames_dir(&capture.gcs_path);
+        let manifest_path = format!("{}/manifest.json", frames_dir);
+        let Ok(manifest_data) =
+            storage::download_capture(gcs, local_storage_path, buckets, &manifest_path).await
+        else {
+            continue;
+        };
+        let Ok(manifest) = serde_json::from_slice::<FrameManifest>(&manifest_data) else {
+            continue;
+        };
+        zip.start_file(manifest_path, deflated)?;
+        zip.write_all(&manifest_data)?;
+        for frame in &manifest.frames {
+            let frame_path = format!("{}/{}", frames_dir, frame.filename);
+            if let Ok(data) =
+                storage::download_capture(gcs, local_storage_path, buckets, &frame_path).await
+            {
+                zip.start_file(frame_path, stored)?;
+                zip.write_all(&data)?;
+            }
+        }
+    }
+
+    let missing_captures = missing_media.len();
+    let mut library_count = 0;
+    for path in exports::list_library_media(pool, user_id).await? {
+        match storage::download_capture(gcs, local_storage_path, buckets, &path).await {
+            Ok(data) => {
+                zip.start_file(format!("media/{}", path), stored)?;
+                zip.write_all(&data)?;
+                library_count += 1;
+            }
+            Err(e) => {
+                eprintln!("[export] Missing library media {}: {}", path, e);
+                missing_media.push(path);
+            }
+        }
+    }
+
+    let summary = ExportSummary {
+        user_id,
+        exported_at: chrono::Utc::now(),
+        media_count: media.len() - missing_captures + library_count,
+        missing_media,
+    };
+    zip.start_file("export.json", deflated)?;
+    zip.write_all(&serde_json::to_vec_pretty(&summary)?)?;
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// Mark expired exports and delete their zips from storage
+async fn sweep_expired(
+    pool: &PgPool,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+) {
+    let expired = match exports::expire_exports(pool).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("[export] Expire error: {}", e);
+            return;
+        }
+    };
+
+    for export in expired {
+        if let Err(e) =
+            storage::delete_object(gcs, local_storage_path, buckets, &export.archive_path).await
+        {
+            eprintln!(
+                "[export] Failed to delete expired export {} ({}): {}",
+                export.id, export.archive_path, e
+            );
+        }
+    }
+}
+
+/// Download link for a finished export.
+/// Local storage is served by GET /me/export/:id/download; GCS gets a signed URL.
+pub async fn download_url(
+    use_local: bool,
+    buckets: &Buckets,
+    export_id: i64,
+    archive_path: &str,
+    expiry_secs: u32,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    if use_local {
+        return Ok(format!("/me/export/{}/download", export_id));
+    }
+
+    let client = cloud_storage::Client::default();
+    let object = client
+        .object()
+        .read(buckets.for_path(archive_path), archive_path)
+        .await?;
+    Ok(object.download_url(expiry_secs)?)
+}
+
+fn export_path(user_id: i64, export_id: i64) -> String {
+    format!("exports/user_{}/cleo-export-{}.zip", user_id, export_id)
+}
+
+pub fn export_poll_interval_secs() -> u64 {
+    crate::config::get().workers.export.poll_interval_secs
+}
+
+fn export_lease_secs() -> i64 {
+    crate::config::get().workers.export.lease_secs
+}
+
+fn export_ttl_hours() -> i64 {
+    crate::config::get().workers.export.ttl_hours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_path_is_per_user() {
+        assert_eq!(export_path(7, 42), "exports/user_7/cleo-export-42.zip");
+    }
+}
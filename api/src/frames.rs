@@ -7,20 +7,15 @@ use image::ImageReader;
 use image_hasher::{HashAlg, HasherConfig, ImageHash};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::env;
 use std::io::Cursor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::process::Command;
 
 use crate::models::CaptureForThumbnail;
-use crate::storage;
+use crate::storage::{self, Buckets};
 
 const MAX_ATTEMPTS: i32 = 5;
-const DEFAULT_CONCURRENCY: usize = 12;
-const DEFAULT_POLL_INTERVAL_SECS: u64 = 5;
-const DEFAULT_LEASE_SECS: i64 = 900;
-const DEFAULT_FFMPEG_THREADS: usize = 1;
 const HALF_RES_WIDTH: u32 = 960;
 const HALF_RES_HEIGHT: u32 = 540;
 const PHASH_DISTANCE_THRESHOLD: u32 = 10;
@@ -44,13 +39,23 @@ pub struct FrameManifest {
     pub frames: Vec<FrameEntry>,
 }
 
+/// Media properties recorded on the captures row during frame extraction
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MediaMetadata {
+    pub duration_secs: Option<f64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub codec: Option<String>,
+    pub file_size_bytes: i64,
+}
+
 /// Start the frame extraction worker.
 /// Poll interval, concurrency, and lease TTL are env-configurable.
 pub async fn run_frame_worker(
     pool: PgPool,
     gcs: Option<google_cloud_storage::client::Storage>,
     local_storage_path: Option<PathBuf>,
-    bucket_name: String,
+    buckets: Buckets,
 ) {
     let concurrency = frame_worker_concurrency();
     let poll_interval_secs = frame_poll_interval_secs();
@@ -64,6 +69,7 @@ pub async fn run_frame_worker(
 
     loop {
         interval.tick().await;
+        crate::services::supervisor::heartbeat("frames");
 
         let mut total_processed = 0;
         let mut total_failed = 0;
@@ -88,14 +94,14 @@ pub async fn run_frame_worker(
                     let pool = pool.clone();
                     let gcs = gcs.clone();
                     let local_path = local_storage_path.clone();
-                    let bucket = bucket_name.clone();
+                    let buckets = buckets.clone();
 
                     tasks.spawn(async move {
                         let result = process_capture(
                             &pool,
                             gcs.as_ref(),
                             local_path.as_ref(),
-                            &bucket,
+                            &buckets,
                             &capture,
                         )
                         .await;
@@ -161,7 +167,7 @@ async fn process_capture(
     pool: &PgPool,
     gcs: Option<&google_cloud_storage::client::Storage>,
     local_storage_path: Option<&PathBuf>,
-    bucket_name: &str,
+    buckets: &Buckets,
     capture: &CaptureForThumbnail,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let hasher = HasherConfig::new()
@@ -171,46 +177,54 @@ async fn process_capture(
 
     let frames_dir = get_frames_dir(&capture.gcs_path);
 
-    let (frame_count, _duration_secs) = if capture.media_type == "video" {
+    let (frame_count, metadata) = if capture.media_type == "video" {
         // Download video to temp file, then drop the bytes
         let temp_dir = std::env::temp_dir().join(format!("cleo_frames_{}", rand::random::<u64>()));
         tokio::fs::create_dir_all(&temp_dir).await?;
         let input_path = temp_dir.join("input.mp4");
 
-        {
+        let file_size_bytes = {
             let data =
-                storage::download_capture(gcs, local_storage_path, bucket_name, &capture.gcs_path)
+                storage::download_capture(gcs, local_storage_path, buckets, &capture.gcs_path)
                     .await?;
             tokio::fs::write(&input_path, &data).await?;
+            data.len() as i64
             // data dropped here — video bytes freed
-        }
+        };
+
+        let metadata = MediaMetadata {
+            file_size_bytes,
+            ..probe_video(&input_path).await
+        };
 
         let result = extract_and_upload_video_frames(
             &input_path,
             &temp_dir,
             &hasher,
             &frames_dir,
+            metadata.duration_secs,
             gcs,
             local_storage_path,
-            bucket_name,
+            buckets,
         )
         .await;
 
         cleanup_temp_dir(&temp_dir).await;
-        result?
+        (result?, metadata)
     } else {
         let data =
-            storage::download_capture(gcs, local_storage_path, bucket_name, &capture.gcs_path)
-                .await?;
-        extract_and_upload_image_frame(
+            storage::download_capture(gcs, local_storage_path, buckets, &capture.gcs_path).await?;
+        let metadata = image_metadata(&data);
+        let frame_count = extract_and_upload_image_frame(
             &data,
             &hasher,
             &frames_dir,
             gcs,
             local_storage_path,
-            bucket_name,
+            buckets,
         )
-        .await?
+        .await?;
+        (frame_count, metadata)
     };
 
     if frame_count == 0 {
@@ -223,12 +237,22 @@ async fn process_capture(
          SET frames_extracted = TRUE,
              frames_processing = FALSE,
              frames_processing_started_at = NULL,
-             frame_count = $1
+             frame_count = $1,
+             duration_secs = $4,
+             width = $5,
+             height = $6,
+             codec = $7,
+             file_size_bytes = $8
          WHERE id = $2 AND captured_at = $3",
     )
     .bind(frame_count as i32)
     .bind(capture.id)
     .bind(capture.captured_at)
+    .bind(metadata.duration_secs)
+    .bind(metadata.width)
+    .bind(metadata.height)
+    .bind(metadata.codec.as_deref())
+    .bind(metadata.file_size_bytes)
     .execute(pool)
     .await
     .map_err(|e| {
@@ -242,43 +266,102 @@ async fn process_capture(
     Ok(())
 }
 
+/// Probe a video's duration, resolution and codec with ffprobe.
+/// Probe failures leave fields empty rather than failing extraction.
+async fn probe_video(input_path: &Path) -> MediaMetadata {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error"])
+        .args(["-select_streams", "v:0"])
+        .args([
+            "-show_entries",
+            "stream=codec_name,width,height:format=duration",
+        ])
+        .args(["-of", "json"])
+        .arg(input_path.to_str().unwrap())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .await;
+
+    match output {
+        Ok(output) => parse_ffprobe_output(&output.stdout),
+        Err(e) => {
+            eprintln!("[frames] ffprobe failed: {}", e);
+            MediaMetadata::default()
+        }
+    }
+}
+
+/// Parse `ffprobe -of json` output for the first video stream
+fn parse_ffprobe_output(stdout: &[u8]) -> MediaMetadata {
+    let Ok(json) = serde_json::from_slice::<serde_json::Value>(stdout) else {
+        return MediaMetadata::default();
+    };
+    let stream = &json["streams"][0];
+    MediaMetadata {
+        // ffprobe reports duration as a string
+        duration_secs: json["format"]["duration"]
+            .as_str()
+            .and_then(|d| d.parse().ok()),
+        width: stream["width"].as_i64().map(|w| w as i32),
+        height: stream["height"].as_i64().map(|h| h as i32),
+        codec: stream["codec_name"].as_str().map(str::to_string),
+        file_size_bytes: 0,
+    }
+}
+
+/// Read dimensions and format from an image header without decoding it
+fn image_metadata(data: &[u8]) -> MediaMetadata {
+    let reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok();
+    let codec = reader.as_ref().and_then(|r| r.format()).map(|f| {
+        f.extensions_str()
+            .first()
+            .copied()
+            .unwrap_or("unknown")
+            .to_string()
+    });
+    let dimensions = reader.and_then(|r| r.into_dimensions().ok());
+    MediaMetadata {
+        duration_secs: None,
+        width: dimensions.map(|(w, _)| w as i32),
+        height: dimensions.map(|(_, h)| h as i32),
+        codec,
+        file_size_bytes: data.len() as i64,
+    }
+}
+
 /// Extract frames from a video, dedup with pHash, upload each frame immediately.
-/// Returns (frame_count, duration_secs). No frame data accumulates in memory.
+/// Returns the number of frames kept. No frame data accumulates in memory.
+#[allow(clippy::too_many_arguments)]
 async fn extract_and_upload_video_frames(
     input_path: &PathBuf,
     temp_dir: &PathBuf,
     hasher: &image_hasher::Hasher,
     frames_dir: &str,
+    duration_secs: Option<f64>,
     gcs: Option<&google_cloud_storage::client::Storage>,
     local_storage_path: Option<&PathBuf>,
-    bucket_name: &str,
-) -> Result<(usize, Option<f64>), Box<dyn std::error::Error + Send + Sync>> {
+    buckets: &Buckets,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
     let ffmpeg_threads = ffmpeg_threads().to_string();
 
-    // Get video duration
-    let probe_output = Command::new("ffprobe")
-        .args(["-v", "error"])
-        .args(["-show_entries", "format=duration"])
-        .args(["-of", "default=noprint_wrappers=1:nokey=1"])
-        .arg(input_path.to_str().unwrap())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .output()
-        .await?;
-
-    let duration_secs = String::from_utf8_lossy(&probe_output.stdout)
-        .trim()
-        .parse::<f64>()
-        .ok();
-
-    // Extract frames at 1fps, already scaled to half-res by ffmpeg
-    let vf = format!("fps=1,scale={}:{}", HALF_RES_WIDTH, HALF_RES_HEIGHT);
+    // Pick real source frames at least 1s apart (rather than resampling to a
+    // constant 1fps, which drifts on VFR recordings), scale to half-res, and
+    // log each kept frame's PTS via showinfo so the manifest has true timestamps
+    let vf = format!(
+        "select='isnan(prev_selected_t)+gte(t-prev_selected_t,1)',scale={}:{},showinfo",
+        HALF_RES_WIDTH, HALF_RES_HEIGHT
+    );
     let output = Command::new("ffmpeg")
-        .args(["-hide_banner", "-loglevel", "error", "-nostdin"])
+        // showinfo logs at info level
+        .args(["-hide_banner", "-loglevel", "info", "-nostdin"])
         .args(["-threads", &ffmpeg_threads])
         .args(["-i", input_path.to_str().unwrap()])
         .args(["-an", "-sn"])
         .args(["-vf", &vf])
+        .args(["-fps_mode", "vfr"])
         .args(["-q:v", "4"])
         .args(["-y", temp_dir.join("frame_%04d.jpg").to_str().unwrap()])
         .stdout(Stdio::null())
@@ -286,10 +369,15 @@ async fn extract_and_upload_video_frames(
         .output()
         .await?;
 
+    let stderr = String::from_utf8_lossy(&output.stderr);
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("ffmpeg frame extraction failed: {}", stderr).into());
+        let errors: Vec<&str> = stderr
+            .lines()
+            .filter(|l| !l.contains("Parsed_showinfo"))
+            .collect();
+        return Err(format!("ffmpeg frame extraction failed: {}", errors.join("\n")).into());
     }
+    let frame_timestamps = parse_showinfo_pts(&stderr);
 
     // Collect frame file paths
     let mut frame_files: Vec<PathBuf> = Vec::new();
@@ -335,19 +423,13 @@ async fn extract_and_upload_video_frames(
         }
 
         last_hash = Some(current_hash.clone());
-        let timestamp_secs = i as f64;
+        // Frame files are numbered in output order, matching showinfo's lines
+        let timestamp_secs = frame_timestamps.get(i).copied().unwrap_or(i as f64);
 
         // Upload immediately, then frame_data is dropped
         let filename = format!("frame_{}.jpg", kept);
         let frame_path = format!("{}/{}", frames_dir, filename);
-        storage::upload_data(
-            gcs,
-            local_storage_path,
-            bucket_name,
-            &frame_path,
-            &frame_data,
-        )
-        .await?;
+        storage::upload_data(gcs, local_storage_path, buckets, &frame_path, &frame_data).await?;
 
         manifest_frames.push(FrameEntry {
             index: kept,
@@ -371,13 +453,25 @@ async fn extract_and_upload_video_frames(
     storage::upload_data(
         gcs,
         local_storage_path,
-        bucket_name,
+        buckets,
         &manifest_path,
         manifest_json.as_bytes(),
     )
     .await?;
 
-    Ok((kept, duration_secs))
+    Ok(kept)
+}
+
+/// Presentation timestamps (seconds) of each frame logged by ffmpeg's showinfo filter
+fn parse_showinfo_pts(stderr: &str) -> Vec<f64> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("Parsed_showinfo"))
+        .filter_map(|line| {
+            let rest = &line[line.find("pts_time:")? + "pts_time:".len()..];
+            rest.split_whitespace().next()?.parse().ok()
+        })
+        .collect()
 }
 
 /// Process a screenshot: resize to half-res, hash, upload immediately.
@@ -387,8 +481,8 @@ async fn extract_and_upload_image_frame(
     frames_dir: &str,
     gcs: Option<&google_cloud_storage::client::Storage>,
     local_storage_path: Option<&PathBuf>,
-    bucket_name: &str,
-) -> Result<(usize, Option<f64>), Box<dyn std::error::Error + Send + Sync>> {
+    buckets: &Buckets,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
     let img = ImageReader::new(Cursor::new(data))
         .with_guessed_format()?
         .decode()?;
@@ -409,14 +503,7 @@ async fn extract_and_upload_image_frame(
     let frame_data = output_buf.into_inner();
     let filename = "frame_0.jpg".to_string();
     let frame_path = format!("{}/{}", frames_dir, filename);
-    storage::upload_data(
-        gcs,
-        local_storage_path,
-        bucket_name,
-        &frame_path,
-        &frame_data,
-    )
-    .await?;
+    storage::upload_data(gcs, local_storage_path, buckets, &frame_path, &frame_data).await?;
 
     let manifest = FrameManifest {
         capture_id: 0,
@@ -435,13 +522,13 @@ async fn extract_and_upload_image_frame(
     storage::upload_data(
         gcs,
         local_storage_path,
-        bucket_name,
+        buckets,
         &manifest_path,
         manifest_json.as_bytes(),
     )
     .await?;
 
-    Ok((1, None))
+    Ok(1)
 }
 
 /// Convert gcs_path to frames directory path
@@ -475,6 +562,7 @@ async fn claim_frame_captures(
             SELECT id, captured_at
             FROM captures
             WHERE frames_extracted = FALSE
+              AND excluded_from_agent = FALSE
               AND frame_attempts < $1
               AND (
                   frames_processing = FALSE
@@ -505,35 +593,19 @@ async fn claim_frame_captures(
 }
 
 fn frame_worker_concurrency() -> usize {
-    env::var("FRAME_WORKER_CONCURRENCY")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .filter(|v| *v > 0)
-        .unwrap_or(DEFAULT_CONCURRENCY)
+    crate::config::get().workers.frames.concurrency
 }
 
-fn frame_poll_interval_secs() -> u64 {
-    env::var("FRAME_POLL_INTERVAL_SECS")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .filter(|v| *v > 0)
-        .unwrap_or(DEFAULT_POLL_INTERVAL_SECS)
+pub fn frame_poll_interval_secs() -> u64 {
+    crate::config::get().workers.frames.poll_interval_secs
 }
 
 fn frame_lease_secs() -> i64 {
-    env::var("FRAME_LEASE_SECS")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .filter(|v| *v > 0)
-        .unwrap_or(DEFAULT_LEASE_SECS)
+    crate::config::get().workers.frames.lease_secs
 }
 
 fn ffmpeg_threads() -> usize {
-    env::var("FFMPEG_THREADS")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .filter(|v| *v > 0)
-        .unwrap_or(DEFAULT_FFMPEG_THREADS)
+    crate::config::get().workers.ffmpeg_threads
 }
 
 #[cfg(test)]
@@ -551,4 +623,32 @@ mod tests {
             "frames/user_1/2025-01-01/789"
         );
     }
+
+    #[test]
+    fn test_parse_ffprobe_output() {
+        let stdout = br#"{"streams":[{"codec_name":"h264","width":2880,"height":1800}],
+                          "format":{"duration":"12.480000"}}"#;
+        assert_eq!(
+            parse_ffprobe_output(stdout),
+            MediaMetadata {
+                duration_secs: Some(12.48),
+                width: Some(2880),
+                height: Some(1800),
+                codec: Some("h264".to_string()),
+                file_size_bytes: 0,
+            }
+        );
+        assert_eq!(parse_ffprobe_output(b"garbage"), MediaMetadata::default());
+    }
+
+    #[test]
+    fn test_parse_showinfo_pts() {
+        let stderr = "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'in.mp4':\n\
+            [Parsed_showinfo_2 @ 0x600] config in time_base: 1/15360, frame_rate: 60/1\n\
+            [Parsed_showinfo_2 @ 0x600] n:   0 pts:      0 pts_time:0       duration:256\n\
+            [Parsed_showinfo_2 @ 0x600] n:   1 pts:  16128 pts_time:1.05    duration:256\n\
+            frame=    2 fps=0.0 q=4.0 size=N/A time=00:00:01.05\n\
+            [Parsed_showinfo_2 @ 0x600] n:   2 pts:  33280 pts_time:2.16667 duration:256\n";
+        assert_eq!(parse_showinfo_pts(stderr), vec![0.0, 1.05, 2.16667]);
+    }
 }
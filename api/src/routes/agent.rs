@@ -1,79 +1,452 @@
+//! Agent run endpoints (/agent/*) and admin impersonation tooling (/admin/*)
+//!
+//! Admins run the agent for another user in two steps: POST /admin/impersonation
+//! issues a short-lived token for the target (naming the admin and a reason),
+//! then POST /admin/agent/run with that token in X-Impersonation-Token starts
+//! the run. Both steps land in the target's audit log, and runs started this way
+//! are flagged `admin_triggered` in run and status responses.
+//!
+//! POST /agent/run with `{"dry_run": true}` waits for the run and returns the
+//! collateral it would have saved instead of saving it. The run is still logged
+//! in `agent_runs`, flagged `dry_run`. `window_start` and `window_end` re-run
+//! the agent over a past window (flagged `replay`) without moving the cursor
+//! the scheduler picks up from. `language` writes the run's drafts in another
+//! language than the user's (GET/PUT /me/language).
+//!
+//! The daemon calls POST /agent/highlight when a recording looks like a
+//! highlight; a focused pass runs over that recording as soon as it's uploaded
+//! and its frames are extracted (flagged `highlight`).
+
 use axum::{
     Json, Router,
     extract::State,
-    http::StatusCode,
+    http::HeaderMap,
     routing::{get, post},
 };
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use super::auth::AuthUser;
+use super::auth::{AdminUser, AuthUser};
 use crate::AppState;
 use crate::agent;
+use crate::domain::users;
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::session::SessionError;
+use crate::services::{impersonation, language};
+
+/// Longest accepted impersonation reason
+const MAX_REASON_LEN: usize = 500;
+/// Longest window a replay may cover, in hours
+const MAX_REPLAY_WINDOW_HOURS: i64 = 24;
+/// Longest recording accepted as a highlight, in hours
+const MAX_HIGHLIGHT_HOURS: i64 = 2;
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/agent/run", post(trigger_run))
         .route("/agent/status", get(run_status))
+        .route("/agent/highlight", post(trigger_highlight))
+        .route("/admin/impersonation", post(start_impersonation))
+        .route("/admin/agent/run", post(admin_trigger_run))
+}
+
+#[derive(Deserialize, Default)]
+struct RunRequest {
+    /// Run the full pipeline and return the collateral without saving it
+    #[serde(default)]
+    dry_run: bool,
+    /// Re-run over this past window (both or neither)
+    window_start: Option<DateTime<Utc>>,
+    window_end: Option<DateTime<Utc>>,
+    /// Write this run's drafts in another language than the user's default
+    language: Option<String>,
+}
+
+impl RunRequest {
+    /// The replay window, if one was asked for
+    fn replay_window(&self) -> Result<Option<agent::TimeWindow>, ApiError> {
+        let (start, end) = match (self.window_start, self.window_end) {
+            (None, None) => return Ok(None),
+            (Some(start), Some(end)) => (start, end),
+            _ => {
+                return Err(ApiError::BadRequest(
+                    "window_start and window_end must be given together".to_string(),
+                ));
+            }
+        };
+        if start >= end {
+            return Err(ApiError::BadRequest(
+                "window_start must be before window_end".to_string(),
+            ));
+        }
+        if end > Utc::now() {
+            return Err(ApiError::BadRequest(
+                "window_end can't be in the future".to_string(),
+            ));
+        }
+        if end - start > chrono::Duration::hours(MAX_REPLAY_WINDOW_HOURS) {
+            return Err(ApiError::BadRequest(format!(
+                "a replay window can cover at most {} hours",
+                MAX_REPLAY_WINDOW_HOURS
+            )));
+        }
+        Ok(Some((start, end)))
+    }
 }
 
 #[derive(Serialize)]
 struct RunResponse {
     status: &'static str,
     run_id: Option<i64>,
+    user_id: i64,
+    /// True when an admin started the run on the user's behalf
+    admin_triggered: bool,
+    dry_run: bool,
+    /// True when the run covers a requested past window
+    replay: bool,
+    /// Proposed collateral (dry runs only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tweets: Option<Vec<agent::TweetCollateral>>,
+}
+
+/// Start a collateral run in the background
+fn spawn_run(state: &AppState, user_id: i64, options: agent::RunOptions) {
+    let db = state.worker_db.clone();
+    let gcs = state.gcs.clone();
+    let gemini = state.gemini.clone();
+    let local_storage_path = state.local_storage_path.clone();
+    let buckets = state.buckets.clone();
+
+    tokio::spawn(async move {
+        match agent::run_collateral_job(
+            db,
+            gcs,
+            gemini,
+            user_id,
+            local_storage_path,
+            buckets,
+            options,
+        )
+        .await
+        {
+            Ok(tweets) => {
+                println!(
+                    "[agent/run] User {} - manual run generated {} tweets",
+                    user_id,
+                    tweets.len()
+                );
+            }
+            Err(e) => {
+                eprintln!("[agent/run] User {} - manual run error: {}", user_id, e);
+            }
+        }
+    });
 }
 
 /// POST /agent/run - trigger an immediate agent run for the current user
 async fn trigger_run(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
-) -> Result<Json<RunResponse>, StatusCode> {
-    let db = state.db.clone();
+    req: Option<Json<RunRequest>>,
+) -> Result<Json<RunResponse>, ApiError> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let window = req.replay_window()?;
+    let language = req.language.as_deref().map(language::checked).transpose()?;
+    let options = agent::RunOptions {
+        dry_run: req.dry_run,
+        window,
+        language,
+        ..Default::default()
+    };
+
+    if !req.dry_run {
+        spawn_run(&state, user_id, options);
+        return Ok(Json(RunResponse {
+            status: "started",
+            run_id: None,
+            user_id,
+            admin_triggered: false,
+            dry_run: false,
+            replay: window.is_some(),
+            tweets: None,
+        }));
+    }
+
+    // A dry run waits for the result, so say so up front rather than
+    // returning an empty run when the slot is taken
+    if active_run(&state, user_id).await?.is_some() {
+        return Err(ApiError::Conflict(
+            "An agent run is already in progress".to_string(),
+        ));
+    }
+
+    let tweets = agent::run_collateral_job(
+        state.worker_db.clone(),
+        state.gcs.clone(),
+        state.gemini.clone(),
+        user_id,
+        state.local_storage_path.clone(),
+        state.buckets.clone(),
+        options,
+    )
+    .await
+    .map_err(|e| {
+        eprintln!("[agent/run] User {} - dry run error: {}", user_id, e);
+        ApiError::Upstream(format!("Dry run failed: {}", e))
+    })?;
+
+    println!(
+        "[agent/run] User {} - dry run proposed {} tweets",
+        user_id,
+        tweets.len()
+    );
+
+    Ok(Json(RunResponse {
+        status: "completed",
+        run_id: None,
+        user_id,
+        admin_triggered: false,
+        dry_run: true,
+        replay: window.is_some(),
+        tweets: Some(tweets),
+    }))
+}
+
+#[derive(Deserialize)]
+struct HighlightRequest {
+    /// When the flagged recording started and stopped
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+}
+
+/// POST /agent/highlight - run a focused pass over a recording the daemon flagged
+async fn trigger_highlight(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<HighlightRequest>,
+) -> Result<Json<RunResponse>, ApiError> {
+    let now = Utc::now();
+    if req.started_at >= req.ended_at {
+        return Err(ApiError::BadRequest(
+            "started_at must be before ended_at".to_string(),
+        ));
+    }
+    // Allow a little clock skew between the daemon and the server
+    if req.ended_at > now + chrono::Duration::minutes(1) {
+        return Err(ApiError::BadRequest(
+            "ended_at can't be in the future".to_string(),
+        ));
+    }
+    if req.ended_at < now - chrono::Duration::hours(1) {
+        return Err(ApiError::BadRequest(
+            "highlight is too old; the scheduler will pick it up".to_string(),
+        ));
+    }
+    if req.ended_at - req.started_at > chrono::Duration::hours(MAX_HIGHLIGHT_HOURS) {
+        return Err(ApiError::BadRequest(format!(
+            "a highlight can cover at most {} hours",
+            MAX_HIGHLIGHT_HOURS
+        )));
+    }
+
+    let db = state.worker_db.clone();
     let gcs = state.gcs.clone();
     let gemini = state.gemini.clone();
     let local_storage_path = state.local_storage_path.clone();
+    let buckets = state.buckets.clone();
+    let window = (req.started_at, req.ended_at);
 
     tokio::spawn(async move {
-        match agent::run_collateral_job(db, gcs, gemini, user_id, local_storage_path).await {
+        match agent::run_highlight_job(
+            db,
+            gcs,
+            gemini,
+            user_id,
+            local_storage_path,
+            buckets,
+            window,
+        )
+        .await
+        {
             Ok(tweets) => {
                 println!(
-                    "[agent/run] User {} - manual run generated {} tweets",
+                    "[agent/highlight] User {} - highlight generated {} tweets",
                     user_id,
                     tweets.len()
                 );
             }
             Err(e) => {
-                eprintln!("[agent/run] User {} - manual run error: {}", user_id, e);
+                eprintln!(
+                    "[agent/highlight] User {} - highlight error: {}",
+                    user_id, e
+                );
             }
         }
     });
 
     Ok(Json(RunResponse {
-        status: "started",
+        status: "queued",
         run_id: None,
+        user_id,
+        admin_triggered: false,
+        dry_run: false,
+        replay: false,
+        tweets: None,
     }))
 }
 
 #[derive(Serialize)]
 struct StatusResponse {
     running: bool,
+    /// True when the active run was started by an admin
+    admin_triggered: bool,
 }
 
-/// GET /agent/status - check if an agent run is currently active for this user
-async fn run_status(
-    State(state): State<Arc<AppState>>,
-    AuthUser(user_id): AuthUser,
-) -> Result<Json<StatusResponse>, StatusCode> {
-    let running = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM agent_runs WHERE user_id = $1 AND status = 'running' AND started_at > NOW() - INTERVAL '30 minutes')"
+/// The user's active run, if any, as its `triggered_by_admin` value
+async fn active_run(state: &AppState, user_id: i64) -> Result<Option<Option<i64>>, ApiError> {
+    sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT triggered_by_admin FROM agent_runs WHERE user_id = $1 AND status = 'running' AND started_at > NOW() - INTERVAL '30 minutes' LIMIT 1"
     )
     .bind(user_id)
-    .fetch_one(&state.db)
+    .fetch_optional(&state.db)
     .await
     .map_err(|e| {
         eprintln!("[agent/status] DB error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
+        ApiError::Internal
+    })
+}
+
+/// GET /agent/status - check if an agent run is currently active for this user
+async fn run_status(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<StatusResponse>, ApiError> {
+    let active = active_run(&state, user_id).await?;
+
+    Ok(Json(StatusResponse {
+        running: active.is_some(),
+        admin_triggered: active.flatten().is_some(),
+    }))
+}
+
+#[derive(Deserialize)]
+struct ImpersonationRequest {
+    user_id: i64,
+    /// Why the admin is acting as this user (shown in the user's audit log)
+    reason: String,
+}
+
+#[derive(Serialize)]
+struct ImpersonationResponse {
+    token: String,
+    user_id: i64,
+    expires_at: DateTime<Utc>,
+}
+
+/// POST /admin/impersonation - Issue a short-lived token to act as a user
+async fn start_impersonation(
+    State(state): State<Arc<AppState>>,
+    AdminUser(admin_user_id): AdminUser,
+    ctx: RequestContext,
+    Json(req): Json<ImpersonationRequest>,
+) -> Result<Json<ImpersonationResponse>, ApiError> {
+    let reason = req.reason.trim();
+    if reason.is_empty() || reason.chars().count() > MAX_REASON_LEN {
+        return Err(ApiError::BadRequest(format!(
+            "reason is required (at most {} characters)",
+            MAX_REASON_LEN
+        )));
+    }
+
+    users::get_user_by_id(&state.db, req.user_id)
+        .await
+        .log_500("Get user by ID error")?
+        .ok_or(ApiError::NotFound("User"))?;
+
+    let (token, expires_at) =
+        impersonation::create_token(req.user_id, admin_user_id, &state.jwt_secret)
+            .log_500("Create impersonation token error")?;
+
+    audit::record(
+        &state.db,
+        &ctx.into_admin(),
+        req.user_id,
+        Action::ImpersonationStarted,
+        None,
+        Some(serde_json::json!({
+            "admin_user_id": admin_user_id,
+            "reason": reason,
+            "expires_at": expires_at,
+        })),
+    )
+    .await;
+
+    println!(
+        "[admin] User {} started impersonating user {}: {}",
+        admin_user_id, req.user_id, reason
+    );
+
+    Ok(Json(ImpersonationResponse {
+        token,
+        user_id: req.user_id,
+        expires_at,
+    }))
+}
+
+/// POST /admin/agent/run - Run the agent as the user named by X-Impersonation-Token
+async fn admin_trigger_run(
+    State(state): State<Arc<AppState>>,
+    AdminUser(admin_user_id): AdminUser,
+    ctx: RequestContext,
+    headers: HeaderMap,
+) -> Result<Json<RunResponse>, ApiError> {
+    let token = headers
+        .get(impersonation::HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(ApiError::Forbidden("Impersonation token required"))?;
+
+    let grant = impersonation::validate_token(token, &state.jwt_secret).map_err(|e| match e {
+        SessionError::Expired => ApiError::Forbidden("Impersonation token expired"),
+        _ => ApiError::Forbidden("Invalid impersonation token"),
     })?;
+    // Tokens are bound to the admin who requested them
+    if grant.admin_user_id != admin_user_id {
+        return Err(ApiError::Forbidden("Invalid impersonation token"));
+    }
+
+    spawn_run(
+        &state,
+        grant.user_id,
+        agent::RunOptions {
+            triggered_by_admin: Some(admin_user_id),
+            ..Default::default()
+        },
+    );
+
+    audit::record(
+        &state.db,
+        &ctx.into_admin(),
+        grant.user_id,
+        Action::AdminAgentRun,
+        None,
+        Some(serde_json::json!({ "admin_user_id": admin_user_id })),
+    )
+    .await;
 
-    Ok(Json(StatusResponse { running }))
+    println!(
+        "[admin] User {} triggered an agent run as user {}",
+        admin_user_id, grant.user_id
+    );
+
+    Ok(Json(RunResponse {
+        status: "started",
+        run_id: None,
+        user_id: grant.user_id,
+        admin_triggered: true,
+        dry_run: false,
+        replay: false,
+        tweets: None,
+    }))
 }
@@ -0,0 +1,108 @@
+//! Admin archive endpoints (/admin/archives)
+//!
+//! Queue a past month for archival and check progress. The archive worker does
+//! the export and drop; these endpoints only manage the job queue.
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use chrono::{DateTime, Datelike, Months, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AdminUser;
+use crate::AppState;
+use crate::archive::{archive_min_age_months, month_label, parse_month};
+use crate::domain::archives;
+use crate::services::error::LogErr;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/admin/archives", get(list_archives).post(request_archive))
+}
+
+/// Archive job API response DTO
+#[derive(Debug, Serialize)]
+pub struct ArchiveResponse {
+    pub id: i64,
+    pub month: String,
+    pub status: String,
+    pub archive_path: Option<String>,
+    pub capture_count: i64,
+    pub activity_count: i64,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<archives::Archive> for ArchiveResponse {
+    fn from(a: archives::Archive) -> Self {
+        Self {
+            id: a.id,
+            month: month_label(a.month),
+            status: a.status,
+            archive_path: a.archive_path,
+            capture_count: a.capture_count,
+            activity_count: a.activity_count,
+            attempts: a.attempts,
+            error: a.error,
+            requested_at: a.requested_at,
+            completed_at: a.completed_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RequestArchiveRequest {
+    /// "YYYY-MM"
+    month: String,
+}
+
+/// POST /admin/archives - Queue a month for export and removal
+async fn request_archive(
+    State(state): State<Arc<AppState>>,
+    AdminUser(user_id): AdminUser,
+    Json(payload): Json<RequestArchiveRequest>,
+) -> Result<(StatusCode, Json<ArchiveResponse>), StatusCode> {
+    let month = parse_month(&payload.month).ok_or(StatusCode::BAD_REQUEST)?;
+
+    // Only months that ended at least ARCHIVE_MIN_AGE_MONTHS ago
+    let current_month = Utc::now()
+        .date_naive()
+        .with_day(1)
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let cutoff = current_month
+        .checked_sub_months(Months::new(archive_min_age_months()))
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    if month >= cutoff {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    let archive = archives::request_archive(&state.db, month, user_id)
+        .await
+        .log_500("Request archive error")?;
+
+    println!(
+        "[archive] User {} requested archive of {} (status {})",
+        user_id, payload.month, archive.status
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(ArchiveResponse::from(archive))))
+}
+
+#[derive(Serialize)]
+struct ListArchivesResponse {
+    archives: Vec<ArchiveResponse>,
+}
+
+/// GET /admin/archives - List archive jobs, newest month first
+async fn list_archives(
+    State(state): State<Arc<AppState>>,
+    AdminUser(_user_id): AdminUser,
+) -> Result<Json<ListArchivesResponse>, StatusCode> {
+    let rows = archives::list_archives(&state.db)
+        .await
+        .log_500("List archives error")?;
+
+    Ok(Json(ListArchivesResponse {
+        archives: rows.into_iter().map(ArchiveResponse::from).collect(),
+    }))
+}
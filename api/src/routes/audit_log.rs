@@ -0,0 +1,87 @@
+//! Account audit trail (/me/audit-log)
+//!
+//! Lists the security-relevant actions recorded by services::audit: logins, API
+//! token generation, publishes, deletions, and settings changes.
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use crate::AppState;
+use crate::constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::domain::audit_log;
+use crate::services::error::LogErr;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/me/audit-log", get(list_audit_log))
+}
+
+/// Audit log entry API response DTO
+#[derive(Debug, Serialize)]
+pub struct AuditEntryResponse {
+    pub id: i64,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub detail: Option<serde_json::Value>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<audit_log::AuditEntry> for AuditEntryResponse {
+    fn from(e: audit_log::AuditEntry) -> Self {
+        Self {
+            id: e.id,
+            actor: e.actor,
+            action: e.action,
+            target: e.target,
+            detail: e.detail,
+            ip: e.ip,
+            user_agent: e.user_agent,
+            created_at: e.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ListAuditLogQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ListAuditLogResponse {
+    entries: Vec<AuditEntryResponse>,
+    total: i64,
+    has_more: bool,
+}
+
+/// GET /me/audit-log - Browse actions taken on the account, newest first
+async fn list_audit_log(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Query(query): Query<ListAuditLogQuery>,
+) -> Result<Json<ListAuditLogResponse>, StatusCode> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0);
+
+    let (rows, total) = audit_log::list_entries_paginated(&state.db, user_id, limit, offset)
+        .await
+        .log_500("List audit log error")?;
+
+    let has_more = offset + (rows.len() as i64) < total;
+
+    Ok(Json(ListAuditLogResponse {
+        entries: rows.into_iter().map(AuditEntryResponse::from).collect(),
+        total,
+        has_more,
+    }))
+}
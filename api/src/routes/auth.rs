@@ -1,22 +1,47 @@
 //! Authentication and session management endpoints
+//!
+//! Besides X (see twitter_oauth), users can sign in with GitHub or Google via
+//! /auth/login/{provider}, or with a one-time link emailed by
+//! POST /auth/email/link. Those accounts start without X; it's linked later
+//! from /me/integrations to publish.
 
 use axum::{
     Json, Router,
-    extract::{FromRequestParts, State},
-    http::{StatusCode, header::SET_COOKIE, request::Parts},
+    extract::{FromRequestParts, Path, State},
+    http::{
+        StatusCode,
+        header::{AUTHORIZATION, SET_COOKIE},
+        request::Parts,
+    },
     response::{IntoResponse, Response},
     routing::{get, post},
 };
 use axum_extra::extract::CookieJar;
-use serde::Serialize;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tower_governor::{
     GovernorLayer, governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor,
 };
 
+use super::captures::get_daemon_identity;
+use super::twitter_oauth::AuthUrlResponse;
 use crate::AppState;
-use crate::domain::users;
-use crate::services::{cookies, session, twitter};
+use crate::config;
+use crate::domain::{email_login_tokens, login_identities, users};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::crypto::hash_token;
+use crate::services::email::{self, EmailMessage};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::html::escape_html;
+use crate::services::login_providers::LoginProvider;
+use crate::services::rate_limit::EMAIL_LINK_LIMITER;
+use crate::services::{cookies, csrf, session, twitter};
+
+/// `login_identities.provider` for email sign-in; the address is the provider user id
+const EMAIL_PROVIDER: &str = "email";
 
 pub fn routes() -> Router<Arc<AppState>> {
     // Rate limit: 10 requests per minute for auth endpoints to prevent brute force
@@ -36,6 +61,11 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/auth/refresh", post(refresh_session))
         .route("/auth/logout", post(logout))
         .route("/auth/me", get(get_me))
+        .route("/auth/providers", get(list_providers))
+        .route("/auth/login/{provider}", get(provider_login))
+        .route("/auth/login/{provider}/token", post(provider_login_token))
+        .route("/auth/email/link", post(request_email_link))
+        .route("/auth/email/verify", post(verify_email_link))
         .layer(rate_limit_layer)
 }
 
@@ -47,7 +77,7 @@ pub fn routes() -> Router<Arc<AppState>> {
 pub struct AuthUser(pub i64);
 
 impl FromRequestParts<Arc<AppState>> for AuthUser {
-    type Rejection = StatusCode;
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -58,53 +88,148 @@ impl FromRequestParts<Arc<AppState>> for AuthUser {
             .await
             .map_err(|e| {
                 eprintln!("Cookie extraction error: {:?}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
+                ApiError::Internal
             })?;
 
         // Get access_token cookie
         let access_token = jar
             .get("access_token")
             .map(|c| c.value())
-            .ok_or(StatusCode::UNAUTHORIZED)?;
+            .ok_or(ApiError::Unauthorized)?;
 
         // Validate JWT
-        let user_id =
-            session::validate_access_token(access_token, &state.jwt_secret).map_err(|e| {
-                eprintln!("JWT validation failed: {:?}", e);
-                StatusCode::UNAUTHORIZED
-            })?;
+        let user_id = session::validate_access_token(access_token, &state.jwt_secret).map_err(
+            |e| match e {
+                session::SessionError::Expired => ApiError::TokenExpired,
+                e => {
+                    eprintln!("JWT validation failed: {:?}", e);
+                    ApiError::Unauthorized
+                }
+            },
+        )?;
 
         Ok(AuthUser(user_id))
     }
 }
 
+/// Extractor for /admin endpoints: an authenticated user listed in ADMIN_USERS
+pub struct AdminUser(pub i64);
+
+impl FromRequestParts<Arc<AppState>> for AdminUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let AuthUser(user_id) = AuthUser::from_request_parts(parts, state).await?;
+
+        if state.admin_users.is_empty() {
+            return Err(ApiError::Forbidden("Admin access required"));
+        }
+
+        let user = users::get_user_by_id(&state.db, user_id)
+            .await
+            .map_err(|e| {
+                eprintln!("Get user by ID error: {}", e);
+                ApiError::Internal
+            })?
+            .ok_or(ApiError::Unauthorized)?;
+
+        // Admins are named by X handle; a GitHub or Google handle never counts
+        let is_admin = user
+            .twitter_username
+            .is_some_and(|u| state.admin_users.contains(&u.to_lowercase()));
+        if !is_admin {
+            return Err(ApiError::Forbidden("Admin access required"));
+        }
+
+        Ok(AdminUser(user_id))
+    }
+}
+
+/// Extractor for endpoints the daemon may call too: the owner of the API token
+/// when the request carries one (Bearer), otherwise the session cookie's user
+pub struct SessionOrDaemonUser(pub i64);
+
+impl FromRequestParts<Arc<AppState>> for SessionOrDaemonUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let bearer = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("Bearer "));
+        if bearer {
+            let identity = get_daemon_identity(&state.db, &parts.headers).await?;
+            return Ok(SessionOrDaemonUser(identity.user_id));
+        }
+
+        let AuthUser(user_id) = AuthUser::from_request_parts(parts, state).await?;
+        Ok(SessionOrDaemonUser(user_id))
+    }
+}
+
 // ============================================================================
 // Session endpoints
 // ============================================================================
 
+/// Start a browser session for the user on `response`
+pub(super) async fn attach_session(
+    state: &AppState,
+    user_id: i64,
+    response: &mut Response,
+) -> Result<(), StatusCode> {
+    // Create session tokens
+    let access_token = session::create_access_token(user_id, &state.jwt_secret).map_err(|e| {
+        eprintln!("Failed to create access token: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let refresh_token = session::create_refresh_token(user_id, &state.db)
+        .await
+        .map_err(|e| {
+            eprintln!("Failed to create refresh token: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    response
+        .headers_mut()
+        .append(SET_COOKIE, cookies::build_access_cookie(&access_token)?);
+    response
+        .headers_mut()
+        .append(SET_COOKIE, cookies::build_refresh_cookie(&refresh_token)?);
+    csrf::attach_token(response.headers_mut())?;
+    Ok(())
+}
+
 /// POST /auth/refresh - Refresh the access token using the refresh token cookie
 /// Implements refresh token rotation: old token is invalidated, new one is issued
 async fn refresh_session(
     State(state): State<Arc<AppState>>,
     jar: CookieJar,
-) -> Result<Response, StatusCode> {
+) -> Result<Response, ApiError> {
     // Get refresh_token cookie
     let old_refresh_token = jar
         .get("refresh_token")
         .map(|c| c.value().to_string())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or(ApiError::Unauthorized)?;
 
     // Rotate refresh token: validate old, delete it, create new one
     // This is atomic - if two requests try to use the same token, only one succeeds
     // (silent - invalid/expired tokens are expected for expired sessions)
     let (user_id, new_refresh_token) = session::rotate_refresh_token(&old_refresh_token, &state.db)
         .await
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        .map_err(|_| ApiError::Unauthorized)?;
 
     // Generate new access token
     let access_token = session::create_access_token(user_id, &state.jwt_secret).map_err(|e| {
         eprintln!("Failed to create access token: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
+        ApiError::Internal
     })?;
 
     // Build response with cookies (204 No Content - only sets cookies)
@@ -116,6 +241,7 @@ async fn refresh_session(
         SET_COOKIE,
         cookies::build_refresh_cookie(&new_refresh_token)?,
     );
+    csrf::attach_token(response.headers_mut())?;
 
     Ok(response)
 }
@@ -138,6 +264,9 @@ async fn logout(State(state): State<Arc<AppState>>, jar: CookieJar) -> Response
     response
         .headers_mut()
         .append(SET_COOKIE, cookies::build_clear_refresh_cookie());
+    response
+        .headers_mut()
+        .append(SET_COOKIE, cookies::build_clear_csrf_cookie());
 
     response
 }
@@ -152,24 +281,359 @@ struct MeResponse {
 async fn get_me(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
-) -> Result<Json<MeResponse>, StatusCode> {
+) -> Result<Json<MeResponse>, ApiError> {
     // Get user from database
     let user = users::get_user_by_id(&state.db, user_id)
         .await
         .map_err(|e| {
             eprintln!("Get user by ID error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            ApiError::Internal
         })?;
 
     // Return 401 if user not found - a valid JWT for a deleted user is still unauthorized
-    let user = user.ok_or(StatusCode::UNAUTHORIZED)?;
+    let user = user.ok_or(ApiError::Unauthorized)?;
 
     Ok(Json(MeResponse {
         id: user_id,
-        username: user.twitter_username,
+        username: user.username,
     }))
 }
 
+// ============================================================================
+// GitHub/Google login
+// ============================================================================
+
+#[derive(Serialize)]
+struct ProvidersResponse {
+    /// "twitter" first, then any configured GitHub/Google login, then "email"
+    providers: Vec<&'static str>,
+}
+
+/// GET /auth/providers - Login options to offer
+async fn list_providers(State(state): State<Arc<AppState>>) -> Json<ProvidersResponse> {
+    let mut providers = vec!["twitter"];
+    providers.extend(state.login.enabled().into_iter().map(LoginProvider::as_str));
+    if state.email.is_some() {
+        providers.push(EMAIL_PROVIDER);
+    }
+    Json(ProvidersResponse { providers })
+}
+
+/// GET /auth/login/:provider - Start a GitHub or Google login, returns URL to redirect to
+async fn provider_login(
+    State(state): State<Arc<AppState>>,
+    Path(provider): Path<LoginProvider>,
+) -> Result<Json<AuthUrlResponse>, ApiError> {
+    let oauth_state = twitter::generate_oauth_state();
+    let (code_verifier, code_challenge) = twitter::generate_pkce();
+    let url = state
+        .login
+        .authorize_url(provider, &oauth_state, &code_challenge)
+        .ok_or(ApiError::NotFound("Login provider"))?;
+
+    login_identities::save_login_state(&state.db, &oauth_state, &code_verifier, provider.as_str())
+        .await
+        .log_500("Save login state error")?;
+
+    Ok(Json(AuthUrlResponse { url }))
+}
+
+#[derive(Deserialize)]
+struct ProviderTokenRequest {
+    code: String,
+    state: String,
+}
+
+#[derive(Serialize)]
+struct ProviderLoginResponse {
+    username: String,
+}
+
+/// POST /auth/login/:provider/token - Exchange a GitHub or Google code for a session
+/// Signs in the account owning that identity, creating one on first login.
+async fn provider_login_token(
+    State(state): State<Arc<AppState>>,
+    ctx: RequestContext,
+    Path(provider): Path<LoginProvider>,
+    Json(req): Json<ProviderTokenRequest>,
+) -> Result<Response, ApiError> {
+    let code_verifier =
+        login_identities::take_login_state(&state.db, &req.state, provider.as_str())
+            .await
+            .log_500("Take login state error")?
+            .ok_or_else(|| ApiError::BadRequest("Login expired or already used".to_string()))?;
+
+    let identity = state
+        .login
+        .fetch_identity(provider, &req.code, &code_verifier)
+        .await
+        .map_err(|e| {
+            eprintln!("{} login error: {}", provider.as_str(), e);
+            ApiError::Internal
+        })?;
+
+    // Check if user is allowed to log in (if allowlist is configured)
+    let names = [Some(identity.username.as_str()), identity.email.as_deref()];
+    if !is_allowed(&state, names.into_iter().flatten()) {
+        eprintln!(
+            "Login denied: {} {} not in ALLOWED_USERS",
+            provider.as_str(),
+            identity.username
+        );
+        return Err(ApiError::Forbidden("Not allowed to log in"));
+    }
+
+    let user_id = match login_identities::update_identity(
+        &state.db,
+        provider.as_str(),
+        &identity.provider_user_id,
+        &identity.username,
+        identity.email.as_deref(),
+        identity.name.as_deref(),
+    )
+    .await
+    .log_500("Update login identity error")?
+    {
+        Some(user_id) => user_id,
+        None => login_identities::create_user_with_identity(
+            &state.db,
+            provider.as_str(),
+            &identity.provider_user_id,
+            &identity.username,
+            identity.email.as_deref(),
+            identity.name.as_deref(),
+        )
+        .await
+        .log_500("Create user with identity error")?,
+    };
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::Login,
+        None,
+        Some(serde_json::json!({
+            "provider": provider.as_str(),
+            "username": identity.username,
+        })),
+    )
+    .await;
+
+    let mut response = Json(ProviderLoginResponse {
+        username: identity.username,
+    })
+    .into_response();
+    attach_session(&state, user_id, &mut response).await?;
+
+    Ok(response)
+}
+
+/// Whether any of a login's names is on the allowlist (always true without one)
+fn is_allowed<'a>(state: &AppState, mut names: impl Iterator<Item = &'a str>) -> bool {
+    state
+        .allowed_users
+        .as_ref()
+        .is_none_or(|allowed| names.any(|name| allowed.contains(&name.to_lowercase())))
+}
+
+// ============================================================================
+// Email sign-in links
+// ============================================================================
+
+#[derive(Deserialize)]
+pub(super) struct EmailLinkRequest {
+    pub(super) email: String,
+}
+
+/// Email a one-time sign-in link. With `link_user_id` the link instead
+/// verifies the address and adds it to that account as a login.
+pub(super) async fn send_email_link(
+    state: &AppState,
+    address: &str,
+    link_user_id: Option<i64>,
+) -> Result<(), ApiError> {
+    let sender = state
+        .email
+        .as_ref()
+        .ok_or(ApiError::ServiceUnavailable("Email login"))?;
+    let address = email::normalize_address(address)
+        .ok_or_else(|| ApiError::Unprocessable("Invalid email address".to_string()))?;
+    if link_user_id.is_none() && !is_allowed(state, std::iter::once(address.as_str())) {
+        eprintln!("Login denied: email {} not in ALLOWED_USERS", address);
+        return Err(ApiError::Forbidden("Not allowed to log in"));
+    }
+    if !EMAIL_LINK_LIMITER.check(address.clone()) {
+        return Err(ApiError::RateLimited);
+    }
+
+    if let Err(e) = email_login_tokens::delete_expired(&state.db).await {
+        eprintln!("Delete expired email login tokens error: {}", e);
+    }
+
+    let token = generate_email_token();
+    let ttl_mins = config::get().email.link_ttl_mins;
+    email_login_tokens::insert_token(
+        &state.db,
+        &hash_token(&token),
+        &address,
+        link_user_id,
+        Utc::now() + Duration::minutes(ttl_mins),
+    )
+    .await
+    .log_500("Insert email login token error")?;
+
+    let url = format!("{}/?email_token={}", config::get().app_origin(), token);
+    let (subject, action) = if link_user_id.is_some() {
+        (
+            "Confirm your email for Cleo",
+            "confirm this address for Cleo",
+        )
+    } else {
+        ("Sign in to Cleo", "sign in to Cleo")
+    };
+    let footer = format!(
+        "It works once and expires in {} minutes. If you didn't ask for it, you can ignore this email.",
+        ttl_mins
+    );
+    let message = EmailMessage {
+        to: address,
+        subject: subject.to_string(),
+        text: format!("Open this link to {}:\n\n{}\n\n{}\n", action, url, footer),
+        html: format!(
+            r#"<p>Open this link to {}:</p>
+<p><a href="{url}">{url}</a></p>
+<p>{}</p>"#,
+            action,
+            footer,
+            url = escape_html(&url)
+        ),
+    };
+    sender.send(&message).await.map_err(|e| {
+        eprintln!("Send email link error: {}", e);
+        ApiError::Upstream("Couldn't send the email".to_string())
+    })
+}
+
+/// New sign-in link token
+fn generate_email_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// POST /auth/email/link - Email a one-time sign-in link
+/// Answers 202 whether or not the address has an account.
+async fn request_email_link(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<EmailLinkRequest>,
+) -> Result<StatusCode, ApiError> {
+    send_email_link(&state, &req.email, None).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[derive(Deserialize)]
+struct EmailVerifyRequest {
+    token: String,
+}
+
+/// POST /auth/email/verify - Trade a sign-in link's token for a session
+/// Signs in the account owning the address, creating one on first use. Links
+/// sent from POST /me/integrations/email add the address to that account.
+async fn verify_email_link(
+    State(state): State<Arc<AppState>>,
+    ctx: RequestContext,
+    Json(req): Json<EmailVerifyRequest>,
+) -> Result<Response, ApiError> {
+    let token = email_login_tokens::redeem_token(&state.db, &hash_token(req.token.trim()))
+        .await
+        .log_500("Redeem email login token error")?
+        .ok_or(ApiError::NotFound("Sign-in link"))?;
+    let address = token.email.as_str();
+
+    let user_id = match token.link_user_id {
+        Some(user_id) => {
+            let owner = login_identities::find_identity_user(&state.db, EMAIL_PROVIDER, address)
+                .await
+                .log_500("Find login identity error")?;
+            if owner.is_some_and(|owner| owner != user_id) {
+                return Err(ApiError::Conflict(
+                    "This email already signs in to another Cleo account".to_string(),
+                ));
+            }
+            login_identities::attach_identity(
+                &state.db,
+                user_id,
+                EMAIL_PROVIDER,
+                address,
+                address,
+                Some(address),
+            )
+            .await
+            .log_500("Attach login identity error")?;
+
+            audit::record(
+                &state.db,
+                &ctx,
+                user_id,
+                Action::IntegrationConnected,
+                None,
+                Some(serde_json::json!({ "provider": EMAIL_PROVIDER, "username": address })),
+            )
+            .await;
+            user_id
+        }
+        None => {
+            let user_id = match login_identities::update_identity(
+                &state.db,
+                EMAIL_PROVIDER,
+                address,
+                address,
+                Some(address),
+                None,
+            )
+            .await
+            .log_500("Update login identity error")?
+            {
+                Some(user_id) => user_id,
+                None => login_identities::create_user_with_identity(
+                    &state.db,
+                    EMAIL_PROVIDER,
+                    address,
+                    address,
+                    Some(address),
+                    None,
+                )
+                .await
+                .log_500("Create user with identity error")?,
+            };
+
+            audit::record(
+                &state.db,
+                &ctx,
+                user_id,
+                Action::Login,
+                None,
+                Some(serde_json::json!({ "provider": EMAIL_PROVIDER, "username": address })),
+            )
+            .await;
+            user_id
+        }
+    };
+
+    let user = users::get_user_by_id(&state.db, user_id)
+        .await
+        .log_500("Get user by ID error")?
+        .ok_or(ApiError::Unauthorized)?;
+
+    let mut response = Json(ProviderLoginResponse {
+        username: user.username,
+    })
+    .into_response();
+    attach_session(&state, user_id, &mut response).await?;
+
+    Ok(response)
+}
+
 // ============================================================================
 // API Token endpoints (for daemon auth)
 // ============================================================================
@@ -183,15 +647,26 @@ struct ApiTokenResponse {
 async fn generate_api_token(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
-) -> Result<(StatusCode, Json<ApiTokenResponse>), StatusCode> {
+    ctx: RequestContext,
+) -> Result<(StatusCode, Json<ApiTokenResponse>), ApiError> {
     let token = twitter::generate_api_token();
     twitter::set_user_api_token(&state.db, user_id, &token)
         .await
         .map_err(|e| {
             eprintln!("Set user API token error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            ApiError::Internal
         })?;
 
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::ApiTokenGenerated,
+        None,
+        None,
+    )
+    .await;
+
     Ok((
         StatusCode::CREATED,
         Json(ApiTokenResponse { api_token: token }),
@@ -202,12 +677,12 @@ async fn generate_api_token(
 async fn get_api_token(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
-) -> Result<Json<Option<String>>, StatusCode> {
+) -> Result<Json<Option<String>>, ApiError> {
     let token = twitter::get_user_api_token(&state.db, user_id)
         .await
         .map_err(|e| {
             eprintln!("Get user API token error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            ApiError::Internal
         })?;
 
     Ok(Json(token))
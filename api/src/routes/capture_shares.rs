@@ -0,0 +1,200 @@
+//! Capture share link endpoints (/captures/:id/share, /captures/:id/shares, /shared/:token)
+//!
+//! A share link lets anyone who has it view one capture without logging in,
+//! until it expires or the owner revokes it. GET /shared/:token redirects to a
+//! short-lived signed media URL, so a revoked link stops working once the last
+//! URL handed out expires.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
+    routing::{delete, get, post},
+};
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use super::captures::signed_url;
+use crate::AppState;
+use crate::domain::{capture_shares, captures as captures_domain};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::error::{ApiError, LogErr};
+use crate::storage::sha256_hex;
+
+/// Link lifetime when none is given
+const DEFAULT_SHARE_HOURS: i64 = 24;
+/// Longest a link may stay valid (7 days)
+const MAX_SHARE_HOURS: i64 = 7 * 24;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/captures/{id}/share", post(create_share))
+        .route("/captures/{id}/shares", get(list_shares))
+        .route("/captures/{id}/shares/{share_id}", delete(revoke_share))
+        .route("/shared/{token}", get(view_share))
+}
+
+/// New random share token; only its hash is stored
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Share link API response DTO
+#[derive(Serialize)]
+struct ShareResponse {
+    id: i64,
+    capture_id: i64,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+    view_count: i32,
+    last_viewed_at: Option<DateTime<Utc>>,
+}
+
+impl From<capture_shares::CaptureShare> for ShareResponse {
+    fn from(s: capture_shares::CaptureShare) -> Self {
+        Self {
+            id: s.id,
+            capture_id: s.capture_id,
+            created_at: s.created_at,
+            expires_at: s.expires_at,
+            revoked_at: s.revoked_at,
+            view_count: s.view_count,
+            last_viewed_at: s.last_viewed_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct CreateShareRequest {
+    /// Hours until the link stops working (1 to 168, default 24)
+    expires_in_hours: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct CreateShareResponse {
+    /// Shown once; only its hash is stored
+    token: String,
+    /// Path of the public link, relative to the API base URL
+    url: String,
+    #[serde(flatten)]
+    details: ShareResponse,
+}
+
+/// POST /captures/:id/share - Create an expiring public link to a capture
+async fn create_share(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    Path(capture_id): Path<i64>,
+    req: Option<Json<CreateShareRequest>>,
+) -> Result<(StatusCode, Json<CreateShareResponse>), ApiError> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let hours = req.expires_in_hours.unwrap_or(DEFAULT_SHARE_HOURS);
+    if !(1..=MAX_SHARE_HOURS).contains(&hours) {
+        return Err(ApiError::Unprocessable(format!(
+            "expires_in_hours must be 1 to {}",
+            MAX_SHARE_HOURS
+        )));
+    }
+
+    captures_domain::get_capture_media(&state.db, capture_id, user_id)
+        .await
+        .log_500("Get capture media error")?
+        .ok_or(ApiError::NotFound("Capture"))?;
+
+    let token = generate_token();
+    let stored = capture_shares::insert_share(
+        &state.db,
+        user_id,
+        capture_id,
+        &sha256_hex(token.as_bytes()),
+        Utc::now() + Duration::hours(hours),
+    )
+    .await
+    .log_500("Insert capture share error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::CaptureShared,
+        Some(&capture_id.to_string()),
+        Some(serde_json::json!({ "share_id": stored.id, "expires_at": stored.expires_at })),
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateShareResponse {
+            url: format!("/shared/{}", token),
+            token,
+            details: stored.into(),
+        }),
+    ))
+}
+
+/// GET /captures/:id/shares - List a capture's share links (without tokens)
+async fn list_shares(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(capture_id): Path<i64>,
+) -> Result<Json<Vec<ShareResponse>>, ApiError> {
+    let shares = capture_shares::list_shares(&state.db, user_id, capture_id)
+        .await
+        .log_500("List capture shares error")?;
+    Ok(Json(shares.into_iter().map(Into::into).collect()))
+}
+
+/// DELETE /captures/:id/shares/:share_id - Revoke a share link
+async fn revoke_share(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    Path((capture_id, share_id)): Path<(i64, i64)>,
+) -> Result<StatusCode, ApiError> {
+    let revoked = capture_shares::revoke_share(&state.db, user_id, capture_id, share_id)
+        .await
+        .log_500("Revoke capture share error")?;
+    if !revoked {
+        return Err(ApiError::NotFound("Share link"));
+    }
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::CaptureShareRevoked,
+        Some(&capture_id.to_string()),
+        Some(serde_json::json!({ "share_id": share_id })),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /shared/:token - View a shared capture (no auth)
+/// Redirects to a signed media URL; 404 once the link expires or is revoked.
+async fn view_share(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Response, ApiError> {
+    let shared = capture_shares::view_share(&state.db, &sha256_hex(token.as_bytes()))
+        .await
+        .log_500("View capture share error")?
+        .ok_or(ApiError::NotFound("Share link"))?;
+
+    let url = signed_url(&state, shared.user_id, &shared.gcs_path).await?;
+    // The signed URL must not outlive a revocation in someone's cache
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Redirect::temporary(&url),
+    )
+        .into_response())
+}
@@ -1,93 +1,376 @@
-//! Capture and media endpoints (/captures/*, /media/*, /activity)
+//! Capture and media endpoints (/captures/*, /media/*, /activity, /activity/summary)
 
 use axum::{
     Json, Router,
+    body::Body,
     extract::{Multipart, Path, Query, State},
     http::{HeaderMap, StatusCode, header},
-    response::IntoResponse,
-    routing::{get, post},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
 };
-use chrono::{DateTime, Utc};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::io::SeekFrom;
 use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
 
 use super::auth::AuthUser;
-use crate::constants::{BUCKET_NAME, SIGNED_URL_EXPIRY_SECS};
-use crate::domain::{activities, captures as captures_domain};
-use crate::services::{error::LogErr, rate_limit::DAEMON_RATE_LIMITER, twitter};
-use crate::{Activity, ActivityEvent, AppState, BatchCaptureResponse, get_extension};
+use crate::config;
+use crate::constants::SIGNED_URL_EXPIRY_SECS;
+use crate::domain::{
+    activities, captures as captures_domain, devices, media_library, projects, sessions,
+};
+use crate::services::media_signing::{self, MediaSignature};
+use crate::services::projects::{self as project_inference, ProjectHint};
+use crate::services::{
+    error::{ApiError, LogErr},
+    idempotency,
+    protobuf::{self, FromProto, ValidBody},
+    quota, range,
+    rate_limit::DAEMON_RATE_LIMITER,
+    timezone, twitter,
+    validation::{ValidJson, Validate, check_max_items, with_body_limit, with_decompression},
+};
+use crate::{
+    Activity, ActivityEvent, ActivitySummary, AppState, BatchCaptureResponse, frames,
+    get_extension, storage, thumbnails,
+};
 
 pub fn routes() -> Router<Arc<AppState>> {
+    let limits = &config::get().limits;
     Router::new()
-        .route("/captures/batch", post(capture_batch))
+        .route(
+            "/captures/batch",
+            with_decompression(with_body_limit(
+                post(capture_batch),
+                limits.capture_upload_bytes,
+            )),
+        )
         .route("/captures/browse", get(browse_captures))
+        .route("/captures/thumbnails", post(batch_thumbnails))
         .route("/captures/{id}/url", get(get_capture_url))
         .route("/captures/{id}/thumbnail", get(get_capture_thumbnail))
+        .route("/captures/{id}/frames", get(get_capture_frames))
+        .route("/captures/{id}/usage", get(get_capture_usage))
+        .route("/captures/{id}/exclude", put(set_capture_excluded))
+        .route("/captures/{id}/public-log", put(set_capture_in_public_log))
         .route("/media/{*path}", get(serve_media))
-        .route("/activity", post(activity))
+        .route(
+            "/activity",
+            with_decompression(with_body_limit(post(activity), limits.activity_body_bytes)),
+        )
+        .route(
+            "/activity/summary",
+            with_decompression(with_body_limit(
+                post(activity_summary),
+                limits.activity_body_bytes,
+            )),
+        )
 }
 
 /// Helper to extract user_id from Bearer token (for daemon auth)
-pub async fn get_user_id_from_bearer(db: &PgPool, headers: &HeaderMap) -> Result<i64, StatusCode> {
+pub async fn get_user_id_from_bearer(db: &PgPool, headers: &HeaderMap) -> Result<i64, ApiError> {
     let auth_header = headers
         .get("authorization")
         .and_then(|v| v.to_str().ok())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or(ApiError::Unauthorized)?;
 
     let token = auth_header
         .strip_prefix("Bearer ")
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or(ApiError::Unauthorized)?;
 
     twitter::get_user_by_api_token(db, token)
         .await
         .log_500("Get user by API token error")?
-        .ok_or(StatusCode::UNAUTHORIZED)
+        .ok_or(ApiError::InvalidApiToken)
 }
 
-#[derive(Serialize)]
-struct SignedUrlResponse {
-    url: String,
-    content_type: String,
+/// Parse the optional X-Session-ID header sent by the daemon
+fn parse_session_header(headers: &HeaderMap) -> Result<Option<i64>, ApiError> {
+    match headers.get("x-session-id") {
+        None => Ok(None),
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Some)
+            .ok_or_else(|| ApiError::BadRequest("Invalid X-Session-ID header".to_string())),
+    }
 }
 
-/// GET /captures/:id/url - Get a signed URL for a capture
-async fn get_capture_url(
-    State(state): State<Arc<AppState>>,
-    AuthUser(user_id): AuthUser,
-    Path(capture_id): Path<i64>,
-) -> Result<Json<SignedUrlResponse>, StatusCode> {
-    // Get capture info and verify ownership
-    let capture = captures_domain::get_capture_media(&state.db, capture_id, user_id)
+/// Reject uploads that reference a session owned by someone else or opened on another device
+async fn ensure_session_owned(
+    db: &PgPool,
+    session_id: i64,
+    user_id: i64,
+    device_id: Option<i64>,
+) -> Result<(), ApiError> {
+    let owned = sessions::session_owned_by(db, session_id, user_id, device_id)
         .await
-        .log_500("Get capture media error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .log_500("Verify session ownership error")?;
+    if owned {
+        Ok(())
+    } else {
+        Err(ApiError::BadRequest(format!(
+            "Session {} does not belong to this device",
+            session_id
+        )))
+    }
+}
+
+/// Maximum accepted length for the X-Device-ID header
+const MAX_DEVICE_UID_LEN: usize = 64;
+/// Maximum stored length for the X-Device-Name header
+const MAX_DEVICE_NAME_LEN: usize = 128;
+/// Values the daemon reports in X-Device-Integrity; anything else is ignored
+const DEVICE_INTEGRITY_LABELS: &[&str] =
+    &["notarized", "signed", "unsigned", "modified", "unknown"];
+
+/// Authenticated daemon caller: the token's user plus the machine it runs on
+pub struct DaemonIdentity {
+    pub user_id: i64,
+    /// None for daemons that predate device tracking (no X-Device-ID header)
+    pub device_id: Option<i64>,
+}
+
+/// Authenticate a daemon request and register/refresh the calling device.
+/// Requests from revoked devices are rejected with 403.
+pub async fn get_daemon_identity(
+    db: &PgPool,
+    headers: &HeaderMap,
+) -> Result<DaemonIdentity, ApiError> {
+    let user_id = get_user_id_from_bearer(db, headers).await?;
 
-    let gcs_path = capture.gcs_path;
-    let content_type = capture.content_type;
+    let Some(device_uid) = headers.get("x-device-id") else {
+        return Ok(DaemonIdentity {
+            user_id,
+            device_id: None,
+        });
+    };
+    let device_uid = device_uid
+        .to_str()
+        .map(str::trim)
+        .ok()
+        .filter(|uid| !uid.is_empty() && uid.len() <= MAX_DEVICE_UID_LEN)
+        .ok_or_else(|| ApiError::BadRequest("Invalid X-Device-ID header".to_string()))?;
+
+    let device_name = headers
+        .get("x-device-name")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| {
+            v.trim()
+                .chars()
+                .take(MAX_DEVICE_NAME_LEN)
+                .collect::<String>()
+        })
+        .filter(|v| !v.is_empty());
+
+    let integrity = headers
+        .get("x-device-integrity")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| DEVICE_INTEGRITY_LABELS.contains(v));
 
+    let device = devices::upsert_device(db, user_id, device_uid, device_name.as_deref(), integrity)
+        .await
+        .log_500("Upsert device error")?;
+
+    if device.revoked_at.is_some() {
+        return Err(ApiError::Forbidden("Device has been revoked"));
+    }
+
+    Ok(DaemonIdentity {
+        user_id,
+        device_id: Some(device.id),
+    })
+}
+
+#[derive(Serialize)]
+pub(super) struct SignedUrlResponse {
+    pub url: String,
+    pub content_type: String,
+}
+
+/// Short-lived URL for a stored object the user owns
+pub(super) async fn signed_url(
+    state: &AppState,
+    user_id: i64,
+    gcs_path: &str,
+) -> Result<String, ApiError> {
     // If local storage is configured, return a local URL
     if state.local_storage_path.is_some() {
-        // Return a URL that points to our /media endpoint
-        let url = format!("/media/{}", gcs_path);
-        return Ok(Json(SignedUrlResponse { url, content_type }));
+        // Return a signed URL that points to our /media endpoint
+        return Ok(media_signing::signed_media_url(
+            &state.jwt_secret,
+            gcs_path,
+            user_id,
+            SIGNED_URL_EXPIRY_SECS,
+        ));
     }
 
     // Generate signed URL (15 min expiry) using cloud-storage crate
     let client = cloud_storage::Client::default();
     let object = client
         .object()
-        .read(BUCKET_NAME, &gcs_path)
+        .read(state.buckets.for_path(gcs_path), gcs_path)
         .await
         .log_500("Object read error")?;
 
-    let signed_url = object
+    Ok(object
         .download_url(SIGNED_URL_EXPIRY_SECS)
-        .log_500("Signed URL error")?;
+        .log_500("Signed URL error")?)
+}
+
+/// GET /captures/:id/url - Get a signed URL for a capture
+async fn get_capture_url(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(capture_id): Path<i64>,
+) -> Result<Json<SignedUrlResponse>, ApiError> {
+    // Get capture info and verify ownership
+    let capture = captures_domain::get_capture_media(&state.db, capture_id, user_id)
+        .await
+        .log_500("Get capture media error")?
+        .ok_or(ApiError::NotFound("Capture"))?;
 
     Ok(Json(SignedUrlResponse {
-        url: signed_url,
-        content_type,
+        url: signed_url(&state, user_id, &capture.gcs_path).await?,
+        content_type: capture.content_type,
+    }))
+}
+
+#[derive(Serialize)]
+struct CaptureTweetResponse {
+    tweet_id: i64,
+    thread_id: Option<i64>,
+    text: String,
+    created_at: DateTime<Utc>,
+    posted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+struct CaptureUsageResponse {
+    capture_id: i64,
+    used_in_drafts: usize,
+    used_in_posts: usize,
+    tweets: Vec<CaptureTweetResponse>,
+}
+
+/// GET /captures/:id/usage - Drafts and posted tweets that use a capture
+async fn get_capture_usage(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(capture_id): Path<i64>,
+) -> Result<Json<CaptureUsageResponse>, ApiError> {
+    captures_domain::get_capture_media(&state.db, capture_id, user_id)
+        .await
+        .log_500("Get capture media error")?
+        .ok_or(ApiError::NotFound("Capture"))?;
+
+    let tweets = captures_domain::list_capture_tweets(&state.db, capture_id, user_id)
+        .await
+        .log_500("List capture tweets error")?;
+    let used_in_posts = tweets.iter().filter(|t| t.posted_at.is_some()).count();
+
+    Ok(Json(CaptureUsageResponse {
+        capture_id,
+        used_in_drafts: tweets.len() - used_in_posts,
+        used_in_posts,
+        tweets: tweets
+            .into_iter()
+            .map(|t| CaptureTweetResponse {
+                tweet_id: t.tweet_id,
+                thread_id: t.thread_id,
+                text: t.text,
+                created_at: t.created_at,
+                posted_at: t.posted_at,
+            })
+            .collect(),
+    }))
+}
+
+/// Most frames returned per page (each needs its own signed URL)
+const MAX_FRAMES_PER_PAGE: usize = 200;
+
+#[derive(Deserialize)]
+struct FramesQuery {
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct FrameResponse {
+    index: usize,
+    timestamp_secs: f64,
+    phash: String,
+    url: String,
+}
+
+#[derive(Serialize)]
+struct FramesResponse {
+    /// False until frame extraction has run for this capture
+    ready: bool,
+    duration_secs: Option<f64>,
+    frame_count: usize,
+    frames: Vec<FrameResponse>,
+}
+
+/// GET /captures/:id/frames - Extracted frames with timestamps, phashes and signed URLs
+/// Paginated with `?offset=&limit=` (at most 200 per page).
+async fn get_capture_frames(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(capture_id): Path<i64>,
+    Query(query): Query<FramesQuery>,
+) -> Result<Json<FramesResponse>, ApiError> {
+    let capture = captures_domain::get_capture_media(&state.db, capture_id, user_id)
+        .await
+        .log_500("Get capture media error")?
+        .ok_or(ApiError::NotFound("Capture"))?;
+
+    // No manifest yet means extraction hasn't run (or is still running)
+    let frames_dir = frames::get_frames_dir(&capture.gcs_path);
+    let manifest = match state
+        .store
+        .get(&format!("{}/manifest.json", frames_dir))
+        .await
+    {
+        Ok(data) => serde_json::from_slice::<frames::FrameManifest>(&data)
+            .log_500("Parse frame manifest error")?,
+        Err(_) => {
+            return Ok(Json(FramesResponse {
+                ready: false,
+                duration_secs: None,
+                frame_count: 0,
+                frames: Vec::new(),
+            }));
+        }
+    };
+
+    let limit = query
+        .limit
+        .unwrap_or(MAX_FRAMES_PER_PAGE)
+        .min(MAX_FRAMES_PER_PAGE);
+    let mut frames = Vec::new();
+    for frame in manifest.frames.into_iter().skip(query.offset).take(limit) {
+        let path = format!("{}/{}", frames_dir, frame.filename);
+        frames.push(FrameResponse {
+            index: frame.index,
+            timestamp_secs: frame.timestamp_secs,
+            phash: frame.phash,
+            url: signed_url(&state, user_id, &path).await?,
+        });
+    }
+
+    Ok(Json(FramesResponse {
+        ready: true,
+        duration_secs: manifest.duration_secs,
+        frame_count: manifest.frame_count,
+        frames,
     }))
 }
 
@@ -97,17 +380,30 @@ struct ThumbnailUrlResponse {
     ready: bool,
 }
 
+#[derive(Deserialize)]
+struct ThumbnailQuery {
+    /// When set, serve the image itself resized to (roughly) this width
+    w: Option<u32>,
+}
+
 /// GET /captures/:id/thumbnail - Get a thumbnail URL for a capture
+/// With `?w=<px>` the thumbnail bytes are served directly, resized and cached.
 async fn get_capture_thumbnail(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Path(capture_id): Path<i64>,
-) -> Result<Json<ThumbnailUrlResponse>, StatusCode> {
+    Query(query): Query<ThumbnailQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     // Get capture info and verify ownership
     let capture = captures_domain::get_capture_thumbnail(&state.db, capture_id, user_id)
         .await
         .log_500("Get capture thumbnail error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Capture"))?;
+
+    if let Some(width) = query.w {
+        return serve_resized_thumbnail(&state, capture_id, capture, width, &headers).await;
+    }
 
     let thumbnail_path = capture.thumbnail_path;
 
@@ -116,23 +412,30 @@ async fn get_capture_thumbnail(
         return Ok(Json(ThumbnailUrlResponse {
             url: None,
             ready: false,
-        }));
+        })
+        .into_response());
     };
 
     // If local storage is configured, return a local URL
     if state.local_storage_path.is_some() {
-        let url = format!("/media/{}", thumb_path);
+        let url = media_signing::signed_media_url(
+            &state.jwt_secret,
+            &thumb_path,
+            user_id,
+            SIGNED_URL_EXPIRY_SECS,
+        );
         return Ok(Json(ThumbnailUrlResponse {
             url: Some(url),
             ready: true,
-        }));
+        })
+        .into_response());
     }
 
     // Generate signed URL for GCS
     let client = cloud_storage::Client::default();
     let object = client
         .object()
-        .read(BUCKET_NAME, &thumb_path)
+        .read(state.buckets.for_path(&thumb_path), &thumb_path)
         .await
         .log_500("Thumbnail object read error")?;
 
@@ -143,6 +446,287 @@ async fn get_capture_thumbnail(
     Ok(Json(ThumbnailUrlResponse {
         url: Some(signed_url),
         ready: true,
+    })
+    .into_response())
+}
+
+/// Serve a thumbnail resized to one of the cached widths.
+/// Derived images are cached in storage next to the thumbnails (thumbnails/w<W>/...),
+/// so each capture+width is only resized once.
+async fn serve_resized_thumbnail(
+    state: &AppState,
+    capture_id: i64,
+    capture: captures_domain::CaptureThumbnail,
+    requested_width: u32,
+    headers: &HeaderMap,
+) -> Result<Response, ApiError> {
+    // 404 until the thumbnail worker has run, so clients can retry later
+    let thumbnail_path = capture
+        .thumbnail_path
+        .ok_or(ApiError::NotFound("Thumbnail"))?;
+    let width = thumbnails::resize_width_for(requested_width);
+
+    // Derived thumbnails never change for a capture+width
+    let etag = format!("\"{}-w{}\"", capture_id, width);
+    let cached_response = Response::builder()
+        .header(
+            header::CACHE_CONTROL,
+            "private, max-age=31536000, immutable",
+        )
+        .header(header::ETAG, &etag);
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| range::etag_matches(v, &etag));
+    if not_modified {
+        return Ok(cached_response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .log_500("Build thumbnail response error")?);
+    }
+
+    let bytes = load_resized_thumbnail(
+        state,
+        &capture.media_type,
+        &capture.gcs_path,
+        &thumbnail_path,
+        width,
+    )
+    .await?;
+
+    Ok(cached_response
+        .header(header::CONTENT_TYPE, "image/jpeg")
+        .header(header::CONTENT_LENGTH, bytes.len())
+        .body(Body::from(bytes))
+        .log_500("Build thumbnail response error")?)
+}
+
+/// Load a resized thumbnail from the storage cache, generating and caching it on a miss
+async fn load_resized_thumbnail(
+    state: &AppState,
+    media_type: &str,
+    gcs_path: &str,
+    thumbnail_path: &str,
+    width: u32,
+) -> Result<Vec<u8>, ApiError> {
+    let cache_path = thumbnails::resized_thumbnail_path(thumbnail_path, width);
+
+    if let Ok(bytes) = state.store.get(&cache_path).await {
+        return Ok(bytes);
+    }
+
+    // Cache miss - resize from the original image when the stored
+    // thumbnail is too small, otherwise from the thumbnail
+    let source_path = if media_type == "image" && thumbnails::resize_needs_original(width) {
+        gcs_path
+    } else {
+        thumbnail_path
+    };
+    let source = state
+        .store
+        .get(source_path)
+        .await
+        .log_500("Thumbnail source download error")?;
+    let resized = tokio::task::spawn_blocking(move || thumbnails::resize_to_width(&source, width))
+        .await
+        .log_500("Thumbnail resize task error")?
+        .log_500("Thumbnail resize error")?;
+
+    if let Err(e) = state.store.put(&cache_path, &resized).await {
+        eprintln!("[thumbnails] Failed to cache {}: {}", cache_path, e);
+    }
+    Ok(resized)
+}
+
+/// Maximum capture ids per POST /captures/thumbnails request
+const MAX_BATCH_THUMBNAILS: usize = 100;
+/// Width of inline base64 previews
+const INLINE_PREVIEW_WIDTH: u32 = 80;
+
+#[derive(Deserialize)]
+struct BatchThumbnailsRequest {
+    ids: Vec<i64>,
+    /// Return small base64 JPEG previews instead of URLs
+    #[serde(default)]
+    inline: bool,
+}
+
+impl Validate for BatchThumbnailsRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        check_max_items(&self.ids, MAX_BATCH_THUMBNAILS, "ids")
+    }
+}
+
+#[derive(Serialize)]
+struct BatchThumbnailItem {
+    id: i64,
+    ready: bool,
+    url: Option<String>,
+    /// data: URI, only when `inline` was requested
+    preview: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BatchThumbnailsResponse {
+    thumbnails: Vec<BatchThumbnailItem>,
+}
+
+/// POST /captures/thumbnails - Thumbnail URLs (or inline previews) for up to 100 captures.
+/// Unknown ids and captures owned by other users are omitted.
+async fn batch_thumbnails(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ValidJson(payload): ValidJson<BatchThumbnailsRequest>,
+) -> Result<Json<BatchThumbnailsResponse>, ApiError> {
+    let mut seen = std::collections::HashSet::new();
+    let ids: Vec<i64> = payload
+        .ids
+        .iter()
+        .copied()
+        .filter(|id| seen.insert(*id))
+        .collect();
+
+    let rows = captures_domain::get_capture_thumbnails(&state.db, user_id, &ids)
+        .await
+        .log_500("Get capture thumbnails error")?;
+
+    let inline = payload.inline;
+    let use_local = state.local_storage_path.is_some();
+    let client = cloud_storage::Client::default();
+
+    let items = futures::future::join_all(rows.into_iter().map(|row| {
+        let state = &state;
+        let client = &client;
+        async move {
+            let Some(thumb_path) = row.thumbnail_path else {
+                return BatchThumbnailItem {
+                    id: row.id,
+                    ready: false,
+                    url: None,
+                    preview: None,
+                };
+            };
+
+            if inline {
+                let preview = load_resized_thumbnail(
+                    state,
+                    &row.media_type,
+                    &row.gcs_path,
+                    &thumb_path,
+                    INLINE_PREVIEW_WIDTH,
+                )
+                .await
+                .ok()
+                .map(|bytes| format!("data:image/jpeg;base64,{}", BASE64_STANDARD.encode(bytes)));
+                return BatchThumbnailItem {
+                    id: row.id,
+                    ready: preview.is_some(),
+                    url: None,
+                    preview,
+                };
+            }
+
+            let url = if use_local {
+                Some(media_signing::signed_media_url(
+                    &state.jwt_secret,
+                    &thumb_path,
+                    user_id,
+                    SIGNED_URL_EXPIRY_SECS,
+                ))
+            } else {
+                match client
+                    .object()
+                    .read(state.buckets.for_path(&thumb_path), &thumb_path)
+                    .await
+                {
+                    Ok(object) => object.download_url(SIGNED_URL_EXPIRY_SECS).ok(),
+                    Err(e) => {
+                        eprintln!("Thumbnail object read error for {}: {}", row.id, e);
+                        None
+                    }
+                }
+            };
+            BatchThumbnailItem {
+                id: row.id,
+                ready: url.is_some(),
+                url,
+                preview: None,
+            }
+        }
+    }))
+    .await;
+
+    Ok(Json(BatchThumbnailsResponse { thumbnails: items }))
+}
+
+#[derive(Deserialize)]
+struct SetExcludedRequest {
+    excluded: bool,
+}
+
+#[derive(Serialize)]
+struct SetExcludedResponse {
+    id: i64,
+    excluded_from_agent: bool,
+}
+
+/// PUT /captures/:id/exclude - Keep a capture out of (or back in) the content agent
+async fn set_capture_excluded(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(capture_id): Path<i64>,
+    Json(payload): Json<SetExcludedRequest>,
+) -> Result<Json<SetExcludedResponse>, ApiError> {
+    let updated =
+        captures_domain::set_capture_excluded(&state.db, capture_id, user_id, payload.excluded)
+            .await
+            .log_500("Set capture excluded error")?;
+
+    if !updated {
+        return Err(ApiError::NotFound("Capture"));
+    }
+
+    Ok(Json(SetExcludedResponse {
+        id: capture_id,
+        excluded_from_agent: payload.excluded,
+    }))
+}
+
+#[derive(Deserialize)]
+struct SetPublicLogRequest {
+    included: bool,
+}
+
+#[derive(Serialize)]
+struct SetPublicLogResponse {
+    id: i64,
+    in_public_log: bool,
+}
+
+/// PUT /captures/:id/public-log - Show a capture in (or hide it from) the public build log
+async fn set_capture_in_public_log(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(capture_id): Path<i64>,
+    Json(payload): Json<SetPublicLogRequest>,
+) -> Result<Json<SetPublicLogResponse>, ApiError> {
+    let updated = captures_domain::set_capture_in_public_log(
+        &state.db,
+        capture_id,
+        user_id,
+        payload.included,
+    )
+    .await
+    .log_500("Set capture public log error")?;
+
+    if !updated {
+        return Err(ApiError::NotFound("Capture"));
+    }
+
+    Ok(Json(SetPublicLogResponse {
+        id: capture_id,
+        in_public_log: payload.included,
     }))
 }
 
@@ -150,8 +734,14 @@ async fn get_capture_thumbnail(
 struct BrowseCapturesQuery {
     start: Option<String>,
     end: Option<String>,
+    /// Local day (YYYY-MM-DD) in the user's time zone; narrowed further by start/end
+    date: Option<NaiveDate>,
     #[serde(rename = "type")]
     media_type: Option<String>,
+    /// Restrict results to a single device
+    device_id: Option<i64>,
+    /// Only captures taken while this project was in the foreground
+    project: Option<String>,
     limit: Option<i64>,
     offset: Option<i64>,
     /// Comma-separated list of capture IDs to always include in results
@@ -166,6 +756,17 @@ struct CaptureItem {
     captured_at: DateTime<Utc>,
     thumbnail_url: Option<String>,
     thumbnail_ready: bool,
+    device_id: Option<i64>,
+    session_id: Option<i64>,
+    excluded_from_agent: bool,
+    in_public_log: bool,
+    duration_secs: Option<f64>,
+    width: Option<i32>,
+    height: Option<i32>,
+    codec: Option<String>,
+    file_size_bytes: Option<i64>,
+    /// Drafts that use this capture; the archiver keeps it while this is non-zero
+    used_in_drafts: i64,
 }
 
 #[derive(Serialize)]
@@ -180,7 +781,7 @@ async fn browse_captures(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Query(query): Query<BrowseCapturesQuery>,
-) -> Result<Json<BrowseCapturesResponse>, StatusCode> {
+) -> Result<Json<BrowseCapturesResponse>, ApiError> {
     let limit = query.limit.unwrap_or(50).min(100);
     let offset = query.offset.unwrap_or(0);
 
@@ -197,6 +798,20 @@ async fn browse_captures(
         .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
         .map(|dt| dt.with_timezone(&Utc));
 
+    let (start_time, end_time) = match query.date {
+        Some(date) => {
+            let tz = timezone::user_timezone(&state.db, user_id).await;
+            let (day_start, day_end) = timezone::day_bounds(date, tz);
+            // `end` is inclusive; stop just short of the next local midnight
+            let day_end = day_end - chrono::Duration::microseconds(1);
+            (
+                Some(start_time.map_or(day_start, |s| s.max(day_start))),
+                Some(end_time.map_or(day_end, |e| e.min(day_end))),
+            )
+        }
+        None => (start_time, end_time),
+    };
+
     // Parse include_ids (comma-separated)
     let include_ids: Option<Vec<i64>> = query.include_ids.as_ref().map(|s| {
         s.split(',')
@@ -211,6 +826,8 @@ async fn browse_captures(
         start_time,
         end_time,
         query.media_type.as_deref(),
+        query.device_id,
+        query.project.as_deref(),
         limit,
         offset,
         include_ids.as_deref(),
@@ -224,7 +841,15 @@ async fn browse_captures(
         .into_iter()
         .map(|row| {
             let (thumbnail_url, thumbnail_ready) = match row.thumbnail_path {
-                Some(path) if use_local => (Some(format!("/media/{}", path)), true),
+                Some(path) if use_local => (
+                    Some(media_signing::signed_media_url(
+                        &state.jwt_secret,
+                        &path,
+                        user_id,
+                        SIGNED_URL_EXPIRY_SECS,
+                    )),
+                    true,
+                ),
                 Some(_) => (Some(format!("/captures/{}/thumbnail", row.id)), true),
                 None => (None, false),
             };
@@ -236,6 +861,16 @@ async fn browse_captures(
                 captured_at: row.captured_at,
                 thumbnail_url,
                 thumbnail_ready,
+                device_id: row.device_id,
+                session_id: row.session_id,
+                excluded_from_agent: row.excluded_from_agent,
+                in_public_log: row.in_public_log,
+                duration_secs: row.duration_secs,
+                width: row.width,
+                height: row.height,
+                codec: row.codec,
+                file_size_bytes: row.file_size_bytes,
+                used_in_drafts: row.used_in_drafts,
             }
         })
         .collect();
@@ -250,27 +885,39 @@ async fn browse_captures(
 }
 
 /// GET /media/*path - Serve local media files
+/// Requires a signed URL from get_capture_url/get_capture_thumbnail/browse.
+/// Streams from disk and honours single byte ranges so video can be scrubbed.
 async fn serve_media(
     State(state): State<Arc<AppState>>,
-    AuthUser(user_id): AuthUser,
     Path(path): Path<String>,
-) -> Result<impl IntoResponse, StatusCode> {
+    Query(signature): Query<MediaSignature>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     // Security: reject paths with traversal attempts or null bytes upfront
     if path.contains("..") || path.contains('\0') {
-        return Err(StatusCode::FORBIDDEN);
+        return Err(ApiError::Forbidden("Invalid media path"));
     }
 
+    let user_id = media_signing::verify_media_signature(&state.jwt_secret, &path, &signature)
+        .ok_or(ApiError::Forbidden("Invalid or expired media signature"))?;
+
     let local_path = state
         .local_storage_path
         .as_ref()
-        .ok_or(StatusCode::NOT_FOUND)?;
-
-    // Enforce per-user access control for locally served media.
-    let owns_path = captures_domain::user_owns_media_path(&state.db, user_id, &path)
-        .await
-        .log_500("Verify media ownership error")?;
+        .ok_or(ApiError::NotFound("Media"))?;
+
+    // Enforce per-user access control for locally served media, even with a valid signature.
+    let owns_path = if path.starts_with(media_library::PATH_PREFIX) {
+        media_library::user_owns_asset_path(&state.db, user_id, &path).await
+    } else if path.starts_with("frames/") {
+        // Frames live under the capture's own user directory
+        Ok(path.starts_with(&format!("frames/user_{}/", user_id)))
+    } else {
+        captures_domain::user_owns_media_path(&state.db, user_id, &path).await
+    }
+    .log_500("Verify media ownership error")?;
     if !owns_path {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(ApiError::NotFound("Media"));
     }
 
     let full_path = local_path.join(&path);
@@ -279,19 +926,21 @@ async fn serve_media(
     // canonicalize() resolves symlinks and normalizes the path
     let canonical = full_path
         .canonicalize()
-        .map_err(|_| StatusCode::NOT_FOUND)?; // Silent - expected for missing files
+        .map_err(|_| ApiError::NotFound("Media"))?; // Silent - expected for missing files
     let storage_canonical = local_path
         .canonicalize()
         .log_500("Failed to canonicalize storage path")?;
 
     if !canonical.starts_with(&storage_canonical) {
-        return Err(StatusCode::FORBIDDEN);
+        return Err(ApiError::Forbidden("Invalid media path"));
     }
 
-    // Read file
-    let bytes = tokio::fs::read(&canonical)
+    let mut file = tokio::fs::File::open(&canonical)
         .await
-        .map_err(|_| StatusCode::NOT_FOUND)?; // Silent - expected for missing files
+        .map_err(|_| ApiError::NotFound("Media"))?; // Silent - expected for missing files
+    let metadata = file.metadata().await.log_500("Media metadata error")?;
+    let len = metadata.len();
+    let etag = range::etag(len, metadata.modified().ok());
 
     // Determine content type from extension
     let content_type = match canonical.extension().and_then(|e| e.to_str()) {
@@ -305,15 +954,74 @@ async fn serve_media(
         _ => "application/octet-stream",
     };
 
-    // Media files are immutable (path includes timestamp), so we can cache aggressively
-    // Cache for 1 year (max-age), mark as immutable to prevent revalidation
-    Ok((
-        [
-            (header::CONTENT_TYPE, content_type),
-            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
-        ],
-        bytes,
-    ))
+    // Media files are immutable (path includes timestamp), but access rests on the
+    // signature: keep responses out of shared caches and stop caching at expiry
+    let builder = Response::builder()
+        .header(
+            header::CACHE_CONTROL,
+            format!(
+                "private, max-age={}, immutable",
+                media_signing::seconds_until_expiry(&signature)
+            ),
+        )
+        .header(header::ETAG, &etag)
+        .header(header::ACCEPT_RANGES, "bytes");
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| range::etag_matches(v, &etag));
+    if not_modified {
+        return Ok(builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .log_500("Build media response error")?);
+    }
+
+    // If-Range: only honour Range when the client's copy is still current
+    let range_header = match headers.get(header::IF_RANGE).and_then(|v| v.to_str().ok()) {
+        Some(if_range) if if_range.trim() != etag => None,
+        _ => headers.get(header::RANGE).and_then(|v| v.to_str().ok()),
+    };
+
+    let builder = builder.header(header::CONTENT_TYPE, content_type);
+
+    match range::parse_range(range_header, len) {
+        range::ByteRange::Full => Ok(builder
+            .header(header::CONTENT_LENGTH, len)
+            .body(Body::from_stream(ReaderStream::new(file)))
+            .log_500("Build media response error")?),
+        range::ByteRange::Partial { start, end } => {
+            file.seek(SeekFrom::Start(start))
+                .await
+                .log_500("Media seek error")?;
+            let chunk_len = end - start + 1;
+            Ok(builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_LENGTH, chunk_len)
+                .header(
+                    header::CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", start, end, len),
+                )
+                .body(Body::from_stream(ReaderStream::new(file.take(chunk_len))))
+                .log_500("Build media response error")?)
+        }
+        range::ByteRange::Unsatisfiable => Ok(builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Body::empty())
+            .log_500("Build media response error")?),
+    }
+}
+
+/// Header carrying the hex SHA-256 of an uploaded part
+const CONTENT_SHA256_HEADER: &str = "x-content-sha256";
+
+/// Normalize a client-supplied hex SHA-256; None if it isn't 64 hex chars
+fn parse_sha256(value: &str) -> Option<String> {
+    let value = value.trim();
+    (value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| value.to_ascii_lowercase())
 }
 
 /// POST /captures/batch - Upload multiple captures in one request
@@ -321,23 +1029,68 @@ async fn serve_media(
 /// - Multiple "file" fields containing the media bytes
 /// - Each file should have proper content-type (image/* or video/*)
 /// - X-Interval-ID header for all captures
+/// - Optional X-Session-ID header linking captures to an open work session
+/// - Optional X-Content-SHA256, either per part or on the request as a
+///   comma-separated list in part order; mismatched parts are rejected
+/// - Optional Idempotency-Key; a retried batch returns the original result
 async fn capture_batch(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-    mut multipart: Multipart,
-) -> Result<(StatusCode, Json<BatchCaptureResponse>), StatusCode> {
-    let user_id = get_user_id_from_bearer(&state.db, &headers).await?;
+    multipart: Multipart,
+) -> Result<Response, ApiError> {
+    let identity = get_daemon_identity(&state.db, &headers).await?;
 
     // Per-user rate limiting
-    if !DAEMON_RATE_LIMITER.check(user_id) {
-        return Err(StatusCode::TOO_MANY_REQUESTS);
+    if !DAEMON_RATE_LIMITER.check(identity.user_id) {
+        return Err(ApiError::RateLimited);
     }
 
+    let idempotency_key = idempotency::key_from_headers(&headers)?;
+    idempotency::run(
+        &state.db,
+        identity.user_id,
+        idempotency_key.as_deref(),
+        "POST /captures/batch",
+        StatusCode::CREATED,
+        ingest_batch(&state, identity, &headers, multipart),
+    )
+    .await
+}
+
+async fn ingest_batch(
+    state: &AppState,
+    DaemonIdentity { user_id, device_id }: DaemonIdentity,
+    headers: &HeaderMap,
+    mut multipart: Multipart,
+) -> Result<BatchCaptureResponse, ApiError> {
     let interval_id: i64 = headers
         .get("x-interval-id")
         .and_then(|v| v.to_str().ok())
         .and_then(|v| v.parse().ok())
-        .ok_or(StatusCode::BAD_REQUEST)?;
+        .filter(|id| *id >= 0)
+        .ok_or_else(|| {
+            ApiError::BadRequest("Missing or invalid X-Interval-ID header".to_string())
+        })?;
+
+    let session_id = parse_session_header(headers)?;
+    if let Some(session_id) = session_id {
+        ensure_session_owned(&state.db, session_id, user_id, device_id).await?;
+    }
+
+    let mut allowance = quota::capture_allowance(&state.db, user_id)
+        .await
+        .log_500("Load capture quota error")?;
+    allowance.check().map_err(ApiError::QuotaExceeded)?;
+
+    // Storage paths are bucketed by the user's local day
+    let tz = timezone::user_timezone(&state.db, user_id).await;
+
+    // Optional request-level checksums: comma-separated, one per part in order
+    let request_checksums: Vec<&str> = headers
+        .get(CONTENT_SHA256_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').map(str::trim).collect())
+        .unwrap_or_default();
 
     let mut ids = Vec::new();
     let mut failed = 0usize;
@@ -347,27 +1100,54 @@ async fn capture_batch(
     while let Some(field) = multipart
         .next_field()
         .await
-        .log_status("Multipart field error", StatusCode::BAD_REQUEST)?
+        .map_err(|e| ApiError::BadRequest(format!("Malformed multipart body: {}", e)))?
     {
         let current_index = field_index;
         field_index += 1;
 
+        // Skipped without reading, so an oversized batch can't store more than the cap
+        if current_index >= config::get().limits.capture_parts {
+            failed += 1;
+            continue;
+        }
+
         let content_type = field
             .content_type()
             .map(|s| s.to_string())
             .unwrap_or_else(|| "application/octet-stream".to_string());
 
-        let media_type = if content_type.starts_with("image/") {
-            "image"
-        } else if content_type.starts_with("video/") {
-            "video"
-        } else {
-            eprintln!(
-                "[capture_batch] Skipping unsupported content type: {}",
-                content_type
-            );
-            failed += 1;
-            continue;
+        // Only formats get_extension knows; anything else would be stored as .bin
+        let media_type = match get_extension(&content_type) {
+            "bin" => {
+                eprintln!(
+                    "[capture_batch] Skipping unsupported content type: {}",
+                    content_type
+                );
+                failed += 1;
+                continue;
+            }
+            _ if content_type.starts_with("image/") => "image",
+            _ => "video",
+        };
+
+        // Per-part header wins over the request-level list
+        let expected_sha256 = match field
+            .headers()
+            .get(CONTENT_SHA256_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .or_else(|| request_checksums.get(current_index).copied())
+            .map(parse_sha256)
+        {
+            Some(Some(sha)) => Some(sha),
+            Some(None) => {
+                eprintln!(
+                    "[capture_batch] Malformed {} for part {}",
+                    CONTENT_SHA256_HEADER, current_index
+                );
+                failed += 1;
+                continue;
+            }
+            None => None,
         };
 
         let body = match field.bytes().await {
@@ -379,8 +1159,30 @@ async fn capture_batch(
             }
         };
 
+        let sha256 = storage::sha256_hex(&body);
+        if let Some(expected) = expected_sha256
+            && expected != sha256
+        {
+            eprintln!(
+                "[capture_batch] Checksum mismatch for part {} (expected {}, got {})",
+                current_index, expected, sha256
+            );
+            failed += 1;
+            continue;
+        }
+
+        // Parts past the quota fail; the response's quota tells the daemon to slow down
+        if !allowance.admit(body.len() as u64) {
+            eprintln!(
+                "[capture_batch] Part {} exceeds the remaining quota for user {}",
+                current_index, user_id
+            );
+            failed += 1;
+            continue;
+        }
+
         let now = Utc::now();
-        let day_bucket = now.format("%Y-%m-%d").to_string();
+        let day_bucket = timezone::local_date(now, tz).format("%Y-%m-%d").to_string();
         let timestamp = now.timestamp_millis();
         let ext = get_extension(&content_type);
 
@@ -390,76 +1192,45 @@ async fn capture_batch(
         );
 
         // Write to local storage or GCS
-        let write_result = if let Some(local_path) = &state.local_storage_path {
-            let full_path = local_path.join(&relative_path);
-            if let Some(parent) = full_path.parent() {
-                if let Err(e) = tokio::fs::create_dir_all(parent).await {
-                    eprintln!(
-                        "[capture_batch] Failed to create directory {:?}: {}",
-                        parent, e
-                    );
-                    failed += 1;
-                    continue;
-                }
-            }
-            match tokio::fs::write(&full_path, &body).await {
-                Ok(()) => {
-                    println!(
-                        "[capture_batch] LOCAL: Saved {} bytes to {:?}",
-                        body.len(),
-                        full_path
-                    );
-                    Ok(())
-                }
-                Err(e) => {
-                    eprintln!(
-                        "[capture_batch] Failed to write file {:?}: {}",
-                        full_path, e
-                    );
-                    Err(e)
-                }
-            }
-        } else if let Some(ref gcs) = state.gcs {
-            let bucket = format!("projects/_/buckets/{}", BUCKET_NAME);
-            match gcs
-                .write_object(&bucket, &relative_path, body.clone())
-                .send_buffered()
-                .await
-            {
-                Ok(_) => {
-                    println!("[capture_batch] GCS: Uploaded to {}", relative_path);
-                    Ok(())
-                }
-                Err(e) => {
-                    eprintln!("[capture_batch] GCS upload failed: {}", e);
-                    Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        e.to_string(),
-                    ))
-                }
-            }
-        } else {
-            eprintln!("[capture_batch] No storage backend configured");
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "No storage backend configured",
-            ))
-        };
-
-        if write_result.is_err() {
+        if let Err(e) = state.store.put(&relative_path, &body).await {
+            eprintln!("[capture_batch] Failed to store {}: {}", relative_path, e);
             failed += 1;
             continue;
         }
+        println!(
+            "[capture_batch] Stored {} bytes at {}",
+            body.len(),
+            relative_path
+        );
+
+        // Read local files back to catch short or corrupt writes. GCS uploads are
+        // already CRC32C-checked end to end by the client library.
+        if state.local_storage_path.is_some() {
+            let written = state.store.get(&relative_path).await;
+            if written.map(|data| storage::sha256_hex(&data)).ok().as_ref() != Some(&sha256) {
+                eprintln!(
+                    "[capture_batch] Stored file {} failed checksum verification",
+                    relative_path
+                );
+                let _ = state.store.delete(&relative_path).await;
+                failed += 1;
+                continue;
+            }
+        }
 
         // Store reference in DB
         match captures_domain::insert_capture(
             &state.db,
             interval_id,
+            session_id,
+            device_id,
             user_id,
             media_type,
             &content_type,
             &relative_path,
             now,
+            body.len() as i64,
+            &sha256,
         )
         .await
         {
@@ -469,33 +1240,17 @@ async fn capture_batch(
             }
             Err(e) => {
                 eprintln!("[capture_batch] DB insert failed: {}", e);
-                // Clean up orphaned file on DB failure
-                if let Some(local_path) = &state.local_storage_path {
-                    let full_path = local_path.join(&relative_path);
-                    if let Err(cleanup_err) = tokio::fs::remove_file(&full_path).await {
-                        eprintln!(
-                            "[capture_batch] Failed to clean up orphaned file {:?}: {}",
-                            full_path, cleanup_err
-                        );
-                    } else {
-                        eprintln!("[capture_batch] Cleaned up orphaned file: {:?}", full_path);
-                    }
+                // Clean up orphaned object on DB failure
+                if let Err(cleanup_err) = state.store.delete(&relative_path).await {
+                    eprintln!(
+                        "[capture_batch] Failed to clean up orphaned object {}: {}",
+                        relative_path, cleanup_err
+                    );
                 } else {
-                    // For GCS, attempt to delete the orphaned object
-                    let client = cloud_storage::Client::default();
-                    if let Err(cleanup_err) =
-                        client.object().delete(BUCKET_NAME, &relative_path).await
-                    {
-                        eprintln!(
-                            "[capture_batch] Failed to clean up orphaned GCS object {}: {}",
-                            relative_path, cleanup_err
-                        );
-                    } else {
-                        eprintln!(
-                            "[capture_batch] Cleaned up orphaned GCS object: {}",
-                            relative_path
-                        );
-                    }
+                    eprintln!(
+                        "[capture_batch] Cleaned up orphaned object: {}",
+                        relative_path
+                    );
                 }
                 failed += 1;
             }
@@ -511,30 +1266,55 @@ async fn capture_batch(
         failed
     );
 
-    Ok((
-        StatusCode::CREATED,
-        Json(BatchCaptureResponse {
-            ids: ids.clone(),
-            uploaded: ids.len(),
-            failed,
-            successful_indices,
-        }),
-    ))
+    Ok(BatchCaptureResponse {
+        ids: ids.clone(),
+        uploaded: ids.len(),
+        failed,
+        successful_indices,
+        quota: allowance.remaining().clone(),
+    })
+}
+
+impl Validate for Vec<Activity> {
+    fn validate(&self) -> Result<(), ApiError> {
+        check_max_items(
+            self,
+            config::get().limits.activities_per_batch,
+            "activity batch",
+        )?;
+        if self.iter().any(|a| a.interval_id < 0) {
+            return Err(ApiError::Unprocessable(
+                "intervalId must be non-negative".to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 async fn activity(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-    Json(activity_list): Json<Vec<Activity>>,
-) -> Result<StatusCode, StatusCode> {
+    ValidBody(activity_list): ValidBody<Vec<Activity>>,
+) -> Result<StatusCode, ApiError> {
     // Authenticate via bearer token
-    let user_id = get_user_id_from_bearer(&state.db, &headers).await?;
+    let DaemonIdentity { user_id, device_id } = get_daemon_identity(&state.db, &headers).await?;
 
     // Per-user rate limiting
     if !DAEMON_RATE_LIMITER.check(user_id) {
-        return Err(StatusCode::TOO_MANY_REQUESTS);
+        return Err(ApiError::RateLimited);
+    }
+
+    let mut session_ids: Vec<i64> = activity_list.iter().filter_map(|a| a.session_id).collect();
+    session_ids.sort_unstable();
+    session_ids.dedup();
+
+    for session_id in session_ids {
+        ensure_session_owned(&state.db, session_id, user_id, device_id).await?;
     }
 
+    // Foreground switches with the project inferred from each window title
+    let mut switches: Vec<(DateTime<Utc>, Option<ProjectHint>)> = Vec::new();
+
     for activity in activity_list {
         let (event_type, application, window) = match &activity.event {
             ActivityEvent::ForegroundSwitch {
@@ -547,19 +1327,226 @@ async fn activity(
             ),
             ActivityEvent::MouseClick => ("MouseClick", None, None),
         };
+        let project = application
+            .zip(window)
+            .and_then(|(app, title)| project_inference::infer_project(app, title));
 
         activities::insert_activity(
             &state.db,
             user_id,
             activity.timestamp,
             activity.interval_id,
+            activity.session_id,
+            device_id,
             event_type,
             application,
             window,
+            project.as_ref().map(ProjectHint::key).as_deref(),
         )
         .await
         .log_500("Insert activity error")?;
+
+        if event_type == "ForegroundSwitch" {
+            switches.push((activity.timestamp, project));
+        }
+    }
+
+    record_projects(&state.db, user_id, device_id, switches).await;
+
+    Ok(StatusCode::CREATED)
+}
+
+/// Credit foreground time to the projects inferred from window titles. The
+/// first slice runs from the device's previous switch, reported in an earlier
+/// batch. Project tracking is best effort; the activities are already stored.
+async fn record_projects(
+    db: &PgPool,
+    user_id: i64,
+    device_id: Option<i64>,
+    mut switches: Vec<(DateTime<Utc>, Option<ProjectHint>)>,
+) {
+    switches.sort_by_key(|(at, _)| *at);
+    let Some(&(first, _)) = switches.first() else {
+        return;
+    };
+    match activities::last_foreground_switch(
+        db,
+        user_id,
+        device_id,
+        first - Duration::days(1),
+        first,
+    )
+    .await
+    {
+        Ok(Some((at, application, window))) => {
+            switches.insert(
+                0,
+                (at, project_inference::infer_project(&application, &window)),
+            );
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[activity] User {} - previous switch error: {}", user_id, e),
+    }
+
+    let timestamps: Vec<DateTime<Utc>> = switches.iter().map(|(at, _)| *at).collect();
+    let slices = project_inference::slice_secs(&timestamps);
+    for ((seen_at, hint), secs) in switches.iter().zip(slices) {
+        let Some(hint) = hint else {
+            continue;
+        };
+        if let Err(e) = projects::record_activity(
+            db,
+            user_id,
+            &hint.name,
+            hint.branch.as_deref(),
+            *seen_at,
+            secs,
+        )
+        .await
+        {
+            eprintln!("[activity] User {} - record project error: {}", user_id, e);
+        }
+    }
+}
+
+impl FromProto for Vec<Activity> {
+    type Message = protobuf::ActivityBatch;
+
+    fn from_proto(batch: protobuf::ActivityBatch) -> Result<Self, ApiError> {
+        use protobuf::activity_event::Event;
+
+        batch
+            .events
+            .into_iter()
+            .map(|event| {
+                let kind = match event.event {
+                    Some(Event::ForegroundSwitch(switch)) => ActivityEvent::ForegroundSwitch {
+                        new_active: switch.new_active,
+                        window_title: switch.window_title,
+                    },
+                    Some(Event::MouseClick(_)) => ActivityEvent::MouseClick,
+                    None => {
+                        return Err(ApiError::Unprocessable("event is required".to_string()));
+                    }
+                };
+                Ok(Activity {
+                    timestamp: protobuf::timestamp_from_millis(event.timestamp_ms, "timestampMs")?,
+                    interval_id: event.interval_id,
+                    session_id: event.session_id,
+                    event: kind,
+                })
+            })
+            .collect()
+    }
+}
+
+impl FromProto for Vec<ActivitySummary> {
+    type Message = protobuf::ActivitySummaryBatch;
+
+    fn from_proto(batch: protobuf::ActivitySummaryBatch) -> Result<Self, ApiError> {
+        batch
+            .summaries
+            .into_iter()
+            .map(|summary| {
+                Ok(ActivitySummary {
+                    interval_id: summary.interval_id,
+                    session_id: summary.session_id,
+                    period_start: protobuf::timestamp_from_millis(
+                        summary.period_start_ms,
+                        "periodStartMs",
+                    )?,
+                    period_end: protobuf::timestamp_from_millis(
+                        summary.period_end_ms,
+                        "periodEndMs",
+                    )?,
+                    top_app: summary.top_app,
+                    app_switches: protobuf::count_from_u32(summary.app_switches, "appSwitches")?,
+                    clicks: protobuf::count_from_u32(summary.clicks, "clicks")?,
+                    keypresses: protobuf::count_from_u32(summary.keypresses, "keypresses")?,
+                    active_secs: protobuf::count_from_u32(summary.active_secs, "activeSecs")?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Validate for Vec<ActivitySummary> {
+    fn validate(&self) -> Result<(), ApiError> {
+        check_max_items(
+            self,
+            config::get().limits.activities_per_batch,
+            "activity summary batch",
+        )?;
+        for summary in self {
+            let period_secs = (summary.period_end - summary.period_start).num_seconds();
+            if summary.interval_id < 0 {
+                return Err(ApiError::Unprocessable(
+                    "intervalId must be non-negative".to_string(),
+                ));
+            }
+            if period_secs <= 0 {
+                return Err(ApiError::Unprocessable(
+                    "periodEnd must be after periodStart".to_string(),
+                ));
+            }
+            if summary.app_switches < 0 || summary.clicks < 0 || summary.keypresses < 0 {
+                return Err(ApiError::Unprocessable(
+                    "counts must be non-negative".to_string(),
+                ));
+            }
+            if summary.active_secs < 0 || i64::from(summary.active_secs) > period_secs {
+                return Err(ApiError::Unprocessable(
+                    "activeSecs must be between 0 and the period length".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// POST /activity/summary - Store per-interval activity rollups from the daemon
+async fn activity_summary(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ValidBody(summaries): ValidBody<Vec<ActivitySummary>>,
+) -> Result<StatusCode, ApiError> {
+    let DaemonIdentity { user_id, device_id } = get_daemon_identity(&state.db, &headers).await?;
+
+    if !DAEMON_RATE_LIMITER.check(user_id) {
+        return Err(ApiError::RateLimited);
+    }
+
+    let mut session_ids: Vec<i64> = summaries.iter().filter_map(|s| s.session_id).collect();
+    session_ids.sort_unstable();
+    session_ids.dedup();
+
+    for session_id in session_ids {
+        ensure_session_owned(&state.db, session_id, user_id, device_id).await?;
+    }
+
+    // All or nothing, so a retried batch doesn't leave duplicates behind
+    let mut tx = state.db.begin().await.log_500("Begin transaction error")?;
+    for summary in &summaries {
+        activities::insert_summary(
+            &mut *tx,
+            activities::NewActivitySummary {
+                user_id,
+                device_id,
+                session_id: summary.session_id,
+                interval_id: summary.interval_id,
+                period_start: summary.period_start,
+                period_end: summary.period_end,
+                top_app: summary.top_app.as_deref(),
+                app_switches: summary.app_switches,
+                clicks: summary.clicks,
+                keypresses: summary.keypresses,
+                active_secs: summary.active_secs,
+            },
+        )
+        .await
+        .log_500("Insert activity summary error")?;
     }
+    tx.commit().await.log_500("Commit transaction error")?;
 
     Ok(StatusCode::CREATED)
 }
@@ -0,0 +1,138 @@
+//! Review comment endpoints on tweet collateral (/content/:id/comments)
+//!
+//! Comments are threaded one level deep: a reply to a reply joins the top-level
+//! comment's thread, and resolving the top-level comment resolves the thread.
+//! Unresolved threads are counted in GET /content. @mentions are recorded on
+//! each comment; collateral is only visible to its owner today, so there is no
+//! one else to notify yet.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, patch},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::domain::comments::{self, MAX_COMMENT_LEN};
+use crate::routes::auth::AuthUser;
+use crate::services::error::{ApiError, LogErr};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/content/{id}/comments",
+            get(list_comments).post(create_comment),
+        )
+        .route("/content/{id}/comments/{comment_id}", patch(update_comment))
+}
+
+/// Comment API response DTO
+#[derive(Serialize)]
+struct CommentResponse {
+    id: i64,
+    parent_id: Option<i64>,
+    author: String,
+    body: String,
+    mentions: Vec<String>,
+    created_at: DateTime<Utc>,
+    resolved_at: Option<DateTime<Utc>>,
+}
+
+impl From<comments::Comment> for CommentResponse {
+    fn from(c: comments::Comment) -> Self {
+        Self {
+            id: c.id,
+            parent_id: c.parent_id,
+            author: c.author_username,
+            body: c.body,
+            mentions: c.mentions,
+            created_at: c.created_at,
+            resolved_at: c.resolved_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateCommentRequest {
+    body: String,
+    /// Comment to reply to
+    parent_id: Option<i64>,
+}
+
+/// POST /content/:id/comments - Comment on (or reply to a comment on) a tweet
+async fn create_comment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_collateral_id): Path<i64>,
+    Json(req): Json<CreateCommentRequest>,
+) -> Result<(StatusCode, Json<CommentResponse>), ApiError> {
+    let body = req.body.trim();
+    let len = body.chars().count();
+    if len == 0 || len > MAX_COMMENT_LEN {
+        return Err(ApiError::Unprocessable(format!(
+            "body must be 1 to {} characters",
+            MAX_COMMENT_LEN
+        )));
+    }
+
+    let comment = comments::insert_comment(
+        &state.db,
+        user_id,
+        tweet_collateral_id,
+        req.parent_id,
+        body,
+        &comments::extract_mentions(body),
+    )
+    .await
+    .log_500("Insert comment error")?
+    .ok_or(ApiError::NotFound(if req.parent_id.is_some() {
+        "Comment"
+    } else {
+        "Tweet"
+    }))?;
+
+    Ok((StatusCode::CREATED, Json(comment.into())))
+}
+
+/// GET /content/:id/comments - A tweet's comments, oldest first
+async fn list_comments(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_collateral_id): Path<i64>,
+) -> Result<Json<Vec<CommentResponse>>, ApiError> {
+    let comments = comments::list_comments(&state.db, user_id, tweet_collateral_id)
+        .await
+        .log_500("List comments error")?;
+    Ok(Json(comments.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Deserialize)]
+struct UpdateCommentRequest {
+    resolved: bool,
+}
+
+/// PATCH /content/:id/comments/:comment_id - Resolve or reopen a comment thread
+async fn update_comment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path((tweet_collateral_id, comment_id)): Path<(i64, i64)>,
+    Json(req): Json<UpdateCommentRequest>,
+) -> Result<StatusCode, ApiError> {
+    let updated = comments::set_resolved(
+        &state.db,
+        user_id,
+        tweet_collateral_id,
+        comment_id,
+        req.resolved,
+    )
+    .await
+    .log_500("Resolve comment error")?;
+    if !updated {
+        return Err(ApiError::NotFound("Comment"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,275 @@
+//! A/B copy experiment endpoints (/tweets/:id/experiment, /insights/experiments)
+//!
+//! PUT attaches two labelled copy variants to a pending draft and makes
+//! variant A the draft's text, so publishing posts A. The metrics sync
+//! (services::copy_experiments) measures the post 24 hours later.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::agent::injection::{MAX_TWEET_CHARS, tweet_length};
+use crate::domain::copy_experiments::{self, CopyExperiment, NewExperiment};
+use crate::domain::twitter::queries::threads as thread_queries;
+use crate::routes::auth::AuthUser;
+use crate::services::copy_experiments::{self as experiments, MIN_EXPERIMENTS_PER_STYLE};
+use crate::services::copy_filter::CopyFilter;
+use crate::services::error::{ApiError, LogErr};
+
+/// Longest style label
+const MAX_STYLE_CHARS: usize = 60;
+/// Recent measured experiments listed with the insights
+const RECENT_EXPERIMENTS: usize = 20;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/tweets/{id}/experiment",
+            get(get_experiment)
+                .put(put_experiment)
+                .delete(delete_experiment),
+        )
+        .route("/insights/experiments", get(get_insights))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Variant {
+    text: String,
+    /// What the copy tries, e.g. "question hook" or "result first"
+    style: String,
+}
+
+#[derive(Deserialize)]
+struct ExperimentRequest {
+    /// Posted when the draft is published
+    a: Variant,
+    b: Variant,
+}
+
+#[derive(Serialize)]
+struct EngagementResponse {
+    likes: i64,
+    reposts: i64,
+    replies: i64,
+    quotes: i64,
+    total: i64,
+}
+
+#[derive(Serialize)]
+struct ExperimentResponse {
+    tweet_id: i64,
+    a: Variant,
+    b: Variant,
+    /// Variant A's engagement 24 hours after posting; None until measured
+    engagement: Option<EngagementResponse>,
+    measured_at: Option<DateTime<Utc>>,
+    /// Why the last measurement attempt failed
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<CopyExperiment> for ExperimentResponse {
+    fn from(e: CopyExperiment) -> Self {
+        let engagement = e.measured_at.map(|_| EngagementResponse {
+            likes: e.like_count.unwrap_or(0),
+            reposts: e.retweet_count.unwrap_or(0),
+            replies: e.reply_count.unwrap_or(0),
+            quotes: e.quote_count.unwrap_or(0),
+            total: experiments::engagement(&e),
+        });
+        Self {
+            tweet_id: e.tweet_collateral_id,
+            a: Variant {
+                text: e.variant_a,
+                style: e.style_a,
+            },
+            b: Variant {
+                text: e.variant_b,
+                style: e.style_b,
+            },
+            engagement,
+            measured_at: e.measured_at,
+            last_error: e.last_error,
+            created_at: e.created_at,
+        }
+    }
+}
+
+/// Trimmed text and a lowercased, whitespace-collapsed style, so "Question
+/// hook" and "question  hook" count as one style
+fn checked_variant(name: &str, variant: &Variant) -> Result<Variant, ApiError> {
+    let text = variant.text.trim();
+    if text.is_empty() || tweet_length(text) > MAX_TWEET_CHARS {
+        return Err(ApiError::Unprocessable(format!(
+            "{}.text must be 1 to {} characters",
+            name, MAX_TWEET_CHARS
+        )));
+    }
+    let style = variant
+        .style
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    if style.is_empty() || style.chars().count() > MAX_STYLE_CHARS {
+        return Err(ApiError::Unprocessable(format!(
+            "{}.style must be 1 to {} characters",
+            name, MAX_STYLE_CHARS
+        )));
+    }
+    Ok(Variant {
+        text: text.to_string(),
+        style,
+    })
+}
+
+/// GET /tweets/:id/experiment - The draft's experiment and, once measured, its engagement
+async fn get_experiment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_id): Path<i64>,
+) -> Result<Json<ExperimentResponse>, ApiError> {
+    let experiment = copy_experiments::get_experiment(&state.db, user_id, tweet_id)
+        .await
+        .log_500("Get copy experiment error")?
+        .ok_or(ApiError::NotFound("Experiment"))?;
+    Ok(Json(experiment.into()))
+}
+
+/// PUT /tweets/:id/experiment - Attach two copy variants to a pending draft,
+/// replacing any earlier pair. The draft's text becomes variant A.
+async fn put_experiment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_id): Path<i64>,
+    Json(req): Json<ExperimentRequest>,
+) -> Result<Json<ExperimentResponse>, ApiError> {
+    let a = checked_variant("a", &req.a)?;
+    let b = checked_variant("b", &req.b)?;
+    if a.text == b.text {
+        return Err(ApiError::Unprocessable(
+            "the two variants must have different text".to_string(),
+        ));
+    }
+    if a.style == b.style {
+        return Err(ApiError::Unprocessable(
+            "the two variants must try different styles".to_string(),
+        ));
+    }
+
+    let review_reason = CopyFilter::for_user(&state.db, user_id)
+        .await
+        .review_reason([a.text.as_str()]);
+
+    let mut tx = state.db.begin().await.log_500("Begin transaction error")?;
+    let experiment = copy_experiments::upsert_experiment(
+        &mut *tx,
+        user_id,
+        tweet_id,
+        &NewExperiment {
+            variant_a: &a.text,
+            style_a: &a.style,
+            variant_b: &b.text,
+            style_b: &b.style,
+        },
+    )
+    .await
+    .log_500("Save copy experiment error")?
+    .ok_or(ApiError::NotFound("Pending tweet"))?;
+    thread_queries::update_tweet_collateral(
+        &mut *tx,
+        tweet_id,
+        user_id,
+        thread_queries::CollateralUpdate {
+            text: Some(&a.text),
+            review: Some(review_reason.as_deref()),
+            ..Default::default()
+        },
+    )
+    .await
+    .log_500("Update tweet text error")?;
+    tx.commit().await.log_500("Commit transaction error")?;
+
+    Ok(Json(experiment.into()))
+}
+
+/// DELETE /tweets/:id/experiment - Drop the experiment from a draft that hasn't
+/// been posted. The draft keeps variant A's text.
+async fn delete_experiment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let deleted = copy_experiments::delete_experiment(&state.db, user_id, tweet_id)
+        .await
+        .log_500("Delete copy experiment error")?;
+    if !deleted {
+        return Err(ApiError::NotFound("Pending experiment"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize)]
+struct StyleResponse {
+    style: String,
+    experiments: usize,
+    avg_engagement: f64,
+}
+
+#[derive(Serialize)]
+struct InsightsResponse {
+    /// Posted styles, best average engagement first
+    styles: Vec<StyleResponse>,
+    /// Style ahead of the rest once two styles have enough measurements
+    winner: Option<String>,
+    /// A line the user can add to their style nudges
+    suggestion: Option<String>,
+    /// Measurements each style needs before a winner is named
+    min_experiments_per_style: usize,
+    /// Most recently measured experiments
+    recent: Vec<ExperimentResponse>,
+}
+
+/// GET /insights/experiments - Which copy styles earn the most engagement
+async fn get_insights(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<InsightsResponse>, ApiError> {
+    let measured = copy_experiments::list_measured(&state.db, user_id)
+        .await
+        .log_500("List copy experiments error")?;
+    let stats = experiments::style_stats(&measured);
+    let winner = experiments::winner(&stats).map(|s| s.style.clone());
+    let suggestion = winner.as_ref().map(|style| {
+        format!(
+            "Lean toward \"{}\" copy; it has earned the most engagement in your experiments.",
+            style
+        )
+    });
+
+    Ok(Json(InsightsResponse {
+        styles: stats
+            .into_iter()
+            .map(|s| StyleResponse {
+                style: s.style,
+                experiments: s.experiments,
+                avg_engagement: s.avg_engagement,
+            })
+            .collect(),
+        winner,
+        suggestion,
+        min_experiments_per_style: MIN_EXPERIMENTS_PER_STYLE,
+        recent: measured
+            .into_iter()
+            .take(RECENT_EXPERIMENTS)
+            .map(Into::into)
+            .collect(),
+    }))
+}
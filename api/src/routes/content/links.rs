@@ -0,0 +1,280 @@
+//! Click-tracked link endpoints (/content/:id/links, /content/:id/clicks, /l/:slug)
+//!
+//! A tracked link stands in for a URL in a tweet. GET /l/:slug redirects to the
+//! target and logs the click with only coarse details: when, the referring
+//! host, and whether the browser looked like desktop, mobile or a bot.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
+    routing::get,
+};
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, Utc};
+use rand::Rng;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::domain::links;
+use crate::routes::auth::AuthUser;
+use crate::services::error::{ApiError, LogErr};
+use crate::services::timezone;
+use crate::services::validation::is_public_https_url;
+
+/// Longest target URL
+const MAX_TARGET_URL_LEN: usize = 2048;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/content/{id}/links", get(list_links).post(create_link))
+        .route("/content/{id}/clicks", get(get_clicks))
+        .route("/l/{slug}", get(follow_link))
+}
+
+/// New random slug (8 URL-safe characters)
+fn generate_slug() -> String {
+    let bytes: [u8; 6] = rand::rng().random();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Host of the Referer header, without scheme, path or query
+fn referrer_host(headers: &HeaderMap) -> Option<String> {
+    let referer = headers.get(header::REFERER)?.to_str().ok()?;
+    let host = Url::parse(referer).ok()?.host_str()?.to_ascii_lowercase();
+    Some(host.trim_start_matches("www.").to_string())
+}
+
+const BOT_MARKERS: &[&str] = &["bot", "crawler", "spider", "preview", "facebookexternalhit"];
+const MOBILE_MARKERS: &[&str] = &["mobile", "android", "iphone", "ipad"];
+const DESKTOP_MARKERS: &[&str] = &["windows", "macintosh", "x11", "linux", "cros"];
+
+/// Coarse class of a User-Agent: "bot", "mobile", "desktop" or "other"
+fn agent_class(user_agent: Option<&str>) -> &'static str {
+    let ua = user_agent.unwrap_or_default().to_ascii_lowercase();
+    let matches = |markers: &[&str]| markers.iter().any(|m| ua.contains(m));
+    if matches(BOT_MARKERS) {
+        "bot"
+    } else if matches(MOBILE_MARKERS) {
+        "mobile"
+    } else if matches(DESKTOP_MARKERS) {
+        "desktop"
+    } else {
+        "other"
+    }
+}
+
+/// Tracked link API response DTO
+#[derive(Serialize)]
+struct LinkResponse {
+    id: i64,
+    slug: String,
+    /// Path of the redirect, relative to the API base URL
+    url: String,
+    target_url: String,
+    clicks: i64,
+    created_at: DateTime<Utc>,
+}
+
+impl From<links::TrackedLink> for LinkResponse {
+    fn from(l: links::TrackedLink) -> Self {
+        Self {
+            id: l.id,
+            url: format!("/l/{}", l.slug),
+            slug: l.slug,
+            target_url: l.target_url,
+            clicks: l.clicks,
+            created_at: l.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateLinkRequest {
+    target_url: String,
+}
+
+/// POST /content/:id/links - Create a tracked link to use in a tweet
+async fn create_link(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_collateral_id): Path<i64>,
+    Json(req): Json<CreateLinkRequest>,
+) -> Result<(StatusCode, Json<LinkResponse>), ApiError> {
+    let target_url = req.target_url.trim();
+    if target_url.len() > MAX_TARGET_URL_LEN || !is_public_https_url(target_url) {
+        return Err(ApiError::Unprocessable(
+            "target_url must be an https URL on a public host".to_string(),
+        ));
+    }
+
+    let link = links::insert_link(
+        &state.db,
+        user_id,
+        tweet_collateral_id,
+        &generate_slug(),
+        target_url,
+    )
+    .await
+    .log_500("Insert tracked link error")?
+    .ok_or(ApiError::NotFound("Tweet"))?;
+
+    Ok((StatusCode::CREATED, Json(link.into())))
+}
+
+/// GET /content/:id/links - A tweet's tracked links with click counts
+async fn list_links(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_collateral_id): Path<i64>,
+) -> Result<Json<Vec<LinkResponse>>, ApiError> {
+    let links = links::list_links(&state.db, user_id, tweet_collateral_id)
+        .await
+        .log_500("List tracked links error")?;
+    Ok(Json(links.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Serialize)]
+struct DayClicks {
+    date: NaiveDate,
+    clicks: i64,
+}
+
+#[derive(Serialize)]
+struct ReferrerClicks {
+    /// None for direct visits and apps that send no Referer
+    referrer: Option<String>,
+    clicks: i64,
+}
+
+#[derive(Serialize)]
+struct AgentClicks {
+    agent: String,
+    clicks: i64,
+}
+
+#[derive(Serialize)]
+struct ClicksResponse {
+    total: i64,
+    /// Days with clicks, in the user's time zone, oldest first
+    daily: Vec<DayClicks>,
+    referrers: Vec<ReferrerClicks>,
+    agents: Vec<AgentClicks>,
+    links: Vec<LinkResponse>,
+}
+
+/// GET /content/:id/clicks - Click analytics for a tweet's tracked links
+async fn get_clicks(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_collateral_id): Path<i64>,
+) -> Result<Json<ClicksResponse>, ApiError> {
+    let tz = timezone::user_timezone(&state.db, user_id).await;
+    let links = links::list_links(&state.db, user_id, tweet_collateral_id)
+        .await
+        .log_500("List tracked links error")?;
+    let daily = links::daily_clicks(&state.db, user_id, tweet_collateral_id, tz.name())
+        .await
+        .log_500("Daily clicks error")?;
+    let referrers = links::clicks_by_referrer(&state.db, user_id, tweet_collateral_id)
+        .await
+        .log_500("Clicks by referrer error")?;
+    let agents = links::clicks_by_agent(&state.db, user_id, tweet_collateral_id)
+        .await
+        .log_500("Clicks by agent error")?;
+
+    Ok(Json(ClicksResponse {
+        total: links.iter().map(|l| l.clicks).sum(),
+        daily: daily
+            .into_iter()
+            .map(|d| DayClicks {
+                date: d.day,
+                clicks: d.clicks,
+            })
+            .collect(),
+        referrers: referrers
+            .into_iter()
+            .map(|r| ReferrerClicks {
+                referrer: r.label,
+                clicks: r.clicks,
+            })
+            .collect(),
+        agents: agents
+            .into_iter()
+            .map(|a| AgentClicks {
+                agent: a.label.unwrap_or_default(),
+                clicks: a.clicks,
+            })
+            .collect(),
+        links: links.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /l/:slug - Follow a tracked link (no auth)
+/// A failed click log still redirects; the visitor shouldn't pay for it.
+async fn follow_link(
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let link = links::get_target(&state.db, &slug)
+        .await
+        .log_500("Get tracked link error")?
+        .ok_or(ApiError::NotFound("Link"))?;
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok());
+    if let Err(e) = links::record_click(
+        &state.db,
+        link.id,
+        referrer_host(&headers).as_deref(),
+        agent_class(user_agent),
+    )
+    .await
+    {
+        eprintln!("[links] Click log error for link {}: {}", link.id, e);
+    }
+
+    // Every click must reach us to be counted
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Redirect::temporary(&link.target_url),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_class() {
+        assert_eq!(
+            agent_class(Some(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) Mobile/15E148"
+            )),
+            "mobile"
+        );
+        assert_eq!(
+            agent_class(Some(
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15"
+            )),
+            "desktop"
+        );
+        assert_eq!(agent_class(Some("Twitterbot/1.0")), "bot");
+        assert_eq!(agent_class(Some("curl/8.4.0")), "other");
+        assert_eq!(agent_class(None), "other");
+    }
+
+    #[test]
+    fn test_referrer_host() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(referrer_host(&headers), None);
+        headers.insert(header::REFERER, "https://www.t.co/abc?x=1".parse().unwrap());
+        assert_eq!(referrer_host(&headers), Some("t.co".to_string()));
+    }
+}
@@ -1,33 +1,48 @@
 //! Content endpoints - unified view of content items by platform
 
+pub mod comments;
+pub mod experiments;
+pub mod links;
 pub mod twitter;
+pub mod views;
 
 use axum::{
     Json, Router,
     extract::{Query, State},
-    http::StatusCode,
     routing::get,
 };
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use super::auth::AuthUser;
 use crate::AppState;
-use crate::domain::content;
-use twitter::{ThreadWithTweetsResponse, TweetResponse};
+use crate::domain::content::ContentSort;
+use crate::domain::content_views::ViewFilters;
+use crate::domain::{comments as comments_domain, content, content_views};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::timezone;
+use twitter::{
+    LongformResponse, ThreadWithTweetsResponse, TweetResponse, embed_media, includes_media,
+};
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/content", get(list_content))
+        .merge(comments::routes())
+        .merge(experiments::routes())
+        .merge(links::routes())
         .merge(twitter::routes())
+        .merge(views::routes())
 }
 
 /// Discriminated union for content items
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentItem {
-    Tweet(TweetResponse),
+    Tweet(Box<TweetResponse>),
     Thread(ThreadWithTweetsResponse),
+    Longform(LongformResponse),
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,12 +53,70 @@ pub struct ContentQuery {
     #[serde(default)]
     pub offset: i64,
     pub status: Option<String>,
+    /// Only content the agent tagged with this project
+    pub project: Option<String>,
+    /// `media` embeds each tweet's capture URLs
+    pub include: Option<String>,
+    /// Case-insensitive text search
+    pub q: Option<String>,
+    /// Created on or after (RFC 3339 timestamp, or a date in the user's time zone)
+    pub from: Option<String>,
+    /// Created on or before (RFC 3339 timestamp, or a date in the user's time zone)
+    pub to: Option<String>,
+    /// Only content with (true) or without (false) images or video
+    pub has_media: Option<bool>,
+    /// `created_at` (default), `posted_at` or `engagement`
+    pub sort: Option<String>,
+    /// Saved view to apply (from /me/views); the parameters above override it
+    pub view: Option<i64>,
 }
 
 fn default_limit() -> i64 {
     500
 }
 
+/// Which end of a date a `from`/`to` bound stands for
+#[derive(Clone, Copy)]
+enum Bound {
+    From,
+    To,
+}
+
+/// Whether a `from`/`to` value is a YYYY-MM-DD date or an RFC 3339 timestamp
+fn is_valid_bound(value: &str) -> bool {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value).is_ok()
+        || NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
+
+/// Parse a `from`/`to` query value. A bare date covers the whole local day:
+/// `from` starts at its midnight and `to` ends just before the next one.
+async fn parse_bound(
+    state: &AppState,
+    user_id: i64,
+    value: Option<&str>,
+    bound: Bound,
+) -> Result<Option<DateTime<Utc>>, ApiError> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Some(at.with_timezone(&Utc)));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        ApiError::BadRequest(format!(
+            "Invalid date {:?}: use YYYY-MM-DD or an RFC 3339 timestamp",
+            value
+        ))
+    })?;
+    let tz = timezone::user_timezone(&state.db, user_id).await;
+    let (day_start, day_end) = timezone::day_bounds(date, tz);
+    Ok(Some(match bound {
+        Bound::From => day_start,
+        Bound::To => day_end - chrono::Duration::microseconds(1),
+    }))
+}
+
 #[derive(Debug, Serialize)]
 pub struct ContentResponse {
     pub items: Vec<ContentItem>,
@@ -52,41 +125,116 @@ pub struct ContentResponse {
 }
 
 /// GET /content?platform=twitter - List all content for a platform
-/// Uses DB-level UNION query for proper pagination (no in-memory sorting)
+/// Uses DB-level UNION query for proper pagination (no in-memory sorting).
+/// Filters: `status`, `project`, `q`, `from`, `to`, `has_media`; order: `sort`;
+/// `view` applies a saved view underneath them
 async fn list_content(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Query(query): Query<ContentQuery>,
-) -> Result<Json<ContentResponse>, StatusCode> {
+) -> Result<Json<ContentResponse>, ApiError> {
     match query.platform.as_str() {
         "twitter" => {
-            let status_filter = query.status.as_deref();
+            let (saved, saved_sort) = match query.view {
+                Some(id) => {
+                    let view = content_views::get_view(&state.db, user_id, id)
+                        .await
+                        .log_500("Get content view error")?
+                        .ok_or(ApiError::NotFound("View"))?;
+                    (view.filters.0, view.sort)
+                }
+                None => (ViewFilters::default(), String::new()),
+            };
+            let from = query.from.as_deref().or(saved.from.as_deref());
+            let to = query.to.as_deref().or(saved.to.as_deref());
+            let filter = content::ContentFilter {
+                status: query.status.as_deref().or(saved.status.as_deref()),
+                project: query.project.as_deref().or(saved.project.as_deref()),
+                q: query.q.as_deref().or(saved.q.as_deref()),
+                from: parse_bound(&state, user_id, from, Bound::From).await?,
+                to: parse_bound(&state, user_id, to, Bound::To).await?,
+                has_media: query.has_media.or(saved.has_media),
+            };
+            let sort = match query.sort.as_deref().unwrap_or(&saved_sort) {
+                "" => ContentSort::default(),
+                key => ContentSort::parse(key).ok_or_else(|| {
+                    ApiError::BadRequest(format!(
+                        "Unsupported sort {:?}: use created_at, posted_at or engagement",
+                        key
+                    ))
+                })?,
+            };
 
             // Use domain function with DB-level pagination via UNION query
             let (domain_items, total) = content::list_content_paginated(
                 &state.db,
                 user_id,
-                status_filter,
+                &filter,
+                sort,
                 query.limit,
                 query.offset,
             )
             .await
             .map_err(|e| {
                 eprintln!("Failed to fetch content: {}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
+                ApiError::Internal
             })?;
 
+            // Open review comments on every tweet on the page, threads included
+            let tweet_ids: Vec<i64> = domain_items
+                .iter()
+                .flat_map(|item| match item {
+                    content::ContentItem::Tweet(t) => vec![t.id],
+                    content::ContentItem::Thread(t) => t.tweets.iter().map(|t| t.id).collect(),
+                    content::ContentItem::Longform(_) => Vec::new(),
+                })
+                .collect();
+            let unresolved = comments_domain::unresolved_counts(&state.db, user_id, &tweet_ids)
+                .await
+                .map_err(|e| {
+                    eprintln!("Failed to count unresolved comments: {}", e);
+                    ApiError::Internal
+                })?;
+            let unresolved_for = |id: i64| unresolved.get(&id).copied().unwrap_or(0);
+
             // Convert domain ContentItem to route ContentItem (with DTOs)
-            let items: Vec<ContentItem> = domain_items
+            let mut items: Vec<ContentItem> = domain_items
                 .into_iter()
                 .map(|item| match item {
-                    content::ContentItem::Tweet(t) => ContentItem::Tweet(TweetResponse::from(t)),
+                    content::ContentItem::Tweet(t) => {
+                        let mut tweet = TweetResponse::from(*t);
+                        tweet.unresolved_comments = Some(unresolved_for(tweet.id));
+                        ContentItem::Tweet(Box::new(tweet))
+                    }
                     content::ContentItem::Thread(t) => {
-                        ContentItem::Thread(ThreadWithTweetsResponse::from(t))
+                        let mut thread = ThreadWithTweetsResponse::from(t);
+                        for tweet in &mut thread.tweets {
+                            tweet.unresolved_comments = Some(unresolved_for(tweet.id));
+                        }
+                        thread.unresolved_comments = Some(
+                            thread
+                                .tweets
+                                .iter()
+                                .filter_map(|t| t.unresolved_comments)
+                                .sum(),
+                        );
+                        ContentItem::Thread(thread)
+                    }
+                    content::ContentItem::Longform(l) => {
+                        ContentItem::Longform(LongformResponse::from(l))
                     }
                 })
                 .collect();
 
+            if includes_media(query.include.as_deref()) {
+                let tweets = items.iter_mut().flat_map(|item| match item {
+                    ContentItem::Tweet(tweet) => std::slice::from_mut(&mut **tweet),
+                    ContentItem::Thread(thread) => thread.tweets.as_mut_slice(),
+                    ContentItem::Longform(_) => &mut [],
+                });
+                embed_media(&state, user_id, tweets).await?;
+            }
+
             let has_more = (query.offset + query.limit) < total;
 
             Ok(Json(ContentResponse {
@@ -95,6 +243,9 @@ async fn list_content(
                 has_more,
             }))
         }
-        _ => Err(StatusCode::BAD_REQUEST),
+        other => Err(ApiError::BadRequest(format!(
+            "Unsupported platform: {}",
+            other
+        ))),
     }
 }
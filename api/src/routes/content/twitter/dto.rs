@@ -2,8 +2,24 @@
 
 use chrono::{DateTime, Utc};
 use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 
-use crate::domain::twitter::{Thread, ThreadStatus, ThreadWithTweets, Tweet};
+use crate::AppState;
+use crate::domain::captures;
+use crate::domain::twitter::{Longform, Poll, Thread, ThreadStatus, ThreadWithTweets, Tweet};
+use crate::routes::captures::signed_url;
+use crate::services::error::{ApiError, LogErr};
+
+/// Resolved URLs for a capture a tweet uses
+#[derive(Debug, Clone, Serialize)]
+pub struct TweetMediaResponse {
+    pub capture_id: i64,
+    pub media_type: String,
+    /// Short-lived URL for the capture; None if it couldn't be signed
+    pub url: Option<String>,
+    /// None until the thumbnail has been generated
+    pub thumbnail_url: Option<String>,
+}
 
 /// Tweet API response
 #[derive(Debug, Clone, Serialize)]
@@ -13,6 +29,7 @@ pub struct TweetResponse {
     pub copy_options: Vec<String>,
     pub video_clip: Option<serde_json::Value>,
     pub image_capture_ids: Vec<i64>,
+    pub library_asset_ids: Vec<i64>,
     pub media_options: Vec<serde_json::Value>,
     pub rationale: String,
     pub created_at: DateTime<Utc>,
@@ -20,10 +37,36 @@ pub struct TweetResponse {
     pub reply_to_tweet_id: Option<String>,
     pub posted_at: Option<DateTime<Utc>>,
     pub tweet_id: Option<String>,
+    pub in_reply_to_url: Option<String>,
+    pub quote_tweet_url: Option<String>,
+    pub poll: Option<Poll>,
+    /// Flagged by the compliance filter; review_reason says why
+    pub needs_review: bool,
+    pub review_reason: Option<String>,
+    /// Project the agent tagged the tweet with
+    pub project: Option<String>,
     pub publish_status: String,
     pub publish_attempts: i32,
     pub publish_error: Option<String>,
     pub publish_error_at: Option<DateTime<Utc>>,
+    /// Open review comment threads (only filled in by GET /content)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unresolved_comments: Option<i64>,
+    /// Video then images, with URLs (only filled in with `?include=media`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<Vec<TweetMediaResponse>>,
+}
+
+impl TweetResponse {
+    /// Captures the tweet uses: the video clip's source, then its images
+    fn capture_ids(&self) -> impl Iterator<Item = i64> + '_ {
+        self.video_clip
+            .as_ref()
+            .and_then(|clip| clip.get("source_capture_id"))
+            .and_then(|id| id.as_i64())
+            .into_iter()
+            .chain(self.image_capture_ids.iter().copied())
+    }
 }
 
 impl From<Tweet> for TweetResponse {
@@ -34,6 +77,7 @@ impl From<Tweet> for TweetResponse {
             copy_options: t.copy_options.0,
             video_clip: t.video_clip,
             image_capture_ids: t.image_capture_ids,
+            library_asset_ids: t.library_asset_ids,
             media_options: t.media_options.0,
             rationale: t.rationale,
             created_at: t.created_at,
@@ -41,10 +85,18 @@ impl From<Tweet> for TweetResponse {
             reply_to_tweet_id: t.reply_to_tweet_id,
             posted_at: t.posted_at,
             tweet_id: t.tweet_id,
+            in_reply_to_url: t.in_reply_to_url,
+            quote_tweet_url: t.quote_tweet_url,
+            poll: Poll::from_columns(t.poll_options.as_ref(), t.poll_duration),
+            needs_review: t.needs_review,
+            review_reason: t.review_reason,
+            project: t.project,
             publish_status: t.publish_status,
             publish_attempts: t.publish_attempts,
             publish_error: t.publish_error,
             publish_error_at: t.publish_error_at,
+            unresolved_comments: None,
+            media: None,
         }
     }
 }
@@ -80,6 +132,9 @@ impl From<Thread> for ThreadResponse {
 pub struct ThreadWithTweetsResponse {
     pub thread: ThreadResponse,
     pub tweets: Vec<TweetResponse>,
+    /// Open review comment threads across the thread's tweets (only filled in by GET /content)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unresolved_comments: Option<i64>,
 }
 
 impl From<ThreadWithTweets> for ThreadWithTweetsResponse {
@@ -87,6 +142,91 @@ impl From<ThreadWithTweets> for ThreadWithTweetsResponse {
         Self {
             thread: t.thread.into(),
             tweets: t.tweets.into_iter().map(Into::into).collect(),
+            unresolved_comments: None,
+        }
+    }
+}
+
+/// True when a comma-separated `include` query parameter asks for media
+pub fn includes_media(include: Option<&str>) -> bool {
+    include.is_some_and(|include| include.split(',').any(|part| part.trim() == "media"))
+}
+
+/// Fill in `media` on each tweet, looking up all their captures in one query.
+/// Captures that no longer exist or belong to someone else are left out.
+pub async fn embed_media<'a>(
+    state: &AppState,
+    user_id: i64,
+    tweets: impl IntoIterator<Item = &'a mut TweetResponse>,
+) -> Result<(), ApiError> {
+    let tweets: Vec<&mut TweetResponse> = tweets.into_iter().collect();
+    let mut seen = HashSet::new();
+    let ids: Vec<i64> = tweets
+        .iter()
+        .flat_map(|tweet| tweet.capture_ids())
+        .filter(|id| seen.insert(*id))
+        .collect();
+    let rows = if ids.is_empty() {
+        Vec::new()
+    } else {
+        captures::get_capture_thumbnails(&state.db, user_id, &ids)
+            .await
+            .log_500("Get tweet media error")?
+    };
+
+    let resolved = futures::future::join_all(rows.into_iter().map(|row| async move {
+        let url = signed_url(state, user_id, &row.gcs_path).await.ok();
+        let thumbnail_url = match &row.thumbnail_path {
+            Some(path) => signed_url(state, user_id, path).await.ok(),
+            None => None,
+        };
+        TweetMediaResponse {
+            capture_id: row.id,
+            media_type: row.media_type,
+            url,
+            thumbnail_url,
+        }
+    }))
+    .await;
+    let by_id: HashMap<i64, TweetMediaResponse> =
+        resolved.into_iter().map(|m| (m.capture_id, m)).collect();
+
+    for tweet in tweets {
+        let media = tweet
+            .capture_ids()
+            .filter_map(|id| by_id.get(&id).cloned())
+            .collect();
+        tweet.media = Some(media);
+    }
+    Ok(())
+}
+
+/// Long-form post API response
+#[derive(Debug, Clone, Serialize)]
+pub struct LongformResponse {
+    pub id: i64,
+    pub title: Option<String>,
+    pub body: String,
+    pub rationale: String,
+    pub created_at: DateTime<Utc>,
+    pub posted_at: Option<DateTime<Utc>>,
+    pub tweet_id: Option<String>,
+    pub publish_status: String,
+    pub publish_error: Option<String>,
+}
+
+impl From<Longform> for LongformResponse {
+    fn from(l: Longform) -> Self {
+        Self {
+            id: l.id,
+            title: l.title,
+            body: l.body,
+            rationale: l.rationale,
+            created_at: l.created_at,
+            posted_at: l.posted_at,
+            tweet_id: l.tweet_id,
+            publish_status: l.publish_status,
+            publish_error: l.publish_error,
         }
     }
 }
@@ -0,0 +1,221 @@
+//! Long-form post endpoints (/longform/*)
+//!
+//! X accepts posts over 280 characters only from subscribed accounts, so
+//! publishing checks the user's subscription tier before posting.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{post, put},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::dto::LongformResponse;
+use crate::AppState;
+use crate::domain::twitter::{MAX_LONGFORM_CHARS, longform};
+use crate::routes::auth::AuthUser;
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::{
+    auth,
+    error::{ApiError, LogErr},
+    twitter,
+};
+
+/// Longest title, in characters
+const MAX_TITLE_CHARS: usize = 200;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/longform", post(create_longform))
+        .route(
+            "/longform/{id}",
+            put(update_longform).delete(dismiss_longform),
+        )
+        .route("/longform/{id}/publish", post(publish_longform))
+}
+
+/// Trimmed title, None if blank
+fn clean_title(title: &str) -> Result<Option<&str>, ApiError> {
+    let title = title.trim();
+    if title.chars().count() > MAX_TITLE_CHARS {
+        return Err(ApiError::Unprocessable(format!(
+            "title must be at most {} characters",
+            MAX_TITLE_CHARS
+        )));
+    }
+    Ok((!title.is_empty()).then_some(title))
+}
+
+/// Reject empty posts and posts over X's long-form limit
+fn check_body(body: &str) -> Result<(), ApiError> {
+    if body.trim().is_empty() {
+        return Err(ApiError::Unprocessable("body cannot be empty".to_string()));
+    }
+    if body.chars().count() > MAX_LONGFORM_CHARS {
+        return Err(ApiError::Unprocessable(format!(
+            "long-form posts must be at most {} characters",
+            MAX_LONGFORM_CHARS
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct CreateLongformRequest {
+    #[serde(default)]
+    title: String,
+    body: String,
+    #[serde(default)]
+    rationale: String,
+}
+
+/// POST /longform - Create a long-form draft
+async fn create_longform(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<CreateLongformRequest>,
+) -> Result<(StatusCode, Json<LongformResponse>), ApiError> {
+    let title = clean_title(&req.title)?;
+    check_body(&req.body)?;
+
+    let post = longform::insert_longform(&state.db, user_id, title, &req.body, &req.rationale)
+        .await
+        .log_500("Insert long-form post error")?;
+
+    Ok((StatusCode::CREATED, Json(post.into())))
+}
+
+#[derive(Deserialize)]
+struct UpdateLongformRequest {
+    /// An empty string removes the title
+    title: Option<String>,
+    body: Option<String>,
+}
+
+/// PUT /longform/:id - Edit an unposted long-form draft
+async fn update_longform(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    Json(req): Json<UpdateLongformRequest>,
+) -> Result<Json<LongformResponse>, ApiError> {
+    let title = req.title.as_deref().map(clean_title).transpose()?;
+    let body = req.body.as_deref();
+    if let Some(body) = body {
+        check_body(body)?;
+    }
+
+    longform::get_longform(&state.db, id, user_id)
+        .await
+        .log_500("Get long-form post error")?
+        .ok_or(ApiError::NotFound("Long-form post"))?;
+
+    let updated = longform::update_longform(&state.db, id, user_id, title, body)
+        .await
+        .log_500("Update long-form post error")?;
+    if !updated {
+        return Err(ApiError::Conflict(
+            "Long-form post is posting or already posted".to_string(),
+        ));
+    }
+
+    let post = longform::get_longform(&state.db, id, user_id)
+        .await
+        .log_500("Get long-form post error")?
+        .ok_or(ApiError::NotFound("Long-form post"))?;
+    Ok(Json(post.into()))
+}
+
+/// DELETE /longform/:id - Dismiss an unposted long-form draft
+async fn dismiss_longform(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let dismissed = longform::dismiss_longform(&state.db, id, user_id)
+        .await
+        .log_500("Dismiss long-form post error")?;
+    if !dismissed {
+        return Err(ApiError::NotFound("Long-form post"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize)]
+struct PublishLongformResponse {
+    tweet_id: String,
+}
+
+/// POST /longform/:id/publish - Post a long-form draft to X
+async fn publish_longform(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    ctx: RequestContext,
+) -> Result<Json<PublishLongformResponse>, ApiError> {
+    let post = longform::get_longform(&state.db, id, user_id)
+        .await
+        .log_500("Get long-form post error")?
+        .ok_or(ApiError::NotFound("Long-form post"))?;
+
+    let tokens = twitter::get_user_tokens(&state.db, user_id)
+        .await
+        .log_500("Get user tokens error")?
+        .ok_or(ApiError::TwitterReauthRequired)?;
+    let access_token =
+        auth::ensure_valid_access_token(&state.db, state.twitter.as_ref(), user_id, tokens).await?;
+
+    // X rejects long posts from free accounts; check up front for a clear error
+    let me = state
+        .twitter
+        .get_me(&access_token)
+        .await
+        .map_err(|e| ApiError::Upstream(format!("Failed to check X subscription: {}", e)))?;
+    if !me.is_premium() {
+        return Err(ApiError::Forbidden(
+            "Long-form posts need an X subscription",
+        ));
+    }
+
+    let claimed = longform::set_longform_posting(&state.db, id, user_id)
+        .await
+        .log_500("Set long-form posting status error")?;
+    if !claimed {
+        return Err(ApiError::Conflict(
+            "Long-form post is already posting or posted".to_string(),
+        ));
+    }
+
+    let posted = match state
+        .twitter
+        .post_tweet(&access_token, &post.body, None, None, None, None)
+        .await
+    {
+        Ok(posted) => posted,
+        Err(e) => {
+            let error = format!("Failed to post long-form post: {}", e);
+            let _ = longform::mark_longform_publish_failed(&state.db, id, user_id, &error).await;
+            return Err(ApiError::Upstream(error));
+        }
+    };
+
+    longform::mark_longform_posted(&state.db, id, &posted.id)
+        .await
+        .log_500("Mark long-form post posted error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::LongformPublished,
+        Some(&id.to_string()),
+        Some(serde_json::json!({ "tweet_id": posted.id })),
+    )
+    .await;
+
+    Ok(Json(PublishLongformResponse {
+        tweet_id: posted.id,
+    }))
+}
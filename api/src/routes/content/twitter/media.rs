@@ -1,9 +1,9 @@
 //! Shared media upload utilities for Twitter routes
 
 use crate::AppState;
-use crate::constants::BUCKET_NAME;
-use crate::domain::captures;
 use crate::domain::twitter::TweetForPosting;
+use crate::domain::{captures, media_library, users};
+use crate::services::media_studio::{self, Watermark, WatermarkPosition};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
@@ -38,6 +38,8 @@ pub async fn upload_tweet_media(
             .ok_or("Invalid video_clip format")?;
 
         let (data, content_type) = fetch_capture_data(state, user_id, capture_id).await?;
+        let watermark = load_watermark(state, user_id).await?;
+        let (data, content_type) = apply_watermark(data, content_type, watermark.as_ref()).await?;
 
         let media_id = state
             .twitter
@@ -46,25 +48,18 @@ pub async fn upload_tweet_media(
             .map_err(|e| format!("Failed to upload video: {}", e))?;
 
         media_ids.push(media_id);
-    } else if !tweet.image_capture_ids.is_empty() {
-        // Upload images (max 4 per Twitter rules)
-        let capture_ids: Vec<i64> = tweet.image_capture_ids.iter().take(4).copied().collect();
-
-        // Batch fetch all capture metadata in one query
-        let captures = fetch_captures_batch(state, user_id, &capture_ids).await?;
-
-        for capture_id in &capture_ids {
-            let capture_info = captures
-                .get(capture_id)
-                .ok_or_else(|| format!("Capture {} not found", capture_id))?;
-
-            let data = fetch_capture_data_from_path(state, &capture_info.gcs_path).await?;
+    } else {
+        let watermark = load_watermark(state, user_id).await?;
+        for image in tweet_images(state, user_id, tweet).await? {
+            let data = fetch_capture_data_from_path(state, &image.path).await?;
+            let (data, content_type) =
+                apply_watermark(data, image.content_type.clone(), watermark.as_ref()).await?;
 
             let media_id = state
                 .twitter
-                .upload_media(access_token, &data, &capture_info.content_type)
+                .upload_media(access_token, &data, &content_type)
                 .await
-                .map_err(|e| format!("Failed to upload image {}: {}", capture_id, e))?;
+                .map_err(|e| format!("Failed to upload image {}: {}", image.label, e))?;
 
             media_ids.push(media_id);
         }
@@ -91,17 +86,19 @@ pub async fn upload_tweet_media_with_progress<T: From<UploadProgress> + Send + '
             .ok_or("Invalid video_clip format")?;
 
         let (data, content_type) = fetch_capture_data(state, user_id, capture_id).await?;
+        let watermark = load_watermark(state, user_id).await?;
+        let (data, content_type) = apply_watermark(data, content_type, watermark.as_ref()).await?;
 
         // For videos, use chunked upload with progress
         if content_type.starts_with("video/") {
             let progress_tx_clone = progress_tx.clone();
             let media_id = state
                 .twitter
-                .upload_media_chunked_with_progress(
+                .upload_media_with_progress(
                     access_token,
                     &data,
                     &content_type,
-                    move |segment, total| {
+                    Box::new(move |segment, total| {
                         let percent = if total > 0 {
                             ((segment as f32 / total as f32) * 100.0) as u8
                         } else {
@@ -115,7 +112,7 @@ pub async fn upload_tweet_media_with_progress<T: From<UploadProgress> + Send + '
                             }
                             .into(),
                         );
-                    },
+                    }),
                 )
                 .await
                 .map_err(|e| format!("Failed to upload video: {}", e))?;
@@ -132,15 +129,12 @@ pub async fn upload_tweet_media_with_progress<T: From<UploadProgress> + Send + '
                 .map_err(|e| format!("Failed to upload media: {}", e))?;
             media_ids.push(media_id);
         }
-    } else if !tweet.image_capture_ids.is_empty() {
-        // Upload images (max 4 per Twitter rules)
-        let capture_ids: Vec<i64> = tweet.image_capture_ids.iter().take(4).copied().collect();
-        let total_images = capture_ids.len();
-
-        // Batch fetch all capture metadata in one query
-        let captures = fetch_captures_batch(state, user_id, &capture_ids).await?;
+    } else {
+        let images = tweet_images(state, user_id, tweet).await?;
+        let total_images = images.len();
+        let watermark = load_watermark(state, user_id).await?;
 
-        for (idx, capture_id) in capture_ids.iter().enumerate() {
+        for (idx, image) in images.iter().enumerate() {
             // Send progress for image uploads
             let percent = ((idx as f32 / total_images as f32) * 100.0) as u8;
             let _ = progress_tx
@@ -154,17 +148,15 @@ pub async fn upload_tweet_media_with_progress<T: From<UploadProgress> + Send + '
                 )
                 .await;
 
-            let capture_info = captures
-                .get(capture_id)
-                .ok_or_else(|| format!("Capture {} not found", capture_id))?;
-
-            let data = fetch_capture_data_from_path(state, &capture_info.gcs_path).await?;
+            let data = fetch_capture_data_from_path(state, &image.path).await?;
+            let (data, content_type) =
+                apply_watermark(data, image.content_type.clone(), watermark.as_ref()).await?;
 
             let media_id = state
                 .twitter
-                .upload_media(access_token, &data, &capture_info.content_type)
+                .upload_media(access_token, &data, &content_type)
                 .await
-                .map_err(|e| format!("Failed to upload image {}: {}", capture_id, e))?;
+                .map_err(|e| format!("Failed to upload image {}: {}", image.label, e))?;
 
             media_ids.push(media_id);
         }
@@ -173,6 +165,131 @@ pub async fn upload_tweet_media_with_progress<T: From<UploadProgress> + Send + '
     Ok(media_ids)
 }
 
+/// Maximum images per tweet (Twitter rules)
+pub const MAX_TWEET_IMAGES: usize = 4;
+
+/// An image to attach, from a capture or the media library
+pub struct TweetImage {
+    /// For error messages, e.g. "capture 12" or "library asset 3"
+    pub label: String,
+    pub path: String,
+    pub content_type: String,
+}
+
+/// A tweet's images in posting order: captures first, then library assets (max 4)
+pub async fn tweet_images(
+    state: &Arc<AppState>,
+    user_id: i64,
+    tweet: &TweetForPosting,
+) -> Result<Vec<TweetImage>, String> {
+    let mut images = Vec::new();
+
+    let capture_ids: Vec<i64> = tweet
+        .image_capture_ids
+        .iter()
+        .take(MAX_TWEET_IMAGES)
+        .copied()
+        .collect();
+    if !capture_ids.is_empty() {
+        // Batch fetch all capture metadata in one query
+        let captures = fetch_captures_batch(state, user_id, &capture_ids).await?;
+        for capture_id in &capture_ids {
+            let capture = captures
+                .get(capture_id)
+                .ok_or_else(|| format!("Capture {} not found", capture_id))?;
+            images.push(TweetImage {
+                label: format!("capture {}", capture_id),
+                path: capture.gcs_path.clone(),
+                content_type: capture.content_type.clone(),
+            });
+        }
+    }
+
+    let asset_ids: Vec<i64> = tweet
+        .library_asset_ids
+        .iter()
+        .take(MAX_TWEET_IMAGES - images.len())
+        .copied()
+        .collect();
+    if !asset_ids.is_empty() {
+        let assets = media_library::get_assets_batch(&state.db, &asset_ids, user_id)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?;
+        for asset_id in &asset_ids {
+            let asset = assets
+                .get(asset_id)
+                .ok_or_else(|| format!("Library asset {} not found", asset_id))?;
+            images.push(TweetImage {
+                label: format!("library asset {}", asset_id),
+                path: asset.gcs_path.clone(),
+                content_type: asset.content_type.clone(),
+            });
+        }
+    }
+
+    Ok(images)
+}
+
+/// The user's branding watermark, if enabled and its logo still exists
+pub async fn load_watermark(
+    state: &Arc<AppState>,
+    user_id: i64,
+) -> Result<Option<Watermark>, String> {
+    let branding = users::get_branding(&state.db, user_id)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+    let Some(branding) = branding.filter(|b| b.enabled) else {
+        return Ok(None);
+    };
+    let Some(asset_id) = branding.asset_id else {
+        return Ok(None);
+    };
+    let Some(asset) = media_library::get_asset(&state.db, asset_id, user_id)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?
+    else {
+        return Ok(None);
+    };
+
+    let logo = fetch_capture_data_from_path(state, &asset.gcs_path).await?;
+    Ok(Some(Watermark {
+        logo,
+        position: WatermarkPosition::parse(&branding.position)
+            .unwrap_or(WatermarkPosition::BottomRight),
+        opacity: branding.opacity,
+    }))
+}
+
+/// Composite the watermark onto media before upload. Returns the (possibly
+/// re-encoded) bytes and content type.
+pub async fn apply_watermark(
+    data: Vec<u8>,
+    content_type: String,
+    watermark: Option<&Watermark>,
+) -> Result<(Vec<u8>, String), String> {
+    let Some(watermark) = watermark else {
+        return Ok((data, content_type));
+    };
+
+    if content_type.starts_with("video/") {
+        let data = media_studio::watermark_video(&data, watermark)
+            .await
+            .map_err(|e| format!("Failed to watermark video: {}", e))?;
+        Ok((data, "video/mp4".to_string()))
+    } else if content_type.starts_with("image/") {
+        // Image decoding is CPU-bound; keep it off the async workers
+        let watermark = watermark.clone();
+        tokio::task::spawn_blocking(move || {
+            media_studio::watermark_image(&data, &content_type, &watermark)
+        })
+        .await
+        .map_err(|e| format!("Watermark task failed: {}", e))?
+        .map_err(|e| format!("Failed to watermark image: {}", e))
+    } else {
+        Ok((data, content_type))
+    }
+}
+
 /// Fetch capture data from local storage or GCS
 pub async fn fetch_capture_data(
     state: &Arc<AppState>,
@@ -187,30 +304,11 @@ pub async fn fetch_capture_data(
 
     let (gcs_path, content_type) = (capture.gcs_path, capture.content_type);
 
-    // Read from local storage or GCS
-    let data = if let Some(local_path) = &state.local_storage_path {
-        let full_path = local_path.join(&gcs_path);
-        tokio::fs::read(&full_path)
-            .await
-            .map_err(|e| format!("Failed to read local file {:?}: {}", full_path, e))?
-    } else if let Some(ref gcs) = state.gcs {
-        // Download from GCS
-        let bucket = format!("projects/_/buckets/{}", BUCKET_NAME);
-        let mut resp = gcs
-            .read_object(&bucket, &gcs_path)
-            .send()
-            .await
-            .map_err(|e| format!("GCS read error: {}", e))?;
-
-        let mut data = Vec::new();
-        while let Some(chunk) = resp.next().await {
-            let bytes = chunk.map_err(|e| format!("GCS stream error: {}", e))?;
-            data.extend_from_slice(&bytes);
-        }
-        data
-    } else {
-        return Err("No storage backend configured".to_string());
-    };
+    let data = state
+        .store
+        .get(&gcs_path)
+        .await
+        .map_err(|e| format!("Storage read error for {}: {}", gcs_path, e))?;
 
     Ok((data, content_type))
 }
@@ -234,27 +332,9 @@ pub async fn fetch_capture_data_from_path(
     state: &Arc<AppState>,
     gcs_path: &str,
 ) -> Result<Vec<u8>, String> {
-    if let Some(local_path) = &state.local_storage_path {
-        let full_path = local_path.join(gcs_path);
-        tokio::fs::read(&full_path)
-            .await
-            .map_err(|e| format!("Failed to read local file {:?}: {}", full_path, e))
-    } else if let Some(ref gcs) = state.gcs {
-        // Download from GCS
-        let bucket = format!("projects/_/buckets/{}", BUCKET_NAME);
-        let mut resp = gcs
-            .read_object(&bucket, gcs_path)
-            .send()
-            .await
-            .map_err(|e| format!("GCS read error: {}", e))?;
-
-        let mut data = Vec::new();
-        while let Some(chunk) = resp.next().await {
-            let bytes = chunk.map_err(|e| format!("GCS stream error: {}", e))?;
-            data.extend_from_slice(&bytes);
-        }
-        Ok(data)
-    } else {
-        Err("No storage backend configured".to_string())
-    }
+    state
+        .store
+        .get(gcs_path)
+        .await
+        .map_err(|e| format!("Storage read error for {}: {}", gcs_path, e))
 }
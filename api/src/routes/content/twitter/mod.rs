@@ -1,18 +1,54 @@
 mod dto;
+pub mod longform;
 pub mod media;
 pub mod threads;
 pub mod tweets;
 
 // Re-export DTOs for parent content/mod.rs
-pub use dto::{ThreadWithTweetsResponse, TweetResponse};
+pub use dto::{
+    LongformResponse, ThreadWithTweetsResponse, TweetResponse, embed_media, includes_media,
+};
 
 use axum::Router;
+use sqlx::PgPool;
 use std::sync::Arc;
 
 use crate::AppState;
+use crate::domain::projects;
+use crate::domain::twitter::TweetForPosting;
+use crate::services::error::{ApiError, LogErr};
+use crate::services::twitter::tweet_id_from_url;
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .merge(tweets::routes())
         .merge(threads::routes())
+        .merge(longform::routes())
+}
+
+/// Twitter IDs a tweet replies to and quotes, parsed from its stored links
+/// (validated when they were set)
+fn referenced_tweet_ids(tweet: &TweetForPosting) -> (Option<String>, Option<String>) {
+    let parse = |url: &Option<String>| url.as_deref().and_then(tweet_id_from_url);
+    (parse(&tweet.in_reply_to_url), parse(&tweet.quote_tweet_url))
+}
+
+/// Refuse to publish a tweet or thread tagged with a project that is linked to
+/// a different X account than the one connected
+async fn check_project_account(
+    db: &PgPool,
+    user_id: i64,
+    tweet_id: Option<i64>,
+    thread_id: Option<i64>,
+) -> Result<(), ApiError> {
+    let conflict = projects::publish_account_conflict(db, user_id, tweet_id, thread_id)
+        .await
+        .log_500("Check project account error")?;
+    match conflict {
+        Some((project, username)) => Err(ApiError::Conflict(format!(
+            "{} publishes from @{}; connect that X account to post it",
+            project, username
+        ))),
+        None => Ok(()),
+    }
 }
@@ -1,26 +1,40 @@
-//! Thread action endpoints (/threads/*, /tweets/:id/collateral)
+//! Thread action endpoints (/threads/*, /tweets/:id/collateral, /tweets/:id/attach-frame)
 
 use axum::{
     Json, Router,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::Response,
     routing::{delete, get, post, put},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use super::dto::{ThreadResponse, ThreadWithTweetsResponse};
-use super::media::upload_tweet_media;
+use super::dto::{ThreadResponse, ThreadWithTweetsResponse, embed_media, includes_media};
+use super::media::{MAX_TWEET_IMAGES, upload_tweet_media};
+use super::{check_project_account, referenced_tweet_ids};
 use crate::AppState;
 use crate::constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
-use crate::domain::twitter::ThreadStatus;
-use crate::domain::{captures, twitter::threads};
+use crate::domain::twitter::{DEFAULT_POLL_DURATION, Poll, ThreadStatus, TweetForPosting};
+use crate::domain::{
+    captures, media_library,
+    twitter::{threads, tweets},
+};
 use crate::routes::auth::AuthUser;
-use crate::services::{auth, error::LogErr, twitter as twitter_service};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::{
+    auth,
+    copy_filter::CopyFilter,
+    error::{ApiError, LogErr},
+    idempotency,
+    media_studio::{MediaStudio, MediaStudioError, TrimParams},
+    thread_suggest, twitter as twitter_service,
+};
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/threads", post(create_thread).get(list_threads))
+        .route("/threads/suggest", post(suggest_threads))
         .route(
             "/threads/{id}",
             get(get_thread).put(update_thread).delete(delete_thread),
@@ -31,7 +45,9 @@ pub fn routes() -> Router<Arc<AppState>> {
             delete(remove_tweet_from_thread),
         )
         .route("/threads/{id}/publish", post(post_thread))
+        .route("/threads/{id}/publish-status", get(get_publish_status))
         .route("/tweets/{id}/collateral", put(update_tweet_collateral))
+        .route("/tweets/{id}/attach-frame", post(attach_frame))
 }
 
 #[derive(Deserialize)]
@@ -52,9 +68,11 @@ async fn create_thread(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Json(payload): Json<CreateThreadRequest>,
-) -> Result<(StatusCode, Json<CreateThreadResponse>), StatusCode> {
+) -> Result<(StatusCode, Json<CreateThreadResponse>), ApiError> {
     if payload.tweet_ids.is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(ApiError::BadRequest(
+            "tweet_ids must not be empty".to_string(),
+        ));
     }
 
     // Verify all tweets belong to this user and are unposted
@@ -63,7 +81,9 @@ async fn create_thread(
         .log_500("Verify tweets for thread error")?;
 
     if !valid {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(ApiError::BadRequest(
+            "Tweets must be your own, unposted and not already in a thread".to_string(),
+        ));
     }
 
     // Use transaction for atomic thread creation + tweet assignment
@@ -89,6 +109,41 @@ async fn create_thread(
     ))
 }
 
+#[derive(Serialize)]
+struct ThreadSuggestionResponse {
+    /// In thread order; send as-is to POST /threads to accept
+    tweet_ids: Vec<i64>,
+    score: f64,
+    signals: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct SuggestThreadsResponse {
+    suggestions: Vec<ThreadSuggestionResponse>,
+}
+
+/// POST /threads/suggest - Propose threads from related pending tweets
+async fn suggest_threads(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<SuggestThreadsResponse>, ApiError> {
+    let candidates =
+        threads::list_thread_candidates(&state.db, user_id, thread_suggest::MAX_CANDIDATES)
+            .await
+            .log_500("List thread candidates error")?;
+
+    let suggestions = thread_suggest::suggest(&candidates)
+        .into_iter()
+        .map(|s| ThreadSuggestionResponse {
+            tweet_ids: s.tweet_ids,
+            score: (s.score * 100.0).round() / 100.0,
+            signals: s.signals.iter().map(|signal| signal.as_str()).collect(),
+        })
+        .collect();
+
+    Ok(Json(SuggestThreadsResponse { suggestions }))
+}
+
 #[derive(Deserialize)]
 struct ListThreadsQuery {
     limit: Option<i64>,
@@ -108,7 +163,7 @@ async fn list_threads(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Query(query): Query<ListThreadsQuery>,
-) -> Result<Json<ListThreadsResponse>, StatusCode> {
+) -> Result<Json<ListThreadsResponse>, ApiError> {
     let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
     let offset = query.offset.unwrap_or(0);
     let status_filter = query.status.as_deref();
@@ -130,16 +185,23 @@ async fn list_threads(
     }))
 }
 
+#[derive(Deserialize)]
+struct GetThreadQuery {
+    /// `media` embeds each tweet's capture URLs
+    include: Option<String>,
+}
+
 /// GET /threads/:id - Get thread with its tweets
 async fn get_thread(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Path(thread_id): Path<i64>,
-) -> Result<Json<ThreadWithTweetsResponse>, StatusCode> {
+    Query(query): Query<GetThreadQuery>,
+) -> Result<Json<ThreadWithTweetsResponse>, ApiError> {
     let result = threads::get_thread_with_tweets(&state.db, thread_id, user_id)
         .await
         .log_500("Get thread error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Thread"))?;
 
     // Also fetch the tweets for the thread
     let tweets = threads::get_thread_tweets(&state.db, thread_id, user_id)
@@ -151,7 +213,11 @@ async fn get_thread(
         tweets,
     };
 
-    Ok(Json(ThreadWithTweetsResponse::from(result_with_tweets)))
+    let mut response = ThreadWithTweetsResponse::from(result_with_tweets);
+    if includes_media(query.include.as_deref()) {
+        embed_media(&state, user_id, &mut response.tweets).await?;
+    }
+    Ok(Json(response))
 }
 
 #[derive(Deserialize)]
@@ -166,14 +232,16 @@ async fn update_thread(
     AuthUser(user_id): AuthUser,
     Path(thread_id): Path<i64>,
     Json(payload): Json<UpdateThreadRequest>,
-) -> Result<StatusCode, StatusCode> {
+) -> Result<StatusCode, ApiError> {
     let status = threads::get_thread_status(&state.db, thread_id, user_id)
         .await
         .log_500("Get thread status error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Thread"))?;
 
     if status == ThreadStatus::Posting || status == ThreadStatus::Posted {
-        return Err(StatusCode::CONFLICT);
+        return Err(ApiError::Conflict(
+            "Thread is already posting or posted".to_string(),
+        ));
     }
 
     // Use transaction for atomic title update + reorder
@@ -192,7 +260,9 @@ async fn update_thread(
             .log_500("Verify tweets in thread error")?;
 
         if !valid {
-            return Err(StatusCode::BAD_REQUEST);
+            return Err(ApiError::BadRequest(
+                "tweet_ids must be exactly the tweets in this thread".to_string(),
+            ));
         }
 
         threads::reorder_thread_tweets(&mut *tx, thread_id, user_id, tweet_ids)
@@ -210,7 +280,8 @@ async fn delete_thread(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Path(thread_id): Path<i64>,
-) -> Result<StatusCode, StatusCode> {
+    ctx: RequestContext,
+) -> Result<StatusCode, ApiError> {
     // Use transaction for atomic check + unlink + delete
     let mut tx = state.db.begin().await.log_500("Begin transaction error")?;
 
@@ -219,7 +290,7 @@ async fn delete_thread(
         .log_500("Check thread exists error")?;
 
     if !exists {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(ApiError::NotFound("Thread"));
     }
 
     threads::unlink_all_tweets_from_thread(&mut *tx, thread_id, user_id)
@@ -232,6 +303,16 @@ async fn delete_thread(
 
     tx.commit().await.log_500("Commit transaction error")?;
 
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::ThreadDeleted,
+        Some(&thread_id.to_string()),
+        None,
+    )
+    .await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -247,24 +328,28 @@ async fn add_tweet_to_thread(
     AuthUser(user_id): AuthUser,
     Path(thread_id): Path<i64>,
     Json(payload): Json<AddTweetToThreadRequest>,
-) -> Result<StatusCode, StatusCode> {
+) -> Result<StatusCode, ApiError> {
     let status = threads::get_thread_status(&state.db, thread_id, user_id)
         .await
         .log_500("Get thread status error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Thread"))?;
 
     if status != ThreadStatus::Draft {
-        return Err(StatusCode::CONFLICT);
+        return Err(ApiError::Conflict(
+            "Only draft threads can be edited".to_string(),
+        ));
     }
 
     // Verify tweet exists, belongs to user, is unposted, and not in another thread
     let existing_thread_id = threads::get_tweet_thread_info(&state.db, payload.tweet_id, user_id)
         .await
         .log_500("Get tweet thread info error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Tweet"))?;
 
     if existing_thread_id.is_some() && existing_thread_id != Some(thread_id) {
-        return Err(StatusCode::CONFLICT);
+        return Err(ApiError::Conflict(
+            "Tweet already belongs to another thread".to_string(),
+        ));
     }
 
     // Use transaction for atomic position shifting + assignment
@@ -304,14 +389,16 @@ async fn remove_tweet_from_thread(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Path((thread_id, tweet_id)): Path<(i64, i64)>,
-) -> Result<StatusCode, StatusCode> {
+) -> Result<StatusCode, ApiError> {
     let status = threads::get_thread_status(&state.db, thread_id, user_id)
         .await
         .log_500("Get thread status error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Thread"))?;
 
     if status != ThreadStatus::Draft {
-        return Err(StatusCode::CONFLICT);
+        return Err(ApiError::Conflict(
+            "Only draft threads can be edited".to_string(),
+        ));
     }
 
     // Use transaction for atomic unlink + position reordering
@@ -324,7 +411,7 @@ async fn remove_tweet_from_thread(
 
     let position = match position {
         Some(pos) => pos,
-        None => return Err(StatusCode::NOT_FOUND), // Tweet not in this thread
+        None => return Err(ApiError::NotFound("Tweet in thread")),
     };
 
     // Unlink tweet from thread
@@ -344,17 +431,12 @@ async fn remove_tweet_from_thread(
     Ok(StatusCode::NO_CONTENT)
 }
 
-#[derive(Serialize)]
-struct PostThreadTweetResult {
-    id: i64,
-    twitter_id: String,
-    reply_to: Option<String>,
-}
-
 #[derive(Serialize)]
 struct PostThreadResponse {
+    /// "posting" while the background job runs, or "posted" if nothing was left to post
     status: String,
-    tweets: Vec<PostThreadTweetResult>,
+    /// Poll for progress and the posted tweet IDs
+    status_url: String,
 }
 
 /// POST /threads/:id/publish - Post entire thread to Twitter as a reply chain
@@ -364,29 +446,60 @@ struct PostThreadResponse {
 /// 2. Make external API calls
 /// 3. Record results in transaction
 /// 4. Attempt compensation on failure
+///
+/// Step 1 runs in the request; steps 2-4 run as a background job so uploading
+/// several videos can't outlast HTTP timeouts. Returns 202; follow along with
+/// GET /threads/:id/publish-status.
+///
+/// With an Idempotency-Key header, a retried publish returns the original result.
 async fn post_thread(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Path(thread_id): Path<i64>,
-) -> Result<Json<PostThreadResponse>, StatusCode> {
+    ctx: RequestContext,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let idempotency_key = idempotency::key_from_headers(&headers)?;
+    let endpoint = format!("POST /threads/{}/publish", thread_id);
+
+    idempotency::run(
+        &state.db,
+        user_id,
+        idempotency_key.as_deref(),
+        &endpoint,
+        StatusCode::ACCEPTED,
+        publish_thread(&state, &ctx, user_id, thread_id),
+    )
+    .await
+}
+
+async fn publish_thread(
+    state: &Arc<AppState>,
+    ctx: &RequestContext,
+    user_id: i64,
+    thread_id: i64,
+) -> Result<PostThreadResponse, ApiError> {
     // Phase 1: Validate and record intent
     let status = threads::get_thread_status(&state.db, thread_id, user_id)
         .await
         .log_500("Get thread status error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Thread"))?;
 
     if status != ThreadStatus::Draft && status != ThreadStatus::PartialFailed {
-        return Err(StatusCode::CONFLICT);
+        return Err(ApiError::Conflict(
+            "Thread is already posting or posted".to_string(),
+        ));
     }
+    check_project_account(&state.db, user_id, None, Some(thread_id)).await?;
 
     let tokens = twitter_service::get_user_tokens(&state.db, user_id)
         .await
         .log_500("Get user tokens error")?
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or(ApiError::TwitterReauthRequired)?;
 
     // Ensure token is valid (refresh if needed)
     let access_token =
-        auth::ensure_valid_access_token(&state.db, &state.twitter, user_id, tokens).await?;
+        auth::ensure_valid_access_token(&state.db, state.twitter.as_ref(), user_id, tokens).await?;
 
     // Record intent in transaction
     let mut tx = state.db.begin().await.log_500("Begin transaction error")?;
@@ -395,14 +508,16 @@ async fn post_thread(
         .await
         .log_500("Set thread posting error")?;
     if !started {
-        return Err(StatusCode::CONFLICT);
+        return Err(ApiError::Conflict(
+            "Thread is already being posted".to_string(),
+        ));
     }
 
     let tweet_list = threads::get_tweets_for_posting(&mut *tx, thread_id, user_id)
         .await
         .log_500("Get tweets for posting error")?;
 
-    let mut previous_tweet_id = if status == ThreadStatus::PartialFailed {
+    let previous_tweet_id = if status == ThreadStatus::PartialFailed {
         threads::get_last_posted_tweet_id(&mut *tx, thread_id, user_id)
             .await
             .log_500("Get last posted tweet error")?
@@ -413,7 +528,9 @@ async fn post_thread(
     if tweet_list.is_empty() {
         if status == ThreadStatus::PartialFailed {
             if previous_tweet_id.is_none() {
-                return Err(StatusCode::BAD_REQUEST);
+                return Err(ApiError::BadRequest(
+                    "Thread has no tweets left to post".to_string(),
+                ));
             }
 
             threads::update_thread_status(
@@ -428,19 +545,51 @@ async fn post_thread(
             tx.commit()
                 .await
                 .log_500("Commit intent transaction error")?;
-            return Ok(Json(PostThreadResponse {
+            return Ok(PostThreadResponse {
                 status: "posted".to_string(),
-                tweets: vec![],
-            }));
+                status_url: format!("/threads/{}/publish-status", thread_id),
+            });
         }
 
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(ApiError::BadRequest("Thread has no tweets".to_string()));
     }
 
     tx.commit()
         .await
         .log_500("Commit intent transaction error")?;
 
+    // Phases 2-4 outlive the request; their errors are logged on the way out
+    let state = state.clone();
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let _ = post_thread_tweets(
+            &state,
+            &ctx,
+            user_id,
+            thread_id,
+            &access_token,
+            tweet_list,
+            previous_tweet_id,
+        )
+        .await;
+    });
+
+    Ok(PostThreadResponse {
+        status: "posting".to_string(),
+        status_url: format!("/threads/{}/publish-status", thread_id),
+    })
+}
+
+/// Post a thread's remaining tweets as a reply chain and record the outcome
+async fn post_thread_tweets(
+    state: &Arc<AppState>,
+    ctx: &RequestContext,
+    user_id: i64,
+    thread_id: i64,
+    access_token: &str,
+    tweet_list: Vec<TweetForPosting>,
+    mut previous_tweet_id: Option<String>,
+) -> Result<(), ApiError> {
     // Phase 2: External API calls with compensation tracking
     let mut posted_results = Vec::new();
     let mut posted_twitter_ids: Vec<String> = Vec::new();
@@ -458,7 +607,7 @@ async fn post_thread(
         }
 
         // Upload media for this tweet
-        let media_ids = match upload_tweet_media(&state, user_id, &tweet, &access_token).await {
+        let media_ids = match upload_tweet_media(state, user_id, &tweet, access_token).await {
             Ok(ids) => ids,
             Err(e) => {
                 eprintln!("Failed to upload media for tweet {}: {}", tweet.id, e);
@@ -474,14 +623,19 @@ async fn post_thread(
             Some(media_ids)
         };
 
-        // Post to Twitter
+        // Post to Twitter. Only the first tweet can reply to an outside tweet;
+        // the rest chain to the previous one.
+        let (in_reply_to, quote_tweet_id) = referenced_tweet_ids(&tweet);
+        let poll = tweet.poll();
         let post_result = state
             .twitter
             .post_tweet(
-                &access_token,
+                access_token,
                 &tweet.text,
-                previous_tweet_id.as_deref(),
+                previous_tweet_id.as_deref().or(in_reply_to.as_deref()),
+                quote_tweet_id.as_deref(),
                 media_ids_ref.as_deref(),
+                poll.as_ref(),
             )
             .await;
 
@@ -552,18 +706,80 @@ async fn post_thread(
         );
     }
 
-    let response_tweets = posted_results
-        .into_iter()
-        .map(|(id, twitter_id, reply_to)| PostThreadTweetResult {
-            id,
-            twitter_id,
-            reply_to,
-        })
-        .collect();
+    if !posted_twitter_ids.is_empty() {
+        audit::record(
+            &state.db,
+            ctx,
+            user_id,
+            Action::ThreadPublished,
+            Some(&thread_id.to_string()),
+            Some(serde_json::json!({
+                "status": final_status,
+                "tweet_ids": posted_twitter_ids,
+            })),
+        )
+        .await;
+    }
 
-    Ok(Json(PostThreadResponse {
-        status: final_status.to_string(),
-        tweets: response_tweets,
+    println!(
+        "[publish_thread] Thread {} {}: {} tweets posted",
+        thread_id,
+        final_status,
+        posted_results.len()
+    );
+    Ok(())
+}
+
+/// One tweet's progress in GET /threads/:id/publish-status
+#[derive(Serialize)]
+struct TweetPublishStatus {
+    id: i64,
+    position: Option<i32>,
+    /// "pending", "posting", "posted" or "failed"
+    status: String,
+    twitter_id: Option<String>,
+    reply_to: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ThreadPublishStatusResponse {
+    /// "draft", "posting", "posted" or "partial_failed"
+    status: ThreadStatus,
+    posted: usize,
+    total: usize,
+    tweets: Vec<TweetPublishStatus>,
+}
+
+/// GET /threads/:id/publish-status - Progress of a thread being published
+async fn get_publish_status(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(thread_id): Path<i64>,
+) -> Result<Json<ThreadPublishStatusResponse>, ApiError> {
+    let status = threads::get_thread_status(&state.db, thread_id, user_id)
+        .await
+        .log_500("Get thread status error")?
+        .ok_or(ApiError::NotFound("Thread"))?;
+    let tweets = threads::list_tweet_publish_states(&state.db, thread_id, user_id)
+        .await
+        .log_500("List tweet publish states error")?;
+
+    Ok(Json(ThreadPublishStatusResponse {
+        status,
+        posted: tweets.iter().filter(|t| t.tweet_id.is_some()).count(),
+        total: tweets.len(),
+        tweets: tweets
+            .into_iter()
+            .map(|t| TweetPublishStatus {
+                id: t.id,
+                position: t.thread_position,
+                status: t.publish_status,
+                twitter_id: t.tweet_id,
+                reply_to: t.reply_to_tweet_id,
+                error: t.publish_error,
+            })
+            .collect(),
     }))
 }
 
@@ -579,23 +795,68 @@ struct VideoClipInput {
 struct UpdateCollateralRequest {
     text: Option<String>,
     image_capture_ids: Option<Vec<i64>>,
+    /// Media library images, posted after the capture images
+    library_asset_ids: Option<Vec<i64>>,
     video_clip: Option<Option<VideoClipInput>>,
+    /// Link to the tweet to reply to; an empty string clears it
+    in_reply_to_url: Option<String>,
+    /// Link to the tweet to quote; an empty string clears it
+    quote_tweet_url: Option<String>,
+    /// Poll choices; an empty list removes the poll
+    poll_options: Option<Vec<String>>,
+    /// Poll length in minutes (default 1 day)
+    poll_duration: Option<i32>,
 }
 
-/// PUT /tweets/:id/collateral - Update tweet's media attachments
+/// Check a reply/quote link: it must point at a tweet the user can see.
+/// Returns the link to store, or None to clear it.
+async fn validate_tweet_reference(
+    state: &AppState,
+    user_id: i64,
+    field: &str,
+    url: &str,
+) -> Result<Option<String>, ApiError> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Ok(None);
+    }
+    let tweet_id = twitter_service::tweet_id_from_url(url)
+        .ok_or_else(|| ApiError::Unprocessable(format!("{} must be a link to a tweet", field)))?;
+
+    let tokens = twitter_service::get_user_tokens(&state.db, user_id)
+        .await
+        .log_500("Get user tokens error")?
+        .ok_or(ApiError::TwitterReauthRequired)?;
+    let access_token =
+        auth::ensure_valid_access_token(&state.db, state.twitter.as_ref(), user_id, tokens).await?;
+
+    match state.twitter.get_tweet(&access_token, &tweet_id).await {
+        Ok(_) => Ok(Some(url.to_string())),
+        Err(twitter_service::TwitterError::Api(e)) => {
+            eprintln!("[update_tweet_collateral] Tweet {} lookup: {}", tweet_id, e);
+            Err(ApiError::Unprocessable(format!(
+                "{} points to a tweet that doesn't exist or isn't visible",
+                field
+            )))
+        }
+        Err(e) => Err(ApiError::Upstream(format!(
+            "Failed to look up tweet: {}",
+            e
+        ))),
+    }
+}
+
+/// PUT /tweets/:id/collateral - Update tweet's text, media attachments, reply/quote links and poll
 async fn update_tweet_collateral(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Path(tweet_id): Path<i64>,
     Json(payload): Json<UpdateCollateralRequest>,
-) -> Result<StatusCode, StatusCode> {
-    let exists = threads::verify_tweet_exists_unposted(&state.db, tweet_id, user_id)
+) -> Result<StatusCode, ApiError> {
+    let current = tweets::get_tweet_for_posting(&state.db, tweet_id, user_id)
         .await
-        .log_500("Verify tweet exists error")?;
-
-    if !exists {
-        return Err(StatusCode::NOT_FOUND);
-    }
+        .log_500("Get tweet for update error")?
+        .ok_or(ApiError::NotFound("Tweet"))?;
 
     if let Some(ref capture_ids) = payload.image_capture_ids
         && !capture_ids.is_empty()
@@ -605,10 +866,116 @@ async fn update_tweet_collateral(
             .log_500("Verify captures owned error")?;
 
         if !valid {
-            return Err(StatusCode::BAD_REQUEST);
+            return Err(ApiError::BadRequest(
+                "image_capture_ids must be your own captures".to_string(),
+            ));
         }
     }
 
+    if let Some(ref asset_ids) = payload.library_asset_ids
+        && !asset_ids.is_empty()
+    {
+        let capture_count = payload
+            .image_capture_ids
+            .as_ref()
+            .unwrap_or(&current.image_capture_ids)
+            .len();
+        if capture_count + asset_ids.len() > MAX_TWEET_IMAGES {
+            return Err(ApiError::Unprocessable(format!(
+                "a tweet can have at most {} images",
+                MAX_TWEET_IMAGES
+            )));
+        }
+
+        let valid = media_library::verify_assets_owned(&state.db, asset_ids, user_id)
+            .await
+            .log_500("Verify library assets owned error")?;
+        if !valid {
+            return Err(ApiError::BadRequest(
+                "library_asset_ids must be your own media library assets".to_string(),
+            ));
+        }
+    }
+
+    // Clip must come from the user's own video and fit inside it
+    if let Some(Some(ref vc)) = payload.video_clip {
+        let source = captures::get_capture_info(&state.db, vc.source_capture_id, user_id)
+            .await
+            .log_500("Get clip source capture error")?
+            .ok_or_else(|| ApiError::BadRequest("Clip source capture not found".to_string()))?;
+
+        let trim = TrimParams {
+            start_timestamp: vc.start_timestamp.clone(),
+            duration_secs: vc.duration_secs,
+        };
+        if !source.content_type.starts_with("video/") {
+            return Err(ApiError::BadRequest(
+                "video_clip source must be a video".to_string(),
+            ));
+        }
+        trim.validate_within(source.duration_secs)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+
+    let in_reply_to_url = match payload.in_reply_to_url.as_deref() {
+        Some(url) => Some(validate_tweet_reference(&state, user_id, "in_reply_to_url", url).await?),
+        None => None,
+    };
+    let quote_tweet_url = match payload.quote_tweet_url.as_deref() {
+        Some(url) => Some(validate_tweet_reference(&state, user_id, "quote_tweet_url", url).await?),
+        None => None,
+    };
+
+    let poll = match (&payload.poll_options, payload.poll_duration) {
+        (None, None) => None,
+        (Some(options), _) if options.is_empty() => Some(None),
+        (options, duration) => {
+            let current_poll = current.poll();
+            let options = options
+                .clone()
+                .or_else(|| current_poll.as_ref().map(|p| p.options.clone()))
+                .ok_or_else(|| {
+                    ApiError::Unprocessable("poll_duration needs poll_options".to_string())
+                })?;
+            let poll = Poll {
+                options: options.iter().map(|o| o.trim().to_string()).collect(),
+                duration_minutes: duration
+                    .or(current_poll.map(|p| p.duration_minutes))
+                    .unwrap_or(DEFAULT_POLL_DURATION),
+            };
+            poll.validate().map_err(ApiError::Unprocessable)?;
+            Some(Some(poll))
+        }
+    };
+
+    // X rejects a poll alongside media or a quote, so check the tweet as it
+    // will be after this update
+    let will_have_poll = match &poll {
+        Some(p) => p.is_some(),
+        None => current.poll().is_some(),
+    };
+    let will_have_images = payload
+        .image_capture_ids
+        .as_ref()
+        .map_or(!current.image_capture_ids.is_empty(), |ids| !ids.is_empty())
+        || payload
+            .library_asset_ids
+            .as_ref()
+            .map_or(!current.library_asset_ids.is_empty(), |ids| !ids.is_empty());
+    let will_have_video = payload
+        .video_clip
+        .as_ref()
+        .map_or(current.video_clip.is_some(), Option::is_some);
+    let will_quote = match &quote_tweet_url {
+        Some(url) => url.is_some(),
+        None => current.quote_tweet_url.is_some(),
+    };
+    if will_have_poll && (will_have_images || will_have_video || will_quote) {
+        return Err(ApiError::Unprocessable(
+            "a poll can't be combined with media or a quote tweet".to_string(),
+        ));
+    }
+
     // Convert typed VideoClipInput to JSON for storage
     let video_clip_json: Option<Option<serde_json::Value>> = match payload.video_clip {
         None => None,
@@ -620,20 +987,119 @@ async fn update_tweet_collateral(
         }))),
     };
 
+    // Edited text is checked again so the flag always describes the current copy
+    let review_reason = match &payload.text {
+        Some(text) => Some(
+            CopyFilter::for_user(&state.db, user_id)
+                .await
+                .review_reason([text.as_str()]),
+        ),
+        None => None,
+    };
+
     let updated = threads::update_tweet_collateral(
         &state.db,
         tweet_id,
         user_id,
-        payload.text.as_deref(),
-        payload.image_capture_ids.as_ref(),
-        video_clip_json,
+        threads::CollateralUpdate {
+            text: payload.text.as_deref(),
+            image_capture_ids: payload.image_capture_ids.as_ref(),
+            library_asset_ids: payload.library_asset_ids.as_ref(),
+            video_clip: video_clip_json,
+            in_reply_to_url: in_reply_to_url.as_ref().map(Option::as_deref),
+            quote_tweet_url: quote_tweet_url.as_ref().map(Option::as_deref),
+            poll: poll.as_ref().map(Option::as_ref),
+            review: review_reason.as_ref().map(Option::as_deref),
+        },
     )
     .await
     .log_500("Update collateral error")?;
 
     if !updated {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(ApiError::NotFound("Tweet"));
     }
 
     Ok(StatusCode::OK)
 }
+
+#[derive(Deserialize)]
+struct AttachFrameRequest {
+    /// Video capture the frame was extracted from
+    capture_id: i64,
+    /// Index from GET /captures/:id/frames
+    frame_index: usize,
+}
+
+#[derive(Serialize)]
+struct AttachFrameResponse {
+    new_capture_id: i64,
+    image_capture_ids: Vec<i64>,
+}
+
+/// POST /tweets/:id/attach-frame - Promote an extracted frame to a full-resolution
+/// still and add it to the tweet's images
+async fn attach_frame(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_id): Path<i64>,
+    Json(req): Json<AttachFrameRequest>,
+) -> Result<Json<AttachFrameResponse>, ApiError> {
+    let current = tweets::get_tweet_for_posting(&state.db, tweet_id, user_id)
+        .await
+        .log_500("Get tweet for update error")?
+        .ok_or(ApiError::NotFound("Tweet"))?;
+
+    if current.image_capture_ids.len() + current.library_asset_ids.len() >= MAX_TWEET_IMAGES {
+        return Err(ApiError::Unprocessable(format!(
+            "a tweet can have at most {} images",
+            MAX_TWEET_IMAGES
+        )));
+    }
+    if current.poll().is_some() {
+        return Err(ApiError::Unprocessable(
+            "a poll can't be combined with media or a quote tweet".to_string(),
+        ));
+    }
+
+    let media_studio = MediaStudio::new(
+        state.db.clone(),
+        state.gcs.clone(),
+        state.local_storage_path.clone(),
+        state.buckets.clone(),
+    );
+    let new_capture_id = media_studio
+        .extract_frame(user_id, req.capture_id, req.frame_index)
+        .await
+        .map_err(|e| match e {
+            MediaStudioError::NotFound => ApiError::NotFound("Capture"),
+            MediaStudioError::InvalidParams(msg) | MediaStudioError::InvalidMediaType(msg) => {
+                ApiError::Unprocessable(msg)
+            }
+            e => {
+                eprintln!("[media_studio] Frame extract error: {}", e);
+                ApiError::Internal
+            }
+        })?;
+
+    let mut image_capture_ids = current.image_capture_ids;
+    image_capture_ids.push(new_capture_id);
+    let updated = threads::update_tweet_collateral(
+        &state.db,
+        tweet_id,
+        user_id,
+        threads::CollateralUpdate {
+            image_capture_ids: Some(&image_capture_ids),
+            ..Default::default()
+        },
+    )
+    .await
+    .log_500("Update collateral error")?;
+    if !updated {
+        return Err(ApiError::NotFound("Tweet"));
+    }
+
+    Ok(Json(AttachFrameResponse {
+        new_capture_id,
+        image_capture_ids,
+    }))
+}
@@ -1,4 +1,4 @@
-//! Tweet action endpoints (/tweets/*)
+//! Tweet action endpoints (/tweets/*, /publish-jobs/*)
 
 use axum::{
     Json, Router,
@@ -6,35 +6,69 @@ use axum::{
         Path, Query, State, WebSocketUpgrade,
         ws::{Message, WebSocket},
     },
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::{delete, get, post},
 };
 use axum_extra::extract::CookieJar;
-use futures::{SinkExt, StreamExt};
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, Stream, StreamExt, stream};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
 use super::dto::TweetResponse;
 use super::media::{UploadProgress, upload_tweet_media, upload_tweet_media_with_progress};
+use super::{check_project_account, referenced_tweet_ids};
 use crate::AppState;
+use crate::agent::injection::{MAX_TWEET_CHARS, tweet_length};
 use crate::constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
-use crate::domain::twitter::{queries::threads as thread_queries, tweets};
-use crate::routes::auth::AuthUser;
+use crate::domain::twitter::{
+    ThreadStatus, TweetForPosting, queries::threads as thread_queries, tweets,
+};
+use crate::domain::users;
+use crate::routes::auth::{AuthUser, SessionOrDaemonUser};
 use crate::routes::nudges::get_sanitized_nudges;
-use crate::services::{auth, error::LogErr, session, twitter};
-use reson_agentic::providers::{GenerationConfig, InferenceClient};
-use reson_agentic::types::ChatMessage;
-use reson_agentic::utils::ConversationMessage;
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::{
+    auth,
+    copy_filter::CopyFilter,
+    error::{ApiError, LogErr},
+    idempotency, language,
+    publish_jobs::{self, PublishProgress},
+    session, twitter,
+    validation::{ValidJson, Validate},
+};
+
+/// Most tweets one bulk request can act on
+const MAX_BULK_TWEETS: usize = 200;
+/// Longest dismissal reason, in characters
+const MAX_DISMISS_REASON_CHARS: usize = 280;
+/// How often the publish queue looks for tweets whose undo window has passed
+pub const PUBLISH_QUEUE_INTERVAL_SECS: u64 = 5;
+/// Queued tweets published per pass
+const PUBLISH_QUEUE_BATCH_SIZE: i64 = 20;
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/tweets", get(list_tweets))
-        .route("/tweets/{id}/publish", post(post_tweet))
+        .route(
+            "/tweets/{id}/publish",
+            post(post_tweet).delete(cancel_publish),
+        )
         .route("/tweets/{id}/publish/ws", get(publish_tweet_ws))
+        .route("/publish-jobs/{job_id}", get(get_publish_job))
+        .route("/publish-jobs/{job_id}/events", get(publish_job_events))
         .route("/tweets/{id}", delete(dismiss_tweet))
+        .route("/tweets/dismissed", get(list_dismissed_tweets))
+        .route("/tweets/bulk", post(bulk_tweets))
         .route("/tweets/{id}/regenerate", post(regenerate_tweet))
+        .route("/tweets/{id}/translate", post(translate_tweet))
+        .route("/tweets/{id}/replace", post(replace_tweet))
+        .route("/tweets/{id}/revisions", get(list_tweet_revisions))
 }
 
 #[derive(Deserialize)]
@@ -56,7 +90,7 @@ async fn list_tweets(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Query(query): Query<ListTweetsQuery>,
-) -> Result<Json<ListTweetsResponse>, StatusCode> {
+) -> Result<Json<ListTweetsResponse>, ApiError> {
     let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
     let offset = query.offset.unwrap_or(0);
     let status_filter = query.status.as_deref();
@@ -79,18 +113,346 @@ async fn list_tweets(
     }))
 }
 
+#[derive(Deserialize)]
+struct ListDismissedQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct DismissedTweetResponse {
+    #[serde(flatten)]
+    tweet: TweetResponse,
+    dismissed_at: DateTime<Utc>,
+    dismiss_reason: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ListDismissedResponse {
+    tweets: Vec<DismissedTweetResponse>,
+    total: i64,
+    has_more: bool,
+}
+
+/// GET /tweets/dismissed - The user's dismissed tweets with why, latest first
+async fn list_dismissed_tweets(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Query(query): Query<ListDismissedQuery>,
+) -> Result<Json<ListDismissedResponse>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = tweets::count_dismissed_tweets(&state.db, user_id)
+        .await
+        .log_500("Count dismissed tweets error")?;
+    let result = tweets::list_dismissed_tweets(&state.db, user_id, limit, offset)
+        .await
+        .log_500("List dismissed tweets error")?;
+
+    let has_more = offset + (result.len() as i64) < total;
+
+    Ok(Json(ListDismissedResponse {
+        tweets: result
+            .into_iter()
+            .map(|d| DismissedTweetResponse {
+                tweet: d.tweet.into(),
+                dismissed_at: d.dismissed_at,
+                dismiss_reason: d.dismiss_reason,
+            })
+            .collect(),
+        total,
+        has_more,
+    }))
+}
+
 #[derive(Serialize)]
 struct PostTweetResponse {
     tweet_id: String,
     text: String,
 }
 
-/// POST /tweets/:id/publish - Post a tweet to Twitter
+#[derive(Serialize)]
+struct QueuedPublishResponse {
+    /// Always "queued"
+    status: &'static str,
+    /// When the publish queue posts it
+    publish_at: DateTime<Utc>,
+    /// DELETE before `publish_at` to cancel
+    cancel_url: String,
+}
+
+#[derive(Deserialize)]
+struct PublishQuery {
+    /// Return a job id at once and publish in the background
+    #[serde(default, rename = "async")]
+    background: bool,
+}
+
+#[derive(Serialize)]
+struct PublishJobResponse {
+    job_id: String,
+    /// Server-Sent Events with the job's progress
+    events_url: String,
+    /// The job's latest progress, for polling
+    status_url: String,
+}
+
+/// POST /tweets/:id/publish - Post a tweet to Twitter (session or daemon auth)
+/// With an Idempotency-Key header, a retried publish returns the original result.
+/// With `?async=true`, returns 202 and a publish job to follow instead.
+/// If the user has an undo window (/me/publish-undo), returns 202 with the tweet
+/// queued instead, whatever `async` says; the publish queue posts it later.
 async fn post_tweet(
     State(state): State<Arc<AppState>>,
-    AuthUser(user_id): AuthUser,
+    SessionOrDaemonUser(user_id): SessionOrDaemonUser,
+    Path(tweet_collateral_id): Path<i64>,
+    Query(query): Query<PublishQuery>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let undo_secs = users::get_publish_undo_secs(&state.db, user_id)
+        .await
+        .log_500("Get publish undo window error")?;
+    if undo_secs > 0 {
+        let idempotency_key = idempotency::key_from_headers(&headers)?;
+        let endpoint = format!("POST /tweets/{}/publish", tweet_collateral_id);
+        return idempotency::run(
+            &state.db,
+            user_id,
+            idempotency_key.as_deref(),
+            &endpoint,
+            StatusCode::ACCEPTED,
+            queue_publish(&state, user_id, tweet_collateral_id, undo_secs),
+        )
+        .await;
+    }
+
+    if query.background {
+        let job_id = start_publish_job(state, ctx, user_id, tweet_collateral_id).await?;
+        let response = PublishJobResponse {
+            events_url: format!("/publish-jobs/{}/events", job_id),
+            status_url: format!("/publish-jobs/{}", job_id),
+            job_id,
+        };
+        return Ok((StatusCode::ACCEPTED, Json(response)).into_response());
+    }
+
+    let idempotency_key = idempotency::key_from_headers(&headers)?;
+    let endpoint = format!("POST /tweets/{}/publish", tweet_collateral_id);
+
+    idempotency::run(
+        &state.db,
+        user_id,
+        idempotency_key.as_deref(),
+        &endpoint,
+        StatusCode::OK,
+        publish_tweet(&state, &ctx, user_id, tweet_collateral_id),
+    )
+    .await
+}
+
+/// Queue a tweet to publish once the undo window has passed
+async fn queue_publish(
+    state: &Arc<AppState>,
+    user_id: i64,
+    tweet_collateral_id: i64,
+    undo_secs: i32,
+) -> Result<QueuedPublishResponse, ApiError> {
+    tweets::get_tweet_for_posting(&state.db, tweet_collateral_id, user_id)
+        .await
+        .log_500("Get tweet for posting error")?
+        .ok_or(ApiError::NotFound("Tweet"))?;
+    check_project_account(&state.db, user_id, Some(tweet_collateral_id), None).await?;
+
+    let publish_at =
+        tweets::queue_tweet_publish(&state.db, tweet_collateral_id, user_id, undo_secs)
+            .await
+            .log_500("Queue tweet publish error")?
+            .ok_or_else(|| ApiError::Conflict("Tweet is already posting or posted".to_string()))?;
+
+    Ok(QueuedPublishResponse {
+        status: "queued",
+        publish_at,
+        cancel_url: format!("/tweets/{}/publish", tweet_collateral_id),
+    })
+}
+
+/// DELETE /tweets/:id/publish - Cancel a queued publish while its undo window is
+/// open. The tweet goes back to pending; nothing has been sent to X.
+async fn cancel_publish(
+    State(state): State<Arc<AppState>>,
+    SessionOrDaemonUser(user_id): SessionOrDaemonUser,
     Path(tweet_collateral_id): Path<i64>,
-) -> Result<Json<PostTweetResponse>, StatusCode> {
+) -> Result<StatusCode, ApiError> {
+    let cancelled = tweets::cancel_queued_publish(&state.db, tweet_collateral_id, user_id)
+        .await
+        .log_500("Cancel queued publish error")?;
+    if cancelled {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    tweets::get_tweet_for_posting(&state.db, tweet_collateral_id, user_id)
+        .await
+        .log_500("Get tweet for posting error")?
+        .ok_or(ApiError::NotFound("Tweet"))?;
+    Err(ApiError::Conflict(
+        "Tweet isn't queued, or its undo window has passed".to_string(),
+    ))
+}
+
+/// Post queued tweets whose undo window has passed
+pub async fn run_publish_queue_worker(state: Arc<AppState>) {
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(PUBLISH_QUEUE_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+        crate::services::supervisor::heartbeat("publish_queue");
+        publish_due_tweets(&state).await;
+    }
+}
+
+/// One publish queue pass: claim due tweets and post them
+pub(crate) async fn publish_due_tweets(state: &Arc<AppState>) {
+    // Publishes made here are audited as the scheduler's
+    let ctx = RequestContext {
+        actor: "scheduler",
+        ..Default::default()
+    };
+
+    let due = match tweets::claim_due_publishes(&state.db, PUBLISH_QUEUE_BATCH_SIZE).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("[publish_queue] Claim error: {}", e);
+            return;
+        }
+    };
+    for (tweet_collateral_id, user_id) in due {
+        let tweet =
+            match tweets::get_tweet_for_posting(&state.db, tweet_collateral_id, user_id).await {
+                Ok(Some(tweet)) => tweet,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!(
+                        "[publish_queue] Load error for tweet {}: {}",
+                        tweet_collateral_id, e
+                    );
+                    continue;
+                }
+            };
+        match post_claimed_tweet(state, &ctx, user_id, tweet).await {
+            Ok(posted) => println!(
+                "[publish_queue] User {} - posted tweet {} as {}",
+                user_id, tweet_collateral_id, posted.tweet_id
+            ),
+            // Upstream failures are already recorded on the tweet
+            Err(ApiError::Upstream(error)) => eprintln!(
+                "[publish_queue] User {} - tweet {} failed: {}",
+                user_id, tweet_collateral_id, error
+            ),
+            Err(e) => {
+                let error = e.message();
+                eprintln!(
+                    "[publish_queue] User {} - tweet {}: {}",
+                    user_id, tweet_collateral_id, error
+                );
+                let _ = tweets::mark_tweet_publish_failed(
+                    &state.db,
+                    tweet_collateral_id,
+                    user_id,
+                    &error,
+                )
+                .await;
+            }
+        }
+    }
+}
+
+/// Publish in a spawned task, reporting progress to a new publish job
+async fn start_publish_job(
+    state: Arc<AppState>,
+    ctx: RequestContext,
+    user_id: i64,
+    tweet_collateral_id: i64,
+) -> Result<String, ApiError> {
+    tweets::get_tweet_for_posting(&state.db, tweet_collateral_id, user_id)
+        .await
+        .log_500("Get tweet for posting error")?
+        .ok_or(ApiError::NotFound("Tweet"))?;
+    check_project_account(&state.db, user_id, Some(tweet_collateral_id), None).await?;
+
+    let (job_id, progress) = publish_jobs::start(user_id);
+    tokio::spawn(async move {
+        let (progress_tx, mut progress_rx) = mpsc::channel::<PublishProgress>(32);
+        let forward = {
+            let progress = progress.clone();
+            tokio::spawn(async move {
+                while let Some(update) = progress_rx.recv().await {
+                    progress.send_replace(update);
+                }
+            })
+        };
+
+        let result =
+            do_publish_with_progress(&state, user_id, tweet_collateral_id, progress_tx).await;
+        let _ = forward.await;
+
+        progress.send_replace(match result {
+            Ok((tweet_id, text)) => {
+                record_tweet_published(&state, &ctx, user_id, tweet_collateral_id, &tweet_id).await;
+                PublishProgress::Complete { tweet_id, text }
+            }
+            Err(message) => PublishProgress::Error { message },
+        });
+    });
+
+    Ok(job_id)
+}
+
+/// GET /publish-jobs/:job_id - A publish job's latest progress
+async fn get_publish_job(
+    AuthUser(user_id): AuthUser,
+    Path(job_id): Path<String>,
+) -> Result<Json<PublishProgress>, ApiError> {
+    let progress =
+        publish_jobs::subscribe(&job_id, user_id).ok_or(ApiError::NotFound("Publish job"))?;
+    let latest = progress.borrow().clone();
+    Ok(Json(latest))
+}
+
+/// GET /publish-jobs/:job_id/events - Follow a publish job (Server-Sent Events)
+/// Sends the current state, then each change, and ends after complete or error.
+/// Closely spaced updates may be coalesced into the latest one.
+async fn publish_job_events(
+    AuthUser(user_id): AuthUser,
+    Path(job_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let progress =
+        publish_jobs::subscribe(&job_id, user_id).ok_or(ApiError::NotFound("Publish job"))?;
+
+    let events = stream::unfold(
+        (progress, true, false),
+        |(mut progress, first, finished)| async move {
+            if finished || (!first && progress.changed().await.is_err()) {
+                return None;
+            }
+            let update = progress.borrow_and_update().clone();
+            let event = Event::default()
+                .event(update.event_name())
+                .json_data(&update);
+            Some((event, (progress, false, update.is_finished())))
+        },
+    );
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn publish_tweet(
+    state: &Arc<AppState>,
+    ctx: &RequestContext,
+    user_id: i64,
+    tweet_collateral_id: i64,
+) -> Result<PostTweetResponse, ApiError> {
     println!(
         "[post_tweet] Handler called for tweet_collateral_id={}",
         tweet_collateral_id
@@ -101,24 +463,39 @@ async fn post_tweet(
     let tweet = tweets::get_tweet_for_posting(&state.db, tweet_collateral_id, user_id)
         .await
         .log_500("Get tweet for posting error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Tweet"))?;
+    check_project_account(&state.db, user_id, Some(tweet_collateral_id), None).await?;
 
     let can_publish = tweets::set_tweet_posting(&state.db, tweet_collateral_id, user_id)
         .await
         .log_500("Set tweet posting status error")?;
     if !can_publish {
-        return Err(StatusCode::CONFLICT);
+        return Err(ApiError::Conflict(
+            "Tweet is already posting or posted".to_string(),
+        ));
     }
 
+    post_claimed_tweet(state, ctx, user_id, tweet).await
+}
+
+/// Post a tweet already moved to 'posting', then mark it posted or failed
+async fn post_claimed_tweet(
+    state: &Arc<AppState>,
+    ctx: &RequestContext,
+    user_id: i64,
+    tweet: TweetForPosting,
+) -> Result<PostTweetResponse, ApiError> {
+    let tweet_collateral_id = tweet.id;
+
     // Get user tokens
     let tokens = twitter::get_user_tokens(&state.db, user_id)
         .await
         .log_500("Get user tokens error")?
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or(ApiError::TwitterReauthRequired)?;
 
     // Ensure token is valid (refresh if needed)
     let access_token =
-        auth::ensure_valid_access_token(&state.db, &state.twitter, user_id, tokens).await?;
+        auth::ensure_valid_access_token(&state.db, state.twitter.as_ref(), user_id, tokens).await?;
 
     let publish_result = (|| async {
         // Upload media if present
@@ -128,7 +505,7 @@ async fn post_tweet(
             tweet.image_capture_ids
         );
 
-        let media_ids = upload_tweet_media(&state, user_id, &tweet, &access_token)
+        let media_ids = upload_tweet_media(state, user_id, &tweet, &access_token)
             .await
             .map_err(|e| e.to_string())?;
 
@@ -144,9 +521,18 @@ async fn post_tweet(
         };
 
         // Post the tweet with media
+        let (in_reply_to, quote_tweet_id) = referenced_tweet_ids(&tweet);
+        let poll = tweet.poll();
         let twitter_response = state
             .twitter
-            .post_tweet(&access_token, &tweet.text, None, media_ids_ref.as_deref())
+            .post_tweet(
+                &access_token,
+                &tweet.text,
+                in_reply_to.as_deref(),
+                quote_tweet_id.as_deref(),
+                media_ids_ref.as_deref(),
+                poll.as_ref(),
+            )
             .await
             .map_err(|e| format!("Failed to post tweet: {}", e))?;
 
@@ -159,66 +545,470 @@ async fn post_tweet(
     .await;
 
     match publish_result {
-        Ok((tweet_id, text)) => Ok(Json(PostTweetResponse { tweet_id, text })),
+        Ok((tweet_id, text)) => {
+            record_tweet_published(state, ctx, user_id, tweet_collateral_id, &tweet_id).await;
+            Ok(PostTweetResponse { tweet_id, text })
+        }
         Err(error) => {
             let _ =
                 tweets::mark_tweet_publish_failed(&state.db, tweet_collateral_id, user_id, &error)
                     .await;
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(ApiError::Upstream(error))
         }
     }
 }
 
-/// DELETE /tweets/:id - Dismiss a pending tweet without posting
+#[derive(Deserialize)]
+struct DismissTweetRequest {
+    /// Why the draft was rejected; recent reasons are shown to the agent
+    reason: Option<String>,
+}
+
+/// Trimmed dismissal reason, None when blank
+fn checked_dismiss_reason(reason: Option<&str>) -> Result<Option<&str>, ApiError> {
+    let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+    if reason.is_some_and(|r| r.chars().count() > MAX_DISMISS_REASON_CHARS) {
+        return Err(ApiError::Unprocessable(format!(
+            "reason must be at most {} characters",
+            MAX_DISMISS_REASON_CHARS
+        )));
+    }
+    Ok(reason)
+}
+
+/// DELETE /tweets/:id - Dismiss a pending tweet without posting (session or daemon auth)
+/// An optional JSON body `{"reason": "..."}` records why.
 async fn dismiss_tweet(
     State(state): State<Arc<AppState>>,
-    AuthUser(user_id): AuthUser,
+    SessionOrDaemonUser(user_id): SessionOrDaemonUser,
     Path(tweet_collateral_id): Path<i64>,
-) -> Result<StatusCode, StatusCode> {
-    let deleted = tweets::delete_tweet(&state.db, tweet_collateral_id, user_id)
+    ctx: RequestContext,
+    req: Option<Json<DismissTweetRequest>>,
+) -> Result<StatusCode, ApiError> {
+    let reason = req.and_then(|Json(r)| r.reason);
+    let reason = checked_dismiss_reason(reason.as_deref())?;
+    let deleted = tweets::delete_tweet(&state.db, tweet_collateral_id, user_id, reason)
         .await
         .log_500("Delete tweet error")?;
 
     if !deleted {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(ApiError::NotFound("Tweet"));
     }
 
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::TweetDeleted,
+        Some(&tweet_collateral_id.to_string()),
+        None,
+    )
+    .await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
-/// WebSocket progress messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BulkAction {
+    /// Dismiss pending tweets, like DELETE /tweets/:id
+    Dismiss,
+    /// Clear the compliance filter's review flag
+    Approve,
+    /// Append standalone tweets to `thread_id`, in the order given
+    AttachToThread,
+}
+
+#[derive(Deserialize)]
+struct BulkTweetsRequest {
+    action: BulkAction,
+    tweet_ids: Vec<i64>,
+    thread_id: Option<i64>,
+    /// Dismissal reason, recorded on every dismissed tweet
+    reason: Option<String>,
+}
+
+impl Validate for BulkTweetsRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        if self.tweet_ids.is_empty() || self.tweet_ids.len() > MAX_BULK_TWEETS {
+            return Err(ApiError::Unprocessable(format!(
+                "tweet_ids must have 1-{} entries",
+                MAX_BULK_TWEETS
+            )));
+        }
+        if (self.action == BulkAction::AttachToThread) != self.thread_id.is_some() {
+            return Err(ApiError::Unprocessable(
+                "thread_id is required for attach_to_thread and only allowed there".to_string(),
+            ));
+        }
+        if self.reason.is_some() && self.action != BulkAction::Dismiss {
+            return Err(ApiError::Unprocessable(
+                "reason is only allowed for dismiss".to_string(),
+            ));
+        }
+        checked_dismiss_reason(self.reason.as_deref())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum BulkOutcome {
+    Ok,
+    /// Not the user's, already posted or already dismissed
+    NotFound,
+    /// In another thread
+    Conflict,
+}
+
 #[derive(Serialize)]
-#[serde(tag = "type")]
-enum WsProgress {
-    #[serde(rename = "uploading")]
-    Uploading {
-        segment: usize,
-        total: usize,
-        percent: u8,
-    },
-    #[serde(rename = "processing")]
-    Processing,
-    #[serde(rename = "posting")]
-    Posting,
-    #[serde(rename = "complete")]
-    Complete { tweet_id: String, text: String },
-    #[serde(rename = "error")]
-    Error { message: String },
+struct BulkTweetResult {
+    id: i64,
+    outcome: BulkOutcome,
 }
 
-impl From<UploadProgress> for WsProgress {
+#[derive(Serialize)]
+struct BulkTweetsResponse {
+    succeeded: usize,
+    failed: usize,
+    results: Vec<BulkTweetResult>,
+}
+
+/// POST /tweets/bulk - Dismiss, approve or attach many tweets to a thread at once.
+/// Runs in one transaction; a tweet the action can't apply to is reported in
+/// `results` and doesn't stop the others.
+async fn bulk_tweets(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    ValidJson(req): ValidJson<BulkTweetsRequest>,
+) -> Result<Json<BulkTweetsResponse>, ApiError> {
+    let mut seen = std::collections::HashSet::new();
+    let ids: Vec<i64> = req
+        .tweet_ids
+        .iter()
+        .copied()
+        .filter(|id| seen.insert(*id))
+        .collect();
+
+    let reason = checked_dismiss_reason(req.reason.as_deref())?;
+
+    let mut tx = state.db.begin().await.log_500("Begin transaction error")?;
+
+    if let Some(thread_id) = req.thread_id {
+        let status = thread_queries::get_thread_status(&mut *tx, thread_id, user_id)
+            .await
+            .log_500("Get thread status error")?
+            .ok_or(ApiError::NotFound("Thread"))?;
+        if status != ThreadStatus::Draft {
+            return Err(ApiError::Conflict(
+                "Only draft threads can be edited".to_string(),
+            ));
+        }
+    }
+
+    let mut results = Vec::with_capacity(ids.len());
+    for &id in &ids {
+        let outcome = match (req.action, req.thread_id) {
+            (BulkAction::Dismiss, _) => tweets::delete_tweet(&mut *tx, id, user_id, reason)
+                .await
+                .log_500("Delete tweet error")?
+                .then_some(BulkOutcome::Ok),
+            (BulkAction::Approve, _) => tweets::approve_tweet(&mut *tx, id, user_id)
+                .await
+                .log_500("Approve tweet error")?
+                .then_some(BulkOutcome::Ok),
+            (BulkAction::AttachToThread, Some(thread_id)) => {
+                match thread_queries::get_tweet_thread_info(&mut *tx, id, user_id)
+                    .await
+                    .log_500("Get tweet thread info error")?
+                {
+                    None => None,
+                    Some(Some(current)) if current == thread_id => Some(BulkOutcome::Ok),
+                    Some(Some(_)) => Some(BulkOutcome::Conflict),
+                    Some(None) => {
+                        let max_pos =
+                            thread_queries::get_max_thread_position(&mut *tx, thread_id, user_id)
+                                .await
+                                .log_500("Get max thread position error")?;
+                        thread_queries::assign_tweet_to_thread(
+                            &mut *tx,
+                            id,
+                            thread_id,
+                            user_id,
+                            max_pos.map_or(0, |p| p + 1),
+                        )
+                        .await
+                        .log_500("Assign tweet to thread error")?;
+                        Some(BulkOutcome::Ok)
+                    }
+                }
+            }
+            (BulkAction::AttachToThread, None) => unreachable!("validated above"),
+        };
+        results.push(BulkTweetResult {
+            id,
+            outcome: outcome.unwrap_or(BulkOutcome::NotFound),
+        });
+    }
+
+    tx.commit().await.log_500("Commit transaction error")?;
+
+    if req.action == BulkAction::Dismiss {
+        for result in results.iter().filter(|r| r.outcome == BulkOutcome::Ok) {
+            audit::record(
+                &state.db,
+                &ctx,
+                user_id,
+                Action::TweetDeleted,
+                Some(&result.id.to_string()),
+                Some(serde_json::json!({ "bulk": true })),
+            )
+            .await;
+        }
+    }
+
+    let succeeded = results
+        .iter()
+        .filter(|r| r.outcome == BulkOutcome::Ok)
+        .count();
+    Ok(Json(BulkTweetsResponse {
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    }))
+}
+
+#[derive(Deserialize)]
+struct ReplaceTweetRequest {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct ReplaceTweetResponse {
+    tweet_id: String,
+    text: String,
+    previous_tweet_id: String,
+    /// False if X refused to delete the old tweet; it may still be up
+    previous_deleted: bool,
+}
+
+/// POST /tweets/:id/replace - Correct a posted tweet
+///
+/// X has no edit API, so this posts the corrected text (re-attaching the same
+/// media), points the collateral at the new tweet, records a revision and then
+/// deletes the old tweet. Posting first means a failed post leaves the original
+/// up rather than nothing at all.
+async fn replace_tweet(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_collateral_id): Path<i64>,
+    ctx: RequestContext,
+    Json(req): Json<ReplaceTweetRequest>,
+) -> Result<Json<ReplaceTweetResponse>, ApiError> {
+    let posted = tweets::get_posted_tweet(&state.db, tweet_collateral_id, user_id)
+        .await
+        .log_500("Get posted tweet error")?
+        .ok_or(ApiError::NotFound("Posted tweet"))?;
+
+    // Later tweets in the thread reply to this one; a new ID would orphan them
+    if posted.thread_id.is_some() {
+        return Err(ApiError::Conflict(
+            "Tweets in a thread can't be replaced".to_string(),
+        ));
+    }
+
+    let text = req.text.trim();
+    if text.is_empty() || text.chars().count() > 280 {
+        return Err(ApiError::Unprocessable(
+            "text must be 1 to 280 characters".to_string(),
+        ));
+    }
+    if text == posted.tweet.text {
+        return Err(ApiError::Unprocessable("text is unchanged".to_string()));
+    }
+
+    let tokens = twitter::get_user_tokens(&state.db, user_id)
+        .await
+        .log_500("Get user tokens error")?
+        .ok_or(ApiError::TwitterReauthRequired)?;
+    let access_token =
+        auth::ensure_valid_access_token(&state.db, state.twitter.as_ref(), user_id, tokens).await?;
+
+    let media_ids = upload_tweet_media(&state, user_id, &posted.tweet, &access_token)
+        .await
+        .map_err(ApiError::Upstream)?;
+    let media_ids_ref = (!media_ids.is_empty()).then_some(media_ids.as_slice());
+
+    let (in_reply_to, quote_tweet_id) = referenced_tweet_ids(&posted.tweet);
+    let poll = posted.tweet.poll();
+    let new_tweet = state
+        .twitter
+        .post_tweet(
+            &access_token,
+            text,
+            in_reply_to.as_deref(),
+            quote_tweet_id.as_deref(),
+            media_ids_ref,
+            poll.as_ref(),
+        )
+        .await
+        .map_err(|e| ApiError::Upstream(format!("Failed to post tweet: {}", e)))?;
+
+    let mut tx = state.db.begin().await.log_500("Begin transaction error")?;
+    let replaced = tweets::replace_posted_tweet(
+        &mut *tx,
+        tweet_collateral_id,
+        user_id,
+        &posted.tweet_id,
+        &new_tweet.text,
+        &new_tweet.id,
+    )
+    .await
+    .log_500("Replace posted tweet error")?;
+
+    if !replaced {
+        // Another correction won the race; take ours back down
+        drop(tx);
+        if let Err(e) = state
+            .twitter
+            .delete_tweet(&access_token, &new_tweet.id)
+            .await
+        {
+            eprintln!(
+                "[replace_tweet] Orphaned tweet {} for collateral {}: {}",
+                new_tweet.id, tweet_collateral_id, e
+            );
+        }
+        return Err(ApiError::Conflict(
+            "Tweet was replaced by another request".to_string(),
+        ));
+    }
+
+    let revision_id = tweets::insert_revision(
+        &mut *tx,
+        tweet_collateral_id,
+        user_id,
+        &posted.tweet.text,
+        &posted.tweet_id,
+        &new_tweet.text,
+        &new_tweet.id,
+    )
+    .await
+    .log_500("Insert tweet revision error")?;
+    tx.commit().await.log_500("Commit transaction error")?;
+
+    let previous_deleted = match state
+        .twitter
+        .delete_tweet(&access_token, &posted.tweet_id)
+        .await
+    {
+        Ok(()) => {
+            tweets::mark_revision_deleted(&state.db, revision_id)
+                .await
+                .log_500("Mark revision deleted error")?;
+            true
+        }
+        Err(e) => {
+            eprintln!(
+                "[replace_tweet] Failed to delete replaced tweet {}: {}",
+                posted.tweet_id, e
+            );
+            false
+        }
+    };
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::TweetReplaced,
+        Some(&tweet_collateral_id.to_string()),
+        Some(serde_json::json!({
+            "previous_tweet_id": posted.tweet_id,
+            "tweet_id": new_tweet.id,
+            "previous_deleted": previous_deleted,
+        })),
+    )
+    .await;
+
+    Ok(Json(ReplaceTweetResponse {
+        tweet_id: new_tweet.id,
+        text: new_tweet.text,
+        previous_tweet_id: posted.tweet_id,
+        previous_deleted,
+    }))
+}
+
+/// Revision API response DTO
+#[derive(Serialize)]
+struct TweetRevisionResponse {
+    id: i64,
+    previous_text: String,
+    previous_tweet_id: String,
+    new_text: String,
+    new_tweet_id: String,
+    previous_deleted: bool,
+    created_at: DateTime<Utc>,
+}
+
+/// GET /tweets/:id/revisions - Corrections made to a posted tweet, newest first
+async fn list_tweet_revisions(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_collateral_id): Path<i64>,
+) -> Result<Json<Vec<TweetRevisionResponse>>, ApiError> {
+    let revisions = tweets::list_revisions(&state.db, tweet_collateral_id, user_id)
+        .await
+        .log_500("List tweet revisions error")?;
+
+    Ok(Json(
+        revisions
+            .into_iter()
+            .map(|r| TweetRevisionResponse {
+                id: r.id,
+                previous_text: r.previous_text,
+                previous_tweet_id: r.previous_tweet_id,
+                new_text: r.new_text,
+                new_tweet_id: r.new_tweet_id,
+                previous_deleted: r.previous_deleted,
+                created_at: r.created_at,
+            })
+            .collect(),
+    ))
+}
+
+async fn record_tweet_published(
+    state: &AppState,
+    ctx: &RequestContext,
+    user_id: i64,
+    tweet_collateral_id: i64,
+    tweet_id: &str,
+) {
+    audit::record(
+        &state.db,
+        ctx,
+        user_id,
+        Action::TweetPublished,
+        Some(&tweet_collateral_id.to_string()),
+        Some(serde_json::json!({ "tweet_id": tweet_id })),
+    )
+    .await;
+}
+
+impl From<UploadProgress> for PublishProgress {
     fn from(p: UploadProgress) -> Self {
         match p {
             UploadProgress::Uploading {
                 segment,
                 total,
                 percent,
-            } => WsProgress::Uploading {
+            } => PublishProgress::Uploading {
                 segment,
                 total,
                 percent,
             },
-            UploadProgress::Processing => WsProgress::Processing,
+            UploadProgress::Processing => PublishProgress::Processing,
         }
     }
 }
@@ -229,29 +1019,37 @@ async fn publish_tweet_ws(
     State(state): State<Arc<AppState>>,
     jar: CookieJar,
     Path(tweet_collateral_id): Path<i64>,
-) -> Result<impl IntoResponse, StatusCode> {
+    ctx: RequestContext,
+) -> Result<impl IntoResponse, ApiError> {
     // Validate JWT from cookie
     let access_token = jar
         .get("access_token")
         .map(|c| c.value())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or(ApiError::Unauthorized)?;
 
-    let user_id = session::validate_access_token(access_token, &state.jwt_secret)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let user_id =
+        session::validate_access_token(access_token, &state.jwt_secret).map_err(|e| match e {
+            session::SessionError::Expired => ApiError::TokenExpired,
+            _ => ApiError::Unauthorized,
+        })?;
+    check_project_account(&state.db, user_id, Some(tweet_collateral_id), None).await?;
 
-    Ok(ws.on_upgrade(move |socket| handle_publish_ws(socket, state, user_id, tweet_collateral_id)))
+    Ok(ws.on_upgrade(move |socket| {
+        handle_publish_ws(socket, state, ctx, user_id, tweet_collateral_id)
+    }))
 }
 
 async fn handle_publish_ws(
     socket: WebSocket,
     state: Arc<AppState>,
+    ctx: RequestContext,
     user_id: i64,
     tweet_collateral_id: i64,
 ) {
     let (mut sender, _receiver) = socket.split();
 
     // Create channel for progress updates
-    let (progress_tx, mut progress_rx) = mpsc::channel::<WsProgress>(32);
+    let (progress_tx, mut progress_rx) = mpsc::channel::<PublishProgress>(32);
 
     // Spawn task to forward progress to WebSocket
     let send_task = tokio::spawn(async move {
@@ -271,12 +1069,15 @@ async fn handle_publish_ws(
     // Send final message
     match result {
         Ok((tweet_id, text)) => {
+            record_tweet_published(&state, &ctx, user_id, tweet_collateral_id, &tweet_id).await;
             let _ = progress_tx
-                .send(WsProgress::Complete { tweet_id, text })
+                .send(PublishProgress::Complete { tweet_id, text })
                 .await;
         }
         Err(e) => {
-            let _ = progress_tx.send(WsProgress::Error { message: e }).await;
+            let _ = progress_tx
+                .send(PublishProgress::Error { message: e })
+                .await;
         }
     }
 
@@ -290,7 +1091,7 @@ async fn do_publish_with_progress(
     state: &Arc<AppState>,
     user_id: i64,
     tweet_collateral_id: i64,
-    progress_tx: mpsc::Sender<WsProgress>,
+    progress_tx: mpsc::Sender<PublishProgress>,
 ) -> Result<(String, String), String> {
     // Get the tweet with media info
     let tweet = tweets::get_tweet_for_posting(&state.db, tweet_collateral_id, user_id)
@@ -315,7 +1116,8 @@ async fn do_publish_with_progress(
 
         // Ensure token is valid (refresh if needed)
         let access_token =
-            auth::ensure_valid_access_token_str(&state.db, &state.twitter, user_id, tokens).await?;
+            auth::ensure_valid_access_token_str(&state.db, state.twitter.as_ref(), user_id, tokens)
+                .await?;
 
         // Upload media with progress
         let media_ids = upload_tweet_media_with_progress(
@@ -329,7 +1131,7 @@ async fn do_publish_with_progress(
         .map_err(|e| format!("Media upload error: {}", e))?;
 
         // Send posting status
-        let _ = progress_tx.send(WsProgress::Posting).await;
+        let _ = progress_tx.send(PublishProgress::Posting).await;
 
         let media_ids_ref: Option<Vec<String>> = if media_ids.is_empty() {
             None
@@ -338,9 +1140,18 @@ async fn do_publish_with_progress(
         };
 
         // Post the tweet
+        let (in_reply_to, quote_tweet_id) = referenced_tweet_ids(&tweet);
+        let poll = tweet.poll();
         let twitter_response = state
             .twitter
-            .post_tweet(&access_token, &tweet.text, None, media_ids_ref.as_deref())
+            .post_tweet(
+                &access_token,
+                &tweet.text,
+                in_reply_to.as_deref(),
+                quote_tweet_id.as_deref(),
+                media_ids_ref.as_deref(),
+                poll.as_ref(),
+            )
             .await
             .map_err(|e| format!("Failed to post tweet: {}", e))?;
 
@@ -367,6 +1178,8 @@ async fn do_publish_with_progress(
 #[derive(Serialize)]
 struct RegenerateTweetResponse {
     text: String,
+    /// Why the compliance filter flagged the new text, if it did
+    review_reason: Option<String>,
 }
 
 /// POST /tweets/:id/regenerate - Generate a new variation of a tweet using AI
@@ -374,18 +1187,18 @@ async fn regenerate_tweet(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Path(tweet_id): Path<i64>,
-) -> Result<Json<RegenerateTweetResponse>, StatusCode> {
-    // Check if Gemini is available
-    let gemini = state.gemini.as_ref().ok_or_else(|| {
-        eprintln!("[regenerate_tweet] Gemini client not available");
-        StatusCode::SERVICE_UNAVAILABLE
+) -> Result<Json<RegenerateTweetResponse>, ApiError> {
+    // Check if an LLM is available
+    let llm = state.llm.as_ref().ok_or_else(|| {
+        eprintln!("[regenerate_tweet] LLM provider not available");
+        ApiError::ServiceUnavailable("Tweet regeneration")
     })?;
 
     // Get the tweet with its context
     let tweet = tweets::get_tweet_for_posting(&state.db, tweet_id, user_id)
         .await
         .log_500("Get tweet error")?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(ApiError::NotFound("Tweet"))?;
 
     // Get user's style nudges for voice customization
     let nudges = get_sanitized_nudges(&state.db, user_id).await;
@@ -406,7 +1219,8 @@ Why this moment matters:
 {}
 {}
 Rules:
-- Keep it under 280 characters
+- Keep it under 280 characters (X counts Chinese, Japanese and Korean characters as 2)
+- Write it in the same language as the original
 - No AI-sounding phrases: "excited to share", "dive into", "game-changer", "incredibly", "just"
 - No emoji spam
 - No over-explaining or hedging
@@ -417,58 +1231,128 @@ Respond with ONLY the new tweet text, nothing else."#,
         tweet.text, tweet.rationale, nudges_section
     );
 
-    // Call Gemini
-    let messages = vec![ConversationMessage::Chat(ChatMessage::user(prompt))];
-    let config = GenerationConfig {
-        model: "gemini-2.5-flash".to_string(),
-        max_tokens: Some(100),
-        temperature: Some(0.9), // Higher temperature for more variation
-        top_p: None,
-        tools: None,
-        native_tools: false,
-        reasoning_effort: None,
-        thinking_budget: None,
-        output_schema: None,
-        output_type_name: None,
-        timeout: Some(std::time::Duration::from_secs(600)),
-    };
-
-    let response = gemini
-        .get_generation(&messages, &config)
-        .await
-        .map_err(|e| {
-            eprintln!("[regenerate_tweet] Gemini error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
-
-    // Extract the text from the response (content is already a String)
-    let new_text = response.content.trim().trim_matches('"').to_string();
+    // Higher temperature for more variation
+    let response = llm.complete(&prompt, 100, 0.9).await.map_err(|e| {
+        eprintln!("[regenerate_tweet] LLM error: {}", e);
+        ApiError::Upstream("Tweet generation failed".to_string())
+    })?;
 
-    // Validate length
-    if new_text.is_empty() || new_text.len() > 280 {
+    let new_text = checked_generated_text(&response).ok_or_else(|| {
         eprintln!(
             "[regenerate_tweet] Invalid generated text length: {}",
-            new_text.len()
+            tweet_length(response.trim())
         );
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
+        ApiError::Upstream("Generated tweet was empty or too long".to_string())
+    })?;
+
+    let review_reason = save_rewritten_text(&state, user_id, tweet_id, &new_text).await?;
+
+    println!(
+        "[regenerate_tweet] Generated new text for tweet {}: {}",
+        tweet_id, new_text
+    );
+
+    Ok(Json(RegenerateTweetResponse {
+        text: new_text,
+        review_reason,
+    }))
+}
+
+/// Model output as tweet text, if it's non-empty and fits in a tweet
+fn checked_generated_text(response: &str) -> Option<String> {
+    let text = response.trim().trim_matches('"').trim();
+    (!text.is_empty() && tweet_length(text) <= MAX_TWEET_CHARS).then(|| text.to_string())
+}
+
+/// Replace a draft's text with an LLM rewrite, returning why the compliance
+/// filter flagged it, if it did
+async fn save_rewritten_text(
+    state: &AppState,
+    user_id: i64,
+    tweet_id: i64,
+    text: &str,
+) -> Result<Option<String>, ApiError> {
+    let review_reason = CopyFilter::for_user(&state.db, user_id)
+        .await
+        .review_reason([text]);
 
-    // Update the tweet in the database
     thread_queries::update_tweet_collateral(
         &state.db,
         tweet_id,
         user_id,
-        Some(&new_text),
-        None,
-        None,
+        thread_queries::CollateralUpdate {
+            text: Some(text),
+            review: Some(review_reason.as_deref()),
+            ..Default::default()
+        },
     )
     .await
     .log_500("Update tweet text error")?;
 
+    Ok(review_reason)
+}
+
+#[derive(Deserialize)]
+struct TranslateTweetRequest {
+    /// Code of the target language (see GET /me/language)
+    language: String,
+}
+
+/// POST /tweets/:id/translate - Rewrite a draft in another language
+async fn translate_tweet(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(tweet_id): Path<i64>,
+    Json(req): Json<TranslateTweetRequest>,
+) -> Result<Json<RegenerateTweetResponse>, ApiError> {
+    let language = language::checked(&req.language)?;
+    let llm = state.llm.as_ref().ok_or_else(|| {
+        eprintln!("[translate_tweet] LLM provider not available");
+        ApiError::ServiceUnavailable("Tweet translation")
+    })?;
+
+    let tweet = tweets::get_tweet_for_posting(&state.db, tweet_id, user_id)
+        .await
+        .log_500("Get tweet error")?
+        .ok_or(ApiError::NotFound("Tweet"))?;
+
+    let prompt = format!(
+        r#"Translate this tweet into {}.
+
+Tweet:
+"{}"
+
+Rules:
+- Keep the meaning and the casual, direct tone; reword rather than translate word for word where that reads better
+- Keep links, @handles, #hashtags, code, commands and product or library names exactly as they are
+- It must still fit in one tweet: 280 characters, where X counts Chinese, Japanese and Korean characters as 2
+
+Respond with ONLY the translated tweet text, nothing else."#,
+        language.name, tweet.text
+    );
+
+    let response = llm.complete(&prompt, 200, 0.3).await.map_err(|e| {
+        eprintln!("[translate_tweet] LLM error: {}", e);
+        ApiError::Upstream("Tweet translation failed".to_string())
+    })?;
+
+    let new_text = checked_generated_text(&response).ok_or_else(|| {
+        eprintln!(
+            "[translate_tweet] Invalid translated text length: {}",
+            tweet_length(response.trim())
+        );
+        ApiError::Upstream("Translated tweet was empty or too long".to_string())
+    })?;
+
+    let review_reason = save_rewritten_text(&state, user_id, tweet_id, &new_text).await?;
+
     println!(
-        "[regenerate_tweet] Generated new text for tweet {}: {}",
-        tweet_id, new_text
+        "[translate_tweet] Translated tweet {} into {}",
+        tweet_id, language.code
     );
 
-    Ok(Json(RegenerateTweetResponse { text: new_text }))
+    Ok(Json(RegenerateTweetResponse {
+        text: new_text,
+        review_reason,
+    }))
 }
@@ -0,0 +1,185 @@
+//! Saved content views (/me/views)
+//!
+//! A view stores a GET /content filter and sort under a name, so the web app
+//! can switch between lists like "Needs review" and "Top performers". Pass its
+//! id as `GET /content?view=` to apply it; query parameters override its values.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::is_valid_bound;
+use crate::AppState;
+use crate::domain::content::ContentSort;
+use crate::domain::content_views::{self, ContentView, ViewFilters};
+use crate::routes::auth::AuthUser;
+use crate::services::error::{ApiError, LogErr};
+use crate::services::validation::{ValidJson, Validate};
+
+/// Longest view name
+const MAX_VIEW_NAME_LEN: usize = 64;
+/// Longest saved search text
+const MAX_QUERY_LEN: usize = 200;
+/// Most views a user can save
+const MAX_VIEWS: i64 = 50;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/me/views", get(list_views).post(create_view))
+        .route("/me/views/{id}", delete(delete_view))
+}
+
+#[derive(Deserialize)]
+struct CreateViewRequest {
+    name: String,
+    #[serde(flatten)]
+    filters: ViewFilters,
+    /// `created_at` (default), `posted_at` or `engagement`
+    sort: Option<String>,
+}
+
+impl Validate for CreateViewRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        let name = self.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_VIEW_NAME_LEN {
+            return Err(ApiError::Unprocessable(format!(
+                "name must be 1-{} characters",
+                MAX_VIEW_NAME_LEN
+            )));
+        }
+        if let Some(sort) = &self.sort
+            && ContentSort::parse(sort).is_none()
+        {
+            return Err(ApiError::Unprocessable(
+                "sort must be created_at, posted_at or engagement".to_string(),
+            ));
+        }
+        if let Some(status) = &self.filters.status
+            && !matches!(status.as_str(), "pending" | "posted" | "all")
+        {
+            return Err(ApiError::Unprocessable(
+                "status must be pending, posted or all".to_string(),
+            ));
+        }
+        if self
+            .filters
+            .q
+            .as_ref()
+            .is_some_and(|q| q.chars().count() > MAX_QUERY_LEN)
+        {
+            return Err(ApiError::Unprocessable(format!(
+                "q must be at most {} characters",
+                MAX_QUERY_LEN
+            )));
+        }
+        for (field, value) in [("from", &self.filters.from), ("to", &self.filters.to)] {
+            if let Some(value) = value
+                && !is_valid_bound(value)
+            {
+                return Err(ApiError::Unprocessable(format!(
+                    "{} must be YYYY-MM-DD or an RFC 3339 timestamp",
+                    field
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct ContentViewResponse {
+    id: i64,
+    name: String,
+    #[serde(flatten)]
+    filters: ViewFilters,
+    sort: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ContentView> for ContentViewResponse {
+    fn from(v: ContentView) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            filters: v.filters.0,
+            sort: v.sort,
+            created_at: v.created_at,
+        }
+    }
+}
+
+/// GET /me/views - The user's saved content views
+async fn list_views(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<ContentViewResponse>>, ApiError> {
+    let views = content_views::list_views(&state.db, user_id)
+        .await
+        .log_500("List content views error")?;
+    Ok(Json(views.into_iter().map(Into::into).collect()))
+}
+
+/// POST /me/views - Save a filter and sort under a name
+async fn create_view(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ValidJson(req): ValidJson<CreateViewRequest>,
+) -> Result<(StatusCode, Json<ContentViewResponse>), ApiError> {
+    let count = content_views::count_views(&state.db, user_id)
+        .await
+        .log_500("Count content views error")?;
+    if count >= MAX_VIEWS {
+        return Err(ApiError::Unprocessable(format!(
+            "at most {} saved views",
+            MAX_VIEWS
+        )));
+    }
+
+    let trimmed = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let filters = ViewFilters {
+        status: trimmed(req.filters.status),
+        project: trimmed(req.filters.project),
+        q: trimmed(req.filters.q),
+        from: trimmed(req.filters.from),
+        to: trimmed(req.filters.to),
+        has_media: req.filters.has_media,
+    };
+    let sort = req
+        .sort
+        .as_deref()
+        .unwrap_or(ContentSort::default().as_str());
+
+    let view = content_views::insert_view(&state.db, user_id, req.name.trim(), &filters, sort)
+        .await
+        .log_500("Insert content view error")?
+        .ok_or_else(|| {
+            ApiError::Conflict(format!("A view named {:?} already exists", req.name.trim()))
+        })?;
+    Ok((StatusCode::CREATED, Json(view.into())))
+}
+
+/// DELETE /me/views/:id - Delete a saved view
+async fn delete_view(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    if content_views::delete_view(&state.db, user_id, id)
+        .await
+        .log_500("Delete content view error")?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound("View"))
+    }
+}
@@ -0,0 +1,144 @@
+//! Device management endpoints (/me/devices)
+//!
+//! Each daemon install identifies itself with a stable X-Device-ID header. The
+//! device row is created when the daemon enrolls its API token and refreshed on
+//! every daemon request, along with the code-signing status the daemon reports in
+//! X-Device-Integrity. Revoking a device makes the API reject it with 403 and
+//! clears the account's API token, so the daemon can't come back under another
+//! device id; the user's remaining daemons sign in again.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use super::captures::get_daemon_identity;
+use crate::AppState;
+use crate::domain::devices;
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::error::LogErr;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/me/devices", get(list_devices).post(enroll_device))
+        .route("/me/devices/{id}", delete(revoke_device))
+}
+
+/// Device API response DTO
+#[derive(Debug, Serialize)]
+pub struct DeviceResponse {
+    pub id: i64,
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Code-signing status the daemon last reported (notarized, signed, ...)
+    pub integrity: Option<String>,
+    pub capture_count: i64,
+    pub last_capture_at: Option<DateTime<Utc>>,
+}
+
+impl From<devices::DeviceWithStats> for DeviceResponse {
+    fn from(d: devices::DeviceWithStats) -> Self {
+        Self {
+            id: d.id,
+            name: d.name,
+            created_at: d.created_at,
+            last_seen_at: d.last_seen_at,
+            revoked_at: d.revoked_at,
+            integrity: d.integrity,
+            capture_count: d.capture_count,
+            last_capture_at: d.last_capture_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EnrollDeviceResponse {
+    id: i64,
+}
+
+/// POST /me/devices - Register the calling daemon (daemon auth, X-Device-ID required)
+async fn enroll_device(
+    State(state): State<Arc<AppState>>,
+    ctx: RequestContext,
+    headers: HeaderMap,
+) -> Result<Json<EnrollDeviceResponse>, StatusCode> {
+    let identity = get_daemon_identity(&state.db, &headers).await?;
+    let id = identity.device_id.ok_or(StatusCode::BAD_REQUEST)?;
+
+    println!(
+        "[devices] Enrolled device {} for user {}",
+        id, identity.user_id
+    );
+
+    audit::record(
+        &state.db,
+        &ctx,
+        identity.user_id,
+        Action::DeviceEnrolled,
+        Some(&id.to_string()),
+        None,
+    )
+    .await;
+
+    Ok(Json(EnrollDeviceResponse { id }))
+}
+
+#[derive(Serialize)]
+struct ListDevicesResponse {
+    devices: Vec<DeviceResponse>,
+}
+
+/// GET /me/devices - List devices with last-seen and capture stats
+async fn list_devices(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<ListDevicesResponse>, StatusCode> {
+    let rows = devices::list_devices(&state.db, user_id)
+        .await
+        .log_500("List devices error")?;
+
+    Ok(Json(ListDevicesResponse {
+        devices: rows.into_iter().map(DeviceResponse::from).collect(),
+    }))
+}
+
+/// DELETE /me/devices/:id - Revoke a device so its daemon can no longer upload
+async fn revoke_device(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(device_id): Path<i64>,
+    ctx: RequestContext,
+) -> Result<StatusCode, StatusCode> {
+    let revoked = devices::revoke_device(&state.db, device_id, user_id)
+        .await
+        .log_500("Revoke device error")?;
+
+    if !revoked {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    println!(
+        "[devices] Revoked device {} for user {}",
+        device_id, user_id
+    );
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::DeviceRevoked,
+        Some(&device_id.to_string()),
+        None,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
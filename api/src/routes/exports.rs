@@ -0,0 +1,162 @@
+//! User data export endpoints (/me/export)
+//!
+//! Exports are built by the export worker; these endpoints queue a job, report
+//! its status, and hand out download links once the zip is ready.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use crate::AppState;
+use crate::constants::SIGNED_URL_EXPIRY_SECS;
+use crate::domain::exports;
+use crate::export;
+use crate::services::error::LogErr;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/me/export", get(list_exports).post(request_export))
+        .route("/me/export/{id}", get(get_export))
+        .route("/me/export/{id}/download", get(download_export))
+}
+
+/// Export API response DTO
+#[derive(Debug, Serialize)]
+pub struct ExportResponse {
+    pub id: i64,
+    pub status: String,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Short-lived download link, present once the export is completed
+    pub download_url: Option<String>,
+}
+
+impl From<exports::DataExport> for ExportResponse {
+    fn from(e: exports::DataExport) -> Self {
+        Self {
+            id: e.id,
+            status: e.status,
+            size_bytes: e.size_bytes,
+            error: e.error,
+            requested_at: e.requested_at,
+            completed_at: e.completed_at,
+            expires_at: e.expires_at,
+            download_url: None,
+        }
+    }
+}
+
+/// POST /me/export - Queue a data export (returns the in-flight export if there is one)
+async fn request_export(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<(StatusCode, Json<ExportResponse>), StatusCode> {
+    let row = exports::request_export(&state.db, user_id)
+        .await
+        .log_500("Request export error")?;
+
+    println!(
+        "[export] User {} requested export {} ({})",
+        user_id, row.id, row.status
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(ExportResponse::from(row))))
+}
+
+#[derive(Serialize)]
+struct ListExportsResponse {
+    exports: Vec<ExportResponse>,
+}
+
+/// GET /me/export - Recent exports, newest first (poll for status)
+async fn list_exports(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<ListExportsResponse>, StatusCode> {
+    let rows = exports::list_exports(&state.db, user_id)
+        .await
+        .log_500("List exports error")?;
+
+    Ok(Json(ListExportsResponse {
+        exports: rows.into_iter().map(ExportResponse::from).collect(),
+    }))
+}
+
+/// GET /me/export/:id - Export status, with a download URL once completed
+async fn get_export(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(export_id): Path<i64>,
+) -> Result<Json<ExportResponse>, StatusCode> {
+    let row = exports::get_export(&state.db, export_id, user_id)
+        .await
+        .log_500("Get export error")?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let archive_path = row.archive_path.clone();
+    let completed = row.status == "completed";
+    let mut response = ExportResponse::from(row);
+
+    if let (true, Some(path)) = (completed, archive_path) {
+        let url = export::download_url(
+            state.local_storage_path.is_some(),
+            &state.buckets,
+            export_id,
+            &path,
+            SIGNED_URL_EXPIRY_SECS,
+        )
+        .await
+        .log_500("Export download URL error")?;
+        response.download_url = Some(url);
+    }
+
+    Ok(Json(response))
+}
+
+/// GET /me/export/:id/download - Serve a completed export zip (local storage only)
+async fn download_export(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(export_id): Path<i64>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let local_path = state
+        .local_storage_path
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let row = exports::get_export(&state.db, export_id, user_id)
+        .await
+        .log_500("Get export error")?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if row.status != "completed" {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let archive_path = row.archive_path.ok_or(StatusCode::NOT_FOUND)?;
+
+    let bytes = tokio::fs::read(local_path.join(&archive_path))
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?; // Silent - expected once the export expires
+
+    let disposition = format!("attachment; filename=\"cleo-export-{}.zip\"", export_id);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        bytes,
+    ))
+}
@@ -0,0 +1,324 @@
+//! Browser extension endpoints (/extension/*, /me/extension-tokens, /me/blocked-domains)
+//!
+//! Users mint scoped tokens for the companion extension from the web app; the
+//! extension sends them as Bearer tokens. See `services::tab_context` for the
+//! privacy rules applied to reported tabs.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use crate::AppState;
+use crate::domain::{activities, extension_tokens, projects, users};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::crypto::hash_token;
+use crate::services::error::{ApiError, LogErr};
+use crate::services::projects::infer_from_url;
+use crate::services::rate_limit::DAEMON_RATE_LIMITER;
+use crate::services::tab_context::{self, SCOPE_TAB_CONTEXT};
+
+/// Longest token name
+const MAX_TOKEN_NAME_LEN: usize = 64;
+/// Interval length used by the daemon for interval ids
+const INTERVAL_SECS: i64 = 5 * 60;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/extension/tab", post(report_tab))
+        .route("/me/extension-tokens", get(list_tokens).post(create_token))
+        .route("/me/extension-tokens/{id}", delete(revoke_token))
+        .route(
+            "/me/blocked-domains",
+            get(get_blocked_domains).put(update_blocked_domains),
+        )
+}
+
+/// Authenticate an extension request and require `scope`
+async fn extension_user(
+    state: &AppState,
+    headers: &HeaderMap,
+    scope: &str,
+) -> Result<i64, ApiError> {
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(ApiError::Unauthorized)?;
+
+    let grant = extension_tokens::use_token(&state.db, &hash_token(token))
+        .await
+        .log_500("Look up extension token error")?
+        .ok_or(ApiError::InvalidApiToken)?;
+
+    if grant.scope != scope {
+        return Err(ApiError::Forbidden("Token lacks the required scope"));
+    }
+    Ok(grant.user_id)
+}
+
+#[derive(Deserialize)]
+struct TabReport {
+    url: String,
+    #[serde(default)]
+    title: String,
+    /// When the tab became active; defaults to now
+    #[serde(default)]
+    timestamp: Option<DateTime<Utc>>,
+    /// Private/incognito windows are never recorded
+    #[serde(default)]
+    incognito: bool,
+}
+
+#[derive(Serialize)]
+struct TabReportResponse {
+    /// False when the privacy rules dropped the tab
+    stored: bool,
+}
+
+/// POST /extension/tab - Record the active browser tab
+async fn report_tab(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(report): Json<TabReport>,
+) -> Result<(StatusCode, Json<TabReportResponse>), ApiError> {
+    let user_id = extension_user(&state, &headers, SCOPE_TAB_CONTEXT).await?;
+
+    if !DAEMON_RATE_LIMITER.check(user_id) {
+        return Err(ApiError::RateLimited);
+    }
+    if report.url.len() > tab_context::MAX_URL_LEN {
+        return Err(ApiError::Unprocessable(format!(
+            "url must be at most {} bytes",
+            tab_context::MAX_URL_LEN
+        )));
+    }
+
+    let dropped = || Ok((StatusCode::OK, Json(TabReportResponse { stored: false })));
+    if report.incognito {
+        return dropped();
+    }
+    let blocked = users::get_blocked_domains(&state.db, user_id)
+        .await
+        .log_500("Get blocked domains error")?;
+    let Some(tab) = tab_context::clean_tab(&report.url, &blocked) else {
+        return dropped();
+    };
+
+    // Reports from a skewed clock can't land in the future
+    let now = Utc::now();
+    let timestamp = report.timestamp.map_or(now, |t| t.min(now));
+    let title: String = report
+        .title
+        .trim()
+        .chars()
+        .take(tab_context::MAX_TITLE_LEN)
+        .collect();
+
+    activities::insert_tab_activity(
+        &state.db,
+        user_id,
+        timestamp,
+        timestamp.timestamp().max(0) / INTERVAL_SECS,
+        &tab.host,
+        &title,
+        &tab.url,
+    )
+    .await
+    .log_500("Insert tab activity error")?;
+
+    // A repo page puts the project on the list; its time comes from the
+    // browser's window title
+    if let Some(hint) = infer_from_url(&tab.url)
+        && let Err(e) = projects::record_activity(
+            &state.db,
+            user_id,
+            &hint.name,
+            hint.branch.as_deref(),
+            timestamp,
+            0,
+        )
+        .await
+    {
+        eprintln!(
+            "[extension/tab] User {} - record project error: {}",
+            user_id, e
+        );
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(TabReportResponse { stored: true }),
+    ))
+}
+
+/// Extension token API response DTO
+#[derive(Serialize)]
+struct ExtensionTokenResponse {
+    id: i64,
+    scope: String,
+    name: Option<String>,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<extension_tokens::ExtensionToken> for ExtensionTokenResponse {
+    fn from(t: extension_tokens::ExtensionToken) -> Self {
+        Self {
+            id: t.id,
+            scope: t.scope,
+            name: t.name,
+            created_at: t.created_at,
+            last_used_at: t.last_used_at,
+            revoked_at: t.revoked_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct CreateTokenRequest {
+    /// Label shown in the token list, e.g. "Work laptop Chrome"
+    name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CreateTokenResponse {
+    /// Shown once; only its hash is stored
+    token: String,
+    #[serde(flatten)]
+    details: ExtensionTokenResponse,
+}
+
+/// POST /me/extension-tokens - Create a tab-context token for the browser extension
+async fn create_token(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    req: Option<Json<CreateTokenRequest>>,
+) -> Result<(StatusCode, Json<CreateTokenResponse>), ApiError> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let name = req
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(|n| n.chars().take(MAX_TOKEN_NAME_LEN).collect::<String>());
+
+    let token = tab_context::generate_token();
+    let stored = extension_tokens::insert_token(
+        &state.db,
+        user_id,
+        &hash_token(&token),
+        SCOPE_TAB_CONTEXT,
+        name.as_deref(),
+    )
+    .await
+    .log_500("Insert extension token error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::ExtensionTokenCreated,
+        Some(&stored.id.to_string()),
+        Some(serde_json::json!({ "scope": SCOPE_TAB_CONTEXT, "name": name })),
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateTokenResponse {
+            token,
+            details: stored.into(),
+        }),
+    ))
+}
+
+/// GET /me/extension-tokens - List the user's extension tokens (without secrets)
+async fn list_tokens(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<ExtensionTokenResponse>>, ApiError> {
+    let tokens = extension_tokens::list_tokens(&state.db, user_id)
+        .await
+        .log_500("List extension tokens error")?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+/// DELETE /me/extension-tokens/:id - Revoke an extension token
+async fn revoke_token(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let revoked = extension_tokens::revoke_token(&state.db, user_id, id)
+        .await
+        .log_500("Revoke extension token error")?;
+    if !revoked {
+        return Err(ApiError::NotFound("Extension token"));
+    }
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::ExtensionTokenRevoked,
+        Some(&id.to_string()),
+        None,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlockedDomains {
+    domains: Vec<String>,
+}
+
+/// GET /me/blocked-domains - Sites whose tabs are never recorded
+async fn get_blocked_domains(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<BlockedDomains>, ApiError> {
+    let domains = users::get_blocked_domains(&state.db, user_id)
+        .await
+        .log_500("Get blocked domains error")?;
+    Ok(Json(BlockedDomains { domains }))
+}
+
+/// PUT /me/blocked-domains - Replace the blocked domain list
+async fn update_blocked_domains(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<BlockedDomains>,
+) -> Result<Json<BlockedDomains>, ApiError> {
+    if req.domains.len() > tab_context::MAX_BLOCKED_DOMAINS {
+        return Err(ApiError::Unprocessable(format!(
+            "at most {} blocked domains",
+            tab_context::MAX_BLOCKED_DOMAINS
+        )));
+    }
+
+    let mut domains = Vec::with_capacity(req.domains.len());
+    for entry in &req.domains {
+        let domain = tab_context::normalize_domain(entry)
+            .ok_or_else(|| ApiError::Unprocessable(format!("Invalid domain: {}", entry)))?;
+        if !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+
+    users::set_blocked_domains(&state.db, user_id, &domains)
+        .await
+        .log_500("Set blocked domains error")?;
+    Ok(Json(BlockedDomains { domains }))
+}
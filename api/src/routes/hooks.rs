@@ -0,0 +1,185 @@
+//! Zapier/Make integration endpoints (/hooks/*), authenticated with the API token
+//!
+//! See services::rest_hooks for the payload and delivery rules.
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::captures::get_user_id_from_bearer;
+use crate::AppState;
+use crate::domain::hooks;
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::rest_hooks::{CollateralPayload, NEW_COLLATERAL_EVENT};
+use crate::services::validation::is_public_https_url;
+
+const DEFAULT_POLL_LIMIT: i64 = 50;
+const MAX_POLL_LIMIT: i64 = 100;
+const MAX_SUBSCRIPTIONS: i64 = 20;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/hooks/new-collateral", get(poll_new_collateral))
+        .route(
+            "/hooks/subscriptions",
+            get(list_subscriptions).post(subscribe),
+        )
+        .route("/hooks/subscriptions/{id}", delete(unsubscribe))
+}
+
+#[derive(Deserialize)]
+struct PollQuery {
+    /// Largest id already seen
+    since: Option<i64>,
+    limit: Option<i64>,
+}
+
+/// GET /hooks/new-collateral - Generated tweets, newest first (polling trigger)
+/// With `since`, only tweets after it, the oldest `limit` of them first, so
+/// advancing `since` to the largest id returned never skips one.
+async fn poll_new_collateral(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<PollQuery>,
+) -> Result<Json<Vec<CollateralPayload>>, ApiError> {
+    let user_id = get_user_id_from_bearer(&state.db, &headers).await?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_POLL_LIMIT)
+        .clamp(1, MAX_POLL_LIMIT);
+
+    let mut items = hooks::list_new_collateral(&state.db, user_id, query.since, limit)
+        .await
+        .log_500("Poll new collateral error")?;
+    items.sort_by_key(|item| std::cmp::Reverse(item.id));
+    Ok(Json(items.into_iter().map(Into::into).collect()))
+}
+
+/// Subscription API response DTO
+#[derive(Serialize)]
+struct SubscriptionResponse {
+    id: i64,
+    event: String,
+    target_url: String,
+    /// Failed deliveries in a row
+    failures: i32,
+    last_error: Option<String>,
+    disabled_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<hooks::HookSubscription> for SubscriptionResponse {
+    fn from(s: hooks::HookSubscription) -> Self {
+        Self {
+            id: s.id,
+            event: s.event,
+            target_url: s.target_url,
+            failures: s.failures,
+            last_error: s.last_error,
+            disabled_at: s.disabled_at,
+            created_at: s.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SubscribeRequest {
+    event: String,
+    /// HTTPS URL that receives one POST per event
+    target_url: String,
+}
+
+/// POST /hooks/subscriptions - Subscribe a URL to an event (REST hook)
+async fn subscribe(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ctx: RequestContext,
+    Json(req): Json<SubscribeRequest>,
+) -> Result<(StatusCode, Json<SubscriptionResponse>), ApiError> {
+    let user_id = get_user_id_from_bearer(&state.db, &headers).await?;
+    if req.event != NEW_COLLATERAL_EVENT {
+        return Err(ApiError::Unprocessable(format!(
+            "event must be {}",
+            NEW_COLLATERAL_EVENT
+        )));
+    }
+    let target_url = req.target_url.trim();
+    if !is_public_https_url(target_url) {
+        return Err(ApiError::Unprocessable(
+            "target_url must be an https URL on a public host".to_string(),
+        ));
+    }
+
+    let count = hooks::count_subscriptions(&state.db, user_id)
+        .await
+        .log_500("Count hook subscriptions error")?;
+    if count >= MAX_SUBSCRIPTIONS {
+        return Err(ApiError::Conflict(format!(
+            "At most {} hook subscriptions per account",
+            MAX_SUBSCRIPTIONS
+        )));
+    }
+
+    let subscription = hooks::insert_subscription(&state.db, user_id, &req.event, target_url)
+        .await
+        .log_500("Insert hook subscription error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::HookSubscribed,
+        Some(&subscription.id.to_string()),
+        Some(serde_json::json!({ "event": subscription.event, "target_url": subscription.target_url })),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(subscription.into())))
+}
+
+/// GET /hooks/subscriptions - The account's REST hook subscriptions
+async fn list_subscriptions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<SubscriptionResponse>>, ApiError> {
+    let user_id = get_user_id_from_bearer(&state.db, &headers).await?;
+    let subscriptions = hooks::list_subscriptions(&state.db, user_id)
+        .await
+        .log_500("List hook subscriptions error")?;
+    Ok(Json(subscriptions.into_iter().map(Into::into).collect()))
+}
+
+/// DELETE /hooks/subscriptions/:id - Unsubscribe
+async fn unsubscribe(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ctx: RequestContext,
+    Path(subscription_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = get_user_id_from_bearer(&state.db, &headers).await?;
+    let deleted = hooks::delete_subscription(&state.db, user_id, subscription_id)
+        .await
+        .log_500("Delete hook subscription error")?;
+    if !deleted {
+        return Err(ApiError::NotFound("Hook subscription"));
+    }
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::HookUnsubscribed,
+        Some(&subscription_id.to_string()),
+        None,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
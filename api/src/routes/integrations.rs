@@ -0,0 +1,154 @@
+//! Linked integrations (/me/integrations)
+//!
+//! An account is identified by its login (X, GitHub, Google or email); X is
+//! also the integration Cleo publishes through. Accounts that signed in another
+//! way link X here, and can unlink it again. X can't be removed from an account
+//! that has no other way to log in. Any account can add a verified email
+//! address as a fallback login.
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::auth::{self, AuthUser, EmailLinkRequest};
+use super::twitter_oauth::{self, AuthUrlResponse, LoginDevice};
+use crate::AppState;
+use crate::domain::{login_identities, users};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::twitter;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/me/integrations", get(list_integrations))
+        .route(
+            "/me/integrations/twitter",
+            post(connect_twitter).delete(disconnect_twitter),
+        )
+        .route("/me/integrations/email", post(add_email))
+}
+
+#[derive(Serialize)]
+struct LoginResponse {
+    provider: String,
+    username: String,
+    email: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct TwitterIntegration {
+    /// "connected", "not_connected" or "needs_reconnect" (as on GET /me)
+    status: &'static str,
+    username: Option<String>,
+}
+
+#[derive(Serialize)]
+struct IntegrationsResponse {
+    /// GitHub/Google/email logins; empty for accounts that only log in with X
+    logins: Vec<LoginResponse>,
+    twitter: TwitterIntegration,
+}
+
+/// GET /me/integrations - Login identities and the X connection
+async fn list_integrations(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<IntegrationsResponse>, ApiError> {
+    let user = twitter::get_user_by_id(&state.db, user_id)
+        .await
+        .log_500("Get user error")?
+        .ok_or(ApiError::NotFound("User"))?;
+    let basic = users::get_user_by_id(&state.db, user_id)
+        .await
+        .log_500("Get user error")?
+        .ok_or(ApiError::NotFound("User"))?;
+    let logins = login_identities::list_identities(&state.db, user_id)
+        .await
+        .log_500("List login identities error")?;
+
+    let status = if !user.twitter_connected {
+        "not_connected"
+    } else if user.needs_reconnect {
+        "needs_reconnect"
+    } else {
+        "connected"
+    };
+
+    Ok(Json(IntegrationsResponse {
+        logins: logins
+            .into_iter()
+            .map(|l| LoginResponse {
+                provider: l.provider,
+                username: l.username,
+                email: l.email,
+                created_at: l.created_at,
+            })
+            .collect(),
+        twitter: TwitterIntegration {
+            status,
+            username: basic.twitter_username,
+        },
+    }))
+}
+
+/// POST /me/integrations/twitter - Start linking X, returns URL to redirect to
+/// Finishes like a login (POST /auth/twitter/token or the callback), but stores
+/// the X tokens on this account. Also used to reconnect a linked account.
+async fn connect_twitter(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Json<AuthUrlResponse> {
+    Json(AuthUrlResponse {
+        url: twitter_oauth::start_login(&state, LoginDevice::Web, Some(user_id)).await,
+    })
+}
+
+/// DELETE /me/integrations/twitter - Unlink X and drop its tokens
+async fn disconnect_twitter(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+) -> Result<StatusCode, ApiError> {
+    let logins = login_identities::list_identities(&state.db, user_id)
+        .await
+        .log_500("List login identities error")?;
+    if logins.is_empty() {
+        return Err(ApiError::Conflict(
+            "X is this account's only login; it can't be disconnected".to_string(),
+        ));
+    }
+
+    twitter::unlink_user(&state.db, user_id)
+        .await
+        .log_500("Unlink X error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::IntegrationDisconnected,
+        None,
+        Some(serde_json::json!({ "provider": "twitter" })),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /me/integrations/email - Email a link that verifies the address and
+/// adds it as a login (replacing any previous address)
+async fn add_email(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<EmailLinkRequest>,
+) -> Result<StatusCode, ApiError> {
+    auth::send_email_link(&state, &req.email, Some(user_id)).await?;
+    Ok(StatusCode::ACCEPTED)
+}
@@ -0,0 +1,128 @@
+//! Launch mode (/me/launch-mode)
+//!
+//! A time-boxed window for launch day: the daemon screenshots more often, the
+//! scheduler runs the agent after a short idle gap, and the agent reacts to
+//! metrics dashboards and analytics first. See services::launch_mode.
+
+use axum::{Json, Router, extract::State, routing::get};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use crate::AppState;
+use crate::domain::users;
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::launch_mode::{
+    self, DEFAULT_DURATION_MINUTES, IDLE_MINUTES, MAX_DURATION_MINUTES, SCREENSHOT_INTERVAL_SECS,
+};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/me/launch-mode",
+        get(get_launch_mode)
+            .post(start_launch_mode)
+            .delete(stop_launch_mode),
+    )
+}
+
+/// Launch mode API response DTO
+#[derive(Serialize)]
+struct LaunchModeResponse {
+    active: bool,
+    /// When the window ends; None when launch mode is off
+    until: Option<DateTime<Utc>>,
+    /// Screenshot interval the daemon is asked to keep while active
+    screenshot_interval_secs: u64,
+    /// Idle gap before the agent runs while active
+    idle_minutes: i64,
+}
+
+impl From<Option<DateTime<Utc>>> for LaunchModeResponse {
+    fn from(until: Option<DateTime<Utc>>) -> Self {
+        Self {
+            active: until.is_some(),
+            until,
+            screenshot_interval_secs: SCREENSHOT_INTERVAL_SECS,
+            idle_minutes: IDLE_MINUTES,
+        }
+    }
+}
+
+fn default_duration_minutes() -> i64 {
+    DEFAULT_DURATION_MINUTES
+}
+
+#[derive(Deserialize)]
+struct StartLaunchModeRequest {
+    /// How long launch mode lasts, in minutes (default 12 hours, max 72)
+    #[serde(default = "default_duration_minutes")]
+    duration_minutes: i64,
+}
+
+/// GET /me/launch-mode - Whether launch mode is on and when it ends
+async fn get_launch_mode(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<LaunchModeResponse>, ApiError> {
+    let until = users::get_launch_mode_until(&state.db, user_id)
+        .await
+        .log_500("Get launch mode error")?;
+    Ok(Json(until.into()))
+}
+
+/// POST /me/launch-mode - Start launch mode for `duration_minutes` from now,
+/// replacing any window already running
+async fn start_launch_mode(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    Json(req): Json<StartLaunchModeRequest>,
+) -> Result<Json<LaunchModeResponse>, ApiError> {
+    let until = launch_mode::window_end(Utc::now(), req.duration_minutes).ok_or_else(|| {
+        ApiError::Unprocessable(format!(
+            "duration_minutes must be between 1 and {}",
+            MAX_DURATION_MINUTES
+        ))
+    })?;
+
+    users::set_launch_mode_until(&state.db, user_id, Some(until))
+        .await
+        .log_500("Start launch mode error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::LaunchModeUpdated,
+        None,
+        Some(serde_json::json!({ "until": until })),
+    )
+    .await;
+
+    Ok(Json(Some(until).into()))
+}
+
+/// DELETE /me/launch-mode - End launch mode early
+async fn stop_launch_mode(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+) -> Result<Json<LaunchModeResponse>, ApiError> {
+    users::set_launch_mode_until(&state.db, user_id, None)
+        .await
+        .log_500("Stop launch mode error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::LaunchModeUpdated,
+        None,
+        Some(serde_json::json!({ "until": null })),
+    )
+    .await;
+
+    Ok(Json(None.into()))
+}
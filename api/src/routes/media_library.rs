@@ -0,0 +1,344 @@
+//! Media library endpoints (/media-library/*)
+//!
+//! Reusable images the user uploads from the web app (logos, banners, pre-made
+//! screenshots), organized into folders. They're attached to tweets through
+//! `library_asset_ids` on PUT /tweets/:id/collateral, next to capture images.
+
+use axum::{
+    Json, Router,
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use super::captures::{SignedUrlResponse, signed_url};
+use crate::domain::media_library::{self, MediaAsset, MediaFolder};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::validation::with_body_limit;
+use crate::{AppState, config, get_extension, storage};
+
+/// Longest asset or folder name
+const MAX_NAME_LEN: usize = 128;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    let limits = &config::get().limits;
+    Router::new()
+        .route(
+            "/media-library",
+            with_body_limit(post(upload_asset), limits.media_library_upload_bytes).get(list_assets),
+        )
+        .route(
+            "/media-library/{id}",
+            delete(delete_asset).patch(update_asset),
+        )
+        .route("/media-library/{id}/url", get(get_asset_url))
+        .route(
+            "/media-library/folders",
+            get(list_folders).post(create_folder),
+        )
+        .route("/media-library/folders/{id}", delete(delete_folder))
+}
+
+/// Trim a name and cap its length; None if blank
+fn clean_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.chars().take(MAX_NAME_LEN).collect())
+}
+
+/// Media library asset API response DTO
+#[derive(Serialize)]
+struct MediaAssetResponse {
+    id: i64,
+    folder_id: Option<i64>,
+    name: String,
+    content_type: String,
+    file_size_bytes: i64,
+    created_at: DateTime<Utc>,
+}
+
+impl From<MediaAsset> for MediaAssetResponse {
+    fn from(a: MediaAsset) -> Self {
+        Self {
+            id: a.id,
+            folder_id: a.folder_id,
+            name: a.name,
+            content_type: a.content_type,
+            file_size_bytes: a.file_size_bytes,
+            created_at: a.created_at,
+        }
+    }
+}
+
+/// Media folder API response DTO
+#[derive(Serialize)]
+struct MediaFolderResponse {
+    id: i64,
+    name: String,
+    created_at: DateTime<Utc>,
+    asset_count: i64,
+}
+
+impl From<MediaFolder> for MediaFolderResponse {
+    fn from(f: MediaFolder) -> Self {
+        Self {
+            id: f.id,
+            name: f.name,
+            created_at: f.created_at,
+            asset_count: f.asset_count,
+        }
+    }
+}
+
+async fn ensure_folder_owned(
+    state: &AppState,
+    folder_id: i64,
+    user_id: i64,
+) -> Result<(), ApiError> {
+    let owned = media_library::folder_owned(&state.db, folder_id, user_id)
+        .await
+        .log_500("Verify folder ownership error")?;
+    if owned {
+        Ok(())
+    } else {
+        Err(ApiError::NotFound("Folder"))
+    }
+}
+
+/// POST /media-library - Upload an image (multipart: `file`, optional `name` and `folder_id`)
+async fn upload_asset(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<MediaAssetResponse>), ApiError> {
+    let mut file: Option<(Vec<u8>, String, Option<String>)> = None;
+    let mut name = None;
+    let mut folder_id = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Malformed multipart body: {}", e)))?
+    {
+        let field_name = field.name().unwrap_or_default().to_string();
+        let malformed = |e| ApiError::BadRequest(format!("Malformed {} field: {}", field_name, e));
+        match field_name.as_str() {
+            "file" => {
+                let content_type = field.content_type().unwrap_or_default().to_string();
+                let file_name = field.file_name().map(str::to_string);
+                let data = field.bytes().await.map_err(malformed)?;
+                file = Some((data.to_vec(), content_type, file_name));
+            }
+            "name" => name = clean_name(&field.text().await.map_err(malformed)?),
+            "folder_id" => {
+                let text = field.text().await.map_err(malformed)?;
+                folder_id =
+                    Some(text.trim().parse::<i64>().map_err(|_| {
+                        ApiError::BadRequest("folder_id must be a number".to_string())
+                    })?);
+            }
+            _ => {}
+        }
+    }
+
+    let (data, content_type, file_name) =
+        file.ok_or_else(|| ApiError::BadRequest("Missing file field".to_string()))?;
+    let ext = get_extension(&content_type);
+    if ext == "bin" || !content_type.starts_with("image/") {
+        return Err(ApiError::Unprocessable(
+            "Media library files must be PNG, JPEG, WebP or GIF images".to_string(),
+        ));
+    }
+    if data.is_empty() {
+        return Err(ApiError::Unprocessable("File is empty".to_string()));
+    }
+    if let Some(folder_id) = folder_id {
+        ensure_folder_owned(&state, folder_id, user_id).await?;
+    }
+    let name = name
+        .or_else(|| file_name.as_deref().and_then(clean_name))
+        .unwrap_or_else(|| format!("Upload.{}", ext));
+
+    let sha256 = storage::sha256_hex(&data);
+    let path = format!(
+        "{}user_{}/{}_{}.{}",
+        media_library::PATH_PREFIX,
+        user_id,
+        Utc::now().timestamp_millis(),
+        &sha256[..12],
+        ext
+    );
+    state
+        .store
+        .put(&path, &data)
+        .await
+        .log_500("Store media library asset error")?;
+
+    let asset = match media_library::insert_asset(
+        &state.db,
+        user_id,
+        folder_id,
+        &name,
+        &content_type,
+        &path,
+        data.len() as i64,
+    )
+    .await
+    {
+        Ok(asset) => asset,
+        Err(e) => {
+            eprintln!("Insert media library asset error: {}", e);
+            // Clean up orphaned object on DB failure
+            let _ = state.store.delete(&path).await;
+            return Err(ApiError::Internal);
+        }
+    };
+
+    Ok((StatusCode::CREATED, Json(asset.into())))
+}
+
+#[derive(Deserialize)]
+struct ListAssetsQuery {
+    folder_id: Option<i64>,
+}
+
+/// GET /media-library - List assets, newest first (optionally one folder's)
+async fn list_assets(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Query(query): Query<ListAssetsQuery>,
+) -> Result<Json<Vec<MediaAssetResponse>>, ApiError> {
+    let assets = media_library::list_assets(&state.db, user_id, query.folder_id)
+        .await
+        .log_500("List media library assets error")?;
+    Ok(Json(assets.into_iter().map(Into::into).collect()))
+}
+
+/// Distinguish an explicit `null` (Some(None)) from an absent field (None)
+fn explicit_null<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+struct UpdateAssetRequest {
+    name: Option<String>,
+    /// Folder to move the asset into; null moves it to the top level
+    #[serde(default, deserialize_with = "explicit_null")]
+    folder_id: Option<Option<i64>>,
+}
+
+/// PATCH /media-library/:id - Rename an asset or move it between folders
+async fn update_asset(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(asset_id): Path<i64>,
+    Json(req): Json<UpdateAssetRequest>,
+) -> Result<Json<MediaAssetResponse>, ApiError> {
+    let name = match req.name.as_deref() {
+        Some(name) => Some(
+            clean_name(name)
+                .ok_or_else(|| ApiError::Unprocessable("name cannot be empty".to_string()))?,
+        ),
+        None => None,
+    };
+    if let Some(Some(folder_id)) = req.folder_id {
+        ensure_folder_owned(&state, folder_id, user_id).await?;
+    }
+
+    let asset =
+        media_library::update_asset(&state.db, asset_id, user_id, name.as_deref(), req.folder_id)
+            .await
+            .log_500("Update media library asset error")?
+            .ok_or(ApiError::NotFound("Media library asset"))?;
+    Ok(Json(asset.into()))
+}
+
+/// DELETE /media-library/:id - Delete an asset (and remove it from unposted tweets)
+async fn delete_asset(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(asset_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let path = media_library::delete_asset(&state.db, asset_id, user_id)
+        .await
+        .log_500("Delete media library asset error")?
+        .ok_or(ApiError::NotFound("Media library asset"))?;
+
+    if let Err(e) = state.store.delete(&path).await {
+        eprintln!("[media_library] Failed to delete object {}: {}", path, e);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /media-library/:id/url - Get a signed URL for an asset
+async fn get_asset_url(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(asset_id): Path<i64>,
+) -> Result<Json<SignedUrlResponse>, ApiError> {
+    let asset = media_library::get_asset(&state.db, asset_id, user_id)
+        .await
+        .log_500("Get media library asset error")?
+        .ok_or(ApiError::NotFound("Media library asset"))?;
+
+    Ok(Json(SignedUrlResponse {
+        url: signed_url(&state, user_id, &asset.gcs_path).await?,
+        content_type: asset.content_type,
+    }))
+}
+
+/// GET /media-library/folders - List folders with their asset counts
+async fn list_folders(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<MediaFolderResponse>>, ApiError> {
+    let folders = media_library::list_folders(&state.db, user_id)
+        .await
+        .log_500("List media folders error")?;
+    Ok(Json(folders.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Deserialize)]
+struct CreateFolderRequest {
+    name: String,
+}
+
+/// POST /media-library/folders - Create a folder
+async fn create_folder(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<CreateFolderRequest>,
+) -> Result<(StatusCode, Json<MediaFolderResponse>), ApiError> {
+    let name = clean_name(&req.name)
+        .ok_or_else(|| ApiError::Unprocessable("name cannot be empty".to_string()))?;
+
+    let folder = media_library::insert_folder(&state.db, user_id, &name)
+        .await
+        .log_500("Create media folder error")?
+        .ok_or_else(|| ApiError::Conflict(format!("A folder named {} already exists", name)))?;
+
+    Ok((StatusCode::CREATED, Json(folder.into())))
+}
+
+/// DELETE /media-library/folders/:id - Delete a folder; its assets move to the top level
+async fn delete_folder(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(folder_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let deleted = media_library::delete_folder(&state.db, folder_id, user_id)
+        .await
+        .log_500("Delete media folder error")?;
+    if !deleted {
+        return Err(ApiError::NotFound("Folder"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -17,7 +17,10 @@ use std::sync::Arc;
 
 use crate::AppState;
 use crate::routes::auth::AuthUser;
-use crate::services::media_studio::{CropParams, MediaStudio, MediaStudioError, TrimParams};
+use crate::services::media_studio::{
+    CaptionParams, CaptionSegment, CaptionStyle, CropParams, MediaStudio, MediaStudioError,
+    TimelapseParams, TrimParams,
+};
 use crate::services::session;
 
 pub fn routes() -> Router<Arc<AppState>> {
@@ -25,6 +28,8 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/media/edit/ws", get(edit_ws))
         .route("/media/crop", post(crop_image))
         .route("/media/trim", post(trim_video))
+        .route("/media/timelapse", post(timelapse_video))
+        .route("/media/captions", post(caption_video))
 }
 
 /// WebSocket command from client
@@ -45,6 +50,15 @@ enum EditCommand {
         start: String,
         duration: f64,
     },
+    #[serde(rename = "timelapse")]
+    Timelapse { capture_id: i64, duration: f64 },
+    #[serde(rename = "captions")]
+    Captions {
+        capture_id: i64,
+        segments: Vec<CaptionSegment>,
+        #[serde(default)]
+        style: CaptionStyle,
+    },
 }
 
 /// WebSocket response to client
@@ -85,6 +99,7 @@ async fn handle_edit_ws(socket: WebSocket, state: Arc<AppState>, user_id: i64) {
         state.db.clone(),
         state.gcs.clone(),
         state.local_storage_path.clone(),
+        state.buckets.clone(),
     );
 
     // Process commands from client
@@ -183,6 +198,51 @@ async fn handle_edit_ws(socket: WebSocket, state: Arc<AppState>, user_id: i64) {
                     )
                     .await
             }
+            EditCommand::Timelapse {
+                capture_id,
+                duration,
+            } => {
+                // Send progress (processing)
+                let progress = EditResponse::Progress {
+                    percent: 30,
+                    status: "Rendering timelapse...".into(),
+                };
+                let _ = sender
+                    .send(Message::Text(
+                        serde_json::to_string(&progress).unwrap().into(),
+                    ))
+                    .await;
+
+                media_studio
+                    .timelapse_video(
+                        user_id,
+                        capture_id,
+                        TimelapseParams {
+                            duration_secs: duration,
+                        },
+                    )
+                    .await
+            }
+            EditCommand::Captions {
+                capture_id,
+                segments,
+                style,
+            } => {
+                // Send progress (processing)
+                let progress = EditResponse::Progress {
+                    percent: 30,
+                    status: "Burning in captions...".into(),
+                };
+                let _ = sender
+                    .send(Message::Text(
+                        serde_json::to_string(&progress).unwrap().into(),
+                    ))
+                    .await;
+
+                media_studio
+                    .caption_video(user_id, capture_id, CaptionParams { segments, style })
+                    .await
+            }
         };
 
         // Send result
@@ -220,6 +280,21 @@ struct TrimRequest {
     duration: f64,
 }
 
+#[derive(Debug, Deserialize)]
+struct TimelapseRequest {
+    capture_id: i64,
+    /// Length of the timelapse in seconds
+    duration: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct CaptionRequest {
+    capture_id: i64,
+    segments: Vec<CaptionSegment>,
+    #[serde(default)]
+    style: CaptionStyle,
+}
+
 #[derive(Debug, Serialize)]
 struct EditResult {
     new_capture_id: i64,
@@ -235,6 +310,7 @@ async fn crop_image(
         state.db.clone(),
         state.gcs.clone(),
         state.local_storage_path.clone(),
+        state.buckets.clone(),
     );
 
     let new_capture_id = media_studio
@@ -272,6 +348,7 @@ async fn trim_video(
         state.db.clone(),
         state.gcs.clone(),
         state.local_storage_path.clone(),
+        state.buckets.clone(),
     );
 
     let new_capture_id = media_studio
@@ -296,3 +373,74 @@ async fn trim_video(
 
     Ok(Json(EditResult { new_capture_id }))
 }
+
+/// POST /media/timelapse - Speed a long recording up into a timelapse (REST endpoint for agent use)
+async fn timelapse_video(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<TimelapseRequest>,
+) -> Result<Json<EditResult>, StatusCode> {
+    let media_studio = MediaStudio::new(
+        state.db.clone(),
+        state.gcs.clone(),
+        state.local_storage_path.clone(),
+        state.buckets.clone(),
+    );
+
+    let new_capture_id = media_studio
+        .timelapse_video(
+            user_id,
+            req.capture_id,
+            TimelapseParams {
+                duration_secs: req.duration,
+            },
+        )
+        .await
+        .map_err(|e| {
+            eprintln!("[media_studio] Timelapse error: {}", e);
+            match e {
+                MediaStudioError::NotFound => StatusCode::NOT_FOUND,
+                MediaStudioError::InvalidParams(_) => StatusCode::BAD_REQUEST,
+                MediaStudioError::InvalidMediaType(_) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }
+        })?;
+
+    Ok(Json(EditResult { new_capture_id }))
+}
+
+/// POST /media/captions - Burn caption segments into a video (REST endpoint for agent use)
+async fn caption_video(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<CaptionRequest>,
+) -> Result<Json<EditResult>, StatusCode> {
+    let media_studio = MediaStudio::new(
+        state.db.clone(),
+        state.gcs.clone(),
+        state.local_storage_path.clone(),
+        state.buckets.clone(),
+    );
+
+    let new_capture_id = media_studio
+        .caption_video(
+            user_id,
+            req.capture_id,
+            CaptionParams {
+                segments: req.segments,
+                style: req.style,
+            },
+        )
+        .await
+        .map_err(|e| {
+            eprintln!("[media_studio] Captions error: {}", e);
+            match e {
+                MediaStudioError::NotFound => StatusCode::NOT_FOUND,
+                MediaStudioError::InvalidParams(_) => StatusCode::BAD_REQUEST,
+                MediaStudioError::InvalidMediaType(_) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }
+        })?;
+
+    Ok(Json(EditResult { new_capture_id }))
+}
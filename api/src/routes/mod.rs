@@ -1,12 +1,34 @@
 pub mod agent;
+pub mod archives;
+pub mod audit_log;
 pub mod auth;
+pub mod capture_shares;
 pub mod captures;
 pub mod content;
+pub mod devices;
+pub mod exports;
+pub mod extension;
+pub mod hooks;
+pub mod integrations;
+pub mod launch_mode;
+pub mod media_library;
 pub mod media_studio;
+pub mod now_widget;
 pub mod nudges;
+pub mod projects;
+pub mod public_log;
 pub mod push;
+pub mod sessions;
+pub mod storage_audit;
+pub mod summary_export;
+pub mod twitter_credentials;
 pub mod twitter_oauth;
 pub mod user;
+pub mod weekly_recap;
+pub mod workers;
+
+#[cfg(test)]
+mod tests;
 
 use axum::Router;
 use std::sync::Arc;
@@ -17,12 +39,31 @@ use crate::AppState;
 pub fn build_routes() -> Router<Arc<AppState>> {
     Router::new()
         .merge(agent::routes())
+        .merge(archives::routes())
+        .merge(audit_log::routes())
         .merge(auth::routes())
+        .merge(capture_shares::routes())
         .merge(captures::routes())
         .merge(content::routes())
+        .merge(devices::routes())
+        .merge(exports::routes())
+        .merge(extension::routes())
+        .merge(hooks::routes())
+        .merge(integrations::routes())
+        .merge(launch_mode::routes())
+        .merge(media_library::routes())
         .merge(media_studio::routes())
         .merge(push::routes())
         .merge(nudges::routes())
+        .merge(now_widget::routes())
+        .merge(projects::routes())
+        .merge(public_log::routes())
+        .merge(sessions::routes())
+        .merge(storage_audit::routes())
+        .merge(summary_export::routes())
+        .merge(twitter_credentials::routes())
         .merge(twitter_oauth::routes())
         .merge(user::routes())
+        .merge(weekly_recap::routes())
+        .merge(workers::routes())
 }
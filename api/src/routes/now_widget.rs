@@ -0,0 +1,200 @@
+//! "Now building" widget endpoints (/public/:username/now.json, /me/now-widget)
+//!
+//! Users opt in with PUT /me/now-widget. The JSON carries only what is already
+//! public on X: the latest posted tweets and how many days in a row the user
+//! has posted. Drafts, captures and activity never appear. Any site may fetch
+//! it, so it is served with `Access-Control-Allow-Origin: *`.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use crate::AppState;
+use crate::domain::public_log;
+use crate::services::error::{ApiError, LogErr};
+use crate::services::timezone;
+
+/// Posted tweets in the widget
+const RECENT_POSTS: i64 = 3;
+/// Longest tweet text in the widget, in characters
+const MAX_TEXT_CHARS: usize = 280;
+/// How far back a streak is counted
+const MAX_STREAK_DAYS: i64 = 365;
+const NOW_WIDGET_CACHE_SECS: u32 = 300;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/public/{username}/now.json", get(get_now_widget))
+        .route(
+            "/me/now-widget",
+            get(get_now_widget_settings).put(update_now_widget_settings),
+        )
+}
+
+#[derive(Serialize, Deserialize)]
+struct NowWidgetSettings {
+    enabled: bool,
+}
+
+/// GET /me/now-widget - Whether the user's widget is public
+async fn get_now_widget_settings(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<NowWidgetSettings>, ApiError> {
+    let enabled = public_log::get_widget_enabled(&state.db, user_id)
+        .await
+        .log_500("Get now widget setting error")?
+        .ok_or(ApiError::Unauthorized)?;
+    Ok(Json(NowWidgetSettings { enabled }))
+}
+
+/// PUT /me/now-widget - Make the user's widget public or private
+async fn update_now_widget_settings(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<NowWidgetSettings>,
+) -> Result<Json<NowWidgetSettings>, ApiError> {
+    public_log::set_widget_enabled(&state.db, user_id, req.enabled)
+        .await
+        .log_500("Set now widget setting error")?;
+    Ok(Json(req))
+}
+
+#[derive(Serialize)]
+struct NowPost {
+    text: String,
+    url: Option<String>,
+    posted_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct NowWidgetResponse {
+    username: String,
+    name: Option<String>,
+    /// Newest first
+    recent_posts: Vec<NowPost>,
+    /// Days in a row (in the user's time zone) with a posted tweet, ending today
+    /// or, if nothing is posted yet today, yesterday
+    streak_days: u32,
+    last_posted_at: Option<DateTime<Utc>>,
+}
+
+/// Cut text to `max` characters, ending in an ellipsis when shortened
+fn truncate_text(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max - 1).collect();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+/// Consecutive days with a post ending today or yesterday.
+/// `days` are local posting dates, newest first (duplicates allowed).
+fn streak_days(days: &[NaiveDate], today: NaiveDate) -> u32 {
+    let mut days = days.iter().copied().peekable();
+    let mut expected = match days.peek() {
+        Some(&d) if d == today => today,
+        Some(&d) if Some(d) == today.pred_opt() => d,
+        _ => return 0,
+    };
+    let mut streak = 0;
+    for day in days {
+        if day == expected {
+            streak += 1;
+            match expected.pred_opt() {
+                Some(prev) => expected = prev,
+                None => break,
+            }
+        } else if day < expected {
+            break;
+        }
+    }
+    streak
+}
+
+/// GET /public/:username/now.json - Embeddable "now building" status (no auth)
+async fn get_now_widget(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+) -> Result<Response, ApiError> {
+    let owner = public_log::get_widget_owner(&state.db, &username)
+        .await
+        .log_500("Get widget owner error")?
+        .ok_or(ApiError::NotFound("Widget"))?;
+    let tz = timezone::parse(&owner.timezone).unwrap_or(Tz::UTC);
+    let now = Utc::now();
+
+    let tweets = public_log::list_posted_tweets(&state.db, owner.id, now, RECENT_POSTS)
+        .await
+        .log_500("List posted tweets error")?;
+    let posted_times =
+        public_log::list_posted_times(&state.db, owner.id, now - Duration::days(MAX_STREAK_DAYS))
+            .await
+            .log_500("List posted times error")?;
+    let posted_days: Vec<NaiveDate> = posted_times
+        .iter()
+        .map(|&at| timezone::local_date(at, tz))
+        .collect();
+
+    let body = NowWidgetResponse {
+        recent_posts: tweets
+            .into_iter()
+            .map(|t| NowPost {
+                text: truncate_text(&t.text, MAX_TEXT_CHARS),
+                url: t
+                    .tweet_id
+                    .map(|id| format!("https://x.com/{}/status/{}", owner.twitter_username, id)),
+                posted_at: t.posted_at,
+            })
+            .collect(),
+        streak_days: streak_days(&posted_days, timezone::local_date(now, tz)),
+        last_posted_at: posted_times.first().copied(),
+        username: owner.twitter_username,
+        name: owner.twitter_name,
+    };
+
+    Ok((
+        [
+            (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*".to_string()),
+            (
+                header::CACHE_CONTROL,
+                format!("public, max-age={}", NOW_WIDGET_CACHE_SECS),
+            ),
+        ],
+        Json(body),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streak_days() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let today = day(16);
+        assert_eq!(streak_days(&[], today), 0);
+        assert_eq!(streak_days(&[day(16), day(16), day(15), day(14)], today), 3);
+        // Nothing posted yet today: the streak through yesterday still counts
+        assert_eq!(streak_days(&[day(15), day(14), day(12)], today), 2);
+        assert_eq!(streak_days(&[day(14), day(13)], today), 0);
+    }
+
+    #[test]
+    fn test_truncate_text() {
+        assert_eq!(truncate_text("short", 10), "short");
+        assert_eq!(truncate_text("one two three", 8), "one two…");
+    }
+}
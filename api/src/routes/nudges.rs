@@ -12,6 +12,7 @@ use std::sync::Arc;
 
 use super::auth::AuthUser;
 use crate::AppState;
+use crate::services::audit::{self, Action, RequestContext};
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
@@ -171,6 +172,7 @@ async fn delete_user_persona(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
     Path(persona_id): Path<i64>,
+    ctx: RequestContext,
 ) -> Result<StatusCode, StatusCode> {
     let result = sqlx::query("DELETE FROM user_personas WHERE id = $1 AND user_id = $2")
         .bind(persona_id)
@@ -186,6 +188,16 @@ async fn delete_user_persona(
         return Err(StatusCode::NOT_FOUND);
     }
 
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::PersonaDeleted,
+        Some(&persona_id.to_string()),
+        None,
+    )
+    .await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -219,6 +231,7 @@ async fn get_nudges(
 async fn update_nudges(
     State(state): State<Arc<AppState>>,
     AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
     Json(req): Json<UpdateNudgesRequest>,
 ) -> Result<Json<NudgesResponse>, StatusCode> {
     // Sanitize nudges
@@ -235,6 +248,16 @@ async fn update_nudges(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::NudgesUpdated,
+        None,
+        Some(serde_json::json!({ "selected_persona_id": req.selected_persona_id })),
+    )
+    .await;
+
     Ok(Json(NudgesResponse {
         nudges: Some(sanitized),
         selected_persona_id: req.selected_persona_id,
@@ -0,0 +1,156 @@
+//! Projects (/me/projects)
+//!
+//! Projects are inferred from window titles as activity comes in (see
+//! services::projects); this lists them with the foreground time credited and
+//! sets each one's publishing defaults.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{get, put},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use crate::AppState;
+use crate::domain::projects::{self, ProjectSettings};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::projects::{MAX_HASHTAGS, normalize_hashtag, normalize_username};
+
+/// Most projects returned
+const MAX_PROJECTS: i64 = 100;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/me/projects", get(list_projects))
+        .route("/me/projects/{name}", put(update_project))
+}
+
+/// Project API response DTO
+#[derive(Serialize)]
+struct ProjectResponse {
+    name: String,
+    /// Branch from the latest title that showed one
+    branch: Option<String>,
+    active_secs: i64,
+    first_seen_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+    /// Appended to agent drafts tagged with the project
+    hashtags: Vec<String>,
+    /// X account the project's content must be published from
+    twitter_username: Option<String>,
+    auto_generate: bool,
+}
+
+impl From<projects::Project> for ProjectResponse {
+    fn from(p: projects::Project) -> Self {
+        Self {
+            name: p.name,
+            branch: p.last_branch,
+            active_secs: p.active_secs,
+            first_seen_at: p.first_seen_at,
+            last_seen_at: p.last_seen_at,
+            hashtags: p.hashtags,
+            twitter_username: p.twitter_username,
+            auto_generate: p.auto_generate,
+        }
+    }
+}
+
+fn default_auto_generate() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct UpdateProjectRequest {
+    /// With or without the '#'
+    #[serde(default)]
+    hashtags: Vec<String>,
+    /// With or without the '@'; null unlinks
+    twitter_username: Option<String>,
+    #[serde(default = "default_auto_generate")]
+    auto_generate: bool,
+}
+
+impl UpdateProjectRequest {
+    fn into_settings(self) -> Result<ProjectSettings, ApiError> {
+        if self.hashtags.len() > MAX_HASHTAGS {
+            return Err(ApiError::Unprocessable(format!(
+                "at most {} hashtags",
+                MAX_HASHTAGS
+            )));
+        }
+        let mut hashtags = Vec::new();
+        for raw in &self.hashtags {
+            let tag = normalize_hashtag(raw)
+                .ok_or_else(|| ApiError::Unprocessable(format!("invalid hashtag: {}", raw)))?;
+            if !hashtags
+                .iter()
+                .any(|t: &String| t.eq_ignore_ascii_case(&tag))
+            {
+                hashtags.push(tag);
+            }
+        }
+        let twitter_username = self
+            .twitter_username
+            .as_deref()
+            .map(|raw| {
+                normalize_username(raw)
+                    .ok_or_else(|| ApiError::Unprocessable(format!("invalid X username: {}", raw)))
+            })
+            .transpose()?;
+        Ok(ProjectSettings {
+            hashtags,
+            twitter_username,
+            auto_generate: self.auto_generate,
+        })
+    }
+}
+
+/// GET /me/projects - Projects inferred from window titles, most recently
+/// seen first
+async fn list_projects(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<ProjectResponse>>, ApiError> {
+    let projects = projects::list_projects(&state.db, user_id, MAX_PROJECTS)
+        .await
+        .log_500("List projects error")?;
+    Ok(Json(projects.into_iter().map(Into::into).collect()))
+}
+
+/// PUT /me/projects/:name - Set a project's default hashtags, linked X account
+/// and whether the agent drafts from it
+async fn update_project(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Path(name): Path<String>,
+    ctx: RequestContext,
+    Json(req): Json<UpdateProjectRequest>,
+) -> Result<Json<ProjectResponse>, ApiError> {
+    let settings = req.into_settings()?;
+    let project = projects::update_settings(&state.db, user_id, &name, &settings)
+        .await
+        .log_500("Update project error")?
+        .ok_or(ApiError::NotFound("Project"))?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::ProjectSettingsUpdated,
+        None,
+        Some(serde_json::json!({
+            "project": project.name,
+            "hashtags": project.hashtags,
+            "twitter_username": project.twitter_username,
+            "auto_generate": project.auto_generate,
+        })),
+    )
+    .await;
+
+    Ok(Json(project.into()))
+}
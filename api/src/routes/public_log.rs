@@ -0,0 +1,378 @@
+//! Public build log endpoints (/public/:username/log, /me/public-log)
+//!
+//! Users opt in with PUT /me/public-log. The log is a chronological feed of
+//! their posted tweets plus the captures they added with
+//! PUT /captures/:id/public-log, served without auth as JSON or, with
+//! `?format=html`, as a standalone page. Media links are signed and expire, so
+//! the page is only cached briefly.
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::header,
+    response::{Html, IntoResponse, Response},
+    routing::get,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use super::captures::signed_url;
+use crate::AppState;
+use crate::constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::domain::public_log::{self, LogCapture, LogOwner, LogTweet};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::html::escape_html;
+
+/// How long browsers and proxies may cache a log page (signed media URLs last 15 minutes)
+const PUBLIC_LOG_CACHE_SECS: u32 = 60;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/public/{username}/log", get(get_public_log))
+        .route(
+            "/me/public-log",
+            get(get_public_log_settings).put(update_public_log_settings),
+        )
+}
+
+#[derive(Serialize, Deserialize)]
+struct PublicLogSettings {
+    enabled: bool,
+}
+
+/// GET /me/public-log - Whether the user's build log is public
+async fn get_public_log_settings(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<PublicLogSettings>, ApiError> {
+    let enabled = public_log::get_enabled(&state.db, user_id)
+        .await
+        .log_500("Get public log setting error")?
+        .ok_or(ApiError::Unauthorized)?;
+    Ok(Json(PublicLogSettings { enabled }))
+}
+
+/// PUT /me/public-log - Make the user's build log public or private
+async fn update_public_log_settings(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<PublicLogSettings>,
+) -> Result<Json<PublicLogSettings>, ApiError> {
+    public_log::set_enabled(&state.db, user_id, req.enabled)
+        .await
+        .log_500("Set public log setting error")?;
+    Ok(Json(req))
+}
+
+#[derive(Deserialize)]
+struct PublicLogQuery {
+    /// "json" (default) or "html"
+    format: Option<String>,
+    /// Only entries older than this (the previous page's `next_before`)
+    before: Option<DateTime<Utc>>,
+    limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct LogMedia {
+    capture_id: i64,
+    media_type: String,
+    content_type: String,
+    url: String,
+}
+
+/// One item in the feed, newest first
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum LogEntry {
+    Tweet {
+        id: i64,
+        text: String,
+        posted_at: DateTime<Utc>,
+        /// Link to the tweet on X
+        url: Option<String>,
+        thread_id: Option<i64>,
+        media: Vec<LogMedia>,
+    },
+    Capture {
+        at: DateTime<Utc>,
+        media: LogMedia,
+    },
+}
+
+impl LogEntry {
+    fn at(&self) -> DateTime<Utc> {
+        match self {
+            LogEntry::Tweet { posted_at, .. } => *posted_at,
+            LogEntry::Capture { at, .. } => *at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PublicLogResponse {
+    username: String,
+    display_name: Option<String>,
+    entries: Vec<LogEntry>,
+    /// Pass as `before` for the next page; None on the last page
+    next_before: Option<DateTime<Utc>>,
+}
+
+/// GET /public/:username/log - A user's public build log (no auth)
+/// 404 unless the user has made their log public.
+async fn get_public_log(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+    Query(query): Query<PublicLogQuery>,
+) -> Result<Response, ApiError> {
+    let html = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("html") => true,
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!("Unknown format: {}", other)));
+        }
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let before = query.before.unwrap_or_else(Utc::now);
+
+    let owner = public_log::get_public_owner(&state.db, &username)
+        .await
+        .log_500("Get public log owner error")?
+        .ok_or(ApiError::NotFound("Build log"))?;
+
+    let log = build_log(&state, &owner, before, limit).await?;
+    let cache_control = format!("public, max-age={}", PUBLIC_LOG_CACHE_SECS);
+    if html {
+        Ok((
+            [(header::CACHE_CONTROL, cache_control)],
+            Html(render_html(&log)),
+        )
+            .into_response())
+    } else {
+        Ok(([(header::CACHE_CONTROL, cache_control)], Json(log)).into_response())
+    }
+}
+
+/// A feed row before its media URLs are signed
+enum LogItem {
+    Tweet(LogTweet),
+    Capture(LogCapture),
+}
+
+/// Merge posted tweets and selected captures into one page of the feed
+async fn build_log(
+    state: &AppState,
+    owner: &LogOwner,
+    before: DateTime<Utc>,
+    limit: i64,
+) -> Result<PublicLogResponse, ApiError> {
+    // Fetch a full page of each, then keep the newest `limit` of the two
+    let tweets = public_log::list_posted_tweets(&state.db, owner.id, before, limit)
+        .await
+        .log_500("List public log tweets error")?;
+    let captures = public_log::list_selected_captures(&state.db, owner.id, before, limit)
+        .await
+        .log_500("List public log captures error")?;
+
+    let mut items: Vec<(DateTime<Utc>, LogItem)> = tweets
+        .into_iter()
+        .map(|t| (t.posted_at, LogItem::Tweet(t)))
+        .chain(
+            captures
+                .into_iter()
+                .map(|c| (c.captured_at, LogItem::Capture(c))),
+        )
+        .collect();
+    // Stable sort keeps thread tweets posted in the same instant in order
+    items.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+    let full_page = items.len() >= limit as usize;
+    items.truncate(limit as usize);
+
+    let image_ids: Vec<i64> = items
+        .iter()
+        .flat_map(|(_, item)| match item {
+            LogItem::Tweet(t) => t.image_capture_ids.as_slice(),
+            LogItem::Capture(_) => &[],
+        })
+        .copied()
+        .collect();
+    let tweet_images: HashMap<i64, LogCapture> =
+        public_log::get_captures(&state.db, owner.id, &image_ids)
+            .await
+            .log_500("Get public log tweet images error")?
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect();
+
+    // Media URLs are only signed for entries on this page
+    let mut entries = Vec::with_capacity(items.len());
+    for (at, item) in items {
+        match item {
+            LogItem::Tweet(tweet) => {
+                let mut media = Vec::new();
+                for id in &tweet.image_capture_ids {
+                    if let Some(capture) = tweet_images.get(id) {
+                        media.push(log_media(state, owner.id, capture).await?);
+                    }
+                }
+                entries.push(LogEntry::Tweet {
+                    id: tweet.id,
+                    text: tweet.text,
+                    posted_at: at,
+                    url: tweet.tweet_id.map(|id| {
+                        format!("https://x.com/{}/status/{}", owner.twitter_username, id)
+                    }),
+                    thread_id: tweet.thread_id,
+                    media,
+                });
+            }
+            LogItem::Capture(capture) => entries.push(LogEntry::Capture {
+                at,
+                media: log_media(state, owner.id, &capture).await?,
+            }),
+        }
+    }
+
+    let next_before = if full_page {
+        entries.last().map(LogEntry::at)
+    } else {
+        None
+    };
+
+    Ok(PublicLogResponse {
+        username: owner.twitter_username.clone(),
+        display_name: owner.twitter_name.clone(),
+        entries,
+        next_before,
+    })
+}
+
+async fn log_media(
+    state: &AppState,
+    user_id: i64,
+    capture: &LogCapture,
+) -> Result<LogMedia, ApiError> {
+    Ok(LogMedia {
+        capture_id: capture.id,
+        media_type: capture.media_type.clone(),
+        content_type: capture.content_type.clone(),
+        url: signed_url(state, user_id, &capture.gcs_path).await?,
+    })
+}
+
+fn render_media_html(media: &LogMedia) -> String {
+    let url = escape_html(&media.url);
+    if media.media_type == "video" {
+        format!(
+            r#"<video src="{}" controls preload="metadata"></video>"#,
+            url
+        )
+    } else {
+        format!(r#"<img src="{}" alt="" loading="lazy">"#, url)
+    }
+}
+
+/// Standalone page for the log, with no scripts or external assets
+fn render_html(log: &PublicLogResponse) -> String {
+    let name = escape_html(log.display_name.as_deref().unwrap_or(&log.username));
+    let username = escape_html(&log.username);
+
+    let mut body = String::new();
+    for entry in &log.entries {
+        body.push_str("<article>");
+        body.push_str(&format!(
+            r#"<time datetime="{0}">{0}</time>"#,
+            entry.at().to_rfc3339()
+        ));
+        match entry {
+            LogEntry::Tweet {
+                text, url, media, ..
+            } => {
+                body.push_str(&format!("<p>{}</p>", escape_html(text)));
+                for m in media {
+                    body.push_str(&render_media_html(m));
+                }
+                if let Some(url) = url {
+                    body.push_str(&format!(
+                        r#"<a href="{}" rel="noopener">View on X</a>"#,
+                        escape_html(url)
+                    ));
+                }
+            }
+            LogEntry::Capture { media, .. } => body.push_str(&render_media_html(media)),
+        }
+        body.push_str("</article>\n");
+    }
+    if log.entries.is_empty() {
+        body.push_str("<p>Nothing here yet.</p>\n");
+    }
+    if let Some(before) = log.next_before {
+        body.push_str(&format!(
+            r#"<nav><a href="?format=html&amp;before={}">Older</a></nav>"#,
+            before.to_rfc3339_opts(SecondsFormat::Micros, true)
+        ));
+    }
+
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{name}'s build log</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 40rem; margin: 2rem auto; padding: 0 1rem; color: #1a1a1a; }}
+article {{ border-bottom: 1px solid #e5e5e5; padding: 1rem 0; }}
+time {{ color: #666; font-size: 0.85rem; }}
+p {{ white-space: pre-wrap; }}
+img, video {{ max-width: 100%; border-radius: 6px; margin-top: 0.5rem; display: block; }}
+</style>
+</head>
+<body>
+<h1>{name}'s build log</h1>
+<p>@{username}</p>
+{body}</body>
+</html>
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_html_escapes_user_text() {
+        let log = PublicLogResponse {
+            username: "builder".to_string(),
+            display_name: Some("<b>Builder</b>".to_string()),
+            entries: vec![LogEntry::Tweet {
+                id: 1,
+                text: "Shipped <script>alert(1)</script> & more".to_string(),
+                posted_at: Utc::now(),
+                url: Some("https://x.com/builder/status/1".to_string()),
+                thread_id: None,
+                media: vec![LogMedia {
+                    capture_id: 2,
+                    media_type: "image".to_string(),
+                    content_type: "image/png".to_string(),
+                    url: "/media/a.png?uid=1&exp=2&sig=\"x".to_string(),
+                }],
+            }],
+            next_before: None,
+        };
+
+        let html = render_html(&log);
+        assert!(html.contains("<title>&lt;b&gt;Builder&lt;/b&gt;'s build log</title>"));
+        assert!(html.contains("Shipped &lt;script&gt;alert(1)&lt;/script&gt; &amp; more"));
+        assert!(html.contains(r#"src="/media/a.png?uid=1&amp;exp=2&amp;sig=&quot;x""#));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("Older"));
+    }
+}
@@ -65,8 +65,11 @@ struct VapidPublicKeyResponse {
 }
 
 async fn get_vapid_public_key() -> Result<impl IntoResponse, StatusCode> {
-    let vapid_public_key =
-        std::env::var("VAPID_PUBLIC_KEY").map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    let vapid_public_key = crate::config::get()
+        .push
+        .vapid_public_key
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
 
     Ok(axum::Json(VapidPublicKeyResponse { vapid_public_key }))
 }
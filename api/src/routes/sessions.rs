@@ -0,0 +1,162 @@
+//! Work session endpoints (/sessions)
+//!
+//! The daemon opens a session when it starts capturing and closes it on shutdown.
+//! Captures and activities uploaded in between carry the session id.
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use super::captures::{DaemonIdentity, get_daemon_identity, get_user_id_from_bearer};
+use crate::AppState;
+use crate::constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::domain::sessions;
+use crate::services::error::LogErr;
+use crate::services::timezone;
+
+/// Maximum stored length for the daemon-reported device name
+const MAX_DEVICE_NAME_LEN: usize = 128;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/sessions", get(list_sessions).post(open_session))
+        .route("/sessions/{id}/end", post(end_session))
+}
+
+/// Session API response DTO
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: i64,
+    pub device: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub app_summary: serde_json::Value,
+    pub capture_count: i64,
+}
+
+impl From<sessions::Session> for SessionResponse {
+    fn from(s: sessions::Session) -> Self {
+        Self {
+            id: s.id,
+            device: s.device,
+            started_at: s.started_at,
+            ended_at: s.ended_at,
+            app_summary: s.app_summary,
+            capture_count: s.capture_count,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenSessionRequest {
+    device: Option<String>,
+}
+
+#[derive(Serialize)]
+struct OpenSessionResponse {
+    id: i64,
+    started_at: DateTime<Utc>,
+}
+
+/// POST /sessions - Open a new work session (daemon auth)
+async fn open_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<OpenSessionRequest>,
+) -> Result<(StatusCode, Json<OpenSessionResponse>), StatusCode> {
+    let DaemonIdentity { user_id, device_id } = get_daemon_identity(&state.db, &headers).await?;
+
+    let device = payload
+        .device
+        .map(|d| {
+            d.trim()
+                .chars()
+                .take(MAX_DEVICE_NAME_LEN)
+                .collect::<String>()
+        })
+        .filter(|d| !d.is_empty());
+
+    let (id, started_at) = sessions::open_session(&state.db, user_id, device_id, device.as_deref())
+        .await
+        .log_500("Open session error")?;
+
+    println!("[sessions] Opened session {} for user {}", id, user_id);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(OpenSessionResponse { id, started_at }),
+    ))
+}
+
+/// POST /sessions/:id/end - Close a work session (daemon auth)
+async fn end_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<i64>,
+) -> Result<Json<SessionResponse>, StatusCode> {
+    let user_id = get_user_id_from_bearer(&state.db, &headers).await?;
+
+    let session = sessions::close_session(&state.db, session_id, user_id)
+        .await
+        .log_500("Close session error")?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    println!(
+        "[sessions] Closed session {} for user {}",
+        session_id, user_id
+    );
+
+    Ok(Json(SessionResponse::from(session)))
+}
+
+#[derive(Deserialize)]
+struct ListSessionsQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+    /// Only sessions started on this local day (YYYY-MM-DD) in the user's time zone
+    date: Option<NaiveDate>,
+}
+
+#[derive(Serialize)]
+struct ListSessionsResponse {
+    sessions: Vec<SessionResponse>,
+    total: i64,
+    has_more: bool,
+}
+
+/// GET /sessions - Browse work sessions, newest first
+async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Query(query): Query<ListSessionsQuery>,
+) -> Result<Json<ListSessionsResponse>, StatusCode> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0);
+
+    let day = match query.date {
+        Some(date) => {
+            let tz = timezone::user_timezone(&state.db, user_id).await;
+            Some(timezone::day_bounds(date, tz))
+        }
+        None => None,
+    };
+
+    let (rows, total) = sessions::list_sessions_paginated(&state.db, user_id, day, limit, offset)
+        .await
+        .log_500("List sessions error")?;
+
+    let has_more = offset + (rows.len() as i64) < total;
+
+    Ok(Json(ListSessionsResponse {
+        sessions: rows.into_iter().map(SessionResponse::from).collect(),
+        total,
+        has_more,
+    }))
+}
@@ -0,0 +1,113 @@
+//! Admin storage audit endpoints (/admin/storage-audit)
+//!
+//! Findings are produced by the storage audit worker; these endpoints list them
+//! and trigger repair of the ones that are safe to fix automatically.
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AdminUser;
+use crate::AppState;
+use crate::domain::storage_audit;
+use crate::services::error::LogErr;
+
+const DEFAULT_LIMIT: i64 = 200;
+const MAX_LIMIT: i64 = 1000;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/admin/storage-audit", get(list_findings))
+        .route("/admin/storage-audit/repair", post(repair_findings))
+}
+
+/// Storage audit finding API response DTO
+#[derive(Debug, Serialize)]
+pub struct FindingResponse {
+    pub id: i64,
+    pub kind: String,
+    pub path: String,
+    pub capture_id: Option<i64>,
+    pub detail: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolution: Option<String>,
+}
+
+impl From<storage_audit::Finding> for FindingResponse {
+    fn from(f: storage_audit::Finding) -> Self {
+        Self {
+            id: f.id,
+            kind: f.kind,
+            path: f.path,
+            capture_id: f.capture_id,
+            detail: f.detail,
+            first_seen_at: f.first_seen_at,
+            last_seen_at: f.last_seen_at,
+            resolved_at: f.resolved_at,
+            resolution: f.resolution,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ListFindingsQuery {
+    #[serde(default)]
+    include_resolved: bool,
+    limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ListFindingsResponse {
+    findings: Vec<FindingResponse>,
+}
+
+/// GET /admin/storage-audit - List open findings (or all with include_resolved=true)
+async fn list_findings(
+    State(state): State<Arc<AppState>>,
+    AdminUser(_user_id): AdminUser,
+    Query(query): Query<ListFindingsQuery>,
+) -> Result<Json<ListFindingsResponse>, StatusCode> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let rows = storage_audit::list_findings(&state.db, query.include_resolved, limit)
+        .await
+        .log_500("List storage audit findings error")?;
+
+    Ok(Json(ListFindingsResponse {
+        findings: rows.into_iter().map(FindingResponse::from).collect(),
+    }))
+}
+
+#[derive(Serialize)]
+struct RepairResponse {
+    repaired: usize,
+}
+
+/// POST /admin/storage-audit/repair - Delete orphaned objects and regenerate missing thumbnails
+async fn repair_findings(
+    State(state): State<Arc<AppState>>,
+    AdminUser(user_id): AdminUser,
+) -> Result<Json<RepairResponse>, StatusCode> {
+    let repaired = crate::storage_audit::repair_findings(
+        &state.db,
+        state.gcs.as_ref(),
+        state.local_storage_path.as_ref(),
+        &state.buckets,
+    )
+    .await
+    .log_500("Storage audit repair error")?;
+
+    println!(
+        "[storage_audit] User {} repaired {} findings",
+        user_id, repaired
+    );
+
+    Ok(Json(RepairResponse { repaired }))
+}
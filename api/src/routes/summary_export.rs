@@ -0,0 +1,271 @@
+//! Daily summary export settings (/me/summary-export)
+//!
+//! One destination per user; the nightly worker in daily_summary writes the
+//! notes. Notion tokens and WebDAV passwords are encrypted at rest and never
+//! returned. A PUT that leaves a secret out keeps the stored one, as long as
+//! the destination doesn't change.
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use crate::AppState;
+use crate::daily_summary::vault_dir;
+use crate::domain::summary_exports::{self, NewSummaryExport, SummaryExport};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::crypto;
+use crate::services::error::{ApiError, LogErr};
+use crate::services::validation::is_public_https_url;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/me/summary-export",
+        get(get_summary_export)
+            .put(update_summary_export)
+            .delete(delete_summary_export),
+    )
+}
+
+/// Summary export API response DTO (secrets omitted)
+#[derive(Serialize)]
+struct SummaryExportResponse {
+    destination: String,
+    enabled: bool,
+    notion_parent_page_id: Option<String>,
+    has_notion_token: bool,
+    webdav_url: Option<String>,
+    webdav_username: Option<String>,
+    has_webdav_password: bool,
+    local_dir: Option<String>,
+    /// Local date of the last day written
+    last_exported_date: Option<NaiveDate>,
+    last_error: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<SummaryExport> for SummaryExportResponse {
+    fn from(e: SummaryExport) -> Self {
+        Self {
+            destination: e.destination,
+            enabled: e.enabled,
+            notion_parent_page_id: e.notion_parent_page_id,
+            has_notion_token: e.notion_token.is_some(),
+            webdav_url: e.webdav_url,
+            webdav_username: e.webdav_username,
+            has_webdav_password: e.webdav_password.is_some(),
+            local_dir: e.local_dir,
+            last_exported_date: e.last_exported_date,
+            last_error: e.last_error,
+            updated_at: e.updated_at,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct UpdateSummaryExportRequest {
+    /// notion, webdav or local
+    destination: String,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    /// Notion internal integration token
+    notion_token: Option<String>,
+    /// Page id or page URL; summaries are created as its child pages
+    notion_parent_page_id: Option<String>,
+    /// HTTPS URL of the folder notes are written to
+    webdav_url: Option<String>,
+    webdav_username: Option<String>,
+    webdav_password: Option<String>,
+    /// Folder under the user's directory in the server's vault root
+    local_dir: Option<String>,
+}
+
+/// Trimmed, or None if blank
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Notion page id (32 hex digits) from an id, with or without dashes, or a page URL
+fn notion_page_id(input: &str) -> Option<String> {
+    let path = input.trim().split(['?', '#']).next().unwrap_or_default();
+    let compact: String = path.chars().filter(|c| *c != '-').collect();
+    let id = compact.get(compact.len().checked_sub(32)?..)?;
+    id.chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| id.to_ascii_lowercase())
+}
+
+/// GET /me/summary-export - Current daily summary destination
+async fn get_summary_export(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<SummaryExportResponse>, ApiError> {
+    let export = summary_exports::get_export(&state.db, user_id)
+        .await
+        .log_500("Get summary export error")?
+        .ok_or(ApiError::NotFound("Summary export"))?;
+    Ok(Json(export.into()))
+}
+
+/// PUT /me/summary-export - Set the daily summary destination
+async fn update_summary_export(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    Json(req): Json<UpdateSummaryExportRequest>,
+) -> Result<Json<SummaryExportResponse>, ApiError> {
+    let existing = summary_exports::get_export(&state.db, user_id)
+        .await
+        .log_500("Get summary export error")?
+        .filter(|e| e.destination == req.destination);
+    // A secret left out keeps the stored one for the same destination
+    let keep = |new: Option<&str>, stored: Option<String>| -> Option<String> {
+        match non_empty(new) {
+            Some(secret) => Some(crypto::cipher().encrypt(secret)),
+            None => stored,
+        }
+    };
+
+    let mut notion_token = None;
+    let mut notion_parent_page_id = None;
+    let mut webdav_url = None;
+    let mut webdav_username = None;
+    let mut webdav_password = None;
+    let mut local_dir = None;
+    match req.destination.as_str() {
+        "notion" => {
+            notion_token = Some(
+                keep(
+                    req.notion_token.as_deref(),
+                    existing.and_then(|e| e.notion_token),
+                )
+                .ok_or_else(|| ApiError::Unprocessable("notion_token is required".to_string()))?,
+            );
+            notion_parent_page_id = Some(
+                req.notion_parent_page_id
+                    .as_deref()
+                    .and_then(notion_page_id)
+                    .ok_or_else(|| {
+                        ApiError::Unprocessable(
+                            "notion_parent_page_id must be a Notion page id or URL".to_string(),
+                        )
+                    })?,
+            );
+        }
+        "webdav" => {
+            webdav_url = Some(
+                non_empty(req.webdav_url.as_deref())
+                    .filter(|u| is_public_https_url(u))
+                    .ok_or_else(|| {
+                        ApiError::Unprocessable(
+                            "webdav_url must be an https URL on a public host".to_string(),
+                        )
+                    })?,
+            );
+            webdav_username = non_empty(req.webdav_username.as_deref());
+            webdav_password = keep(
+                req.webdav_password.as_deref(),
+                existing.and_then(|e| e.webdav_password),
+            );
+        }
+        "local" => {
+            let root = crate::config::get()
+                .workers
+                .daily_summary
+                .vault_root
+                .as_ref()
+                .ok_or(ApiError::ServiceUnavailable("Local vault exports"))?;
+            local_dir = non_empty(req.local_dir.as_deref());
+            if vault_dir(root, user_id, local_dir).is_none() {
+                return Err(ApiError::Unprocessable(
+                    "local_dir must be a relative folder without '..'".to_string(),
+                ));
+            }
+        }
+        _ => {
+            return Err(ApiError::Unprocessable(
+                "destination must be notion, webdav or local".to_string(),
+            ));
+        }
+    }
+
+    let export = NewSummaryExport {
+        destination: &req.destination,
+        enabled: req.enabled,
+        notion_token: notion_token.as_deref(),
+        notion_parent_page_id: notion_parent_page_id.as_deref(),
+        webdav_url,
+        webdav_username,
+        webdav_password: webdav_password.as_deref(),
+        local_dir,
+    };
+    let saved = summary_exports::upsert_export(&state.db, user_id, export)
+        .await
+        .log_500("Save summary export error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::SummaryExportUpdated,
+        None,
+        Some(serde_json::json!({ "destination": saved.destination, "enabled": saved.enabled })),
+    )
+    .await;
+
+    Ok(Json(saved.into()))
+}
+
+/// DELETE /me/summary-export - Stop exporting daily summaries and forget the destination
+async fn delete_summary_export(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+) -> Result<StatusCode, ApiError> {
+    let deleted = summary_exports::delete_export(&state.db, user_id)
+        .await
+        .log_500("Delete summary export error")?;
+    if !deleted {
+        return Err(ApiError::NotFound("Summary export"));
+    }
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::SummaryExportRemoved,
+        None,
+        None,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notion_page_id() {
+        let id = "0123456789abcdef0123456789abcdef";
+        assert_eq!(notion_page_id(id).as_deref(), Some(id));
+        assert_eq!(
+            notion_page_id("01234567-89ab-cdef-0123-456789ABCDEF").as_deref(),
+            Some(id)
+        );
+        assert_eq!(
+            notion_page_id(
+                "https://www.notion.so/team/Daily-Log-0123456789abcdef0123456789abcdef?pvs=4"
+            )
+            .as_deref(),
+            Some(id)
+        );
+        assert!(notion_page_id("not a page").is_none());
+    }
+}
@@ -0,0 +1,3480 @@
+//! Route tests against a real database with in-memory storage and mock providers.
+//! Run with `cargo test -- --ignored` and DATABASE_URL pointing at a TimescaleDB server.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use chrono::{Duration, Utc};
+
+use crate::services::twitter;
+use crate::storage;
+use crate::test_support::{TestApp, body_json};
+
+const BOUNDARY: &str = "cleo-test-boundary";
+
+/// Multipart body with one file part per (content type, bytes, optional checksum)
+fn multipart_body(parts: &[(&str, &[u8], Option<&str>)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (i, (content_type, data, sha256)) in parts.iter().enumerate() {
+        body.extend_from_slice(format!("--{}\r\n", BOUNDARY).as_bytes());
+        body.extend_from_slice(
+            format!(
+                "Content-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n",
+                i
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+        if let Some(sha256) = sha256 {
+            body.extend_from_slice(format!("X-Content-SHA256: {}\r\n", sha256).as_bytes());
+        }
+        body.extend_from_slice(b"\r\n");
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+    body
+}
+
+fn capture_batch_request(token: &str, body: Vec<u8>) -> Request<Body> {
+    Request::post("/captures/batch")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        )
+        .header("x-interval-id", "1")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn capture_batch_stores_objects_and_browse_lists_them(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("capturer").await;
+    let token = app.api_token(user_id).await;
+
+    let png = b"not really a png";
+    let checksum = storage::sha256_hex(png);
+    let response = app
+        .request(capture_batch_request(
+            &token,
+            multipart_body(&[("image/png", png, Some(&checksum))]),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let batch = body_json(response).await;
+    assert_eq!(batch["uploaded"], 1);
+    assert_eq!(batch["failed"], 0);
+    assert_eq!(app.store.paths().len(), 1);
+
+    let response = app
+        .request(
+            Request::get("/captures/browse")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let browse = body_json(response).await;
+    assert_eq!(browse["total"], 1);
+    assert_eq!(browse["captures"][0]["file_size_bytes"], png.len());
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn capture_batch_rejects_checksum_mismatch(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("mismatch").await;
+    let token = app.api_token(user_id).await;
+
+    let wrong = storage::sha256_hex(b"something else");
+    let response = app
+        .request(capture_batch_request(
+            &token,
+            multipart_body(&[("image/png", b"payload", Some(&wrong))]),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let batch = body_json(response).await;
+    assert_eq!(batch["uploaded"], 0);
+    assert_eq!(batch["failed"], 1);
+    assert!(app.store.paths().is_empty());
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn unsigned_media_requests_are_forbidden(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+
+    for uri in [
+        "/media/image/user_1/a.png",
+        "/media/image/user_1/a.png?uid=1",
+        "/media/image/user_1/a.png?uid=1&exp=9999999999",
+        "/media/image/user_1/a.png?uid=1&sig=AAAA",
+    ] {
+        let response = app
+            .request(Request::get(uri).body(Body::empty()).unwrap())
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", uri);
+    }
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn regenerate_tweet_uses_llm_response(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("writer").await;
+    let tweet_id = app.create_tweet(user_id, "Original draft").await;
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/regenerate", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["text"], app.llm.response.as_str());
+
+    let prompts = app.llm.prompts.lock().unwrap();
+    assert_eq!(prompts.len(), 1);
+    assert!(prompts[0].contains("Original draft"));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn draft_language_setting_and_translation(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("writer").await;
+    let tweet_id = app.create_tweet(user_id, "Cut build from 84s to 19s").await;
+
+    let put_language = |body: &'static str| {
+        Request::put("/me/language")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+    let response = app.request(put_language(r#"{"language":"tlh"}"#)).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app.request(put_language(r#"{"language":"zh-cn"}"#)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["language"], "zh-CN");
+    assert_eq!(body["name"], "Simplified Chinese");
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/translate", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"language":"ja"}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["text"], app.llm.response.as_str());
+
+    let prompts = app.llm.prompts.lock().unwrap();
+    assert_eq!(prompts.len(), 1);
+    assert!(prompts[0].contains("into Japanese"));
+    assert!(prompts[0].contains("Cut build from 84s to 19s"));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn copy_experiment_posts_variant_a_and_ranks_styles(pool: sqlx::PgPool) {
+    use crate::domain::copy_experiments::{self, Engagement};
+
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("writer").await;
+    let tweet_id = app.create_tweet(user_id, "Original draft").await;
+
+    let put_experiment = |body: &'static str| {
+        Request::put(format!("/tweets/{}/experiment", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+    let response = app
+        .request(put_experiment(
+            r#"{"a":{"text":"One","style":"Hook"},"b":{"text":"Two","style":" hook "}}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(put_experiment(
+            r#"{"a":{"text":"Why is my build 84s?","style":"Question  Hook"},"b":{"text":"Build: 84s to 19s","style":"result first"}}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["a"]["style"], "question hook");
+    assert!(body["engagement"].is_null());
+
+    let text: String = sqlx::query_scalar("SELECT text FROM tweet_collateral WHERE id = $1")
+        .bind(tweet_id)
+        .fetch_one(&app.state.db)
+        .await
+        .unwrap();
+    assert_eq!(text, "Why is my build 84s?");
+
+    let experiment_id: i64 =
+        sqlx::query_scalar("SELECT id FROM copy_experiments WHERE tweet_collateral_id = $1")
+            .bind(tweet_id)
+            .fetch_one(&app.state.db)
+            .await
+            .unwrap();
+    copy_experiments::record_engagement(
+        &app.state.db,
+        experiment_id,
+        Engagement {
+            like_count: 7,
+            retweet_count: 2,
+            reply_count: 1,
+            quote_count: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let response = app
+        .request(
+            Request::get("/insights/experiments")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["styles"][0]["style"], "question hook");
+    assert_eq!(body["styles"][0]["avg_engagement"], 10.0);
+    assert!(body["winner"].is_null());
+    assert_eq!(body["recent"][0]["engagement"]["total"], 10);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn dev_signals_scan_terminal_captures_only(pool: sqlx::PgPool) {
+    use crate::dev_signals::{TERMINAL_APPS, parse_signals};
+    use crate::domain::dev_signals;
+
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("builder").await;
+    let now = Utc::now();
+    for (minutes_ago, application) in [(10, "Ghostty"), (8, "Safari")] {
+        sqlx::query(
+            "INSERT INTO activities (user_id, timestamp, interval_id, event_type, application, \"window\")
+             VALUES ($1, $2, 1, 'AppSwitch', $3, '')",
+        )
+        .bind(user_id)
+        .bind(now - Duration::minutes(minutes_ago))
+        .bind(application)
+        .execute(&app.state.db)
+        .await
+        .unwrap();
+    }
+    let mut capture_ids = Vec::new();
+    for minutes_ago in [9, 7] {
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO captures (interval_id, user_id, media_type, content_type, gcs_path, captured_at)
+             VALUES (1, $1, 'image', 'image/png', 'image/ocr.png', $2) RETURNING id",
+        )
+        .bind(user_id)
+        .bind(now - Duration::minutes(minutes_ago))
+        .fetch_one(&app.state.db)
+        .await
+        .unwrap();
+        capture_ids.push(id);
+    }
+
+    let claimed = dev_signals::claim_captures(&app.state.db, 10, TERMINAL_APPS)
+        .await
+        .unwrap();
+    let mut terminal: Vec<(i64, bool)> = claimed.iter().map(|c| (c.id, c.terminal)).collect();
+    terminal.sort();
+    assert_eq!(terminal, [(capture_ids[0], true), (capture_ids[1], false)]);
+    // Claimed captures are marked scanned
+    assert!(
+        dev_signals::claim_captures(&app.state.db, 10, TERMINAL_APPS)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let capture = claimed.iter().find(|c| c.terminal).unwrap();
+    for signal in parse_signals("Finished `dev` profile [unoptimized] target(s) in 19.32s") {
+        dev_signals::insert_signal(
+            &app.state.db,
+            user_id,
+            capture.id,
+            capture.captured_at,
+            &signal,
+        )
+        .await
+        .unwrap();
+    }
+    let stored = dev_signals::list_for_captures(&app.state.db, user_id, &capture_ids)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].capture_id, capture_ids[0]);
+    assert_eq!(stored[0].signal.tool.as_deref(), Some("cargo"));
+    assert_eq!(stored[0].signal.duration_secs, Some(19.32));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn launch_mode_shortens_idle_threshold(pool: sqlx::PgPool) {
+    use crate::agent::find_idle_users_with_pending_captures;
+
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("launcher").await;
+    let start = |body: &'static str| {
+        Request::post("/me/launch-mode")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    sqlx::query(
+        "INSERT INTO captures (interval_id, user_id, media_type, content_type, gcs_path, captured_at)
+         VALUES (1, $1, 'image', 'image/jpeg', 'image/launch.jpg', NOW() - INTERVAL '10 minutes')",
+    )
+    .bind(user_id)
+    .execute(&app.state.db)
+    .await
+    .unwrap();
+
+    // Active ten minutes ago: not idle against a 20 minute threshold
+    let idle = find_idle_users_with_pending_captures(&app.state.db, 20, false)
+        .await
+        .unwrap();
+    assert!(!idle.contains(&user_id));
+
+    let response = app.request(start(r#"{"duration_minutes":0}"#)).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app.request(start(r#"{"duration_minutes":120}"#)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["active"], true);
+    assert!(body["until"].is_string());
+
+    let idle = find_idle_users_with_pending_captures(&app.state.db, 20, false)
+        .await
+        .unwrap();
+    assert!(idle.contains(&user_id));
+
+    let response = app
+        .request(
+            Request::delete("/me/launch-mode")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["active"], false);
+    let idle = find_idle_users_with_pending_captures(&app.state.db, 20, false)
+        .await
+        .unwrap();
+    assert!(!idle.contains(&user_id));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn weekly_recap_schedule_and_thread_draft(pool: sqlx::PgPool) {
+    use crate::domain::weekly_recaps;
+    use crate::services::weekly_recap;
+    use crate::test_support::MockLlm;
+
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("shipper").await;
+    let put_schedule = |body: &'static str| {
+        Request::put("/me/weekly-recap")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+    let compose = || {
+        Request::post("/me/weekly-recap/compose")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app
+        .request(
+            Request::get("/me/weekly-recap")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = body_json(response).await;
+    assert_eq!(body["day"], "friday");
+    assert_eq!(body["hour"], 17);
+
+    let response = app
+        .request(put_schedule(r#"{"day":"someday","hour":9}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app
+        .request(put_schedule(r#"{"day":"Sunday","hour":9}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["day"], "sunday");
+    assert_eq!(body["enabled"], true);
+
+    // Nothing drafted or posted yet
+    let response = app.request(compose()).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let shipped = app.create_tweet(user_id, "Cut build from 84s to 19s").await;
+    sqlx::query("UPDATE tweet_collateral SET posted_at = NOW(), tweet_id = 'x-1' WHERE id = $1")
+        .bind(shipped)
+        .execute(&app.state.db)
+        .await
+        .unwrap();
+    app.create_tweet(user_id, "Sketching onboarding").await;
+
+    // The default mock answer isn't a JSON thread
+    let response = app.request(compose()).await;
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+
+    let llm = MockLlm::new(r#"["My week in review", "Builds got 4x faster", "Next: onboarding"]"#);
+    let now = Utc::now();
+    let recap = weekly_recap::compose_recap(
+        &app.state.db,
+        &llm,
+        user_id,
+        now.date_naive(),
+        now - Duration::days(7),
+        now,
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(recap.tweets.len(), 3);
+    let prompt = llm.prompts.lock().unwrap()[0].clone();
+    assert!(prompt.contains("Posted this week:\n- "));
+    assert!(prompt.contains("Cut build from 84s to 19s"));
+
+    let texts: Vec<String> = sqlx::query_scalar(
+        "SELECT text FROM tweet_collateral WHERE thread_id = $1 ORDER BY thread_position",
+    )
+    .bind(recap.thread_id)
+    .fetch_all(&app.state.db)
+    .await
+    .unwrap();
+    assert_eq!(texts[1], "Builds got 4x faster");
+
+    // The next recap doesn't summarize this one
+    let week = weekly_recaps::list_week_tweets(
+        &app.state.db,
+        user_id,
+        now - Duration::days(7),
+        Utc::now(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(week.len(), 2);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn content_is_scoped_to_the_session_user(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let owner = app.create_user("owner").await;
+    let other = app.create_user("other").await;
+    let tweet_id = app.create_tweet(owner, "Owner's draft").await;
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/regenerate", tweet_id))
+                .header(header::COOKIE, app.session_cookie(other))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(body_json(response).await["error"], "not_found");
+    assert!(app.llm.prompts.lock().unwrap().is_empty());
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn publish_tweet_posts_through_social_client(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("publisher").await;
+    let tweet_id = app.create_tweet(user_id, "Shipping today").await;
+
+    let publish = || {
+        Request::post(format!("/tweets/{}/publish", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.request(publish()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["tweet_id"], "mock-tweet-1");
+
+    let posted = app.social.posted.lock().unwrap().clone();
+    assert_eq!(posted.len(), 1);
+    assert_eq!(posted[0].text, "Shipping today");
+    assert!(posted[0].in_reply_to.is_none());
+    assert!(posted[0].media_ids.is_empty());
+
+    // Already posted, so it is no longer publishable
+    let response = app.request(publish()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(app.social.posted.lock().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn revoked_refresh_token_flags_reconnect(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("revoked").await;
+    let tweet_id = app.create_tweet(user_id, "Never leaves the queue").await;
+    twitter::update_user_tokens(
+        &app.state.db,
+        user_id,
+        "expired-access-token",
+        Some("revoked-refresh-token"),
+        Utc::now() - Duration::hours(1),
+    )
+    .await
+    .unwrap();
+
+    let get_me = || {
+        Request::get("/me")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let body = body_json(app.request(get_me()).await).await;
+    assert_eq!(body["twitter_connection"], "connected");
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        body_json(response).await["error"],
+        "twitter_reauth_required"
+    );
+    assert!(app.social.posted.lock().unwrap().is_empty());
+
+    let body = body_json(app.request(get_me()).await).await;
+    assert_eq!(body["twitter_connection"], "needs_reconnect");
+
+    // Logging in again stores fresh tokens and clears the flag
+    app.create_user("revoked").await;
+    let body = body_json(app.request(get_me()).await).await;
+    assert_eq!(body["twitter_connection"], "connected");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn async_publish_streams_progress_events(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("publisher").await;
+    let tweet_id = app
+        .create_tweet(user_id, "Shipping in the background")
+        .await;
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish?async=true", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body = body_json(response).await;
+    let events_url = body["events_url"].as_str().unwrap().to_string();
+
+    // Someone else's job doesn't exist for them
+    let other = app.create_user("other").await;
+    let events = |user_id: i64| {
+        Request::get(events_url.as_str())
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.request(events(other)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // The stream ends once the job finishes
+    let response = app.request(events(user_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(text.contains("event: complete"), "{}", text);
+    assert!(text.contains("mock-tweet-1"), "{}", text);
+
+    let response = app
+        .request(
+            Request::get(body["status_url"].as_str().unwrap())
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(body_json(response).await["type"], "complete");
+    assert_eq!(app.social.posted.lock().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn thread_publishes_in_background(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("threader").await;
+    let first = app.create_tweet(user_id, "1/ What we shipped").await;
+    let second = app.create_tweet(user_id, "2/ What's next").await;
+
+    let response = app
+        .request(
+            Request::post("/threads")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "tweet_ids": [first, second] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let thread_id = body_json(response).await["id"].as_i64().unwrap();
+
+    let response = app
+        .request(
+            Request::post(format!("/threads/{}/publish", thread_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body = body_json(response).await;
+    assert_eq!(body["status"], "posting");
+    let status_url = body["status_url"].as_str().unwrap().to_string();
+
+    let mut status = serde_json::Value::Null;
+    for _ in 0..100 {
+        let response = app
+            .request(
+                Request::get(status_url.as_str())
+                    .header(header::COOKIE, app.session_cookie(user_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        status = body_json(response).await;
+        if status["status"] != "posting" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(status["status"], "posted");
+    assert_eq!(status["posted"], 2);
+    assert_eq!(
+        status["tweets"][1]["reply_to"],
+        status["tweets"][0]["twitter_id"]
+    );
+    assert_eq!(app.social.posted.lock().unwrap().len(), 2);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn publish_passes_reply_and_quote_references(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("quoter").await;
+    let tweet_id = app.create_tweet(user_id, "Agreed, and here's why").await;
+    app.social.missing.lock().unwrap().push("404".to_string());
+
+    let update = |body: &'static str| {
+        Request::put(format!("/tweets/{}/collateral", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .request(update(
+            r#"{"quote_tweet_url":"https://x.com/jack/status/404"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app
+        .request(update(r#"{"in_reply_to_url":"https://example.com/jack"}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(update(
+            r#"{"in_reply_to_url":"https://twitter.com/jack/status/21","quote_tweet_url":"https://x.com/jack/status/20?s=46"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let posted = app.social.posted.lock().unwrap().clone();
+    assert_eq!(posted[0].in_reply_to.as_deref(), Some("21"));
+    assert_eq!(posted[0].quote_tweet_id.as_deref(), Some("20"));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn publish_attaches_validated_poll(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("pollster").await;
+    let tweet_id = app.create_tweet(user_id, "Which one?").await;
+
+    let update = |body: &'static str| {
+        Request::put(format!("/tweets/{}/collateral", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app.request(update(r#"{"poll_options":["Tabs"]}"#)).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app
+        .request(update(
+            r#"{"poll_options":["Tabs","Spaces"],"quote_tweet_url":"https://x.com/jack/status/20"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(update(r#"{"poll_options":["Tabs","Spaces"]}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let posted = app.social.posted.lock().unwrap().clone();
+    let poll = posted[0].poll.as_ref().unwrap();
+    assert_eq!(poll.options, vec!["Tabs".to_string(), "Spaces".to_string()]);
+    assert_eq!(poll.duration_minutes, 24 * 60);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn media_library_assets_attach_to_tweets(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("brander").await;
+    let tweet_id = app.create_tweet(user_id, "New logo!").await;
+
+    let json = |method: &str, path: String, body: String| {
+        Request::builder()
+            .method(method)
+            .uri(path)
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+    let upload = |content_type: &str| {
+        Request::post("/media-library")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", BOUNDARY),
+            )
+            .body(Body::from(multipart_body(&[(content_type, b"logo", None)])))
+            .unwrap()
+    };
+
+    let response = app.request(upload("text/plain")).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app.request(upload("image/png")).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let asset_id = body_json(response).await["id"].as_i64().unwrap();
+
+    let response = app
+        .request(json(
+            "POST",
+            "/media-library/folders".to_string(),
+            r#"{"name":"Brand"}"#.to_string(),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let folder_id = body_json(response).await["id"].as_i64().unwrap();
+
+    let response = app
+        .request(json(
+            "PATCH",
+            format!("/media-library/{}", asset_id),
+            serde_json::json!({ "folder_id": folder_id }).to_string(),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .request(json(
+            "GET",
+            format!("/media-library?folder_id={}", folder_id),
+            String::new(),
+        ))
+        .await;
+    let assets = body_json(response).await;
+    assert_eq!(assets.as_array().unwrap().len(), 1);
+    assert_eq!(assets[0]["name"], "0");
+
+    let collateral = |ids: serde_json::Value| {
+        json(
+            "PUT",
+            format!("/tweets/{}/collateral", tweet_id),
+            serde_json::json!({ "library_asset_ids": ids }).to_string(),
+        )
+    };
+    let response = app
+        .request(collateral(serde_json::json!([asset_id + 1000])))
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = app.request(collateral(serde_json::json!([asset_id]))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .request(json(
+            "POST",
+            format!("/tweets/{}/publish", tweet_id),
+            String::new(),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(*app.social.uploads.lock().unwrap(), vec!["image/png"]);
+    let posted = app.social.posted.lock().unwrap().clone();
+    assert_eq!(posted[0].media_ids.len(), 1);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn longform_publish_requires_subscription(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("essayist").await;
+
+    let post = |path: String, body: String| {
+        Request::post(path)
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let too_long = "a".repeat(25_001);
+    let response = app
+        .request(post(
+            "/longform".to_string(),
+            serde_json::json!({ "body": too_long }).to_string(),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = "word ".repeat(200);
+    let response = app
+        .request(post(
+            "/longform".to_string(),
+            serde_json::json!({ "title": "Notes", "body": body }).to_string(),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let id = body_json(response).await["id"].as_i64().unwrap();
+
+    let response = app
+        .request(
+            Request::get("/content?platform=twitter")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let content = body_json(response).await;
+    assert_eq!(content["items"][0]["type"], "longform");
+
+    let publish = || post(format!("/longform/{}/publish", id), String::new());
+    let response = app.request(publish()).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(app.social.posted.lock().unwrap().is_empty());
+
+    *app.social.subscription_type.lock().unwrap() = Some("Premium".to_string());
+    let response = app.request(publish()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let posted = app.social.posted.lock().unwrap().clone();
+    assert_eq!(posted[0].text, body);
+
+    let response = app.request(publish()).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn replace_tweet_reposts_and_records_revision(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("corrector").await;
+    let tweet_id = app.create_tweet(user_id, "Shipping todya").await;
+
+    let post = |path: String, body: &'static str| {
+        Request::post(path)
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+    let replace = |body| post(format!("/tweets/{}/replace", tweet_id), body);
+
+    // Not posted yet
+    let response = app.request(replace(r#"{"text":"Shipping today"}"#)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .request(post(format!("/tweets/{}/publish", tweet_id), ""))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app.request(replace(r#"{"text":"Shipping todya"}"#)).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app.request(replace(r#"{"text":"Shipping today"}"#)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["tweet_id"], "mock-tweet-2");
+    assert_eq!(body["previous_tweet_id"], "mock-tweet-1");
+    assert_eq!(body["previous_deleted"], true);
+    assert_eq!(
+        *app.social.deleted.lock().unwrap(),
+        vec!["mock-tweet-1".to_string()]
+    );
+
+    let response = app
+        .request(
+            Request::get(format!("/tweets/{}/revisions", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let revisions = body_json(response).await;
+    assert_eq!(revisions.as_array().unwrap().len(), 1);
+    assert_eq!(revisions[0]["previous_text"], "Shipping todya");
+    assert_eq!(revisions[0]["new_text"], "Shipping today");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn publish_replays_response_for_same_idempotency_key(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("retrier").await;
+    let tweet_id = app.create_tweet(user_id, "Posted once").await;
+
+    let publish = || {
+        Request::post(format!("/tweets/{}/publish", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header("idempotency-key", "publish-attempt-1")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let first = app.request(publish()).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    let first = body_json(first).await;
+
+    let replay = app.request(publish()).await;
+    assert_eq!(replay.status(), StatusCode::OK);
+    assert_eq!(replay.headers()["idempotent-replayed"], "true");
+    assert_eq!(body_json(replay).await, first);
+    assert_eq!(app.social.posted.lock().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn activity_enforces_body_limit_and_validation(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("tracker").await;
+    let token = app.api_token(user_id).await;
+
+    let response = app
+        .request(
+            Request::post("/activity")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::CONTENT_LENGTH, 64 * 1024 * 1024)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body_json(response).await["error"], "payload_too_large");
+
+    let body = r#"[{"timestamp":"2026-01-01T00:00:00Z","intervalId":-1,"event":{"type":"ForegroundSwitch","newActive":"x","windowTitle":"y"}}]"#;
+    let response = app
+        .request(
+            Request::post("/activity")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body_json(response).await["error"], "validation_failed");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn activity_summary_stores_valid_rollups(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("summarized").await;
+    let token = app.api_token(user_id).await;
+
+    let post = |body: &'static str| {
+        Request::post("/activity/summary")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .request(post(
+            r#"[{"intervalId":5904576,"periodStart":"2026-02-20T08:00:00Z","periodEnd":"2026-02-20T08:05:00Z","topApp":"Code","appSwitches":4,"clicks":31,"keypresses":420,"activeSecs":270}]"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // More active time than the interval is long
+    let response = app
+        .request(post(
+            r#"[{"intervalId":5904577,"periodStart":"2026-02-20T08:05:00Z","periodEnd":"2026-02-20T08:10:00Z","appSwitches":0,"clicks":0,"activeSecs":301}]"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let stored: Vec<(Option<String>, i32, i32)> = sqlx::query_as(
+        "SELECT top_app, clicks, active_secs FROM activity_summaries WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_all(&app.state.db)
+    .await
+    .unwrap();
+    assert_eq!(stored, vec![(Some("Code".to_string()), 31, 270)]);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn activity_accepts_protobuf_bodies(pool: sqlx::PgPool) {
+    use crate::services::protobuf::{self, activity_event::Event};
+    use prost::Message;
+
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("compact").await;
+    let token = app.api_token(user_id).await;
+
+    let post = |path: &str, body: Vec<u8>| {
+        Request::post(path)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, protobuf::PROTOBUF_CONTENT_TYPE)
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let events = protobuf::ActivityBatch {
+        events: vec![
+            protobuf::ActivityEvent {
+                timestamp_ms: 1_772_438_400_000,
+                interval_id: 1,
+                session_id: None,
+                event: Some(Event::ForegroundSwitch(protobuf::ForegroundSwitch {
+                    new_active: "Code".to_string(),
+                    window_title: "main.rs — cleoapp".to_string(),
+                })),
+            },
+            protobuf::ActivityEvent {
+                timestamp_ms: 1_772_438_401_000,
+                interval_id: 1,
+                session_id: None,
+                event: Some(Event::MouseClick(protobuf::MouseClick {})),
+            },
+        ],
+    };
+    let response = app.request(post("/activity", events.encode_to_vec())).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let summaries = protobuf::ActivitySummaryBatch {
+        summaries: vec![protobuf::ActivitySummary {
+            interval_id: 5904576,
+            session_id: None,
+            period_start_ms: 1_771_574_400_000,
+            period_end_ms: 1_771_574_700_000,
+            top_app: Some("Code".to_string()),
+            app_switches: 4,
+            clicks: 31,
+            keypresses: 420,
+            active_secs: 270,
+        }],
+    };
+    let response = app
+        .request(post("/activity/summary", summaries.encode_to_vec()))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Same validation as JSON bodies, and garbage is a 400
+    let mut invalid = summaries.clone();
+    invalid.summaries[0].active_secs = 301;
+    let response = app
+        .request(post("/activity/summary", invalid.encode_to_vec()))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app.request(post("/activity", vec![0xff, 0xff, 0xff])).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let stored: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT event_type, application FROM activities WHERE user_id = $1 ORDER BY timestamp",
+    )
+    .bind(user_id)
+    .fetch_all(&app.state.db)
+    .await
+    .unwrap();
+    assert_eq!(
+        stored,
+        vec![
+            ("ForegroundSwitch".to_string(), Some("Code".to_string())),
+            ("MouseClick".to_string(), None),
+        ]
+    );
+    let summary_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM activity_summaries WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&app.state.db)
+            .await
+            .unwrap();
+    assert_eq!(summary_count, 1);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn activity_accepts_compressed_bodies(pool: sqlx::PgPool) {
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("squeezed").await;
+    let token = app.api_token(user_id).await;
+
+    let post = |encoding: &str, body: Vec<u8>| {
+        Request::post("/activity/summary")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::CONTENT_ENCODING, encoding)
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let json = r#"[{"intervalId":5904576,"periodStart":"2026-02-20T08:00:00Z","periodEnd":"2026-02-20T08:05:00Z","topApp":"Code","appSwitches":4,"clicks":31,"keypresses":420,"activeSecs":270}]"#;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(json.as_bytes()).unwrap();
+    let response = app.request(post("gzip", encoder.finish().unwrap())).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = app.request(post("br", json.as_bytes().to_vec())).await;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let response = app.request(post("gzip", json.as_bytes().to_vec())).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let stored: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM activity_summaries WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&app.state.db)
+            .await
+            .unwrap();
+    assert_eq!(stored, 1);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn activity_credits_time_to_inferred_projects(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("projects").await;
+    let token = app.api_token(user_id).await;
+
+    let post = |body: &'static str| {
+        Request::post("/activity")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .request(post(
+            r#"[{"timestamp":"2026-03-02T09:00:00Z","intervalId":1,"event":{"type":"ForegroundSwitch","newActive":"Code","windowTitle":"main.rs — cleoapp"}},
+                {"timestamp":"2026-03-02T09:10:00Z","intervalId":1,"event":{"type":"ForegroundSwitch","newActive":"Slack","windowTitle":"general - Acme"}}]"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    // The Slack slice runs from the previous batch; the terminal slice is
+    // only credited once the next switch arrives
+    let response = app
+        .request(post(
+            r#"[{"timestamp":"2026-03-02T09:15:00Z","intervalId":1,"event":{"type":"ForegroundSwitch","newActive":"iTerm2","windowTitle":"ian@mbp: ~/code/CleoApp (launch-mode)"}},
+                {"timestamp":"2026-03-02T09:16:40Z","intervalId":1,"event":{"type":"ForegroundSwitch","newActive":"Safari","windowTitle":"Pricing · Stripe"}}]"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = app
+        .request(
+            Request::get("/me/projects")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let projects = body_json(response).await;
+    let projects = projects.as_array().unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0]["name"], "cleoapp");
+    assert_eq!(projects[0]["branch"], "launch-mode");
+    assert_eq!(projects[0]["active_secs"], 700);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn project_settings_scope_content_captures_and_publishing(pool: sqlx::PgPool) {
+    use crate::agent::fetch_captures_in_window;
+    use crate::domain::users::AgentDataPolicy;
+
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("multiproduct").await;
+    let token = app.api_token(user_id).await;
+
+    let response = app
+        .request(
+            Request::post("/activity")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    r#"[{"timestamp":"2026-03-02T09:00:00Z","intervalId":1,"event":{"type":"ForegroundSwitch","newActive":"Code","windowTitle":"main.rs — cleoapp"}},
+                        {"timestamp":"2026-03-02T09:10:00Z","intervalId":1,"event":{"type":"ForegroundSwitch","newActive":"Slack","windowTitle":"general - Acme"}}]"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let capture_ids: Vec<i64> = sqlx::query_scalar(
+        "INSERT INTO captures (interval_id, user_id, media_type, content_type, gcs_path, captured_at)
+         VALUES (1, $1, 'image', 'image/jpeg', 'image/editor.jpg', '2026-03-02T09:05:00Z'),
+                (1, $1, 'image', 'image/jpeg', 'image/slack.jpg', '2026-03-02T09:12:00Z')
+         RETURNING id",
+    )
+    .bind(user_id)
+    .fetch_all(&app.state.db)
+    .await
+    .unwrap();
+    let (editor_capture, slack_capture) = (
+        capture_ids[0].min(capture_ids[1]),
+        capture_ids[0].max(capture_ids[1]),
+    );
+
+    let get = |uri: &str| {
+        Request::get(uri)
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.request(get("/captures/browse?project=CleoApp")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["captures"][0]["id"], editor_capture);
+
+    let put = |name: &str, body: &'static str| {
+        Request::put(format!("/me/projects/{}", name))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+    let response = app.request(put("unknown", r#"{"hashtags":[]}"#)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app
+        .request(put("cleoapp", r##"{"hashtags":["#build in public"]}"##))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app
+        .request(put(
+            "cleoapp",
+            r##"{"hashtags":["#buildinpublic"],"twitter_username":"@cleo_hq","auto_generate":false}"##,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["hashtags"], serde_json::json!(["buildinpublic"]));
+    assert_eq!(body["twitter_username"], "cleo_hq");
+
+    // The agent no longer sees captures taken while cleoapp was up front
+    let window_start = "2026-03-02T09:00:00Z".parse().unwrap();
+    let window_end = "2026-03-02T10:00:00Z".parse().unwrap();
+    let captures = fetch_captures_in_window(
+        &app.state.db,
+        user_id,
+        window_start,
+        window_end,
+        &AgentDataPolicy::default(),
+    )
+    .await
+    .unwrap();
+    let seen: Vec<i64> = captures.iter().map(|c| c.id).collect();
+    assert_eq!(seen, vec![slack_capture]);
+
+    let tagged = app.create_tweet(user_id, "Shipped projects").await;
+    app.create_tweet(user_id, "Untagged").await;
+    sqlx::query("UPDATE tweet_collateral SET project = 'cleoapp' WHERE id = $1")
+        .bind(tagged)
+        .execute(&app.state.db)
+        .await
+        .unwrap();
+    let response = app
+        .request(get("/content?platform=twitter&project=cleoapp"))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["id"], tagged);
+    assert_eq!(body["items"][0]["project"], "cleoapp");
+
+    // Linked to @cleo_hq, but @multiproduct is connected
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish", tagged))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert!(app.social.posted.lock().unwrap().is_empty());
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn extension_tab_applies_privacy_rules(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("browsing").await;
+
+    let response = app
+        .request(
+            Request::post("/me/extension-tokens")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"name":"Chrome"}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created = body_json(response).await;
+    assert_eq!(created["scope"], "tab_context");
+    let token = created["token"].as_str().unwrap().to_string();
+
+    let response = app
+        .request(
+            Request::put("/me/blocked-domains")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"domains":["https://Bank.example/login"]}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(body_json(response).await["domains"][0], "bank.example");
+
+    let report = |token: &str, body: &'static str| {
+        Request::post("/extension/tab")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .request(report(
+            &token,
+            r#"{"url":"https://online.bank.example/accounts","title":"Accounts"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["stored"], false);
+
+    let response = app
+        .request(report(
+            &token,
+            r#"{"url":"https://github.com/org/repo/pull/42?token=abc","title":"PR","incognito":true}"#,
+        ))
+        .await;
+    assert_eq!(body_json(response).await["stored"], false);
+
+    let response = app
+        .request(report(
+            &token,
+            r#"{"url":"https://github.com/org/repo/pull/42?token=abc#diff","title":"Fix the parser"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // The daemon token isn't an extension token
+    let daemon_token = app.api_token(user_id).await;
+    let response = app
+        .request(report(&daemon_token, r#"{"url":"https://example.com/"}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let stored: Vec<(String, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT event_type, application, url FROM activities WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(&app.state.db)
+            .await
+            .unwrap();
+    assert_eq!(
+        stored,
+        vec![(
+            "TabContext".to_string(),
+            Some("github.com".to_string()),
+            Some("https://github.com/org/repo/pull/42".to_string()),
+        )]
+    );
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn audit_log_records_publish_for_the_acting_user(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("audited").await;
+    let other = app.create_user("bystander").await;
+    let tweet_id = app.create_tweet(user_id, "On the record").await;
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header("x-forwarded-for", "203.0.113.7")
+                .header(header::USER_AGENT, "test-browser/1.0")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let audit_log = |user| {
+        Request::get("/me/audit-log")
+            .header(header::COOKIE, app.session_cookie(user))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let body = body_json(app.request(audit_log(user_id)).await).await;
+    assert_eq!(body["total"], 1);
+    let entry = &body["entries"][0];
+    assert_eq!(entry["action"], "tweet.published");
+    assert_eq!(entry["actor"], "web");
+    assert_eq!(entry["target"], tweet_id.to_string());
+    assert_eq!(entry["detail"]["tweet_id"], "mock-tweet-1");
+    assert_eq!(entry["ip"], "203.0.113.7");
+    assert_eq!(entry["user_agent"], "test-browser/1.0");
+
+    let body = body_json(app.request(audit_log(other)).await).await;
+    assert_eq!(body["total"], 0);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn public_log_lists_posted_tweets_once_enabled(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("Shipper").await;
+    let posted = app.create_tweet(user_id, "Launched the beta").await;
+    app.create_tweet(user_id, "Still a draft").await;
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish", posted))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let public_log = || {
+        Request::get("/public/shipper/log")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // Private until the user opts in
+    let response = app.request(public_log()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .request(
+            Request::put("/me/public-log")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"enabled":true}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app.request(public_log()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["username"], "Shipper");
+    assert_eq!(body["entries"].as_array().unwrap().len(), 1);
+    let entry = &body["entries"][0];
+    assert_eq!(entry["type"], "tweet");
+    assert_eq!(entry["text"], "Launched the beta");
+    assert_eq!(entry["url"], "https://x.com/Shipper/status/mock-tweet-1");
+    assert!(body["next_before"].is_null());
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn capture_share_links_are_scoped_and_revocable(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("sharer").await;
+    let other = app.create_user("stranger").await;
+    let token = app.api_token(user_id).await;
+
+    let response = app
+        .request(capture_batch_request(
+            &token,
+            multipart_body(&[("image/png", b"screen", None)]),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let capture_id: i64 = sqlx::query_scalar("SELECT id FROM captures WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&app.state.db)
+        .await
+        .unwrap();
+
+    let share = |user, body: &'static str| {
+        Request::post(format!("/captures/{}/share", capture_id))
+            .header(header::COOKIE, app.session_cookie(user))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    // Only the owner can share, and only for up to a week
+    let response = app.request(share(other, "{}")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app
+        .request(share(user_id, r#"{"expires_in_hours":1000}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(share(user_id, r#"{"expires_in_hours":2}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created = body_json(response).await;
+    let share_id = created["id"].as_i64().unwrap();
+    let url = created["url"].as_str().unwrap().to_string();
+    assert!(url.starts_with("/shared/"));
+
+    let response = app
+        .request(
+            Request::delete(format!("/captures/{}/shares/{}", capture_id, share_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app
+        .request(Request::get(&url).body(Body::empty()).unwrap())
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .request(
+            Request::get(format!("/captures/{}/shares", capture_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let shares = body_json(response).await;
+    assert_eq!(shares[0]["id"], share_id);
+    assert!(!shares[0]["revoked_at"].is_null());
+    assert_eq!(shares[0]["view_count"], 0);
+}
+
+/// `unresolved_comments` of the newest item in the user's /content listing
+async fn first_item_unresolved_comments(app: &TestApp, user_id: i64) -> serde_json::Value {
+    let response = app
+        .request(
+            Request::get("/content?platform=twitter")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    body_json(response).await["items"][0]["unresolved_comments"].clone()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn comment_threads_count_until_resolved(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("reviewer").await;
+    let other = app.create_user("outsider").await;
+    let tweet_id = app.create_tweet(user_id, "Draft under review").await;
+
+    let comment = |user, body: String| {
+        Request::post(format!("/content/{}/comments", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .request(comment(other, r#"{"body":"Not mine"}"#.to_string()))
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .request(comment(
+            user_id,
+            r#"{"body":"@Editor is the hook strong enough?"}"#.to_string(),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let root = body_json(response).await;
+    assert_eq!(root["mentions"][0], "editor");
+    let root_id = root["id"].as_i64().unwrap();
+
+    let response = app
+        .request(comment(
+            user_id,
+            format!(r#"{{"body":"Tightened it","parent_id":{}}}"#, root_id),
+        ))
+        .await;
+    let reply_id = body_json(response).await["id"].as_i64().unwrap();
+
+    // A reply to a reply joins the top-level thread
+    let response = app
+        .request(comment(
+            user_id,
+            format!(r#"{{"body":"Looks good","parent_id":{}}}"#, reply_id),
+        ))
+        .await;
+    assert_eq!(body_json(response).await["parent_id"], root_id);
+
+    assert_eq!(first_item_unresolved_comments(&app, user_id).await, 1);
+
+    let resolve = |comment_id: i64| {
+        Request::patch(format!("/content/{}/comments/{}", tweet_id, comment_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"resolved":true}"#))
+            .unwrap()
+    };
+    // Only top-level comments can be resolved
+    let response = app.request(resolve(reply_id)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app.request(resolve(root_id)).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(first_item_unresolved_comments(&app, user_id).await, 0);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn summary_export_keeps_secrets_out_of_responses(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("notetaker").await;
+    let put = |body: &'static str| {
+        Request::put("/me/summary-export")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    // Private addresses aren't valid WebDAV targets
+    let response = app
+        .request(put(
+            r#"{"destination":"webdav","webdav_url":"https://10.0.0.5/vault"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(put(
+            r#"{"destination":"webdav","webdav_url":"https://dav.example.com/vault","webdav_username":"me","webdav_password":"hunter2"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["has_webdav_password"], true);
+    assert!(body.get("webdav_password").is_none());
+
+    // Leaving the password out keeps the stored one
+    let response = app
+        .request(put(
+            r#"{"destination":"webdav","webdav_url":"https://dav.example.com/notes","enabled":false}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["webdav_url"], "https://dav.example.com/notes");
+    assert_eq!(body["enabled"], false);
+    assert_eq!(body["has_webdav_password"], true);
+
+    // Switching to Notion needs a token of its own
+    let response = app
+        .request(put(
+            r#"{"destination":"notion","notion_parent_page_id":"0123456789abcdef0123456789abcdef"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(
+            Request::delete("/me/summary-export")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app
+        .request(
+            Request::get("/me/summary-export")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn hooks_poll_new_collateral_by_cursor(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("zapper").await;
+    let other = app.create_user("bystander").await;
+    let token = app.api_token(user_id).await;
+    let first = app.create_tweet(user_id, "First").await;
+    let second = app.create_tweet(user_id, "Second").await;
+    let third = app.create_tweet(user_id, "Third").await;
+    app.create_tweet(other, "Not yours").await;
+
+    let poll = |query: String| {
+        Request::get(format!("/hooks/new-collateral{}", query))
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let ids = |body: serde_json::Value| -> Vec<i64> {
+        body.as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_i64().unwrap())
+            .collect()
+    };
+
+    // Without a cursor: the latest tweets, newest first
+    let response = app.request(poll(String::new())).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body[0]["event"], "new_collateral");
+    assert_eq!(body[0]["text"], "Third");
+    assert_eq!(ids(body), vec![third, second, first]);
+
+    // With a cursor, the page after it is the oldest unseen tweets
+    let response = app.request(poll(format!("?since={}&limit=1", first))).await;
+    assert_eq!(ids(body_json(response).await), vec![second]);
+    let response = app.request(poll(format!("?since={}", third))).await;
+    assert!(ids(body_json(response).await).is_empty());
+
+    let subscribe = |target: &str| {
+        Request::post("/hooks/subscriptions")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({ "event": "new_collateral", "target_url": target }).to_string(),
+            ))
+            .unwrap()
+    };
+    let response = app.request(subscribe("http://127.0.0.1:8080/hook")).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(subscribe("https://hooks.zapier.com/hooks/standard/1/abc"))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let subscription_id = body_json(response).await["id"].as_i64().unwrap();
+
+    let response = app
+        .request(
+            Request::delete(format!("/hooks/subscriptions/{}", subscription_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn now_widget_shows_posted_tweets_once_enabled(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("Builder").await;
+    let posted = app.create_tweet(user_id, "Shipped offline mode").await;
+    app.create_tweet(user_id, "Secret draft").await;
+
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish", posted))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let widget = || {
+        Request::get("/public/builder/now.json")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.request(widget()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .request(
+            Request::put("/me/now-widget")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"enabled":true}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app.request(widget()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    let body = body_json(response).await;
+    assert_eq!(body["username"], "Builder");
+    assert_eq!(body["streak_days"], 1);
+    let posts = body["recent_posts"].as_array().unwrap();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["text"], "Shipped offline mode");
+    assert_eq!(posts[0]["url"], "https://x.com/Builder/status/mock-tweet-1");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn agent_data_policy_defaults_to_everything(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("Builder").await;
+
+    let get_policy = || {
+        Request::get("/me/agent-data-policy")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.request(get_policy()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(
+        body,
+        serde_json::json!({ "images": true, "video": true, "activity": true })
+    );
+
+    let response = app
+        .request(
+            Request::put("/me/agent-data-policy")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    r#"{"images":true,"video":false,"activity":true}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app.request(get_policy()).await;
+    let body = body_json(response).await;
+    assert_eq!(body["video"], false);
+    assert_eq!(body["images"], true);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn notification_preferences_mute_pairs_and_set_quiet_hours(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("Builder").await;
+
+    let put_notifications = |body: &'static str| {
+        Request::put("/me/notifications")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .request(
+            Request::get("/me/notifications")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["channels"]["push"]["content"], true);
+    assert_eq!(body["channels"]["slack"]["reconnect"], true);
+    assert!(body["quiet_hours"].is_null());
+
+    let response = app
+        .request(put_notifications(
+            r#"{"quiet_hours":{"start":"22:00","end":"22:00"}}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(put_notifications(
+            r#"{"channels":{"push":{"content":false}},"quiet_hours":{"start":"22:00","end":"07:30"}}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["channels"]["push"]["content"], false);
+    assert_eq!(body["channels"]["push"]["export"], true);
+    assert_eq!(body["channels"]["email"]["content"], true);
+    assert_eq!(
+        body["quiet_hours"],
+        serde_json::json!({ "start": "22:00", "end": "07:30" })
+    );
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn edited_copy_mentioning_competitor_needs_review(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("Builder").await;
+    let tweet_id = app.create_tweet(user_id, "Streaming exports shipped").await;
+
+    let response = app
+        .request(
+            Request::put("/me/copy-filter")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    r#"{"confidential_terms":[" Project  Falcon "],"competitor_terms":["Acme","acme"]}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(
+        body["confidential_terms"],
+        serde_json::json!(["Project Falcon"])
+    );
+    assert_eq!(body["competitor_terms"], serde_json::json!(["Acme"]));
+
+    let edit = |body: &'static str| {
+        Request::put(format!("/tweets/{}/collateral", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+    let review = || async {
+        sqlx::query_as::<_, (bool, Option<String>)>(
+            "SELECT needs_review, review_reason FROM tweet_collateral WHERE id = $1",
+        )
+        .bind(tweet_id)
+        .fetch_one(&app.state.db)
+        .await
+        .unwrap()
+    };
+
+    let response = app
+        .request(edit(r#"{"text":"Exports are 3x faster than Acme's"}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        review().await,
+        (true, Some("competitor mention: \"Acme\"".to_string()))
+    );
+
+    let response = app
+        .request(edit(r#"{"text":"Exports are 3x faster"}"#))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(review().await, (false, None));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn tracked_link_redirects_and_counts_clicks(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("Builder").await;
+    let tweet_id = app.create_tweet(user_id, "Release notes are up").await;
+
+    let create = |target: &str| {
+        Request::post(format!("/content/{}/links", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({ "target_url": target }).to_string(),
+            ))
+            .unwrap()
+    };
+    let response = app.request(create("http://127.0.0.1/admin")).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app
+        .request(create("https://github.com/acme/app/releases"))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let url = body_json(response).await["url"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let follow = |user_agent: &'static str| {
+        Request::get(url.as_str())
+            .header(header::USER_AGENT, user_agent)
+            .header(header::REFERER, "https://t.co/xyz")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.request(follow("Mozilla/5.0 (iPhone) Mobile")).await;
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(
+        response.headers()[header::LOCATION],
+        "https://github.com/acme/app/releases"
+    );
+    app.request(follow("Twitterbot/1.0")).await;
+
+    let response = app
+        .request(
+            Request::get(format!("/content/{}/clicks", tweet_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(
+        body["referrers"],
+        serde_json::json!([{ "referrer": "t.co", "clicks": 2 }])
+    );
+    assert_eq!(body["agents"].as_array().unwrap().len(), 2);
+    assert_eq!(body["daily"][0]["clicks"], 2);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn daemon_login_callback_issues_one_time_enrollment_code(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let get = |uri: &str| {
+        Request::get(uri)
+            .header("x-forwarded-for", "203.0.113.9")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.request(get("/auth/twitter/login?device=daemon")).await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        response.headers()[header::LOCATION],
+        "https://example.test/authorize"
+    );
+
+    let response = app
+        .request(get("/auth/twitter/callback?code=abc&state=state"))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key(header::SET_COOKIE));
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let page = String::from_utf8(bytes.to_vec()).unwrap();
+    let code = page
+        .split(r#"<p class="code">"#)
+        .nth(1)
+        .and_then(|rest| rest.split("</p>").next())
+        .expect("enrollment code on page")
+        .to_string();
+
+    let enroll = || {
+        Request::post("/auth/daemon/enroll")
+            .header("x-forwarded-for", "203.0.113.9")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({ "code": code.to_lowercase() }).to_string(),
+            ))
+            .unwrap()
+    };
+    let response = app.request(enroll()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let api_token = body_json(response).await["api_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let user_id = twitter::get_user_by_api_token(&app.state.db, &api_token)
+        .await
+        .unwrap();
+    assert!(user_id.is_some());
+
+    // The code works once, and a reused state is rejected
+    let response = app.request(enroll()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app
+        .request(get("/auth/twitter/callback?code=abc&state=state"))
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn github_login_links_x_as_an_integration(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let get = |uri: &str, cookie: Option<&str>| {
+        let mut req = Request::get(uri).header("x-forwarded-for", "203.0.113.9");
+        if let Some(cookie) = cookie {
+            req = req.header(header::COOKIE, cookie);
+        }
+        req.body(Body::empty()).unwrap()
+    };
+    let post_json = |uri: &str, body: serde_json::Value| {
+        Request::post(uri)
+            .header("x-forwarded-for", "203.0.113.9")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let body = body_json(app.request(get("/auth/providers", None)).await).await;
+    assert_eq!(
+        body["providers"],
+        serde_json::json!(["twitter", "github", "google", "email"])
+    );
+
+    let body = body_json(app.request(get("/auth/login/github", None)).await).await;
+    let url = body["url"].as_str().unwrap();
+    let state = url.split("state=").nth(1).unwrap().to_string();
+
+    let response = app
+        .request(post_json(
+            "/auth/login/github/token",
+            serde_json::json!({ "code": "octocat", "state": state }),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let cookie = response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .find(|v| v.starts_with("access_token="))
+        .and_then(|v| v.split(';').next())
+        .unwrap()
+        .to_string();
+    assert_eq!(body_json(response).await["username"], "octocat");
+
+    let body = body_json(app.request(get("/auth/me", Some(&cookie))).await).await;
+    let user_id = body["id"].as_i64().unwrap();
+    let body = body_json(app.request(get("/me", Some(&cookie))).await).await;
+    assert_eq!(body["username"], "octocat");
+    assert_eq!(body["twitter_connection"], "not_connected");
+
+    // Publishing needs X
+    let tweet_id = app.create_tweet(user_id, "Waiting on X").await;
+    let response = app
+        .request(
+            Request::post(format!("/tweets/{}/publish", tweet_id))
+                .header(header::COOKIE, &cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        body_json(response).await["error"],
+        "twitter_reauth_required"
+    );
+
+    // Linking X stores its tokens on this account rather than logging in as X
+    let response = app
+        .request(
+            Request::post("/me/integrations/twitter")
+                .header(header::COOKIE, &cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(
+        body_json(response).await["url"],
+        "https://example.test/authorize"
+    );
+    let response = app
+        .request(post_json(
+            "/auth/twitter/token",
+            serde_json::json!({ "code": "abc", "state": "state" }),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = body_json(app.request(get("/me", Some(&cookie))).await).await;
+    assert_eq!(body["id"], user_id);
+    assert_eq!(body["username"], "testuser");
+    assert_eq!(body["twitter_connection"], "connected");
+    let body = body_json(app.request(get("/me/integrations", Some(&cookie))).await).await;
+    assert_eq!(body["logins"][0]["provider"], "github");
+    assert_eq!(body["twitter"]["username"], "testuser");
+
+    let response = app
+        .request(
+            Request::delete("/me/integrations/twitter")
+                .header(header::COOKIE, &cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let body = body_json(app.request(get("/me", Some(&cookie))).await).await;
+    assert_eq!(body["username"], "octocat");
+    assert_eq!(body["twitter_connection"], "not_connected");
+
+    // X can't be unlinked from an account that logs in with it
+    let x_user = app.create_user("xonly").await;
+    let response = app
+        .request(
+            Request::delete("/me/integrations/twitter")
+                .header(header::COOKIE, app.session_cookie(x_user))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn email_link_login_merges_into_existing_x_account(pool: sqlx::PgPool) {
+    let app = &TestApp::new(pool);
+    let post_json = |uri: &str, cookie: Option<&str>, body: serde_json::Value| {
+        let mut req = Request::post(uri)
+            .header("x-forwarded-for", "203.0.113.10")
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(cookie) = cookie {
+            req = req.header(header::COOKIE, cookie);
+        }
+        req.body(Body::from(body.to_string())).unwrap()
+    };
+    let session_of = |response: &axum::response::Response| {
+        response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .find(|v| v.starts_with("access_token="))
+            .and_then(|v| v.split(';').next())
+            .unwrap()
+            .to_string()
+    };
+    let signed_in_as = move |cookie: String| async move {
+        let response = app
+            .request(
+                Request::get("/auth/me")
+                    .header("x-forwarded-for", "203.0.113.10")
+                    .header(header::COOKIE, cookie)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        body_json(response).await["id"].as_i64().unwrap()
+    };
+    let sign_in = move || async move {
+        let response = app
+            .request(post_json(
+                "/auth/email/link",
+                None,
+                serde_json::json!({ "email": " Ada@Example.com" }),
+            ))
+            .await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let token = app.email.last_token("ada@example.com").unwrap();
+        let response = app
+            .request(post_json(
+                "/auth/email/verify",
+                None,
+                serde_json::json!({ "token": token }),
+            ))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        (token, session_of(&response))
+    };
+
+    // First link creates an account; the link works once
+    let (token, cookie) = sign_in().await;
+    let email_user = signed_in_as(cookie.clone()).await;
+    let response = app
+        .request(post_json(
+            "/auth/email/verify",
+            None,
+            serde_json::json!({ "token": token }),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // The X account the mock client returns already has a Cleo account
+    let x_user = twitter::upsert_user(
+        &app.state.db,
+        "42",
+        "testuser",
+        Some("Test User"),
+        "twitter-access-token",
+        None,
+        Utc::now() + Duration::days(1),
+    )
+    .await
+    .unwrap();
+
+    // Connecting it folds the empty email account into the X one
+    app.request(post_json(
+        "/me/integrations/twitter",
+        Some(&cookie),
+        serde_json::json!({}),
+    ))
+    .await;
+    let response = app
+        .request(post_json(
+            "/auth/twitter/token",
+            None,
+            serde_json::json!({ "code": "abc", "state": "state" }),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(signed_in_as(session_of(&response)).await, x_user);
+    assert!(
+        twitter::get_user_by_id(&app.state.db, email_user)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // The address now signs in to the X account
+    let (_, cookie) = sign_in().await;
+    assert_eq!(signed_in_as(cookie).await, x_user);
+
+    // Three links per address per window
+    let link = || {
+        post_json(
+            "/auth/email/link",
+            None,
+            serde_json::json!({ "email": "ada@example.com" }),
+        )
+    };
+    assert_eq!(app.request(link()).await.status(), StatusCode::ACCEPTED);
+    assert_eq!(
+        app.request(link()).await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn x_link_refuses_to_merge_an_account_with_a_device(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let x_user = twitter::upsert_user(
+        &app.state.db,
+        "42",
+        "testuser",
+        Some("Test User"),
+        "owner-access-token",
+        None,
+        Utc::now() + Duration::days(1),
+    )
+    .await
+    .unwrap();
+    let user_id = app.create_user("enrolled").await;
+    let token = app.api_token(user_id).await;
+    let cookie = app.session_cookie(user_id);
+
+    let response = app
+        .request(
+            Request::post("/me/devices")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header("X-Device-ID", "work-mac")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let device_id = body_json(response).await["id"].as_i64().unwrap();
+
+    // Revoking clears the API token but the device stays on the account
+    let response = app
+        .request(
+            Request::delete(format!("/me/devices/{}", device_id))
+                .header(header::COOKIE, &cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let post_json = |uri: &str, cookie: Option<&str>| {
+        let mut req = Request::post(uri)
+            .header("x-forwarded-for", "203.0.113.10")
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(cookie) = cookie {
+            req = req.header(header::COOKIE, cookie);
+        }
+        req
+    };
+    app.request(
+        post_json("/me/integrations/twitter", Some(&cookie))
+            .body(Body::from("{}"))
+            .unwrap(),
+    )
+    .await;
+    let response = app
+        .request(
+            post_json("/auth/twitter/token", None)
+                .body(Body::from(
+                    serde_json::json!({ "code": "abc", "state": "state" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Neither account changed
+    assert!(
+        twitter::get_user_by_id(&app.state.db, user_id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    let owner_tokens = twitter::get_user_tokens(&app.state.db, x_user)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(owner_tokens.access_token, "owner-access-token");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn daemon_lists_pending_drafts_with_deep_links(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("menubar").await;
+    let token = app.api_token(user_id).await;
+    let older = app
+        .create_tweet(user_id, "Shipped the spool guard\n\nMore below")
+        .await;
+    let newer = app
+        .create_tweet(user_id, "\n  Fixed the resume marker")
+        .await;
+
+    let drafts = |query: &str| {
+        Request::get(format!("/me/drafts{}", query))
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.request(drafts("")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["total"], 2);
+    let listed: Vec<i64> = body["drafts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(listed, vec![newer, older]);
+    assert_eq!(body["drafts"][0]["first_line"], "Fixed the resume marker");
+    assert_eq!(body["drafts"][1]["first_line"], "Shipped the spool guard");
+    assert!(
+        body["drafts"][1]["url"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("/?view=queue&draft={}", older))
+    );
+
+    let response = app.request(drafts("?limit=1")).await;
+    let body = body_json(response).await;
+    assert_eq!(body["drafts"].as_array().unwrap().len(), 1);
+    assert_eq!(body["total"], 2);
+
+    let response = app
+        .request(Request::get("/me/drafts").body(Body::empty()).unwrap())
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn polled_endpoints_answer_unchanged_with_not_modified(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("poller").await;
+    let token = app.api_token(user_id).await;
+
+    let limits = |etag: Option<&str>| {
+        let mut request =
+            Request::get("/me/limits").header(header::AUTHORIZATION, format!("Bearer {}", token));
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        request.body(Body::empty()).unwrap()
+    };
+
+    let response = app.request(limits(None)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[header::ETAG]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(body_json(response).await["drafts_ready"], 0);
+
+    let response = app.request(limits(Some(&etag))).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::ETAG], etag.as_str());
+
+    // A new draft changes the body, so the old tag no longer matches
+    app.create_tweet(user_id, "Fresh draft").await;
+    let response = app.request(limits(Some(&etag))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[header::ETAG], etag.as_str());
+    assert_eq!(body_json(response).await["drafts_ready"], 1);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn revoked_device_loses_its_api_token(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("laptops").await;
+    let token = app.api_token(user_id).await;
+
+    let limits = |token: &str, device_uid: Option<&str>| {
+        let mut request =
+            Request::get("/me/limits").header(header::AUTHORIZATION, format!("Bearer {}", token));
+        if let Some(uid) = device_uid {
+            request = request.header("X-Device-ID", uid);
+        }
+        request.body(Body::empty()).unwrap()
+    };
+
+    let response = app
+        .request(
+            Request::post("/me/devices")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header("X-Device-ID", "stolen-mac")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let device_id = body_json(response).await["id"].as_i64().unwrap();
+
+    let response = app
+        .request(
+            Request::delete(format!("/me/devices/{}", device_id))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // Dropping the device header or making up a new device doesn't get around it
+    let response = app.request(limits(&token, None)).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = app.request(limits(&token, Some("fresh-mac"))).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // After signing in again the revoked machine stays locked out
+    let token = app.api_token(user_id).await;
+    let response = app.request(limits(&token, Some("stolen-mac"))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app.request(limits(&token, Some("work-mac"))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn heartbeat_records_reported_integrity(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("signed").await;
+    let token = app.api_token(user_id).await;
+
+    let heartbeat = |integrity: &str| {
+        Request::get("/me/limits")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header("X-Device-ID", "work-mac")
+            .header("X-Device-Integrity", integrity)
+            .body(Body::empty())
+            .unwrap()
+    };
+    let integrity = || async {
+        let response = app
+            .request(
+                Request::get("/me/devices")
+                    .header(header::COOKIE, app.session_cookie(user_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        body_json(response).await["devices"][0]["integrity"].clone()
+    };
+
+    let response = app.request(heartbeat("signed")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(integrity().await, "signed");
+
+    let response = app.request(heartbeat("notarized")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(integrity().await, "notarized");
+
+    // Unknown values don't overwrite the last report
+    let response = app.request(heartbeat("trust me")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(integrity().await, "notarized");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn daemon_token_can_publish_and_dismiss_drafts(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("palette").await;
+    let other_id = app.create_user("bystander").await;
+    let token = app.api_token(user_id).await;
+    let to_post = app.create_tweet(user_id, "Posted from the palette").await;
+    let to_dismiss = app.create_tweet(user_id, "Not this one").await;
+    let not_mine = app.create_tweet(other_id, "Someone else's draft").await;
+
+    let daemon = |method: &str, uri: String| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app
+        .request(daemon("POST", format!("/tweets/{}/publish", to_post)))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(app.social.posted.lock().unwrap().len(), 1);
+
+    let response = app
+        .request(daemon("DELETE", format!("/tweets/{}", to_dismiss)))
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app
+        .request(daemon("DELETE", format!("/tweets/{}", not_mine)))
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app.request(daemon("GET", "/me/drafts".to_string())).await;
+    assert_eq!(body_json(response).await["total"], 0);
+
+    let actor: String =
+        sqlx::query_scalar("SELECT actor FROM audit_log WHERE user_id = $1 AND target = $2")
+            .bind(user_id)
+            .bind(to_dismiss.to_string())
+            .fetch_one(&app.state.db)
+            .await
+            .unwrap();
+    assert_eq!(actor, "daemon");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn captures_used_by_drafts_are_counted_and_survive_archiving(pool: sqlx::PgPool) {
+    use crate::domain::archives;
+
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("reuser").await;
+    let captured_at = Utc::now() - Duration::days(60);
+    let mut capture_ids = Vec::new();
+    for path in ["image/used.png", "image/unused.png"] {
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO captures (interval_id, user_id, media_type, content_type, gcs_path, captured_at)
+             VALUES (1, $1, 'image', 'image/png', $2, $3) RETURNING id",
+        )
+        .bind(user_id)
+        .bind(path)
+        .bind(captured_at)
+        .fetch_one(&app.state.db)
+        .await
+        .unwrap();
+        capture_ids.push(id);
+    }
+    let (used, unused) = (capture_ids[0], capture_ids[1]);
+
+    // The same capture attached to two drafts, one of which gets posted
+    let draft = app.create_tweet(user_id, "first draft").await;
+    let posted = app.create_tweet(user_id, "posted").await;
+    sqlx::query("UPDATE tweet_collateral SET image_capture_ids = ARRAY[$1, $1] WHERE id = ANY($2)")
+        .bind(used)
+        .bind(vec![draft, posted])
+        .execute(&app.state.db)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE tweet_collateral SET posted_at = NOW() WHERE id = $1")
+        .bind(posted)
+        .execute(&app.state.db)
+        .await
+        .unwrap();
+
+    let get = |uri: String| {
+        Request::get(uri)
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app
+        .request(get(format!(
+            "/captures/browse?include_ids={},{}",
+            used, unused
+        )))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    let count = |id: i64| {
+        body["captures"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["id"] == id)
+            .unwrap()["used_in_drafts"]
+            .clone()
+    };
+    assert_eq!(count(used), 1);
+    assert_eq!(count(unused), 0);
+
+    let response = app.request(get(format!("/captures/{}/usage", used))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["used_in_drafts"], 1);
+    assert_eq!(body["used_in_posts"], 1);
+    assert_eq!(body["tweets"].as_array().unwrap().len(), 2);
+
+    let other = app.create_user("stranger").await;
+    let response = app
+        .request(
+            Request::get(format!("/captures/{}/usage", used))
+                .header(header::COOKIE, app.session_cookie(other))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let mut conn = app.state.db.acquire().await.unwrap();
+    let kept = archives::drop_month(
+        &mut conn,
+        captured_at - Duration::days(1),
+        captured_at + Duration::days(1),
+    )
+    .await
+    .unwrap();
+    assert_eq!(kept, [used]);
+    let remaining: Vec<i64> = sqlx::query_scalar("SELECT id FROM captures WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_all(&app.state.db)
+        .await
+        .unwrap();
+    assert_eq!(remaining, [used]);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn include_media_embeds_only_owned_captures(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("embedder").await;
+    let other = app.create_user("bystander").await;
+    let foreign: i64 = sqlx::query_scalar(
+        "INSERT INTO captures (interval_id, user_id, media_type, content_type, gcs_path, captured_at)
+         VALUES (1, $1, 'image', 'image/png', 'image/theirs.png', NOW()) RETURNING id",
+    )
+    .bind(other)
+    .fetch_one(&app.state.db)
+    .await
+    .unwrap();
+    let first = app.create_tweet(user_id, "1/ Media test").await;
+    let second = app.create_tweet(user_id, "2/ No media").await;
+    sqlx::query("UPDATE tweet_collateral SET image_capture_ids = ARRAY[$1] WHERE id = $2")
+        .bind(foreign)
+        .bind(first)
+        .execute(&app.state.db)
+        .await
+        .unwrap();
+
+    let response = app
+        .request(
+            Request::post("/threads")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "tweet_ids": [first, second] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let thread_id = body_json(response).await["id"].as_i64().unwrap();
+
+    let get = |uri: String| {
+        Request::get(uri)
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.request(get(format!("/threads/{}", thread_id))).await;
+    let body = body_json(response).await;
+    assert!(body["tweets"][0].get("media").is_none());
+
+    // Another user's capture id is never resolved
+    let response = app
+        .request(get(format!("/threads/{}?include=media", thread_id)))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["tweets"][0]["image_capture_ids"][0], foreign);
+    assert_eq!(body["tweets"][0]["media"], serde_json::json!([]));
+    assert_eq!(body["tweets"][1]["media"], serde_json::json!([]));
+
+    let response = app
+        .request(get("/content?platform=twitter&include=media".to_string()))
+        .await;
+    let body = body_json(response).await;
+    assert_eq!(body["items"][0]["type"], "thread");
+    assert_eq!(
+        body["items"][0]["tweets"][0]["media"],
+        serde_json::json!([])
+    );
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn content_filters_by_text_date_and_media(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("searcher").await;
+    let onboarding = app
+        .create_tweet(
+            user_id,
+            "Reworked the Onboarding flow: 3 steps instead of 7",
+        )
+        .await;
+    let percent = app.create_tweet(user_id, "Cut p99 latency by 40%").await;
+    let recent = app
+        .create_tweet(user_id, "Onboarding emails go out today")
+        .await;
+    sqlx::query(
+        "UPDATE tweet_collateral SET created_at = '2026-03-10T12:00:00Z', image_capture_ids = '{1}'
+         WHERE id = $1",
+    )
+    .bind(onboarding)
+    .execute(&app.state.db)
+    .await
+    .unwrap();
+
+    let ids = |body: serde_json::Value| -> Vec<i64> {
+        body["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_i64().unwrap())
+            .collect()
+    };
+    let get = |query: &str| {
+        Request::get(format!("/content?platform=twitter&{}", query))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.request(get("q=onboarding")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(ids(body), [recent, onboarding]);
+
+    // LIKE wildcards in the query match literally
+    let response = app.request(get("q=40%25")).await;
+    assert_eq!(ids(body_json(response).await), [percent]);
+    let response = app.request(get("q=%25%25")).await;
+    assert_eq!(body_json(response).await["total"], 0);
+
+    let response = app
+        .request(get("q=onboarding&from=2026-03-01&to=2026-03-31"))
+        .await;
+    assert_eq!(ids(body_json(response).await), [onboarding]);
+    let response = app.request(get("to=2026-03-10T11:59:59Z")).await;
+    assert_eq!(body_json(response).await["total"], 0);
+
+    let response = app.request(get("has_media=true")).await;
+    assert_eq!(ids(body_json(response).await), [onboarding]);
+    let response = app.request(get("has_media=false")).await;
+    assert_eq!(ids(body_json(response).await), [recent, percent]);
+
+    let response = app.request(get("from=last-month")).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn content_sorts_and_saved_views(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("curator").await;
+    let draft = app.create_tweet(user_id, "Still a draft").await;
+    let quiet = app.create_tweet(user_id, "Posted, few likes").await;
+    let hit = app.create_tweet(user_id, "Posted, went viral").await;
+    sqlx::query(
+        "UPDATE tweet_collateral SET posted_at = NOW() - make_interval(hours => $2) WHERE id = $1",
+    )
+    .bind(quiet)
+    .bind(1)
+    .execute(&app.state.db)
+    .await
+    .unwrap();
+    sqlx::query(
+        "UPDATE tweet_collateral SET posted_at = NOW() - make_interval(hours => $2) WHERE id = $1",
+    )
+    .bind(hit)
+    .bind(30)
+    .execute(&app.state.db)
+    .await
+    .unwrap();
+    for (tweet, likes) in [(quiet, 3_i64), (hit, 900)] {
+        sqlx::query(
+            "INSERT INTO copy_experiments
+                 (user_id, tweet_collateral_id, variant_a, style_a, variant_b, style_b,
+                  like_count, retweet_count, reply_count, quote_count, measured_at)
+             VALUES ($1, $2, 'a', 'plain', 'b', 'question hook', $3, 0, 0, 0, NOW())",
+        )
+        .bind(user_id)
+        .bind(tweet)
+        .bind(likes)
+        .execute(&app.state.db)
+        .await
+        .unwrap();
+    }
+
+    let cookie = app.session_cookie(user_id);
+    let ids = |body: serde_json::Value| -> Vec<i64> {
+        body["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_i64().unwrap())
+            .collect()
+    };
+    let get = |uri: String| {
+        Request::get(uri)
+            .header(header::COOKIE, cookie.clone())
+            .body(Body::empty())
+            .unwrap()
+    };
+    let list = |query: &str| get(format!("/content?platform=twitter&{}", query));
+
+    let response = app.request(list("sort=created_at")).await;
+    assert_eq!(ids(body_json(response).await), [hit, quiet, draft]);
+    let response = app.request(list("sort=posted_at")).await;
+    assert_eq!(ids(body_json(response).await), [quiet, hit, draft]);
+    let response = app.request(list("sort=engagement")).await;
+    assert_eq!(ids(body_json(response).await), [hit, quiet, draft]);
+    let response = app.request(list("sort=likes")).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let create = |body: serde_json::Value| {
+        Request::post("/me/views")
+            .header(header::COOKIE, cookie.clone())
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let response = app
+        .request(create(serde_json::json!({
+            "name": "Top performers",
+            "status": "posted",
+            "sort": "engagement",
+        })))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let view = body_json(response).await;
+    assert_eq!(view["status"], "posted");
+    assert_eq!(view["sort"], "engagement");
+    let view_id = view["id"].as_i64().unwrap();
+
+    let response = app
+        .request(create(serde_json::json!({ "name": "top PERFORMERS" })))
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = app
+        .request(create(
+            serde_json::json!({ "name": "Bad", "sort": "likes" }),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app.request(get("/me/views".to_string())).await;
+    let views = body_json(response).await;
+    assert_eq!(views.as_array().unwrap().len(), 1);
+    assert_eq!(views[0]["name"], "Top performers");
+
+    // The view's filter and sort apply, and query parameters override them
+    let response = app.request(list(&format!("view={}", view_id))).await;
+    assert_eq!(ids(body_json(response).await), [hit, quiet]);
+    let response = app
+        .request(list(&format!("view={}&sort=posted_at", view_id)))
+        .await;
+    assert_eq!(ids(body_json(response).await), [quiet, hit]);
+
+    let other = app.create_user("snoop").await;
+    let response = app
+        .request(
+            Request::get(format!("/content?platform=twitter&view={}", view_id))
+                .header(header::COOKIE, app.session_cookie(other))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .request(
+            Request::delete(format!("/me/views/{}", view_id))
+                .header(header::COOKIE, cookie.clone())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = app.request(list(&format!("view={}", view_id))).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn bulk_tweet_actions_report_each_id(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("bulker").await;
+    let other = app.create_user("bystander").await;
+    let first = app.create_tweet(user_id, "1/ Bulk").await;
+    let second = app.create_tweet(user_id, "2/ Bulk").await;
+    let loose = app.create_tweet(user_id, "Standalone").await;
+    let theirs = app.create_tweet(other, "Not yours").await;
+    sqlx::query(
+        "UPDATE tweet_collateral SET needs_review = TRUE, review_reason = 'x' WHERE id = ANY($1)",
+    )
+    .bind(vec![first, loose])
+    .execute(&app.state.db)
+    .await
+    .unwrap();
+
+    let bulk = |body: serde_json::Value| {
+        Request::post("/tweets/bulk")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let outcomes = |body: &serde_json::Value| -> Vec<(i64, String)> {
+        body["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| {
+                (
+                    r["id"].as_i64().unwrap(),
+                    r["outcome"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    };
+
+    let response = app
+        .request(bulk(serde_json::json!({
+            "action": "approve",
+            "tweet_ids": [first, theirs, first],
+        })))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(
+        outcomes(&body),
+        vec![(first, "ok".to_string()), (theirs, "not_found".to_string())]
+    );
+    assert_eq!(
+        (body["succeeded"].as_i64(), body["failed"].as_i64()),
+        (Some(1), Some(1))
+    );
+    let flagged: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM tweet_collateral WHERE user_id = $1 AND needs_review ORDER BY id",
+    )
+    .bind(user_id)
+    .fetch_all(&app.state.db)
+    .await
+    .unwrap();
+    assert_eq!(flagged, vec![loose]);
+
+    // attach_to_thread needs a thread_id
+    let response = app
+        .request(bulk(serde_json::json!({
+            "action": "attach_to_thread",
+            "tweet_ids": [second],
+        })))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(
+            Request::post("/threads")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "tweet_ids": [first] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let thread_id = body_json(response).await["id"].as_i64().unwrap();
+
+    let response = app
+        .request(bulk(serde_json::json!({
+            "action": "attach_to_thread",
+            "thread_id": thread_id,
+            "tweet_ids": [first, second, theirs],
+        })))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(
+        outcomes(&body),
+        vec![
+            (first, "ok".to_string()),
+            (second, "ok".to_string()),
+            (theirs, "not_found".to_string()),
+        ]
+    );
+    let positions: Vec<(i64, Option<i32>)> = sqlx::query_as(
+        "SELECT id, thread_position FROM tweet_collateral WHERE thread_id = $1 ORDER BY thread_position",
+    )
+    .bind(thread_id)
+    .fetch_all(&app.state.db)
+    .await
+    .unwrap();
+    assert_eq!(positions, vec![(first, Some(0)), (second, Some(1))]);
+
+    let response = app
+        .request(bulk(serde_json::json!({
+            "action": "dismiss",
+            "tweet_ids": [loose, theirs],
+        })))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(
+        outcomes(&body),
+        vec![(loose, "ok".to_string()), (theirs, "not_found".to_string())]
+    );
+    let dismissed: bool =
+        sqlx::query_scalar("SELECT dismissed_at IS NOT NULL FROM tweet_collateral WHERE id = $1")
+            .bind(loose)
+            .fetch_one(&app.state.db)
+            .await
+            .unwrap();
+    assert!(dismissed);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn thread_suggestions_group_related_drafts(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("suggester").await;
+    let second = app
+        .create_tweet(
+            user_id,
+            "2/ The CSV exporter streams rows instead of buffering",
+        )
+        .await;
+    let first = app
+        .create_tweet(user_id, "1/ Rewrote the CSV exporter so it streams rows")
+        .await;
+    app.create_tweet(user_id, "Lunch break, trying the new ramen place")
+        .await;
+
+    let suggest = || {
+        Request::post("/threads/suggest")
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.request(suggest()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(
+        body["suggestions"],
+        serde_json::json!([{
+            "tweet_ids": [first, second],
+            "score": body["suggestions"][0]["score"],
+            "signals": ["similar_text", "same_session"],
+        }])
+    );
+
+    // Accepting is a plain POST /threads with the suggested ids
+    let response = app
+        .request(
+            Request::post("/threads")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "tweet_ids": body["suggestions"][0]["tweet_ids"] })
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = body_json(app.request(suggest()).await).await;
+    assert_eq!(body["suggestions"], serde_json::json!([]));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn undo_window_queues_publish_until_it_passes(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("hesitant").await;
+    let tweet_id = app.create_tweet(user_id, "Shipping after a pause").await;
+
+    let response = app
+        .request(
+            Request::put("/me/publish-undo")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"undo_secs":601}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app
+        .request(
+            Request::put("/me/publish-undo")
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"undo_secs":60}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let publish = |method: &str| {
+        Request::builder()
+            .method(method)
+            .uri(format!("/tweets/{}/publish", tweet_id))
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let status = || async {
+        sqlx::query_scalar::<_, String>("SELECT publish_status FROM tweet_collateral WHERE id = $1")
+            .bind(tweet_id)
+            .fetch_one(&app.state.db)
+            .await
+            .unwrap()
+    };
+
+    let response = app.request(publish("POST")).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body = body_json(response).await;
+    assert_eq!(body["status"], "queued");
+    assert_eq!(body["cancel_url"], format!("/tweets/{}/publish", tweet_id));
+    assert_eq!(status().await, "queued");
+
+    // Queued already, so a second publish conflicts
+    let response = app.request(publish("POST")).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = app.request(publish("DELETE")).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(status().await, "pending");
+    let response = app.request(publish("DELETE")).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Not due yet: the queue leaves it alone
+    let response = app.request(publish("POST")).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    crate::routes::content::twitter::tweets::publish_due_tweets(&app.state).await;
+    assert!(app.social.posted.lock().unwrap().is_empty());
+
+    sqlx::query(
+        "UPDATE tweet_collateral SET publish_at = NOW() - INTERVAL '1 second' WHERE id = $1",
+    )
+    .bind(tweet_id)
+    .execute(&app.state.db)
+    .await
+    .unwrap();
+    let response = app.request(publish("DELETE")).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    crate::routes::content::twitter::tweets::publish_due_tweets(&app.state).await;
+    let posted = app.social.posted.lock().unwrap().clone();
+    assert_eq!(posted.len(), 1);
+    assert_eq!(posted[0].text, "Shipping after a pause");
+    assert_eq!(status().await, "posted");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL (TimescaleDB)"]
+async fn dismissed_tweets_keep_their_reason(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool);
+    let user_id = app.create_user("picky").await;
+    let kept = app.create_tweet(user_id, "Shipped streaming exports").await;
+    let vague = app.create_tweet(user_id, "Big things coming soon").await;
+    let silent = app.create_tweet(user_id, "Refactored some code").await;
+
+    let dismiss = |id: i64, body: Option<&'static str>| {
+        let request = Request::delete(format!("/tweets/{}", id))
+            .header(header::COOKIE, app.session_cookie(user_id));
+        match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap(),
+            None => request.body(Body::empty()).unwrap(),
+        }
+    };
+    let too_long = format!(r#"{{"reason":"{}"}}"#, "x".repeat(281));
+    let response = app
+        .request(
+            Request::delete(format!("/tweets/{}", vague))
+                .header(header::COOKIE, app.session_cookie(user_id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(too_long))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = app
+        .request(dismiss(
+            vague,
+            Some(r#"{"reason":"  too vague, say what shipped  "}"#),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = app.request(dismiss(silent, None)).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // Listings leave dismissed tweets out
+    let get = |uri: &str| {
+        Request::get(uri)
+            .header(header::COOKIE, app.session_cookie(user_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let body = body_json(app.request(get("/tweets")).await).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["tweets"][0]["id"], kept);
+
+    let response = app.request(get("/tweets/dismissed")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body["total"], 2);
+    let reasons: Vec<(i64, serde_json::Value)> = body["tweets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| (t["id"].as_i64().unwrap(), t["dismiss_reason"].clone()))
+        .collect();
+    assert!(reasons.contains(&(vague, serde_json::json!("too vague, say what shipped"))));
+    assert!(reasons.contains(&(silent, serde_json::Value::Null)));
+    assert_eq!(body["tweets"][0]["publish_status"], "dismissed");
+    assert!(body["tweets"][0]["dismissed_at"].is_string());
+
+    // Only reasoned dismissals are fed back to the agent
+    let feedback =
+        crate::domain::twitter::tweets::list_dismissal_feedback(&app.state.db, user_id, 30, 10)
+            .await
+            .unwrap();
+    assert_eq!(feedback.len(), 1);
+    assert_eq!(feedback[0].text, "Big things coming soon");
+    assert_eq!(feedback[0].reason, "too vague, say what shipped");
+}
@@ -0,0 +1,178 @@
+//! Admin Twitter credential rotation (/admin/twitter/credentials)
+//!
+//! See services::twitter_credentials for the rotation procedure.
+
+use axum::{
+    Json, Router,
+    extract::State,
+    routing::{get, post},
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::auth::AdminUser;
+use crate::AppState;
+use crate::services::error::{ApiError, LogErr};
+use crate::services::twitter;
+use crate::services::twitter_credentials::tagged_credential_id;
+
+const BATCH_SIZE: i64 = 500;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/admin/twitter/credentials", get(credential_status))
+        .route(
+            "/admin/twitter/credentials/rotate",
+            post(rotate_credentials),
+        )
+}
+
+#[derive(Serialize)]
+struct TokenCount {
+    /// None for refresh tokens stored before tagging
+    credential_id: Option<String>,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct CredentialStatusResponse {
+    current_id: String,
+    next_id: Option<String>,
+    refresh_tokens: Vec<TokenCount>,
+    /// Encrypted under a key that isn't configured
+    unreadable: usize,
+}
+
+/// GET /admin/twitter/credentials - Configured credentials and stored tokens per credential
+async fn credential_status(
+    State(state): State<Arc<AppState>>,
+    AdminUser(_user_id): AdminUser,
+) -> Result<Json<CredentialStatusResponse>, ApiError> {
+    let credentials = state
+        .twitter
+        .credentials()
+        .ok_or(ApiError::ServiceUnavailable("Twitter credentials"))?;
+
+    let mut counts: BTreeMap<Option<String>, usize> = BTreeMap::new();
+    let mut unreadable = 0;
+    let mut after_id = 0;
+    loop {
+        let batch = twitter::list_refresh_tokens(&state.db, after_id, BATCH_SIZE)
+            .await
+            .log_500("List refresh tokens error")?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        after_id = last.user_id;
+
+        for token in batch {
+            match token.refresh_token {
+                Ok(t) => {
+                    *counts
+                        .entry(tagged_credential_id(&t).map(str::to_string))
+                        .or_default() += 1
+                }
+                Err(_) => unreadable += 1,
+            }
+        }
+    }
+
+    Ok(Json(CredentialStatusResponse {
+        current_id: credentials.current.id.clone(),
+        next_id: credentials.next.as_ref().map(|c| c.id.clone()),
+        refresh_tokens: counts
+            .into_iter()
+            .map(|(credential_id, count)| TokenCount {
+                credential_id,
+                count,
+            })
+            .collect(),
+        unreadable,
+    }))
+}
+
+#[derive(Serialize, Default)]
+struct RotateResponse {
+    next_id: String,
+    /// Re-tagged and re-encrypted under the next credential
+    rotated: usize,
+    /// Already tagged with the next credential, or refreshed concurrently
+    skipped: usize,
+    /// Tagged with a credential, or encrypted with a key, that is no longer
+    /// configured; those users must reconnect
+    unreadable: usize,
+}
+
+/// POST /admin/twitter/credentials/rotate - Move stored refresh tokens to the next credential
+async fn rotate_credentials(
+    State(state): State<Arc<AppState>>,
+    AdminUser(user_id): AdminUser,
+) -> Result<Json<RotateResponse>, ApiError> {
+    let credentials = state
+        .twitter
+        .credentials()
+        .ok_or(ApiError::ServiceUnavailable("Twitter credentials"))?;
+    let next = credentials.next.as_ref().ok_or_else(|| {
+        ApiError::Conflict(
+            "No next credential configured (TWITTER_NEXT_CLIENT_ID / TWITTER_NEXT_CLIENT_SECRET)"
+                .to_string(),
+        )
+    })?;
+    // Refresh tokens belong to the app that issued them; only the secret can rotate
+    if next.client_id != credentials.current.client_id {
+        return Err(ApiError::Conflict(
+            "Next credential has a different client id; users must reconnect instead".to_string(),
+        ));
+    }
+
+    let mut response = RotateResponse {
+        next_id: next.id.clone(),
+        ..Default::default()
+    };
+    let mut after_id = 0;
+    loop {
+        let batch = twitter::list_refresh_tokens(&state.db, after_id, BATCH_SIZE)
+            .await
+            .log_500("List refresh tokens error")?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        after_id = last.user_id;
+
+        for token in batch {
+            let Ok(refresh_token) = &token.refresh_token else {
+                response.unreadable += 1;
+                continue;
+            };
+            if tagged_credential_id(refresh_token) == Some(next.id.as_str()) {
+                response.skipped += 1;
+                continue;
+            }
+            let Some(opened) = credentials.open(refresh_token) else {
+                response.unreadable += 1;
+                continue;
+            };
+            let replaced = twitter::replace_refresh_token(
+                &state.db,
+                token.user_id,
+                &token.stored,
+                &next.tag(&opened.refresh_token),
+            )
+            .await
+            .log_500("Replace refresh token error")?;
+            if replaced {
+                response.rotated += 1;
+            } else {
+                response.skipped += 1;
+            }
+        }
+    }
+
+    println!(
+        "[twitter] User {} rotated refresh tokens to credential {}: {} rotated, {} skipped, {} unreadable",
+        user_id, response.next_id, response.rotated, response.skipped, response.unreadable
+    );
+
+    Ok(Json(response))
+}
@@ -1,21 +1,49 @@
-//! Twitter OAuth endpoints (/auth/twitter/*)
+//! Twitter OAuth endpoints (/auth/twitter/*, /auth/daemon/enroll)
+//!
+//! Logins either finish in the SPA, which posts the code to
+//! POST /auth/twitter/token, or on the server at GET /auth/twitter/callback
+//! when TWITTER_REDIRECT_URI points there. Every login gets its own state and
+//! PKCE verifier, stored with the device that started it. A `device=daemon`
+//! login ends on a page showing a one-time enrollment code; the daemon trades
+//! it for its API token at POST /auth/daemon/enroll.
+//!
+//! The same flow links X to an account that signed in with GitHub, Google or
+//! email: the state then names that account (see POST /me/integrations/twitter),
+//! and finishing it stores the X tokens there instead of logging in as X. If
+//! that X account already has its own Cleo account, a new account with nothing
+//! in it yet is merged into that one (its logins move over) and the session
+//! switches to it.
 
 use axum::{
     Json, Router,
-    extract::State,
-    http::{StatusCode, header::SET_COOKIE},
-    response::{IntoResponse, Response},
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{Html, IntoResponse, Redirect, Response},
     routing::{get, post},
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tower_governor::{
     GovernorLayer, governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor,
 };
 
+use super::auth::attach_session;
 use crate::AppState;
-use crate::services::{cookies, session, twitter};
+use crate::config;
+use crate::domain::{enrollment_codes, login_identities};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::crypto::hash_token;
+use crate::services::error::{ApiError, LogErr};
+use crate::services::html::escape_html;
+use crate::services::twitter;
+
+/// How long an enrollment code can be redeemed
+const ENROLLMENT_CODE_TTL_MINS: i64 = 10;
+/// Characters in an enrollment code; no 0/O or 1/I to misread
+const ENROLLMENT_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const ENROLLMENT_CODE_LEN: usize = 8;
 
 pub fn routes() -> Router<Arc<AppState>> {
     // Rate limit: Stricter for OAuth - 5 requests per minute to prevent abuse
@@ -32,17 +60,44 @@ pub fn routes() -> Router<Arc<AppState>> {
 
     Router::new()
         .route("/auth/twitter", get(auth_twitter))
+        .route("/auth/twitter/login", get(auth_twitter_login))
         .route("/auth/twitter/token", post(auth_twitter_token))
+        .route("/auth/twitter/callback", get(auth_twitter_callback))
+        .route("/auth/daemon/enroll", post(redeem_enrollment_code))
         .layer(rate_limit_layer)
 }
 
-#[derive(Serialize)]
-struct AuthUrlResponse {
-    url: String,
+/// Device a login is for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum LoginDevice {
+    #[default]
+    Web,
+    Daemon,
 }
 
-/// GET /auth/twitter - Start OAuth flow, returns URL to redirect user to
-async fn auth_twitter(State(state): State<Arc<AppState>>) -> Json<AuthUrlResponse> {
+impl LoginDevice {
+    fn as_str(self) -> &'static str {
+        match self {
+            LoginDevice::Web => "web",
+            LoginDevice::Daemon => "daemon",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginQuery {
+    #[serde(default)]
+    device: LoginDevice,
+}
+
+/// Authorize URL for a new login, with its state and PKCE verifier saved.
+/// With `link_user_id`, finishing it links X to that account instead.
+pub(super) async fn start_login(
+    state: &AppState,
+    device: LoginDevice,
+    link_user_id: Option<i64>,
+) -> String {
     let auth_request = state.twitter.get_authorize_url(&[
         "tweet.read",
         "tweet.write",
@@ -52,19 +107,48 @@ async fn auth_twitter(State(state): State<Arc<AppState>>) -> Json<AuthUrlRespons
     ]);
 
     // Store state and code_verifier for callback
-    if let Err(e) =
-        twitter::save_oauth_state(&state.db, &auth_request.state, &auth_request.code_verifier).await
+    if let Err(e) = twitter::save_oauth_state(
+        &state.db,
+        &auth_request.state,
+        &auth_request.code_verifier,
+        device.as_str(),
+        link_user_id,
+    )
+    .await
     {
         eprintln!("Failed to save OAuth state: {}", e);
         // Return the URL anyway - login will fail at token exchange if state isn't found
         // This is better than blocking the user completely
     }
 
+    auth_request.url
+}
+
+#[derive(Serialize)]
+pub(super) struct AuthUrlResponse {
+    pub(super) url: String,
+}
+
+/// GET /auth/twitter - Start OAuth flow, returns URL to redirect user to
+async fn auth_twitter(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LoginQuery>,
+) -> Json<AuthUrlResponse> {
     Json(AuthUrlResponse {
-        url: auth_request.url,
+        url: start_login(&state, query.device, None).await,
     })
 }
 
+/// GET /auth/twitter/login - Start OAuth flow by redirecting to X
+/// (for links opened outside the SPA, e.g. by the daemon with `?device=daemon`)
+async fn auth_twitter_login(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LoginQuery>,
+) -> Response {
+    let url = start_login(&state, query.device, None).await;
+    ([(header::CACHE_CONTROL, "no-store")], Redirect::to(&url)).into_response()
+}
+
 #[derive(Deserialize)]
 struct TokenRequest {
     code: String,
@@ -76,25 +160,24 @@ struct LoginResponse {
     username: String,
 }
 
-/// POST /auth/twitter/token - Exchange OAuth code for session
-/// Sets httpOnly cookies for access_token (JWT) and refresh_token
-async fn auth_twitter_token(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<TokenRequest>,
-) -> Result<Response, StatusCode> {
-    // Retrieve and validate state
-    let code_verifier = twitter::get_oauth_state(&state.db, &req.state)
-        .await
-        .map_err(|e| {
-            eprintln!("Get OAuth state error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .ok_or(StatusCode::BAD_REQUEST)?;
+/// A finished login
+struct Login {
+    user_id: i64,
+    username: String,
+}
 
+/// Exchange an OAuth code for X tokens and store the user, or link them to
+/// `oauth_state.link_user_id`
+async fn complete_login(
+    state: &AppState,
+    ctx: &RequestContext,
+    code: &str,
+    oauth_state: &twitter::OAuthState,
+) -> Result<Login, StatusCode> {
     // Exchange code for tokens
     let token_response = state
         .twitter
-        .exchange_code(&req.code, &code_verifier)
+        .exchange_code(code, &oauth_state.code_verifier)
         .await
         .map_err(|e| {
             eprintln!("Token exchange error: {}", e);
@@ -111,6 +194,54 @@ async fn auth_twitter_token(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
+    // Calculate token expiry
+    let expires_at = Utc::now() + Duration::seconds(token_response.expires_in);
+
+    // Linking to an account that already passed the allowlist at login
+    if let Some(user_id) = oauth_state.link_user_id {
+        let linked = twitter::link_user(
+            &state.db,
+            user_id,
+            &twitter_user.id,
+            &twitter_user.username,
+            Some(&twitter_user.name),
+            &token_response.access_token,
+            token_response.refresh_token.as_deref(),
+            expires_at,
+        )
+        .await
+        .map_err(|e| {
+            eprintln!("Link user error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        if !linked {
+            return merge_into_twitter_account(
+                state,
+                ctx,
+                user_id,
+                &twitter_user,
+                &token_response,
+                expires_at,
+            )
+            .await;
+        }
+
+        audit::record(
+            &state.db,
+            ctx,
+            user_id,
+            Action::IntegrationConnected,
+            None,
+            Some(serde_json::json!({ "provider": "twitter", "username": twitter_user.username })),
+        )
+        .await;
+
+        return Ok(Login {
+            user_id,
+            username: twitter_user.username,
+        });
+    }
+
     // Check if user is allowed to log in (if allowlist is configured)
     if let Some(ref allowed) = state.allowed_users {
         if !allowed.contains(&twitter_user.username.to_lowercase()) {
@@ -122,9 +253,6 @@ async fn auth_twitter_token(
         }
     }
 
-    // Calculate token expiry
-    let expires_at = Utc::now() + Duration::seconds(token_response.expires_in);
-
     // Upsert user
     let user_id = twitter::upsert_user(
         &state.db,
@@ -141,31 +269,359 @@ async fn auth_twitter_token(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    // Create session tokens
-    let access_token = session::create_access_token(user_id, &state.jwt_secret).map_err(|e| {
-        eprintln!("Failed to create access token: {}", e);
+    audit::record(
+        &state.db,
+        ctx,
+        user_id,
+        Action::Login,
+        None,
+        Some(serde_json::json!({ "username": twitter_user.username })),
+    )
+    .await;
+
+    Ok(Login {
+        user_id,
+        username: twitter_user.username,
+    })
+}
+
+/// Linking found the X account on another Cleo account: fold the signed-in
+/// account into it if that loses nothing, otherwise refuse with CONFLICT.
+/// The owner's X tokens are only replaced once the merge has gone through.
+async fn merge_into_twitter_account(
+    state: &AppState,
+    ctx: &RequestContext,
+    user_id: i64,
+    twitter_user: &twitter::TwitterUser,
+    token_response: &twitter::TokenResponse,
+    expires_at: DateTime<Utc>,
+) -> Result<Login, StatusCode> {
+    let owner_id = twitter::find_user_by_twitter_id(&state.db, &twitter_user.id)
+        .await
+        .map_err(|e| {
+            eprintln!("Find X account owner error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        // Unlinked since link_user saw it; let the user retry
+        .ok_or(StatusCode::CONFLICT)?;
+
+    let merged = async {
+        let mut tx = state.db.begin().await?;
+        let merged = login_identities::merge_into(&mut tx, user_id, owner_id).await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(merged)
+    }
+    .await
+    .map_err(|e| {
+        eprintln!("Merge account error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if !merged {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    twitter::upsert_user(
+        &state.db,
+        &twitter_user.id,
+        &twitter_user.username,
+        Some(&twitter_user.name),
+        &token_response.access_token,
+        token_response.refresh_token.as_deref(),
+        expires_at,
+    )
+    .await
+    .map_err(|e| {
+        eprintln!("Upsert user error: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    let refresh_token = session::create_refresh_token(user_id, &state.db)
+    audit::record(
+        &state.db,
+        ctx,
+        owner_id,
+        Action::AccountMerged,
+        None,
+        Some(serde_json::json!({ "merged_user_id": user_id })),
+    )
+    .await;
+
+    Ok(Login {
+        user_id: owner_id,
+        username: twitter_user.username.clone(),
+    })
+}
+
+/// POST /auth/twitter/token - Exchange OAuth code for session
+/// Sets httpOnly cookies for access_token (JWT) and refresh_token
+async fn auth_twitter_token(
+    State(state): State<Arc<AppState>>,
+    ctx: RequestContext,
+    Json(req): Json<TokenRequest>,
+) -> Result<Response, StatusCode> {
+    // Retrieve and validate state
+    let oauth_state = twitter::get_oauth_state(&state.db, &req.state)
         .await
         .map_err(|e| {
-            eprintln!("Failed to create refresh token: {}", e);
+            eprintln!("Get OAuth state error: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        })?
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    let login = complete_login(&state, &ctx, &req.code, &oauth_state).await?;
 
     // Build response with cookies
-    let body = Json(LoginResponse {
-        username: twitter_user.username,
-    });
+    let mut response = Json(LoginResponse {
+        username: login.username,
+    })
+    .into_response();
+    attach_session(&state, login.user_id, &mut response).await?;
 
-    let mut response = body.into_response();
-    response
-        .headers_mut()
-        .append(SET_COOKIE, cookies::build_access_cookie(&access_token)?);
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    /// Set by X instead of `code` when the user declines or the request is bad
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// GET /auth/twitter/callback - Finish a login on the server (X redirects here)
+/// Sets the session cookies, then shows a success page; daemon logins also get
+/// a one-time enrollment code. Denials and failures get an error page.
+async fn auth_twitter_callback(
+    State(state): State<Arc<AppState>>,
+    ctx: RequestContext,
+    Query(query): Query<CallbackQuery>,
+) -> Response {
+    // A denied login still uses up its state
+    let oauth_state = match query.state.as_deref() {
+        Some(s) => twitter::get_oauth_state(&state.db, s).await,
+        None => Ok(None),
+    };
+
+    if let Some(error) = query.error.as_deref() {
+        return if error == "access_denied" {
+            callback_page(
+                StatusCode::OK,
+                "Login cancelled",
+                "<p>You didn't authorize Cleo on X, so nothing was connected.</p>",
+            )
+        } else {
+            let detail = query.error_description.as_deref().unwrap_or(error);
+            callback_page(
+                StatusCode::BAD_REQUEST,
+                "Login failed",
+                &format!("<p>X reported an error: {}</p>", escape_html(detail)),
+            )
+        };
+    }
+
+    let oauth_state = match oauth_state {
+        Ok(Some(oauth_state)) => oauth_state,
+        Ok(None) => {
+            return callback_page(
+                StatusCode::BAD_REQUEST,
+                "Login expired",
+                "<p>This login link has expired or was already used.</p>",
+            );
+        }
+        Err(e) => {
+            eprintln!("Get OAuth state error: {}", e);
+            return callback_error(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let Some(code) = query.code.as_deref() else {
+        return callback_page(
+            StatusCode::BAD_REQUEST,
+            "Login failed",
+            "<p>X didn't send an authorization code.</p>",
+        );
+    };
+
+    let login = match complete_login(&state, &ctx, code, &oauth_state).await {
+        Ok(login) => login,
+        Err(status) => return callback_error(status),
+    };
+
+    let username = escape_html(&login.username);
+    let mut response = if oauth_state.device == LoginDevice::Daemon.as_str() {
+        match issue_enrollment_code(&state, login.user_id).await {
+            Ok(code) => callback_page(
+                StatusCode::OK,
+                "Connect Cleo Recorder",
+                &format!(
+                    r#"<p>Logged in as @{username}. Enter this code in Cleo Recorder under <em>Set API Token…</em>:</p>
+<p class="code">{code}</p>
+<p>It works once and expires in {ENROLLMENT_CODE_TTL_MINS} minutes.</p>"#
+                ),
+            ),
+            Err(_) => return callback_error(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    } else if oauth_state.link_user_id.is_some() {
+        callback_page(
+            StatusCode::OK,
+            "X connected",
+            &format!(
+                r#"<p>@{username} is now connected to your Cleo account.</p>
+<p><a href="{}/">Continue to Cleo</a></p>"#,
+                escape_html(config::get().app_origin())
+            ),
+        )
+    } else {
+        callback_page(
+            StatusCode::OK,
+            "You're logged in",
+            &format!(
+                r#"<p>Logged in as @{username}.</p>
+<p><a href="{}/">Continue to Cleo</a></p>"#,
+                escape_html(config::get().app_origin())
+            ),
+        )
+    };
+
+    if let Err(status) = attach_session(&state, login.user_id, &mut response).await {
+        return callback_error(status);
+    }
     response
-        .headers_mut()
-        .append(SET_COOKIE, cookies::build_refresh_cookie(&refresh_token)?);
+}
 
-    Ok(response)
+/// Error page for a failed server-side login
+fn callback_error(status: StatusCode) -> Response {
+    let message = if status == StatusCode::FORBIDDEN {
+        "<p>This X account isn't allowed to use Cleo.</p>"
+    } else if status == StatusCode::CONFLICT {
+        "<p>This X account is already connected to another Cleo account, and this \
+         account already has captures or posts, so they can't be merged.</p>"
+    } else {
+        "<p>Something went wrong finishing your login. Please try again.</p>"
+    };
+    callback_page(status, "Login failed", message)
+}
+
+/// Standalone page with no scripts or external assets; `body` is trusted HTML
+fn callback_page(status: StatusCode, title: &str, body: &str) -> Response {
+    let html = format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title} - Cleo</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 32rem; margin: 4rem auto; padding: 0 1rem; color: #1a1a1a; text-align: center; }}
+.code {{ font-family: ui-monospace, monospace; font-size: 2rem; letter-spacing: 0.2em; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+{body}
+</body>
+</html>
+"#
+    );
+    (status, [(header::CACHE_CONTROL, "no-store")], Html(html)).into_response()
+}
+
+/// New enrollment code, e.g. "K7QM-2XWD"
+fn generate_enrollment_code() -> String {
+    let mut rng = rand::rng();
+    let chars: String = (0..ENROLLMENT_CODE_LEN)
+        .map(|_| {
+            ENROLLMENT_CODE_ALPHABET[rng.random_range(0..ENROLLMENT_CODE_ALPHABET.len())] as char
+        })
+        .collect();
+    format!("{}-{}", &chars[..4], &chars[4..])
+}
+
+/// Canonical form of a typed code: uppercase, separators and spaces dropped
+fn normalize_enrollment_code(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Create and store an enrollment code for the user; only its hash is kept
+async fn issue_enrollment_code(state: &AppState, user_id: i64) -> Result<String, ApiError> {
+    if let Err(e) = enrollment_codes::delete_expired(&state.db).await {
+        eprintln!("Delete expired enrollment codes error: {}", e);
+    }
+
+    let code = generate_enrollment_code();
+    let expires_at = Utc::now() + Duration::minutes(ENROLLMENT_CODE_TTL_MINS);
+    enrollment_codes::insert_code(
+        &state.db,
+        user_id,
+        &hash_token(&normalize_enrollment_code(&code)),
+        expires_at,
+    )
+    .await
+    .log_500("Insert enrollment code error")?;
+    Ok(code)
+}
+
+#[derive(Deserialize)]
+struct EnrollRequest {
+    code: String,
+}
+
+#[derive(Serialize)]
+struct EnrollResponse {
+    api_token: String,
+}
+
+/// POST /auth/daemon/enroll - Trade a one-time enrollment code for an API token
+/// Like POST /me/token, this replaces the user's previous API token.
+async fn redeem_enrollment_code(
+    State(state): State<Arc<AppState>>,
+    ctx: RequestContext,
+    Json(req): Json<EnrollRequest>,
+) -> Result<Json<EnrollResponse>, ApiError> {
+    let code_hash = hash_token(&normalize_enrollment_code(&req.code));
+    let user_id = enrollment_codes::redeem_code(&state.db, &code_hash)
+        .await
+        .log_500("Redeem enrollment code error")?
+        .ok_or(ApiError::NotFound("Enrollment code"))?;
+
+    let api_token = twitter::generate_api_token();
+    twitter::set_user_api_token(&state.db, user_id, &api_token)
+        .await
+        .log_500("Set user API token error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::ApiTokenGenerated,
+        None,
+        Some(serde_json::json!({ "via": "enrollment_code" })),
+    )
+    .await;
+
+    Ok(Json(EnrollResponse { api_token }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrollment_code_format() {
+        let code = generate_enrollment_code();
+        assert_eq!(code.len(), ENROLLMENT_CODE_LEN + 1);
+        assert_eq!(&code[4..5], "-");
+        assert!(
+            normalize_enrollment_code(&code)
+                .bytes()
+                .all(|b| ENROLLMENT_CODE_ALPHABET.contains(&b))
+        );
+    }
+
+    #[test]
+    fn test_normalize_enrollment_code() {
+        assert_eq!(normalize_enrollment_code(" k7qm-2xwd\n"), "K7QM2XWD");
+    }
 }
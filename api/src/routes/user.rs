@@ -1,20 +1,37 @@
-//! User info and limits endpoints (/me, /me/limits)
+//! User info, settings and limits endpoints (/me, /me/timezone, /me/language,
+//! /me/publish-undo, /me/branding, /me/agent-data-policy, /me/copy-filter,
+//! /me/notifications, /me/limits, /me/drafts)
 
 use axum::{
     Json, Router,
-    extract::State,
+    extract::{Query, State},
     http::{HeaderMap, StatusCode},
+    response::Response,
     routing::get,
 };
-use chrono::{DateTime, Utc};
-use serde::Serialize;
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use super::auth::AuthUser;
-use super::captures::get_user_id_from_bearer;
+use super::captures::get_daemon_identity;
 use crate::AppState;
-use crate::constants::BUCKET_NAME;
-use crate::services::twitter;
+use crate::config;
+use crate::domain::notification_preferences::NotificationPreferences;
+use crate::domain::twitter::tweets;
+use crate::domain::users::{AgentDataPolicy, CopyFilterTerms};
+use crate::domain::{content, media_library, notification_preferences, users};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::conditional;
+use crate::services::copy_filter::{self, MAX_TERM_CHARS, MAX_TERMS};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::language::{self, LANGUAGES, Language};
+use crate::services::launch_mode;
+use crate::services::media_studio::WatermarkPosition;
+use crate::services::notifications::{self, Channel, NotificationEvent};
+use crate::services::{timezone, twitter};
+use crate::storage::Buckets;
 
 /// User API response DTO
 #[derive(Debug, Serialize)]
@@ -23,6 +40,10 @@ pub struct UserResponse {
     pub username: String,
     pub display_name: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// "connected", "not_connected" for accounts without X (GitHub/Google
+    /// logins), or "needs_reconnect" once X has rejected the refresh token and
+    /// the user must log in again to publish
+    pub twitter_connection: &'static str,
     // twitter_id intentionally omitted - internal use only
 }
 
@@ -30,9 +51,16 @@ impl From<twitter::User> for UserResponse {
     fn from(u: twitter::User) -> Self {
         Self {
             id: u.id,
-            username: u.twitter_username,
-            display_name: u.twitter_name,
+            username: u.username,
+            display_name: u.display_name,
             created_at: u.created_at,
+            twitter_connection: if !u.twitter_connected {
+                "not_connected"
+            } else if u.needs_reconnect {
+                "needs_reconnect"
+            } else {
+                "connected"
+            },
         }
     }
 }
@@ -40,7 +68,27 @@ impl From<twitter::User> for UserResponse {
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/me", get(get_me))
+        .route("/me/timezone", get(get_timezone).put(update_timezone))
+        .route("/me/language", get(get_language).put(update_language))
+        .route(
+            "/me/publish-undo",
+            get(get_publish_undo).put(update_publish_undo),
+        )
+        .route("/me/branding", get(get_branding).put(update_branding))
+        .route(
+            "/me/agent-data-policy",
+            get(get_agent_data_policy).put(update_agent_data_policy),
+        )
+        .route(
+            "/me/copy-filter",
+            get(get_copy_filter).put(update_copy_filter),
+        )
+        .route(
+            "/me/notifications",
+            get(get_notifications).put(update_notifications),
+        )
         .route("/me/limits", get(get_limits))
+        .route("/me/drafts", get(list_drafts))
 }
 
 /// GET /me - Get current user info
@@ -61,6 +109,428 @@ async fn get_me(
     Ok(Json(UserResponse::from(user)))
 }
 
+#[derive(Serialize)]
+struct TimezoneResponse {
+    /// IANA zone name, e.g. "Europe/Berlin"
+    timezone: String,
+}
+
+#[derive(Deserialize)]
+struct UpdateTimezoneRequest {
+    timezone: String,
+}
+
+/// GET /me/timezone - The zone used for the user's day buckets
+async fn get_timezone(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<TimezoneResponse>, ApiError> {
+    let timezone = timezone::user_timezone(&state.db, user_id).await;
+    Ok(Json(TimezoneResponse {
+        timezone: timezone.name().to_string(),
+    }))
+}
+
+/// PUT /me/timezone - Set the zone used for the user's day buckets
+async fn update_timezone(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<UpdateTimezoneRequest>,
+) -> Result<Json<TimezoneResponse>, ApiError> {
+    let tz = timezone::parse(req.timezone.trim()).ok_or_else(|| {
+        ApiError::BadRequest(format!("Unknown time zone: {}", req.timezone.trim()))
+    })?;
+
+    users::set_user_timezone(&state.db, user_id, tz.name())
+        .await
+        .log_500("Set user timezone error")?;
+
+    Ok(Json(TimezoneResponse {
+        timezone: tz.name().to_string(),
+    }))
+}
+
+#[derive(Serialize)]
+struct LanguageOption {
+    code: &'static str,
+    name: &'static str,
+}
+
+impl From<&Language> for LanguageOption {
+    fn from(l: &Language) -> Self {
+        Self {
+            code: l.code,
+            name: l.name,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LanguageResponse {
+    /// Code of the language drafts are written in, e.g. "ja"
+    language: &'static str,
+    name: &'static str,
+    supported: Vec<LanguageOption>,
+}
+
+impl From<&'static Language> for LanguageResponse {
+    fn from(l: &'static Language) -> Self {
+        Self {
+            language: l.code,
+            name: l.name,
+            supported: LANGUAGES.iter().map(LanguageOption::from).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UpdateLanguageRequest {
+    language: String,
+}
+
+/// GET /me/language - The language the agent writes drafts in
+async fn get_language(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Json<LanguageResponse> {
+    Json(language::user_language(&state.db, user_id).await.into())
+}
+
+/// PUT /me/language - Set the language the agent writes drafts in. Applies from
+/// the next agent run; existing drafts can be translated one at a time.
+async fn update_language(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<UpdateLanguageRequest>,
+) -> Result<Json<LanguageResponse>, ApiError> {
+    let language = language::checked(&req.language)?;
+    users::set_draft_language(&state.db, user_id, language.code)
+        .await
+        .log_500("Set draft language error")?;
+    Ok(Json(language.into()))
+}
+
+/// Longest undo window a user can set
+const MAX_PUBLISH_UNDO_SECS: i32 = 300;
+
+/// How long POST /tweets/:id/publish waits before posting, so it can be cancelled
+#[derive(Serialize, Deserialize)]
+struct PublishUndoSettings {
+    /// 0 posts right away
+    undo_secs: i32,
+}
+
+/// GET /me/publish-undo - The user's undo window for publishing
+async fn get_publish_undo(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<PublishUndoSettings>, ApiError> {
+    let undo_secs = users::get_publish_undo_secs(&state.db, user_id)
+        .await
+        .log_500("Get publish undo window error")?;
+    Ok(Json(PublishUndoSettings { undo_secs }))
+}
+
+/// PUT /me/publish-undo - Set the undo window. Tweets already queued keep theirs.
+async fn update_publish_undo(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<PublishUndoSettings>,
+) -> Result<Json<PublishUndoSettings>, ApiError> {
+    if !(0..=MAX_PUBLISH_UNDO_SECS).contains(&req.undo_secs) {
+        return Err(ApiError::Unprocessable(format!(
+            "undo_secs must be 0-{}",
+            MAX_PUBLISH_UNDO_SECS
+        )));
+    }
+    users::set_publish_undo_secs(&state.db, user_id, req.undo_secs)
+        .await
+        .log_500("Set publish undo window error")?;
+    Ok(Json(req))
+}
+
+/// Branding watermark settings, applied to images and clips when they're published
+#[derive(Serialize, Deserialize)]
+struct BrandingSettings {
+    enabled: bool,
+    /// Media library image used as the logo
+    asset_id: Option<i64>,
+    position: WatermarkPosition,
+    /// 0.0 (exclusive) to 1.0
+    opacity: f32,
+}
+
+impl From<users::Branding> for BrandingSettings {
+    fn from(b: users::Branding) -> Self {
+        Self {
+            enabled: b.enabled,
+            asset_id: b.asset_id,
+            position: WatermarkPosition::parse(&b.position)
+                .unwrap_or(WatermarkPosition::BottomRight),
+            opacity: b.opacity,
+        }
+    }
+}
+
+/// GET /me/branding - The user's watermark settings
+async fn get_branding(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<BrandingSettings>, ApiError> {
+    let branding = users::get_branding(&state.db, user_id)
+        .await
+        .log_500("Get branding error")?
+        .ok_or(ApiError::Unauthorized)?;
+    Ok(Json(branding.into()))
+}
+
+/// PUT /me/branding - Replace the user's watermark settings
+async fn update_branding(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<BrandingSettings>,
+) -> Result<Json<BrandingSettings>, ApiError> {
+    if !(req.opacity > 0.0 && req.opacity <= 1.0) {
+        return Err(ApiError::Unprocessable(
+            "opacity must be greater than 0 and at most 1".to_string(),
+        ));
+    }
+    match req.asset_id {
+        Some(asset_id) => {
+            let asset = media_library::get_asset(&state.db, asset_id, user_id)
+                .await
+                .log_500("Get media library asset error")?
+                .ok_or(ApiError::NotFound("Media library asset"))?;
+            if asset.content_type == "image/gif" {
+                return Err(ApiError::Unprocessable(
+                    "Branding logos must be PNG, JPEG or WebP images".to_string(),
+                ));
+            }
+        }
+        None if req.enabled => {
+            return Err(ApiError::Unprocessable(
+                "Choose a logo before enabling branding".to_string(),
+            ));
+        }
+        None => {}
+    }
+
+    let branding = users::Branding {
+        enabled: req.enabled,
+        asset_id: req.asset_id,
+        position: req.position.as_str().to_string(),
+        opacity: req.opacity,
+    };
+    users::set_branding(&state.db, user_id, &branding)
+        .await
+        .log_500("Set branding error")?;
+
+    Ok(Json(branding.into()))
+}
+
+/// GET /me/agent-data-policy - Which kinds of data the agent may read
+async fn get_agent_data_policy(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<AgentDataPolicy>, ApiError> {
+    let policy = users::get_agent_data_policy(&state.db, user_id)
+        .await
+        .log_500("Get agent data policy error")?
+        .ok_or(ApiError::Unauthorized)?;
+    Ok(Json(policy))
+}
+
+/// PUT /me/agent-data-policy - Choose which kinds of data the agent may read.
+/// Applies from the next agent run; runs record the policy they used.
+async fn update_agent_data_policy(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    Json(req): Json<AgentDataPolicy>,
+) -> Result<Json<AgentDataPolicy>, ApiError> {
+    users::set_agent_data_policy(&state.db, user_id, &req)
+        .await
+        .log_500("Set agent data policy error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::AgentDataPolicyUpdated,
+        None,
+        Some(serde_json::json!(req)),
+    )
+    .await;
+
+    Ok(Json(req))
+}
+
+/// GET /me/copy-filter - Confidential codenames and competitor names that get
+/// generated tweets flagged for review
+async fn get_copy_filter(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<CopyFilterTerms>, ApiError> {
+    let terms = users::get_copy_filter_terms(&state.db, user_id)
+        .await
+        .log_500("Get copy filter terms error")?
+        .ok_or(ApiError::Unauthorized)?;
+    Ok(Json(terms))
+}
+
+/// Trim and dedupe a term list, rejecting oversized ones
+fn checked_terms(field: &str, terms: &[String]) -> Result<Vec<String>, ApiError> {
+    let terms = copy_filter::normalize_terms(terms);
+    if terms.len() > MAX_TERMS {
+        return Err(ApiError::Unprocessable(format!(
+            "at most {} {}",
+            MAX_TERMS, field
+        )));
+    }
+    if terms.iter().any(|t| t.chars().count() > MAX_TERM_CHARS) {
+        return Err(ApiError::Unprocessable(format!(
+            "{} must be at most {} characters each",
+            field, MAX_TERM_CHARS
+        )));
+    }
+    Ok(terms)
+}
+
+/// PUT /me/copy-filter - Replace both term lists. Applies to tweets generated
+/// or edited from now on.
+async fn update_copy_filter(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    Json(req): Json<CopyFilterTerms>,
+) -> Result<Json<CopyFilterTerms>, ApiError> {
+    let terms = CopyFilterTerms {
+        confidential_terms: checked_terms("confidential_terms", &req.confidential_terms)?,
+        competitor_terms: checked_terms("competitor_terms", &req.competitor_terms)?,
+    };
+    users::set_copy_filter_terms(&state.db, user_id, &terms)
+        .await
+        .log_500("Set copy filter terms error")?;
+    Ok(Json(terms))
+}
+
+/// Quiet hours as "HH:MM" local times in the user's zone; `end` before `start`
+/// wraps past midnight
+#[derive(Debug, Serialize, Deserialize)]
+struct QuietHours {
+    start: String,
+    end: String,
+}
+
+/// Notification preferences as the API shows them
+#[derive(Debug, Serialize, Deserialize)]
+struct NotificationSettings {
+    /// channel -> event type -> on/off. Every pair is listed on GET; pairs left
+    /// out of a PUT are on.
+    #[serde(default)]
+    channels: BTreeMap<Channel, BTreeMap<NotificationEvent, bool>>,
+    /// Nothing is sent in this window. None to always send.
+    quiet_hours: Option<QuietHours>,
+}
+
+impl From<NotificationPreferences> for NotificationSettings {
+    fn from(prefs: NotificationPreferences) -> Self {
+        let channels = Channel::ALL
+            .into_iter()
+            .map(|channel| {
+                let events = NotificationEvent::ALL
+                    .into_iter()
+                    .map(|event| (event, notifications::is_enabled(&prefs, channel, event)))
+                    .collect();
+                (channel, events)
+            })
+            .collect();
+        let quiet_hours = match (prefs.quiet_hours_start, prefs.quiet_hours_end) {
+            (Some(start), Some(end)) => Some(QuietHours {
+                start: start.format("%H:%M").to_string(),
+                end: end.format("%H:%M").to_string(),
+            }),
+            _ => None,
+        };
+        Self {
+            channels,
+            quiet_hours,
+        }
+    }
+}
+
+/// Parse an "HH:MM" quiet-hours bound
+fn parse_quiet_time(field: &str, value: &str) -> Result<NaiveTime, ApiError> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| ApiError::Unprocessable(format!("{} must be a time like \"22:00\"", field)))
+}
+
+/// GET /me/notifications - Which notifications the user gets, per channel and
+/// event type, and their quiet hours
+async fn get_notifications(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<NotificationSettings>, ApiError> {
+    let prefs = notification_preferences::get_preferences(&state.db, user_id)
+        .await
+        .log_500("Get notification preferences error")?
+        .unwrap_or_default();
+    Ok(Json(prefs.into()))
+}
+
+/// PUT /me/notifications - Replace the notification preferences. Every sender
+/// checks them from now on.
+async fn update_notifications(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    Json(req): Json<NotificationSettings>,
+) -> Result<Json<NotificationSettings>, ApiError> {
+    let muted = req
+        .channels
+        .iter()
+        .flat_map(|(channel, events)| {
+            events
+                .iter()
+                .filter(|(_, on)| !**on)
+                .map(|(event, _)| notifications::muted_key(*channel, *event))
+        })
+        .collect();
+    let (quiet_hours_start, quiet_hours_end) = match &req.quiet_hours {
+        Some(quiet) => {
+            let start = parse_quiet_time("quiet_hours.start", &quiet.start)?;
+            let end = parse_quiet_time("quiet_hours.end", &quiet.end)?;
+            if start == end {
+                return Err(ApiError::Unprocessable(
+                    "quiet_hours.start and quiet_hours.end must differ".to_string(),
+                ));
+            }
+            (Some(start), Some(end))
+        }
+        None => (None, None),
+    };
+    let prefs = NotificationPreferences {
+        muted,
+        quiet_hours_start,
+        quiet_hours_end,
+    };
+    notification_preferences::set_preferences(&state.db, user_id, &prefs)
+        .await
+        .log_500("Set notification preferences error")?;
+
+    let settings = NotificationSettings::from(prefs);
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::NotificationPreferencesUpdated,
+        None,
+        Some(serde_json::json!(settings)),
+    )
+    .await;
+
+    Ok(Json(settings))
+}
+
 #[derive(Serialize)]
 struct RecordingLimits {
     /// Maximum duration of a single recording in seconds
@@ -73,14 +543,25 @@ struct RecordingLimits {
     storage_limit_bytes: u64,
     /// Current storage used in bytes
     storage_used_bytes: u64,
+    /// When the user's launch mode ends; None when it's off
+    launch_mode_until: Option<DateTime<Utc>>,
+    /// Longest gap between screenshots the daemon should allow; None leaves
+    /// the daemon's own cadence alone
+    screenshot_interval_secs: Option<u64>,
+    /// Pending drafts (tweets, threads and long-form posts) waiting for
+    /// review; the daemon notifies when this goes up
+    drafts_ready: i64,
+    /// Web app the daemon's notifications open
+    app_url: String,
 }
 
 /// GET /me/limits - Get recording limits for the authenticated user (daemon auth)
 async fn get_limits(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-) -> Result<Json<RecordingLimits>, StatusCode> {
-    let user_id = get_user_id_from_bearer(&state.db, &headers).await?;
+) -> Result<Response, StatusCode> {
+    // Daemons poll this endpoint, so it doubles as the device last-seen heartbeat
+    let user_id = get_daemon_identity(&state.db, &headers).await?.user_id;
 
     // TODO: Look up user's subscription tier and return appropriate limits
     // For now, use default free tier limits
@@ -89,13 +570,118 @@ async fn get_limits(
     // Calculate storage usage from actual storage (local folder or GCS)
     let storage_used = calculate_user_storage(&state, user_id).await;
 
-    Ok(Json(RecordingLimits {
+    let launch_mode_until = users::get_launch_mode_until(&state.db, user_id)
+        .await
+        .map_err(|e| {
+            eprintln!("Get launch mode error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let drafts_ready = content::count_content(
+        &state.db,
+        user_id,
+        &content::ContentFilter {
+            status: Some("pending"),
+            ..Default::default()
+        },
+    )
+    .await
+    .map_err(|e| {
+        eprintln!("Count pending drafts error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let limits = RecordingLimits {
         max_recording_duration_secs: 5 * 60, // 5 minutes
         recording_budget_secs: 30 * 60,      // 30 minutes per hour
         inactivity_timeout_secs: 30,         // 30 seconds of inactivity
         storage_limit_bytes: storage_limit,
         storage_used_bytes: storage_used,
-    }))
+        launch_mode_until,
+        screenshot_interval_secs: launch_mode_until.map(|_| launch_mode::SCREENSHOT_INTERVAL_SECS),
+        drafts_ready,
+        app_url: config::get().app_origin().to_string(),
+    };
+    // Polled every minute, so unchanged limits come back as a bodiless 304
+    Ok(conditional::json_with_etag(&headers, &limits)?)
+}
+
+/// Most drafts /me/drafts returns; the daemon lists them in a menu
+const MAX_DRAFT_PREVIEWS: i64 = 20;
+const DEFAULT_DRAFT_PREVIEWS: i64 = 10;
+
+#[derive(Deserialize)]
+struct DraftsQuery {
+    limit: Option<i64>,
+}
+
+/// A pending tweet, trimmed down for the daemon's status menu
+#[derive(Serialize)]
+struct DraftPreview {
+    id: i64,
+    /// First non-empty line of the tweet text
+    first_line: String,
+    created_at: DateTime<Utc>,
+    /// Web review page opened at this draft
+    url: String,
+}
+
+#[derive(Serialize)]
+struct DraftsResponse {
+    drafts: Vec<DraftPreview>,
+    /// All pending standalone tweets, including those past `limit`
+    total: i64,
+    /// Review page for the whole queue
+    app_url: String,
+}
+
+fn first_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// GET /me/drafts - Newest pending tweets with deep links to review them (daemon auth)
+async fn list_drafts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<DraftsQuery>,
+) -> Result<Response, ApiError> {
+    let user_id = get_daemon_identity(&state.db, &headers).await?.user_id;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DRAFT_PREVIEWS)
+        .clamp(1, MAX_DRAFT_PREVIEWS);
+
+    let total = tweets::count_standalone_tweets(&state.db, user_id, Some("pending"))
+        .await
+        .log_500("Count drafts error")?;
+    let pending =
+        tweets::list_pending_tweets_paginated(&state.db, user_id, Some("pending"), limit, 0)
+            .await
+            .log_500("List drafts error")?;
+
+    let app_url = config::get().app_origin().to_string();
+    let drafts = pending
+        .into_iter()
+        .map(|tweet| DraftPreview {
+            id: tweet.id,
+            first_line: first_line(&tweet.text),
+            created_at: tweet.created_at,
+            url: format!("{}/?view=queue&draft={}", app_url, tweet.id),
+        })
+        .collect();
+
+    conditional::json_with_etag(
+        &headers,
+        &DraftsResponse {
+            drafts,
+            total,
+            app_url,
+        },
+    )
 }
 
 /// Calculate total storage used by a user from local folder or GCS
@@ -103,9 +689,12 @@ async fn calculate_user_storage(state: &AppState, user_id: i64) -> u64 {
     if let Some(local_path) = &state.local_storage_path {
         // Calculate from local filesystem
         calculate_local_storage(local_path, user_id).await
-    } else {
+    } else if state.gcs.is_some() {
         // Calculate from GCS - list objects with user prefix and sum sizes
-        calculate_gcs_storage(user_id).await
+        calculate_gcs_storage(&state.buckets, user_id).await
+    } else {
+        // No storage backend to measure (in-memory store in tests)
+        0
     }
 }
 
@@ -134,21 +723,30 @@ async fn calculate_local_storage(base_path: &std::path::Path, user_id: i64) -> u
     total
 }
 
-async fn calculate_gcs_storage(user_id: i64) -> u64 {
+async fn calculate_gcs_storage(buckets: &Buckets, user_id: i64) -> u64 {
     use futures::{StreamExt, pin_mut};
 
     // Use cloud-storage crate for listing (same one used for signed URLs)
     let client = cloud_storage::Client::default();
     let mut total: u64 = 0;
 
-    for media_type in ["image", "video"] {
+    // Media studio renders live under the same prefixes in the derived bucket
+    let mut bucket_names = vec![buckets.media.as_str()];
+    if buckets.derived != buckets.media {
+        bucket_names.push(buckets.derived.as_str());
+    }
+
+    for (bucket, media_type) in bucket_names
+        .into_iter()
+        .flat_map(|b| [(b, "image"), (b, "video")])
+    {
         let prefix = format!("{}/user_{}/", media_type, user_id);
         let request = cloud_storage::ListRequest {
             prefix: Some(prefix),
             ..Default::default()
         };
 
-        if let Ok(stream) = client.object().list(BUCKET_NAME, request).await {
+        if let Ok(stream) = client.object().list(bucket, request).await {
             pin_mut!(stream);
             while let Some(result) = stream.next().await {
                 if let Ok(object_list) = result {
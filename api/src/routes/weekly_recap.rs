@@ -0,0 +1,188 @@
+//! Weekly recap settings (/me/weekly-recap)
+//!
+//! The worker in services::weekly_recap drafts a "week in review" thread on
+//! the user's recap day; these endpoints pick the day and hour, show how the
+//! last recap went, and draft one on demand.
+
+use axum::{
+    Json, Router,
+    extract::State,
+    routing::{get, post},
+};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::auth::AuthUser;
+use crate::AppState;
+use crate::domain::weekly_recaps::{self, WeeklyRecap};
+use crate::services::audit::{self, Action, RequestContext};
+use crate::services::error::{ApiError, LogErr};
+use crate::services::timezone;
+use crate::services::weekly_recap::{
+    self as recap, DEFAULT_HOUR, DEFAULT_WEEKDAY, RECAP_DAYS, RecapError, WEEKDAYS,
+};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/me/weekly-recap",
+            get(get_weekly_recap).put(update_weekly_recap),
+        )
+        .route("/me/weekly-recap/compose", post(compose_weekly_recap))
+}
+
+/// Weekly recap API response DTO
+#[derive(Serialize)]
+struct WeeklyRecapResponse {
+    enabled: bool,
+    /// Lowercase weekday name, e.g. "friday"
+    day: &'static str,
+    /// Local hour (0-23) in the user's zone the recap is drafted from
+    hour: i16,
+    /// Local date of the last recap
+    last_recap_on: Option<NaiveDate>,
+    /// Thread drafted by the last recap; None if that week was empty
+    last_thread_id: Option<i64>,
+    last_error: Option<String>,
+}
+
+impl From<WeeklyRecap> for WeeklyRecapResponse {
+    fn from(r: WeeklyRecap) -> Self {
+        Self {
+            enabled: r.enabled,
+            day: recap::weekday_from_iso(r.weekday)
+                .map(recap::weekday_name)
+                .unwrap_or(recap::weekday_name(DEFAULT_WEEKDAY)),
+            hour: r.hour,
+            last_recap_on: r.last_recap_on,
+            last_thread_id: r.last_thread_id,
+            last_error: r.last_error,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct UpdateWeeklyRecapRequest {
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    /// Weekday name, e.g. "friday"
+    day: String,
+    /// Local hour, 0-23
+    hour: i16,
+}
+
+/// GET /me/weekly-recap - Recap schedule (Fridays at 17:00 unless changed) and the last recap
+async fn get_weekly_recap(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<WeeklyRecapResponse>, ApiError> {
+    let settings = weekly_recaps::get_recap(&state.db, user_id)
+        .await
+        .log_500("Get weekly recap error")?
+        .unwrap_or(WeeklyRecap {
+            enabled: true,
+            weekday: recap::iso_weekday(DEFAULT_WEEKDAY),
+            hour: DEFAULT_HOUR as i16,
+            last_recap_on: None,
+            last_thread_id: None,
+            last_error: None,
+        });
+    Ok(Json(settings.into()))
+}
+
+/// PUT /me/weekly-recap - Choose the day and local hour the recap is drafted, or switch it off
+async fn update_weekly_recap(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+    ctx: RequestContext,
+    Json(req): Json<UpdateWeeklyRecapRequest>,
+) -> Result<Json<WeeklyRecapResponse>, ApiError> {
+    let weekday = recap::weekday_from_name(&req.day).ok_or_else(|| {
+        ApiError::Unprocessable(format!("day must be one of {}", WEEKDAYS.join(", ")))
+    })?;
+    if !(0..=23).contains(&req.hour) {
+        return Err(ApiError::Unprocessable(
+            "hour must be between 0 and 23".to_string(),
+        ));
+    }
+
+    let saved = weekly_recaps::set_schedule(
+        &state.db,
+        user_id,
+        req.enabled,
+        recap::iso_weekday(weekday),
+        req.hour,
+    )
+    .await
+    .log_500("Save weekly recap error")?;
+
+    audit::record(
+        &state.db,
+        &ctx,
+        user_id,
+        Action::WeeklyRecapUpdated,
+        None,
+        Some(serde_json::json!({
+            "enabled": saved.enabled,
+            "day": recap::weekday_name(weekday),
+            "hour": saved.hour,
+        })),
+    )
+    .await;
+
+    Ok(Json(saved.into()))
+}
+
+#[derive(Serialize)]
+struct ComposedRecapResponse {
+    thread_id: i64,
+    title: String,
+    tweets: Vec<String>,
+}
+
+/// POST /me/weekly-recap/compose - Draft a recap of the last seven days now. It
+/// counts as this week's recap, so the scheduled one is skipped.
+async fn compose_weekly_recap(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<ComposedRecapResponse>, ApiError> {
+    let llm = state.llm.as_ref().ok_or_else(|| {
+        eprintln!("[weekly_recap] LLM provider not available");
+        ApiError::ServiceUnavailable("Weekly recaps")
+    })?;
+
+    let tz = timezone::user_timezone(&state.db, user_id).await;
+    let now = Utc::now();
+    let today = timezone::local_date(now, tz);
+    let start = now - Duration::days(RECAP_DAYS);
+    let composed = recap::compose_recap(&state.db, llm.as_ref(), user_id, today, start, now)
+        .await
+        .map_err(|e| match e {
+            RecapError::Db(e) => {
+                eprintln!("Compose weekly recap error: {}", e);
+                ApiError::Internal
+            }
+            RecapError::Llm(e) => {
+                eprintln!("[weekly_recap] LLM error for user {}: {}", user_id, e);
+                ApiError::Upstream("Weekly recap generation failed".to_string())
+            }
+        })?
+        .ok_or_else(|| {
+            ApiError::Unprocessable("nothing was drafted or posted in the last 7 days".to_string())
+        })?;
+
+    weekly_recaps::complete_recap(&state.db, user_id, today, Some(composed.thread_id))
+        .await
+        .log_500("Save weekly recap error")?;
+
+    Ok(Json(ComposedRecapResponse {
+        thread_id: composed.thread_id,
+        title: composed.title,
+        tweets: composed.tweets,
+    }))
+}
@@ -0,0 +1,25 @@
+//! Admin background worker status (/admin/workers)
+
+use axum::{Json, Router, routing::get};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::auth::AdminUser;
+use crate::AppState;
+use crate::services::supervisor::{self, WorkerStatus};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/admin/workers", get(list_workers))
+}
+
+#[derive(Serialize)]
+struct WorkersResponse {
+    workers: Vec<WorkerStatus>,
+}
+
+/// GET /admin/workers - Supervised workers with last heartbeat, restarts and last error
+async fn list_workers(AdminUser(_user_id): AdminUser) -> Json<WorkersResponse> {
+    Json(WorkersResponse {
+        workers: supervisor::snapshot(),
+    })
+}
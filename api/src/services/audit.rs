@@ -0,0 +1,210 @@
+//! Audit trail for security-relevant account actions
+//!
+//! Handlers take a `RequestContext` extractor and call `record` after the action
+//! succeeds. Recording is best-effort: a failed insert is logged, never surfaced
+//! to the caller. Users read their trail at GET /me/audit-log.
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{HeaderMap, header, request::Parts},
+};
+use sqlx::PgPool;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use crate::domain::audit_log::{self, NewAuditEntry};
+
+/// Longest user agent kept; browsers stay well under this
+const MAX_USER_AGENT_LEN: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Login,
+    ApiTokenGenerated,
+    TweetPublished,
+    ThreadPublished,
+    TweetDeleted,
+    TweetReplaced,
+    LongformPublished,
+    ThreadDeleted,
+    PersonaDeleted,
+    DeviceEnrolled,
+    DeviceRevoked,
+    ExtensionTokenCreated,
+    ExtensionTokenRevoked,
+    CaptureShared,
+    CaptureShareRevoked,
+    NudgesUpdated,
+    AgentDataPolicyUpdated,
+    SummaryExportUpdated,
+    SummaryExportRemoved,
+    NotificationPreferencesUpdated,
+    WeeklyRecapUpdated,
+    LaunchModeUpdated,
+    ProjectSettingsUpdated,
+    HookSubscribed,
+    HookUnsubscribed,
+    ImpersonationStarted,
+    AdminAgentRun,
+    IntegrationConnected,
+    IntegrationDisconnected,
+    AccountMerged,
+}
+
+impl Action {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Login => "login",
+            Action::ApiTokenGenerated => "api_token.generated",
+            Action::TweetPublished => "tweet.published",
+            Action::ThreadPublished => "thread.published",
+            Action::TweetDeleted => "tweet.deleted",
+            Action::TweetReplaced => "tweet.replaced",
+            Action::LongformPublished => "longform.published",
+            Action::ThreadDeleted => "thread.deleted",
+            Action::PersonaDeleted => "persona.deleted",
+            Action::DeviceEnrolled => "device.enrolled",
+            Action::DeviceRevoked => "device.revoked",
+            Action::ExtensionTokenCreated => "extension_token.created",
+            Action::ExtensionTokenRevoked => "extension_token.revoked",
+            Action::CaptureShared => "capture.shared",
+            Action::CaptureShareRevoked => "capture.share_revoked",
+            Action::NudgesUpdated => "settings.nudges_updated",
+            Action::AgentDataPolicyUpdated => "settings.agent_data_policy_updated",
+            Action::SummaryExportUpdated => "settings.summary_export_updated",
+            Action::SummaryExportRemoved => "settings.summary_export_removed",
+            Action::NotificationPreferencesUpdated => "settings.notifications_updated",
+            Action::WeeklyRecapUpdated => "settings.weekly_recap_updated",
+            Action::LaunchModeUpdated => "settings.launch_mode_updated",
+            Action::ProjectSettingsUpdated => "settings.project_updated",
+            Action::HookSubscribed => "hook.subscribed",
+            Action::HookUnsubscribed => "hook.unsubscribed",
+            Action::ImpersonationStarted => "admin.impersonation_started",
+            Action::AdminAgentRun => "admin.agent_run",
+            Action::IntegrationConnected => "integration.connected",
+            Action::IntegrationDisconnected => "integration.disconnected",
+            Action::AccountMerged => "account.merged",
+        }
+    }
+}
+
+/// Who performed a request, and from where
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    /// "daemon" for API token (Bearer) requests, "admin" when an admin acts on
+    /// the user's behalf, "scheduler" for queued publishes, otherwise "web"
+    pub actor: &'static str,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl RequestContext {
+    pub fn from_parts(headers: &HeaderMap, peer: Option<SocketAddr>) -> Self {
+        let header_str = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+
+        // Same precedence as SmartIpKeyExtractor: the proxy-supplied client address first
+        let ip = header_str("x-forwarded-for")
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+            .or_else(|| header_str("x-real-ip"))
+            .map(str::to_string)
+            .or_else(|| peer.map(|addr| addr.ip().to_string()));
+
+        let user_agent = header_str(header::USER_AGENT.as_str())
+            .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect());
+
+        let actor = if header_str(header::AUTHORIZATION.as_str())
+            .is_some_and(|v| v.starts_with("Bearer "))
+        {
+            "daemon"
+        } else {
+            "web"
+        };
+
+        Self {
+            actor,
+            ip,
+            user_agent,
+        }
+    }
+
+    /// Mark the request as an admin acting on another user's account
+    pub fn into_admin(self) -> Self {
+        Self {
+            actor: "admin",
+            ..self
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestContext {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr);
+        Ok(Self::from_parts(&parts.headers, peer))
+    }
+}
+
+/// Record an action on a user's account. Failures are logged and swallowed.
+pub async fn record(
+    db: &PgPool,
+    ctx: &RequestContext,
+    user_id: i64,
+    action: Action,
+    target: Option<&str>,
+    detail: Option<serde_json::Value>,
+) {
+    let entry = NewAuditEntry {
+        user_id,
+        actor: ctx.actor,
+        action: action.as_str(),
+        target,
+        detail,
+        ip: ctx.ip.as_deref(),
+        user_agent: ctx.user_agent.as_deref(),
+    };
+
+    if let Err(e) = audit_log::insert_entry(db, entry).await {
+        eprintln!(
+            "[audit] Failed to record {} for user {}: {}",
+            action.as_str(),
+            user_id,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_context_from_headers() {
+        let peer: SocketAddr = "10.0.0.5:4000".parse().unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "203.0.113.7, 10.0.0.1".parse().unwrap());
+        headers.insert(header::USER_AGENT, "cleo-daemon/1.2".parse().unwrap());
+        headers.insert(header::AUTHORIZATION, "Bearer abc".parse().unwrap());
+        let ctx = RequestContext::from_parts(&headers, Some(peer));
+        assert_eq!(ctx.actor, "daemon");
+        assert_eq!(ctx.ip.as_deref(), Some("203.0.113.7"));
+        assert_eq!(ctx.user_agent.as_deref(), Some("cleo-daemon/1.2"));
+
+        // No proxy headers: fall back to the socket peer
+        let ctx = RequestContext::from_parts(&HeaderMap::new(), Some(peer));
+        assert_eq!(ctx.actor, "web");
+        assert_eq!(ctx.ip.as_deref(), Some("10.0.0.5"));
+        assert_eq!(ctx.user_agent, None);
+    }
+}
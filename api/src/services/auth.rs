@@ -1,37 +1,46 @@
 //! Authentication helpers for token refresh
+//!
+//! When X refuses a refresh token for good (invalid_grant, e.g. the user
+//! revoked app access) the user is flagged `needs_reconnect`, which GET /me
+//! reports, and gets one push nudging them to log in again. Storing new
+//! tokens clears the flag.
 
-use axum::http::StatusCode;
 use chrono::{Duration, Utc};
 use sqlx::PgPool;
 
-use super::twitter::{self, TwitterClient, UserTokens};
+use super::error::ApiError;
+use super::push;
+use super::social::SocialClient;
+use super::twitter::{self, TwitterError, UserTokens};
 
 /// Ensures the access token is valid, refreshing if expired.
-/// Returns the valid access token or a StatusCode error.
+/// Fails with `TwitterReauthRequired` when the token can't be refreshed.
 pub async fn ensure_valid_access_token(
     db: &PgPool,
-    twitter_client: &TwitterClient,
+    twitter_client: &dyn SocialClient,
     user_id: i64,
     tokens: UserTokens,
-) -> Result<String, StatusCode> {
+) -> Result<String, ApiError> {
     // Token still valid
     if tokens.token_expires_at >= Utc::now() {
         return Ok(tokens.access_token);
     }
 
     // Need to refresh
-    let refresh_token = tokens.refresh_token.ok_or_else(|| {
+    let Some(refresh_token) = tokens.refresh_token else {
         eprintln!("Token expired and no refresh token for user {}", user_id);
-        StatusCode::UNAUTHORIZED
-    })?;
+        request_reconnect(db, user_id).await;
+        return Err(ApiError::TwitterReauthRequired);
+    };
 
-    let new_tokens = twitter_client
-        .refresh_token(&refresh_token)
-        .await
-        .map_err(|e| {
+    let new_tokens = match twitter_client.refresh_token(&refresh_token).await {
+        Ok(new_tokens) => new_tokens,
+        Err(e) => {
             eprintln!("Token refresh error: {}", e);
-            StatusCode::UNAUTHORIZED
-        })?;
+            refresh_failed(db, user_id, &e).await;
+            return Err(ApiError::TwitterReauthRequired);
+        }
+    };
 
     let expires_at = Utc::now() + Duration::seconds(new_tokens.expires_in);
     twitter::update_user_tokens(
@@ -44,7 +53,7 @@ pub async fn ensure_valid_access_token(
     .await
     .map_err(|e| {
         eprintln!("Update user tokens error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
+        ApiError::Internal
     })?;
 
     Ok(new_tokens.access_token)
@@ -53,7 +62,7 @@ pub async fn ensure_valid_access_token(
 /// Variant that returns String errors (for WebSocket handlers).
 pub async fn ensure_valid_access_token_str(
     db: &PgPool,
-    twitter_client: &TwitterClient,
+    twitter_client: &dyn SocialClient,
     user_id: i64,
     tokens: UserTokens,
 ) -> Result<String, String> {
@@ -63,14 +72,18 @@ pub async fn ensure_valid_access_token_str(
     }
 
     // Need to refresh
-    let refresh_token = tokens
-        .refresh_token
-        .ok_or("Token expired and no refresh token")?;
+    let Some(refresh_token) = tokens.refresh_token else {
+        request_reconnect(db, user_id).await;
+        return Err("Token expired and no refresh token".to_string());
+    };
 
-    let new_tokens = twitter_client
-        .refresh_token(&refresh_token)
-        .await
-        .map_err(|e| format!("Token refresh failed: {}", e))?;
+    let new_tokens = match twitter_client.refresh_token(&refresh_token).await {
+        Ok(new_tokens) => new_tokens,
+        Err(e) => {
+            refresh_failed(db, user_id, &e).await;
+            return Err(format!("Token refresh failed: {}", e));
+        }
+    };
 
     let expires_at = Utc::now() + Duration::seconds(new_tokens.expires_in);
     twitter::update_user_tokens(
@@ -85,3 +98,33 @@ pub async fn ensure_valid_access_token_str(
 
     Ok(new_tokens.access_token)
 }
+
+/// Only invalid_grant means the connection is gone; network errors and X
+/// outages are worth retrying later
+async fn refresh_failed(db: &PgPool, user_id: i64, error: &TwitterError) {
+    if error.is_invalid_grant() {
+        request_reconnect(db, user_id).await;
+    }
+}
+
+/// Flag the user as needing to reconnect and, the first time, push a nudge
+async fn request_reconnect(db: &PgPool, user_id: i64) {
+    match twitter::mark_needs_reconnect(db, user_id).await {
+        Ok(true) => {
+            let db = db.clone();
+            tokio::spawn(async move {
+                if let Err(e) = push::notify_reconnect_needed(&db, user_id).await {
+                    eprintln!(
+                        "[auth] Failed to send reconnect notification for user {}: {}",
+                        user_id, e
+                    );
+                }
+            });
+        }
+        Ok(false) => {}
+        Err(e) => eprintln!(
+            "[auth] Mark needs_reconnect error for user {}: {}",
+            user_id, e
+        ),
+    }
+}
@@ -0,0 +1,75 @@
+//! Conditional GET for JSON endpoints the daemon polls (/me/limits, /me/drafts)
+//!
+//! The ETag is a hash of the serialized body, so it changes exactly when the
+//! response does. The handler still does its work; a matching If-None-Match
+//! saves the transfer and the client's parse, which is what makes frequent
+//! polling cheap for daemons on slow links.
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, StatusCode, header},
+    response::Response,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::error::{ApiError, LogErr};
+use super::range;
+
+/// `value` as JSON with a content ETag, or an empty 304 if the client's
+/// If-None-Match already has it
+pub fn json_with_etag<T: Serialize>(headers: &HeaderMap, value: &T) -> Result<Response, ApiError> {
+    let body = serde_json::to_vec(value).log_500("Serialize response error")?;
+    let etag = content_etag(&body);
+    let response = Response::builder()
+        .header(header::ETAG, &etag)
+        // Cacheable, but always revalidated
+        .header(header::CACHE_CONTROL, "private, no-cache");
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| range::etag_matches(v, &etag));
+    if not_modified {
+        return Ok(response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .log_500("Build response error")?);
+    }
+
+    Ok(response
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .log_500("Build response error")?)
+}
+
+/// Strong ETag from the first 128 bits of the body's SHA-256
+fn content_etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("\"{}\"", hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn etag_follows_the_body() {
+        let tag = content_etag(br#"{"drafts_ready":2}"#);
+        assert_eq!(tag.len(), 34);
+        assert_eq!(tag, content_etag(br#"{"drafts_ready":2}"#));
+        assert_ne!(tag, content_etag(br#"{"drafts_ready":3}"#));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, tag.parse().unwrap());
+        let value = serde_json::json!({"drafts_ready": 2});
+        let response = json_with_etag(&headers, &value).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let value = serde_json::json!({"drafts_ready": 3});
+        let response = json_with_etag(&headers, &value).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], tag.as_str());
+    }
+}
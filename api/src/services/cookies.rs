@@ -11,6 +11,8 @@ pub mod config {
     pub const ACCESS_TOKEN_NAME: &str = "access_token";
     /// Refresh token cookie name
     pub const REFRESH_TOKEN_NAME: &str = "refresh_token";
+    /// CSRF token cookie name (readable by JS, see services::csrf)
+    pub const CSRF_TOKEN_NAME: &str = "csrf_token";
     /// Access token max-age in seconds (10 minutes)
     pub const ACCESS_TOKEN_MAX_AGE_SECS: u32 = 600;
     /// Refresh token max-age in seconds (30 days)
@@ -23,13 +25,19 @@ pub mod config {
     pub const REFRESH_COOKIE_PATH: &str = "/";
 }
 
-fn is_dev() -> bool {
-    std::env::var("ENV").as_deref() != Ok("prod")
+/// Secure flag: COOKIE_SECURE=true/false overrides the default (on unless in dev)
+fn cookie_secure() -> bool {
+    crate::config::get().cookie_secure()
+}
+
+fn secure_attr() -> &'static str {
+    if cookie_secure() { " Secure;" } else { "" }
 }
 
 fn cookie_same_site() -> &'static str {
-    match std::env::var("COOKIE_SAMESITE")
-        .unwrap_or_else(|_| "Lax".to_string())
+    match crate::config::get()
+        .server
+        .cookie_samesite
         .to_lowercase()
         .as_str()
     {
@@ -43,7 +51,7 @@ fn cookie_same_site() -> &'static str {
 /// Build an access token Set-Cookie header value
 pub fn build_access_cookie(token: &str) -> Result<HeaderValue, StatusCode> {
     let same_site = cookie_same_site();
-    let secure = if is_dev() { "" } else { " Secure;" };
+    let secure = secure_attr();
     let cookie = format!(
         "{}={}; HttpOnly;{} SameSite={}; Path={}; Max-Age={}",
         config::ACCESS_TOKEN_NAME,
@@ -62,7 +70,7 @@ pub fn build_access_cookie(token: &str) -> Result<HeaderValue, StatusCode> {
 /// Build a refresh token Set-Cookie header value
 pub fn build_refresh_cookie(token: &str) -> Result<HeaderValue, StatusCode> {
     let same_site = cookie_same_site();
-    let secure = if is_dev() { "" } else { " Secure;" };
+    let secure = secure_attr();
     let cookie = format!(
         "{}={}; HttpOnly;{} SameSite={}; Path={}; Max-Age={}",
         config::REFRESH_TOKEN_NAME,
@@ -78,6 +86,24 @@ pub fn build_refresh_cookie(token: &str) -> Result<HeaderValue, StatusCode> {
     })
 }
 
+/// Build a CSRF token Set-Cookie header value. Not HttpOnly: the web client
+/// reads it and echoes it in the X-CSRF-Token header. Lives as long as the
+/// refresh token so every refresh replaces it.
+pub fn build_csrf_cookie(token: &str) -> Result<HeaderValue, StatusCode> {
+    let cookie = format!(
+        "{}={};{} SameSite={}; Path=/; Max-Age={}",
+        config::CSRF_TOKEN_NAME,
+        token,
+        secure_attr(),
+        cookie_same_site(),
+        config::REFRESH_TOKEN_MAX_AGE_SECS
+    );
+    cookie.parse().map_err(|_| {
+        eprintln!("Failed to parse CSRF cookie header");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
 /// Build a Set-Cookie header to clear the access token
 pub fn build_clear_access_cookie() -> HeaderValue {
     format!(
@@ -99,3 +125,13 @@ pub fn build_clear_refresh_cookie() -> HeaderValue {
     .parse()
     .expect("static cookie string should always parse")
 }
+
+/// Build a Set-Cookie header to clear the CSRF token
+pub fn build_clear_csrf_cookie() -> HeaderValue {
+    format!(
+        "{}=; Secure; SameSite=Lax; Path=/; Max-Age=0",
+        config::CSRF_TOKEN_NAME
+    )
+    .parse()
+    .expect("static cookie string should always parse")
+}
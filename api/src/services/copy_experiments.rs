@@ -0,0 +1,185 @@
+//! A/B copy experiments
+//!
+//! A draft can carry two copy variants, each labelled with the style it tries
+//! (PUT /tweets/:id/experiment). Publishing posts variant A. The metrics sync
+//! reads the post's public metrics once it's 24 hours old, and
+//! GET /insights/experiments ranks styles by their average engagement (likes,
+//! replies, reposts and quotes) so the user can fold the winner into their
+//! style nudges. Only posted variants are measured; put a style in A on some
+//! drafts and in B on others to compare them.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use super::auth::ensure_valid_access_token_str;
+use super::social::SocialClient;
+use super::twitter;
+use crate::domain::copy_experiments::{self, CopyExperiment, DueExperiment, Engagement};
+
+pub const SYNC_INTERVAL_SECS: u64 = 10 * 60;
+/// How long a claimed experiment is held by one worker
+const LEASE_SECS: i64 = 120;
+/// Wait before retrying an experiment whose metrics couldn't be read
+const RETRY_SECS: i64 = 60 * 60;
+const BATCH_SIZE: i64 = 20;
+/// Measured experiments a style needs before it can be called the winner
+pub const MIN_EXPERIMENTS_PER_STYLE: usize = 3;
+
+/// Measure posted experiments as they turn 24 hours old
+pub async fn run_metrics_worker(pool: PgPool, social: Arc<dyn SocialClient>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(SYNC_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+        super::supervisor::heartbeat("experiments");
+
+        let due = match copy_experiments::claim_due(&pool, LEASE_SECS, BATCH_SIZE).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("[experiments] Claim error: {}", e);
+                continue;
+            }
+        };
+        for experiment in due {
+            let result = match fetch_engagement(&pool, social.as_ref(), &experiment).await {
+                Ok(engagement) => {
+                    copy_experiments::record_engagement(&pool, experiment.id, engagement).await
+                }
+                Err(error) => {
+                    eprintln!(
+                        "[experiments] Experiment {} for user {}: {}",
+                        experiment.id, experiment.user_id, error
+                    );
+                    copy_experiments::record_failure(&pool, experiment.id, &error, RETRY_SECS).await
+                }
+            };
+            if let Err(e) = result {
+                eprintln!("[experiments] Save error for {}: {}", experiment.id, e);
+            }
+        }
+    }
+}
+
+async fn fetch_engagement(
+    pool: &PgPool,
+    social: &dyn SocialClient,
+    experiment: &DueExperiment,
+) -> Result<Engagement, String> {
+    let tokens = twitter::get_user_tokens(pool, experiment.user_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("X is not connected")?;
+    let access_token =
+        ensure_valid_access_token_str(pool, social, experiment.user_id, tokens).await?;
+    let tweet = social
+        .get_tweet(&access_token, &experiment.tweet_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let metrics = tweet.public_metrics.ok_or("X returned no public metrics")?;
+    Ok(Engagement {
+        like_count: metrics.like_count,
+        retweet_count: metrics.retweet_count,
+        reply_count: metrics.reply_count,
+        quote_count: metrics.quote_count,
+    })
+}
+
+/// Likes, replies, reposts and quotes of a measured experiment
+pub fn engagement(experiment: &CopyExperiment) -> i64 {
+    [
+        experiment.like_count,
+        experiment.retweet_count,
+        experiment.reply_count,
+        experiment.quote_count,
+    ]
+    .into_iter()
+    .flatten()
+    .sum()
+}
+
+/// How one style did across the experiments that posted it
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleStats {
+    pub style: String,
+    pub experiments: usize,
+    pub avg_engagement: f64,
+}
+
+/// Posted styles of measured experiments, best average engagement first
+pub fn style_stats(experiments: &[CopyExperiment]) -> Vec<StyleStats> {
+    let mut by_style: BTreeMap<&str, (usize, i64)> = BTreeMap::new();
+    for experiment in experiments.iter().filter(|e| e.measured_at.is_some()) {
+        let entry = by_style.entry(experiment.style_a.as_str()).or_default();
+        entry.0 += 1;
+        entry.1 += engagement(experiment);
+    }
+    let mut stats: Vec<StyleStats> = by_style
+        .into_iter()
+        .map(|(style, (count, total))| StyleStats {
+            style: style.to_string(),
+            experiments: count,
+            avg_engagement: total as f64 / count as f64,
+        })
+        .collect();
+    stats.sort_by(|a, b| b.avg_engagement.total_cmp(&a.avg_engagement));
+    stats
+}
+
+/// The style ahead of every other one, once at least two styles have
+/// MIN_EXPERIMENTS_PER_STYLE measurements. None while there's too little data
+/// or the top two are tied.
+pub fn winner(stats: &[StyleStats]) -> Option<&StyleStats> {
+    let mut sampled = stats
+        .iter()
+        .filter(|s| s.experiments >= MIN_EXPERIMENTS_PER_STYLE);
+    let best = sampled.next()?;
+    let runner_up = sampled.next()?;
+    (best.avg_engagement > runner_up.avg_engagement).then_some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn measured(style: &str, likes: i64) -> CopyExperiment {
+        CopyExperiment {
+            tweet_collateral_id: 0,
+            variant_a: String::new(),
+            style_a: style.to_string(),
+            variant_b: String::new(),
+            style_b: String::new(),
+            measured_at: Some(Utc::now()),
+            like_count: Some(likes),
+            retweet_count: Some(1),
+            reply_count: Some(0),
+            quote_count: Some(0),
+            last_error: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_style_stats_rank_posted_styles() {
+        let mut experiments = vec![
+            measured("question hook", 9),
+            measured("question hook", 5),
+            measured("result first", 2),
+        ];
+        let stats = style_stats(&experiments);
+        assert_eq!(stats[0].style, "question hook");
+        assert_eq!(stats[0].experiments, 2);
+        assert_eq!(stats[0].avg_engagement, 8.0);
+        assert_eq!(stats[1].avg_engagement, 3.0);
+        // Two measurements aren't enough to call it
+        assert!(winner(&stats).is_none());
+
+        experiments.push(measured("question hook", 3));
+        experiments.push(measured("result first", 4));
+        experiments.push(measured("result first", 1));
+        let stats = style_stats(&experiments);
+        assert_eq!(winner(&stats).unwrap().style, "question hook");
+    }
+}
@@ -0,0 +1,209 @@
+//! Compliance filter for generated tweet copy
+//!
+//! Generated text (the tweet and its copy options) is scanned for profanity,
+//! slurs, the user's confidential project codenames and competitor names
+//! (GET/PUT /me/copy-filter) before it is saved. Matching tweets are still
+//! saved, flagged `needs_review` with the reason attached, so nothing is lost
+//! and nothing slips out unnoticed. Terms match whole words, case-insensitively;
+//! multi-word terms match as a phrase. Operators can turn the built-in word
+//! lists off or extend them (agent.copy_filter / AGENT_COPY_FILTER,
+//! agent.extra_blocked_terms / AGENT_EXTRA_BLOCKED_TERMS).
+
+use sqlx::PgPool;
+
+use crate::config;
+use crate::domain::users;
+
+/// Most confidential or competitor terms per user, per list
+pub const MAX_TERMS: usize = 100;
+/// Longest single term, in characters
+pub const MAX_TERM_CHARS: usize = 100;
+
+const PROFANITY: &[&str] = &[
+    "fuck",
+    "fucks",
+    "fucked",
+    "fucker",
+    "fuckers",
+    "fucking",
+    "motherfucker",
+    "shit",
+    "shits",
+    "shitty",
+    "bullshit",
+    "bitch",
+    "bitches",
+    "asshole",
+    "assholes",
+    "bastard",
+    "bastards",
+    "cunt",
+    "cunts",
+    "dickhead",
+    "piss",
+    "pissed",
+    "wanker",
+    "twat",
+    "prick",
+];
+
+const SLURS: &[&str] = &[
+    "retard", "retarded", "retards", "faggot", "faggots", "fag", "fags", "tranny", "trannies",
+    "nigger", "niggers", "nigga", "spic", "spics", "chink", "chinks", "kike", "kikes", "wetback",
+    "gook", "gooks", "dyke", "dykes",
+];
+
+/// Lowercase words of `text`, joined by single spaces and padded with one on
+/// each side so a phrase can be matched with `contains(" term ")`
+fn word_line(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+/// Trimmed, deduplicated terms with blanks removed
+pub fn normalize_terms(terms: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for term in terms {
+        let term = term.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !term.is_empty() && !out.iter().any(|t| t.eq_ignore_ascii_case(&term)) {
+            out.push(term);
+        }
+    }
+    out
+}
+
+/// One category of terms
+struct TermList {
+    label: &'static str,
+    /// (as configured, normalized word line)
+    terms: Vec<(String, String)>,
+}
+
+impl TermList {
+    fn new<S: AsRef<str>>(label: &'static str, terms: &[S]) -> Self {
+        Self {
+            label,
+            terms: terms
+                .iter()
+                .map(|t| (t.as_ref().to_string(), word_line(t.as_ref())))
+                .filter(|(_, line)| !line.trim().is_empty())
+                .collect(),
+        }
+    }
+
+    fn first_match(&self, line: &str) -> Option<&str> {
+        self.terms
+            .iter()
+            .find(|(_, needle)| line.contains(needle.as_str()))
+            .map(|(term, _)| term.as_str())
+    }
+}
+
+/// Term lists in effect for one user
+pub struct CopyFilter {
+    lists: Vec<TermList>,
+}
+
+impl CopyFilter {
+    /// Built-in and operator lists from config plus the user's own terms
+    pub fn new(confidential: &[String], competitors: &[String]) -> Self {
+        let agent = &config::get().agent;
+        let mut lists = Vec::new();
+        if agent.copy_filter {
+            lists.push(TermList::new("profanity", PROFANITY));
+            lists.push(TermList::new("slur", SLURS));
+        }
+        lists.push(TermList::new("blocked term", &agent.extra_blocked_terms));
+        lists.push(TermList::new("confidential codename", confidential));
+        lists.push(TermList::new("competitor mention", competitors));
+        Self { lists }
+    }
+
+    /// Load the user's confidential and competitor terms. A failed lookup
+    /// still filters with the config lists.
+    pub async fn for_user(db: &PgPool, user_id: i64) -> Self {
+        let terms = users::get_copy_filter_terms(db, user_id)
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!(
+                    "[copy_filter] Term lookup failed for user {}: {}",
+                    user_id, e
+                );
+                None
+            })
+            .unwrap_or_default();
+        Self::new(&terms.confidential_terms, &terms.competitor_terms)
+    }
+
+    /// Why these texts need review, or None when they're clean. Each category
+    /// is reported once, naming the first term found.
+    pub fn review_reason<'a>(&self, texts: impl IntoIterator<Item = &'a str>) -> Option<String> {
+        let lines: Vec<String> = texts.into_iter().map(word_line).collect();
+        let reasons: Vec<String> = self
+            .lists
+            .iter()
+            .filter_map(|list| {
+                lines
+                    .iter()
+                    .find_map(|line| list.first_match(line))
+                    .map(|term| format!("{}: \"{}\"", list.label, term))
+            })
+            .collect();
+        (!reasons.is_empty()).then(|| reasons.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter() -> CopyFilter {
+        CopyFilter::new(
+            &["Project Falcon".to_string(), "bluebird".to_string()],
+            &["Acme".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_clean_copy_passes() {
+        assert_eq!(
+            filter().review_reason(["Shipped streaming exports", "Shitake risotto for lunch"]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_review_reasons() {
+        assert_eq!(
+            filter().review_reason(["This bug was a total shitshow, fucking finally fixed"]),
+            Some("profanity: \"fucking\"".to_string())
+        );
+        assert_eq!(
+            filter().review_reason(["Falcon is fast", "project  FALCON beats acme's importer"]),
+            Some(
+                "confidential codename: \"Project Falcon\"; competitor mention: \"Acme\""
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            filter().review_reason(["Bluebird's first build is green"]),
+            Some("confidential codename: \"bluebird\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_terms() {
+        assert_eq!(
+            normalize_terms(&[
+                " Project   Falcon ".to_string(),
+                "project falcon".to_string(),
+                "".to_string()
+            ]),
+            vec!["Project Falcon".to_string()]
+        );
+    }
+}
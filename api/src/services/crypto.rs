@@ -0,0 +1,227 @@
+//! Application-level encryption for secrets stored in Postgres
+//!
+//! OAuth tokens in `users` are encrypted with AES-256-GCM before they are written
+//! and decrypted on read (see the token helpers in services::twitter). Stored
+//! values look like `enc:<key id>:<base64url(nonce || ciphertext)>`; the key id
+//! is a fingerprint of the key, so old keys can stay configured for reading
+//! while everything is re-encrypted under the current one.
+//!
+//! The key comes from TOKEN_ENCRYPTION_KEY (base64, 32 bytes) or
+//! TOKEN_ENCRYPTION_KEY_FILE (e.g. a secret mounted from KMS / Secret Manager).
+//! Outside prod it falls back to a key derived from JWT_SECRET.
+//! TOKEN_ENCRYPTION_PREVIOUS_KEYS lists retired keys that can still decrypt.
+//!
+//! Values without the `enc:` prefix are plaintext from before encryption and are
+//! returned as-is; `api --encrypt-tokens` converts them (and anything under a
+//! previous key) in place.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+use crate::config;
+use crate::storage::sha256_hex;
+
+const PREFIX: &str = "enc:";
+const NONCE_LEN: usize = 12;
+const DERIVED_KEY_CONTEXT: &[u8] = b"cleo token encryption v1";
+
+static CIPHER: OnceLock<TokenCipher> = OnceLock::new();
+
+#[derive(Debug)]
+pub enum CryptoError {
+    /// Encrypted under a key that isn't configured
+    UnknownKey(String),
+    /// Malformed value or failed authentication
+    Corrupt,
+    /// The configured key couldn't be loaded
+    Key(String),
+}
+
+impl std::fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CryptoError::UnknownKey(id) => write!(f, "encrypted with unknown key {}", id),
+            CryptoError::Corrupt => write!(f, "encrypted value is corrupt"),
+            CryptoError::Key(e) => write!(f, "token encryption key: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+struct Key {
+    id: String,
+    cipher: Aes256Gcm,
+}
+
+impl Key {
+    fn new(bytes: [u8; 32]) -> Self {
+        let id = Sha256::digest(bytes)[..4]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Self {
+            id,
+            cipher: Aes256Gcm::new(&bytes.into()),
+        }
+    }
+}
+
+/// Current key plus retired keys that may still decrypt
+pub struct TokenCipher {
+    current: Key,
+    previous: Vec<Key>,
+}
+
+impl TokenCipher {
+    pub fn new(current: [u8; 32], previous: Vec<[u8; 32]>) -> Self {
+        Self {
+            current: Key::new(current),
+            previous: previous.into_iter().map(Key::new).collect(),
+        }
+    }
+
+    /// Build from config: explicit key or key file, else derived from the JWT secret
+    pub fn from_config(config: &config::Config) -> Result<Self, CryptoError> {
+        let auth = &config.auth;
+        let current = match (&auth.token_encryption_key, &auth.token_encryption_key_file) {
+            (Some(key), _) => decode_key(key)?,
+            (None, Some(path)) => {
+                let contents = std::fs::read_to_string(path)
+                    .map_err(|e| CryptoError::Key(format!("{}: {}", path.display(), e)))?;
+                decode_key(contents.trim())?
+            }
+            (None, None) => {
+                let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(auth.jwt_secret.as_bytes())
+                    .expect("HMAC accepts keys of any length");
+                mac.update(DERIVED_KEY_CONTEXT);
+                mac.finalize().into_bytes().into()
+            }
+        };
+        let previous = auth
+            .previous_token_encryption_keys
+            .iter()
+            .map(|k| decode_key(k))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(current, previous))
+    }
+
+    pub fn current_key_id(&self) -> &str {
+        &self.current.id
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        let nonce_bytes: [u8; NONCE_LEN] = rand::rng().random();
+        let ciphertext = self
+            .current
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_bytes())
+            .expect("AES-GCM encryption of an in-memory buffer can't fail");
+
+        let mut payload = nonce_bytes.to_vec();
+        payload.extend(ciphertext);
+        format!(
+            "{}{}:{}",
+            PREFIX,
+            self.current.id,
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(payload)
+        )
+    }
+
+    /// Decrypt a stored value; unencrypted (legacy) values pass through
+    pub fn decrypt(&self, stored: &str) -> Result<String, CryptoError> {
+        let Some(rest) = stored.strip_prefix(PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let (id, encoded) = rest.split_once(':').ok_or(CryptoError::Corrupt)?;
+        let key = std::iter::once(&self.current)
+            .chain(&self.previous)
+            .find(|k| k.id == id)
+            .ok_or_else(|| CryptoError::UnknownKey(id.to_string()))?;
+
+        let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| CryptoError::Corrupt)?;
+        if payload.len() <= NONCE_LEN {
+            return Err(CryptoError::Corrupt);
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = key
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| CryptoError::Corrupt)?;
+        String::from_utf8(plaintext).map_err(|_| CryptoError::Corrupt)
+    }
+
+    /// True when the value is already encrypted under the current key
+    pub fn is_current(&self, stored: &str) -> bool {
+        stored
+            .strip_prefix(PREFIX)
+            .and_then(|rest| rest.split_once(':'))
+            .is_some_and(|(id, _)| id == self.current.id)
+    }
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; 32], CryptoError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| CryptoError::Key("must be 32 bytes, base64-encoded".to_string()))
+}
+
+/// Install the cipher built at startup
+pub fn init(cipher: TokenCipher) {
+    if CIPHER.set(cipher).is_err() {
+        eprintln!("[crypto] Token cipher already initialized; keeping the first one");
+    }
+}
+
+/// Process-wide token cipher (built from config if startup didn't install one)
+pub fn cipher() -> &'static TokenCipher {
+    CIPHER.get_or_init(|| {
+        TokenCipher::from_config(config::get()).expect("token encryption key is invalid")
+    })
+}
+
+/// Hash stored for (and looked up by) a bearer secret such as an extension
+/// token, email login link or enrollment code
+pub fn hash_token(token: &str) -> String {
+    sha256_hex(token.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_key_rotation() {
+        let old = TokenCipher::new([1; 32], vec![]);
+        let stored = old.encrypt("secret-token");
+        assert!(stored.starts_with("enc:"));
+        assert_ne!(old.encrypt("secret-token"), stored, "nonce must vary");
+        assert_eq!(old.decrypt(&stored).unwrap(), "secret-token");
+        assert_eq!(old.decrypt("legacy-plaintext").unwrap(), "legacy-plaintext");
+
+        let rotated = TokenCipher::new([2; 32], vec![[1; 32]]);
+        assert!(!rotated.is_current(&stored));
+        assert_eq!(rotated.decrypt(&stored).unwrap(), "secret-token");
+        assert!(rotated.is_current(&rotated.encrypt("secret-token")));
+
+        let unrelated = TokenCipher::new([3; 32], vec![]);
+        assert!(matches!(
+            unrelated.decrypt(&stored),
+            Err(CryptoError::UnknownKey(_))
+        ));
+
+        let mut tampered = stored.clone();
+        tampered.pop();
+        tampered.push(if stored.ends_with('A') { 'B' } else { 'A' });
+        assert!(old.decrypt(&tampered).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! Double-submit CSRF protection for cookie-authenticated requests
+//!
+//! Login and refresh set a `csrf_token` cookie (readable by JS) and echo the same
+//! value in the `X-CSRF-Token` response header. State-changing browser requests
+//! must send it back in the `X-CSRF-Token` request header. A cross-site page can
+//! make the browser attach cookies but can't read them or set custom headers, so
+//! it can't produce a matching pair.
+//!
+//! Requests without session cookies (daemon bearer tokens, logged-out users) are
+//! not subject to the check.
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header::SET_COOKIE},
+    middleware::Next,
+    response::Response,
+};
+use axum_extra::extract::CookieJar;
+use rand::Rng;
+
+use super::cookies::{self, config};
+use super::error::ApiError;
+use super::session::hex;
+
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Session endpoints that must work before a CSRF cookie exists. Refresh and
+/// logout only rotate or clear the caller's own cookies; the token exchange is
+/// protected by the OAuth state parameter.
+const EXEMPT_PATHS: &[&str] = &["/auth/refresh", "/auth/logout", "/auth/twitter/token"];
+
+/// Random token for the csrf_token cookie
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    hex::encode(bytes.as_slice())
+}
+
+/// Issue a fresh token on a login or refresh response: as a cookie for same-origin
+/// clients and as a header for clients that can't read the API's cookies
+pub fn attach_token(headers: &mut HeaderMap) -> Result<(), StatusCode> {
+    let token = generate_token();
+    headers.append(SET_COOKIE, cookies::build_csrf_cookie(&token)?);
+    headers.insert(
+        CSRF_HEADER,
+        HeaderValue::from_str(&token).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+    );
+    Ok(())
+}
+
+/// Middleware: reject state-changing cookie-authenticated requests without a
+/// matching X-CSRF-Token header
+pub async fn require_csrf(request: Request, next: Next) -> Result<Response, ApiError> {
+    check(request.method(), request.uri().path(), request.headers())?;
+    Ok(next.run(request).await)
+}
+
+fn check(method: &Method, path: &str, headers: &HeaderMap) -> Result<(), ApiError> {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+        || EXEMPT_PATHS.contains(&path)
+    {
+        return Ok(());
+    }
+
+    let jar = CookieJar::from_headers(headers);
+    let has_session = jar.get(config::ACCESS_TOKEN_NAME).is_some()
+        || jar.get(config::REFRESH_TOKEN_NAME).is_some();
+    if !has_session {
+        return Ok(());
+    }
+
+    let cookie = jar.get(config::CSRF_TOKEN_NAME).map(|c| c.value());
+    let header = headers.get(CSRF_HEADER).and_then(|v| v.to_str().ok());
+    match (cookie, header) {
+        (Some(cookie), Some(header)) if !cookie.is_empty() && constant_time_eq(cookie, header) => {
+            Ok(())
+        }
+        _ => Err(ApiError::CsrfTokenInvalid),
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::COOKIE;
+
+    fn headers(cookie: &str, csrf: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::from_str(cookie).unwrap());
+        if let Some(csrf) = csrf {
+            headers.insert(CSRF_HEADER, HeaderValue::from_str(csrf).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_check() {
+        let session = "access_token=jwt; csrf_token=abc123";
+
+        assert!(
+            check(
+                &Method::POST,
+                "/tweets/1/publish",
+                &headers(session, Some("abc123"))
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                &Method::POST,
+                "/tweets/1/publish",
+                &headers(session, Some("abc124"))
+            )
+            .is_err()
+        );
+        assert!(check(&Method::DELETE, "/tweets/1", &headers(session, None)).is_err());
+        assert!(
+            check(
+                &Method::POST,
+                "/tweets/1/publish",
+                &headers("access_token=jwt", Some(""))
+            )
+            .is_err()
+        );
+
+        // Safe methods, exempt paths and cookie-less requests pass
+        assert!(check(&Method::GET, "/tweets", &headers(session, None)).is_ok());
+        assert!(check(&Method::POST, "/auth/refresh", &headers(session, None)).is_ok());
+        assert!(check(&Method::POST, "/activity", &HeaderMap::new()).is_ok());
+    }
+}
@@ -1,6 +1,8 @@
-//! Database transaction utilities
+//! Database transaction utilities and schema migrations
 //!
-//! This module provides utilities for database transaction management.
+//! This module provides utilities for database transaction management, builds
+//! the request and worker connection pools, and runs the migrations in
+//! `migrations/` (embedded at build time) on startup.
 //! The primary pattern is using sqlx's generic Executor trait, which allows
 //! domain functions to accept both `&PgPool` and `&mut PgConnection` (transactions).
 //!
@@ -42,6 +44,210 @@
 //! }
 //! ```
 
+// # Connection pools
+//
+// Request handlers and background workers (frame extraction, thumbnails, agent
+// runs) use separate pools so a heavy worker cycle can't starve interactive
+// endpoints. Each pool has its own size, acquire timeout and statement timeout
+// ([database.request_pool] / [database.worker_pool] in the config file):
+//
+// | Pool    | Size env (default)         | Statement timeout env (default)            |
+// |---------|----------------------------|--------------------------------------------|
+// | request | DB_POOL_SIZE (20)          | DB_STATEMENT_TIMEOUT_MS (30000)            |
+// | worker  | DB_WORKER_POOL_SIZE (10)   | DB_WORKER_STATEMENT_TIMEOUT_MS (300000)    |
+//
+// Acquire timeouts: DB_ACQUIRE_TIMEOUT_SECS (5) / DB_WORKER_ACQUIRE_TIMEOUT_SECS (30).
+// WORKER_DATABASE_URL optionally routes the worker pool through a different
+// endpoint for the same database (e.g. a dedicated pgbouncer); workers write, so
+// it must not be a read-only replica.
+
+use serde::Serialize;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::PoolConfig;
+
+/// Settings for one connection pool
+#[derive(Debug, Clone)]
+pub struct PoolSettings {
+    pub name: &'static str,
+    pub max_connections: u32,
+    pub acquire_timeout: Duration,
+    pub statement_timeout_ms: u64,
+}
+
+impl PoolSettings {
+    pub fn new(name: &'static str, config: &PoolConfig) -> Self {
+        Self {
+            name,
+            max_connections: config.max_connections,
+            acquire_timeout: Duration::from_secs(config.acquire_timeout_secs),
+            statement_timeout_ms: config.statement_timeout_ms,
+        }
+    }
+}
+
+/// Open a pool whose connections enforce the configured statement timeout
+pub async fn connect_pool(
+    database_url: &str,
+    settings: &PoolSettings,
+) -> Result<PgPool, sqlx::Error> {
+    let options = PgConnectOptions::from_str(database_url)?.options([(
+        "statement_timeout",
+        settings.statement_timeout_ms.to_string(),
+    )]);
+
+    let pool = PgPoolOptions::new()
+        .max_connections(settings.max_connections)
+        .acquire_timeout(settings.acquire_timeout)
+        .connect_with(options)
+        .await?;
+
+    println!(
+        "[startup] Database {} pool: {} max connections, {}ms statement timeout",
+        settings.name, settings.max_connections, settings.statement_timeout_ms
+    );
+
+    Ok(pool)
+}
+
+/// Point-in-time connection counts for a pool
+#[derive(Debug, Serialize)]
+pub struct PoolStats {
+    pub max: u32,
+    pub open: u32,
+    pub idle: usize,
+    pub in_use: usize,
+}
+
+pub fn pool_stats(pool: &PgPool) -> PoolStats {
+    let open = pool.size();
+    let idle = pool.num_idle();
+    PoolStats {
+        max: pool.options().get_max_connections(),
+        open,
+        idle,
+        in_use: (open as usize).saturating_sub(idle),
+    }
+}
+
+pub const POOL_MONITOR_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically warn when a pool is fully checked out, so starvation shows up in logs
+pub async fn run_pool_monitor(pools: Vec<(&'static str, PgPool)>) {
+    let mut interval = tokio::time::interval(POOL_MONITOR_INTERVAL);
+    loop {
+        interval.tick().await;
+        super::supervisor::heartbeat("db");
+        for (name, pool) in &pools {
+            let stats = pool_stats(pool);
+            if stats.open >= stats.max && stats.idle == 0 {
+                println!(
+                    "[db] {} pool saturated: {}/{} connections in use",
+                    name, stats.in_use, stats.max
+                );
+            }
+        }
+    }
+}
+
+// # Migrations
+//
+// `run_migrations` applies pending files from `migrations/` and records them in
+// sqlx's `_sqlx_migrations` table, the same history `sqlx migrate run` uses.
+//
+// Databases created before migrations were tracked (schema applied by hand or by
+// the old psql loop in docker-entrypoint.sh) have tables but no history. Set
+// MIGRATIONS_BASELINE to the last migration version already applied there; those
+// versions are recorded as applied without being run, then the rest run normally.
+
+use sqlx::PgPool;
+use sqlx::migrate::Migrator;
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Apply all pending migrations, baselining untracked legacy databases first
+pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
+    if is_untracked_legacy_schema(pool).await? {
+        let baseline = migrations_baseline().ok_or_else(|| {
+            sqlx::Error::Configuration(
+                "database has tables but no migration history; set MIGRATIONS_BASELINE \
+                 to the last migration already applied (e.g. 18) or pass --skip-migrations"
+                    .into(),
+            )
+        })?;
+        record_baseline(pool, baseline).await?;
+        println!(
+            "[migrations] Recorded migrations up to {} as already applied",
+            baseline
+        );
+    }
+
+    MIGRATOR.run(pool).await?;
+    Ok(())
+}
+
+/// Last migration version to mark as applied on untracked databases (MIGRATIONS_BASELINE)
+fn migrations_baseline() -> Option<i64> {
+    crate::config::get()
+        .database
+        .migrations_baseline
+        .filter(|v| *v > 0)
+}
+
+/// True when the schema exists (users table) but sqlx has never recorded a migration
+async fn is_untracked_legacy_schema(pool: &PgPool) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT to_regclass('public._sqlx_migrations') IS NULL
+           AND to_regclass('public.users') IS NOT NULL
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+async fn record_baseline(pool: &PgPool, baseline: i64) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Same shape sqlx creates, so later `MIGRATOR.run` and sqlx-cli accept it
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+            version BIGINT PRIMARY KEY,
+            description TEXT NOT NULL,
+            installed_on TIMESTAMPTZ NOT NULL DEFAULT now(),
+            success BOOLEAN NOT NULL,
+            checksum BYTEA NOT NULL,
+            execution_time BIGINT NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for migration in MIGRATOR
+        .iter()
+        .filter(|m| m.version <= baseline && !m.migration_type.is_down_migration())
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+            VALUES ($1, $2, TRUE, $3, 0)
+            ON CONFLICT (version) DO NOTHING
+            "#,
+        )
+        .bind(migration.version)
+        .bind(migration.description.as_ref())
+        .bind(migration.checksum.as_ref())
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
 // Re-export commonly used types for convenience
 // These are currently used via direct sqlx:: imports in domain modules,
 // but are available here for future use if needed.
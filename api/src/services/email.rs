@@ -0,0 +1,111 @@
+//! Transactional email
+//!
+//! Only sign-in links are sent for now. The sender sits behind a trait so
+//! email flows can be tested offline; `HttpEmailSender` posts to a
+//! Resend-compatible API (`email.api_url`).
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+
+use crate::config::EmailConfig;
+
+/// One plain-text email with an HTML alternative
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String>;
+}
+
+pub struct HttpEmailSender {
+    http: Client,
+    api_url: String,
+    api_key: String,
+    from: String,
+}
+
+#[derive(Serialize)]
+struct SendRequest<'a> {
+    from: &'a str,
+    to: [&'a str; 1],
+    subject: &'a str,
+    text: &'a str,
+    html: &'a str,
+}
+
+impl HttpEmailSender {
+    /// None when email isn't configured
+    pub fn from_config(config: &EmailConfig) -> Option<Self> {
+        Some(Self {
+            http: Client::new(),
+            api_url: config.api_url.clone(),
+            api_key: config.api_key.clone()?,
+            from: config.from.clone()?,
+        })
+    }
+}
+
+#[async_trait]
+impl EmailSender for HttpEmailSender {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        let resp = self
+            .http
+            .post(&self.api_url)
+            .bearer_auth(&self.api_key)
+            .json(&SendRequest {
+                from: &self.from,
+                to: [&message.to],
+                subject: &message.subject,
+                text: &message.text,
+                html: &message.html,
+            })
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(format!("email API returned {}: {}", status, text));
+        }
+        Ok(())
+    }
+}
+
+/// Lowercased, trimmed address if it looks deliverable: one `@`, a non-empty
+/// local part and a dotted domain, no spaces
+pub fn normalize_address(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    let (local, domain) = email.split_once('@')?;
+    let valid = !local.is_empty()
+        && email.len() <= 254
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !email.chars().any(char::is_whitespace);
+    valid.then_some(email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_address() {
+        assert_eq!(
+            normalize_address("  Ada@Example.COM "),
+            Some("ada@example.com".to_string())
+        );
+        assert_eq!(normalize_address("ada@localhost"), None);
+        assert_eq!(normalize_address("@example.com"), None);
+        assert_eq!(normalize_address("ada@@example.com"), None);
+        assert_eq!(normalize_address("a da@example.com"), None);
+    }
+}
@@ -1,14 +1,18 @@
 //! Error handling utilities for route handlers
 
-use axum::http::StatusCode;
+use axum::{
+    Json,
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::services::quota::{QuotaExceeded, QuotaKind, QuotaRemaining};
 
 /// Extension trait for logging errors and converting to StatusCode
 pub trait LogErr<T> {
     /// Log error with context and return INTERNAL_SERVER_ERROR
     fn log_500(self, context: &str) -> Result<T, StatusCode>;
-
-    /// Log error with context and return a custom StatusCode
-    fn log_status(self, context: &str, status: StatusCode) -> Result<T, StatusCode>;
 }
 
 impl<T, E: std::fmt::Display> LogErr<T> for Result<T, E> {
@@ -18,11 +22,196 @@ impl<T, E: std::fmt::Display> LogErr<T> for Result<T, E> {
             StatusCode::INTERNAL_SERVER_ERROR
         })
     }
+}
 
-    fn log_status(self, context: &str, status: StatusCode) -> Result<T, StatusCode> {
-        self.map_err(|e| {
-            eprintln!("{}: {}", context, e);
-            status
-        })
+/// Handler error rendered as `{"error": "<code>", "message": "..."}`.
+/// `error` is a stable machine-readable code; `message` is for humans.
+#[derive(Debug)]
+pub enum ApiError {
+    /// Malformed or invalid input
+    BadRequest(String),
+    /// No credentials, or credentials that don't identify a user
+    Unauthorized,
+    /// Session JWT has expired; the client should call /auth/refresh
+    TokenExpired,
+    /// API token is unknown or was revoked
+    InvalidApiToken,
+    /// Twitter tokens are gone or can't be refreshed; the user must reconnect
+    TwitterReauthRequired,
+    Forbidden(&'static str),
+    /// Cookie-authenticated request without a matching X-CSRF-Token header
+    CsrfTokenInvalid,
+    /// The named resource doesn't exist or isn't owned by the caller
+    NotFound(&'static str),
+    Conflict(String),
+    PayloadTooLarge,
+    /// Body has an unsupported content type
+    UnsupportedMediaType(String),
+    /// Well-formed request that fails validation (bad field values, too many items)
+    Unprocessable(String),
+    RateLimited,
+    /// Capture ingestion quota used up: 429 for the daily allowance, 402 for storage
+    QuotaExceeded(QuotaExceeded),
+    /// An optional dependency (e.g. the LLM) isn't configured
+    ServiceUnavailable(&'static str),
+    /// Posting to Twitter or another upstream call failed
+    Upstream(String),
+    Internal,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quota: Option<QuotaRemaining>,
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized
+            | ApiError::TokenExpired
+            | ApiError::InvalidApiToken
+            | ApiError::TwitterReauthRequired => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) | ApiError::CsrfTokenInvalid => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::QuotaExceeded(q) => match q.kind {
+                QuotaKind::Daily => StatusCode::TOO_MANY_REQUESTS,
+                QuotaKind::Storage => StatusCode::PAYMENT_REQUIRED,
+            },
+            ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::TokenExpired => "token_expired",
+            ApiError::InvalidApiToken => "invalid_api_token",
+            ApiError::TwitterReauthRequired => "twitter_reauth_required",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::CsrfTokenInvalid => "csrf_token_invalid",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::PayloadTooLarge => "payload_too_large",
+            ApiError::UnsupportedMediaType(_) => "unsupported_media_type",
+            ApiError::Unprocessable(_) => "validation_failed",
+            ApiError::RateLimited => "rate_limited",
+            ApiError::QuotaExceeded(q) => match q.kind {
+                QuotaKind::Daily => "quota_exceeded",
+                QuotaKind::Storage => "storage_quota_exceeded",
+            },
+            ApiError::ServiceUnavailable(_) => "service_unavailable",
+            ApiError::Upstream(_) => "upstream_error",
+            ApiError::Internal => "internal_error",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::BadRequest(msg)
+            | ApiError::Conflict(msg)
+            | ApiError::UnsupportedMediaType(msg)
+            | ApiError::Unprocessable(msg)
+            | ApiError::Upstream(msg) => msg.clone(),
+            ApiError::Unauthorized => "Authentication required".to_string(),
+            ApiError::TokenExpired => "Session expired".to_string(),
+            ApiError::InvalidApiToken => "Invalid API token".to_string(),
+            ApiError::TwitterReauthRequired => "Reconnect your Twitter account".to_string(),
+            ApiError::Forbidden(what) => what.to_string(),
+            ApiError::CsrfTokenInvalid => "Missing or invalid CSRF token".to_string(),
+            ApiError::NotFound(what) => format!("{} not found", what),
+            ApiError::PayloadTooLarge => "Request body too large".to_string(),
+            ApiError::RateLimited => "Too many requests".to_string(),
+            ApiError::QuotaExceeded(q) => match q.kind {
+                QuotaKind::Daily => "Daily capture quota reached".to_string(),
+                QuotaKind::Storage => "Capture storage quota reached".to_string(),
+            },
+            ApiError::ServiceUnavailable(what) => format!("{} is not available", what),
+            ApiError::Internal => "Internal server error".to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (retry_after, quota) = match &self {
+            ApiError::QuotaExceeded(q) => (q.retry_after_secs(), Some(q.remaining.clone())),
+            _ => (None, None),
+        };
+        let body = ErrorBody {
+            error: self.code(),
+            message: self.message(),
+            retry_after,
+            quota,
+        };
+        let mut response = (self.status(), Json(body)).into_response();
+        if let Some(secs) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
+    }
+}
+
+/// Lets `LogErr` results and StatusCode helpers flow into `ApiError` handlers via `?`
+impl From<StatusCode> for ApiError {
+    fn from(status: StatusCode) -> Self {
+        match status {
+            StatusCode::BAD_REQUEST => ApiError::BadRequest("Invalid request".to_string()),
+            StatusCode::UNAUTHORIZED => ApiError::Unauthorized,
+            StatusCode::FORBIDDEN => ApiError::Forbidden("Not allowed"),
+            StatusCode::NOT_FOUND => ApiError::NotFound("Resource"),
+            StatusCode::CONFLICT => ApiError::Conflict("Conflicting state".to_string()),
+            StatusCode::PAYLOAD_TOO_LARGE => ApiError::PayloadTooLarge,
+            StatusCode::TOO_MANY_REQUESTS => ApiError::RateLimited,
+            StatusCode::SERVICE_UNAVAILABLE => ApiError::ServiceUnavailable("Service"),
+            _ => ApiError::Internal,
+        }
+    }
+}
+
+/// For handlers that still return bare StatusCodes
+impl From<ApiError> for StatusCode {
+    fn from(err: ApiError) -> Self {
+        err.status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            StatusCode::BAD_REQUEST,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::FORBIDDEN,
+            StatusCode::NOT_FOUND,
+            StatusCode::CONFLICT,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ] {
+            assert_eq!(ApiError::from(status).status(), status);
+        }
+        assert_eq!(ApiError::TokenExpired.code(), "token_expired");
+        assert_eq!(
+            ApiError::NotFound("Tweet").message(),
+            "Tweet not found".to_string()
+        );
     }
 }
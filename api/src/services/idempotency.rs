@@ -0,0 +1,173 @@
+//! Idempotency-Key handling for publish and upload endpoints
+//!
+//! Clients send an `Idempotency-Key` header on requests they may retry. The first
+//! request with a key runs and its successful response is stored; repeats within
+//! KEY_TTL_HOURS get that response back (marked `Idempotent-Replayed: true`)
+//! instead of posting or ingesting again. Failed requests release the key so a
+//! retry with the same key runs normally.
+
+use axum::{
+    Json,
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::future::Future;
+
+use super::error::{ApiError, LogErr};
+use crate::domain::idempotency::{self, IdempotencyRecord};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LEN: usize = 255;
+const KEY_TTL_HOURS: i64 = 24;
+/// Unfinished claims older than this are assumed abandoned (e.g. the server restarted)
+const STALE_CLAIM_MINUTES: i64 = 15;
+pub const CLEANUP_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Read the optional Idempotency-Key header: 1-255 visible ASCII characters
+pub fn key_from_headers(headers: &HeaderMap) -> Result<Option<String>, ApiError> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    value
+        .to_str()
+        .ok()
+        .filter(|key| is_valid_key(key))
+        .map(|key| Some(key.to_string()))
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Idempotency-Key must be 1-{} visible ASCII characters",
+                MAX_KEY_LEN
+            ))
+        })
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= MAX_KEY_LEN && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Run `handler` at most once per (user, key). Without a key the handler just runs.
+/// `endpoint` identifies the request (method, path and target) so a key reused for
+/// a different request is rejected rather than answered with the wrong response.
+pub async fn run<T, F>(
+    db: &PgPool,
+    user_id: i64,
+    key: Option<&str>,
+    endpoint: &str,
+    status: StatusCode,
+    handler: F,
+) -> Result<Response, ApiError>
+where
+    T: Serialize,
+    F: Future<Output = Result<T, ApiError>>,
+{
+    let Some(key) = key else {
+        return handler
+            .await
+            .map(|body| (status, Json(body)).into_response());
+    };
+
+    let stale_before = Utc::now() - Duration::minutes(STALE_CLAIM_MINUTES);
+    let claimed = idempotency::claim_key(db, user_id, key, endpoint, stale_before)
+        .await
+        .log_500("Claim idempotency key error")?;
+    if !claimed {
+        let record = idempotency::get_key(db, user_id, key)
+            .await
+            .log_500("Get idempotency key error")?;
+        return replay(record, endpoint);
+    }
+
+    match handler.await {
+        Ok(body) => {
+            let body = serde_json::to_value(&body).log_500("Serialize response error")?;
+            if let Err(e) =
+                idempotency::complete_key(db, user_id, key, status.as_u16() as i16, &body).await
+            {
+                // The work is done; a lost record only means a retry isn't deduplicated
+                eprintln!(
+                    "[idempotency] Failed to store response for key {}: {}",
+                    key, e
+                );
+            }
+            Ok((status, Json(body)).into_response())
+        }
+        Err(err) => {
+            if let Err(e) = idempotency::release_key(db, user_id, key).await {
+                eprintln!("[idempotency] Failed to release key {}: {}", key, e);
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Answer a repeated key from its stored record
+fn replay(record: Option<IdempotencyRecord>, endpoint: &str) -> Result<Response, ApiError> {
+    // Released by a failing request between our insert and read
+    let Some(record) = record else {
+        return Err(ApiError::Conflict(
+            "A request with this Idempotency-Key just failed; retry it".to_string(),
+        ));
+    };
+
+    if record.endpoint != endpoint {
+        return Err(ApiError::BadRequest(
+            "Idempotency-Key was already used for a different request".to_string(),
+        ));
+    }
+
+    match (record.status_code, record.response_body) {
+        (Some(code), Some(body)) => {
+            let status = StatusCode::from_u16(code as u16).unwrap_or(StatusCode::OK);
+            let mut response = (status, Json(body)).into_response();
+            response
+                .headers_mut()
+                .insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+            Ok(response)
+        }
+        _ => Err(ApiError::Conflict(
+            "A request with this Idempotency-Key is still in progress".to_string(),
+        )),
+    }
+}
+
+/// Periodically delete keys older than KEY_TTL_HOURS
+pub async fn run_cleanup_worker(pool: PgPool) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(CLEANUP_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+        super::supervisor::heartbeat("idempotency");
+
+        let cutoff = Utc::now() - Duration::hours(KEY_TTL_HOURS);
+        match idempotency::delete_expired_keys(&pool, cutoff).await {
+            Ok(0) => {}
+            Ok(deleted) => println!("[idempotency] Deleted {} expired keys", deleted),
+            Err(e) => eprintln!("[idempotency] Cleanup error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_validation() {
+        assert!(is_valid_key("3f2b9c1e-7d4a-4b8e-9a61-0c5d2e7f8a90"));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("has space"));
+        assert!(!is_valid_key(&"k".repeat(MAX_KEY_LEN + 1)));
+
+        let mut headers = HeaderMap::new();
+        assert!(key_from_headers(&headers).unwrap().is_none());
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_static("batch-42"));
+        assert_eq!(
+            key_from_headers(&headers).unwrap().as_deref(),
+            Some("batch-42")
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Short-lived impersonation tokens for admin tooling
+//!
+//! An admin requests a token for a target user (POST /admin/impersonation), then
+//! presents it alongside their own session to act as that user. Tokens are JWTs
+//! signed with a key derived from JWT_SECRET, so they can never be replayed as
+//! an access token, and they name the admin who may use them.
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::services::session::SessionError;
+
+/// Header carrying the impersonation token on admin requests
+pub const HEADER: &str = "x-impersonation-token";
+
+const TOKEN_EXPIRY_MINUTES: i64 = 15;
+const SIGNING_KEY_CONTEXT: &[u8] = b"cleo impersonation v1";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImpersonationClaims {
+    /// Impersonated user id
+    sub: String,
+    /// Admin user id allowed to use the token
+    act: String,
+    exp: i64,
+    iat: i64,
+}
+
+/// A validated impersonation grant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Impersonation {
+    pub user_id: i64,
+    pub admin_user_id: i64,
+}
+
+fn signing_key(jwt_secret: &[u8]) -> Vec<u8> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(jwt_secret).expect("HMAC accepts keys of any length");
+    mac.update(SIGNING_KEY_CONTEXT);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Issue a token letting `admin_user_id` act as `user_id`. Returns the token and its expiry.
+pub fn create_token(
+    user_id: i64,
+    admin_user_id: i64,
+    jwt_secret: &[u8],
+) -> Result<(String, DateTime<Utc>), SessionError> {
+    let now = Utc::now();
+    let expires_at = now + Duration::minutes(TOKEN_EXPIRY_MINUTES);
+
+    let claims = ImpersonationClaims {
+        sub: user_id.to_string(),
+        act: admin_user_id.to_string(),
+        exp: expires_at.timestamp(),
+        iat: now.timestamp(),
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(&signing_key(jwt_secret)),
+    )
+    .map_err(|_| SessionError::InvalidToken)?;
+
+    Ok((token, expires_at))
+}
+
+/// Validate an impersonation token
+pub fn validate_token(token: &str, jwt_secret: &[u8]) -> Result<Impersonation, SessionError> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_required_spec_claims(&["exp", "sub", "iat"]);
+
+    let claims = decode::<ImpersonationClaims>(
+        token,
+        &DecodingKey::from_secret(&signing_key(jwt_secret)),
+        &validation,
+    )
+    .map_err(|e| match e.kind() {
+        jsonwebtoken::errors::ErrorKind::ExpiredSignature => SessionError::Expired,
+        _ => SessionError::InvalidToken,
+    })?
+    .claims;
+
+    Ok(Impersonation {
+        user_id: claims.sub.parse().map_err(|_| SessionError::InvalidToken)?,
+        admin_user_id: claims.act.parse().map_err(|_| SessionError::InvalidToken)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::session;
+
+    const SECRET: &[u8] = b"test-secret-that-is-at-least-32-bytes-long";
+
+    #[test]
+    fn test_token_round_trip_and_not_a_session() {
+        let (token, _) = create_token(7, 1, SECRET).unwrap();
+        assert_eq!(
+            validate_token(&token, SECRET).unwrap(),
+            Impersonation {
+                user_id: 7,
+                admin_user_id: 1
+            }
+        );
+
+        // Signed with a different key, so it can't be used as the target's session
+        assert!(session::validate_access_token(&token, SECRET).is_err());
+        let access = session::create_access_token(7, SECRET).unwrap();
+        assert!(validate_token(&access, SECRET).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! Draft languages
+//!
+//! The agent writes drafts in the user's language (`users.draft_language`,
+//! GET/PUT /me/language) unless a run asks for another (POST /agent/run
+//! `language`), and POST /tweets/:id/translate rewrites a draft in a new one.
+//! Languages come from a fixed list so prompts can name them. X counts
+//! Chinese, Japanese and Korean characters twice toward the tweet limit; see
+//! the agent's `tweet_length`.
+
+use sqlx::PgPool;
+
+use crate::domain::users;
+use crate::services::error::ApiError;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    /// BCP 47 tag stored on the user and the run
+    pub code: &'static str,
+    /// English name used in prompts
+    pub name: &'static str,
+}
+
+const fn lang(code: &'static str, name: &'static str) -> Language {
+    Language { code, name }
+}
+
+/// Supported draft languages; the first is the default
+pub static LANGUAGES: &[Language] = &[
+    lang("en", "English"),
+    lang("es", "Spanish"),
+    lang("pt", "Portuguese"),
+    lang("fr", "French"),
+    lang("de", "German"),
+    lang("it", "Italian"),
+    lang("nl", "Dutch"),
+    lang("pl", "Polish"),
+    lang("tr", "Turkish"),
+    lang("ru", "Russian"),
+    lang("uk", "Ukrainian"),
+    lang("hi", "Hindi"),
+    lang("id", "Indonesian"),
+    lang("vi", "Vietnamese"),
+    lang("ja", "Japanese"),
+    lang("ko", "Korean"),
+    lang("zh-CN", "Simplified Chinese"),
+    lang("zh-TW", "Traditional Chinese"),
+];
+
+pub fn default_language() -> &'static Language {
+    &LANGUAGES[0]
+}
+
+/// Look up a language by code, ignoring case ("zh-cn" finds "zh-CN")
+pub fn find(code: &str) -> Option<&'static Language> {
+    let code = code.trim();
+    LANGUAGES.iter().find(|l| l.code.eq_ignore_ascii_case(code))
+}
+
+/// A language code from a request, or a 422 listing the supported ones
+pub fn checked(code: &str) -> Result<&'static Language, ApiError> {
+    find(code).ok_or_else(|| {
+        let supported: Vec<&str> = LANGUAGES.iter().map(|l| l.code).collect();
+        ApiError::Unprocessable(format!(
+            "unsupported language {:?}; use one of {}",
+            code.trim(),
+            supported.join(", ")
+        ))
+    })
+}
+
+/// Load the user's draft language, falling back to English if unknown
+pub async fn user_language(db: &PgPool, user_id: i64) -> &'static Language {
+    match users::get_draft_language(db, user_id).await {
+        Ok(Some(code)) => find(&code).unwrap_or_else(|| {
+            eprintln!(
+                "[language] User {} has unknown language {:?}",
+                user_id, code
+            );
+            default_language()
+        }),
+        Ok(None) => default_language(),
+        Err(e) => {
+            eprintln!(
+                "[language] Failed to load language for user {}: {}",
+                user_id, e
+            );
+            default_language()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_ignores_case() {
+        assert_eq!(find("ja").unwrap().name, "Japanese");
+        assert_eq!(find(" zh-tw ").unwrap().code, "zh-TW");
+        assert!(find("klingon").is_none());
+        assert_eq!(default_language().code, "en");
+    }
+}
@@ -0,0 +1,42 @@
+//! Launch mode - a time-boxed window for launch day
+//!
+//! While a user's `launch_mode_until` is in the future:
+//! - GET /me/limits tells the daemon to screenshot every `SCREENSHOT_INTERVAL_SECS`
+//!   (the daemon treats it as a ceiling on its adaptive cadence)
+//! - the background scheduler runs the agent after `IDLE_MINUTES` of quiet
+//!   instead of the configured idle threshold
+//! - the agent's prompt asks it to react to metrics dashboards and analytics
+//!   screens first
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Default window when the request doesn't give a duration
+pub const DEFAULT_DURATION_MINUTES: i64 = 12 * 60;
+/// Longest window; launch mode is meant for a launch, not a new default
+pub const MAX_DURATION_MINUTES: i64 = 72 * 60;
+/// Screenshot interval the daemon is asked to keep during launch mode
+pub const SCREENSHOT_INTERVAL_SECS: u64 = 2;
+/// Idle gap before the scheduler runs the agent during launch mode
+pub const IDLE_MINUTES: i64 = 3;
+
+/// End of a launch window of `minutes` starting at `now`, or None when the
+/// duration is out of range
+pub fn window_end(now: DateTime<Utc>, minutes: i64) -> Option<DateTime<Utc>> {
+    (1..=MAX_DURATION_MINUTES)
+        .contains(&minutes)
+        .then(|| now + Duration::minutes(minutes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_end_bounds_duration() {
+        let now = Utc::now();
+        assert_eq!(window_end(now, 90), Some(now + Duration::minutes(90)));
+        assert!(window_end(now, MAX_DURATION_MINUTES).is_some());
+        assert_eq!(window_end(now, 0), None);
+        assert_eq!(window_end(now, MAX_DURATION_MINUTES + 1), None);
+    }
+}
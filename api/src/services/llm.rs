@@ -0,0 +1,69 @@
+//! Single-turn LLM completions behind a trait so handlers can be tested offline
+//!
+//! The content agent drives reson's tool loop directly and still uses the
+//! concrete Gemini client; this covers simple prompt-in, text-out calls.
+
+use async_trait::async_trait;
+use reson_agentic::providers::{GenerationConfig, GoogleGenAIClient, InferenceClient};
+use reson_agentic::types::ChatMessage;
+use reson_agentic::utils::ConversationMessage;
+
+pub type LlmError = Box<dyn std::error::Error + Send + Sync>;
+
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Generate a text response to a single user prompt
+    async fn complete(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        temperature: f32,
+    ) -> Result<String, LlmError>;
+}
+
+/// Gemini-backed provider
+pub struct GeminiProvider {
+    client: GoogleGenAIClient,
+    model: String,
+}
+
+impl GeminiProvider {
+    pub fn new(client: GoogleGenAIClient, model: &str) -> Self {
+        Self {
+            client,
+            model: model.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GeminiProvider {
+    async fn complete(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        temperature: f32,
+    ) -> Result<String, LlmError> {
+        let messages = vec![ConversationMessage::Chat(ChatMessage::user(prompt))];
+        let config = GenerationConfig {
+            model: self.model.clone(),
+            max_tokens: Some(max_tokens),
+            temperature: Some(temperature),
+            top_p: None,
+            tools: None,
+            native_tools: false,
+            reasoning_effort: None,
+            thinking_budget: None,
+            output_schema: None,
+            output_type_name: None,
+            timeout: Some(std::time::Duration::from_secs(600)),
+        };
+
+        let response = self
+            .client
+            .get_generation(&messages, &config)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(response.content)
+    }
+}
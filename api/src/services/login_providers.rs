@@ -0,0 +1,273 @@
+//! GitHub and Google login
+//!
+//! These providers only establish who the user is: the API reads their
+//! provider user id, handle and email, never any provider data beyond that.
+//! Publishing still needs X, linked to the account as an integration
+//! (/me/integrations). The client sits behind a trait so login flows can be
+//! tested offline; `OAuthLoginClient` is the production implementation.
+
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, LoginProviderConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoginProvider {
+    Github,
+    Google,
+}
+
+impl LoginProvider {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LoginProvider::Github => "github",
+            LoginProvider::Google => "google",
+        }
+    }
+}
+
+/// Who the provider says the user is
+#[derive(Debug, Clone)]
+pub struct ProviderIdentity {
+    /// Stable id at the provider (GitHub user id, Google `sub`)
+    pub provider_user_id: String,
+    /// GitHub login or Google email
+    pub username: String,
+    pub email: Option<String>,
+    pub name: Option<String>,
+}
+
+#[async_trait]
+pub trait LoginClient: Send + Sync {
+    /// Providers with an OAuth app configured
+    fn enabled(&self) -> Vec<LoginProvider>;
+
+    /// Authorization URL, or None if the provider isn't configured
+    fn authorize_url(
+        &self,
+        provider: LoginProvider,
+        state: &str,
+        code_challenge: &str,
+    ) -> Option<String>;
+
+    /// Exchange the code and look up the user
+    async fn fetch_identity(
+        &self,
+        provider: LoginProvider,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<ProviderIdentity, String>;
+}
+
+/// Client ID, secret and callback for one provider
+#[derive(Clone)]
+struct ProviderApp {
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+}
+
+#[derive(Clone)]
+pub struct OAuthLoginClient {
+    http: Client,
+    github: Option<ProviderApp>,
+    google: Option<ProviderApp>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GithubUser {
+    id: i64,
+    login: String,
+    name: Option<String>,
+    email: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GoogleUser {
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+impl OAuthLoginClient {
+    pub fn from_config(config: &Config) -> Self {
+        let app = |provider: &LoginProviderConfig| {
+            provider.is_enabled().then(|| ProviderApp {
+                client_id: provider.client_id.clone(),
+                client_secret: provider.client_secret.clone(),
+                redirect_uri: config.login_redirect_uri(provider),
+            })
+        };
+        Self {
+            http: Client::new(),
+            github: app(&config.github),
+            google: app(&config.google),
+        }
+    }
+
+    fn app(&self, provider: LoginProvider) -> Option<&ProviderApp> {
+        match provider {
+            LoginProvider::Github => self.github.as_ref(),
+            LoginProvider::Google => self.google.as_ref(),
+        }
+    }
+
+    async fn exchange_code(
+        &self,
+        provider: LoginProvider,
+        app: &ProviderApp,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<String, String> {
+        let url = match provider {
+            LoginProvider::Github => "https://github.com/login/oauth/access_token",
+            LoginProvider::Google => "https://oauth2.googleapis.com/token",
+        };
+        let params = [
+            ("client_id", app.client_id.as_str()),
+            ("client_secret", app.client_secret.as_str()),
+            ("code", code),
+            ("code_verifier", code_verifier),
+            ("redirect_uri", app.redirect_uri.as_str()),
+            ("grant_type", "authorization_code"),
+        ];
+
+        let resp = self
+            .http
+            .post(url)
+            .header("Accept", "application/json")
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = resp.status();
+        // GitHub reports a bad code with 200 and an `error` field
+        let token: TokenResponse = resp.json().await.map_err(|e| e.to_string())?;
+        match token.access_token {
+            Some(access_token) if status.is_success() => Ok(access_token),
+            _ => Err(format!(
+                "{} token exchange failed ({}): {}",
+                provider.as_str(),
+                status,
+                token
+                    .error_description
+                    .or(token.error)
+                    .unwrap_or_else(|| "no access token".to_string())
+            )),
+        }
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        access_token: &str,
+    ) -> Result<T, String> {
+        let resp = self
+            .http
+            .get(url)
+            .bearer_auth(access_token)
+            // GitHub rejects requests without a User-Agent
+            .header("User-Agent", "cleo")
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(format!("{} from {}: {}", status, url, text));
+        }
+        resp.json().await.map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl LoginClient for OAuthLoginClient {
+    fn enabled(&self) -> Vec<LoginProvider> {
+        [LoginProvider::Github, LoginProvider::Google]
+            .into_iter()
+            .filter(|p| self.app(*p).is_some())
+            .collect()
+    }
+
+    fn authorize_url(
+        &self,
+        provider: LoginProvider,
+        state: &str,
+        code_challenge: &str,
+    ) -> Option<String> {
+        let app = self.app(provider)?;
+        let (base, scope) = match provider {
+            LoginProvider::Github => ("https://github.com/login/oauth/authorize", "read:user"),
+            LoginProvider::Google => (
+                "https://accounts.google.com/o/oauth2/v2/auth",
+                "openid email profile",
+            ),
+        };
+        let mut url = Url::parse(base).ok()?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &app.client_id)
+            .append_pair("redirect_uri", &app.redirect_uri)
+            .append_pair("scope", scope)
+            .append_pair("state", state)
+            .append_pair("code_challenge", code_challenge)
+            .append_pair("code_challenge_method", "S256");
+        Some(url.to_string())
+    }
+
+    async fn fetch_identity(
+        &self,
+        provider: LoginProvider,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<ProviderIdentity, String> {
+        let app = self
+            .app(provider)
+            .ok_or_else(|| format!("{} login is not configured", provider.as_str()))?;
+        let access_token = self
+            .exchange_code(provider, app, code, code_verifier)
+            .await?;
+
+        match provider {
+            LoginProvider::Github => {
+                let user: GithubUser = self
+                    .get_json("https://api.github.com/user", &access_token)
+                    .await?;
+                Ok(ProviderIdentity {
+                    provider_user_id: user.id.to_string(),
+                    username: user.login,
+                    email: user.email,
+                    name: user.name,
+                })
+            }
+            LoginProvider::Google => {
+                let user: GoogleUser = self
+                    .get_json(
+                        "https://openidconnect.googleapis.com/v1/userinfo",
+                        &access_token,
+                    )
+                    .await?;
+                // An unverified address could belong to someone else
+                let email = user.email.filter(|_| user.email_verified);
+                Ok(ProviderIdentity {
+                    username: email.clone().unwrap_or_else(|| user.sub.clone()),
+                    provider_user_id: user.sub,
+                    email,
+                    name: user.name,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! HMAC-signed, expiring URLs for locally served media
+//!
+//! Local storage has no equivalent of GCS signed URLs, so `/media/*` links carry
+//! `uid`, `exp` and `sig` query params instead. The signature covers the path, the
+//! owning user and the expiry; `serve_media` verifies it and then re-checks
+//! ownership for that user, so a leaked link stops working once it expires.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Query params appended to signed media URLs. All optional so a bare or
+/// truncated link is rejected as forbidden rather than as a malformed query.
+#[derive(Debug, Deserialize)]
+pub struct MediaSignature {
+    pub uid: Option<i64>,
+    pub exp: Option<i64>,
+    pub sig: Option<String>,
+}
+
+fn mac(secret: &[u8], path: &str, user_id: i64, expires_at: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    // Domain-separate from other uses of the same secret
+    mac.update(b"cleo-media\n");
+    mac.update(path.as_bytes());
+    mac.update(format!("\n{}\n{}", user_id, expires_at).as_bytes());
+    mac
+}
+
+/// Build a signed `/media/{path}` URL valid for at least `ttl_secs`.
+/// Expiry is rounded up to a multiple of `ttl_secs` so the URL (and browser cache
+/// entry) stays stable within each window.
+pub fn signed_media_url(secret: &[u8], path: &str, user_id: i64, ttl_secs: u32) -> String {
+    let ttl = i64::from(ttl_secs.max(1));
+    let expires_at = (Utc::now().timestamp() / ttl + 2) * ttl;
+    let sig = URL_SAFE_NO_PAD.encode(
+        mac(secret, path, user_id, expires_at)
+            .finalize()
+            .into_bytes(),
+    );
+    format!(
+        "/media/{}?uid={}&exp={}&sig={}",
+        path, user_id, expires_at, sig
+    )
+}
+
+/// Seconds until the signature expires (0 once it has), so responses are never
+/// cached past the point the URL stops working
+pub fn seconds_until_expiry(signature: &MediaSignature) -> i64 {
+    signature
+        .exp
+        .map_or(0, |exp| (exp - Utc::now().timestamp()).max(0))
+}
+
+/// Check a signature for `path` and return the user it was issued to; None if
+/// it is missing, malformed, forged or expired
+pub fn verify_media_signature(
+    secret: &[u8],
+    path: &str,
+    signature: &MediaSignature,
+) -> Option<i64> {
+    let (uid, exp, sig) = (signature.uid?, signature.exp?, signature.sig.as_deref()?);
+    if exp < Utc::now().timestamp() {
+        return None;
+    }
+    let sig = URL_SAFE_NO_PAD.decode(sig).ok()?;
+    mac(secret, path, uid, exp)
+        .verify_slice(&sig)
+        .is_ok()
+        .then_some(uid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature_of(url: &str) -> MediaSignature {
+        let query = url.split_once('?').unwrap().1;
+        let param = |name: &str| {
+            query
+                .split('&')
+                .find_map(|p| p.strip_prefix(&format!("{}=", name)))
+                .unwrap()
+                .to_string()
+        };
+        MediaSignature {
+            uid: Some(param("uid").parse().unwrap()),
+            exp: Some(param("exp").parse().unwrap()),
+            sig: Some(param("sig")),
+        }
+    }
+
+    #[test]
+    fn test_signed_url_round_trip() {
+        let secret = b"test-secret";
+        let url = signed_media_url(secret, "image/user_1/a.png", 1, 900);
+        let signature = signature_of(&url);
+
+        assert_eq!(
+            verify_media_signature(secret, "image/user_1/a.png", &signature),
+            Some(1)
+        );
+        assert_eq!(
+            verify_media_signature(secret, "image/user_2/a.png", &signature),
+            None
+        );
+        assert_eq!(
+            verify_media_signature(b"other", "image/user_1/a.png", &signature),
+            None
+        );
+
+        let other_user = MediaSignature {
+            uid: Some(2),
+            ..signature
+        };
+        assert_eq!(
+            verify_media_signature(secret, "image/user_1/a.png", &other_user),
+            None
+        );
+    }
+
+    #[test]
+    fn test_missing_params_rejected() {
+        let secret = b"test-secret";
+        let url = signed_media_url(secret, "a.png", 1, 900);
+
+        let no_sig = MediaSignature {
+            sig: None,
+            ..signature_of(&url)
+        };
+        assert_eq!(verify_media_signature(secret, "a.png", &no_sig), None);
+
+        let no_exp = MediaSignature {
+            exp: None,
+            ..signature_of(&url)
+        };
+        assert_eq!(verify_media_signature(secret, "a.png", &no_exp), None);
+        assert_eq!(seconds_until_expiry(&no_exp), 0);
+    }
+
+    #[test]
+    fn test_expired_signature_rejected() {
+        let secret = b"test-secret";
+        let exp = Utc::now().timestamp() - 1;
+        let expired = MediaSignature {
+            uid: Some(1),
+            exp: Some(exp),
+            sig: Some(URL_SAFE_NO_PAD.encode(mac(secret, "a.png", 1, exp).finalize().into_bytes())),
+        };
+        assert_eq!(verify_media_signature(secret, "a.png", &expired), None);
+        assert_eq!(seconds_until_expiry(&expired), 0);
+    }
+
+    #[test]
+    fn test_expiry_covers_the_signed_window() {
+        let url = signed_media_url(b"test-secret", "a.png", 1, 900);
+        let remaining = seconds_until_expiry(&signature_of(&url));
+        assert!((900..=1800).contains(&remaining));
+    }
+}
@@ -15,9 +15,11 @@ use std::path::PathBuf;
 use std::process::Stdio;
 use tokio::process::Command;
 
-use crate::constants::BUCKET_NAME;
 use crate::domain::captures;
+use crate::frames::{self, FrameManifest};
 use crate::get_extension;
+use crate::services::timezone;
+use crate::storage::Buckets;
 
 /// Error types for media studio operations
 #[derive(Debug)]
@@ -116,14 +118,500 @@ impl TrimParams {
                 "duration must be positive".into(),
             ));
         }
-        // Validate timestamp format (simple check)
-        if self.start_timestamp.is_empty() {
+        if parse_timestamp_secs(&self.start_timestamp).is_none() {
             return Err(MediaStudioError::InvalidParams(
-                "start_timestamp is required".into(),
+                "start_timestamp must be HH:MM:SS, MM:SS or SS".into(),
             ));
         }
         Ok(())
     }
+
+    /// Check the clip fits inside a video of `video_duration` seconds (if known)
+    pub fn validate_within(&self, video_duration: Option<f64>) -> Result<(), MediaStudioError> {
+        self.validate()?;
+        let (Some(total), Some(start)) =
+            (video_duration, parse_timestamp_secs(&self.start_timestamp))
+        else {
+            return Ok(());
+        };
+        // Container durations are rounded; allow a little slack at the end
+        if start + self.duration_secs > total + CLIP_END_TOLERANCE_SECS {
+            return Err(MediaStudioError::InvalidParams(format!(
+                "clip ends at {:.1}s but video is {:.1}s long",
+                start + self.duration_secs,
+                total
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Parameters for speeding a recording up into a timelapse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelapseParams {
+    /// Length of the rendered timelapse in seconds
+    pub duration_secs: f64,
+}
+
+/// Shortest timelapse worth rendering
+const MIN_TIMELAPSE_SECS: f64 = 1.0;
+/// Longest timelapse (X's video length limit)
+const MAX_TIMELAPSE_SECS: f64 = 140.0;
+/// Output frame rate; sped-up frames beyond this are dropped
+const TIMELAPSE_FPS: u32 = 30;
+
+impl TimelapseParams {
+    pub fn validate(&self) -> Result<(), MediaStudioError> {
+        if !(MIN_TIMELAPSE_SECS..=MAX_TIMELAPSE_SECS).contains(&self.duration_secs) {
+            return Err(MediaStudioError::InvalidParams(format!(
+                "duration must be between {} and {} seconds",
+                MIN_TIMELAPSE_SECS, MAX_TIMELAPSE_SECS
+            )));
+        }
+        Ok(())
+    }
+
+    /// Speed-up factor for a source of `video_duration` seconds. The source
+    /// must be longer than the timelapse, and its duration must be known.
+    pub fn speed_factor(&self, video_duration: Option<f64>) -> Result<f64, MediaStudioError> {
+        self.validate()?;
+        let total = video_duration.ok_or_else(|| {
+            MediaStudioError::InvalidParams("video duration is not known yet".into())
+        })?;
+        if total <= self.duration_secs {
+            return Err(MediaStudioError::InvalidParams(format!(
+                "video is {:.1}s long; a timelapse must be shorter",
+                total
+            )));
+        }
+        Ok(total / self.duration_secs)
+    }
+}
+
+/// ffmpeg video filter that compresses time by `factor`, then samples frames
+/// down to a steady output rate
+fn timelapse_filter(factor: f64) -> String {
+    format!("setpts=PTS/{:.6},fps={}", factor, TIMELAPSE_FPS)
+}
+
+/// One caption shown from `start_secs` to `end_secs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptionSegment {
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub text: String,
+}
+
+/// Where captions sit on the frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptionPosition {
+    #[default]
+    Bottom,
+    Top,
+}
+
+/// Caption styling options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptionStyle {
+    /// Font size in points (relative to a 288px-tall frame, as libass scales it)
+    pub font_size: u32,
+    pub position: CaptionPosition,
+    /// Text color as `#RRGGBB`
+    pub color: String,
+    /// Draw a dark box behind the text for legibility
+    pub background: bool,
+}
+
+impl Default for CaptionStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 18,
+            position: CaptionPosition::Bottom,
+            color: "#FFFFFF".to_string(),
+            background: true,
+        }
+    }
+}
+
+/// Parameters for burning captions into a video
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptionParams {
+    pub segments: Vec<CaptionSegment>,
+    #[serde(default)]
+    pub style: CaptionStyle,
+}
+
+/// Most caption segments per video
+const MAX_CAPTION_SEGMENTS: usize = 500;
+/// Longest single caption, in characters
+const MAX_CAPTION_CHARS: usize = 200;
+
+impl CaptionParams {
+    pub fn validate(&self) -> Result<(), MediaStudioError> {
+        if self.segments.is_empty() || self.segments.len() > MAX_CAPTION_SEGMENTS {
+            return Err(MediaStudioError::InvalidParams(format!(
+                "between 1 and {} caption segments are required",
+                MAX_CAPTION_SEGMENTS
+            )));
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            if !segment.start_secs.is_finite()
+                || segment.start_secs < 0.0
+                || !segment.end_secs.is_finite()
+                || segment.end_secs <= segment.start_secs
+            {
+                return Err(MediaStudioError::InvalidParams(format!(
+                    "caption {} must end after it starts",
+                    i + 1
+                )));
+            }
+            let chars = segment.text.trim().chars().count();
+            if chars == 0 || chars > MAX_CAPTION_CHARS {
+                return Err(MediaStudioError::InvalidParams(format!(
+                    "caption {} must be 1 to {} characters",
+                    i + 1,
+                    MAX_CAPTION_CHARS
+                )));
+            }
+        }
+        if !(8..=72).contains(&self.style.font_size) {
+            return Err(MediaStudioError::InvalidParams(
+                "font_size must be between 8 and 72".into(),
+            ));
+        }
+        if parse_hex_color(&self.style.color).is_none() {
+            return Err(MediaStudioError::InvalidParams(
+                "color must be #RRGGBB".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check every caption starts inside a video of `video_duration` seconds (if known)
+    pub fn validate_within(&self, video_duration: Option<f64>) -> Result<(), MediaStudioError> {
+        self.validate()?;
+        let Some(total) = video_duration else {
+            return Ok(());
+        };
+        if let Some((i, _)) = self
+            .segments
+            .iter()
+            .enumerate()
+            .find(|(_, s)| s.start_secs >= total + CLIP_END_TOLERANCE_SECS)
+        {
+            return Err(MediaStudioError::InvalidParams(format!(
+                "caption {} starts at or after the end of the video ({:.1}s)",
+                i + 1,
+                total
+            )));
+        }
+        Ok(())
+    }
+
+    /// Render the segments as an SRT subtitle file
+    fn to_srt(&self) -> String {
+        let mut out = String::new();
+        for (i, segment) in self.segments.iter().enumerate() {
+            out.push_str(&format!(
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                srt_timestamp(segment.start_secs),
+                srt_timestamp(segment.end_secs),
+                // Blank lines would end the cue early
+                segment
+                    .text
+                    .trim()
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+        out
+    }
+
+    /// libass `force_style` override for the subtitles filter
+    fn force_style(&self) -> String {
+        let (r, g, b) = parse_hex_color(&self.style.color).unwrap_or((255, 255, 255));
+        // ASS colors are &HAABBGGRR; alignment 2 is bottom-center, 8 is top-center
+        let alignment = match self.style.position {
+            CaptionPosition::Bottom => 2,
+            CaptionPosition::Top => 8,
+        };
+        let mut style = format!(
+            "FontSize={},PrimaryColour=&H00{:02X}{:02X}{:02X},Alignment={},MarginV=20",
+            self.style.font_size, b, g, r, alignment
+        );
+        if self.style.background {
+            style.push_str(",BorderStyle=4,BackColour=&H80000000,Outline=0,Shadow=0");
+        }
+        style
+    }
+}
+
+/// Format seconds as an SRT `HH:MM:SS,mmm` timestamp
+fn srt_timestamp(secs: f64) -> String {
+    let millis = (secs.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        millis / 3_600_000,
+        (millis / 60_000) % 60,
+        (millis / 1000) % 60,
+        millis % 1000
+    )
+}
+
+/// Parse a `#RRGGBB` color
+fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// A frame promoted to a full-resolution still
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameParams {
+    /// Index into the capture's frame manifest
+    pub frame_index: usize,
+    /// Where the still was taken from in the source video
+    pub timestamp_secs: f64,
+}
+
+/// Slack allowed past the recorded video duration when validating clip ranges
+const CLIP_END_TOLERANCE_SECS: f64 = 0.5;
+
+/// Parse an HH:MM:SS(.fff), MM:SS or SS timestamp into seconds
+pub fn parse_timestamp_secs(timestamp: &str) -> Option<f64> {
+    let parts: Vec<&str> = timestamp.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let (seconds, whole) = parts.split_last()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    if !seconds.is_finite() || seconds < 0.0 || (!whole.is_empty() && seconds >= 60.0) {
+        return None;
+    }
+    let mut total = 0.0;
+    for (i, part) in whole.iter().enumerate() {
+        let value: u32 = part.parse().ok()?;
+        // Minutes are bounded when hours are present
+        if i > 0 && value >= 60 {
+            return None;
+        }
+        total = total * 60.0 + f64::from(value);
+    }
+    Some(total * 60.0 + seconds)
+}
+
+/// Corner a branding watermark is placed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl WatermarkPosition {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WatermarkPosition::TopLeft => "top_left",
+            WatermarkPosition::TopRight => "top_right",
+            WatermarkPosition::BottomLeft => "bottom_left",
+            WatermarkPosition::BottomRight => "bottom_right",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "top_left" => Some(WatermarkPosition::TopLeft),
+            "top_right" => Some(WatermarkPosition::TopRight),
+            "bottom_left" => Some(WatermarkPosition::BottomLeft),
+            "bottom_right" => Some(WatermarkPosition::BottomRight),
+            _ => None,
+        }
+    }
+
+    /// Top-left pixel of a `logo_w`x`logo_h` logo on a `width`x`height` canvas
+    fn origin(self, width: u32, height: u32, logo_w: u32, logo_h: u32) -> (i64, i64) {
+        let margin = i64::from(width.min(height)) * WATERMARK_MARGIN_PCT / 100;
+        let right = i64::from(width) - i64::from(logo_w) - margin;
+        let bottom = i64::from(height) - i64::from(logo_h) - margin;
+        match self {
+            WatermarkPosition::TopLeft => (margin, margin),
+            WatermarkPosition::TopRight => (right, margin),
+            WatermarkPosition::BottomLeft => (margin, bottom),
+            WatermarkPosition::BottomRight => (right, bottom),
+        }
+    }
+
+    /// ffmpeg overlay x:y expression for this corner
+    fn overlay_expr(self) -> String {
+        let m = format!("min(W\\,H)*{}/100", WATERMARK_MARGIN_PCT);
+        match self {
+            WatermarkPosition::TopLeft => format!("{m}:{m}"),
+            WatermarkPosition::TopRight => format!("W-w-{m}:{m}"),
+            WatermarkPosition::BottomLeft => format!("{m}:H-h-{m}"),
+            WatermarkPosition::BottomRight => format!("W-w-{m}:H-h-{m}"),
+        }
+    }
+}
+
+/// Logo width as a percentage of the media width
+const WATERMARK_WIDTH_PCT: u32 = 15;
+/// Gap between the logo and the edges, as a percentage of the shorter side
+const WATERMARK_MARGIN_PCT: i64 = 3;
+
+/// A branding logo to composite onto published media
+#[derive(Debug, Clone)]
+pub struct Watermark {
+    /// Encoded logo image (PNG with transparency works best)
+    pub logo: Vec<u8>,
+    pub position: WatermarkPosition,
+    /// 0.0 (invisible) to 1.0 (opaque)
+    pub opacity: f32,
+}
+
+/// Composite a watermark onto an image. Returns the new bytes and content type;
+/// animated GIFs are returned unchanged since compositing would drop the animation.
+pub fn watermark_image(
+    data: &[u8],
+    content_type: &str,
+    watermark: &Watermark,
+) -> Result<(Vec<u8>, String), MediaStudioError> {
+    if content_type == "image/gif" {
+        return Ok((data.to_vec(), content_type.to_string()));
+    }
+
+    let decode = |bytes: &[u8], what: &str| {
+        ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(|e| MediaStudioError::Processing(format!("Failed to read {}: {}", what, e)))?
+            .decode()
+            .map_err(|e| MediaStudioError::Processing(format!("Failed to decode {}: {}", what, e)))
+    };
+    let mut img = decode(data, "image")?.to_rgba8();
+    let logo = decode(&watermark.logo, "watermark logo")?;
+
+    let logo_w = (img.width() * WATERMARK_WIDTH_PCT / 100).max(1);
+    let logo_h = ((u64::from(logo.height()) * u64::from(logo_w)) / u64::from(logo.width().max(1)))
+        .max(1) as u32;
+    let mut logo = logo
+        .resize_exact(logo_w, logo_h, image::imageops::FilterType::Lanczos3)
+        .to_rgba8();
+    let opacity = watermark.opacity.clamp(0.0, 1.0);
+    for pixel in logo.pixels_mut() {
+        pixel[3] = (f32::from(pixel[3]) * opacity).round() as u8;
+    }
+
+    let (x, y) = watermark
+        .position
+        .origin(img.width(), img.height(), logo_w, logo_h);
+    image::imageops::overlay(&mut img, &logo, x, y);
+
+    // Keep JPEGs as JPEG so photos stay under upload size limits
+    let mut output = Cursor::new(Vec::new());
+    let content_type = if content_type == "image/jpeg" || content_type == "image/jpg" {
+        image::DynamicImage::ImageRgba8(img)
+            .to_rgb8()
+            .write_to(&mut output, image::ImageFormat::Jpeg)
+            .map_err(|e| MediaStudioError::Processing(format!("Failed to encode image: {}", e)))?;
+        "image/jpeg"
+    } else {
+        img.write_to(&mut output, image::ImageFormat::Png)
+            .map_err(|e| MediaStudioError::Processing(format!("Failed to encode image: {}", e)))?;
+        "image/png"
+    };
+
+    Ok((output.into_inner(), content_type.to_string()))
+}
+
+/// Composite a watermark onto a video with ffmpeg (re-encodes to H.264 MP4)
+pub async fn watermark_video(
+    data: &[u8],
+    watermark: &Watermark,
+) -> Result<Vec<u8>, MediaStudioError> {
+    let temp_dir = std::env::temp_dir();
+    let id = rand::random::<u64>();
+    let input_path = temp_dir.join(format!("cleo_watermark_input_{}.tmp", id));
+    let logo_path = temp_dir.join(format!("cleo_watermark_logo_{}.tmp", id));
+    let output_path = temp_dir.join(format!("cleo_watermark_output_{}.mp4", id));
+
+    let cleanup = || async {
+        let _ = tokio::fs::remove_file(&input_path).await;
+        let _ = tokio::fs::remove_file(&logo_path).await;
+        let _ = tokio::fs::remove_file(&output_path).await;
+    };
+
+    if let Err(e) = tokio::fs::write(&input_path, data).await {
+        cleanup().await;
+        return Err(MediaStudioError::Processing(format!(
+            "Failed to write temp input: {}",
+            e
+        )));
+    }
+    if let Err(e) = tokio::fs::write(&logo_path, &watermark.logo).await {
+        cleanup().await;
+        return Err(MediaStudioError::Processing(format!(
+            "Failed to write temp logo: {}",
+            e
+        )));
+    }
+
+    // Scale the logo relative to the video width, fade it, then pin it to the corner
+    let filter = format!(
+        "[1:v][0:v]scale2ref=w=iw*{}/100:h=ow/mdar[logo][base];\
+         [logo]format=rgba,colorchannelmixer=aa={:.3}[wm];\
+         [base][wm]overlay={}",
+        WATERMARK_WIDTH_PCT,
+        watermark.opacity.clamp(0.0, 1.0),
+        watermark.position.overlay_expr()
+    );
+    let output = Command::new("ffmpeg")
+        .args([
+            "-i",
+            input_path.to_str().unwrap(),
+            "-i",
+            logo_path.to_str().unwrap(),
+            "-filter_complex",
+            &filter,
+            "-c:v",
+            "libx264",
+            "-pix_fmt",
+            "yuv420p",
+            "-c:a",
+            "copy",
+            "-movflags",
+            "+faststart",
+            "-y",
+            output_path.to_str().unwrap(),
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await;
+
+    let result = match output {
+        Err(e) => Err(MediaStudioError::Processing(format!(
+            "Failed to spawn ffmpeg: {}",
+            e
+        ))),
+        Ok(output) if !output.status.success() => Err(MediaStudioError::Processing(format!(
+            "ffmpeg watermark failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ))),
+        Ok(_) => tokio::fs::read(&output_path).await.map_err(|e| {
+            MediaStudioError::Processing(format!("Failed to read watermarked output: {}", e))
+        }),
+    };
+    cleanup().await;
+    result
 }
 
 /// Edit parameters stored with derived captures
@@ -132,6 +620,9 @@ impl TrimParams {
 pub enum EditParams {
     Crop(CropParams),
     Trim(TrimParams),
+    Timelapse(TimelapseParams),
+    Captions(CaptionParams),
+    Frame(FrameParams),
 }
 
 /// Media Studio service for editing operations
@@ -139,14 +630,21 @@ pub struct MediaStudio {
     db: PgPool,
     gcs: Option<Storage>,
     local_storage_path: Option<PathBuf>,
+    buckets: Buckets,
 }
 
 impl MediaStudio {
-    pub fn new(db: PgPool, gcs: Option<Storage>, local_storage_path: Option<PathBuf>) -> Self {
+    pub fn new(
+        db: PgPool,
+        gcs: Option<Storage>,
+        local_storage_path: Option<PathBuf>,
+        buckets: Buckets,
+    ) -> Self {
         Self {
             db,
             gcs,
             local_storage_path,
+            buckets,
         }
     }
 
@@ -181,7 +679,7 @@ impl MediaStudio {
 
         // 4. Upload cropped image
         let extension = get_extension(&source.content_type);
-        let new_path = self.generate_edited_path(user_id, "image", extension);
+        let new_path = self.generate_edited_path(user_id, "image", extension).await;
         self.upload_capture(&new_path, &cropped_data).await?;
 
         // 5. Create new capture record
@@ -229,6 +727,7 @@ impl MediaStudio {
                 source.content_type
             )));
         }
+        trim.validate_within(source.duration_secs)?;
 
         // 2. Download source video
         let data = self.download_capture(&source.gcs_path).await?;
@@ -238,7 +737,7 @@ impl MediaStudio {
 
         // 4. Upload trimmed video
         let extension = get_extension(&source.content_type);
-        let new_path = self.generate_edited_path(user_id, "video", extension);
+        let new_path = self.generate_edited_path(user_id, "video", extension).await;
         self.upload_capture(&new_path, &trimmed_data).await?;
 
         // 5. Create new capture record
@@ -264,6 +763,199 @@ impl MediaStudio {
         Ok(new_id)
     }
 
+    /// Speed a long video capture up into a timelapse, creating a new capture
+    ///
+    /// Returns the new capture ID
+    pub async fn timelapse_video(
+        &self,
+        user_id: i64,
+        source_capture_id: i64,
+        timelapse: TimelapseParams,
+    ) -> Result<i64, MediaStudioError> {
+        timelapse.validate()?;
+
+        // 1. Verify user owns source capture and it's a video
+        let source = captures::get_capture_info(&self.db, source_capture_id, user_id)
+            .await?
+            .ok_or(MediaStudioError::NotFound)?;
+
+        if !source.content_type.starts_with("video/") {
+            return Err(MediaStudioError::InvalidMediaType(format!(
+                "Expected video, got {}",
+                source.content_type
+            )));
+        }
+        let factor = timelapse.speed_factor(source.duration_secs)?;
+
+        // 2. Download source video
+        let data = self.download_capture(&source.gcs_path).await?;
+
+        // 3. Speed up and resample with ffmpeg
+        let rendered = self
+            .apply_video_timelapse(&data, factor, timelapse.duration_secs)
+            .await?;
+
+        // 4. Upload the timelapse (always re-encoded to MP4)
+        let new_path = self.generate_edited_path(user_id, "video", "mp4").await;
+        self.upload_capture(&new_path, &rendered).await?;
+
+        // 5. Create new capture record
+        let edit_params = serde_json::to_value(EditParams::Timelapse(timelapse))
+            .map_err(|e| MediaStudioError::Processing(e.to_string()))?;
+
+        let new_id = self
+            .insert_edited_capture(
+                user_id,
+                "video",
+                "video/mp4",
+                &new_path,
+                source_capture_id,
+                edit_params,
+            )
+            .await?;
+
+        println!(
+            "[media_studio] Rendered {:.1}x timelapse {} -> {} for user {}",
+            factor, source_capture_id, new_id, user_id
+        );
+
+        Ok(new_id)
+    }
+
+    /// Burn captions into a video capture, creating a new capture
+    ///
+    /// Returns the new capture ID
+    pub async fn caption_video(
+        &self,
+        user_id: i64,
+        source_capture_id: i64,
+        captions: CaptionParams,
+    ) -> Result<i64, MediaStudioError> {
+        captions.validate()?;
+
+        // 1. Verify user owns source capture and it's a video
+        let source = captures::get_capture_info(&self.db, source_capture_id, user_id)
+            .await?
+            .ok_or(MediaStudioError::NotFound)?;
+
+        if !source.content_type.starts_with("video/") {
+            return Err(MediaStudioError::InvalidMediaType(format!(
+                "Expected video, got {}",
+                source.content_type
+            )));
+        }
+        captions.validate_within(source.duration_secs)?;
+
+        // 2. Download source video
+        let data = self.download_capture(&source.gcs_path).await?;
+
+        // 3. Burn in captions with ffmpeg
+        let rendered = self.apply_video_captions(&data, &captions).await?;
+
+        // 4. Upload the captioned video (always re-encoded to MP4)
+        let new_path = self.generate_edited_path(user_id, "video", "mp4").await;
+        self.upload_capture(&new_path, &rendered).await?;
+
+        // 5. Create new capture record
+        let edit_params = serde_json::to_value(EditParams::Captions(captions))
+            .map_err(|e| MediaStudioError::Processing(e.to_string()))?;
+
+        let new_id = self
+            .insert_edited_capture(
+                user_id,
+                "video",
+                "video/mp4",
+                &new_path,
+                source_capture_id,
+                edit_params,
+            )
+            .await?;
+
+        println!(
+            "[media_studio] Captioned video {} -> {} for user {}",
+            source_capture_id, new_id, user_id
+        );
+
+        Ok(new_id)
+    }
+
+    /// Re-extract one manifest frame from its source video at full resolution,
+    /// creating a new image capture
+    ///
+    /// Returns the new capture ID
+    pub async fn extract_frame(
+        &self,
+        user_id: i64,
+        source_capture_id: i64,
+        frame_index: usize,
+    ) -> Result<i64, MediaStudioError> {
+        // 1. Verify user owns source capture and it's a video
+        let source = captures::get_capture_info(&self.db, source_capture_id, user_id)
+            .await?
+            .ok_or(MediaStudioError::NotFound)?;
+
+        if !source.content_type.starts_with("video/") {
+            return Err(MediaStudioError::InvalidMediaType(format!(
+                "Expected video, got {}",
+                source.content_type
+            )));
+        }
+
+        // 2. Look the frame's timestamp up in the manifest
+        let manifest_path = format!("{}/manifest.json", frames::get_frames_dir(&source.gcs_path));
+        let manifest: FrameManifest = self
+            .download_capture(&manifest_path)
+            .await
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .ok_or_else(|| {
+                MediaStudioError::InvalidParams("frames have not been extracted yet".into())
+            })?;
+        let frame = manifest
+            .frames
+            .iter()
+            .find(|f| f.index == frame_index)
+            .ok_or_else(|| {
+                MediaStudioError::InvalidParams(format!("capture has no frame {}", frame_index))
+            })?;
+        let params = FrameParams {
+            frame_index,
+            timestamp_secs: frame.timestamp_secs,
+        };
+
+        // 3. Download source video and grab the frame with ffmpeg
+        let data = self.download_capture(&source.gcs_path).await?;
+        let still = self
+            .apply_frame_extract(&data, params.timestamp_secs)
+            .await?;
+
+        // 4. Upload the still
+        let new_path = self.generate_edited_path(user_id, "image", "png").await;
+        self.upload_capture(&new_path, &still).await?;
+
+        // 5. Create new capture record
+        let edit_params = serde_json::to_value(EditParams::Frame(params))
+            .map_err(|e| MediaStudioError::Processing(e.to_string()))?;
+
+        let new_id = self
+            .insert_edited_capture(
+                user_id,
+                "image",
+                "image/png",
+                &new_path,
+                source_capture_id,
+                edit_params,
+            )
+            .await?;
+
+        println!(
+            "[media_studio] Extracted frame {} of video {} -> {} for user {}",
+            frame_index, source_capture_id, new_id, user_id
+        );
+
+        Ok(new_id)
+    }
+
     // ============== Private helpers ==============
 
     async fn download_capture(&self, gcs_path: &str) -> Result<Vec<u8>, MediaStudioError> {
@@ -273,7 +965,7 @@ impl MediaStudio {
                 .await
                 .map_err(|e| MediaStudioError::Storage(format!("Local read failed: {}", e)))
         } else if let Some(ref gcs) = self.gcs {
-            let bucket = format!("projects/_/buckets/{}", BUCKET_NAME);
+            let bucket = format!("projects/_/buckets/{}", self.buckets.for_path(gcs_path));
             let mut resp = gcs
                 .read_object(&bucket, gcs_path)
                 .send()
@@ -310,7 +1002,7 @@ impl MediaStudio {
                 full_path
             );
         } else if let Some(ref gcs) = self.gcs {
-            let bucket = format!("projects/_/buckets/{}", BUCKET_NAME);
+            let bucket = format!("projects/_/buckets/{}", self.buckets.for_path(path));
             let bytes = Bytes::copy_from_slice(data);
             gcs.write_object(&bucket, path, bytes)
                 .send_buffered()
@@ -325,9 +1017,15 @@ impl MediaStudio {
         Ok(())
     }
 
-    fn generate_edited_path(&self, user_id: i64, media_type: &str, extension: &str) -> String {
+    async fn generate_edited_path(
+        &self,
+        user_id: i64,
+        media_type: &str,
+        extension: &str,
+    ) -> String {
         let now = Utc::now();
-        let date = now.format("%Y-%m-%d");
+        let tz = timezone::user_timezone(&self.db, user_id).await;
+        let date = timezone::local_date(now, tz).format("%Y-%m-%d");
         let timestamp = now.timestamp_millis();
         format!(
             "{}/user_{}/{}/edited_{}.{}",
@@ -462,4 +1160,330 @@ impl MediaStudio {
 
         Ok(trimmed_data)
     }
+
+    async fn apply_video_timelapse(
+        &self,
+        data: &[u8],
+        factor: f64,
+        duration_secs: f64,
+    ) -> Result<Vec<u8>, MediaStudioError> {
+        let temp_dir = std::env::temp_dir();
+        let input_path = temp_dir.join(format!(
+            "cleo_timelapse_input_{}.tmp",
+            rand::random::<u64>()
+        ));
+        let output_path = temp_dir.join(format!(
+            "cleo_timelapse_output_{}.mp4",
+            rand::random::<u64>()
+        ));
+
+        // Write input to temp file
+        tokio::fs::write(&input_path, data).await.map_err(|e| {
+            MediaStudioError::Processing(format!("Failed to write temp input: {}", e))
+        })?;
+
+        // Re-encode with compressed timestamps; audio can't be sped up this far, so drop it.
+        // -t guards against the container duration being slightly off.
+        let output = Command::new("ffmpeg")
+            .args([
+                "-i",
+                input_path.to_str().unwrap(),
+                "-vf",
+                &timelapse_filter(factor),
+                "-an",
+                "-t",
+                &duration_secs.to_string(),
+                "-c:v",
+                "libx264",
+                "-pix_fmt",
+                "yuv420p",
+                "-movflags",
+                "+faststart",
+                "-y",
+                output_path.to_str().unwrap(),
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| MediaStudioError::Processing(format!("Failed to spawn ffmpeg: {}", e)))?;
+
+        // Clean up input
+        let _ = tokio::fs::remove_file(&input_path).await;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let _ = tokio::fs::remove_file(&output_path).await;
+            return Err(MediaStudioError::Processing(format!(
+                "ffmpeg timelapse failed: {}",
+                stderr
+            )));
+        }
+
+        let rendered = tokio::fs::read(&output_path).await.map_err(|e| {
+            MediaStudioError::Processing(format!("Failed to read timelapse output: {}", e))
+        })?;
+
+        // Clean up output
+        let _ = tokio::fs::remove_file(&output_path).await;
+
+        Ok(rendered)
+    }
+
+    async fn apply_frame_extract(
+        &self,
+        data: &[u8],
+        timestamp_secs: f64,
+    ) -> Result<Vec<u8>, MediaStudioError> {
+        let temp_dir = std::env::temp_dir();
+        let input_path = temp_dir.join(format!("cleo_frame_input_{}.tmp", rand::random::<u64>()));
+        let output_path = temp_dir.join(format!("cleo_frame_output_{}.png", rand::random::<u64>()));
+
+        // Write input to temp file
+        tokio::fs::write(&input_path, data).await.map_err(|e| {
+            MediaStudioError::Processing(format!("Failed to write temp input: {}", e))
+        })?;
+
+        // -ss after -i decodes up to the exact timestamp instead of snapping to a keyframe
+        let output = Command::new("ffmpeg")
+            .args([
+                "-i",
+                input_path.to_str().unwrap(),
+                "-ss",
+                &format!("{:.3}", timestamp_secs),
+                "-frames:v",
+                "1",
+                "-y",
+                output_path.to_str().unwrap(),
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| MediaStudioError::Processing(format!("Failed to spawn ffmpeg: {}", e)))?;
+
+        // Clean up input
+        let _ = tokio::fs::remove_file(&input_path).await;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let _ = tokio::fs::remove_file(&output_path).await;
+            return Err(MediaStudioError::Processing(format!(
+                "ffmpeg frame extract failed: {}",
+                stderr
+            )));
+        }
+
+        let still = tokio::fs::read(&output_path).await.map_err(|e| {
+            MediaStudioError::Processing(format!("Failed to read extracted frame: {}", e))
+        })?;
+
+        // Clean up output
+        let _ = tokio::fs::remove_file(&output_path).await;
+
+        Ok(still)
+    }
+
+    async fn apply_video_captions(
+        &self,
+        data: &[u8],
+        captions: &CaptionParams,
+    ) -> Result<Vec<u8>, MediaStudioError> {
+        let temp_dir = std::env::temp_dir();
+        let id = rand::random::<u64>();
+        let input_path = temp_dir.join(format!("cleo_captions_input_{}.tmp", id));
+        let srt_path = temp_dir.join(format!("cleo_captions_{}.srt", id));
+        let output_path = temp_dir.join(format!("cleo_captions_output_{}.mp4", id));
+
+        let cleanup = || async {
+            let _ = tokio::fs::remove_file(&input_path).await;
+            let _ = tokio::fs::remove_file(&srt_path).await;
+            let _ = tokio::fs::remove_file(&output_path).await;
+        };
+
+        let written = match tokio::fs::write(&input_path, data).await {
+            Ok(()) => tokio::fs::write(&srt_path, captions.to_srt()).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            cleanup().await;
+            return Err(MediaStudioError::Processing(format!(
+                "Failed to write temp input: {}",
+                e
+            )));
+        }
+
+        // Temp paths contain no filter metacharacters, so only the style needs quoting
+        let filter = format!(
+            "subtitles={}:force_style='{}'",
+            srt_path.to_str().unwrap(),
+            captions.force_style()
+        );
+        let output = Command::new("ffmpeg")
+            .args([
+                "-i",
+                input_path.to_str().unwrap(),
+                "-vf",
+                &filter,
+                "-c:v",
+                "libx264",
+                "-pix_fmt",
+                "yuv420p",
+                "-c:a",
+                "copy",
+                "-movflags",
+                "+faststart",
+                "-y",
+                output_path.to_str().unwrap(),
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .await;
+
+        let result = match output {
+            Err(e) => Err(MediaStudioError::Processing(format!(
+                "Failed to spawn ffmpeg: {}",
+                e
+            ))),
+            Ok(output) if !output.status.success() => Err(MediaStudioError::Processing(format!(
+                "ffmpeg captions failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+            Ok(_) => tokio::fs::read(&output_path).await.map_err(|e| {
+                MediaStudioError::Processing(format!("Failed to read captioned output: {}", e))
+            }),
+        };
+        cleanup().await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        let img = image::RgbaImage::from_pixel(width, height, image::Rgba(color));
+        let mut out = Cursor::new(Vec::new());
+        img.write_to(&mut out, image::ImageFormat::Png).unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn timelapse_speed_factor() {
+        let params = TimelapseParams {
+            duration_secs: 30.0,
+        };
+        assert_eq!(params.speed_factor(Some(600.0)).unwrap(), 20.0);
+        assert!(params.speed_factor(Some(30.0)).is_err());
+        assert!(params.speed_factor(None).is_err());
+
+        let too_long = TimelapseParams {
+            duration_secs: 300.0,
+        };
+        assert!(too_long.speed_factor(Some(3600.0)).is_err());
+        assert_eq!(timelapse_filter(20.0), "setpts=PTS/20.000000,fps=30");
+    }
+
+    fn caption(start_secs: f64, end_secs: f64, text: &str) -> CaptionSegment {
+        CaptionSegment {
+            start_secs,
+            end_secs,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn captions_render_as_srt() {
+        let params = CaptionParams {
+            segments: vec![
+                caption(0.0, 2.5, "Hello"),
+                caption(3661.25, 3662.0, "line one\n\nline two"),
+            ],
+            style: CaptionStyle::default(),
+        };
+        assert_eq!(
+            params.to_srt(),
+            "1\n00:00:00,000 --> 00:00:02,500\nHello\n\n\
+             2\n01:01:01,250 --> 01:01:02,000\nline one\nline two\n\n"
+        );
+    }
+
+    #[test]
+    fn caption_validation() {
+        let mut params = CaptionParams {
+            segments: vec![caption(1.0, 2.0, "ok")],
+            style: CaptionStyle::default(),
+        };
+        assert!(params.validate_within(Some(10.0)).is_ok());
+        // A caption starting right at the (tolerated) end would never show
+        assert!(params.validate_within(Some(0.5)).is_err());
+        assert!(params.validate_within(Some(0.6)).is_ok());
+
+        params.segments = vec![caption(2.0, 2.0, "zero length")];
+        assert!(params.validate().is_err());
+        params.segments = vec![caption(0.0, 1.0, "   ")];
+        assert!(params.validate().is_err());
+
+        params.segments = vec![caption(0.0, 1.0, "ok")];
+        params.style.color = "white".to_string();
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn caption_style_uses_ass_color_order() {
+        let params = CaptionParams {
+            segments: vec![],
+            style: CaptionStyle {
+                font_size: 20,
+                position: CaptionPosition::Top,
+                color: "#FF8000".to_string(),
+                background: false,
+            },
+        };
+        assert_eq!(
+            params.force_style(),
+            "FontSize=20,PrimaryColour=&H000080FF,Alignment=8,MarginV=20"
+        );
+    }
+
+    #[test]
+    fn watermark_origin_respects_corner_and_margin() {
+        // 3% of the 100px short side = 3px margin
+        let pos = |p: WatermarkPosition| p.origin(200, 100, 30, 10);
+        assert_eq!(pos(WatermarkPosition::TopLeft), (3, 3));
+        assert_eq!(pos(WatermarkPosition::TopRight), (167, 3));
+        assert_eq!(pos(WatermarkPosition::BottomLeft), (3, 87));
+        assert_eq!(pos(WatermarkPosition::BottomRight), (167, 87));
+    }
+
+    #[test]
+    fn watermark_image_composites_into_chosen_corner() {
+        let watermark = Watermark {
+            logo: png(10, 10, [255, 0, 0, 255]),
+            position: WatermarkPosition::BottomRight,
+            opacity: 1.0,
+        };
+        let (out, content_type) =
+            watermark_image(&png(200, 100, [0, 0, 255, 255]), "image/png", &watermark).unwrap();
+        assert_eq!(content_type, "image/png");
+
+        let img = image::load_from_memory(&out).unwrap().to_rgba8();
+        // Logo is 30x30 at (167, 67); top-left stays untouched
+        assert_eq!(img.get_pixel(180, 80).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(5, 5).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn watermark_image_leaves_gifs_alone() {
+        let watermark = Watermark {
+            logo: png(10, 10, [255, 0, 0, 255]),
+            position: WatermarkPosition::TopLeft,
+            opacity: 0.5,
+        };
+        let (out, content_type) = watermark_image(b"GIF89a", "image/gif", &watermark).unwrap();
+        assert_eq!(out, b"GIF89a");
+        assert_eq!(content_type, "image/gif");
+    }
 }
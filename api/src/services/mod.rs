@@ -1,9 +1,40 @@
+pub mod audit;
 pub mod auth;
+pub mod conditional;
 pub mod cookies;
+pub mod copy_experiments;
+pub mod copy_filter;
+pub mod crypto;
+pub mod csrf;
 pub mod db;
+pub mod email;
 pub mod error;
+pub mod html;
+pub mod idempotency;
+pub mod impersonation;
+pub mod language;
+pub mod launch_mode;
+pub mod llm;
+pub mod login_providers;
+pub mod media_signing;
 pub mod media_studio;
+pub mod notifications;
+pub mod object_store;
+pub mod projects;
+pub mod protobuf;
+pub mod publish_jobs;
 pub mod push;
+pub mod quota;
+pub mod range;
 pub mod rate_limit;
+pub mod rest_hooks;
 pub mod session;
+pub mod social;
+pub mod supervisor;
+pub mod tab_context;
+pub mod thread_suggest;
+pub mod timezone;
 pub mod twitter;
+pub mod twitter_credentials;
+pub mod validation;
+pub mod weekly_recap;
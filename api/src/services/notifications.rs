@@ -0,0 +1,173 @@
+//! Notification preferences
+//!
+//! Users choose, per channel and event type, what they hear about, and can set
+//! quiet hours in their own time zone (GET/PUT /me/notifications). Every sender
+//! asks `should_notify` first; a notification that isn't allowed is dropped,
+//! not queued. Sign-in links are requested by the user rather than sent to
+//! them, so they always go out.
+
+use chrono::{NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::domain::notification_preferences::{self, NotificationPreferences};
+use crate::services::timezone;
+
+/// Where a notification is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    /// Web push to the user's subscribed browsers
+    Push,
+    Email,
+    Slack,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 3] = [Channel::Push, Channel::Email, Channel::Slack];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Channel::Push => "push",
+            Channel::Email => "email",
+            Channel::Slack => "slack",
+        }
+    }
+}
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationEvent {
+    /// New drafts from an agent run are waiting for review
+    Content,
+    /// A data export finished
+    Export,
+    /// X rejected the refresh token and must be reconnected
+    Reconnect,
+    /// A "week in review" thread draft is waiting for approval
+    Recap,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 4] = [
+        NotificationEvent::Content,
+        NotificationEvent::Export,
+        NotificationEvent::Reconnect,
+        NotificationEvent::Recap,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationEvent::Content => "content",
+            NotificationEvent::Export => "export",
+            NotificationEvent::Reconnect => "reconnect",
+            NotificationEvent::Recap => "recap",
+        }
+    }
+}
+
+/// How a channel/event pair is stored in `muted`
+pub fn muted_key(channel: Channel, event: NotificationEvent) -> String {
+    format!("{}.{}", channel.as_str(), event.as_str())
+}
+
+/// Whether the pair is switched on, ignoring quiet hours
+pub fn is_enabled(
+    prefs: &NotificationPreferences,
+    channel: Channel,
+    event: NotificationEvent,
+) -> bool {
+    let key = muted_key(channel, event);
+    !prefs.muted.contains(&key)
+}
+
+/// Whether local time `now` falls in `[start, end)`, which wraps past midnight
+/// when `end` is earlier than `start`
+pub fn in_quiet_hours(now: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// Whether the preferences allow the notification at local time `now`
+pub fn allows(
+    prefs: &NotificationPreferences,
+    channel: Channel,
+    event: NotificationEvent,
+    now: NaiveTime,
+) -> bool {
+    let quiet = match (prefs.quiet_hours_start, prefs.quiet_hours_end) {
+        (Some(start), Some(end)) => in_quiet_hours(now, start, end),
+        _ => false,
+    };
+    !quiet && is_enabled(prefs, channel, event)
+}
+
+/// Load the user's preferences and check them against their local time now.
+/// Unreadable preferences fall back to the defaults (everything on).
+pub async fn should_notify(
+    db: &PgPool,
+    user_id: i64,
+    channel: Channel,
+    event: NotificationEvent,
+) -> bool {
+    let prefs = match notification_preferences::get_preferences(db, user_id).await {
+        Ok(prefs) => prefs.unwrap_or_default(),
+        Err(e) => {
+            eprintln!(
+                "[notifications] Failed to load preferences for user {}: {}",
+                user_id, e
+            );
+            NotificationPreferences::default()
+        }
+    };
+    let now = if prefs.quiet_hours_start.is_some() {
+        let tz = timezone::user_timezone(db, user_id).await;
+        Utc::now().with_timezone(&tz).time()
+    } else {
+        Utc::now().time()
+    };
+    allows(&prefs, channel, event, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_past_midnight() {
+        assert!(in_quiet_hours(at(23, 0), at(22, 0), at(8, 0)));
+        assert!(in_quiet_hours(at(3, 30), at(22, 0), at(8, 0)));
+        assert!(!in_quiet_hours(at(8, 0), at(22, 0), at(8, 0)));
+        assert!(!in_quiet_hours(at(12, 0), at(22, 0), at(8, 0)));
+
+        assert!(in_quiet_hours(at(13, 0), at(12, 0), at(14, 0)));
+        assert!(!in_quiet_hours(at(14, 0), at(12, 0), at(14, 0)));
+    }
+
+    #[test]
+    fn test_allows_checks_toggles_and_quiet_hours() {
+        use Channel::*;
+        use NotificationEvent::*;
+
+        let prefs = NotificationPreferences {
+            muted: vec![muted_key(Push, Content)],
+            quiet_hours_start: Some(at(22, 0)),
+            quiet_hours_end: Some(at(7, 0)),
+        };
+        assert!(!allows(&prefs, Push, Content, at(12, 0)));
+        assert!(allows(&prefs, Push, Export, at(12, 0)));
+        assert!(allows(&prefs, Email, Content, at(12, 0)));
+        assert!(!allows(&prefs, Push, Export, at(23, 0)));
+
+        let defaults = NotificationPreferences::default();
+        assert!(allows(&defaults, Slack, Reconnect, at(3, 0)));
+    }
+}
@@ -0,0 +1,77 @@
+//! Object storage behind a trait so handlers can be tested without GCS
+//!
+//! Request handlers read and write object bytes through `AppState::store`.
+//! Background workers still take the GCS client and local path directly.
+
+use async_trait::async_trait;
+use google_cloud_storage::client::Storage;
+use std::path::PathBuf;
+
+use crate::storage::{self, Buckets};
+
+pub type StoreError = Box<dyn std::error::Error + Send + Sync>;
+
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Read a whole object
+    async fn get(&self, path: &str) -> Result<Vec<u8>, StoreError>;
+    /// Create or replace an object
+    async fn put(&self, path: &str, data: &[u8]) -> Result<(), StoreError>;
+    /// Delete an object
+    async fn delete(&self, path: &str) -> Result<(), StoreError>;
+}
+
+/// Local disk when LOCAL_STORAGE_PATH is set, otherwise GCS
+pub struct ConfiguredStore {
+    gcs: Option<Storage>,
+    local_storage_path: Option<PathBuf>,
+    buckets: Buckets,
+}
+
+impl ConfiguredStore {
+    pub fn new(
+        gcs: Option<Storage>,
+        local_storage_path: Option<PathBuf>,
+        buckets: Buckets,
+    ) -> Self {
+        Self {
+            gcs,
+            local_storage_path,
+            buckets,
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ConfiguredStore {
+    async fn get(&self, path: &str) -> Result<Vec<u8>, StoreError> {
+        storage::download_capture(
+            self.gcs.as_ref(),
+            self.local_storage_path.as_ref(),
+            &self.buckets,
+            path,
+        )
+        .await
+    }
+
+    async fn put(&self, path: &str, data: &[u8]) -> Result<(), StoreError> {
+        storage::upload_data(
+            self.gcs.as_ref(),
+            self.local_storage_path.as_ref(),
+            &self.buckets,
+            path,
+            data,
+        )
+        .await
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), StoreError> {
+        storage::delete_object(
+            self.gcs.as_ref(),
+            self.local_storage_path.as_ref(),
+            &self.buckets,
+            path,
+        )
+        .await
+    }
+}
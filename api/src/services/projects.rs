@@ -0,0 +1,447 @@
+//! Project inference from window titles
+//!
+//! Editor, terminal and code host window titles usually carry the repo name and
+//! sometimes the branch:
+//! - "main.rs — cleoapp" (VS Code, Cursor), "cleoapp – agent.rs" (JetBrains, Zed, Xcode)
+//! - "ian@mbp: ~/code/cleoapp (launch-mode)" (terminals)
+//! - "cleoapp/api/src/main.rs at launch-mode · iantbutler01/cleoapp" (GitHub in a browser)
+//!
+//! Activity ingestion runs each ForegroundSwitch through `infer_project` and
+//! credits the time until the next switch to that project (domain::projects);
+//! tab reports from the extension go through `infer_from_url`. The agent can
+//! tag collateral with one of the user's projects.
+//!
+//! Each project also carries publishing defaults set at PUT /me/projects/:name:
+//! hashtags appended to the agent's drafts (`with_hashtags`), the X account its
+//! content is published from, and whether the agent drafts from it at all.
+
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+
+use crate::agent::injection::{MAX_TWEET_CHARS, tweet_length};
+use crate::dev_signals::TERMINAL_APPS;
+
+/// Most time credited for one foreground slice; a longer gap is probably the
+/// machine sleeping with the window still up
+pub const MAX_SLICE_SECS: i64 = 30 * 60;
+/// Longest project or branch name kept
+pub const MAX_NAME_LEN: usize = 100;
+/// Most default hashtags on a project
+pub const MAX_HASHTAGS: usize = 5;
+/// Longest default hashtag, without the '#'
+pub const MAX_HASHTAG_LEN: usize = 50;
+/// Longest X username
+const MAX_USERNAME_LEN: usize = 15;
+
+/// A project (and branch, when the title shows one) read from a window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectHint {
+    pub name: String,
+    pub branch: Option<String>,
+}
+
+impl ProjectHint {
+    /// The project's user_projects.key
+    pub fn key(&self) -> String {
+        self.name.to_lowercase()
+    }
+}
+
+/// Editors whose title ends with the project: "file — project"
+const PROJECT_LAST_EDITORS: &[&str] = &["code", "visual studio code", "cursor", "windsurf"];
+/// Editors whose title starts with the project: "project – file"
+const PROJECT_FIRST_EDITORS: &[&str] = &[
+    "zed",
+    "xcode",
+    "intellij idea",
+    "pycharm",
+    "webstorm",
+    "goland",
+    "rustrover",
+    "clion",
+    "rubymine",
+    "phpstorm",
+    "android studio",
+];
+const BROWSERS: &[&str] = &[
+    "safari",
+    "google chrome",
+    "chrome",
+    "arc",
+    "firefox",
+    "brave browser",
+    "microsoft edge",
+];
+/// Directories that hold checkouts; the project is the directory below one
+const CODE_ROOTS: &[&str] = &[
+    "code",
+    "src",
+    "dev",
+    "developer",
+    "projects",
+    "repos",
+    "git",
+    "github",
+    "workspace",
+    "work",
+];
+/// Path components and titles that are never a project
+const NOT_PROJECTS: &[&str] = &[
+    "~",
+    "desktop",
+    "documents",
+    "downloads",
+    "tmp",
+    "welcome",
+    "settings",
+    "untitled",
+];
+
+/// Probable project behind a foreground window, if the title gives one away
+pub fn infer_project(application: &str, window_title: &str) -> Option<ProjectHint> {
+    let app = application.trim().to_lowercase();
+    let title = window_title.trim();
+    if PROJECT_LAST_EDITORS.contains(&app.as_str()) {
+        editor_project(title, &app, true)
+    } else if PROJECT_FIRST_EDITORS.contains(&app.as_str()) {
+        editor_project(title, &app, false)
+    } else if TERMINAL_APPS.contains(&app.as_str()) {
+        terminal_project(title)
+    } else if BROWSERS.contains(&app.as_str()) {
+        github_title_project(title)
+    } else {
+        None
+    }
+}
+
+/// Project from a GitHub or GitLab page URL ("/owner/repo/tree/branch/...")
+pub fn infer_from_url(url: &str) -> Option<ProjectHint> {
+    let url = Url::parse(url).ok()?;
+    if !matches!(url.host_str()?, "github.com" | "gitlab.com") {
+        return None;
+    }
+    let mut path = url.path_segments()?.filter(|s| !s.is_empty());
+    let _owner = path.next()?;
+    let repo = path.next()?;
+    // GitLab puts a "-" segment before tree/blob
+    let mut rest = path.skip_while(|s| *s == "-");
+    let branch = match rest.next() {
+        Some("tree" | "blob") => rest.next().and_then(branch_name),
+        _ => None,
+    };
+    Some(ProjectHint {
+        name: project_name(repo.trim_end_matches(".git"))?,
+        branch,
+    })
+}
+
+/// Foreground seconds for each of a run of ForegroundSwitch timestamps (in
+/// order): a switch lasts until the next one, capped at `MAX_SLICE_SECS`. The
+/// last switch has no end yet and gets 0; the next batch credits it.
+pub fn slice_secs(timestamps: &[DateTime<Utc>]) -> Vec<i64> {
+    timestamps
+        .iter()
+        .enumerate()
+        .map(|(i, start)| {
+            timestamps.get(i + 1).map_or(0, |end| {
+                (*end - *start).num_seconds().clamp(0, MAX_SLICE_SECS)
+            })
+        })
+        .collect()
+}
+
+/// A default hashtag as stored: without the '#', letters, digits and
+/// underscores only, and not all digits (X doesn't link those)
+pub fn normalize_hashtag(raw: &str) -> Option<String> {
+    let tag = raw.trim().trim_start_matches('#');
+    (!tag.is_empty()
+        && tag.chars().count() <= MAX_HASHTAG_LEN
+        && tag.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !tag.chars().all(|c| c.is_ascii_digit()))
+    .then(|| tag.to_string())
+}
+
+/// An X username as stored: without the '@'
+pub fn normalize_username(raw: &str) -> Option<String> {
+    let name = raw.trim().trim_start_matches('@');
+    (!name.is_empty()
+        && name.len() <= MAX_USERNAME_LEN
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+    .then(|| name.to_string())
+}
+
+/// `text` with each of `hashtags` it doesn't already carry appended, as long
+/// as the tweet still fits
+pub fn with_hashtags(text: &str, hashtags: &[String]) -> String {
+    let mut text = text.to_string();
+    for tag in hashtags {
+        let tag = format!("#{}", tag);
+        let lower_tag = tag.to_lowercase();
+        let present = text
+            .to_lowercase()
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '#'))
+            .any(|word| word == lower_tag);
+        let candidate = format!("{} {}", text.trim_end(), tag);
+        if !present && tweet_length(&candidate) <= MAX_TWEET_CHARS {
+            text = candidate;
+        }
+    }
+    text
+}
+
+/// "file — project" or "project — file"; a lone segment is too ambiguous to
+/// tell a folder from a file, so it's skipped
+fn editor_project(title: &str, app: &str, project_last: bool) -> Option<ProjectHint> {
+    let segments: Vec<&str> = title
+        .split(" — ")
+        .flat_map(|s| s.split(" – "))
+        .flat_map(|s| s.split(" - "))
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case(app) && *s != "Edited")
+        .collect();
+    if segments.len() < 2 {
+        return None;
+    }
+    let segment = if project_last {
+        segments[segments.len() - 1]
+    } else {
+        segments[0]
+    };
+    // "cleoapp [~/code/cleoapp]", "cleoapp [SSH: devbox]", "cleoapp (Workspace)"
+    let name = segment.split(" [").next()?.trim_end_matches(" (Workspace)");
+    Some(ProjectHint {
+        name: project_name(name)?,
+        branch: None,
+    })
+}
+
+/// The working directory in a terminal title, plus a "(branch)", "[branch]"
+/// or "git:(branch)" right after it
+fn terminal_project(title: &str) -> Option<ProjectHint> {
+    let mut tokens = title.split_whitespace();
+    let path = tokens.by_ref().find_map(path_in)?;
+    let branch = tokens.next().and_then(|token| {
+        let token = token.strip_prefix("git:").unwrap_or(token);
+        token
+            .strip_prefix('(')
+            .and_then(|t| t.strip_suffix(')'))
+            .or_else(|| token.strip_prefix('[').and_then(|t| t.strip_suffix(']')))
+            .and_then(branch_name)
+    });
+    Some(ProjectHint {
+        name: project_from_path(path)?,
+        branch,
+    })
+}
+
+/// The path in "~/code/x", "ian@mbp:~/code/x" or "/Users/ian/code/x"
+fn path_in(token: &str) -> Option<&str> {
+    let start = if token.starts_with('/') {
+        Some(0)
+    } else {
+        token.find("~/").or_else(|| token.find(":/").map(|i| i + 1))
+    }?;
+    Some(token[start..].trim_end_matches([':', ',', '/']))
+}
+
+/// The checkout directory: the one below a code root ("~/code/cleoapp/api" ->
+/// "cleoapp"), skipping "github.com/owner" in Go-style trees, or else the
+/// first directory under home. Paths outside home without a code root are
+/// system directories.
+fn project_from_path(path: &str) -> Option<String> {
+    let mut components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    let under_home = if components.first() == Some(&"~") {
+        components.remove(0);
+        true
+    } else if matches!(components.first(), Some(&"Users" | &"home")) && components.len() >= 2 {
+        components.drain(..2);
+        true
+    } else {
+        false
+    };
+
+    let root = components
+        .iter()
+        .position(|c| CODE_ROOTS.contains(&c.to_lowercase().as_str()));
+    let mut rest = match root {
+        Some(i) => &components[i + 1..],
+        None if under_home => &components[..],
+        None => return None,
+    };
+    if rest.first().is_some_and(|c| c.contains('.')) && rest.len() >= 3 {
+        rest = &rest[2..];
+    }
+    project_name(rest.first()?)
+}
+
+/// "path at branch · owner/repo" or "GitHub - owner/repo: description"
+fn github_title_project(title: &str) -> Option<ProjectHint> {
+    if let Some((head, tail)) = title.rsplit_once(" · ") {
+        let repo = repo_in(tail)?;
+        let branch = head
+            .split(" · ")
+            .next()
+            .and_then(|first| first.rsplit_once(" at "))
+            .filter(|(path, _)| path.contains('/'))
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .and_then(branch_name);
+        return Some(ProjectHint {
+            name: project_name(repo)?,
+            branch,
+        });
+    }
+    let repo = repo_in(title.strip_prefix("GitHub - ")?)?;
+    Some(ProjectHint {
+        name: project_name(repo)?,
+        branch: None,
+    })
+}
+
+/// The repo in a leading "owner/repo" token
+fn repo_in(text: &str) -> Option<&str> {
+    let token = text.split_whitespace().next()?.trim_end_matches(':');
+    let (owner, repo) = token.split_once('/')?;
+    let valid = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    (valid(owner) && valid(repo)).then_some(repo)
+}
+
+fn branch_name(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    (!raw.is_empty()
+        && raw.len() <= MAX_NAME_LEN
+        && !raw.starts_with('-')
+        && !raw.contains(char::is_whitespace))
+    .then(|| raw.to_string())
+}
+
+fn project_name(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    (!raw.is_empty()
+        && raw.chars().count() <= MAX_NAME_LEN
+        && !raw.chars().any(char::is_control)
+        && !NOT_PROJECTS.contains(&raw.to_lowercase().as_str()))
+    .then(|| raw.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hint(name: &str, branch: Option<&str>) -> Option<ProjectHint> {
+        Some(ProjectHint {
+            name: name.to_string(),
+            branch: branch.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn infers_project_from_editor_titles() {
+        assert_eq!(
+            infer_project("Code", "● main.rs — cleoapp"),
+            hint("cleoapp", None)
+        );
+        assert_eq!(
+            infer_project("Cursor", "agent.rs - cleoapp [SSH: devbox] - Cursor"),
+            hint("cleoapp", None)
+        );
+        assert_eq!(
+            infer_project("RustRover", "cleoapp [~/code/cleoapp] – agent.rs"),
+            hint("cleoapp", None)
+        );
+        assert_eq!(
+            infer_project("Xcode", "Cleo — main.swift — Edited"),
+            hint("Cleo", None)
+        );
+        // A lone file or folder name could be either
+        assert_eq!(infer_project("Code", "main.rs"), None);
+        assert_eq!(infer_project("Slack", "general - Acme"), None);
+    }
+
+    #[test]
+    fn infers_project_from_terminal_titles() {
+        assert_eq!(
+            infer_project("iTerm2", "ian@mbp: ~/code/cleoapp/api (launch-mode)"),
+            hint("cleoapp", Some("launch-mode"))
+        );
+        assert_eq!(
+            infer_project("Terminal", "/Users/ian/cleoapp/api git:(main) — -zsh"),
+            hint("cleoapp", Some("main"))
+        );
+        assert_eq!(
+            infer_project("Ghostty", "~/go/src/github.com/ian/tool"),
+            hint("tool", None)
+        );
+        assert_eq!(infer_project("Warp", "~ — -zsh"), None);
+        assert_eq!(infer_project("kitty", "/usr/local/bin"), None);
+    }
+
+    #[test]
+    fn slices_run_until_the_next_switch() {
+        let at = |secs: i64| DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap();
+        assert_eq!(
+            slice_secs(&[at(0), at(90), at(90 + 3 * 3600)]),
+            vec![90, MAX_SLICE_SECS, 0]
+        );
+        assert_eq!(slice_secs(&[]), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn hashtags_are_appended_while_the_tweet_fits() {
+        let tags = vec!["buildinpublic".to_string(), "rustlang".to_string()];
+        assert_eq!(
+            with_hashtags("Shipped launch mode.", &tags),
+            "Shipped launch mode. #buildinpublic #rustlang"
+        );
+        assert_eq!(
+            with_hashtags("Day 30 of #BuildInPublic: shipped", &tags),
+            "Day 30 of #BuildInPublic: shipped #rustlang"
+        );
+        let long = "x".repeat(MAX_TWEET_CHARS - 5);
+        assert_eq!(with_hashtags(&long, &tags), long);
+
+        assert_eq!(
+            normalize_hashtag(" #rust_lang "),
+            Some("rust_lang".to_string())
+        );
+        assert_eq!(normalize_hashtag("#2026"), None);
+        assert_eq!(normalize_hashtag("build in public"), None);
+        assert_eq!(
+            normalize_username("@cleo_app"),
+            Some("cleo_app".to_string())
+        );
+        assert_eq!(normalize_username("cleo.app"), None);
+    }
+
+    #[test]
+    fn infers_project_from_code_host_pages() {
+        assert_eq!(
+            infer_project(
+                "Google Chrome",
+                "cleoapp/api/src/main.rs at launch-mode · iantbutler01/cleoapp - Google Chrome"
+            ),
+            hint("cleoapp", Some("launch-mode"))
+        );
+        assert_eq!(
+            infer_project(
+                "Safari",
+                "Add projects by ian · Pull Request #12 · iantbutler01/cleoapp"
+            ),
+            hint("cleoapp", None)
+        );
+        assert_eq!(infer_project("Arc", "Pricing · Stripe"), None);
+        assert_eq!(
+            infer_from_url("https://github.com/iantbutler01/cleoapp/tree/launch-mode/api"),
+            hint("cleoapp", Some("launch-mode"))
+        );
+        assert_eq!(
+            infer_from_url("https://gitlab.com/acme/widgets/-/blob/main/README.md"),
+            hint("widgets", Some("main"))
+        );
+        assert_eq!(infer_from_url("https://github.com/settings"), None);
+        assert_eq!(infer_from_url("https://example.com/a/b"), None);
+    }
+}
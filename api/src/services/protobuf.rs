@@ -0,0 +1,155 @@
+//! Protobuf request bodies for the daemon's ingestion endpoints
+//!
+//! `/activity` and `/activity/summary` accept `application/x-protobuf` as a
+//! smaller, cheaper-to-parse alternative to JSON (schema in
+//! `proto/ingest.proto`). `ValidBody<T>` picks the decoder from the
+//! Content-Type and hands the handler the same `T` that `ValidJson<T>` would,
+//! so both encodings go through the same validation and domain code.
+
+use axum::{
+    extract::{FromRequest, Request},
+    http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
+};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use prost::Message;
+use serde::de::DeserializeOwned;
+
+use super::error::ApiError;
+use super::validation::{ValidJson, Validate};
+
+/// Content-Type of protobuf request bodies
+pub const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Body types that can also be decoded from a protobuf message
+pub trait FromProto: Sized {
+    type Message: Message + Default;
+
+    /// Convert the decoded message; failures become 422 responses
+    fn from_proto(message: Self::Message) -> Result<Self, ApiError>;
+}
+
+/// `ValidJson<T>` that also accepts `T`'s protobuf encoding
+pub struct ValidBody<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidBody<T>
+where
+    T: DeserializeOwned + FromProto + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !is_protobuf(req.headers()) {
+            let ValidJson(value) = ValidJson::<T>::from_request(req, state).await?;
+            return Ok(ValidBody(value));
+        }
+        let body = Bytes::from_request(req, state).await.map_err(|e| {
+            if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+                ApiError::PayloadTooLarge
+            } else {
+                ApiError::BadRequest(e.body_text())
+            }
+        })?;
+        let message = T::Message::decode(body)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid protobuf body: {}", e)))?;
+        let value = T::from_proto(message)?;
+        value.validate()?;
+        Ok(ValidBody(value))
+    }
+}
+
+fn is_protobuf(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|mime| {
+            let mime = mime.trim();
+            mime.eq_ignore_ascii_case(PROTOBUF_CONTENT_TYPE)
+                || mime.eq_ignore_ascii_case("application/protobuf")
+        })
+}
+
+/// Milliseconds since the epoch as a timestamp
+pub fn timestamp_from_millis(ms: i64, field: &str) -> Result<DateTime<Utc>, ApiError> {
+    DateTime::from_timestamp_millis(ms)
+        .ok_or_else(|| ApiError::Unprocessable(format!("{} is out of range", field)))
+}
+
+/// Unsigned protobuf count as the i32 the JSON body would carry
+pub fn count_from_u32(value: u32, field: &str) -> Result<i32, ApiError> {
+    i32::try_from(value).map_err(|_| ApiError::Unprocessable(format!("{} is too large", field)))
+}
+
+/// POST /activity
+#[derive(Clone, PartialEq, Message)]
+pub struct ActivityBatch {
+    #[prost(message, repeated, tag = "1")]
+    pub events: Vec<ActivityEvent>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActivityEvent {
+    /// Milliseconds since the Unix epoch
+    #[prost(int64, tag = "1")]
+    pub timestamp_ms: i64,
+    #[prost(int64, tag = "2")]
+    pub interval_id: i64,
+    #[prost(int64, optional, tag = "3")]
+    pub session_id: Option<i64>,
+    #[prost(oneof = "activity_event::Event", tags = "4, 5")]
+    pub event: Option<activity_event::Event>,
+}
+
+pub mod activity_event {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "4")]
+        ForegroundSwitch(super::ForegroundSwitch),
+        #[prost(message, tag = "5")]
+        MouseClick(super::MouseClick),
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ForegroundSwitch {
+    #[prost(string, tag = "1")]
+    pub new_active: String,
+    #[prost(string, tag = "2")]
+    pub window_title: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MouseClick {}
+
+/// POST /activity/summary
+#[derive(Clone, PartialEq, Message)]
+pub struct ActivitySummaryBatch {
+    #[prost(message, repeated, tag = "1")]
+    pub summaries: Vec<ActivitySummary>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActivitySummary {
+    #[prost(int64, tag = "1")]
+    pub interval_id: i64,
+    #[prost(int64, optional, tag = "2")]
+    pub session_id: Option<i64>,
+    #[prost(int64, tag = "3")]
+    pub period_start_ms: i64,
+    #[prost(int64, tag = "4")]
+    pub period_end_ms: i64,
+    /// App in the foreground longest during the interval
+    #[prost(string, optional, tag = "5")]
+    pub top_app: Option<String>,
+    #[prost(uint32, tag = "6")]
+    pub app_switches: u32,
+    #[prost(uint32, tag = "7")]
+    pub clicks: u32,
+    #[prost(uint32, tag = "8")]
+    pub keypresses: u32,
+    /// Seconds with recent keyboard or mouse input
+    #[prost(uint32, tag = "9")]
+    pub active_secs: u32,
+}
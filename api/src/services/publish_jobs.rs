@@ -0,0 +1,115 @@
+//! In-process publish jobs with progress reporting
+//!
+//! POST /tweets/:id/publish?async=true starts a job and returns its id right
+//! away; the upload and post run in a spawned task that reports each step here.
+//! Clients follow along with GET /publish-jobs/:id/events (Server-Sent Events)
+//! or poll GET /publish-jobs/:id. Jobs live in this process only, so the
+//! events must be read from the instance that started the job; finished jobs
+//! are forgotten after JOB_TTL_SECS.
+
+use base64::Engine;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// How long a finished job's outcome stays readable
+const JOB_TTL_SECS: u64 = 15 * 60;
+
+/// Progress of a publish, as sent to clients
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PublishProgress {
+    Queued,
+    /// Media upload: video chunks (INIT/APPEND) or images, by count
+    Uploading {
+        segment: usize,
+        total: usize,
+        percent: u8,
+    },
+    /// X is processing an uploaded video
+    Processing,
+    Posting,
+    Complete {
+        tweet_id: String,
+        text: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl PublishProgress {
+    /// Complete or Error: no more updates follow
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Complete { .. } | Self::Error { .. })
+    }
+
+    /// SSE event name
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Uploading { .. } => "uploading",
+            Self::Processing => "processing",
+            Self::Posting => "posting",
+            Self::Complete { .. } => "complete",
+            Self::Error { .. } => "error",
+        }
+    }
+}
+
+struct Job {
+    user_id: i64,
+    started_at: Instant,
+    progress: watch::Sender<PublishProgress>,
+}
+
+static JOBS: LazyLock<Mutex<HashMap<String, Job>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Register a job for the user; report progress through the returned sender
+pub fn start(user_id: i64) -> (String, watch::Sender<PublishProgress>) {
+    let bytes: [u8; 16] = rand::rng().random();
+    let job_id = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+    let (progress, _) = watch::channel(PublishProgress::Queued);
+
+    let mut jobs = JOBS.lock().unwrap();
+    let ttl = Duration::from_secs(JOB_TTL_SECS);
+    jobs.retain(|_, job| !(job.progress.borrow().is_finished() && job.started_at.elapsed() > ttl));
+    jobs.insert(
+        job_id.clone(),
+        Job {
+            user_id,
+            started_at: Instant::now(),
+            progress: progress.clone(),
+        },
+    );
+    (job_id, progress)
+}
+
+/// Follow one of the user's jobs. None if it doesn't exist here or isn't theirs.
+pub fn subscribe(job_id: &str, user_id: i64) -> Option<watch::Receiver<PublishProgress>> {
+    JOBS.lock()
+        .unwrap()
+        .get(job_id)
+        .filter(|job| job.user_id == user_id)
+        .map(|job| job.progress.subscribe())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jobs_are_private_to_their_user() {
+        let (job_id, progress) = start(7);
+        assert!(subscribe(&job_id, 8).is_none());
+
+        let rx = subscribe(&job_id, 7).unwrap();
+        assert_eq!(*rx.borrow(), PublishProgress::Queued);
+        progress.send_replace(PublishProgress::Posting);
+        assert_eq!(*rx.borrow(), PublishProgress::Posting);
+        assert!(!rx.borrow().is_finished());
+    }
+}
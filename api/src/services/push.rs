@@ -1,4 +1,5 @@
 use crate::domain::push as domain_push;
+use crate::services::notifications::{self, Channel, NotificationEvent};
 use serde::Serialize;
 use sqlx::PgPool;
 use web_push::{
@@ -69,8 +70,94 @@ pub async fn notify_new_content(
     user_id: i64,
     content_count: usize,
 ) -> Result<(), String> {
-    let private_key = match std::env::var("VAPID_PRIVATE_KEY") {
-        Ok(key) if !key.is_empty() => key,
+    let payload = PushPayload {
+        title: "Cleo".to_string(),
+        body: if content_count == 1 {
+            "1 new item is ready".to_string()
+        } else {
+            format!("{} new items are ready", content_count)
+        },
+        tag: "cleo-content".to_string(),
+        data: PushPayloadData {
+            url: "/?view=queue".to_string(),
+            kind: NotificationEvent::Content.as_str().to_string(),
+            count: content_count,
+        },
+    };
+
+    send_to_user(db, user_id, NotificationEvent::Content, &payload).await
+}
+
+/// Tell the user their data export is ready; `download_url` opens the zip directly
+pub async fn notify_export_ready(
+    db: &PgPool,
+    user_id: i64,
+    download_url: &str,
+) -> Result<(), String> {
+    let payload = PushPayload {
+        title: "Cleo".to_string(),
+        body: "Your data export is ready to download".to_string(),
+        tag: "cleo-export".to_string(),
+        data: PushPayloadData {
+            url: download_url.to_string(),
+            kind: NotificationEvent::Export.as_str().to_string(),
+            count: 1,
+        },
+    };
+
+    send_to_user(db, user_id, NotificationEvent::Export, &payload).await
+}
+
+/// Tell the user X access was lost; the link opens the app's reconnect prompt
+pub async fn notify_reconnect_needed(db: &PgPool, user_id: i64) -> Result<(), String> {
+    let payload = PushPayload {
+        title: "Cleo".to_string(),
+        body: "Reconnect your X account to keep publishing".to_string(),
+        tag: "cleo-reconnect".to_string(),
+        data: PushPayloadData {
+            url: "/?reconnect=twitter".to_string(),
+            kind: NotificationEvent::Reconnect.as_str().to_string(),
+            count: 1,
+        },
+    };
+
+    send_to_user(db, user_id, NotificationEvent::Reconnect, &payload).await
+}
+
+/// Tell the user their "week in review" thread is drafted and waiting for approval
+pub async fn notify_weekly_recap(
+    db: &PgPool,
+    user_id: i64,
+    tweet_count: usize,
+) -> Result<(), String> {
+    let payload = PushPayload {
+        title: "Cleo".to_string(),
+        body: "Your week in review thread is ready to approve".to_string(),
+        tag: "cleo-recap".to_string(),
+        data: PushPayloadData {
+            url: "/?view=queue".to_string(),
+            kind: NotificationEvent::Recap.as_str().to_string(),
+            count: tweet_count,
+        },
+    };
+
+    send_to_user(db, user_id, NotificationEvent::Recap, &payload).await
+}
+
+/// Send to every subscribed browser, unless the user's notification
+/// preferences hold this event back right now
+async fn send_to_user(
+    db: &PgPool,
+    user_id: i64,
+    event: NotificationEvent,
+    payload: &PushPayload,
+) -> Result<(), String> {
+    if !notifications::should_notify(db, user_id, Channel::Push, event).await {
+        return Ok(());
+    }
+
+    let private_key = match crate::config::get().push.vapid_private_key.clone() {
+        Some(key) if !key.is_empty() => key,
         _ => {
             eprintln!(
                 "[push] Missing VAPID_PRIVATE_KEY; skipping push notification for user {}",
@@ -89,22 +176,7 @@ pub async fn notify_new_content(
     }
 
     let client = IsahcWebPushClient::new().map_err(|error| error.to_string())?;
-
-    let payload = PushPayload {
-        title: "Cleo".to_string(),
-        body: if content_count == 1 {
-            "1 new item is ready".to_string()
-        } else {
-            format!("{} new items are ready", content_count)
-        },
-        tag: "cleo-content".to_string(),
-        data: PushPayloadData {
-            url: "/?view=queue".to_string(),
-            kind: "content".to_string(),
-            count: content_count,
-        },
-    };
-    let payload_bytes = serde_json::to_vec(&payload).map_err(|error| error.to_string())?;
+    let payload_bytes = serde_json::to_vec(payload).map_err(|error| error.to_string())?;
 
     for subscription in subscriptions {
         if let Err(error) =
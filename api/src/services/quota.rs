@@ -0,0 +1,195 @@
+//! Per-user capture ingestion quotas
+//!
+//! A user's tier (`users.tier`) selects a `TierQuota` from config [quotas]:
+//! captures and bytes per UTC day, and total stored bytes. `capture_batch`
+//! checks the allowance before reading any part and stops admitting parts once
+//! it runs out. Over the daily allowance the API answers 429 with `retry_after`
+//! (seconds until the UTC day rolls over); over the storage allowance it answers
+//! 402, which only an upgrade or deleted captures clear. Both bodies, and
+//! successful batch responses, carry the remaining quota so the daemon can slow
+//! its capture cadence instead of retrying.
+
+use chrono::{DateTime, Days, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::config::{self, TierQuota};
+use crate::domain::{captures, users};
+
+/// What is left of a user's quota; None means unlimited
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuotaRemaining {
+    pub captures: Option<u64>,
+    pub bytes: Option<u64>,
+    pub storage_bytes: Option<u64>,
+    /// When the daily counters reset
+    pub resets_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaKind {
+    /// Daily captures or bytes used up; clears at `resets_at`
+    Daily,
+    /// Stored bytes at the tier limit
+    Storage,
+}
+
+/// Rejection carried by `ApiError::QuotaExceeded`
+#[derive(Debug, Clone)]
+pub struct QuotaExceeded {
+    pub kind: QuotaKind,
+    pub remaining: QuotaRemaining,
+}
+
+impl QuotaExceeded {
+    /// Seconds until uploads can succeed again; None when waiting won't help
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self.kind {
+            QuotaKind::Daily => {
+                let secs = (self.remaining.resets_at - Utc::now()).num_seconds();
+                Some(secs.max(1) as u64)
+            }
+            QuotaKind::Storage => None,
+        }
+    }
+}
+
+/// Remaining allowance for one batch upload
+#[derive(Debug, Clone)]
+pub struct CaptureAllowance {
+    remaining: QuotaRemaining,
+}
+
+/// Start of the next UTC day
+fn next_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = now.date_naive() + Days::new(1);
+    tomorrow
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc()
+}
+
+/// `limit - used`, or None for an unlimited (0) limit
+fn left(limit: u64, used: i64) -> Option<u64> {
+    (limit > 0).then(|| limit.saturating_sub(used.max(0) as u64))
+}
+
+impl CaptureAllowance {
+    pub fn new(quota: &TierQuota, usage: &captures::CaptureUsage, now: DateTime<Utc>) -> Self {
+        Self {
+            remaining: QuotaRemaining {
+                captures: left(quota.daily_captures, usage.captures_today),
+                bytes: left(quota.daily_bytes, usage.bytes_today),
+                storage_bytes: left(quota.storage_bytes, usage.stored_bytes),
+                resets_at: next_reset(now),
+            },
+        }
+    }
+
+    /// Reject the upload outright when nothing is left
+    pub fn check(&self) -> Result<(), QuotaExceeded> {
+        let r = &self.remaining;
+        let kind = if r.storage_bytes == Some(0) {
+            QuotaKind::Storage
+        } else if r.captures == Some(0) || r.bytes == Some(0) {
+            QuotaKind::Daily
+        } else {
+            return Ok(());
+        };
+        Err(QuotaExceeded {
+            kind,
+            remaining: r.clone(),
+        })
+    }
+
+    /// Count a part of `size` bytes against the allowance; false if it doesn't fit
+    pub fn admit(&mut self, size: u64) -> bool {
+        let r = &mut self.remaining;
+        let fits = r.captures.is_none_or(|c| c > 0)
+            && r.bytes.is_none_or(|b| b >= size)
+            && r.storage_bytes.is_none_or(|s| s >= size);
+        if fits {
+            r.captures = r.captures.map(|c| c - 1);
+            r.bytes = r.bytes.map(|b| b - size);
+            r.storage_bytes = r.storage_bytes.map(|s| s - size);
+        }
+        fits
+    }
+
+    pub fn remaining(&self) -> &QuotaRemaining {
+        &self.remaining
+    }
+}
+
+/// Load the user's tier quota and today's usage
+pub async fn capture_allowance(db: &PgPool, user_id: i64) -> Result<CaptureAllowance, sqlx::Error> {
+    let now = Utc::now();
+    let day_start = next_reset(now) - Days::new(1);
+
+    let tier = users::get_user_tier(db, user_id).await?;
+    let quotas = &config::get().quotas;
+    let quota = match tier.as_deref() {
+        Some("pro") => &quotas.pro,
+        _ => &quotas.free,
+    };
+
+    let usage = captures::get_capture_usage(db, user_id, day_start).await?;
+    Ok(CaptureAllowance::new(quota, &usage, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_allowance_admits_until_exhausted() {
+        let quota = TierQuota {
+            daily_captures: 3,
+            daily_bytes: 100,
+            storage_bytes: 0,
+        };
+        let usage = captures::CaptureUsage {
+            captures_today: 1,
+            bytes_today: 40,
+            stored_bytes: 1_000_000,
+        };
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 22, 0, 0).unwrap();
+        let mut allowance = CaptureAllowance::new(&quota, &usage, now);
+        assert!(allowance.check().is_ok());
+        assert_eq!(
+            allowance.remaining().resets_at,
+            Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap()
+        );
+        assert_eq!(allowance.remaining().storage_bytes, None);
+
+        assert!(!allowance.admit(61), "over the daily byte allowance");
+        assert!(allowance.admit(50));
+        assert!(allowance.admit(10));
+        assert!(!allowance.admit(0), "out of captures");
+        assert_eq!(allowance.remaining().bytes, Some(0));
+
+        let exhausted = CaptureAllowance::new(
+            &quota,
+            &captures::CaptureUsage {
+                captures_today: 3,
+                bytes_today: 0,
+                stored_bytes: 0,
+            },
+            now,
+        );
+        let err = exhausted.check().unwrap_err();
+        assert_eq!(err.kind, QuotaKind::Daily);
+        assert!(err.retry_after_secs().is_some());
+
+        let full = TierQuota {
+            storage_bytes: 10,
+            ..quota
+        };
+        let err = CaptureAllowance::new(&full, &usage, now)
+            .check()
+            .unwrap_err();
+        assert_eq!(err.kind, QuotaKind::Storage);
+        assert_eq!(err.retry_after_secs(), None);
+    }
+}
@@ -0,0 +1,120 @@
+//! HTTP Range and ETag helpers for serving media files
+//!
+//! Only single byte ranges are honoured; multi-range requests get the full
+//! body, which RFC 9110 allows.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What to send for a request's Range header
+#[derive(Debug, PartialEq, Eq)]
+pub enum ByteRange {
+    /// No (usable) Range header: send the whole file with 200
+    Full,
+    /// Inclusive byte range to send with 206
+    Partial { start: u64, end: u64 },
+    /// Range starts past the end of the file: 416
+    Unsatisfiable,
+}
+
+/// Parse a Range header value against a file of `len` bytes
+pub fn parse_range(header: Option<&str>, len: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    match (start.trim(), end.trim()) {
+        // bytes=-N: last N bytes
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(n) => ByteRange::Partial {
+                start: len.saturating_sub(n),
+                end: len - 1,
+            },
+            Err(_) => ByteRange::Full,
+        },
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return ByteRange::Full;
+            };
+            if start >= len {
+                return ByteRange::Unsatisfiable;
+            }
+            let end = if end.is_empty() {
+                len - 1
+            } else {
+                match end.parse::<u64>() {
+                    Ok(end) if end >= start => end.min(len - 1),
+                    _ => return ByteRange::Full,
+                }
+            };
+            ByteRange::Partial { start, end }
+        }
+    }
+}
+
+/// Validator from modification time and size (same scheme as nginx).
+/// Media paths are immutable, so this is treated as a strong ETag.
+pub fn etag(len: u64, modified: Option<SystemTime>) -> String {
+    let mtime = modified
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!("\"{:x}-{:x}\"", mtime, len)
+}
+
+/// Whether an If-None-Match value matches our ETag (weak comparison)
+pub fn etag_matches(header: &str, etag: &str) -> bool {
+    let ours = etag.trim_start_matches("W/");
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == ours)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range(None, 100), ByteRange::Full);
+        assert_eq!(
+            parse_range(Some("bytes=0-9"), 100),
+            ByteRange::Partial { start: 0, end: 9 }
+        );
+        assert_eq!(
+            parse_range(Some("bytes=90-"), 100),
+            ByteRange::Partial { start: 90, end: 99 }
+        );
+        assert_eq!(
+            parse_range(Some("bytes=-10"), 100),
+            ByteRange::Partial { start: 90, end: 99 }
+        );
+        assert_eq!(
+            parse_range(Some("bytes=50-500"), 100),
+            ByteRange::Partial { start: 50, end: 99 }
+        );
+        assert_eq!(
+            parse_range(Some("bytes=100-"), 100),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("items=0-1"), 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let tag = etag(100, None);
+        assert!(etag_matches(&tag, &tag));
+        assert!(etag_matches(&format!("W/{}", tag), &tag));
+        assert!(etag_matches("*", &tag));
+        assert!(!etag_matches("\"other\"", &tag));
+    }
+}
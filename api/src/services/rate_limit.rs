@@ -1,11 +1,32 @@
-//! Per-user rate limiting for daemon API endpoints
+//! Rate limiting for daemon endpoints and expensive user-facing routes
 //!
 //! Uses a simple token bucket algorithm with in-memory storage.
-//! Tokens are stored per user_id and refill over time.
+//! Tokens are stored per key (user id, API token or IP) and refill over time.
+//!
+//! `DAEMON_RATE_LIMITER` is checked inline by the capture/activity handlers,
+//! and `EMAIL_LINK_LIMITER` by the sign-in link senders.
+//! Other routes get a policy from `policy_for`, enforced by the
+//! `enforce_route_limits` middleware, which also sets `RateLimit-*` headers.
 
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, HeaderValue, Method, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::CookieJar;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::sync::{LazyLock, Mutex};
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::{Arc, LazyLock, Mutex};
 use std::time::{Duration, Instant};
+use tower_governor::key_extractor::{KeyExtractor, SmartIpKeyExtractor};
+
+use super::cookies::config::ACCESS_TOKEN_NAME;
+use super::error::ApiError;
+use super::session;
+use crate::AppState;
 
 /// Rate limiter configuration
 pub struct RateLimitConfig {
@@ -15,18 +36,42 @@ pub struct RateLimitConfig {
     pub refill_rate: f64,
 }
 
-struct UserBucket {
+impl RateLimitConfig {
+    /// `limit` requests per `window`, all of which may be used as a burst
+    pub fn per(limit: u32, window: Duration) -> Self {
+        Self {
+            max_tokens: limit,
+            refill_rate: limit as f64 / window.as_secs_f64(),
+        }
+    }
+}
+
+struct Bucket {
     tokens: f64,
     last_update: Instant,
 }
 
-/// Per-user rate limiter using token bucket algorithm
-pub struct UserRateLimiter {
+/// Outcome of one `acquire`, with the numbers for RateLimit-* headers
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the bucket is full again
+    pub reset_secs: u64,
+    /// Seconds until the next request would be allowed (0 when allowed)
+    pub retry_after_secs: u64,
+}
+
+/// Rate limiter using token bucket algorithm, one bucket per key
+pub struct KeyedRateLimiter<K> {
     config: RateLimitConfig,
-    buckets: Mutex<HashMap<i64, UserBucket>>,
+    buckets: Mutex<HashMap<K, Bucket>>,
 }
 
-impl UserRateLimiter {
+/// Per-user rate limiter
+pub type UserRateLimiter = KeyedRateLimiter<i64>;
+
+impl<K: Hash + Eq> KeyedRateLimiter<K> {
     pub fn new(config: RateLimitConfig) -> Self {
         Self {
             config,
@@ -34,35 +79,51 @@ impl UserRateLimiter {
         }
     }
 
-    /// Check if a request is allowed for the given user_id.
+    /// Check if a request is allowed for the given key.
     /// Returns true if allowed, false if rate limited.
-    pub fn check(&self, user_id: i64) -> bool {
+    pub fn check(&self, key: K) -> bool {
+        self.acquire(key).allowed
+    }
+
+    /// Try to consume a token for `key` and report the bucket state
+    pub fn acquire(&self, key: K) -> RateLimitDecision {
         let mut buckets = self.buckets.lock().unwrap();
         let now = Instant::now();
+        let max = self.config.max_tokens as f64;
+        let rate = self.config.refill_rate;
 
-        let bucket = buckets.entry(user_id).or_insert_with(|| UserBucket {
-            tokens: self.config.max_tokens as f64,
+        let bucket = buckets.entry(key).or_insert_with(|| Bucket {
+            tokens: max,
             last_update: now,
         });
 
         // Refill tokens based on time elapsed
         let elapsed = now.duration_since(bucket.last_update);
-        let refill = elapsed.as_secs_f64() * self.config.refill_rate;
-        bucket.tokens = (bucket.tokens + refill).min(self.config.max_tokens as f64);
+        let refill = elapsed.as_secs_f64() * rate;
+        bucket.tokens = (bucket.tokens + refill).min(max);
         bucket.last_update = now;
 
         // Try to consume a token
-        if bucket.tokens >= 1.0 {
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
             bucket.tokens -= 1.0;
-            true
-        } else {
-            false
+        }
+
+        RateLimitDecision {
+            allowed,
+            limit: self.config.max_tokens,
+            remaining: bucket.tokens.floor() as u32,
+            reset_secs: ((max - bucket.tokens) / rate).ceil() as u64,
+            retry_after_secs: if allowed {
+                0
+            } else {
+                ((1.0 - bucket.tokens) / rate).ceil() as u64
+            },
         }
     }
 
-    /// Clean up old entries (users who haven't made requests in a while)
+    /// Clean up old entries (keys that haven't made requests in a while)
     /// Call this periodically to prevent memory growth
-    #[allow(dead_code)]
     pub fn cleanup(&self, max_age: Duration) {
         let mut buckets = self.buckets.lock().unwrap();
         let now = Instant::now();
@@ -79,3 +140,195 @@ pub static DAEMON_RATE_LIMITER: LazyLock<UserRateLimiter> = LazyLock::new(|| {
         refill_rate: 2.0,
     })
 });
+
+/// Sign-in links sent to one address (lowercased): 3 per 15 minutes, so the
+/// endpoint can't be used to flood someone's inbox
+pub static EMAIL_LINK_LIMITER: LazyLock<KeyedRateLimiter<String>> =
+    LazyLock::new(|| KeyedRateLimiter::new(RateLimitConfig::per(3, Duration::from_secs(15 * 60))));
+
+/// Who a route limit applies to: the session user when there is one, otherwise
+/// the API token, otherwise the client IP
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientKey {
+    User(i64),
+    ApiToken([u8; 32]),
+    Ip(IpAddr),
+}
+
+pub type RouteRateLimiter = KeyedRateLimiter<ClientKey>;
+
+/// POST /agent/run and /agent/highlight, shared: 5 per minute
+static AGENT_RUN_LIMITER: LazyLock<RouteRateLimiter> =
+    LazyLock::new(|| RouteRateLimiter::new(RateLimitConfig::per(5, Duration::from_secs(60))));
+
+/// Tweet and thread publishing, shared: 30 per minute
+static PUBLISH_LIMITER: LazyLock<RouteRateLimiter> =
+    LazyLock::new(|| RouteRateLimiter::new(RateLimitConfig::per(30, Duration::from_secs(60))));
+
+/// LLM rewrites of a draft (regenerate, translate) and on-demand weekly recaps,
+/// shared: 20 per minute
+static REGENERATE_LIMITER: LazyLock<RouteRateLimiter> =
+    LazyLock::new(|| RouteRateLimiter::new(RateLimitConfig::per(20, Duration::from_secs(60))));
+
+/// Unauthenticated public pages (build logs, widgets, share links), usually keyed by IP,
+/// shared: 60 per minute
+static PUBLIC_LIMITER: LazyLock<RouteRateLimiter> =
+    LazyLock::new(|| RouteRateLimiter::new(RateLimitConfig::per(60, Duration::from_secs(60))));
+
+/// Sign-in link sends per caller, shared: 10 per hour
+static EMAIL_SEND_LIMITER: LazyLock<RouteRateLimiter> =
+    LazyLock::new(|| RouteRateLimiter::new(RateLimitConfig::per(10, Duration::from_secs(60 * 60))));
+
+/// Policy for a route, matched on method and the route pattern as registered
+fn policy_for(method: &Method, path: &str) -> Option<&'static RouteRateLimiter> {
+    let limiter = match (method.as_str(), path) {
+        ("POST", "/agent/run") | ("POST", "/agent/highlight") => &AGENT_RUN_LIMITER,
+        ("POST", "/tweets/{id}/publish")
+        | ("GET", "/tweets/{id}/publish/ws")
+        | ("POST", "/tweets/{id}/replace")
+        | ("POST", "/threads/{id}/publish")
+        | ("POST", "/longform/{id}/publish") => &PUBLISH_LIMITER,
+        ("POST", "/tweets/{id}/regenerate")
+        | ("POST", "/tweets/{id}/translate")
+        | ("POST", "/me/weekly-recap/compose") => &REGENERATE_LIMITER,
+        ("GET", "/public/{username}/log")
+        | ("GET", "/public/{username}/now.json")
+        | ("GET", "/shared/{token}") => &PUBLIC_LIMITER,
+        ("POST", "/auth/email/link") | ("POST", "/me/integrations/email") => &EMAIL_SEND_LIMITER,
+        _ => return None,
+    };
+    Some(LazyLock::force(limiter))
+}
+
+/// Identify the caller without touching the database. Invalid session cookies
+/// fall through to the IP, so forged tokens can't drain another user's bucket.
+fn client_key(request: &Request, jwt_secret: &[u8]) -> Option<ClientKey> {
+    let jar = CookieJar::from_headers(request.headers());
+    if let Some(user_id) = jar
+        .get(ACCESS_TOKEN_NAME)
+        .and_then(|c| session::validate_access_token(c.value(), jwt_secret).ok())
+    {
+        return Some(ClientKey::User(user_id));
+    }
+
+    if let Some(token) = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    {
+        return Some(ClientKey::ApiToken(Sha256::digest(token.as_bytes()).into()));
+    }
+
+    SmartIpKeyExtractor.extract(request).ok().map(ClientKey::Ip)
+}
+
+/// Middleware: apply the route's policy from `policy_for`, if any. Must be
+/// added with `Router::layer` so the matched route pattern is available.
+pub async fn enforce_route_limits(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limiter = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| policy_for(request.method(), path.as_str()));
+    let Some(limiter) = limiter else {
+        return next.run(request).await;
+    };
+    let Some(key) = client_key(&request, &state.jwt_secret) else {
+        return next.run(request).await;
+    };
+
+    let decision = limiter.acquire(key);
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        ApiError::RateLimited.into_response()
+    };
+    set_headers(response.headers_mut(), &decision);
+    response
+}
+
+fn set_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert("ratelimit-limit", HeaderValue::from(decision.limit));
+    headers.insert("ratelimit-remaining", HeaderValue::from(decision.remaining));
+    headers.insert("ratelimit-reset", HeaderValue::from(decision.reset_secs));
+    if !decision.allowed {
+        headers.insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(decision.retry_after_secs),
+        );
+    }
+}
+
+pub const CLEANUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Periodically drop buckets that have been idle for an hour
+pub async fn run_cleanup_worker() {
+    let max_age = Duration::from_secs(60 * 60);
+    let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        super::supervisor::heartbeat("rate_limit");
+
+        DAEMON_RATE_LIMITER.cleanup(max_age);
+        for limiter in [
+            &AGENT_RUN_LIMITER,
+            &PUBLISH_LIMITER,
+            &REGENERATE_LIMITER,
+            &EMAIL_SEND_LIMITER,
+        ] {
+            limiter.cleanup(max_age);
+        }
+        EMAIL_LINK_LIMITER.cleanup(max_age);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_reports_remaining_and_retry() {
+        let limiter = KeyedRateLimiter::new(RateLimitConfig::per(2, Duration::from_secs(60)));
+
+        let first = limiter.acquire("a");
+        assert!(first.allowed);
+        assert_eq!((first.limit, first.remaining), (2, 1));
+        assert!(limiter.acquire("a").allowed);
+
+        let denied = limiter.acquire("a");
+        assert!(!denied.allowed);
+        assert_eq!(denied.remaining, 0);
+        assert!(denied.retry_after_secs > 0 && denied.retry_after_secs <= 30);
+
+        // Buckets are independent per key
+        assert!(limiter.acquire("b").allowed);
+    }
+
+    #[test]
+    fn test_policy_lookup() {
+        assert!(policy_for(&Method::POST, "/agent/run").is_some());
+        assert!(policy_for(&Method::POST, "/agent/highlight").is_some());
+        assert!(policy_for(&Method::GET, "/agent/status").is_none());
+        assert!(std::ptr::eq(
+            policy_for(&Method::GET, "/public/{username}/log").unwrap(),
+            policy_for(&Method::GET, "/shared/{token}").unwrap()
+        ));
+        assert!(std::ptr::eq(
+            policy_for(&Method::GET, "/public/{username}/log").unwrap(),
+            policy_for(&Method::GET, "/public/{username}/now.json").unwrap()
+        ));
+        assert!(std::ptr::eq(
+            policy_for(&Method::POST, "/auth/email/link").unwrap(),
+            policy_for(&Method::POST, "/me/integrations/email").unwrap(),
+        ));
+        assert!(std::ptr::eq(
+            policy_for(&Method::POST, "/tweets/{id}/publish").unwrap(),
+            policy_for(&Method::POST, "/threads/{id}/publish").unwrap(),
+        ));
+    }
+}
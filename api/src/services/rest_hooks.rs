@@ -0,0 +1,167 @@
+//! REST hooks and polling triggers for no-code tools (Zapier, Make)
+//!
+//! Both deliver the same payload per generated tweet (`CollateralPayload`).
+//! Polling tools call GET /hooks/new-collateral?since=<largest id seen>; REST
+//! hook tools subscribe a URL via POST /hooks/subscriptions and get one POST per
+//! new tweet from the delivery worker. A receiver answering 410 Gone is
+//! unsubscribed; MAX_FAILURES failed deliveries in a row disable the subscription
+//! until it is subscribed again.
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::domain::hooks::{self, ClaimedSubscription, NewCollateral};
+
+/// The only event so far
+pub const NEW_COLLATERAL_EVENT: &str = "new_collateral";
+pub const DELIVERY_INTERVAL_SECS: u64 = 30;
+/// Retry delay after a failed delivery
+const LEASE_SECS: i64 = 5 * 60;
+const MAX_FAILURES: i32 = 20;
+/// Subscriptions per delivery pass, and tweets per subscription
+const BATCH_SIZE: i64 = 50;
+const DELIVERY_TIMEOUT_SECS: u64 = 10;
+
+/// Trigger payload for a generated tweet. Fields are only ever added.
+#[derive(Debug, Serialize)]
+pub struct CollateralPayload {
+    /// Stable, increasing id; also the polling cursor
+    pub id: i64,
+    pub event: &'static str,
+    pub text: String,
+    /// Why the agent suggested it
+    pub rationale: String,
+    pub thread_id: Option<i64>,
+    pub thread_position: Option<i32>,
+    pub image_capture_ids: Vec<i64>,
+    pub has_video: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<NewCollateral> for CollateralPayload {
+    fn from(c: NewCollateral) -> Self {
+        Self {
+            id: c.id,
+            event: NEW_COLLATERAL_EVENT,
+            text: c.text,
+            rationale: c.rationale,
+            thread_id: c.thread_id,
+            thread_position: c.thread_position,
+            image_capture_ids: c.image_capture_ids,
+            has_video: c.has_video,
+            created_at: c.created_at,
+        }
+    }
+}
+
+/// Deliver new tweets to REST hook subscribers
+pub async fn run_delivery_worker(pool: PgPool) {
+    // Target URLs are checked when subscribed; don't let a redirect take us elsewhere
+    let http = Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(std::time::Duration::from_secs(DELIVERY_TIMEOUT_SECS))
+        .build()
+        .expect("HTTP client");
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(DELIVERY_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+        super::supervisor::heartbeat("hooks");
+
+        let claimed = match hooks::claim_subscriptions(&pool, LEASE_SECS, BATCH_SIZE).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("[hooks] Claim error: {}", e);
+                continue;
+            }
+        };
+        for subscription in claimed {
+            deliver(&pool, &http, &subscription).await;
+        }
+    }
+}
+
+enum Delivery {
+    Delivered,
+    /// The receiver asked to be unsubscribed
+    Gone,
+    Failed(String),
+}
+
+async fn post_payload(
+    http: &Client,
+    subscription: &ClaimedSubscription,
+    payload: &CollateralPayload,
+) -> Delivery {
+    let result = http
+        .post(&subscription.target_url)
+        .header("X-Cleo-Event", payload.event)
+        .header("X-Cleo-Subscription", subscription.id)
+        .json(payload)
+        .send()
+        .await;
+    match result {
+        Ok(resp) if resp.status().is_success() => Delivery::Delivered,
+        Ok(resp) if resp.status() == reqwest::StatusCode::GONE => Delivery::Gone,
+        Ok(resp) => Delivery::Failed(format!("receiver returned {}", resp.status())),
+        Err(e) => Delivery::Failed(e.to_string()),
+    }
+}
+
+/// Post a subscription's undelivered tweets in order, stopping at the first failure
+async fn deliver(pool: &PgPool, http: &Client, subscription: &ClaimedSubscription) {
+    let pending = match hooks::list_new_collateral(
+        pool,
+        subscription.user_id,
+        Some(subscription.cursor_id),
+        BATCH_SIZE,
+    )
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!(
+                "[hooks] List error for subscription {}: {}",
+                subscription.id, e
+            );
+            return;
+        }
+    };
+
+    let mut cursor = subscription.cursor_id;
+    for collateral in pending {
+        let payload = CollateralPayload::from(collateral);
+        match post_payload(http, subscription, &payload).await {
+            Delivery::Delivered => cursor = payload.id,
+            Delivery::Gone => {
+                println!(
+                    "[hooks] Receiver for subscription {} is gone; unsubscribing",
+                    subscription.id
+                );
+                if let Err(e) = hooks::remove_subscription(pool, subscription.id).await {
+                    eprintln!("[hooks] Remove error: {}", e);
+                }
+                return;
+            }
+            Delivery::Failed(error) => {
+                eprintln!(
+                    "[hooks] Delivery of tweet {} to subscription {} failed: {}",
+                    payload.id, subscription.id, error
+                );
+                if let Err(e) =
+                    hooks::fail_delivery(pool, subscription.id, cursor, &error, MAX_FAILURES).await
+                {
+                    eprintln!("[hooks] Fail error: {}", e);
+                }
+                return;
+            }
+        }
+    }
+
+    if let Err(e) = hooks::finish_delivery(pool, subscription.id, cursor).await {
+        eprintln!("[hooks] Finish error: {}", e);
+    }
+}
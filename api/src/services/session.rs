@@ -201,7 +201,7 @@ pub async fn cleanup_expired_tokens(db: &PgPool) -> Result<u64, SessionError> {
 }
 
 // Hex encoding helper since we don't want to add another dependency
-mod hex {
+pub(crate) mod hex {
     const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
 
     pub fn encode(bytes: &[u8]) -> String {
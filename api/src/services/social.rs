@@ -0,0 +1,158 @@
+//! Social network client behind a trait so publish flows can be tested offline
+//!
+//! `TwitterClient` is the only production implementation.
+
+use async_trait::async_trait;
+
+use super::twitter::{
+    AuthorizeRequest, TokenResponse, TweetResponse, TwitterClient, TwitterError, TwitterUser,
+};
+use super::twitter_credentials::CredentialSet;
+use crate::domain::twitter::Poll;
+
+/// Progress callback for chunked uploads: (current_segment, total_segments)
+pub type UploadProgressFn = Box<dyn Fn(usize, usize) + Send + Sync>;
+
+#[async_trait]
+pub trait SocialClient: Send + Sync {
+    /// Build the OAuth authorization URL plus the state/verifier to store
+    fn get_authorize_url(&self, scopes: &[&str]) -> AuthorizeRequest;
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<TokenResponse, TwitterError>;
+
+    /// Refresh from a stored refresh token; the new one comes back ready to store
+    async fn refresh_token(&self, refresh_token: &str) -> Result<TokenResponse, TwitterError>;
+
+    /// Credentials stored refresh tokens are tagged with, if the client has any
+    fn credentials(&self) -> Option<&CredentialSet> {
+        None
+    }
+
+    async fn get_me(&self, access_token: &str) -> Result<TwitterUser, TwitterError>;
+
+    /// Look up a tweet; fails if it doesn't exist or isn't visible to the user
+    async fn get_tweet(
+        &self,
+        access_token: &str,
+        tweet_id: &str,
+    ) -> Result<TweetResponse, TwitterError>;
+
+    async fn post_tweet(
+        &self,
+        access_token: &str,
+        text: &str,
+        in_reply_to: Option<&str>,
+        quote_tweet_id: Option<&str>,
+        media_ids: Option<&[String]>,
+        poll: Option<&Poll>,
+    ) -> Result<TweetResponse, TwitterError>;
+
+    async fn delete_tweet(&self, access_token: &str, tweet_id: &str) -> Result<(), TwitterError>;
+
+    /// Upload media and return its media ID
+    async fn upload_media(
+        &self,
+        access_token: &str,
+        data: &[u8],
+        media_type: &str,
+    ) -> Result<String, TwitterError>;
+
+    /// Chunked upload reporting progress after each segment
+    async fn upload_media_with_progress(
+        &self,
+        access_token: &str,
+        data: &[u8],
+        media_type: &str,
+        on_progress: UploadProgressFn,
+    ) -> Result<String, TwitterError>;
+}
+
+#[async_trait]
+impl SocialClient for TwitterClient {
+    fn get_authorize_url(&self, scopes: &[&str]) -> AuthorizeRequest {
+        TwitterClient::get_authorize_url(self, scopes)
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<TokenResponse, TwitterError> {
+        TwitterClient::exchange_code(self, code, code_verifier).await
+    }
+
+    async fn refresh_token(&self, refresh_token: &str) -> Result<TokenResponse, TwitterError> {
+        TwitterClient::refresh_token(self, refresh_token).await
+    }
+
+    fn credentials(&self) -> Option<&CredentialSet> {
+        Some(TwitterClient::credentials(self))
+    }
+
+    async fn get_me(&self, access_token: &str) -> Result<TwitterUser, TwitterError> {
+        TwitterClient::get_me(self, access_token).await
+    }
+
+    async fn get_tweet(
+        &self,
+        access_token: &str,
+        tweet_id: &str,
+    ) -> Result<TweetResponse, TwitterError> {
+        TwitterClient::get_tweet(self, access_token, tweet_id).await
+    }
+
+    async fn post_tweet(
+        &self,
+        access_token: &str,
+        text: &str,
+        in_reply_to: Option<&str>,
+        quote_tweet_id: Option<&str>,
+        media_ids: Option<&[String]>,
+        poll: Option<&Poll>,
+    ) -> Result<TweetResponse, TwitterError> {
+        TwitterClient::post_tweet(
+            self,
+            access_token,
+            text,
+            in_reply_to,
+            quote_tweet_id,
+            media_ids,
+            poll,
+        )
+        .await
+    }
+
+    async fn delete_tweet(&self, access_token: &str, tweet_id: &str) -> Result<(), TwitterError> {
+        TwitterClient::delete_tweet(self, access_token, tweet_id).await
+    }
+
+    async fn upload_media(
+        &self,
+        access_token: &str,
+        data: &[u8],
+        media_type: &str,
+    ) -> Result<String, TwitterError> {
+        TwitterClient::upload_media(self, access_token, data, media_type).await
+    }
+
+    async fn upload_media_with_progress(
+        &self,
+        access_token: &str,
+        data: &[u8],
+        media_type: &str,
+        on_progress: UploadProgressFn,
+    ) -> Result<String, TwitterError> {
+        TwitterClient::upload_media_chunked_with_progress(
+            self,
+            access_token,
+            data,
+            media_type,
+            on_progress,
+        )
+        .await
+    }
+}
@@ -0,0 +1,180 @@
+//! Background worker supervisor
+//!
+//! Workers started with `spawn` run in their own task; if one panics or returns,
+//! it is restarted after an exponential backoff (reset once a run lasts
+//! BACKOFF_RESET_SECS). Workers call `heartbeat` once per loop iteration so
+//! /admin/workers can show when each last did something, and /readyz reports
+//! not-ready while any worker is crash-looping.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+const MIN_BACKOFF_SECS: u64 = 1;
+const MAX_BACKOFF_SECS: u64 = 300;
+const BACKOFF_RESET_SECS: u64 = 300;
+/// Consecutive failures after which a worker counts as crash-looping
+const CRASH_LOOP_FAILURES: u32 = 3;
+
+/// Current status of one supervised worker
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatus {
+    pub name: &'static str,
+    /// "running" or "restarting" (waiting out the backoff)
+    pub state: &'static str,
+    /// How often the worker is expected to heartbeat
+    pub interval_secs: u64,
+    pub started_at: DateTime<Utc>,
+    pub last_tick: Option<DateTime<Utc>>,
+    /// No heartbeat for over twice the interval. Long jobs can cause this
+    /// without anything being wrong, so it doesn't affect readiness.
+    pub stale: bool,
+    pub restarts: u32,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+}
+
+static WORKERS: LazyLock<Mutex<BTreeMap<&'static str, WorkerStatus>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Run a worker under supervision. `make` is called for every (re)start.
+pub fn spawn<F, Fut>(name: &'static str, interval: Duration, make: F)
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    WORKERS.lock().unwrap().insert(
+        name,
+        WorkerStatus {
+            name,
+            state: "running",
+            interval_secs: interval.as_secs(),
+            started_at: Utc::now(),
+            last_tick: None,
+            stale: false,
+            restarts: 0,
+            consecutive_failures: 0,
+            last_error: None,
+            last_failure_at: None,
+        },
+    );
+
+    tokio::spawn(async move {
+        let mut backoff = MIN_BACKOFF_SECS;
+        loop {
+            let started = Instant::now();
+            update(name, |status| {
+                status.state = "running";
+                status.started_at = Utc::now();
+            });
+
+            let error = match tokio::spawn(make()).await {
+                Ok(()) => "worker exited".to_string(),
+                Err(e) if e.is_panic() => panic_message(e.into_panic()),
+                Err(e) => e.to_string(),
+            };
+
+            if started.elapsed() >= Duration::from_secs(BACKOFF_RESET_SECS) {
+                backoff = MIN_BACKOFF_SECS;
+            }
+            eprintln!(
+                "[supervisor] Worker {} stopped ({}), restarting in {}s",
+                name, error, backoff
+            );
+            update(name, |status| {
+                status.state = "restarting";
+                status.restarts += 1;
+                status.consecutive_failures = if backoff == MIN_BACKOFF_SECS {
+                    1
+                } else {
+                    status.consecutive_failures + 1
+                };
+                status.last_error = Some(error);
+                status.last_failure_at = Some(Utc::now());
+            });
+
+            tokio::time::sleep(Duration::from_secs(backoff)).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+        }
+    });
+}
+
+/// Record that a worker completed a loop iteration
+pub fn heartbeat(name: &'static str) {
+    update(name, |status| status.last_tick = Some(Utc::now()));
+}
+
+fn update(name: &'static str, f: impl FnOnce(&mut WorkerStatus)) {
+    if let Some(status) = WORKERS.lock().unwrap().get_mut(name) {
+        f(status);
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    format!("panicked: {}", message)
+}
+
+/// Status of all supervised workers, by name
+pub fn snapshot() -> Vec<WorkerStatus> {
+    let now = Utc::now();
+    WORKERS
+        .lock()
+        .unwrap()
+        .values()
+        .cloned()
+        .map(|mut status| {
+            let since = status.last_tick.unwrap_or(status.started_at);
+            status.stale = (now - since).num_seconds() > 2 * status.interval_secs as i64 + 60;
+            status
+        })
+        .collect()
+}
+
+/// Names of workers that keep failing right after (re)start
+pub fn crash_looping(workers: &[WorkerStatus]) -> Vec<&'static str> {
+    workers
+        .iter()
+        .filter(|w| w.state == "restarting" && w.consecutive_failures >= CRASH_LOOP_FAILURES)
+        .map(|w| w.name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_panicking_worker_is_restarted() {
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = runs.clone();
+        spawn("test-panicker", Duration::from_secs(1), move || {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                heartbeat("test-panicker");
+                panic!("boom");
+            }
+        });
+
+        // First restart comes after MIN_BACKOFF_SECS
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        let status = snapshot()
+            .into_iter()
+            .find(|w| w.name == "test-panicker")
+            .unwrap();
+        assert!(runs.load(std::sync::atomic::Ordering::SeqCst) >= 2);
+        assert!(status.restarts >= 1);
+        assert!(status.last_tick.is_some());
+        assert_eq!(status.last_error.as_deref(), Some("panicked: boom"));
+    }
+}
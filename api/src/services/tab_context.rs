@@ -0,0 +1,110 @@
+//! Browser tab context from the companion extension
+//!
+//! The extension authenticates with a scoped extension token (not the daemon API
+//! token) and reports the active tab at POST /extension/tab. Before anything is
+//! stored the report goes through the privacy rules: private/incognito tabs and
+//! non-web pages are dropped, as are hosts on the user's blocked domain list;
+//! the query string, fragment and any credentials are stripped from the URL.
+//! Accepted tabs become `TabContext` activities (host in `application`, page
+//! title in `window`, cleaned URL in `url`) for the content agent.
+
+use base64::Engine;
+use rand::Rng;
+use reqwest::Url;
+
+/// Scope allowing POST /extension/tab
+pub const SCOPE_TAB_CONTEXT: &str = "tab_context";
+
+/// Longest stored page title
+pub const MAX_TITLE_LEN: usize = 512;
+/// Longest accepted URL
+pub const MAX_URL_LEN: usize = 2048;
+/// Most entries in a user's blocked domain list
+pub const MAX_BLOCKED_DOMAINS: usize = 200;
+
+/// New random extension token; only its hash is stored
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    format!(
+        "cleo_ext_{}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    )
+}
+
+/// A tab that passed the privacy rules
+#[derive(Debug, PartialEq, Eq)]
+pub struct CleanTab {
+    pub host: String,
+    pub url: String,
+}
+
+/// Normalize a blocked-domain entry ("https://Docs.Example.com/x" -> "docs.example.com")
+pub fn normalize_domain(entry: &str) -> Option<String> {
+    let entry = entry.trim().to_ascii_lowercase();
+    let host = match Url::parse(&entry) {
+        Ok(url) if url.has_host() => url.host_str()?.to_string(),
+        _ => entry.split('/').next()?.to_string(),
+    };
+    let host = host.trim_start_matches("*.").trim_matches('.').to_string();
+    (!host.is_empty() && !host.contains(char::is_whitespace)).then_some(host)
+}
+
+/// True if `host` is a blocked domain or a subdomain of one
+fn is_blocked(host: &str, blocked: &[String]) -> bool {
+    blocked.iter().any(|domain| {
+        host == domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+/// Apply the privacy rules to a reported URL. None means don't store it.
+pub fn clean_tab(raw_url: &str, blocked: &[String]) -> Option<CleanTab> {
+    let mut url = Url::parse(raw_url.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.to_ascii_lowercase();
+    if is_blocked(&host, blocked) {
+        return None;
+    }
+
+    // Tokens and personal data tend to live in these
+    url.set_query(None);
+    url.set_fragment(None);
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+
+    Some(CleanTab {
+        host,
+        url: url.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_tab_applies_privacy_rules() {
+        let blocked = vec![normalize_domain("https://Bank.example/login").unwrap()];
+        assert_eq!(blocked, vec!["bank.example".to_string()]);
+
+        assert_eq!(
+            clean_tab(
+                "https://user:pw@github.com/org/repo/pull/42?token=abc#diff",
+                &blocked
+            ),
+            Some(CleanTab {
+                host: "github.com".to_string(),
+                url: "https://github.com/org/repo/pull/42".to_string(),
+            })
+        );
+        assert!(clean_tab("https://online.bank.example/accounts", &blocked).is_none());
+        assert!(clean_tab("https://notbank.example/", &blocked).is_some());
+        assert!(clean_tab("chrome://settings", &blocked).is_none());
+        assert!(clean_tab("file:///Users/me/notes.txt", &blocked).is_none());
+        assert!(clean_tab("not a url", &blocked).is_none());
+    }
+}
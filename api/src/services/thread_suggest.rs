@@ -0,0 +1,275 @@
+//! Thread suggestions from related pending drafts (POST /threads/suggest)
+//!
+//! Standalone drafts are compared pairwise on three signals: text similarity
+//! (cosine over bag-of-words vectors, since there is no embedding model in the
+//! stack), captures they share, and how close together they were generated.
+//! The strongest pairs are linked greedily into groups of at most
+//! `MAX_SUGGESTED_TWEETS`; every group of two or more becomes a suggestion,
+//! ordered the way it would read as a thread.
+
+use std::collections::HashMap;
+
+use crate::domain::twitter::queries::threads::ThreadCandidate;
+
+/// Most recent standalone drafts considered
+pub const MAX_CANDIDATES: i64 = 200;
+/// Longest suggested thread
+pub const MAX_SUGGESTED_TWEETS: usize = 10;
+/// Drafts generated further apart than this only link through a shared capture
+const SESSION_WINDOW_SECS: i64 = 6 * 60 * 60;
+/// Lowest pair score that links two drafts
+const LINK_THRESHOLD: f64 = 0.35;
+/// Text similarity at which a pair counts as being about the same thing
+const SIMILAR_TEXT: f64 = 0.3;
+
+const TEXT_WEIGHT: f64 = 0.6;
+const CAPTURE_WEIGHT: f64 = 0.3;
+const SESSION_WEIGHT: f64 = 0.1;
+
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "are", "was", "but", "not", "you", "your", "our",
+    "its", "from", "have", "has", "had", "now", "just", "into", "than", "then", "can", "will",
+    "all", "out", "about", "more", "what", "when", "how", "why", "http", "https", "www", "com",
+];
+
+/// Why two drafts were grouped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Signal {
+    SimilarText,
+    SharedCaptures,
+    SameSession,
+}
+
+impl Signal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Signal::SimilarText => "similar_text",
+            Signal::SharedCaptures => "shared_captures",
+            Signal::SameSession => "same_session",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    /// In thread order
+    pub tweet_ids: Vec<i64>,
+    /// Mean score of the links that formed the group, 0-1
+    pub score: f64,
+    pub signals: Vec<Signal>,
+}
+
+/// Lowercased content words and how often each appears
+fn word_counts(text: &str) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() >= 3 && !STOPWORDS.contains(&w.as_str()))
+    {
+        *counts.entry(word).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(word, x)| b.get(word).map(|y| x * y))
+        .sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 { 0.0 } else { dot / denom }
+}
+
+/// The `N` of a leading "N/" thread marker, e.g. "2/ Then we..."
+fn thread_number(text: &str) -> Option<u32> {
+    let (number, _) = text.trim_start().split_once('/')?;
+    number.parse().ok()
+}
+
+/// Link score and signals for a pair, or None if they aren't related
+fn link(
+    a: &ThreadCandidate,
+    b: &ThreadCandidate,
+    words_a: &HashMap<String, f64>,
+    words_b: &HashMap<String, f64>,
+) -> Option<(f64, Vec<Signal>)> {
+    let apart = (a.created_at - b.created_at).num_seconds().abs();
+    let same_session = apart <= SESSION_WINDOW_SECS;
+    let shared = a.capture_ids.iter().any(|id| b.capture_ids.contains(id));
+    if !same_session && !shared {
+        return None;
+    }
+
+    let text = cosine(words_a, words_b);
+    let mut score = TEXT_WEIGHT * text;
+    let mut signals = Vec::new();
+    if text >= SIMILAR_TEXT {
+        signals.push(Signal::SimilarText);
+    }
+    if shared {
+        score += CAPTURE_WEIGHT;
+        signals.push(Signal::SharedCaptures);
+    }
+    if same_session {
+        score += SESSION_WEIGHT * (1.0 - apart as f64 / SESSION_WINDOW_SECS as f64);
+        signals.push(Signal::SameSession);
+    }
+    (score >= LINK_THRESHOLD).then_some((score, signals))
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Group related drafts into suggested threads, best first
+pub fn suggest(candidates: &[ThreadCandidate]) -> Vec<Suggestion> {
+    let words: Vec<_> = candidates.iter().map(|c| word_counts(&c.text)).collect();
+
+    let mut links = Vec::new();
+    for i in 0..candidates.len() {
+        for j in i + 1..candidates.len() {
+            if let Some((score, signals)) =
+                link(&candidates[i], &candidates[j], &words[i], &words[j])
+            {
+                links.push((score, i, j, signals));
+            }
+        }
+    }
+    links.sort_by(|a, b| b.0.total_cmp(&a.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+
+    // Strongest links first, never growing a group past MAX_SUGGESTED_TWEETS
+    let mut parent: Vec<usize> = (0..candidates.len()).collect();
+    let mut size = vec![1usize; candidates.len()];
+    let mut used = Vec::new();
+    for (score, i, j, signals) in links {
+        let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+        if ri == rj || size[ri] + size[rj] > MAX_SUGGESTED_TWEETS {
+            continue;
+        }
+        parent[rj] = ri;
+        size[ri] += size[rj];
+        used.push((score, i, signals));
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..candidates.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+    let mut link_stats: HashMap<usize, (f64, usize, Vec<Signal>)> = HashMap::new();
+    for (score, i, signals) in used {
+        let entry = link_stats
+            .entry(find(&mut parent, i))
+            .or_insert((0.0, 0, Vec::new()));
+        entry.0 += score;
+        entry.1 += 1;
+        entry.2.extend(signals);
+    }
+
+    let mut suggestions: Vec<Suggestion> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(root, mut members)| {
+            members.sort_by_key(|&i| {
+                let c = &candidates[i];
+                (
+                    thread_number(&c.text).unwrap_or(u32::MAX),
+                    c.created_at,
+                    c.id,
+                )
+            });
+            let (total, count, mut signals) = link_stats.remove(&root).unwrap_or_default();
+            signals.sort();
+            signals.dedup();
+            Suggestion {
+                tweet_ids: members.iter().map(|&i| candidates[i].id).collect(),
+                score: total / count.max(1) as f64,
+                signals,
+            }
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.tweet_ids[0].cmp(&b.tweet_ids[0]))
+    });
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration, Utc};
+
+    fn candidate(id: i64, text: &str, minutes: i64, capture_ids: &[i64]) -> ThreadCandidate {
+        ThreadCandidate {
+            id,
+            text: text.to_string(),
+            created_at: DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minutes),
+            capture_ids: capture_ids.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_groups_related_drafts_in_thread_order() {
+        let suggestions = suggest(&[
+            candidate(
+                1,
+                "2/ The CSV exporter streams rows instead of buffering",
+                5,
+                &[],
+            ),
+            candidate(2, "Lunch break, trying the new ramen place", 6, &[]),
+            candidate(3, "1/ Rewrote the CSV exporter so it streams rows", 0, &[]),
+            candidate(4, "Benchmarks for the streaming exporter", 2_000, &[7]),
+            candidate(5, "Chart from today's profiling run", 1_990, &[7]),
+        ]);
+
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].tweet_ids, vec![3, 1]);
+        assert_eq!(
+            suggestions[0].signals,
+            vec![Signal::SimilarText, Signal::SameSession]
+        );
+        assert_eq!(suggestions[1].tweet_ids, vec![5, 4]);
+        assert!(suggestions[1].signals.contains(&Signal::SharedCaptures));
+    }
+
+    #[test]
+    fn test_similar_text_needs_a_shared_capture_across_sessions() {
+        let far_apart = [
+            candidate(1, "Rewrote the CSV exporter to stream rows", 0, &[]),
+            candidate(2, "The CSV exporter now streams rows", 10_000, &[]),
+        ];
+        assert!(suggest(&far_apart).is_empty());
+
+        let sharing = [
+            candidate(1, "Rewrote the CSV exporter to stream rows", 0, &[3]),
+            candidate(2, "The CSV exporter now streams rows", 10_000, &[3]),
+        ];
+        assert_eq!(suggest(&sharing)[0].tweet_ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_groups_are_capped() {
+        let drafts: Vec<_> = (0..MAX_SUGGESTED_TWEETS as i64 + 3)
+            .map(|i| candidate(i, "Streaming exporter progress", i, &[1]))
+            .collect();
+        let suggestions = suggest(&drafts);
+        assert!(
+            suggestions
+                .iter()
+                .all(|s| s.tweet_ids.len() <= MAX_SUGGESTED_TWEETS)
+        );
+        assert_eq!(
+            suggestions.iter().map(|s| s.tweet_ids.len()).sum::<usize>(),
+            drafts.len()
+        );
+    }
+}
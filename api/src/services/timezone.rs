@@ -0,0 +1,81 @@
+//! Per-user time zones
+//!
+//! Users pick an IANA zone (`users.timezone`, default UTC). Day buckets use the
+//! user's local date rather than the UTC one: the date folder in capture storage
+//! paths, the `date` filter on /captures/browse and /sessions, and the clock
+//! times the content agent sees. Late-evening work far from UTC then stays on
+//! the day it happened. Quota days still roll over at UTC midnight.
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use sqlx::PgPool;
+
+use crate::domain::users;
+
+/// Parse an IANA zone name such as "America/Los_Angeles"
+pub fn parse(name: &str) -> Option<Tz> {
+    name.parse().ok()
+}
+
+/// The user's calendar date at `at`
+pub fn local_date(at: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    at.with_timezone(&tz).date_naive()
+}
+
+/// First instant of `date` in `tz`. Zones that skip midnight on a DST change
+/// start that day at the first valid local time.
+fn start_of_day(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+    (0..=2)
+        .find_map(|h| {
+            tz.from_local_datetime(&(midnight + Duration::hours(h)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+/// UTC bounds `[start, end)` of the local day `date`
+pub fn day_bounds(date: NaiveDate, tz: Tz) -> (DateTime<Utc>, DateTime<Utc>) {
+    let next = date.succ_opt().unwrap_or(date);
+    (start_of_day(date, tz), start_of_day(next, tz))
+}
+
+/// Load the user's zone, falling back to UTC if unset or unknown
+pub async fn user_timezone(db: &PgPool, user_id: i64) -> Tz {
+    match users::get_user_timezone(db, user_id).await {
+        Ok(Some(name)) => parse(&name).unwrap_or_else(|| {
+            eprintln!("[timezone] User {} has unknown zone {:?}", user_id, name);
+            Tz::UTC
+        }),
+        Ok(None) => Tz::UTC,
+        Err(e) => {
+            eprintln!("[timezone] Failed to load zone for user {}: {}", user_id, e);
+            Tz::UTC
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_days_follow_the_user_zone() {
+        let tz = parse("America/Los_Angeles").unwrap();
+        assert!(parse("Mars/Olympus_Mons").is_none());
+
+        // 10pm Pacific on March 1st is already March 2nd in UTC
+        let late_evening = Utc.with_ymd_and_hms(2026, 3, 2, 6, 0, 0).unwrap();
+        let day = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_eq!(local_date(late_evening, tz), day);
+
+        let (start, end) = day_bounds(day, tz);
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap());
+
+        // Spring forward: the local day is 23 hours long
+        let (start, end) = day_bounds(NaiveDate::from_ymd_opt(2026, 3, 8).unwrap(), tz);
+        assert_eq!(end - start, Duration::hours(23));
+    }
+}
@@ -6,6 +6,10 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 
+use super::crypto;
+use super::twitter_credentials::{CredentialSet, TwitterCredentials};
+use crate::domain::twitter::Poll;
+
 #[allow(dead_code)]
 pub enum TwitterStatsResponse {
     Tweets(Vec<TweetResponse>),
@@ -15,62 +19,34 @@ pub enum TwitterStatsResponse {
 
 #[derive(Clone)]
 pub struct TwitterClient {
-    client_id: String,
-    client_secret: String,
+    credentials: CredentialSet,
     redirect_uri: String,
     http: Client,
 }
 
 impl TwitterClient {
-    pub fn new(client_id: &str, client_secret: &str, redirect_uri: &str) -> Self {
+    pub fn new(credentials: CredentialSet, redirect_uri: &str) -> Self {
         Self {
-            client_id: client_id.to_string(),
-            client_secret: client_secret.to_string(),
+            credentials,
             redirect_uri: redirect_uri.to_string(),
             http: Client::new(),
         }
     }
 
-    /// Generate PKCE code verifier and challenge
-    fn generate_pkce() -> (String, String) {
-        // Generate random 32 bytes for code verifier
-        let verifier_bytes: [u8; 32] = rand::rng().random();
-        let code_verifier = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(verifier_bytes);
-
-        // Create code challenge (SHA256 hash of verifier, base64url encoded)
-        let mut hasher = Sha256::new();
-        hasher.update(code_verifier.as_bytes());
-        let hash = hasher.finalize();
-        let code_challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(hash);
-
-        (code_verifier, code_challenge)
-    }
-
-    /// Generate random state for CSRF protection
-    fn generate_state() -> String {
-        let bytes: [u8; 16] = rand::rng().random();
-        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
-    }
-
-    /// Build Basic auth header for OAuth token requests
-    fn basic_auth_header(&self) -> String {
-        let credentials = format!("{}:{}", self.client_id, self.client_secret);
-        format!(
-            "Basic {}",
-            base64::engine::general_purpose::STANDARD.encode(credentials)
-        )
+    pub fn credentials(&self) -> &CredentialSet {
+        &self.credentials
     }
 
     /// Step 1: Build authorization URL and return state + verifier to store
     pub fn get_authorize_url(&self, scopes: &[&str]) -> AuthorizeRequest {
-        let state = Self::generate_state();
-        let (code_verifier, code_challenge) = Self::generate_pkce();
+        let state = generate_oauth_state();
+        let (code_verifier, code_challenge) = generate_pkce();
 
         let scope = scopes.join("%20");
 
         let url = format!(
             "https://x.com/i/oauth2/authorize?response_type=code&client_id={}&redirect_uri={}&scope={}&state={}&code_challenge={}&code_challenge_method=S256",
-            percent_encode(&self.client_id),
+            percent_encode(&self.credentials.active().client_id),
             percent_encode(&self.redirect_uri),
             scope,
             percent_encode(&state),
@@ -84,7 +60,8 @@ impl TwitterClient {
         }
     }
 
-    /// Step 2: Exchange authorization code for access token
+    /// Step 2: Exchange authorization code for access token.
+    /// The returned refresh token is tagged with the credential for storage.
     pub async fn exchange_code(
         &self,
         code: &str,
@@ -99,10 +76,11 @@ impl TwitterClient {
             ("code_verifier", code_verifier),
         ];
 
+        let credentials = self.credentials.active();
         let resp = self
             .http
             .post(url)
-            .header("Authorization", self.basic_auth_header())
+            .header("Authorization", credentials.basic_auth_header())
             .header("Content-Type", "application/x-www-form-urlencoded")
             .form(&params)
             .send()
@@ -113,12 +91,53 @@ impl TwitterClient {
             return Err(TwitterError::Api(text));
         }
 
-        let token: TokenResponse = resp.json().await?;
+        let mut token: TokenResponse = resp.json().await?;
+        token.refresh_token = token.refresh_token.map(|t| credentials.tag(&t));
+        Ok(token)
+    }
+
+    /// Refresh an access token from a stored (tagged or legacy untagged) refresh token.
+    /// Uses the credential the token is tagged with, then the other one with the
+    /// same client id if Twitter rejects it. The new refresh token comes back tagged
+    /// with whichever credential succeeded.
+    pub async fn refresh_token(&self, stored: &str) -> Result<TokenResponse, TwitterError> {
+        let opened = self.credentials.open(stored).ok_or_else(|| {
+            TwitterError::Api(
+                "refresh token is tagged with a credential that is no longer configured"
+                    .to_string(),
+            )
+        })?;
+
+        let result = self
+            .request_refresh(opened.credentials, &opened.refresh_token)
+            .await;
+        let (credentials, mut token) = match result {
+            Ok(token) => (opened.credentials, token),
+            Err(TwitterError::Api(e)) => {
+                let Some(fallback) = self.credentials.fallback_for(opened.credentials) else {
+                    return Err(TwitterError::Api(e));
+                };
+                eprintln!(
+                    "[twitter] Refresh rejected for credential {}, retrying with {}",
+                    opened.credentials.id, fallback.id
+                );
+                let token = self
+                    .request_refresh(fallback, &opened.refresh_token)
+                    .await?;
+                (fallback, token)
+            }
+            Err(e) => return Err(e),
+        };
+
+        token.refresh_token = token.refresh_token.map(|t| credentials.tag(&t));
         Ok(token)
     }
 
-    /// Refresh an access token
-    pub async fn refresh_token(&self, refresh_token: &str) -> Result<TokenResponse, TwitterError> {
+    async fn request_refresh(
+        &self,
+        credentials: &TwitterCredentials,
+        refresh_token: &str,
+    ) -> Result<TokenResponse, TwitterError> {
         let url = "https://api.x.com/2/oauth2/token";
 
         let params = [
@@ -129,7 +148,7 @@ impl TwitterClient {
         let resp = self
             .http
             .post(url)
-            .header("Authorization", self.basic_auth_header())
+            .header("Authorization", credentials.basic_auth_header())
             .header("Content-Type", "application/x-www-form-urlencoded")
             .form(&params)
             .send()
@@ -144,9 +163,9 @@ impl TwitterClient {
         Ok(token)
     }
 
-    /// Get the authenticated user's info
+    /// Get the authenticated user's info, including their subscription tier
     pub async fn get_me(&self, access_token: &str) -> Result<TwitterUser, TwitterError> {
-        let url = "https://api.x.com/2/users/me";
+        let url = "https://api.x.com/2/users/me?user.fields=subscription_type";
 
         let resp = self
             .http
@@ -169,14 +188,18 @@ impl TwitterClient {
     /// # Arguments
     /// * `access_token` - OAuth 2.0 bearer token for the user
     /// * `text` - The tweet text content
-    /// * `in_reply_to` - If posting as part of a thread, the Twitter ID of the previous tweet to chain to
+    /// * `in_reply_to` - Twitter ID of the tweet to reply to (the previous tweet when posting a thread)
+    /// * `quote_tweet_id` - Twitter ID of a tweet to quote
     /// * `media_ids` - Twitter media IDs to attach (uploaded via `upload_media`). Max 4 images OR 1 video.
+    /// * `poll` - Poll to attach (not allowed with media or a quote)
     pub async fn post_tweet(
         &self,
         access_token: &str,
         text: &str,
         in_reply_to: Option<&str>,
+        quote_tweet_id: Option<&str>,
         media_ids: Option<&[String]>,
+        poll: Option<&Poll>,
     ) -> Result<TweetResponse, TwitterError> {
         let url = "https://api.x.com/2/tweets";
 
@@ -188,6 +211,10 @@ impl TwitterClient {
             });
         }
 
+        if let Some(quoted_id) = quote_tweet_id {
+            body["quote_tweet_id"] = serde_json::json!(quoted_id);
+        }
+
         if let Some(ids) = media_ids {
             if !ids.is_empty() {
                 body["media"] = serde_json::json!({
@@ -196,6 +223,13 @@ impl TwitterClient {
             }
         }
 
+        if let Some(poll) = poll {
+            body["poll"] = serde_json::json!({
+                "options": poll.options,
+                "duration_minutes": poll.duration_minutes
+            });
+        }
+
         let resp = self
             .http
             .post(url)
@@ -214,13 +248,35 @@ impl TwitterClient {
         Ok(wrapper.data)
     }
 
-    #[allow(dead_code)]
+    /// Delete one of the user's tweets
+    pub async fn delete_tweet(
+        &self,
+        access_token: &str,
+        tweet_id: &str,
+    ) -> Result<(), TwitterError> {
+        let url = format!("https://api.x.com/2/tweets/{}", tweet_id);
+
+        let resp = self
+            .http
+            .delete(url)
+            .header("Authorization", format!("Bearer {}", access_token))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let text = resp.text().await?;
+            return Err(TwitterError::Api(text));
+        }
+
+        Ok(())
+    }
+
     pub async fn get_tweet(
         &self,
         access_token: &str,
         tweet_id: &str,
     ) -> Result<TweetResponse, TwitterError> {
-        let url = format!("https://api.x.com/2/tweets/{tweet_id}&tweet.fields=public_metrics");
+        let url = format!("https://api.x.com/2/tweets/{tweet_id}?tweet.fields=public_metrics");
 
         let resp = self
             .http
@@ -600,6 +656,27 @@ struct MediaProcessingInfo {
     check_after_secs: Option<i32>,
 }
 
+/// Generate PKCE code verifier and challenge
+pub fn generate_pkce() -> (String, String) {
+    // Generate random 32 bytes for code verifier
+    let verifier_bytes: [u8; 32] = rand::rng().random();
+    let code_verifier = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(verifier_bytes);
+
+    // Create code challenge (SHA256 hash of verifier, base64url encoded)
+    let mut hasher = Sha256::new();
+    hasher.update(code_verifier.as_bytes());
+    let hash = hasher.finalize();
+    let code_challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(hash);
+
+    (code_verifier, code_challenge)
+}
+
+/// Generate random state for CSRF protection
+pub fn generate_oauth_state() -> String {
+    let bytes: [u8; 16] = rand::rng().random();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
 fn percent_encode(s: &str) -> String {
     percent_encoding::utf8_percent_encode(s, percent_encoding::NON_ALPHANUMERIC).to_string()
 }
@@ -630,6 +707,19 @@ pub struct TwitterUser {
     pub id: String,
     pub name: String,
     pub username: String,
+    /// "None", "Basic", "Premium" or "PremiumPlus"
+    #[serde(default)]
+    pub subscription_type: Option<String>,
+}
+
+impl TwitterUser {
+    /// Whether the account can post long-form (over 280 character) posts
+    pub fn is_premium(&self) -> bool {
+        matches!(
+            self.subscription_type.as_deref(),
+            Some("Basic" | "Premium" | "PremiumPlus")
+        )
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -690,6 +780,19 @@ impl std::fmt::Display for TwitterError {
     }
 }
 
+impl TwitterError {
+    /// X refused a refresh token for good (revoked app access, expired or
+    /// already used), so only a new login will restore access
+    pub fn is_invalid_grant(&self) -> bool {
+        match self {
+            TwitterError::Api(body) => {
+                body.contains("invalid_grant") || body.contains("token was invalid")
+            }
+            TwitterError::Http(_) => false,
+        }
+    }
+}
+
 impl std::error::Error for TwitterError {}
 
 // Database operations
@@ -697,46 +800,73 @@ impl std::error::Error for TwitterError {}
 #[derive(Debug, sqlx::FromRow)]
 pub struct User {
     pub id: i64,
-    #[allow(dead_code)] // Fetched from DB but intentionally not exposed in API responses
-    pub twitter_id: String,
-    pub twitter_username: String,
-    pub twitter_name: Option<String>,
+    /// X handle, or the GitHub/Google handle for accounts without X
+    pub username: String,
+    pub display_name: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// X is linked to the account
+    pub twitter_connected: bool,
+    /// X rejected the refresh token; the user must log in again to publish
+    pub needs_reconnect: bool,
+}
+
+/// A pending X login: its PKCE verifier and the device that started it
+#[derive(Debug, sqlx::FromRow)]
+pub struct OAuthState {
+    pub code_verifier: String,
+    /// "web" or "daemon"
+    pub device: String,
+    /// Signed-in account X is being linked to, instead of logging in with X
+    pub link_user_id: Option<i64>,
 }
 
 pub async fn save_oauth_state(
     db: &PgPool,
     state: &str,
     code_verifier: &str,
+    device: &str,
+    link_user_id: Option<i64>,
 ) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
-        INSERT INTO oauth_states (state, code_verifier)
-        VALUES ($1, $2)
+        INSERT INTO oauth_states (state, code_verifier, device, link_user_id)
+        VALUES ($1, $2, $3, $4)
         "#,
     )
     .bind(state)
     .bind(code_verifier)
+    .bind(device)
+    .bind(link_user_id)
     .execute(db)
     .await?;
     Ok(())
 }
 
-pub async fn get_oauth_state(db: &PgPool, state: &str) -> Result<Option<String>, sqlx::Error> {
+pub async fn get_oauth_state(db: &PgPool, state: &str) -> Result<Option<OAuthState>, sqlx::Error> {
     // Atomic DELETE + RETURNING prevents race conditions where two requests
     // could get the same state before either deletes it
-    let row: Option<(String,)> = sqlx::query_as(
+    sqlx::query_as(
         r#"
         DELETE FROM oauth_states
-        WHERE state = $1 AND created_at > NOW() - INTERVAL '10 minutes'
-        RETURNING code_verifier
+        WHERE state = $1 AND provider = 'twitter'
+          AND created_at > NOW() - INTERVAL '10 minutes'
+        RETURNING code_verifier, device, link_user_id
         "#,
     )
     .bind(state)
     .fetch_optional(db)
-    .await?;
+    .await
+}
 
-    Ok(row.map(|r| r.0))
+/// The Cleo account an X account is linked to, if any
+pub async fn find_user_by_twitter_id(
+    db: &PgPool,
+    twitter_id: &str,
+) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar("SELECT id FROM users WHERE twitter_id = $1")
+        .bind(twitter_id)
+        .fetch_optional(db)
+        .await
 }
 
 pub async fn upsert_user(
@@ -748,6 +878,7 @@ pub async fn upsert_user(
     refresh_token: Option<&str>,
     expires_at: DateTime<Utc>,
 ) -> Result<i64, sqlx::Error> {
+    let cipher = crypto::cipher();
     let row: (i64,) = sqlx::query_as(
         r#"
         INSERT INTO users (twitter_id, twitter_username, twitter_name, access_token, refresh_token, token_expires_at)
@@ -758,6 +889,7 @@ pub async fn upsert_user(
             access_token = $4,
             refresh_token = COALESCE($5, users.refresh_token),
             token_expires_at = $6,
+            needs_reconnect = FALSE,
             updated_at = NOW()
         RETURNING id
         "#,
@@ -765,8 +897,8 @@ pub async fn upsert_user(
     .bind(twitter_id)
     .bind(twitter_username)
     .bind(twitter_name)
-    .bind(access_token)
-    .bind(refresh_token)
+    .bind(cipher.encrypt(access_token))
+    .bind(refresh_token.map(|t| cipher.encrypt(t)))
     .bind(expires_at)
     .fetch_one(db)
     .await?;
@@ -774,11 +906,86 @@ pub async fn upsert_user(
     Ok(row.0)
 }
 
+/// Link X to an existing account. Returns false if that X account already
+/// belongs to another Cleo account.
+#[allow(clippy::too_many_arguments)]
+pub async fn link_user(
+    db: &PgPool,
+    user_id: i64,
+    twitter_id: &str,
+    twitter_username: &str,
+    twitter_name: Option<&str>,
+    access_token: &str,
+    refresh_token: Option<&str>,
+    expires_at: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let cipher = crypto::cipher();
+    let result = sqlx::query(
+        r#"
+        UPDATE users SET
+            twitter_id = $2,
+            twitter_username = $3,
+            twitter_name = $4,
+            access_token = $5,
+            refresh_token = $6,
+            token_expires_at = $7,
+            needs_reconnect = FALSE,
+            updated_at = NOW()
+        WHERE id = $1
+          AND NOT EXISTS (SELECT 1 FROM users WHERE twitter_id = $2 AND id <> $1)
+        "#,
+    )
+    .bind(user_id)
+    .bind(twitter_id)
+    .bind(twitter_username)
+    .bind(twitter_name)
+    .bind(cipher.encrypt(access_token))
+    .bind(refresh_token.map(|t| cipher.encrypt(t)))
+    .bind(expires_at)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Remove X from an account, dropping its tokens
+pub async fn unlink_user(db: &PgPool, user_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE users SET
+            twitter_id = NULL,
+            twitter_username = NULL,
+            twitter_name = NULL,
+            access_token = NULL,
+            refresh_token = NULL,
+            token_expires_at = NULL,
+            needs_reconnect = FALSE,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
 pub async fn get_user_by_id(db: &PgPool, user_id: i64) -> Result<Option<User>, sqlx::Error> {
     sqlx::query_as(
         r#"
-        SELECT id, twitter_id, twitter_username, twitter_name, created_at
-        FROM users WHERE id = $1
+        SELECT u.id,
+               COALESCE(u.twitter_username, i.username) AS username,
+               COALESCE(u.twitter_name, i.name) AS display_name,
+               u.created_at,
+               u.twitter_id IS NOT NULL AS twitter_connected,
+               u.needs_reconnect
+        FROM users u
+        LEFT JOIN LATERAL (
+            SELECT username, name FROM login_identities
+            WHERE user_id = u.id
+            ORDER BY id
+            LIMIT 1
+        ) i ON TRUE
+        WHERE u.id = $1
         "#,
     )
     .bind(user_id)
@@ -786,23 +993,40 @@ pub async fn get_user_by_id(db: &PgPool, user_id: i64) -> Result<Option<User>, s
     .await
 }
 
-#[derive(Debug, sqlx::FromRow)]
+/// A user's Twitter tokens, decrypted
+#[derive(Debug)]
 pub struct UserTokens {
     pub access_token: String,
     pub refresh_token: Option<String>,
     pub token_expires_at: DateTime<Utc>,
 }
 
+fn decrypt(stored: &str) -> Result<String, sqlx::Error> {
+    crypto::cipher()
+        .decrypt(stored)
+        .map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+/// None for unknown users and accounts without X
 pub async fn get_user_tokens(db: &PgPool, user_id: i64) -> Result<Option<UserTokens>, sqlx::Error> {
-    sqlx::query_as(
+    let row: Option<(String, Option<String>, DateTime<Utc>)> = sqlx::query_as(
         r#"
         SELECT access_token, refresh_token, token_expires_at
-        FROM users WHERE id = $1
+        FROM users WHERE id = $1 AND access_token IS NOT NULL
         "#,
     )
     .bind(user_id)
     .fetch_optional(db)
-    .await
+    .await?;
+
+    row.map(|(access_token, refresh_token, token_expires_at)| {
+        Ok(UserTokens {
+            access_token: decrypt(&access_token)?,
+            refresh_token: refresh_token.as_deref().map(decrypt).transpose()?,
+            token_expires_at,
+        })
+    })
+    .transpose()
 }
 
 pub async fn update_user_tokens(
@@ -812,25 +1036,186 @@ pub async fn update_user_tokens(
     refresh_token: Option<&str>,
     expires_at: DateTime<Utc>,
 ) -> Result<(), sqlx::Error> {
+    let cipher = crypto::cipher();
     sqlx::query(
         r#"
         UPDATE users SET
             access_token = $2,
             refresh_token = COALESCE($3, refresh_token),
             token_expires_at = $4,
+            needs_reconnect = FALSE,
             updated_at = NOW()
         WHERE id = $1
         "#,
     )
     .bind(user_id)
-    .bind(access_token)
-    .bind(refresh_token)
+    .bind(cipher.encrypt(access_token))
+    .bind(refresh_token.map(|t| cipher.encrypt(t)))
     .bind(expires_at)
     .execute(db)
     .await?;
     Ok(())
 }
 
+/// Flag the user as needing to log in again. Returns true only when the flag
+/// was newly set, so the user is nudged once per lost connection.
+pub async fn mark_needs_reconnect(db: &PgPool, user_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE users SET needs_reconnect = TRUE, updated_at = NOW()
+        WHERE id = $1 AND NOT needs_reconnect
+        "#,
+    )
+    .bind(user_id)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// A stored refresh token, for credential rotation
+#[derive(Debug)]
+pub struct StoredRefreshToken {
+    pub user_id: i64,
+    /// Column value as stored, for compare-and-swap in `replace_refresh_token`
+    pub stored: String,
+    /// Decrypted token; Err when its encryption key isn't configured
+    pub refresh_token: Result<String, crypto::CryptoError>,
+}
+
+/// Stored refresh tokens in user id order
+pub async fn list_refresh_tokens(
+    db: &PgPool,
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<StoredRefreshToken>, sqlx::Error> {
+    let rows: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT id, refresh_token FROM users
+        WHERE refresh_token IS NOT NULL AND id > $1
+        ORDER BY id
+        LIMIT $2
+        "#,
+    )
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    let cipher = crypto::cipher();
+    Ok(rows
+        .into_iter()
+        .map(|(user_id, stored)| StoredRefreshToken {
+            user_id,
+            refresh_token: cipher.decrypt(&stored),
+            stored,
+        })
+        .collect())
+}
+
+/// Swap a stored refresh token, unless a concurrent refresh already replaced it
+pub async fn replace_refresh_token(
+    db: &PgPool,
+    user_id: i64,
+    expected_stored: &str,
+    refresh_token: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE users SET refresh_token = $3, updated_at = NOW()
+        WHERE id = $1 AND refresh_token = $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(expected_stored)
+    .bind(crypto::cipher().encrypt(refresh_token))
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Counts from `encrypt_stored_tokens`
+#[derive(Debug, Default)]
+pub struct EncryptTokensSummary {
+    pub users: usize,
+    pub encrypted: usize,
+    /// Encrypted under a key that isn't configured; left untouched
+    pub unreadable: usize,
+    /// Changed by a concurrent login or refresh (already written encrypted)
+    pub skipped: usize,
+}
+
+/// Encrypt plaintext tokens, and re-encrypt ones under a previous key, in place.
+/// Safe to run while the API is serving: rows are swapped only if unchanged.
+pub async fn encrypt_stored_tokens(db: &PgPool) -> Result<EncryptTokensSummary, sqlx::Error> {
+    const BATCH_SIZE: i64 = 500;
+    let cipher = crypto::cipher();
+    let mut summary = EncryptTokensSummary::default();
+    let mut after_id = 0i64;
+
+    loop {
+        let rows: Vec<(i64, String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT id, access_token, refresh_token FROM users
+            WHERE id > $1 AND access_token IS NOT NULL
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(BATCH_SIZE)
+        .fetch_all(db)
+        .await?;
+        let Some((last_id, _, _)) = rows.last() else {
+            break;
+        };
+        after_id = *last_id;
+
+        for (user_id, access_token, refresh_token) in rows {
+            summary.users += 1;
+            let up_to_date = cipher.is_current(&access_token)
+                && refresh_token
+                    .as_deref()
+                    .is_none_or(|t| cipher.is_current(t));
+            if up_to_date {
+                continue;
+            }
+
+            let decrypted = cipher.decrypt(&access_token).and_then(|access| {
+                let refresh = refresh_token
+                    .as_deref()
+                    .map(|t| cipher.decrypt(t))
+                    .transpose()?;
+                Ok((access, refresh))
+            });
+            let Ok((access, refresh)) = decrypted else {
+                summary.unreadable += 1;
+                continue;
+            };
+
+            let result = sqlx::query(
+                r#"
+                UPDATE users SET access_token = $4, refresh_token = $5
+                WHERE id = $1 AND access_token = $2 AND refresh_token IS NOT DISTINCT FROM $3
+                "#,
+            )
+            .bind(user_id)
+            .bind(&access_token)
+            .bind(&refresh_token)
+            .bind(cipher.encrypt(&access))
+            .bind(refresh.map(|t| cipher.encrypt(&t)))
+            .execute(db)
+            .await?;
+            if result.rows_affected() == 1 {
+                summary.encrypted += 1;
+            } else {
+                summary.skipped += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
 /// Generate a new API token for a user
 pub fn generate_api_token() -> String {
     let bytes: [u8; 32] = rand::rng().random();
@@ -864,15 +1249,14 @@ pub async fn get_user_by_api_token(
     db: &PgPool,
     api_token: &str,
 ) -> Result<Option<i64>, sqlx::Error> {
-    let row: Option<(i64,)> = sqlx::query_as(
+    sqlx::query_scalar!(
         r#"
         SELECT id FROM users WHERE api_token = $1
         "#,
+        api_token,
     )
-    .bind(api_token)
     .fetch_optional(db)
-    .await?;
-    Ok(row.map(|r| r.0))
+    .await
 }
 
 /// Get a user's current API token
@@ -887,3 +1271,49 @@ pub async fn get_user_api_token(db: &PgPool, user_id: i64) -> Result<Option<Stri
     .await?;
     Ok(row.and_then(|r| r.0))
 }
+
+/// Twitter ID from a tweet link such as https://x.com/user/status/123 (also
+/// twitter.com, www./mobile. hosts and /i/web/status/ links)
+pub fn tweet_id_from_url(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("mobile."))
+        .unwrap_or(&host);
+    if host != "x.com" && host != "twitter.com" {
+        return None;
+    }
+
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let id = match segments.as_slice() {
+        [_, "status", id, ..] | ["i", "web", "status", id, ..] => *id,
+        _ => return None,
+    };
+    (!id.is_empty() && id.len() <= 20 && id.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tweet_id_from_url() {
+        for url in [
+            "https://x.com/jack/status/20",
+            "https://twitter.com/jack/status/20?s=46&t=abc",
+            "https://mobile.twitter.com/jack/status/20/photo/1",
+            "https://x.com/i/web/status/20",
+        ] {
+            assert_eq!(tweet_id_from_url(url).as_deref(), Some("20"), "{}", url);
+        }
+        assert!(tweet_id_from_url("https://x.com/jack").is_none());
+        assert!(tweet_id_from_url("https://x.com/jack/status/abc").is_none());
+        assert!(tweet_id_from_url("https://example.com/jack/status/20").is_none());
+        assert!(tweet_id_from_url("not a url").is_none());
+    }
+}
@@ -0,0 +1,163 @@
+//! Twitter client credentials and credential-tagged refresh tokens
+//!
+//! Refresh tokens are stored tagged with the credential that issued them,
+//! `cred:<credential id>:<token>`, inside the at-rest encryption of
+//! services::crypto. The tag tells `TwitterClient` which secret to refresh with,
+//! so the secret can be rotated without downtime:
+//!
+//! 1. Set TWITTER_NEXT_CLIENT_ID / TWITTER_NEXT_CLIENT_SECRET. New logins use
+//!    the next credential; existing tokens keep refreshing with the one they are
+//!    tagged with, falling back to the other if Twitter rejects it.
+//! 2. POST /admin/twitter/credentials/rotate re-tags and re-encrypts every
+//!    stored token under the next credential.
+//! 3. Promote the next values to TWITTER_CLIENT_ID / TWITTER_CLIENT_SECRET and
+//!    remove the NEXT variables.
+//!
+//! Untagged tokens were stored before tagging existed; they belong to the
+//! current credential and are tagged on their next refresh or rotation.
+
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+const TAG_PREFIX: &str = "cred:";
+
+/// One Twitter app client id/secret pair
+#[derive(Clone)]
+pub struct TwitterCredentials {
+    /// Short fingerprint of the pair, used to tag stored refresh tokens
+    pub id: String,
+    pub client_id: String,
+    client_secret: String,
+}
+
+impl std::fmt::Debug for TwitterCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TwitterCredentials")
+            .field("id", &self.id)
+            .field("client_id", &self.client_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TwitterCredentials {
+    pub fn new(client_id: &str, client_secret: &str) -> Self {
+        let fingerprint = Sha256::digest(format!("{}:{}", client_id, client_secret).as_bytes());
+        let id = fingerprint[..6]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        Self {
+            id,
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+        }
+    }
+
+    /// Basic auth header for OAuth token requests
+    pub fn basic_auth_header(&self) -> String {
+        let credentials = format!("{}:{}", self.client_id, self.client_secret);
+        format!(
+            "Basic {}",
+            base64::engine::general_purpose::STANDARD.encode(credentials)
+        )
+    }
+
+    /// Tag a refresh token with this credential for storage
+    pub fn tag(&self, refresh_token: &str) -> String {
+        format!("{}{}:{}", TAG_PREFIX, self.id, refresh_token)
+    }
+}
+
+/// A stored refresh token with the credential it belongs to
+#[derive(Debug)]
+pub struct OpenedToken<'a> {
+    pub refresh_token: String,
+    /// Credential the token is tagged with
+    pub credentials: &'a TwitterCredentials,
+}
+
+/// The current credential and, during a rotation, the next one
+#[derive(Clone, Debug)]
+pub struct CredentialSet {
+    pub current: TwitterCredentials,
+    pub next: Option<TwitterCredentials>,
+}
+
+impl CredentialSet {
+    /// Credential used for new logins: the next one while a rotation is in progress
+    pub fn active(&self) -> &TwitterCredentials {
+        self.next.as_ref().unwrap_or(&self.current)
+    }
+
+    pub fn by_id(&self, id: &str) -> Option<&TwitterCredentials> {
+        std::iter::once(&self.current)
+            .chain(self.next.as_ref())
+            .find(|c| c.id == id)
+    }
+
+    /// The other credential with the same client id, to retry a rejected refresh with
+    pub fn fallback_for(&self, credentials: &TwitterCredentials) -> Option<&TwitterCredentials> {
+        std::iter::once(&self.current)
+            .chain(self.next.as_ref())
+            .find(|c| c.id != credentials.id && c.client_id == credentials.client_id)
+    }
+
+    /// Split a (decrypted) stored refresh token into the token and its credential.
+    /// None when it is tagged with a credential that is no longer configured.
+    pub fn open<'a>(&'a self, stored: &str) -> Option<OpenedToken<'a>> {
+        let Some(rest) = stored.strip_prefix(TAG_PREFIX) else {
+            return Some(OpenedToken {
+                refresh_token: stored.to_string(),
+                credentials: &self.current,
+            });
+        };
+        let (id, refresh_token) = rest.split_once(':')?;
+        Some(OpenedToken {
+            refresh_token: refresh_token.to_string(),
+            credentials: self.by_id(id)?,
+        })
+    }
+}
+
+/// Credential id a stored refresh token is tagged with (None if untagged)
+pub fn tagged_credential_id(stored: &str) -> Option<&str> {
+    stored
+        .strip_prefix(TAG_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .map(|(id, _)| id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_open_and_rotation_lookup() {
+        let set = CredentialSet {
+            current: TwitterCredentials::new("client", "old-secret"),
+            next: Some(TwitterCredentials::new("client", "new-secret")),
+        };
+        let current_id = set.current.id.clone();
+
+        let tagged = set.current.tag("refresh-abc");
+        assert_eq!(tagged_credential_id(&tagged), Some(current_id.as_str()));
+        let opened = set.open(&tagged).unwrap();
+        assert_eq!(opened.refresh_token, "refresh-abc");
+        assert_eq!(opened.credentials.id, current_id);
+        assert_eq!(set.active().id, set.next.as_ref().unwrap().id);
+        assert_eq!(
+            set.fallback_for(opened.credentials).map(|c| &c.id),
+            set.next.as_ref().map(|c| &c.id)
+        );
+
+        // Untagged tokens belong to the current credential
+        let legacy = set.open("plain-token").unwrap();
+        assert_eq!(legacy.refresh_token, "plain-token");
+        assert_eq!(legacy.credentials.id, current_id);
+
+        // Tagged by a credential that has since been removed
+        let retired = TwitterCredentials::new("client", "retired").tag("refresh-abc");
+        assert!(set.open(&retired).is_none());
+    }
+}
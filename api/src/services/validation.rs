@@ -0,0 +1,184 @@
+//! Request validation: per-route body limits and validated JSON extraction
+//!
+//! A global DefaultBodyLimit (limits.json_body_bytes) applies to every route;
+//! `with_body_limit` raises or lowers it for a single route, and
+//! `with_decompression` lets daemon uploads arrive compressed. `ValidJson<T>`
+//! replaces `Json<T>` where the payload has bounds of its own (see `Validate`).
+//! `is_public_https_url` vets user-supplied URLs the server will call.
+
+use axum::{
+    Json,
+    extract::{DefaultBodyLimit, FromRequest, Request, State, rejection::JsonRejection},
+    http::header::CONTENT_LENGTH,
+    middleware::{self, Next},
+    response::Response,
+    routing::MethodRouter,
+};
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use std::net::IpAddr;
+use tower_http::decompression::RequestDecompressionLayer;
+
+use super::error::ApiError;
+
+/// Payload checks run after deserialization; failures become 422 responses
+pub trait Validate {
+    fn validate(&self) -> Result<(), ApiError>;
+}
+
+/// `Json<T>` that also runs `T::validate` and reports both failures as `ApiError`
+pub struct ValidJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        value.validate()?;
+        Ok(ValidJson(value))
+    }
+}
+
+/// Cap the request body for one route. Requests declaring a larger
+/// Content-Length get a JSON 413 before the handler runs; streamed bodies are
+/// still cut off at `max` bytes by the extractors.
+pub fn with_body_limit<S>(route: MethodRouter<S>, max: usize) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route
+        .layer(DefaultBodyLimit::max(max))
+        .layer(middleware::from_fn_with_state(max, reject_oversized))
+}
+
+/// Accept gzip- and zstd-compressed request bodies (Content-Encoding) on one
+/// route. Body limits then apply to the decompressed size; other encodings
+/// get a 415.
+pub fn with_decompression<S>(route: MethodRouter<S>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route.layer(RequestDecompressionLayer::new())
+}
+
+async fn reject_oversized(
+    State(max): State<usize>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let declared = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+
+    if declared.is_some_and(|len| len > max) {
+        return Err(ApiError::PayloadTooLarge);
+    }
+    Ok(next.run(request).await)
+}
+
+/// Reject collections over `max` items with a 422
+pub fn check_max_items<T>(items: &[T], max: usize, field: &str) -> Result<(), ApiError> {
+    if items.len() > max {
+        return Err(ApiError::Unprocessable(format!(
+            "{} accepts at most {} items (got {})",
+            field,
+            max,
+            items.len()
+        )));
+    }
+    Ok(())
+}
+
+/// HTTPS URL whose host isn't this machine or a private network. Only the URL
+/// is checked: a public hostname that resolves to a private address passes, so
+/// callers also refuse redirects.
+pub fn is_public_https_url(input: &str) -> bool {
+    let Ok(url) = Url::parse(input) else {
+        return false;
+    };
+    if url.scheme() != "https" {
+        return false;
+    }
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    match host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        Ok(ip) => is_public_ip(ip),
+        Err(_) => {
+            let host = host.trim_end_matches('.').to_ascii_lowercase();
+            host != "localhost" && !host.ends_with(".localhost")
+        }
+    }
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => {
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || (ip.segments()[0] & 0xfe00) == 0xfc00
+                || (ip.segments()[0] & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            JsonRejection::JsonDataError(e) => ApiError::Unprocessable(e.body_text()),
+            JsonRejection::JsonSyntaxError(e) => ApiError::BadRequest(e.body_text()),
+            JsonRejection::MissingJsonContentType(_) => ApiError::UnsupportedMediaType(
+                "Expected Content-Type: application/json".to_string(),
+            ),
+            JsonRejection::BytesRejection(e)
+                if e.status() == axum::http::StatusCode::PAYLOAD_TOO_LARGE =>
+            {
+                ApiError::PayloadTooLarge
+            }
+            other => ApiError::BadRequest(other.body_text()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_max_items() {
+        assert!(check_max_items(&[1, 2, 3], 3, "ids").is_ok());
+        let err = check_max_items(&[1, 2, 3], 2, "ids").unwrap_err();
+        assert_eq!(err.code(), "validation_failed");
+        assert_eq!(err.message(), "ids accepts at most 2 items (got 3)");
+    }
+
+    #[test]
+    fn test_is_public_https_url() {
+        assert!(is_public_https_url(
+            "https://dav.example.com/remote.php/dav/files/me/Vault"
+        ));
+        assert!(!is_public_https_url("http://dav.example.com/Vault"));
+        assert!(!is_public_https_url("https://localhost/Vault"));
+        assert!(!is_public_https_url("https://127.0.0.1/Vault"));
+        assert!(!is_public_https_url("https://10.0.0.5/Vault"));
+        assert!(!is_public_https_url("https://[::1]/Vault"));
+        assert!(!is_public_https_url("https://169.254.169.254/latest"));
+    }
+}
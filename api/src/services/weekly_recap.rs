@@ -0,0 +1,492 @@
+//! Weekly "week in review" threads
+//!
+//! Once a week (Fridays from 17:00 in the user's zone, or the day and hour set
+//! via PUT /me/weekly-recap) the worker drafts a thread from the past seven
+//! days: the posts that shipped and the drafts whose captured moments the agent
+//! picked out, posted ones first. The thread is saved as an ordinary draft for
+//! the user to edit and approve, with screenshots from the top highlights on
+//! the opening tweet, and a push notification says it's ready.
+//! POST /me/weekly-recap/compose drafts one right away.
+//!
+//! Weeks missed while the worker was down aren't backfilled, and weeks with
+//! nothing in them are marked done without a thread.
+
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use sqlx::PgPool;
+
+use super::copy_filter::CopyFilter;
+use super::language::{self, Language};
+use super::llm::LlmProvider;
+use super::{push, timezone};
+use crate::agent::injection::{MAX_TWEET_CHARS, tweet_length};
+use crate::daily_summary::format_duration;
+use crate::domain::summary_exports::{self, AppTime};
+use crate::domain::weekly_recaps::{self, WeekTweet};
+use crate::routes::nudges::get_sanitized_nudges;
+
+pub const POLL_INTERVAL_SECS: u64 = 15 * 60;
+/// How long a claimed recap is held; a failed one is retried after this
+const LEASE_SECS: i64 = 30 * 60;
+pub const DEFAULT_WEEKDAY: Weekday = Weekday::Fri;
+pub const DEFAULT_HOUR: u32 = 17;
+/// Days a recap covers, ending when it's composed
+pub const RECAP_DAYS: i64 = 7;
+/// Fewest days between two recaps, so moving the recap day doesn't draft a
+/// second one the same week
+const MIN_RECAP_GAP_DAYS: i64 = 6;
+/// Tweets from the week shown to the model
+const MAX_HIGHLIGHTS: usize = 12;
+/// Screenshots attached to the opening tweet
+const MAX_IMAGES: usize = 2;
+const MIN_THREAD_TWEETS: usize = 3;
+const MAX_THREAD_TWEETS: usize = 6;
+/// Apps with the most active time mentioned in the prompt
+const TOP_APPS: i64 = 3;
+
+/// Lowercase weekday names, Monday first, as the API takes and shows them
+pub const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+#[derive(Debug)]
+pub enum RecapError {
+    Db(sqlx::Error),
+    /// The model failed or didn't return a usable thread
+    Llm(String),
+}
+
+impl fmt::Display for RecapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecapError::Db(e) => write!(f, "database error: {}", e),
+            RecapError::Llm(e) => write!(f, "LLM error: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for RecapError {
+    fn from(e: sqlx::Error) -> Self {
+        RecapError::Db(e)
+    }
+}
+
+/// A recap thread saved as a draft
+#[derive(Debug)]
+pub struct ComposedRecap {
+    pub thread_id: i64,
+    pub title: String,
+    pub tweets: Vec<String>,
+}
+
+/// Draft each user's recap once their recap day and hour come around
+pub async fn run_weekly_recap_worker(pool: PgPool, llm: Arc<dyn LlmProvider>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(POLL_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+        super::supervisor::heartbeat("weekly_recap");
+
+        let now = Utc::now();
+        let candidates = match weekly_recaps::list_candidates(
+            &pool,
+            now.date_naive(),
+            iso_weekday(DEFAULT_WEEKDAY),
+            DEFAULT_HOUR as i16,
+        )
+        .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("[weekly_recap] List error: {}", e);
+                continue;
+            }
+        };
+
+        for candidate in candidates {
+            let tz = timezone::parse(&candidate.timezone).unwrap_or(Tz::UTC);
+            let Some(weekday) = weekday_from_iso(candidate.weekday) else {
+                continue;
+            };
+            let Some(day) = due_day(
+                now,
+                tz,
+                weekday,
+                candidate.hour as u32,
+                candidate.last_recap_on,
+            ) else {
+                continue;
+            };
+
+            let recapped_before = day - Duration::days(MIN_RECAP_GAP_DAYS - 1);
+            match weekly_recaps::claim_recap(&pool, candidate.user_id, recapped_before, LEASE_SECS)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    eprintln!("[weekly_recap] Claim error: {}", e);
+                    continue;
+                }
+            }
+
+            let start = now - Duration::days(RECAP_DAYS);
+            let result =
+                compose_recap(&pool, llm.as_ref(), candidate.user_id, day, start, now).await;
+            match result {
+                Ok(recap) => {
+                    let thread_id = recap.as_ref().map(|r| r.thread_id);
+                    if let Err(e) =
+                        weekly_recaps::complete_recap(&pool, candidate.user_id, day, thread_id)
+                            .await
+                    {
+                        eprintln!("[weekly_recap] Complete error: {}", e);
+                    }
+                    if let Some(recap) = recap {
+                        println!(
+                            "[weekly_recap] Drafted thread {} for user {}",
+                            recap.thread_id, candidate.user_id
+                        );
+                        if let Err(e) =
+                            push::notify_weekly_recap(&pool, candidate.user_id, recap.tweets.len())
+                                .await
+                        {
+                            eprintln!(
+                                "[weekly_recap] Push error for user {}: {}",
+                                candidate.user_id, e
+                            );
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!(
+                        "[weekly_recap] Recap for user {} failed: {}",
+                        candidate.user_id, e
+                    );
+                    if let Err(e) =
+                        weekly_recaps::fail_recap(&pool, candidate.user_id, &e.to_string()).await
+                    {
+                        eprintln!("[weekly_recap] Fail error: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// ISO weekday number, 1 = Monday
+pub fn iso_weekday(weekday: Weekday) -> i16 {
+    weekday.number_from_monday() as i16
+}
+
+pub fn weekday_from_iso(n: i16) -> Option<Weekday> {
+    (1..=7)
+        .contains(&n)
+        .then(|| (1..n).fold(Weekday::Mon, |day, _| day.succ()))
+}
+
+/// Weekday from its lowercase name in WEEKDAYS
+pub fn weekday_from_name(name: &str) -> Option<Weekday> {
+    let index = WEEKDAYS
+        .iter()
+        .position(|d| d.eq_ignore_ascii_case(name.trim()))?;
+    weekday_from_iso(index as i16 + 1)
+}
+
+pub fn weekday_name(weekday: Weekday) -> &'static str {
+    WEEKDAYS[weekday.num_days_from_monday() as usize]
+}
+
+/// The local date to recap at `now`: today, if it's the recap day, the local
+/// clock has passed `hour`, and the last recap was at least
+/// MIN_RECAP_GAP_DAYS ago
+fn due_day(
+    now: DateTime<Utc>,
+    tz: Tz,
+    weekday: Weekday,
+    hour: u32,
+    last_recap_on: Option<NaiveDate>,
+) -> Option<NaiveDate> {
+    let local = now.with_timezone(&tz);
+    if local.weekday() != weekday || local.hour() < hour {
+        return None;
+    }
+    let day = local.date_naive();
+    last_recap_on
+        .is_none_or(|last| (day - last).num_days() >= MIN_RECAP_GAP_DAYS)
+        .then_some(day)
+}
+
+/// Draft the recap of `[start, end)` as a thread marked with `recap_on`.
+/// Returns None if nothing was drafted or posted in that time.
+pub async fn compose_recap(
+    db: &PgPool,
+    llm: &dyn LlmProvider,
+    user_id: i64,
+    recap_on: NaiveDate,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Option<ComposedRecap>, RecapError> {
+    let week = weekly_recaps::list_week_tweets(db, user_id, start, end).await?;
+    let highlights = pick_highlights(&week, MAX_HIGHLIGHTS);
+    if highlights.is_empty() {
+        return Ok(None);
+    }
+    let apps = summary_exports::top_apps(db, user_id, start, end, TOP_APPS).await?;
+    let tz = timezone::user_timezone(db, user_id).await;
+    let language = language::user_language(db, user_id).await;
+    let nudges = get_sanitized_nudges(db, user_id).await;
+
+    let prompt = build_prompt(&highlights, &apps, tz, language, nudges.as_deref());
+    let response = llm
+        .complete(&prompt, 2000, 0.7)
+        .await
+        .map_err(|e| RecapError::Llm(e.to_string()))?;
+    let tweets = parse_thread(&response)
+        .ok_or_else(|| RecapError::Llm("the model didn't return a usable thread".to_string()))?;
+
+    let title = format!(
+        "Week in review: {} – {}",
+        timezone::local_date(start, tz).format("%b %-d"),
+        recap_on.format("%b %-d")
+    );
+    let posted = highlights.iter().filter(|t| t.posted_at.is_some()).count();
+    let rationale = format!(
+        "Week in review drafted from {} posted and {} drafted tweets",
+        posted,
+        highlights.len() - posted
+    );
+    let images = recap_images(&highlights);
+    let filter = CopyFilter::for_user(db, user_id).await;
+
+    let mut tx = db.begin().await?;
+    let thread_id = weekly_recaps::create_recap_thread(&mut *tx, user_id, &title, recap_on).await?;
+    for (position, text) in tweets.iter().enumerate() {
+        let review_reason = filter.review_reason([text.as_str()]);
+        let image_capture_ids: &[i64] = if position == 0 { &images } else { &[] };
+        weekly_recaps::insert_recap_tweet(
+            &mut *tx,
+            user_id,
+            thread_id,
+            position as i32,
+            text,
+            image_capture_ids,
+            &rationale,
+            review_reason.as_deref(),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(Some(ComposedRecap {
+        thread_id,
+        title,
+        tweets,
+    }))
+}
+
+/// The week's most significant tweets, oldest first: posted ones ahead of
+/// drafts, then ones with screenshots, then the most recent
+fn pick_highlights(week: &[WeekTweet], max: usize) -> Vec<&WeekTweet> {
+    let mut ranked: Vec<(usize, &WeekTweet)> = week.iter().enumerate().collect();
+    ranked.sort_by_key(|(i, t)| {
+        (
+            t.posted_at.is_none(),
+            t.image_capture_ids.is_empty(),
+            std::cmp::Reverse(*i),
+        )
+    });
+    ranked.truncate(max);
+    ranked.sort_by_key(|(i, _)| *i);
+    ranked.into_iter().map(|(_, t)| t).collect()
+}
+
+/// Screenshots for the opening tweet, from posted highlights first
+fn recap_images(highlights: &[&WeekTweet]) -> Vec<i64> {
+    let (posted, drafted): (Vec<&WeekTweet>, Vec<&WeekTweet>) = highlights
+        .iter()
+        .copied()
+        .partition(|t| t.posted_at.is_some());
+    let mut images: Vec<i64> = Vec::new();
+    for tweet in posted.iter().rev().chain(drafted.iter().rev()) {
+        for id in &tweet.image_capture_ids {
+            if images.len() < MAX_IMAGES && !images.contains(id) {
+                images.push(*id);
+            }
+        }
+    }
+    images
+}
+
+fn build_prompt(
+    highlights: &[&WeekTweet],
+    apps: &[AppTime],
+    tz: Tz,
+    language: &Language,
+    nudges: Option<&str>,
+) -> String {
+    let line = |t: &WeekTweet, with_rationale: bool| {
+        let at = t.posted_at.unwrap_or(t.created_at).with_timezone(&tz);
+        let mut line = format!("- {}: \"{}\"", at.format("%a %H:%M"), t.text);
+        if with_rationale && !t.rationale.trim().is_empty() {
+            line.push_str(&format!(" (why it mattered: {})", t.rationale.trim()));
+        }
+        line
+    };
+    let shipped: Vec<String> = highlights
+        .iter()
+        .filter(|t| t.posted_at.is_some())
+        .map(|t| line(t, false))
+        .collect();
+    let drafted: Vec<String> = highlights
+        .iter()
+        .filter(|t| t.posted_at.is_none())
+        .map(|t| line(t, true))
+        .collect();
+
+    let mut week = String::new();
+    if !shipped.is_empty() {
+        week.push_str(&format!("Posted this week:\n{}\n\n", shipped.join("\n")));
+    }
+    if !drafted.is_empty() {
+        week.push_str(&format!(
+            "Other moments captured this week (drafts they haven't posted):\n{}\n\n",
+            drafted.join("\n")
+        ));
+    }
+    if !apps.is_empty() {
+        let apps: Vec<String> = apps
+            .iter()
+            .map(|a| format!("{} ({})", a.app, format_duration(a.active_secs)))
+            .collect();
+        week.push_str(&format!("Most time spent in: {}\n\n", apps.join(", ")));
+    }
+    let nudges_section = match nudges {
+        Some(n) if !n.trim().is_empty() => format!("User's style preferences:\n{}\n\n", n),
+        _ => String::new(),
+    };
+
+    format!(
+        r#"You are a ghostwriter drafting a "week in review" thread for someone who builds in public. Here is their week, oldest first.
+
+{}{}Rules:
+- Write {} to {} tweets. The first opens the recap and makes people want to read on; the last looks ahead to next week or asks a question
+- Lead with what shipped; group related work rather than listing every item
+- Only mention work listed above; don't invent results, numbers or links
+- Each tweet must fit in 280 characters (X counts Chinese, Japanese and Korean characters as 2)
+- Write in {}
+- No AI-sounding phrases: "excited to share", "dive into", "game-changer", "incredibly", "just"
+- No hashtags, no emoji spam, no "1/" numbering
+
+Respond with ONLY a JSON array of the tweet texts, in thread order."#,
+        week, nudges_section, MIN_THREAD_TWEETS, MAX_THREAD_TWEETS, language.name
+    )
+}
+
+/// Thread tweets from the model's JSON array, if there are the right number
+/// and each fits in a tweet
+fn parse_thread(response: &str) -> Option<Vec<String>> {
+    let body = response.trim();
+    let body = body
+        .strip_prefix("```json")
+        .or_else(|| body.strip_prefix("```"))
+        .map(|b| b.trim_end().trim_end_matches("```"))
+        .unwrap_or(body);
+    let tweets: Vec<String> = serde_json::from_str::<Vec<String>>(body.trim())
+        .ok()?
+        .into_iter()
+        .map(|t| t.trim().to_string())
+        .collect();
+    let fits = (MIN_THREAD_TWEETS..=MAX_THREAD_TWEETS).contains(&tweets.len())
+        && tweets
+            .iter()
+            .all(|t| !t.is_empty() && tweet_length(t) <= MAX_TWEET_CHARS);
+    fits.then_some(tweets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn tweet(id: i64, posted: bool, images: &[i64]) -> WeekTweet {
+        let at = Utc.with_ymd_and_hms(2026, 10, 12, 9, 0, 0).unwrap() + Duration::hours(id);
+        WeekTweet {
+            text: format!("tweet {}", id),
+            rationale: String::new(),
+            image_capture_ids: images.to_vec(),
+            created_at: at,
+            posted_at: posted.then_some(at),
+        }
+    }
+
+    #[test]
+    fn test_recap_is_due_on_the_local_recap_day() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        // 21:30 UTC on Friday October 16th is 17:30 in New York
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 21, 30, 0).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(due_day(now, tz, Weekday::Fri, 17, None), Some(friday));
+        assert_eq!(due_day(now, tz, Weekday::Fri, 18, None), None);
+        assert_eq!(due_day(now, tz, Weekday::Thu, 9, None), None);
+        // Already recapped this week, e.g. before moving the day from Wednesday
+        let wednesday = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        assert_eq!(due_day(now, tz, Weekday::Fri, 17, Some(wednesday)), None);
+        let last_week = NaiveDate::from_ymd_opt(2026, 10, 9).unwrap();
+        assert_eq!(
+            due_day(now, tz, Weekday::Fri, 17, Some(last_week)),
+            Some(friday)
+        );
+
+        assert_eq!(
+            weekday_from_iso(iso_weekday(Weekday::Sun)),
+            Some(Weekday::Sun)
+        );
+        assert_eq!(weekday_from_name("Friday"), Some(Weekday::Fri));
+        assert_eq!(weekday_name(Weekday::Mon), "monday");
+        assert_eq!(weekday_from_iso(0), None);
+        assert_eq!(weekday_from_iso(8), None);
+    }
+
+    #[test]
+    fn test_highlights_favor_shipped_work() {
+        let week = vec![
+            tweet(1, false, &[]),
+            tweet(2, true, &[20]),
+            tweet(3, false, &[30, 31]),
+            tweet(4, true, &[]),
+        ];
+        let picked: Vec<&str> = pick_highlights(&week, 3)
+            .iter()
+            .map(|t| t.text.as_str())
+            .collect();
+        assert_eq!(picked, vec!["tweet 2", "tweet 3", "tweet 4"]);
+        let highlights = pick_highlights(&week, 3);
+        assert_eq!(recap_images(&highlights), vec![20, 30]);
+    }
+
+    #[test]
+    fn test_parse_thread_accepts_fenced_json() {
+        let thread = parse_thread("```json\n[\"Week one\", \" Shipped it \", \"Next up\"]\n```");
+        assert_eq!(
+            thread,
+            Some(vec![
+                "Week one".to_string(),
+                "Shipped it".to_string(),
+                "Next up".to_string()
+            ])
+        );
+        assert!(parse_thread("[\"Too\", \"short\"]").is_none());
+        assert!(parse_thread("[\"One\", \"\", \"Three\"]").is_none());
+        assert!(parse_thread("Here's your thread!").is_none());
+    }
+}
@@ -4,20 +4,74 @@
 //! download/upload logic across the codebase.
 
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::config::StorageConfig;
+use crate::constants::DEFAULT_BUCKET_NAME;
+
+/// Object name prefixes for derived artifacts (regenerable from the originals)
+const DERIVED_PREFIXES: &[&str] = &["thumbnails/", "frames/"];
+
+/// GCS buckets for this deployment.
+/// Derived artifacts (thumbnails, frames, media studio renders) go to `derived`,
+/// which defaults to the media bucket.
+#[derive(Debug, Clone)]
+pub struct Buckets {
+    pub media: String,
+    pub derived: String,
+}
+
+impl Buckets {
+    /// Media bucket (GCS_BUCKET) and derived bucket (GCS_DERIVED_BUCKET)
+    pub fn from_config(config: &StorageConfig) -> Self {
+        let media = Some(config.gcs_bucket.trim())
+            .filter(|s| !s.is_empty())
+            .unwrap_or(DEFAULT_BUCKET_NAME)
+            .to_string();
+        let derived = config
+            .gcs_derived_bucket
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| media.clone());
+        Self { media, derived }
+    }
+
+    /// Bucket holding the object at `path`
+    pub fn for_path(&self, path: &str) -> &str {
+        if is_derived_path(path) {
+            &self.derived
+        } else {
+            &self.media
+        }
+    }
+}
+
+/// Thumbnails, extracted frames and media studio output (`edited_*` files)
+fn is_derived_path(path: &str) -> bool {
+    DERIVED_PREFIXES.iter().any(|p| path.starts_with(p))
+        || path
+            .rsplit('/')
+            .next()
+            .is_some_and(|name| name.starts_with("edited_"))
+}
+
 /// Download a single capture from local storage or GCS.
 pub async fn download_capture(
     gcs: Option<&google_cloud_storage::client::Storage>,
     local_storage_path: Option<&PathBuf>,
-    bucket_name: &str,
+    buckets: &Buckets,
     gcs_path: &str,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
     if let Some(local_path) = local_storage_path {
         let full_path = local_path.join(gcs_path);
         Ok(tokio::fs::read(&full_path).await?)
     } else if let Some(gcs) = gcs {
-        let bucket = format!("projects/_/buckets/{}", bucket_name);
+        let bucket = format!("projects/_/buckets/{}", buckets.for_path(gcs_path));
         let mut resp = gcs.read_object(&bucket, gcs_path).send().await?;
         let mut data = Vec::new();
         while let Some(chunk) = resp.next().await {
@@ -33,7 +87,7 @@ pub async fn download_capture(
 pub async fn upload_data(
     gcs: Option<&google_cloud_storage::client::Storage>,
     local_storage_path: Option<&PathBuf>,
-    bucket_name: &str,
+    buckets: &Buckets,
     path: &str,
     data: &[u8],
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -44,7 +98,7 @@ pub async fn upload_data(
         }
         tokio::fs::write(&full_path, data).await?;
     } else if let Some(gcs) = gcs {
-        let bucket = format!("projects/_/buckets/{}", bucket_name);
+        let bucket = format!("projects/_/buckets/{}", buckets.for_path(path));
         let bytes = Bytes::copy_from_slice(data);
         gcs.write_object(&bucket, path, bytes)
             .send_buffered()
@@ -54,3 +108,159 @@ pub async fn upload_data(
     }
     Ok(())
 }
+
+/// Delete an object from local storage or GCS.
+/// A missing local file is not an error; GCS reports missing objects as errors.
+pub async fn delete_object(
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(local_path) = local_storage_path {
+        match tokio::fs::remove_file(local_path.join(path)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    } else if gcs.is_some() {
+        let client = cloud_storage::Client::default();
+        client.object().delete(buckets.for_path(path), path).await?;
+        Ok(())
+    } else {
+        Err("No storage backend configured".into())
+    }
+}
+
+/// Size and modification time of a stored object
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// List every object under `prefix`, keyed by its storage path.
+pub async fn list_objects(
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    prefix: &str,
+) -> Result<HashMap<String, StoredObject>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut objects = HashMap::new();
+
+    if let Some(local_path) = local_storage_path {
+        let mut dirs = vec![local_path.join(prefix)];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let metadata = entry.metadata().await?;
+                if metadata.is_dir() {
+                    dirs.push(entry.path());
+                    continue;
+                }
+                let Ok(relative) = entry.path().strip_prefix(local_path).map(|p| p.to_owned())
+                else {
+                    continue;
+                };
+                let path = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                objects.insert(
+                    path,
+                    StoredObject {
+                        size: metadata.len(),
+                        modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+                    },
+                );
+            }
+        }
+    } else if gcs.is_some() {
+        let client = cloud_storage::Client::default();
+        let request = cloud_storage::ListRequest {
+            prefix: Some(prefix.to_string()),
+            ..Default::default()
+        };
+        let mut pages = Box::pin(
+            client
+                .object()
+                .list(buckets.for_path(prefix), request)
+                .await?,
+        );
+        while let Some(page) = pages.next().await {
+            for object in page?.items {
+                objects.insert(
+                    object.name,
+                    StoredObject {
+                        size: object.size,
+                        modified: Some(object.updated),
+                    },
+                );
+            }
+        }
+    } else {
+        return Err("No storage backend configured".into());
+    }
+
+    Ok(objects)
+}
+
+/// Whether an object exists in local storage or GCS.
+pub async fn object_exists(
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+    path: &str,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(local_path) = local_storage_path {
+        Ok(tokio::fs::try_exists(local_path.join(path)).await?)
+    } else {
+        // Listing with the full path as prefix distinguishes "missing" from errors
+        let objects = list_objects(gcs, None, buckets, path).await?;
+        Ok(objects.contains_key(path))
+    }
+}
+
+/// Lowercase hex SHA-256 of `data`, the format stored in `captures.sha256`.
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets_for_path() {
+        let buckets = Buckets {
+            media: "media".to_string(),
+            derived: "derived".to_string(),
+        };
+        assert_eq!(buckets.for_path("image/user_1/2025-01-01/1.png"), "media");
+        assert_eq!(
+            buckets.for_path("thumbnails/user_1/2025-01-01/1.jpg"),
+            "derived"
+        );
+        assert_eq!(
+            buckets.for_path("frames/user_1/2025-01-01/1/manifest.json"),
+            "derived"
+        );
+        assert_eq!(
+            buckets.for_path("video/user_1/2025-01-01/edited_1.mp4"),
+            "derived"
+        );
+        assert_eq!(
+            buckets.for_path("exports/user_1/cleo-export-1.zip"),
+            "media"
+        );
+    }
+}
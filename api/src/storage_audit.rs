@@ -0,0 +1,274 @@
+//! Storage reconciliation audit worker
+//!
+//! Walks the media and thumbnail prefixes in storage and compares them with the
+//! captures table, recording discrepancies in `storage_audit`: objects no row
+//! references, rows whose media or thumbnail is gone, and size mismatches.
+//! Repairs (deleting orphans, regenerating thumbnails) run when
+//! STORAGE_AUDIT_AUTO_REPAIR is set, or on demand via /admin/storage-audit/repair.
+
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::path::PathBuf;
+
+use crate::domain::storage_audit;
+use crate::storage::{self, Buckets};
+
+const BATCH_SIZE: i64 = 1000;
+/// Objects newer than this may belong to an upload whose row isn't committed yet
+const ORPHAN_GRACE_MINUTES: i64 = 60;
+const AUDITED_PREFIXES: &[&str] = &["image/", "video/", "thumbnails/"];
+
+pub const ORPHANED_OBJECT: &str = "orphaned_object";
+pub const MISSING_OBJECT: &str = "missing_object";
+pub const MISSING_THUMBNAIL: &str = "missing_thumbnail";
+pub const SIZE_MISMATCH: &str = "size_mismatch";
+/// Kinds `repair_findings` knows how to fix; the rest need a human
+pub const REPAIRABLE_KINDS: &[&str] = &[ORPHANED_OBJECT, MISSING_THUMBNAIL];
+
+/// Counts from one audit run
+#[derive(Debug, Default)]
+pub struct AuditSummary {
+    pub objects: usize,
+    pub captures: usize,
+    pub orphaned: usize,
+    pub missing: usize,
+    pub missing_thumbnails: usize,
+    pub size_mismatches: usize,
+    pub resolved: u64,
+}
+
+/// Start the storage audit worker.
+/// Runs on every replica but an advisory lock keeps audits from overlapping.
+pub async fn run_storage_audit_worker(
+    pool: PgPool,
+    gcs: Option<google_cloud_storage::client::Storage>,
+    local_storage_path: Option<PathBuf>,
+    buckets: Buckets,
+) {
+    let interval_secs = storage_audit_interval_secs();
+    let auto_repair = storage_audit_auto_repair();
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+
+    println!(
+        "[storage_audit] Worker starting ({}s interval, auto-repair {})",
+        interval_secs,
+        if auto_repair { "on" } else { "off" }
+    );
+
+    loop {
+        interval.tick().await;
+        crate::services::supervisor::heartbeat("storage_audit");
+
+        let mut conn = match pool.acquire().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("[storage_audit] Failed to acquire connection: {}", e);
+                continue;
+            }
+        };
+        match storage_audit::try_lock_audit(&mut conn).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                eprintln!("[storage_audit] Lock error: {}", e);
+                continue;
+            }
+        }
+
+        match audit_storage(&pool, gcs.as_ref(), local_storage_path.as_ref(), &buckets).await {
+            Ok(summary) => println!(
+                "[storage_audit] Checked {} objects against {} captures: {} orphaned, {} missing, {} missing thumbnails, {} size mismatches ({} resolved)",
+                summary.objects,
+                summary.captures,
+                summary.orphaned,
+                summary.missing,
+                summary.missing_thumbnails,
+                summary.size_mismatches,
+                summary.resolved
+            ),
+            Err(e) => eprintln!("[storage_audit] Audit failed: {}", e),
+        }
+
+        if auto_repair {
+            match repair_findings(&pool, gcs.as_ref(), local_storage_path.as_ref(), &buckets).await
+            {
+                Ok(0) => {}
+                Ok(repaired) => println!("[storage_audit] Repaired {} findings", repaired),
+                Err(e) => eprintln!("[storage_audit] Repair failed: {}", e),
+            }
+        }
+
+        if let Err(e) = storage_audit::unlock_audit(&mut conn).await {
+            eprintln!("[storage_audit] Unlock error: {}", e);
+        }
+    }
+}
+
+/// Compare storage with the captures table and record discrepancies.
+/// Holds the object listing in memory, which is fine at current bucket sizes.
+async fn audit_storage(
+    pool: &PgPool,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+) -> Result<AuditSummary, Box<dyn std::error::Error + Send + Sync>> {
+    let run_started_at = Utc::now();
+    let mut summary = AuditSummary::default();
+
+    // List first, then read rows: a row whose object appears later is re-checked
+    // below, and an object whose row appears later is covered by the grace period
+    let mut objects = std::collections::HashMap::new();
+    for prefix in AUDITED_PREFIXES {
+        objects.extend(storage::list_objects(gcs, local_storage_path, buckets, prefix).await?);
+    }
+    objects.retain(|path, _| !is_derived_thumbnail(path));
+    summary.objects = objects.len();
+
+    let mut after_id = 0;
+    loop {
+        let batch = storage_audit::fetch_capture_batch(pool, after_id, BATCH_SIZE).await?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        after_id = last.id;
+        summary.captures += batch.len();
+
+        for capture in batch {
+            let capture_ref = Some((capture.id, capture.captured_at));
+
+            match objects.remove(&capture.gcs_path) {
+                Some(object) => {
+                    if let Some(expected) = capture.file_size_bytes
+                        && expected as u64 != object.size
+                    {
+                        let detail = format!("row says {} bytes, stored {}", expected, object.size);
+                        storage_audit::record_finding(
+                            pool,
+                            SIZE_MISMATCH,
+                            &capture.gcs_path,
+                            capture_ref,
+                            Some(&detail),
+                        )
+                        .await?;
+                        summary.size_mismatches += 1;
+                    }
+                }
+                None => {
+                    if !storage::object_exists(gcs, local_storage_path, buckets, &capture.gcs_path)
+                        .await?
+                    {
+                        storage_audit::record_finding(
+                            pool,
+                            MISSING_OBJECT,
+                            &capture.gcs_path,
+                            capture_ref,
+                            None,
+                        )
+                        .await?;
+                        summary.missing += 1;
+                    }
+                }
+            }
+
+            if let Some(thumbnail_path) = capture.thumbnail_path
+                && objects.remove(&thumbnail_path).is_none()
+                && !storage::object_exists(gcs, local_storage_path, buckets, &thumbnail_path)
+                    .await?
+            {
+                storage_audit::record_finding(
+                    pool,
+                    MISSING_THUMBNAIL,
+                    &thumbnail_path,
+                    capture_ref,
+                    None,
+                )
+                .await?;
+                summary.missing_thumbnails += 1;
+            }
+        }
+    }
+
+    // Whatever no row claimed is orphaned, unless it may still be mid-upload
+    let grace_cutoff = run_started_at - Duration::minutes(ORPHAN_GRACE_MINUTES);
+    for (path, object) in objects {
+        if object.modified.is_some_and(|m| m > grace_cutoff) {
+            continue;
+        }
+        let detail = format!("{} bytes", object.size);
+        storage_audit::record_finding(pool, ORPHANED_OBJECT, &path, None, Some(&detail)).await?;
+        summary.orphaned += 1;
+    }
+
+    summary.resolved = storage_audit::resolve_stale_findings(pool, run_started_at).await?;
+    Ok(summary)
+}
+
+/// Fix open findings that are safe to repair automatically.
+/// Orphans are re-checked against the captures table before deletion.
+pub async fn repair_findings(
+    pool: &PgPool,
+    gcs: Option<&google_cloud_storage::client::Storage>,
+    local_storage_path: Option<&PathBuf>,
+    buckets: &Buckets,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let findings = storage_audit::list_open_findings(pool, REPAIRABLE_KINDS).await?;
+    let mut repaired = 0;
+
+    for finding in findings {
+        let resolution = match finding.kind.as_str() {
+            ORPHANED_OBJECT => {
+                if storage_audit::path_referenced(pool, &finding.path).await? {
+                    "referenced again"
+                } else {
+                    storage::delete_object(gcs, local_storage_path, buckets, &finding.path).await?;
+                    "deleted orphaned object"
+                }
+            }
+            MISSING_THUMBNAIL => {
+                let (Some(capture_id), Some(captured_at)) =
+                    (finding.capture_id, finding.captured_at)
+                else {
+                    continue;
+                };
+                storage_audit::reset_thumbnail(pool, capture_id, captured_at).await?;
+                "queued thumbnail regeneration"
+            }
+            _ => continue,
+        };
+
+        storage_audit::resolve_finding(pool, finding.id, resolution).await?;
+        repaired += 1;
+    }
+
+    Ok(repaired)
+}
+
+/// Resized thumbnails (`thumbnails/w{W}/...`) are caches with no row of their own
+fn is_derived_thumbnail(path: &str) -> bool {
+    path.strip_prefix("thumbnails/w")
+        .and_then(|rest| rest.split_once('/'))
+        .is_some_and(|(width, _)| !width.is_empty() && width.bytes().all(|b| b.is_ascii_digit()))
+}
+
+pub fn storage_audit_interval_secs() -> u64 {
+    crate::config::get().workers.storage_audit.interval_secs
+}
+
+fn storage_audit_auto_repair() -> bool {
+    crate::config::get().workers.storage_audit.auto_repair
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_derived_thumbnail() {
+        assert!(is_derived_thumbnail(
+            "thumbnails/w320/user_1/2025-01-01/1.jpg"
+        ));
+        assert!(!is_derived_thumbnail("thumbnails/user_1/2025-01-01/1.jpg"));
+        assert!(!is_derived_thumbnail("thumbnails/wide/1.jpg"));
+        assert!(!is_derived_thumbnail("image/user_1/w320/1.png"));
+    }
+}
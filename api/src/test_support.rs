@@ -0,0 +1,404 @@
+//! Test harness: AppState backed by in-memory storage and mock providers
+//!
+//! Route tests build the real router over a `#[sqlx::test]` database and drive
+//! it with `TestApp::request`. Mocks record what handlers sent them.
+
+use async_trait::async_trait;
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, Response};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+use crate::AppState;
+use crate::domain::twitter::Poll;
+use crate::routes;
+use crate::services::email::{EmailMessage, EmailSender};
+use crate::services::llm::{LlmError, LlmProvider};
+use crate::services::login_providers::{LoginClient, LoginProvider, ProviderIdentity};
+use crate::services::object_store::{ObjectStore, StoreError};
+use crate::services::session;
+use crate::services::social::{SocialClient, UploadProgressFn};
+use crate::services::twitter::{
+    self, AuthorizeRequest, TokenResponse, TweetResponse, TwitterError, TwitterUser,
+};
+use crate::storage::Buckets;
+
+const TEST_JWT_SECRET: &[u8] = b"test-secret-test-secret-test-secret";
+
+/// Objects kept in a map keyed by storage path
+#[derive(Default)]
+pub struct MemoryStore {
+    objects: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn paths(&self) -> Vec<String> {
+        self.objects.lock().unwrap().keys().cloned().collect()
+    }
+}
+
+#[async_trait]
+impl ObjectStore for MemoryStore {
+    async fn get(&self, path: &str) -> Result<Vec<u8>, StoreError> {
+        self.objects
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| format!("object not found: {}", path).into())
+    }
+
+    async fn put(&self, path: &str, data: &[u8]) -> Result<(), StoreError> {
+        self.objects
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), StoreError> {
+        self.objects.lock().unwrap().remove(path);
+        Ok(())
+    }
+}
+
+/// A tweet the handler asked the mock to post
+#[derive(Debug, Clone)]
+pub struct PostedTweet {
+    pub text: String,
+    pub in_reply_to: Option<String>,
+    pub quote_tweet_id: Option<String>,
+    pub media_ids: Vec<String>,
+    pub poll: Option<Poll>,
+}
+
+/// Social client that never leaves the process
+#[derive(Default)]
+pub struct MockSocialClient {
+    pub posted: Mutex<Vec<PostedTweet>>,
+    pub deleted: Mutex<Vec<String>>,
+    /// Tweet IDs `get_tweet` reports as not found
+    pub missing: Mutex<Vec<String>>,
+    pub uploads: Mutex<Vec<String>>,
+    /// Subscription tier `get_me` reports
+    pub subscription_type: Mutex<Option<String>>,
+}
+
+#[async_trait]
+impl SocialClient for MockSocialClient {
+    fn get_authorize_url(&self, _scopes: &[&str]) -> AuthorizeRequest {
+        AuthorizeRequest {
+            url: "https://example.test/authorize".to_string(),
+            state: "state".to_string(),
+            code_verifier: "verifier".to_string(),
+        }
+    }
+
+    async fn exchange_code(
+        &self,
+        _code: &str,
+        _code_verifier: &str,
+    ) -> Result<TokenResponse, TwitterError> {
+        Ok(TokenResponse {
+            access_token: "mock-access-token".to_string(),
+            token_type: "bearer".to_string(),
+            expires_in: 7200,
+            refresh_token: Some("mock-refresh-token".to_string()),
+            scope: "tweet.read tweet.write users.read offline.access".to_string(),
+        })
+    }
+
+    /// Every refresh token is treated as revoked
+    async fn refresh_token(&self, _refresh_token: &str) -> Result<TokenResponse, TwitterError> {
+        Err(TwitterError::Api(
+            r#"{"error":"invalid_grant","error_description":"Value passed for the token was invalid."}"#
+                .to_string(),
+        ))
+    }
+
+    async fn get_me(&self, _access_token: &str) -> Result<TwitterUser, TwitterError> {
+        Ok(TwitterUser {
+            id: "42".to_string(),
+            name: "Test User".to_string(),
+            username: "testuser".to_string(),
+            subscription_type: self.subscription_type.lock().unwrap().clone(),
+        })
+    }
+
+    async fn get_tweet(
+        &self,
+        _access_token: &str,
+        tweet_id: &str,
+    ) -> Result<TweetResponse, TwitterError> {
+        if self.missing.lock().unwrap().iter().any(|id| id == tweet_id) {
+            return Err(TwitterError::Api("Could not find tweet".to_string()));
+        }
+        Ok(TweetResponse {
+            id: tweet_id.to_string(),
+            text: "An existing tweet".to_string(),
+            public_metrics: None,
+        })
+    }
+
+    async fn post_tweet(
+        &self,
+        _access_token: &str,
+        text: &str,
+        in_reply_to: Option<&str>,
+        quote_tweet_id: Option<&str>,
+        media_ids: Option<&[String]>,
+        poll: Option<&Poll>,
+    ) -> Result<TweetResponse, TwitterError> {
+        let mut posted = self.posted.lock().unwrap();
+        posted.push(PostedTweet {
+            text: text.to_string(),
+            in_reply_to: in_reply_to.map(str::to_string),
+            quote_tweet_id: quote_tweet_id.map(str::to_string),
+            media_ids: media_ids.map(<[String]>::to_vec).unwrap_or_default(),
+            poll: poll.cloned(),
+        });
+        Ok(TweetResponse {
+            id: format!("mock-tweet-{}", posted.len()),
+            text: text.to_string(),
+            public_metrics: None,
+        })
+    }
+
+    async fn delete_tweet(&self, _access_token: &str, tweet_id: &str) -> Result<(), TwitterError> {
+        self.deleted.lock().unwrap().push(tweet_id.to_string());
+        Ok(())
+    }
+
+    async fn upload_media(
+        &self,
+        _access_token: &str,
+        _data: &[u8],
+        media_type: &str,
+    ) -> Result<String, TwitterError> {
+        let mut uploads = self.uploads.lock().unwrap();
+        uploads.push(media_type.to_string());
+        Ok(format!("mock-media-{}", uploads.len()))
+    }
+
+    async fn upload_media_with_progress(
+        &self,
+        access_token: &str,
+        data: &[u8],
+        media_type: &str,
+        on_progress: UploadProgressFn,
+    ) -> Result<String, TwitterError> {
+        on_progress(1, 1);
+        self.upload_media(access_token, data, media_type).await
+    }
+}
+
+/// LLM that answers every prompt with a fixed response
+pub struct MockLlm {
+    pub response: String,
+    pub prompts: Mutex<Vec<String>>,
+}
+
+impl MockLlm {
+    pub fn new(response: &str) -> Self {
+        Self {
+            response: response.to_string(),
+            prompts: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MockLlm {
+    async fn complete(
+        &self,
+        prompt: &str,
+        _max_tokens: u32,
+        _temperature: f32,
+    ) -> Result<String, LlmError> {
+        self.prompts.lock().unwrap().push(prompt.to_string());
+        Ok(self.response.clone())
+    }
+}
+
+/// Email sender that keeps what it was asked to send
+#[derive(Default)]
+pub struct MockEmailSender {
+    pub sent: Mutex<Vec<EmailMessage>>,
+}
+
+impl MockEmailSender {
+    /// The sign-in token from the last link sent to `to`
+    pub fn last_token(&self, to: &str) -> Option<String> {
+        self.sent
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|m| m.to == to)
+            .and_then(|m| m.text.split("email_token=").nth(1))
+            .and_then(|rest| rest.split_whitespace().next())
+            .map(str::to_string)
+    }
+}
+
+#[async_trait]
+impl EmailSender for MockEmailSender {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        self.sent.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+}
+
+/// GitHub/Google login where the code names the user: code "octocat" logs in
+/// as octocat, with email octocat@example.test
+pub struct MockLoginClient;
+
+#[async_trait]
+impl LoginClient for MockLoginClient {
+    fn enabled(&self) -> Vec<LoginProvider> {
+        vec![LoginProvider::Github, LoginProvider::Google]
+    }
+
+    fn authorize_url(
+        &self,
+        provider: LoginProvider,
+        state: &str,
+        _code_challenge: &str,
+    ) -> Option<String> {
+        Some(format!(
+            "https://example.test/{}/authorize?state={}",
+            provider.as_str(),
+            state
+        ))
+    }
+
+    async fn fetch_identity(
+        &self,
+        provider: LoginProvider,
+        code: &str,
+        _code_verifier: &str,
+    ) -> Result<ProviderIdentity, String> {
+        Ok(ProviderIdentity {
+            provider_user_id: format!("{}-{}", provider.as_str(), code),
+            username: code.to_string(),
+            email: Some(format!("{}@example.test", code)),
+            name: None,
+        })
+    }
+}
+
+/// Router plus handles on the mocks behind it
+pub struct TestApp {
+    pub state: Arc<AppState>,
+    pub store: Arc<MemoryStore>,
+    pub social: Arc<MockSocialClient>,
+    pub llm: Arc<MockLlm>,
+    pub email: Arc<MockEmailSender>,
+    router: Router,
+}
+
+impl TestApp {
+    pub fn new(pool: PgPool) -> Self {
+        let store = Arc::new(MemoryStore::default());
+        let social = Arc::new(MockSocialClient::default());
+        let llm = Arc::new(MockLlm::new("A fresh take on the same idea"));
+        let email = Arc::new(MockEmailSender::default());
+
+        let state = Arc::new(AppState {
+            db: pool.clone(),
+            worker_db: pool,
+            gcs: None,
+            store: store.clone(),
+            twitter: social.clone(),
+            login: Arc::new(MockLoginClient),
+            email: Some(email.clone()),
+            local_storage_path: None,
+            buckets: Buckets {
+                media: "test-media".to_string(),
+                derived: "test-derived".to_string(),
+            },
+            jwt_secret: TEST_JWT_SECRET.to_vec(),
+            gemini: None,
+            llm: Some(llm.clone()),
+            allowed_users: None,
+            admin_users: HashSet::new(),
+        });
+        let router = routes::build_routes().with_state(state.clone());
+
+        Self {
+            state,
+            store,
+            social,
+            llm,
+            email,
+            router,
+        }
+    }
+
+    /// Create a user with a long-lived Twitter token
+    pub async fn create_user(&self, username: &str) -> i64 {
+        twitter::upsert_user(
+            &self.state.db,
+            &format!("tw-{}", username),
+            username,
+            Some(username),
+            "twitter-access-token",
+            None,
+            Utc::now() + Duration::days(1),
+        )
+        .await
+        .expect("create user")
+    }
+
+    /// Cookie header value for a logged-in browser session
+    pub fn session_cookie(&self, user_id: i64) -> String {
+        let token = session::create_access_token(user_id, &self.state.jwt_secret)
+            .expect("create access token");
+        format!("access_token={}", token)
+    }
+
+    /// Bearer token for daemon requests
+    pub async fn api_token(&self, user_id: i64) -> String {
+        let token = twitter::generate_api_token();
+        twitter::set_user_api_token(&self.state.db, user_id, &token)
+            .await
+            .expect("set api token");
+        token
+    }
+
+    /// Insert a pending tweet for the user and return its collateral ID
+    pub async fn create_tweet(&self, user_id: i64, text: &str) -> i64 {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO tweet_collateral (user_id, text, image_capture_ids, rationale, created_at)
+            VALUES ($1, $2, '{}', 'test rationale', NOW())
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(text)
+        .fetch_one(&self.state.db)
+        .await
+        .expect("create tweet")
+    }
+
+    pub async fn request(&self, request: Request<Body>) -> Response<Body> {
+        self.router
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("router is infallible")
+    }
+}
+
+/// Collect a response body as JSON
+pub async fn body_json(response: Response<Body>) -> serde_json::Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    serde_json::from_slice(&bytes).expect("JSON body")
+}
@@ -6,9 +6,9 @@ use apalis::prelude::*;
 use apalis_cron::{CronStream, Schedule};
 use apalis_sql::postgres::PostgresStorage;
 use image::ImageReader;
+use image::codecs::jpeg::JpegEncoder;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::env;
 use std::io::Cursor;
 use std::path::PathBuf;
 use std::process::Stdio;
@@ -16,16 +16,14 @@ use std::str::FromStr;
 use tokio::process::Command;
 
 use crate::models::CaptureForThumbnail;
-use crate::storage;
+use crate::storage::{self, Buckets};
 
 const THUMBNAIL_WIDTH: u32 = 300;
 const THUMBNAIL_QUALITY: u8 = 80;
 const MAX_ATTEMPTS: i32 = 5;
 const CLAIM_BATCH_SIZE: i64 = 64;
-const DEFAULT_CONCURRENCY: usize = 12;
-const DEFAULT_CRON_SECONDS: u64 = 5;
-const DEFAULT_LEASE_SECONDS: i64 = 900;
-const DEFAULT_FFMPEG_THREADS: usize = 1;
+/// Widths served by the resizing thumbnail proxy; requests round up to the next one
+const RESIZE_WIDTHS: &[u32] = &[80, 160, 240, 320, 480, 640];
 
 /// Job input - marker for batch processing
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,7 +43,7 @@ pub struct ThumbnailContext {
     pub pool: PgPool,
     pub gcs: Option<google_cloud_storage::client::Storage>,
     pub local_storage_path: Option<PathBuf>,
-    pub bucket_name: String,
+    pub buckets: Buckets,
 }
 
 /// Job handler - processes a batch of thumbnails
@@ -54,6 +52,7 @@ async fn process_thumbnail_job(
     _job: ThumbnailJob,
     ctx: Data<ThumbnailContext>,
 ) -> Result<(), Error> {
+    crate::services::supervisor::heartbeat("thumbnails");
     match process_thumbnail_batch(&ctx).await {
         Ok((processed, failed)) => {
             if processed > 0 || failed > 0 {
@@ -75,13 +74,13 @@ pub async fn run_thumbnail_worker(
     pool: PgPool,
     gcs: Option<google_cloud_storage::client::Storage>,
     local_storage_path: Option<PathBuf>,
-    bucket_name: String,
+    buckets: Buckets,
 ) {
     let ctx = ThumbnailContext {
         pool: pool.clone(),
         gcs,
         local_storage_path,
-        bucket_name,
+        buckets,
     };
 
     let cron_seconds = thumbnail_cron_seconds();
@@ -147,13 +146,13 @@ async fn process_thumbnail_batch(
                 let pool = ctx.pool.clone();
                 let gcs = ctx.gcs.clone();
                 let local_path = ctx.local_storage_path.clone();
-                let bucket = ctx.bucket_name.clone();
+                let buckets = ctx.buckets.clone();
 
                 tasks.spawn(async move {
                     let data = match storage::download_capture(
                         gcs.as_ref(),
                         local_path.as_ref(),
-                        &bucket,
+                        &buckets,
                         &capture.gcs_path,
                     )
                     .await
@@ -173,7 +172,7 @@ async fn process_thumbnail_batch(
                         &pool,
                         gcs.as_ref(),
                         local_path.as_ref(),
-                        &bucket,
+                        &buckets,
                         &capture,
                         &data,
                     )
@@ -240,7 +239,7 @@ async fn process_single_capture(
     pool: &PgPool,
     gcs: Option<&google_cloud_storage::client::Storage>,
     local_storage_path: Option<&PathBuf>,
-    bucket_name: &str,
+    buckets: &Buckets,
     capture: &CaptureForThumbnail,
     data: &[u8],
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -254,7 +253,7 @@ async fn process_single_capture(
     storage::upload_data(
         gcs,
         local_storage_path,
-        bucket_name,
+        buckets,
         &thumbnail_path,
         &thumbnail_data,
     )
@@ -275,7 +274,7 @@ async fn process_single_capture(
 
     if let Err(e) = db_result {
         if let Err(cleanup_err) =
-            delete_thumbnail(gcs, local_storage_path, bucket_name, &thumbnail_path).await
+            delete_thumbnail(gcs, local_storage_path, buckets, &thumbnail_path).await
         {
             eprintln!(
                 "[thumbnails] Failed to clean up orphaned thumbnail {}: {}",
@@ -297,7 +296,7 @@ async fn process_single_capture(
 async fn delete_thumbnail(
     _gcs: Option<&google_cloud_storage::client::Storage>,
     local_storage_path: Option<&PathBuf>,
-    bucket_name: &str,
+    buckets: &Buckets,
     thumbnail_path: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     if let Some(local_path) = local_storage_path {
@@ -305,7 +304,10 @@ async fn delete_thumbnail(
         tokio::fs::remove_file(&full_path).await?;
     } else {
         let client = cloud_storage::Client::default();
-        client.object().delete(bucket_name, thumbnail_path).await?;
+        client
+            .object()
+            .delete(buckets.for_path(thumbnail_path), thumbnail_path)
+            .await?;
     }
     Ok(())
 }
@@ -322,6 +324,59 @@ fn get_thumbnail_path(original_path: &str) -> String {
     format!("thumbnails/{}/{}.jpg", parent.display(), stem)
 }
 
+/// Snap a requested width to one of the cached resize widths
+pub fn resize_width_for(requested: u32) -> u32 {
+    RESIZE_WIDTHS
+        .iter()
+        .copied()
+        .find(|w| *w >= requested)
+        .unwrap_or(RESIZE_WIDTHS[RESIZE_WIDTHS.len() - 1])
+}
+
+/// Whether a resize needs the original image rather than the stored thumbnail
+pub fn resize_needs_original(width: u32) -> bool {
+    width > THUMBNAIL_WIDTH
+}
+
+/// Storage path of a derived thumbnail, e.g.
+/// "thumbnails/user_1/2025-01-01/123.jpg" -> "thumbnails/w160/user_1/2025-01-01/123.jpg"
+pub fn resized_thumbnail_path(thumbnail_path: &str, width: u32) -> String {
+    let rest = thumbnail_path
+        .strip_prefix("thumbnails/")
+        .unwrap_or(thumbnail_path);
+    format!("thumbnails/w{}/{}", width, rest)
+}
+
+/// All derived thumbnail paths that may exist for a thumbnail (for cleanup)
+pub fn resized_thumbnail_paths(thumbnail_path: &str) -> Vec<String> {
+    RESIZE_WIDTHS
+        .iter()
+        .map(|w| resized_thumbnail_path(thumbnail_path, *w))
+        .collect()
+}
+
+/// Resize an image to `width` (keeping aspect ratio, never upscaling) as JPEG
+pub fn resize_to_width(
+    data: &[u8],
+    width: u32,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let img = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()?
+        .decode()?;
+
+    let resized = if img.width() > width {
+        img.thumbnail(width, u32::MAX)
+    } else {
+        img
+    };
+
+    let mut output = Cursor::new(Vec::new());
+    let encoder = JpegEncoder::new_with_quality(&mut output, THUMBNAIL_QUALITY);
+    resized.to_rgb8().write_with_encoder(encoder)?;
+
+    Ok(output.into_inner())
+}
+
 fn generate_image_thumbnail(
     data: &[u8],
 ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
@@ -464,35 +519,19 @@ async fn claim_thumbnail_captures(
 }
 
 fn thumbnail_concurrency() -> usize {
-    env::var("THUMBNAIL_CONCURRENCY")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .filter(|v| *v > 0)
-        .unwrap_or(DEFAULT_CONCURRENCY)
+    crate::config::get().workers.thumbnails.concurrency
 }
 
-fn thumbnail_cron_seconds() -> u64 {
-    env::var("THUMBNAIL_CRON_SECONDS")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .filter(|v| *v > 0 && *v <= 59)
-        .unwrap_or(DEFAULT_CRON_SECONDS)
+pub fn thumbnail_cron_seconds() -> u64 {
+    crate::config::get().workers.thumbnails.cron_seconds
 }
 
 fn thumbnail_lease_seconds() -> i64 {
-    env::var("THUMBNAIL_LEASE_SECONDS")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .filter(|v| *v > 0)
-        .unwrap_or(DEFAULT_LEASE_SECONDS)
+    crate::config::get().workers.thumbnails.lease_secs
 }
 
 fn ffmpeg_threads() -> usize {
-    env::var("FFMPEG_THREADS")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .filter(|v| *v > 0)
-        .unwrap_or(DEFAULT_FFMPEG_THREADS)
+    crate::config::get().workers.ffmpeg_threads
 }
 
 #[cfg(test)]
@@ -510,4 +549,16 @@ mod tests {
             "thumbnails/user_1/2025-01-01/123456.jpg"
         );
     }
+
+    #[test]
+    fn test_resize_width_buckets() {
+        assert_eq!(resize_width_for(1), 80);
+        assert_eq!(resize_width_for(160), 160);
+        assert_eq!(resize_width_for(300), 320);
+        assert_eq!(resize_width_for(4000), 640);
+        assert_eq!(
+            resized_thumbnail_path("thumbnails/user_1/2025-01-01/123456.jpg", 160),
+            "thumbnails/w160/user_1/2025-01-01/123456.jpg"
+        );
+    }
 }
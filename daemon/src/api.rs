@@ -1,28 +1,69 @@
 use std::fmt;
+use std::io::{self, Cursor};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
-use reqwest::blocking::{Client, RequestBuilder, Response, multipart};
-use reqwest::header::AUTHORIZATION;
+use reqwest::blocking::{Body, Client, RequestBuilder, Response, multipart};
+use reqwest::header::{
+    AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE, ETAG, HeaderMap, HeaderValue, IF_NONE_MATCH,
+};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
+use crate::bandwidth::{RateLimiter, ThrottledReader};
+use crate::compression::Compression;
+use crate::device::DeviceIdentity;
+use crate::ingest_proto;
 use crate::interval::current_interval_id;
+use crate::session::current_session_id;
+use crate::throttle;
+
+/// How long to wait for X to accept a draft published from the daemon
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Default request timeout; rate-limited uploads get extra time on top
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Errors that can occur while interacting with the remote capture API.
 #[derive(Debug)]
 pub enum ApiError {
     Http(reqwest::Error),
-    UnexpectedStatus { status: StatusCode, body: String },
+    /// Serializing or compressing a request body failed
+    Encode(io::Error),
+    UnexpectedStatus {
+        status: StatusCode,
+        body: String,
+    },
+    /// Capture quota used up: 429 (daily, clears after `retry_after`) or 402 (storage).
+    QuotaExceeded {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+        quota: Option<QuotaRemaining>,
+    },
 }
 
 impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ApiError::Http(err) => write!(f, "http error: {err}"),
+            ApiError::Encode(err) => write!(f, "request encoding error: {err}"),
             ApiError::UnexpectedStatus { status, body } => {
                 write!(f, "unexpected status {status}: {body}")
             }
+            ApiError::QuotaExceeded {
+                status,
+                retry_after: Some(wait),
+                ..
+            } => write!(
+                f,
+                "capture quota exceeded ({status}), retry in {}s",
+                wait.as_secs()
+            ),
+            ApiError::QuotaExceeded { status, .. } => {
+                write!(f, "capture storage quota exceeded ({status})")
+            }
         }
     }
 }
@@ -36,6 +77,68 @@ pub struct BatchUploadResult {
     pub failed: usize,
     #[serde(default)]
     pub successful_indices: Vec<usize>,
+    /// Quota left after this batch (absent from older servers)
+    #[serde(default)]
+    pub quota: Option<QuotaRemaining>,
+}
+
+/// Remaining capture quota reported by the API; None fields are unlimited.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuotaRemaining {
+    pub captures: Option<u64>,
+    pub bytes: Option<u64>,
+    pub storage_bytes: Option<u64>,
+    /// When the daily counters reset
+    pub resets_at: DateTime<Utc>,
+}
+
+/// Error body the API sends with quota rejections.
+#[derive(Debug, Deserialize)]
+struct QuotaErrorBody {
+    error: String,
+    #[serde(default)]
+    retry_after: Option<u64>,
+    #[serde(default)]
+    quota: Option<QuotaRemaining>,
+}
+
+/// Message field of the API's JSON error bodies.
+#[derive(Debug, Deserialize)]
+struct ErrorMessageBody {
+    message: String,
+}
+
+impl ApiError {
+    /// Short description for the user: the API's own message when it sent one.
+    pub fn user_message(&self) -> String {
+        match self {
+            ApiError::Http(err) if err.is_timeout() => "the server took too long".to_string(),
+            ApiError::Http(_) => "couldn't reach the server".to_string(),
+            ApiError::Encode(_) => "couldn't prepare the upload".to_string(),
+            ApiError::UnexpectedStatus { status, body } => {
+                serde_json::from_str::<ErrorMessageBody>(body)
+                    .map(|parsed| parsed.message)
+                    .unwrap_or_else(|_| format!("server returned {status}"))
+            }
+            ApiError::QuotaExceeded { .. } => self.to_string(),
+        }
+    }
+
+    /// Interprets a failed response, recognising quota rejections.
+    fn from_status(status: StatusCode, body: String) -> Self {
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::PAYMENT_REQUIRED {
+            if let Ok(parsed) = serde_json::from_str::<QuotaErrorBody>(&body) {
+                if parsed.error == "quota_exceeded" || parsed.error == "storage_quota_exceeded" {
+                    return ApiError::QuotaExceeded {
+                        status,
+                        retry_after: parsed.retry_after.map(Duration::from_secs),
+                        quota: parsed.quota,
+                    };
+                }
+            }
+        }
+        ApiError::UnexpectedStatus { status, body }
+    }
 }
 
 /// Recording limits fetched from the API.
@@ -51,6 +154,18 @@ pub struct RecordingLimits {
     pub storage_limit_bytes: u64,
     /// Current storage used in bytes
     pub storage_used_bytes: u64,
+    /// When the user's launch mode ends; None when it's off
+    #[serde(default)]
+    pub launch_mode_until: Option<DateTime<Utc>>,
+    /// Longest screenshot interval to allow while launch mode is on
+    #[serde(default)]
+    pub screenshot_interval_secs: Option<u64>,
+    /// Drafts waiting for review
+    #[serde(default)]
+    pub drafts_ready: u64,
+    /// Web app that notifications open
+    #[serde(default)]
+    pub app_url: Option<String>,
 }
 
 impl RecordingLimits {
@@ -64,6 +179,14 @@ impl RecordingLimits {
     pub fn storage_exceeded(&self) -> bool {
         self.storage_used_bytes >= self.storage_limit_bytes
     }
+
+    /// Returns the screenshot interval ceiling while launch mode is still on at `now`
+    pub fn screenshot_ceiling(&self, now: DateTime<Utc>) -> Option<Duration> {
+        match (self.launch_mode_until, self.screenshot_interval_secs) {
+            (Some(until), Some(secs)) if until > now => Some(Duration::from_secs(secs.max(1))),
+            _ => None,
+        }
+    }
 }
 
 impl From<reqwest::Error> for ApiError {
@@ -72,26 +195,97 @@ impl From<reqwest::Error> for ApiError {
     }
 }
 
+/// A pending draft tweet from `/me/drafts`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DraftPreview {
+    pub id: i64,
+    /// First non-empty line of the tweet
+    pub first_line: String,
+    /// Web review page opened at this draft
+    pub url: String,
+}
+
+/// Newest pending drafts fetched from the API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DraftsPage {
+    pub drafts: Vec<DraftPreview>,
+    /// All pending drafts, including those past the requested limit
+    pub total: u64,
+    /// Review page for the whole queue
+    pub app_url: String,
+}
+
+/// Encoding of activity uploads (`daemon.upload.transport` in the Cleo config)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    #[default]
+    Json,
+    /// Protobuf bodies, see `ingest_proto`
+    Protobuf,
+}
+
+/// Last response of a polled endpoint with its ETag, shared by clones of the
+/// client so every poller revalidates against the same copy
+type Cached<T> = Arc<Mutex<Option<(String, T)>>>;
+
 /// Blocking API client that knows how to hit Cleo's capture endpoints.
 #[derive(Debug, Clone)]
 pub struct ApiClient {
     base_url: String,
     http: Client,
     auth_token: Option<String>,
+    device: Option<DeviceIdentity>,
+    /// Shared by clones, so all capture uploads together stay under the limit
+    upload_limiter: Option<Arc<RateLimiter>>,
+    transport: Transport,
+    compression: Compression,
+    limits_cache: Cached<RecordingLimits>,
+    drafts_cache: Cached<DraftsPage>,
 }
 
 impl ApiClient {
     /// Create a new client targeting the provided base URL.
     pub fn new(base_url: impl Into<String>, auth_token: Option<String>) -> Result<Self, ApiError> {
-        let http = Client::builder().timeout(Duration::from_secs(60)).build()?;
+        let http = Client::builder().timeout(REQUEST_TIMEOUT).build()?;
 
         Ok(Self {
             base_url: base_url.into().trim_end_matches('/').to_string(),
             http,
             auth_token,
+            device: None,
+            upload_limiter: None,
+            transport: Transport::Json,
+            compression: Compression::Off,
+            limits_cache: Cached::default(),
+            drafts_cache: Cached::default(),
         })
     }
 
+    /// Identify this machine on every request via X-Device-ID / X-Device-Name.
+    pub fn with_device(mut self, device: DeviceIdentity) -> Self {
+        self.device = Some(device);
+        self
+    }
+
+    /// Cap capture uploads at `kb_per_sec` KB/s; 0 means no limit.
+    pub fn with_upload_limit(mut self, kb_per_sec: u64) -> Self {
+        self.upload_limiter = (kb_per_sec > 0).then(|| Arc::new(RateLimiter::new(kb_per_sec)));
+        self
+    }
+
+    /// Send activity and activity summaries as JSON or protobuf.
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Compress activity uploads and screenshot batches.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
     /// Uploads a batch of images to the `/captures/batch` endpoint.
     pub fn upload_images(
         &self,
@@ -101,7 +295,7 @@ impl ApiClient {
             .into_iter()
             .map(|(b, f)| (b, f.mime_type()))
             .collect();
-        self.upload_batch(parts)
+        self.upload_batch(parts, self.compression)
     }
 
     /// Uploads a batch of videos to the `/captures/batch` endpoint.
@@ -113,18 +307,21 @@ impl ApiClient {
             .into_iter()
             .map(|(b, f)| (b, f.mime_type()))
             .collect();
-        self.upload_batch(parts)
+        // Already compressed video doesn't get any smaller
+        self.upload_batch(parts, Compression::Off)
     }
 
     fn upload_batch(
         &self,
         captures: Vec<(Vec<u8>, &'static str)>,
+        compression: Compression,
     ) -> Result<BatchUploadResult, ApiError> {
         if captures.is_empty() {
             return Ok(BatchUploadResult {
                 uploaded: 0,
                 failed: 0,
                 successful_indices: vec![],
+                quota: None,
             });
         }
 
@@ -132,19 +329,72 @@ impl ApiClient {
         let interval_id = current_interval_id();
 
         let mut form = multipart::Form::new();
+        // Same parts => same key, so a retried batch isn't ingested twice
+        let mut batch_digest = Sha256::new();
+        let mut total_bytes = 0u64;
         for (i, (bytes, mime_type)) in captures.into_iter().enumerate() {
-            let part = multipart::Part::bytes(bytes)
+            // Let the server reject parts corrupted in transit
+            let digest: String = Sha256::digest(&bytes)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            batch_digest.update(digest.as_bytes());
+            let mut part_headers = HeaderMap::new();
+            part_headers.insert(
+                "X-Content-SHA256",
+                HeaderValue::from_str(&digest).expect("hex digest is a valid header value"),
+            );
+            let len = bytes.len() as u64;
+            total_bytes += len;
+            // A compressed form is throttled as a whole below
+            let part = match &self.upload_limiter {
+                Some(limiter) if compression == Compression::Off => {
+                    multipart::Part::reader_with_length(
+                        ThrottledReader::new(Cursor::new(bytes), Arc::clone(limiter)),
+                        len,
+                    )
+                }
+                _ => multipart::Part::bytes(bytes),
+            };
+            let part = part
                 .mime_str(mime_type)
                 .map_err(|e| ApiError::Http(e.into()))?
-                .file_name(format!("file_{}", i));
+                .file_name(format!("file_{}", i))
+                .headers(part_headers);
             form = form.part("file", part);
         }
 
-        let request = self
+        let idempotency_key: String = batch_digest
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        let mut request = self
             .http
             .post(url)
             .header("X-Interval-ID", interval_id.to_string())
-            .multipart(form);
+            .header("Idempotency-Key", idempotency_key);
+        if let Some(session_id) = current_session_id() {
+            request = request.header("X-Session-ID", session_id.to_string());
+        }
+        request = match compression.content_encoding() {
+            Some(encoding) => {
+                let content_type = format!("multipart/form-data; boundary={}", form.boundary());
+                let body = compression
+                    .compress(form.into_reader())
+                    .map_err(ApiError::Encode)?;
+                total_bytes = body.len() as u64;
+                request
+                    .header(CONTENT_TYPE, content_type)
+                    .header(CONTENT_ENCODING, encoding)
+                    .body(self.throttled_body(body))
+            }
+            None => request.multipart(form),
+        };
+        if let Some(limiter) = &self.upload_limiter {
+            request = request.timeout(REQUEST_TIMEOUT + limiter.duration_for(total_bytes));
+        }
         let response = self.authorized(request).send()?;
 
         if response.status().is_success() {
@@ -152,31 +402,94 @@ impl ApiClient {
                 uploaded: 0,
                 failed: 0,
                 successful_indices: vec![],
+                quota: None,
             });
+            if let Some(quota) = &result.quota {
+                throttle::record_quota(quota);
+            }
             Ok(result)
         } else {
             let status = response.status();
             let body = response.text().unwrap_or_default();
-            Err(ApiError::UnexpectedStatus { status, body })
+            let err = ApiError::from_status(status, body);
+            // Back off instead of retrying every cycle until the quota clears
+            if let ApiError::QuotaExceeded { retry_after, .. } = &err {
+                throttle::pause_for_quota(*retry_after);
+            }
+            Err(err)
         }
     }
 
     /// Sends a batch of activity events to the `/activity` endpoint.
     pub fn upload_activity(&self, events: &[ActivityEntry]) -> Result<(), ApiError> {
         let url = format!("{}/activity", self.base_url);
-        let request = self.http.post(url).json(events);
+        let body = match self.transport {
+            Transport::Json => {
+                serde_json::to_vec(events).map_err(|e| ApiError::Encode(e.into()))?
+            }
+            Transport::Protobuf => ingest_proto::encode_activity(events),
+        };
+        let request = self.activity_request(url, body)?;
         let response = self.authorized(request).send()?;
         Self::handle_response(response)
     }
 
-    /// Fetches recording limits from the `/me/limits` endpoint.
-    pub fn fetch_limits(&self) -> Result<RecordingLimits, ApiError> {
-        let url = format!("{}/me/limits", self.base_url);
-        let request = self.http.get(url);
+    /// Sends per-interval activity rollups to the `/activity/summary` endpoint.
+    pub fn upload_activity_summaries(&self, summaries: &[ActivitySummary]) -> Result<(), ApiError> {
+        let url = format!("{}/activity/summary", self.base_url);
+        let body = match self.transport {
+            Transport::Json => {
+                serde_json::to_vec(summaries).map_err(|e| ApiError::Encode(e.into()))?
+            }
+            Transport::Protobuf => ingest_proto::encode_summaries(summaries),
+        };
+        let request = self.activity_request(url, body)?;
+        let response = self.authorized(request).send()?;
+        Self::handle_response(response)
+    }
+
+    /// POST an activity body encoded for the configured transport, compressed if enabled
+    fn activity_request(&self, url: String, body: Vec<u8>) -> Result<RequestBuilder, ApiError> {
+        let content_type = match self.transport {
+            Transport::Json => "application/json",
+            Transport::Protobuf => ingest_proto::CONTENT_TYPE,
+        };
+        let request = self.http.post(url).header(CONTENT_TYPE, content_type);
+        Ok(match self.compression.content_encoding() {
+            Some(encoding) => {
+                let body = self
+                    .compression
+                    .compress(body.as_slice())
+                    .map_err(ApiError::Encode)?;
+                request.header(CONTENT_ENCODING, encoding).body(body)
+            }
+            None => request.body(body),
+        })
+    }
+
+    /// Capture upload body that keeps to the upload limit, if there is one
+    fn throttled_body(&self, bytes: Vec<u8>) -> Body {
+        match &self.upload_limiter {
+            Some(limiter) => {
+                let len = bytes.len() as u64;
+                Body::sized(
+                    ThrottledReader::new(Cursor::new(bytes), Arc::clone(limiter)),
+                    len,
+                )
+            }
+            None => Body::from(bytes),
+        }
+    }
+
+    /// Opens a work session via `POST /sessions` and returns its id.
+    pub fn open_session(&self, device: Option<&str>) -> Result<i64, ApiError> {
+        let url = format!("{}/sessions", self.base_url);
+        let request = self.http.post(url).json(&OpenSessionRequest { device });
         let response = self.authorized(request).send()?;
 
         if response.status().is_success() {
-            response.json().map_err(ApiError::from)
+            let opened: OpenSessionResponse = response.json()?;
+            Ok(opened.id)
         } else {
             let status = response.status();
             let body = response.text().unwrap_or_default();
@@ -184,6 +497,129 @@ impl ApiClient {
         }
     }
 
+    /// Closes a work session via `POST /sessions/{id}/end`.
+    pub fn end_session(&self, session_id: i64) -> Result<(), ApiError> {
+        let url = format!("{}/sessions/{}/end", self.base_url, session_id);
+        let request = self.http.post(url);
+        let response = self.authorized(request).send()?;
+        Self::handle_response(response)
+    }
+
+    /// Asks for an immediate agent pass over a highlight recording via `POST /agent/highlight`.
+    pub fn report_highlight(
+        &self,
+        started_at: DateTime<Utc>,
+        ended_at: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        let url = format!("{}/agent/highlight", self.base_url);
+        let request = self.http.post(url).json(&HighlightRequest {
+            started_at,
+            ended_at,
+        });
+        let response = self.authorized(request).send()?;
+        Self::handle_response(response)
+    }
+
+    /// Registers this machine via `POST /me/devices` and returns its server-side id.
+    pub fn enroll_device(&self) -> Result<i64, ApiError> {
+        let url = format!("{}/me/devices", self.base_url);
+        let request = self.http.post(url);
+        let response = self.authorized(request).send()?;
+
+        if response.status().is_success() {
+            let enrolled: EnrollDeviceResponse = response.json()?;
+            Ok(enrolled.id)
+        } else {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            Err(ApiError::UnexpectedStatus { status, body })
+        }
+    }
+
+    /// Trades a one-time enrollment code from the daemon login page for an API
+    /// token via `POST /auth/daemon/enroll`.
+    pub fn redeem_enrollment_code(&self, code: &str) -> Result<String, ApiError> {
+        let url = format!("{}/auth/daemon/enroll", self.base_url);
+        let response = self
+            .http
+            .post(url)
+            .json(&EnrollmentCodeRequest { code })
+            .send()?;
+
+        if response.status().is_success() {
+            let enrolled: EnrollmentCodeResponse = response.json()?;
+            Ok(enrolled.api_token)
+        } else {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            Err(ApiError::UnexpectedStatus { status, body })
+        }
+    }
+
+    /// Fetches recording limits from the `/me/limits` endpoint.
+    pub fn fetch_limits(&self) -> Result<RecordingLimits, ApiError> {
+        let url = format!("{}/me/limits", self.base_url);
+        self.get_conditional(self.http.get(url), &self.limits_cache)
+    }
+
+    /// Fetches the newest `limit` pending drafts from the `/me/drafts` endpoint.
+    pub fn fetch_drafts(&self, limit: usize) -> Result<DraftsPage, ApiError> {
+        let url = format!("{}/me/drafts", self.base_url);
+        let request = self.http.get(url).query(&[("limit", limit)]);
+        self.get_conditional(request, &self.drafts_cache)
+    }
+
+    /// GET a polled endpoint, revalidating the cached copy with If-None-Match
+    /// so an unchanged response comes back as a bodiless 304.
+    fn get_conditional<T: DeserializeOwned + Clone>(
+        &self,
+        mut request: RequestBuilder,
+        cache: &Cached<T>,
+    ) -> Result<T, ApiError> {
+        let cached = cache.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some((etag, _)) = &cached {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = self.authorized(request).send()?;
+        let status = response.status();
+
+        if status == StatusCode::NOT_MODIFIED
+            && let Some((_, value)) = cached
+        {
+            return Ok(value);
+        }
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(ApiError::UnexpectedStatus { status, body });
+        }
+
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let value: T = response.json()?;
+        *cache.lock().unwrap_or_else(|e| e.into_inner()) = etag.map(|etag| (etag, value.clone()));
+        Ok(value)
+    }
+
+    /// Publishes a pending draft to X through `/tweets/:id/publish`.
+    pub fn publish_draft(&self, id: i64) -> Result<(), ApiError> {
+        let url = format!("{}/tweets/{id}/publish", self.base_url);
+        // Posting uploads the draft's media to X first, which can outlast the default timeout
+        let request = self.http.post(url).timeout(PUBLISH_TIMEOUT);
+        let response = self.authorized(request).send()?;
+        Self::handle_response(response)
+    }
+
+    /// Dismisses a pending draft without posting it.
+    pub fn dismiss_draft(&self, id: i64) -> Result<(), ApiError> {
+        let url = format!("{}/tweets/{id}", self.base_url);
+        let request = self.http.delete(url);
+        let response = self.authorized(request).send()?;
+        Self::handle_response(response)
+    }
+
     /// Returns the base URL configured for this client.
     pub fn base_url(&self) -> &str {
         &self.base_url
@@ -200,6 +636,20 @@ impl ApiClient {
     }
 
     fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        let integrity = crate::integrity::check();
+        // Reported on every request, so the `/me/limits` heartbeat carries it.
+        let request = request.header("X-Device-Integrity", integrity.label());
+        // Never hand the API token to the network from a build we can't vouch for.
+        if !integrity.allows_credentials() {
+            return request;
+        }
+        let mut request = request;
+        if let Some(device) = &self.device {
+            request = request.header("X-Device-ID", &device.uid);
+            if let Some(name) = &device.name {
+                request = request.header("X-Device-Name", name);
+            }
+        }
         if let Some(token) = &self.auth_token {
             request.header(AUTHORIZATION, format!("Bearer {}", token))
         } else {
@@ -246,12 +696,45 @@ impl VideoFormat {
     }
 }
 
+#[derive(Debug, Serialize)]
+struct HighlightRequest {
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenSessionRequest<'a> {
+    device: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenSessionResponse {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnrollDeviceResponse {
+    id: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct EnrollmentCodeRequest<'a> {
+    code: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnrollmentCodeResponse {
+    api_token: String,
+}
+
 /// Activity payload envelope that mirrors the `/activity` schema.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActivityEntry {
     pub timestamp: DateTime<Utc>,
     #[serde(rename = "intervalId")]
     pub interval_id: u64,
+    #[serde(rename = "sessionId", skip_serializing_if = "Option::is_none", default)]
+    pub session_id: Option<i64>,
     pub event: ActivityEvent,
 }
 
@@ -260,11 +743,30 @@ impl ActivityEntry {
         Self {
             timestamp,
             interval_id,
+            session_id: current_session_id(),
             event,
         }
     }
 }
 
+/// One interval of activity, mirroring the `/activity/summary` schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivitySummary {
+    pub interval_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub session_id: Option<i64>,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    /// App in the foreground longest during the interval
+    pub top_app: Option<String>,
+    pub app_switches: u32,
+    pub clicks: u32,
+    pub keypresses: u32,
+    /// Seconds with recent keyboard or mouse input
+    pub active_secs: u32,
+}
+
 /// Specific activity types supported by the server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -276,8 +778,6 @@ pub enum ActivityEvent {
         #[serde(rename = "windowTitle")]
         window_title: String,
     },
-    #[serde(rename = "MouseClick")]
-    MouseClick,
 }
 
 impl ActivityEvent {
@@ -290,10 +790,6 @@ impl ActivityEvent {
             window_title: window_title.into(),
         }
     }
-
-    pub fn mouse_click() -> Self {
-        ActivityEvent::MouseClick
-    }
 }
 
 #[cfg(test)]
@@ -313,4 +809,37 @@ mod tests {
         assert!(json.contains("\"windowTitle\":\"Docs - Meeting\""));
         assert!(json.contains("\"intervalId\":42"));
     }
+
+    #[test]
+    fn user_message_prefers_the_api_message() {
+        let err = ApiError::from_status(
+            StatusCode::CONFLICT,
+            r#"{"error":"conflict","message":"Tweet is already posting or posted"}"#.to_string(),
+        );
+        assert_eq!(err.user_message(), "Tweet is already posting or posted");
+
+        let err = ApiError::from_status(StatusCode::BAD_GATEWAY, "<html>".to_string());
+        assert_eq!(err.user_message(), "server returned 502 Bad Gateway");
+    }
+
+    #[test]
+    fn quota_rejection_parses_retry_after() {
+        let body = r#"{"error":"quota_exceeded","message":"Daily capture quota reached","retry_after":120,"quota":{"captures":0,"bytes":5,"storage_bytes":null,"resets_at":"2026-03-02T00:00:00Z"}}"#;
+        match ApiError::from_status(StatusCode::TOO_MANY_REQUESTS, body.to_string()) {
+            ApiError::QuotaExceeded {
+                retry_after, quota, ..
+            } => {
+                assert_eq!(retry_after, Some(Duration::from_secs(120)));
+                assert_eq!(quota.and_then(|q| q.captures), Some(0));
+            }
+            other => panic!("expected quota error, got {other}"),
+        }
+
+        // Plain rate limiting stays an unexpected status
+        let body = r#"{"error":"rate_limited","message":"Too many requests"}"#;
+        assert!(matches!(
+            ApiError::from_status(StatusCode::TOO_MANY_REQUESTS, body.to_string()),
+            ApiError::UnexpectedStatus { .. }
+        ));
+    }
 }
@@ -9,8 +9,8 @@ use objc2::rc::Retained;
 use objc2::runtime::{AnyClass, AnyObject, Sel};
 use objc2::{ClassType, MainThreadOnly, class, msg_send, sel};
 use objc2_app_kit::{
-    NSApplication, NSApplicationActivationPolicy, NSImage, NSMenu, NSMenuItem, NSStatusBar,
-    NSStatusItem,
+    NSApplication, NSApplicationActivationPolicy, NSControlStateValueOff, NSControlStateValueOn,
+    NSImage, NSMenu, NSMenuItem, NSStatusBar, NSStatusItem,
 };
 use objc2_foundation::{MainThreadMarker, NSArray, NSBundle, NSObject, NSString, NSURL};
 use url::Url;
@@ -39,6 +39,7 @@ thread_local! {
 pub struct StatusItem {
     item: Retained<NSStatusItem>,
     _menu: Retained<NSMenu>,
+    mtm: MainThreadMarker,
 }
 
 impl StatusItem {
@@ -100,7 +101,25 @@ impl StatusItem {
 
             item.setMenu(Some(&menu));
 
-            Self { item, _menu: menu }
+            Self {
+                item,
+                _menu: menu,
+                mtm,
+            }
+        }
+    }
+
+    /// Show short text next to the icon (None clears it) and replace the tooltip
+    pub fn set_badge(&self, badge: Option<&str>, tooltip: &str) {
+        let Some(button) = self.item.button(self.mtm) else {
+            return;
+        };
+        unsafe {
+            let title = NSString::from_str(badge.unwrap_or(""));
+            let _: () = msg_send![&*button, setTitle: &*title];
+            // NSImageLeft, so the icon stays in front of the text
+            let _: () = msg_send![&*button, setImagePosition: 2usize];
+            button.setToolTip(Some(&NSString::from_str(tooltip)));
         }
     }
 }
@@ -160,6 +179,82 @@ fn menu_action_target_class() -> &'static AnyClass {
     })
 }
 
+/// Callbacks of a submenu whose items are filled in while the app runs
+struct SubmenuCallbacks {
+    on_open: Box<dyn Fn() + Send + Sync + 'static>,
+    on_select: Box<dyn Fn(usize) + Send + Sync + 'static>,
+}
+
+static SUBMENU_CALLBACKS: OnceLock<std::sync::Mutex<Vec<SubmenuCallbacks>>> = OnceLock::new();
+
+fn submenu_callbacks() -> &'static std::sync::Mutex<Vec<SubmenuCallbacks>> {
+    SUBMENU_CALLBACKS.get_or_init(|| std::sync::Mutex::new(Vec::new()))
+}
+
+/// Create the SubmenuTarget class: the submenu's delegate (menuWillOpen:)
+/// and the target of its items, which carry their position as the tag
+fn submenu_target_class() -> &'static AnyClass {
+    static CLASS: OnceLock<&'static AnyClass> = OnceLock::new();
+    CLASS.get_or_init(|| {
+        let superclass = NSObject::class();
+        let mut builder =
+            ClassBuilder::new(c"CleoSubmenuTarget", superclass).expect("Failed to create class");
+
+        builder.add_ivar::<usize>(c"submenuIndex");
+
+        unsafe extern "C" fn menu_will_open(
+            this: *mut AnyObject,
+            _sel: Sel,
+            _menu: *mut AnyObject,
+        ) {
+            let cls = (*this).class();
+            let ivar = cls.instance_variable(c"submenuIndex").unwrap();
+            let idx = *ivar.load::<usize>(&*this);
+            if let Ok(callbacks) = submenu_callbacks().lock()
+                && let Some(callbacks) = callbacks.get(idx)
+            {
+                (callbacks.on_open)();
+            }
+        }
+
+        unsafe extern "C" fn select_item(this: *mut AnyObject, _sel: Sel, sender: *mut AnyObject) {
+            let cls = (*this).class();
+            let ivar = cls.instance_variable(c"submenuIndex").unwrap();
+            let idx = *ivar.load::<usize>(&*this);
+            let tag: isize = msg_send![sender, tag];
+            if let Ok(callbacks) = submenu_callbacks().lock()
+                && let Some(callbacks) = callbacks.get(idx)
+                && let Ok(position) = usize::try_from(tag)
+            {
+                (callbacks.on_select)(position);
+            }
+        }
+
+        unsafe {
+            builder.add_method(
+                sel!(menuWillOpen:),
+                menu_will_open as unsafe extern "C" fn(*mut AnyObject, Sel, *mut AnyObject),
+            );
+            builder.add_method(
+                sel!(selectItem:),
+                select_item as unsafe extern "C" fn(*mut AnyObject, Sel, *mut AnyObject),
+            );
+        }
+
+        builder.register()
+    })
+}
+
+/// An entry in a dynamic submenu
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubmenuItem {
+    /// Clickable; selecting it passes its position in the item list
+    Action(String),
+    /// Greyed-out text, e.g. "Loading..."
+    Label(String),
+    Separator,
+}
+
 /// Menu builder for creating NSMenu with actions
 pub struct MenuBuilder {
     menu: Retained<NSMenu>,
@@ -276,6 +371,70 @@ impl MenuBuilder {
         (self, handle)
     }
 
+    /// Add a submenu whose items are set later through the handle.
+    /// `on_open` runs each time the submenu opens; `on_select` gets the
+    /// position of the clicked item in the last `set_items` list.
+    pub fn add_submenu_with_handle<O, S>(
+        mut self,
+        title: &str,
+        on_open: O,
+        on_select: S,
+    ) -> (Self, SubmenuHandle)
+    where
+        O: Fn() + Send + Sync + 'static,
+        S: Fn(usize) + Send + Sync + 'static,
+    {
+        let title_str = NSString::from_str(title);
+
+        // Store callbacks and get their index
+        let idx = {
+            let mut callbacks = submenu_callbacks().lock().unwrap();
+            let idx = callbacks.len();
+            callbacks.push(SubmenuCallbacks {
+                on_open: Box::new(on_open),
+                on_select: Box::new(on_select),
+            });
+            idx
+        };
+
+        // Create target
+        let cls = submenu_target_class();
+        let target: *mut AnyObject = unsafe { msg_send![cls, new] };
+        let target = unsafe { Retained::retain(target).unwrap() };
+
+        // Set submenu index
+        unsafe {
+            let ivar = cls.instance_variable(c"submenuIndex").unwrap();
+            *ivar.load_mut::<usize>(&mut *Retained::as_ptr(&target).cast_mut()) = idx;
+        }
+
+        let submenu = unsafe { NSMenu::initWithTitle(NSMenu::alloc(self.mtm), &title_str) };
+        // The menu holds its delegate weakly; the handle and builder keep it alive
+        unsafe {
+            let _: () = msg_send![&submenu, setDelegate: &*target];
+        }
+
+        let item = unsafe {
+            NSMenuItem::initWithTitle_action_keyEquivalent(
+                NSMenuItem::alloc(self.mtm),
+                &title_str,
+                None,
+                &NSString::from_str(""),
+            )
+        };
+        unsafe { item.setSubmenu(Some(&submenu)) };
+
+        let handle = SubmenuHandle {
+            item: item.clone(),
+            menu: submenu,
+            target: target.clone(),
+            mtm: self.mtm,
+        };
+        self.menu.addItem(&item);
+        self.targets.push(target);
+        (self, handle)
+    }
+
     /// Add a separator item
     pub fn add_separator(self) -> Self {
         let sep = NSMenuItem::separatorItem(self.mtm);
@@ -308,6 +467,61 @@ impl MenuItemHandle {
     pub fn set_enabled(&self, enabled: bool) {
         self.item.setEnabled(enabled);
     }
+
+    pub fn set_checked(&self, checked: bool) {
+        let state = if checked {
+            NSControlStateValueOn
+        } else {
+            NSControlStateValueOff
+        };
+        unsafe { self.item.setState(state) };
+    }
+}
+
+/// Handle to a dynamic submenu for replacing its items later
+#[derive(Clone)]
+pub struct SubmenuHandle {
+    item: Retained<NSMenuItem>,
+    menu: Retained<NSMenu>,
+    target: Retained<AnyObject>,
+    mtm: MainThreadMarker,
+}
+
+impl SubmenuHandle {
+    pub fn set_title(&self, title: &str) {
+        let title_str = NSString::from_str(title);
+        self.item.setTitle(&title_str);
+    }
+
+    /// Replace the submenu's items; safe while the submenu is open
+    pub fn set_items(&self, items: &[SubmenuItem]) {
+        unsafe { self.menu.removeAllItems() };
+        for (position, entry) in items.iter().enumerate() {
+            let item = match entry {
+                SubmenuItem::Separator => NSMenuItem::separatorItem(self.mtm),
+                SubmenuItem::Action(title) | SubmenuItem::Label(title) => {
+                    let action = matches!(entry, SubmenuItem::Action(_)).then(|| sel!(selectItem:));
+                    let item = unsafe {
+                        NSMenuItem::initWithTitle_action_keyEquivalent(
+                            NSMenuItem::alloc(self.mtm),
+                            &NSString::from_str(title),
+                            action,
+                            &NSString::from_str(""),
+                        )
+                    };
+                    // Items without an action are disabled by the menu's autoenabling
+                    if action.is_some() {
+                        unsafe {
+                            let _: () = msg_send![&item, setTarget: &*self.target];
+                            let _: () = msg_send![&item, setTag: position as isize];
+                        }
+                    }
+                    item
+                }
+            };
+            self.menu.addItem(&item);
+        }
+    }
 }
 
 /// Create the AppDelegate class using ClassBuilder
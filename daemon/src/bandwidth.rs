@@ -0,0 +1,174 @@
+//! Upload bandwidth limiting and metered-network detection.
+//!
+//! Capture uploads run constantly in the background, so on a slow or shared
+//! link they can crowd out everything else. `RateLimiter` caps the upload rate
+//! of capture batches, and the network monitor tells the batch uploader when
+//! the Mac is on an expensive (cellular, Personal Hotspot) or Low Data Mode
+//! connection, so recordings can wait in the spool until it's unmetered.
+
+use std::ffi::c_void;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use block2::{Block, RcBlock};
+use dispatch2::{DispatchQoS, DispatchQueue, GlobalQueueIdentifier};
+use log::info;
+
+/// Largest read handed to the HTTP client at once, so pacing stays smooth
+const CHUNK_BYTES: usize = 16 * 1024;
+/// A pause longer than this starts a new measuring window, so idle time
+/// isn't spent later as one big burst
+const IDLE_RESET: Duration = Duration::from_secs(1);
+
+#[link(name = "Network", kind = "framework")]
+unsafe extern "C" {
+    fn nw_path_monitor_create() -> *mut c_void;
+    fn nw_path_monitor_set_queue(monitor: *mut c_void, queue: &DispatchQueue);
+    fn nw_path_monitor_set_update_handler(
+        monitor: *mut c_void,
+        handler: &Block<dyn Fn(*mut c_void)>,
+    );
+    fn nw_path_monitor_start(monitor: *mut c_void);
+    fn nw_path_is_expensive(path: *mut c_void) -> bool;
+    fn nw_path_is_constrained(path: *mut c_void) -> bool;
+}
+
+static METERED: AtomicBool = AtomicBool::new(false);
+static MONITOR_STARTED: Once = Once::new();
+
+/// Caps the combined rate of everything read through its `ThrottledReader`s
+#[derive(Debug)]
+pub struct RateLimiter {
+    bytes_per_sec: u64,
+    window: Mutex<Window>,
+}
+
+#[derive(Debug)]
+struct Window {
+    /// Start of the current stretch of sending; None before the first read
+    started: Option<Instant>,
+    sent: u64,
+}
+
+impl RateLimiter {
+    pub fn new(kb_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec: kb_per_sec.max(1) * 1024,
+            window: Mutex::new(Window {
+                started: None,
+                sent: 0,
+            }),
+        }
+    }
+
+    /// Count `bytes` as sent at `now` and return how long to wait so the
+    /// average rate stays under the limit
+    fn reserve(&self, bytes: u64, now: Instant) -> Duration {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        let earned = self.duration_for(window.sent);
+        let started = match window.started {
+            Some(started) if now.saturating_duration_since(started) <= earned + IDLE_RESET => {
+                started
+            }
+            _ => {
+                window.sent = 0;
+                now
+            }
+        };
+        window.started = Some(started);
+        window.sent += bytes;
+        let due = started + self.duration_for(window.sent);
+        due.saturating_duration_since(now)
+    }
+
+    /// How long sending `bytes` takes at the limit
+    pub fn duration_for(&self, bytes: u64) -> Duration {
+        Duration::from_secs_f64(bytes as f64 / self.bytes_per_sec as f64)
+    }
+}
+
+/// Reader that sleeps as needed to keep its limiter's rate
+pub struct ThrottledReader<R> {
+    inner: R,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<R> ThrottledReader<R> {
+    pub fn new(inner: R, limiter: Arc<RateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(CHUNK_BYTES);
+        let read = self.inner.read(&mut buf[..len])?;
+        let wait = self.limiter.reserve(read as u64, Instant::now());
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+        Ok(read)
+    }
+}
+
+/// Start following the network path; `is_metered` stays false until the
+/// first update. Only the first call does anything.
+pub fn start_network_monitor() {
+    MONITOR_STARTED.call_once(|| {
+        let handler = RcBlock::new(|path: *mut c_void| {
+            let metered = unsafe { nw_path_is_expensive(path) || nw_path_is_constrained(path) };
+            if METERED.swap(metered, Ordering::Relaxed) != metered {
+                info!(
+                    "Network is now {}",
+                    if metered { "metered" } else { "unmetered" }
+                );
+            }
+        });
+        // The monitor runs for the life of the process and is never released
+        unsafe {
+            let monitor = nw_path_monitor_create();
+            if monitor.is_null() {
+                return;
+            }
+            let queue = DispatchQueue::global_queue(GlobalQueueIdentifier::QualityOfService(
+                DispatchQoS::Utility,
+            ));
+            nw_path_monitor_set_queue(monitor, &queue);
+            nw_path_monitor_set_update_handler(monitor, &handler);
+            nw_path_monitor_start(monitor);
+        }
+    });
+}
+
+/// Whether the current connection is expensive (cellular, Personal Hotspot)
+/// or in Low Data Mode
+pub fn is_metered() -> bool {
+    METERED.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limiter_spaces_reads_to_the_rate() {
+        let limiter = RateLimiter::new(100);
+        let start = Instant::now();
+        // 100 KB at 100 KB/s: the first 50 KB is due half a second in
+        let wait = limiter.reserve(50 * 1024, start);
+        assert_eq!(wait, Duration::from_millis(500));
+        let wait = limiter.reserve(50 * 1024, start + Duration::from_millis(500));
+        assert_eq!(wait, Duration::from_millis(500));
+
+        // After a long pause the window starts over instead of allowing a burst
+        let later = start + Duration::from_secs(30);
+        assert_eq!(
+            limiter.reserve(10 * 1024, later),
+            Duration::from_millis(100)
+        );
+        assert_eq!(limiter.duration_for(300 * 1024), Duration::from_secs(3));
+    }
+}
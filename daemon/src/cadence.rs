@@ -0,0 +1,216 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Bounds for adaptive screenshot scheduling.
+#[derive(Debug, Clone, Copy)]
+pub struct CadenceSettings {
+    /// Interval during bursts of activity
+    pub min_interval: Duration,
+    /// Interval during ordinary activity
+    pub base_interval: Duration,
+    /// Interval after a long stretch without activity events
+    pub max_interval: Duration,
+    /// Quiet time before the interval starts stretching toward `max_interval`
+    pub quiet_after: Duration,
+    /// Window and event count that count as a burst
+    pub burst_window: Duration,
+    pub burst_events: usize,
+    /// When false, always use `base_interval`
+    pub adaptive: bool,
+}
+
+impl CadenceSettings {
+    /// The same settings with no interval longer than `ceiling`.
+    pub fn capped(self, ceiling: Duration) -> Self {
+        Self {
+            min_interval: self.min_interval.min(ceiling),
+            base_interval: self.base_interval.min(ceiling),
+            max_interval: self.max_interval.min(ceiling),
+            ..self
+        }
+    }
+
+    /// The same settings with no interval shorter than `floor`.
+    pub fn floored(self, floor: Duration) -> Self {
+        Self {
+            min_interval: self.min_interval.max(floor),
+            base_interval: self.base_interval.max(floor),
+            max_interval: self.max_interval.max(floor),
+            ..self
+        }
+    }
+}
+
+/// Decides when the next screenshot is due from recent activity events.
+///
+/// Bursts (many clicks/keys/app switches within `burst_window`) drop to
+/// `min_interval`; once no events arrive for `quiet_after`, the interval grows
+/// by a second for every further quiet second until it reaches `max_interval`.
+#[derive(Debug)]
+pub struct Cadence {
+    settings: CadenceSettings,
+    recent: VecDeque<Instant>,
+    last_activity: Option<Instant>,
+    last_capture: Option<Instant>,
+}
+
+impl Cadence {
+    pub fn new(settings: CadenceSettings) -> Self {
+        Self {
+            settings,
+            recent: VecDeque::new(),
+            last_activity: None,
+            last_capture: None,
+        }
+    }
+
+    /// Swaps in new settings, keeping the activity and capture history.
+    pub fn set_settings(&mut self, settings: CadenceSettings) {
+        self.settings = settings;
+    }
+
+    /// Records a click, keypress or app switch.
+    pub fn record_activity(&mut self, now: Instant) {
+        self.last_activity = Some(now);
+        self.recent.push_back(now);
+        self.prune(now);
+    }
+
+    /// Records that a screenshot was taken.
+    pub fn record_capture(&mut self, now: Instant) {
+        self.last_capture = Some(now);
+    }
+
+    /// Returns true once the current interval has elapsed since the last capture.
+    pub fn is_due(&mut self, now: Instant) -> bool {
+        match self.last_capture {
+            Some(last) => now.duration_since(last) >= self.interval(now),
+            None => true,
+        }
+    }
+
+    /// The screenshot interval for the activity seen up to `now`.
+    pub fn interval(&mut self, now: Instant) -> Duration {
+        let s = self.settings;
+        if !s.adaptive {
+            return s.base_interval;
+        }
+
+        self.prune(now);
+        if self.recent.len() >= s.burst_events {
+            return s.min_interval;
+        }
+
+        let quiet = self
+            .last_activity
+            .map_or(Duration::MAX, |last| now.duration_since(last));
+        if quiet <= s.quiet_after {
+            return s.base_interval;
+        }
+        s.base_interval
+            .saturating_add(quiet - s.quiet_after)
+            .min(s.max_interval)
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(&front) = self.recent.front() {
+            if now.duration_since(front) > self.settings.burst_window {
+                self.recent.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> CadenceSettings {
+        CadenceSettings {
+            min_interval: Duration::from_secs(2),
+            base_interval: Duration::from_secs(5),
+            max_interval: Duration::from_secs(60),
+            quiet_after: Duration::from_secs(30),
+            burst_window: Duration::from_secs(5),
+            burst_events: 5,
+            adaptive: true,
+        }
+    }
+
+    #[test]
+    fn interval_tracks_activity() {
+        let start = Instant::now();
+        let mut cadence = Cadence::new(settings());
+
+        // Nothing recorded yet: treated as quiet
+        assert_eq!(cadence.interval(start), Duration::from_secs(60));
+
+        cadence.record_activity(start);
+        assert_eq!(cadence.interval(start), Duration::from_secs(5));
+
+        for i in 1..=4 {
+            cadence.record_activity(start + Duration::from_millis(i * 250));
+        }
+        let burst_end = start + Duration::from_secs(1);
+        assert_eq!(cadence.interval(burst_end), Duration::from_secs(2));
+
+        // Burst window passes, still recently active
+        assert_eq!(
+            cadence.interval(burst_end + Duration::from_secs(10)),
+            Duration::from_secs(5)
+        );
+        // Quiet stretch ramps up, capped at the max
+        assert_eq!(
+            cadence.interval(burst_end + Duration::from_secs(40)),
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            cadence.interval(burst_end + Duration::from_secs(600)),
+            Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn fixed_interval_when_not_adaptive() {
+        let start = Instant::now();
+        let mut cadence = Cadence::new(CadenceSettings {
+            adaptive: false,
+            ..settings()
+        });
+        assert!(cadence.is_due(start));
+        cadence.record_capture(start);
+        assert!(!cadence.is_due(start + Duration::from_secs(4)));
+        assert!(cadence.is_due(start + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn capped_settings_shorten_quiet_stretches() {
+        let start = Instant::now();
+        let mut cadence = Cadence::new(settings());
+        cadence.record_activity(start);
+        cadence.set_settings(settings().capped(Duration::from_secs(3)));
+
+        assert_eq!(cadence.interval(start), Duration::from_secs(3));
+        assert_eq!(
+            cadence.interval(start + Duration::from_secs(600)),
+            Duration::from_secs(3)
+        );
+    }
+
+    #[test]
+    fn floored_settings_slow_bursts() {
+        let start = Instant::now();
+        let mut cadence = Cadence::new(settings().floored(Duration::from_secs(15)));
+        for i in 0..5 {
+            cadence.record_activity(start + Duration::from_millis(i * 100));
+        }
+        let burst_end = start + Duration::from_secs(1);
+        assert_eq!(cadence.interval(burst_end), Duration::from_secs(15));
+        assert_eq!(
+            cadence.interval(start + Duration::from_secs(600)),
+            Duration::from_secs(60)
+        );
+    }
+}
@@ -53,6 +53,13 @@ const KEY_T: u16 = 17;
 const KEY_R: u16 = 15;
 const KEY_S: u16 = 1;
 const KEY_B: u16 = 11;
+const KEY_D: u16 = 2;
+const KEY_P: u16 = 35;
+const KEY_O: u16 = 31;
+const KEY_DELETE: u16 = 51;
+
+/// Header while reviewing drafts
+const DRAFTS_HEADER: &str = "Drafts   ↵ post · ⌫ dismiss · O open · esc back";
 
 /// Commands available in the palette
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -61,6 +68,32 @@ pub enum PaletteCommand {
     ToggleRecording,
     TakeScreenshot,
     ToggleBanApp,
+    ReviewDrafts,
+}
+
+/// What to do with the selected draft while reviewing drafts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftAction {
+    /// Approve it and post it to X
+    Post,
+    Dismiss,
+    /// Open it in the web review page
+    Open,
+}
+
+/// What a key press in the palette asks the daemon to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteAction {
+    Run(PaletteCommand),
+    /// Act on the draft at this position in the list last passed to `show_drafts`
+    Draft(DraftAction, usize),
+}
+
+/// Which list the palette rows show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PaletteMode {
+    Commands,
+    Drafts,
 }
 
 /// State needed to render command labels
@@ -69,6 +102,8 @@ pub struct PaletteState {
     pub recording: bool,
     pub current_app_name: Option<String>,
     pub current_app_banned: bool,
+    /// Pending drafts last seen; 0 when unknown
+    pub drafts_waiting: u64,
 }
 
 impl PaletteCommand {
@@ -78,6 +113,7 @@ impl PaletteCommand {
             PaletteCommand::ToggleRecording,
             PaletteCommand::TakeScreenshot,
             PaletteCommand::ToggleBanApp,
+            PaletteCommand::ReviewDrafts,
         ]
     }
 
@@ -87,6 +123,7 @@ impl PaletteCommand {
             PaletteCommand::ToggleRecording => "R",
             PaletteCommand::TakeScreenshot => "S",
             PaletteCommand::ToggleBanApp => "B",
+            PaletteCommand::ReviewDrafts => "D",
         }
     }
 
@@ -96,6 +133,7 @@ impl PaletteCommand {
             PaletteCommand::ToggleRecording => "record.circle",
             PaletteCommand::TakeScreenshot => "camera",
             PaletteCommand::ToggleBanApp => "eye.slash",
+            PaletteCommand::ReviewDrafts => "tray.full",
         }
     }
 
@@ -121,6 +159,10 @@ impl PaletteCommand {
                 Some(name) => format!("Ban {}", name),
                 None => "Ban Current App".to_string(),
             },
+            PaletteCommand::ReviewDrafts => match state.drafts_waiting {
+                0 => "Review Drafts".to_string(),
+                n => format!("Review Drafts ({})", n),
+            },
         }
     }
 }
@@ -146,12 +188,51 @@ impl fmt::Display for CommandPaletteError {
 
 impl std::error::Error for CommandPaletteError {}
 
+/// Draft action bound to a key while reviewing drafts
+fn draft_key_action(key_code: u16) -> Option<DraftAction> {
+    match key_code {
+        KEY_RETURN | KEY_P => Some(DraftAction::Post),
+        KEY_DELETE | KEY_D => Some(DraftAction::Dismiss),
+        KEY_O => Some(DraftAction::Open),
+        _ => None,
+    }
+}
+
+/// First draft to show so the selected one stays within `rows` visible rows
+fn scroll_offset(selected: usize, offset: usize, rows: usize) -> usize {
+    if selected < offset {
+        selected
+    } else if selected >= offset + rows {
+        selected + 1 - rows
+    } else {
+        offset
+    }
+}
+
+/// SF Symbol image sized for a palette row
+fn row_icon(name: &str) -> Option<Retained<NSImage>> {
+    let config = unsafe {
+        NSImageSymbolConfiguration::configurationWithPointSize_weight_scale(
+            18.0,
+            FONT_WEIGHT_MEDIUM,
+            NSImageSymbolScale::Medium,
+        )
+    };
+    let image = unsafe {
+        NSImage::imageWithSystemSymbolName_accessibilityDescription(&NSString::from_str(name), None)
+    }?;
+    unsafe { image.imageWithSymbolConfiguration(&config) }
+}
+
 /// The command palette floating panel
 pub struct CommandPalette {
     panel: Retained<NSPanel>,
+    header_label: Retained<NSTextField>,
     command_labels: RefCell<Vec<Retained<NSTextField>>>,
     icon_views: RefCell<Vec<Retained<NSImageView>>>,
     selection_views: RefCell<Vec<Retained<NSView>>>,
+    /// Keybind badge backgrounds and text, hidden while reviewing drafts
+    badge_views: RefCell<Vec<Retained<NSView>>>,
     local_monitor: RefCell<Option<Retained<AnyObject>>>,
     _monitor_block: RefCell<Option<RcBlock<dyn Fn(*mut AnyObject) -> *mut AnyObject>>>,
     visible: Cell<bool>,
@@ -160,6 +241,13 @@ pub struct CommandPalette {
     recording: Cell<bool>,
     current_app_name: RefCell<Option<String>>,
     current_app_banned: Cell<bool>,
+    drafts_waiting: Cell<u64>,
+    mode: Cell<PaletteMode>,
+    /// Titles of the drafts under review; `selected_index` points into this
+    /// list while reviewing drafts
+    draft_titles: RefCell<Vec<String>>,
+    /// First draft shown in the top row
+    draft_offset: Cell<usize>,
 }
 
 impl CommandPalette {
@@ -263,7 +351,7 @@ impl CommandPalette {
         };
 
         // Create header with app name
-        unsafe {
+        let header_label = unsafe {
             let header_frame = NSRect::new(
                 NSPoint::new(20.0, panel_height - header_height + 8.0),
                 NSSize::new(panel_width - 40.0, 28.0),
@@ -296,12 +384,15 @@ impl CommandPalette {
             separator.setFrame(separator_frame);
             separator.setBoxType(NSBoxType::Separator);
             content_view.addSubview(&separator);
-        }
+
+            label
+        };
 
         // Create command rows
         let mut command_labels = Vec::new();
         let mut icon_views = Vec::new();
         let mut selection_views = Vec::new();
+        let mut badge_views = Vec::new();
 
         for (i, cmd) in commands.iter().enumerate() {
             let row_y =
@@ -338,21 +429,11 @@ impl CommandPalette {
                 let image_view = NSImageView::new(mtm);
                 image_view.setFrame(icon_frame);
 
-                let icon_name = NSString::from_str(cmd.icon_name());
-                let config = NSImageSymbolConfiguration::configurationWithPointSize_weight_scale(
-                    18.0,
-                    FONT_WEIGHT_MEDIUM,
-                    NSImageSymbolScale::Medium,
-                );
-                if let Some(image) =
-                    NSImage::imageWithSystemSymbolName_accessibilityDescription(&icon_name, None)
-                {
-                    if let Some(configured) = image.imageWithSymbolConfiguration(&config) {
-                        image_view.setImage(Some(&configured));
-                    }
-                    let color = NSColor::secondaryLabelColor();
-                    image_view.setContentTintColor(Some(&color));
+                if let Some(image) = row_icon(cmd.icon_name()) {
+                    image_view.setImage(Some(&image));
                 }
+                let color = NSColor::secondaryLabelColor();
+                image_view.setContentTintColor(Some(&color));
 
                 content_view.addSubview(&image_view);
                 image_view
@@ -374,6 +455,7 @@ impl CommandPalette {
                     recording: false,
                     current_app_name: None,
                     current_app_banned: false,
+                    drafts_waiting: 0,
                 };
                 let text = NSString::from_str(&cmd.label(&initial_state));
                 label.setStringValue(&text);
@@ -416,6 +498,7 @@ impl CommandPalette {
                 }
 
                 content_view.addSubview(&badge_bg);
+                badge_views.push(badge_bg);
 
                 // Badge text
                 let text_frame = NSRect::new(
@@ -440,14 +523,17 @@ impl CommandPalette {
                 hint.setTextColor(Some(&color));
 
                 content_view.addSubview(&hint);
+                badge_views.push(Retained::into_super(Retained::into_super(hint)));
             }
         }
 
         let palette = Self {
             panel,
+            header_label,
             command_labels: RefCell::new(command_labels),
             icon_views: RefCell::new(icon_views),
             selection_views: RefCell::new(selection_views),
+            badge_views: RefCell::new(badge_views),
             local_monitor: RefCell::new(None),
             _monitor_block: RefCell::new(None),
             visible: Cell::new(false),
@@ -456,6 +542,10 @@ impl CommandPalette {
             recording: Cell::new(false),
             current_app_name: RefCell::new(None),
             current_app_banned: Cell::new(false),
+            drafts_waiting: Cell::new(0),
+            mode: Cell::new(PaletteMode::Commands),
+            draft_titles: RefCell::new(Vec::new()),
+            draft_offset: Cell::new(0),
         };
 
         palette.update_selection();
@@ -481,8 +571,7 @@ impl CommandPalette {
         }
 
         self.visible.set(true);
-        self.selected_index.set(0);
-        self.update_selection();
+        self.set_mode(PaletteMode::Commands);
     }
 
     /// Hide the command palette
@@ -527,8 +616,15 @@ impl CommandPalette {
         self.update_labels();
     }
 
-    /// Handle a key event, returns the command if one was triggered
-    pub fn handle_key(&self, key_code: u16) -> Option<PaletteCommand> {
+    /// Handle a key event, returns the action if one was triggered
+    pub fn handle_key(&self, key_code: u16) -> Option<PaletteAction> {
+        if self.mode.get() == PaletteMode::Drafts {
+            return self.handle_draft_key(key_code);
+        }
+        self.handle_command_key(key_code).map(PaletteAction::Run)
+    }
+
+    fn handle_command_key(&self, key_code: u16) -> Option<PaletteCommand> {
         match key_code {
             KEY_ESCAPE => {
                 self.hide();
@@ -557,6 +653,9 @@ impl CommandPalette {
                 if cmd == PaletteCommand::TakeScreenshot {
                     self.hide();
                 }
+                if cmd == PaletteCommand::ReviewDrafts {
+                    self.set_mode(PaletteMode::Drafts);
+                }
                 Some(cmd)
             }
             KEY_T => Some(PaletteCommand::ToggleCapture),
@@ -566,10 +665,128 @@ impl CommandPalette {
                 Some(PaletteCommand::TakeScreenshot)
             }
             KEY_B => Some(PaletteCommand::ToggleBanApp),
+            KEY_D => {
+                self.set_mode(PaletteMode::Drafts);
+                Some(PaletteCommand::ReviewDrafts)
+            }
+            _ => None,
+        }
+    }
+
+    fn handle_draft_key(&self, key_code: u16) -> Option<PaletteAction> {
+        let count = self.draft_titles.borrow().len();
+        let idx = self.selected_index.get();
+        match key_code {
+            KEY_ESCAPE => {
+                self.set_mode(PaletteMode::Commands);
+                None
+            }
+            KEY_UP => {
+                if idx > 0 {
+                    self.selected_index.set(idx - 1);
+                    self.render_drafts();
+                }
+                None
+            }
+            KEY_DOWN => {
+                if idx + 1 < count {
+                    self.selected_index.set(idx + 1);
+                    self.render_drafts();
+                }
+                None
+            }
+            _ if idx < count => {
+                draft_key_action(key_code).map(|action| PaletteAction::Draft(action, idx))
+            }
             _ => None,
         }
     }
 
+    /// Whether the rows show drafts rather than commands
+    pub fn is_reviewing_drafts(&self) -> bool {
+        self.mode.get() == PaletteMode::Drafts
+    }
+
+    /// Replace the drafts under review, keeping the selection in range
+    pub fn show_drafts(&self, titles: Vec<String>) {
+        let last = titles.len().saturating_sub(1);
+        self.selected_index.set(self.selected_index.get().min(last));
+        self.draft_titles.replace(titles);
+        if self.is_reviewing_drafts() {
+            self.render_drafts();
+        }
+    }
+
+    /// Show progress or an error in the header while reviewing drafts;
+    /// None restores the key hints
+    pub fn set_drafts_status(&self, status: Option<&str>) {
+        if self.is_reviewing_drafts() {
+            let text = status.unwrap_or(DRAFTS_HEADER);
+            self.header_label.setStringValue(&NSString::from_str(text));
+        }
+    }
+
+    /// Update the pending draft count on the Review Drafts command
+    pub fn set_drafts_waiting(&self, count: u64) {
+        self.drafts_waiting.set(count);
+        self.update_labels();
+    }
+
+    fn set_mode(&self, mode: PaletteMode) {
+        self.mode.set(mode);
+        self.selected_index.set(0);
+        self.draft_offset.set(0);
+
+        let reviewing = mode == PaletteMode::Drafts;
+        for badge in self.badge_views.borrow().iter() {
+            badge.setHidden(reviewing);
+        }
+        let header = if reviewing { DRAFTS_HEADER } else { "Cleo" };
+        self.header_label
+            .setStringValue(&NSString::from_str(header));
+
+        if reviewing {
+            self.render_drafts();
+        } else {
+            let icons = self.icon_views.borrow();
+            for (icon, cmd) in icons.iter().zip(PaletteCommand::all()) {
+                unsafe { icon.setImage(row_icon(cmd.icon_name()).as_deref()) };
+            }
+            drop(icons);
+            self.update_labels();
+            self.update_selection();
+        }
+    }
+
+    /// Fill the rows with the drafts around the selection
+    fn render_drafts(&self) {
+        let titles = self.draft_titles.borrow();
+        let labels = self.command_labels.borrow();
+        let icons = self.icon_views.borrow();
+        let offset = scroll_offset(
+            self.selected_index.get(),
+            self.draft_offset.get(),
+            labels.len(),
+        );
+        self.draft_offset.set(offset);
+
+        let draft_icon = row_icon("text.bubble");
+        for (row, label) in labels.iter().enumerate() {
+            let title = titles.get(offset + row);
+            let text = match title {
+                Some(title) => title.as_str(),
+                None if row == 0 && titles.is_empty() => "No drafts waiting",
+                None => "",
+            };
+            label.setStringValue(&NSString::from_str(text));
+            if let Some(icon) = icons.get(row) {
+                unsafe { icon.setImage(title.and(draft_icon.as_deref())) };
+            }
+        }
+        drop((titles, labels, icons));
+        self.update_selection();
+    }
+
     /// Install local keyboard monitor for when panel is visible
     pub fn install_local_monitor<F>(&self, on_key: F)
     where
@@ -612,7 +829,15 @@ impl CommandPalette {
     }
 
     fn update_selection(&self) {
-        let selected = self.selected_index.get();
+        let selected = match self.mode.get() {
+            PaletteMode::Commands => self.selected_index.get(),
+            // Nothing to highlight in an empty list
+            PaletteMode::Drafts if self.draft_titles.borrow().is_empty() => usize::MAX,
+            PaletteMode::Drafts => self
+                .selected_index
+                .get()
+                .saturating_sub(self.draft_offset.get()),
+        };
         let views = self.selection_views.borrow();
         let icons = self.icon_views.borrow();
 
@@ -654,11 +879,15 @@ impl CommandPalette {
     }
 
     fn update_labels(&self) {
+        if self.is_reviewing_drafts() {
+            return;
+        }
         let state = PaletteState {
             auto_capture_enabled: self.auto_capture_enabled.get(),
             recording: self.recording.get(),
             current_app_name: self.current_app_name.borrow().clone(),
             current_app_banned: self.current_app_banned.get(),
+            drafts_waiting: self.drafts_waiting.get(),
         };
         let labels = self.command_labels.borrow();
 
@@ -735,3 +964,26 @@ impl Drop for HotkeyTracker {
         let _ = self.manager.unregister(self.hotkey);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draft_keys_map_to_actions() {
+        assert_eq!(draft_key_action(KEY_RETURN), Some(DraftAction::Post));
+        assert_eq!(draft_key_action(KEY_P), Some(DraftAction::Post));
+        assert_eq!(draft_key_action(KEY_DELETE), Some(DraftAction::Dismiss));
+        assert_eq!(draft_key_action(KEY_O), Some(DraftAction::Open));
+        assert_eq!(draft_key_action(KEY_S), None);
+    }
+
+    #[test]
+    fn scroll_offset_keeps_the_selection_visible() {
+        assert_eq!(scroll_offset(0, 0, 5), 0);
+        assert_eq!(scroll_offset(4, 0, 5), 0);
+        assert_eq!(scroll_offset(5, 0, 5), 1);
+        assert_eq!(scroll_offset(9, 1, 5), 5);
+        assert_eq!(scroll_offset(2, 5, 5), 2);
+    }
+}
@@ -0,0 +1,69 @@
+//! Identity of this machine as seen by the API.
+//!
+//! Each install gets a random device id, persisted in `~/.config/cleo.json`, that
+//! is sent with every API request so the server can attribute uploads to a
+//! machine and let the user revoke it.
+
+use std::ffi::CStr;
+use std::fs::File;
+use std::io::Read;
+
+/// Stable identifier plus a human-readable name for this machine.
+#[derive(Debug, Clone)]
+pub struct DeviceIdentity {
+    pub uid: String,
+    pub name: Option<String>,
+}
+
+/// Generates a new random device id (32 hex chars).
+pub fn generate_device_uid() -> std::io::Result<String> {
+    let mut bytes = [0u8; 16];
+    File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Host name reported to the server so sessions and devices can be told apart.
+pub fn device_name() -> Option<String> {
+    let mut buf = [0 as libc::c_char; 256];
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr(), buf.len()) };
+    if rc != 0 {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(buf.as_ptr()) }
+        .to_string_lossy()
+        .trim()
+        .to_string();
+    header_safe(&name)
+}
+
+/// Keeps only characters that are valid in an HTTP header value.
+fn header_safe(value: &str) -> Option<String> {
+    let cleaned: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic() || *c == ' ')
+        .collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        None
+    } else {
+        Some(cleaned.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_uid_is_hex() {
+        let uid = generate_device_uid().expect("read urandom");
+        assert_eq!(uid.len(), 32);
+        assert!(uid.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn header_safe_strips_non_ascii() {
+        assert_eq!(header_safe("Jo’s MacBook"), Some("Js MacBook".to_string()));
+        assert_eq!(header_safe("\u{2603}"), None);
+    }
+}
@@ -0,0 +1,152 @@
+//! Disk-space guard for the spool folder (pending captures).
+//!
+//! Screenshots and recordings wait in the spool until the batch uploader sends
+//! them, so an offline machine or a paused quota can grow it without bound.
+//! The daemon stops writing to it while free space or the spool size is past
+//! the configured limits, and picks up again once uploads drain it.
+
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/// Keep at least this much free on the spool's volume (2 GiB)
+pub const MIN_FREE_BYTES_DEFAULT: u64 = 2 * 1024 * 1024 * 1024;
+/// Stop capturing once the spool holds this much (10 GiB)
+pub const MAX_SPOOL_BYTES_DEFAULT: u64 = 10 * 1024 * 1024 * 1024;
+/// How often the spool is re-measured
+pub const CHECK_INTERVAL_SECS: u64 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiskLimits {
+    pub min_free_bytes: u64,
+    pub max_spool_bytes: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiskStatus {
+    Ok,
+    /// The volume is below the free-space floor
+    LowDisk {
+        free_bytes: u64,
+    },
+    /// The spool is at its size cap
+    SpoolFull {
+        spool_bytes: u64,
+    },
+}
+
+impl DiskStatus {
+    pub fn is_ok(self) -> bool {
+        self == DiskStatus::Ok
+    }
+
+    /// Why capturing is paused, for notifications and the status menu
+    pub fn message(self) -> String {
+        match self {
+            DiskStatus::Ok => "Capturing normally".to_string(),
+            DiskStatus::LowDisk { free_bytes } => format!(
+                "Only {} free on disk; capturing paused until space frees up",
+                format_bytes(free_bytes)
+            ),
+            DiskStatus::SpoolFull { spool_bytes } => format!(
+                "{} of captures waiting to upload; capturing paused until they drain",
+                format_bytes(spool_bytes)
+            ),
+        }
+    }
+}
+
+/// Compare measured free space and spool size against the limits
+pub fn evaluate(limits: DiskLimits, free_bytes: u64, spool_bytes: u64) -> DiskStatus {
+    if free_bytes < limits.min_free_bytes {
+        DiskStatus::LowDisk { free_bytes }
+    } else if spool_bytes >= limits.max_spool_bytes {
+        DiskStatus::SpoolFull { spool_bytes }
+    } else {
+        DiskStatus::Ok
+    }
+}
+
+/// Bytes available to this user on the volume holding `path`. A path that
+/// doesn't exist yet is measured at its nearest existing ancestor.
+pub fn free_bytes(path: &Path) -> io::Result<u64> {
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(Path::new("/"));
+    let c_path = CString::new(existing.as_os_str().as_bytes()).map_err(io::Error::other)?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // Block count and size widths differ between macOS and Linux
+    #[allow(clippy::unnecessary_cast)]
+    let bytes = (stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64);
+    Ok(bytes)
+}
+
+/// Total size of the files under `path`; unreadable entries count as 0
+pub fn dir_size_bytes(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size_bytes(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Human-readable size, e.g. "1.2 GB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn evaluate_checks_free_space_before_spool_size() {
+        let limits = DiskLimits {
+            min_free_bytes: 2 * GIB,
+            max_spool_bytes: 10 * GIB,
+        };
+        assert_eq!(evaluate(limits, 50 * GIB, GIB), DiskStatus::Ok);
+        assert_eq!(
+            evaluate(limits, GIB, 11 * GIB),
+            DiskStatus::LowDisk { free_bytes: GIB }
+        );
+        assert_eq!(
+            evaluate(limits, 50 * GIB, 10 * GIB),
+            DiskStatus::SpoolFull {
+                spool_bytes: 10 * GIB
+            }
+        );
+    }
+
+    #[test]
+    fn format_bytes_picks_a_readable_unit() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_bytes(GIB + GIB / 5), "1.2 GB");
+    }
+}
@@ -0,0 +1,125 @@
+//! Drafts submenu in the status bar.
+//!
+//! Lists the first line of the newest pending tweets; clicking one opens the
+//! web review page at that draft. The list is fetched again each time the
+//! submenu opens, showing the previous list until the new one arrives.
+
+use crate::api::DraftsPage;
+use crate::app::SubmenuItem;
+
+/// Drafts requested for the menu
+pub const MAX_MENU_DRAFTS: usize = 10;
+/// Longest item title before it's cut off with an ellipsis
+const MAX_TITLE_CHARS: usize = 60;
+
+/// Title of the submenu item in the status menu
+pub fn menu_title(page: Option<&DraftsPage>) -> String {
+    match page {
+        Some(page) if page.total > 0 => format!("Drafts ({})", page.total),
+        _ => "Drafts".to_string(),
+    }
+}
+
+/// The first line of a draft, shortened to fit the menu
+pub fn item_title(first_line: &str) -> String {
+    let line = first_line.trim();
+    if line.is_empty() {
+        return "(media only)".to_string();
+    }
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(MAX_TITLE_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Submenu items for a fetched page: one per draft, then a link to the queue
+pub fn items(page: &DraftsPage) -> Vec<SubmenuItem> {
+    if page.drafts.is_empty() {
+        return vec![
+            SubmenuItem::Label("No drafts waiting".to_string()),
+            SubmenuItem::Separator,
+            SubmenuItem::Action("Open Review Queue...".to_string()),
+        ];
+    }
+    let mut items: Vec<SubmenuItem> = page
+        .drafts
+        .iter()
+        .map(|draft| SubmenuItem::Action(item_title(&draft.first_line)))
+        .collect();
+    items.push(SubmenuItem::Separator);
+    let remaining = page.total.saturating_sub(page.drafts.len() as u64);
+    items.push(SubmenuItem::Action(if remaining > 0 {
+        format!("Review All {} Drafts...", page.total)
+    } else {
+        "Open Review Queue...".to_string()
+    }));
+    items
+}
+
+/// URL to open for the item at `position` in `items(page)`
+pub fn url_at(page: &DraftsPage, position: usize) -> Option<&str> {
+    if let Some(draft) = page.drafts.get(position) {
+        return Some(&draft.url);
+    }
+    // The queue link is the last item, after the separator
+    let queue_position = page.drafts.len().max(1) + 1;
+    (position == queue_position).then_some(page.app_url.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::DraftPreview;
+
+    fn page(ids: &[i64], total: u64) -> DraftsPage {
+        DraftsPage {
+            drafts: ids
+                .iter()
+                .map(|&id| DraftPreview {
+                    id,
+                    first_line: format!("Draft {id}"),
+                    url: format!("https://app.cleo.test/?view=queue&draft={id}"),
+                })
+                .collect(),
+            total,
+            app_url: "https://app.cleo.test".to_string(),
+        }
+    }
+
+    #[test]
+    fn item_title_shortens_long_lines() {
+        assert_eq!(
+            item_title("  Shipped the spool guard "),
+            "Shipped the spool guard"
+        );
+        assert_eq!(item_title(""), "(media only)");
+        let long = "word ".repeat(20);
+        let title = item_title(&long);
+        assert_eq!(title.chars().count(), MAX_TITLE_CHARS);
+        assert!(title.ends_with("word…"));
+    }
+
+    #[test]
+    fn positions_map_to_drafts_and_the_queue() {
+        let listed = page(&[7, 3], 5);
+        let entries = items(&listed);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            entries[3],
+            SubmenuItem::Action("Review All 5 Drafts...".to_string())
+        );
+        assert_eq!(
+            url_at(&listed, 1),
+            Some("https://app.cleo.test/?view=queue&draft=3")
+        );
+        assert_eq!(url_at(&listed, 2), None);
+        assert_eq!(url_at(&listed, 3), Some("https://app.cleo.test"));
+
+        let empty = page(&[], 0);
+        assert_eq!(items(&empty).len(), 3);
+        assert_eq!(url_at(&empty, 2), Some("https://app.cleo.test"));
+        assert_eq!(menu_title(Some(&empty)), "Drafts");
+        assert_eq!(menu_title(Some(&listed)), "Drafts (5)");
+    }
+}
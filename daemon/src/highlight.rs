@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+/// Thresholds for flagging a recording as a highlight.
+#[derive(Debug, Clone, Copy)]
+pub struct HighlightSettings {
+    /// Shortest recording that can be a highlight
+    pub min_recording: Duration,
+    /// Window and event count that count as an activity spike
+    pub spike_window: Duration,
+    pub spike_events: usize,
+    /// Quiet period after a highlight before another can be flagged
+    pub cooldown: Duration,
+}
+
+/// A recording worth an immediate agent pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+}
+
+/// Flags recordings that look like a "just shipped it" moment.
+///
+/// A recording counts when it ran for at least `min_recording` and, while it
+/// ran, `spike_events` or more clicks, keypresses or app switches landed within
+/// one `spike_window`. After a highlight, others are ignored for `cooldown` so
+/// a long session doesn't ask for a pass on every clip.
+#[derive(Debug)]
+pub struct HighlightDetector {
+    settings: HighlightSettings,
+    /// When the current recording started (monotonic and wall clock)
+    recording_since: Option<(Instant, DateTime<Utc>)>,
+    recent: VecDeque<Instant>,
+    /// Most events seen in one spike window during the current recording
+    peak: usize,
+    last_highlight: Option<Instant>,
+}
+
+impl HighlightDetector {
+    pub fn new(settings: HighlightSettings) -> Self {
+        Self {
+            settings,
+            recording_since: None,
+            recent: VecDeque::new(),
+            peak: 0,
+            last_highlight: None,
+        }
+    }
+
+    /// Records that a recording started.
+    pub fn recording_started(&mut self, now: Instant, at: DateTime<Utc>) {
+        self.recording_since = Some((now, at));
+        self.recent.clear();
+        self.peak = 0;
+    }
+
+    /// Records a click, keypress or app switch. Ignored when not recording.
+    pub fn record_activity(&mut self, now: Instant) {
+        if self.recording_since.is_none() {
+            return;
+        }
+        self.recent.push_back(now);
+        while let Some(&front) = self.recent.front() {
+            if now.duration_since(front) > self.settings.spike_window {
+                self.recent.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.peak = self.peak.max(self.recent.len());
+    }
+
+    /// Records that the recording stopped, returning it if it was a highlight.
+    pub fn recording_stopped(&mut self, now: Instant, at: DateTime<Utc>) -> Option<Highlight> {
+        let (since, started_at) = self.recording_since.take()?;
+        let peak = std::mem::take(&mut self.peak);
+        self.recent.clear();
+
+        let s = self.settings;
+        if now.duration_since(since) < s.min_recording || peak < s.spike_events {
+            return None;
+        }
+        if let Some(last) = self.last_highlight
+            && now.duration_since(last) < s.cooldown
+        {
+            return None;
+        }
+        self.last_highlight = Some(now);
+        Some(Highlight {
+            started_at,
+            ended_at: at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> HighlightSettings {
+        HighlightSettings {
+            min_recording: Duration::from_secs(60),
+            spike_window: Duration::from_secs(10),
+            spike_events: 5,
+            cooldown: Duration::from_secs(600),
+        }
+    }
+
+    /// Records `count` events 100ms apart starting at `from`
+    fn burst(detector: &mut HighlightDetector, from: Instant, count: u64) {
+        for i in 0..count {
+            detector.record_activity(from + Duration::from_millis(i * 100));
+        }
+    }
+
+    #[test]
+    fn long_recording_with_spike_is_a_highlight() {
+        let start = Instant::now();
+        let started_at = Utc::now();
+        let mut detector = HighlightDetector::new(settings());
+
+        detector.recording_started(start, started_at);
+        burst(&mut detector, start + Duration::from_secs(30), 5);
+        let ended_at = started_at + chrono::Duration::seconds(90);
+        assert_eq!(
+            detector.recording_stopped(start + Duration::from_secs(90), ended_at),
+            Some(Highlight {
+                started_at,
+                ended_at
+            })
+        );
+    }
+
+    #[test]
+    fn short_or_steady_recordings_are_not() {
+        let start = Instant::now();
+        let now = Utc::now();
+        let mut detector = HighlightDetector::new(settings());
+
+        // Spike, but too short
+        detector.recording_started(start, now);
+        burst(&mut detector, start, 10);
+        assert_eq!(
+            detector.recording_stopped(start + Duration::from_secs(30), now),
+            None
+        );
+
+        // Long, but events spread thinner than the spike window
+        detector.recording_started(start, now);
+        for i in 0..20 {
+            detector.record_activity(start + Duration::from_secs(i * 5));
+        }
+        assert_eq!(
+            detector.recording_stopped(start + Duration::from_secs(120), now),
+            None
+        );
+
+        // Activity outside a recording doesn't count toward the next one
+        burst(&mut detector, start + Duration::from_secs(200), 10);
+        detector.recording_started(start + Duration::from_secs(210), now);
+        assert_eq!(
+            detector.recording_stopped(start + Duration::from_secs(300), now),
+            None
+        );
+    }
+
+    #[test]
+    fn cooldown_between_highlights() {
+        let start = Instant::now();
+        let now = Utc::now();
+        let mut detector = HighlightDetector::new(settings());
+
+        let mut highlight_at = |offset: u64| {
+            let from = start + Duration::from_secs(offset);
+            detector.recording_started(from, now);
+            burst(&mut detector, from, 5);
+            detector
+                .recording_stopped(from + Duration::from_secs(60), now)
+                .is_some()
+        };
+        assert!(highlight_at(0));
+        assert!(!highlight_at(100));
+        assert!(highlight_at(700));
+    }
+}
@@ -0,0 +1,177 @@
+//! Protobuf encoding of activity uploads.
+//!
+//! With `daemon.upload.transport` set to "protobuf", `/activity` and
+//! `/activity/summary` bodies are sent in this encoding instead of JSON: a
+//! fraction of the size, since window titles and app names aren't wrapped in
+//! field names. The schema is `api/proto/ingest.proto`; these types are kept
+//! in step with it by hand.
+
+use prost::Message;
+
+use crate::api::{ActivityEntry, ActivityEvent, ActivitySummary};
+
+/// Content-Type the API expects for protobuf bodies
+pub const CONTENT_TYPE: &str = "application/x-protobuf";
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActivityBatch {
+    #[prost(message, repeated, tag = "1")]
+    pub events: Vec<ActivityEventProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActivityEventProto {
+    /// Milliseconds since the Unix epoch
+    #[prost(int64, tag = "1")]
+    pub timestamp_ms: i64,
+    #[prost(int64, tag = "2")]
+    pub interval_id: i64,
+    #[prost(int64, optional, tag = "3")]
+    pub session_id: Option<i64>,
+    #[prost(oneof = "Event", tags = "4, 5")]
+    pub event: Option<Event>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum Event {
+    #[prost(message, tag = "4")]
+    ForegroundSwitch(ForegroundSwitch),
+    #[prost(message, tag = "5")]
+    MouseClick(MouseClick),
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ForegroundSwitch {
+    #[prost(string, tag = "1")]
+    pub new_active: String,
+    #[prost(string, tag = "2")]
+    pub window_title: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MouseClick {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActivitySummaryBatch {
+    #[prost(message, repeated, tag = "1")]
+    pub summaries: Vec<ActivitySummaryProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActivitySummaryProto {
+    #[prost(int64, tag = "1")]
+    pub interval_id: i64,
+    #[prost(int64, optional, tag = "2")]
+    pub session_id: Option<i64>,
+    #[prost(int64, tag = "3")]
+    pub period_start_ms: i64,
+    #[prost(int64, tag = "4")]
+    pub period_end_ms: i64,
+    #[prost(string, optional, tag = "5")]
+    pub top_app: Option<String>,
+    #[prost(uint32, tag = "6")]
+    pub app_switches: u32,
+    #[prost(uint32, tag = "7")]
+    pub clicks: u32,
+    #[prost(uint32, tag = "8")]
+    pub keypresses: u32,
+    #[prost(uint32, tag = "9")]
+    pub active_secs: u32,
+}
+
+/// `/activity` body for `events`
+pub fn encode_activity(events: &[ActivityEntry]) -> Vec<u8> {
+    let batch = ActivityBatch {
+        events: events
+            .iter()
+            .map(|entry| ActivityEventProto {
+                timestamp_ms: entry.timestamp.timestamp_millis(),
+                interval_id: entry.interval_id as i64,
+                session_id: entry.session_id,
+                event: Some(match &entry.event {
+                    ActivityEvent::ForegroundSwitch {
+                        new_active,
+                        window_title,
+                    } => Event::ForegroundSwitch(ForegroundSwitch {
+                        new_active: new_active.clone(),
+                        window_title: window_title.clone(),
+                    }),
+                }),
+            })
+            .collect(),
+    };
+    batch.encode_to_vec()
+}
+
+/// `/activity/summary` body for `summaries`
+pub fn encode_summaries(summaries: &[ActivitySummary]) -> Vec<u8> {
+    let batch = ActivitySummaryBatch {
+        summaries: summaries
+            .iter()
+            .map(|summary| ActivitySummaryProto {
+                interval_id: summary.interval_id as i64,
+                session_id: summary.session_id,
+                period_start_ms: summary.period_start.timestamp_millis(),
+                period_end_ms: summary.period_end.timestamp_millis(),
+                top_app: summary.top_app.clone(),
+                app_switches: summary.app_switches,
+                clicks: summary.clicks,
+                keypresses: summary.keypresses,
+                active_secs: summary.active_secs,
+            })
+            .collect(),
+    };
+    batch.encode_to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn activity_encodes_smaller_than_json() {
+        let timestamp = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        let events = vec![
+            ActivityEntry {
+                timestamp,
+                interval_id: 5904576,
+                session_id: Some(12),
+                event: ActivityEvent::foreground_switch("Code", "main.rs — cleoapp"),
+            };
+            20
+        ];
+        let encoded = encode_activity(&events);
+        let decoded = ActivityBatch::decode(encoded.as_slice()).unwrap();
+        assert_eq!(decoded.events.len(), 20);
+        assert_eq!(
+            decoded.events[0],
+            ActivityEventProto {
+                timestamp_ms: timestamp.timestamp_millis(),
+                interval_id: 5904576,
+                session_id: Some(12),
+                event: Some(Event::ForegroundSwitch(ForegroundSwitch {
+                    new_active: "Code".to_string(),
+                    window_title: "main.rs — cleoapp".to_string(),
+                })),
+            }
+        );
+        assert!(encoded.len() * 2 < serde_json::to_vec(&events).unwrap().len());
+
+        let summary = ActivitySummary {
+            interval_id: 5904576,
+            session_id: None,
+            period_start: timestamp,
+            period_end: timestamp + chrono::Duration::minutes(5),
+            top_app: Some("Code".to_string()),
+            app_switches: 4,
+            clicks: 31,
+            keypresses: 420,
+            active_secs: 270,
+        };
+        let decoded =
+            ActivitySummaryBatch::decode(encode_summaries(&[summary]).as_slice()).unwrap();
+        assert_eq!(decoded.summaries[0].active_secs, 270);
+        assert_eq!(decoded.summaries[0].session_id, None);
+    }
+}
@@ -0,0 +1,180 @@
+//! Launch-time integrity check for the running app bundle.
+//!
+//! Verifies the code signature with `codesign` and the notarization ticket with
+//! `spctl`. A build whose signature is missing or broken is treated as untrusted:
+//! the user is warned, the heartbeat reports it, and the API client refuses to
+//! attach the stored API token to outgoing requests.
+
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use log::{info, warn};
+
+/// Escape hatch for local development builds that are run outside a signed bundle.
+const ALLOW_UNSIGNED_ENV: &str = "CLEO_ALLOW_UNSIGNED";
+
+static INTEGRITY: OnceLock<IntegrityStatus> = OnceLock::new();
+
+/// Outcome of verifying the running build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityStatus {
+    /// Valid signature and accepted by Gatekeeper (notarized).
+    Notarized,
+    /// Valid signature, but Gatekeeper rejected it (e.g. a local developer signature).
+    SignedNotNotarized,
+    /// No code signature present.
+    Unsigned,
+    /// Signature present but the bundle contents no longer match it.
+    Modified(String),
+    /// The check itself could not run (codesign missing, no executable path).
+    Unknown(String),
+}
+
+impl IntegrityStatus {
+    /// Whether this build may send the API token to the server.
+    pub fn allows_credentials(&self) -> bool {
+        match self {
+            IntegrityStatus::Notarized | IntegrityStatus::SignedNotNotarized => true,
+            IntegrityStatus::Unsigned
+            | IntegrityStatus::Modified(_)
+            | IntegrityStatus::Unknown(_) => allow_unsigned_override(),
+        }
+    }
+
+    /// Short label used in heartbeat logs and the `X-Device-Integrity` header.
+    pub fn label(&self) -> &'static str {
+        match self {
+            IntegrityStatus::Notarized => "notarized",
+            IntegrityStatus::SignedNotNotarized => "signed",
+            IntegrityStatus::Unsigned => "unsigned",
+            IntegrityStatus::Modified(_) => "modified",
+            IntegrityStatus::Unknown(_) => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for IntegrityStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityStatus::Notarized => write!(f, "signed and notarized"),
+            IntegrityStatus::SignedNotNotarized => write!(f, "signed but not notarized"),
+            IntegrityStatus::Unsigned => write!(f, "unsigned build"),
+            IntegrityStatus::Modified(detail) => {
+                write!(f, "code signature invalid: {detail}")
+            }
+            IntegrityStatus::Unknown(detail) => {
+                write!(f, "could not verify code signature: {detail}")
+            }
+        }
+    }
+}
+
+/// Run the integrity check once and cache the result for the process lifetime.
+pub fn check() -> &'static IntegrityStatus {
+    INTEGRITY.get_or_init(|| {
+        let status = verify_running_build();
+        match &status {
+            IntegrityStatus::Notarized => info!("Integrity check: {status}"),
+            IntegrityStatus::SignedNotNotarized => warn!("Integrity check: {status}"),
+            _ => warn!(
+                "Integrity check failed ({status}); API token will {}be sent",
+                if status.allows_credentials() {
+                    ""
+                } else {
+                    "not "
+                }
+            ),
+        }
+        status
+    })
+}
+
+fn allow_unsigned_override() -> bool {
+    env::var(ALLOW_UNSIGNED_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn verify_running_build() -> IntegrityStatus {
+    let exe = match env::current_exe() {
+        Ok(path) => path,
+        Err(err) => return IntegrityStatus::Unknown(format!("current_exe: {err}")),
+    };
+    let target = bundle_root(&exe).unwrap_or(exe);
+
+    let output = match Command::new("/usr/bin/codesign")
+        .args(["--verify", "--deep", "--strict"])
+        .arg(&target)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+    {
+        Ok(output) => output,
+        Err(err) => return IntegrityStatus::Unknown(format!("codesign: {err}")),
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return classify_codesign_failure(&stderr);
+    }
+
+    let assessed = Command::new("/usr/sbin/spctl")
+        .args(["--assess", "--type", "execute"])
+        .arg(&target)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+
+    if assessed {
+        IntegrityStatus::Notarized
+    } else {
+        IntegrityStatus::SignedNotNotarized
+    }
+}
+
+fn classify_codesign_failure(stderr: &str) -> IntegrityStatus {
+    if stderr.contains("not signed at all") {
+        IntegrityStatus::Unsigned
+    } else {
+        IntegrityStatus::Modified(stderr.lines().last().unwrap_or(stderr).to_string())
+    }
+}
+
+/// Walk up from the executable to the enclosing `.app` bundle, if any.
+fn bundle_root(exe: &Path) -> Option<PathBuf> {
+    exe.ancestors()
+        .find(|p| p.extension().map(|e| e == "app").unwrap_or(false))
+        .map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_root_finds_enclosing_app() {
+        let exe = Path::new("/Applications/Cleo.app/Contents/MacOS/cleo");
+        assert_eq!(
+            bundle_root(exe),
+            Some(PathBuf::from("/Applications/Cleo.app"))
+        );
+        assert_eq!(bundle_root(Path::new("/tmp/target/debug/cleo")), None);
+    }
+
+    #[test]
+    fn codesign_failures_are_classified() {
+        assert_eq!(
+            classify_codesign_failure("target/debug/cleo: code object is not signed at all"),
+            IntegrityStatus::Unsigned
+        );
+        assert!(matches!(
+            classify_codesign_failure("Cleo.app: a sealed resource is missing or invalid"),
+            IntegrityStatus::Modified(_)
+        ));
+    }
+}
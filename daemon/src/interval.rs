@@ -1,13 +1,29 @@
-use std::sync::OnceLock;
-use std::time::Instant;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-const INTERVAL_SECONDS: u64 = 5 * 60;
+use chrono::{DateTime, Utc};
 
-static START_TIME: OnceLock<Instant> = OnceLock::new();
+const INTERVAL_SECONDS: u64 = 5 * 60;
 
-/// Returns the current 5-minute interval identifier since the app booted.
+/// Returns the current 5-minute interval identifier, counted from the Unix epoch.
+///
+/// Wall-clock based so ids don't restart at zero when the daemon relaunches. The
+/// server scopes intervals by device, so two machines reporting the same id is fine.
 pub fn current_interval_id() -> u64 {
-    let start = START_TIME.get_or_init(Instant::now);
-    let elapsed = start.elapsed().as_secs();
-    elapsed / INTERVAL_SECONDS
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    now / INTERVAL_SECONDS
+}
+
+/// Returns the interval containing `at`.
+pub fn interval_id_at(at: DateTime<Utc>) -> u64 {
+    at.timestamp().max(0) as u64 / INTERVAL_SECONDS
+}
+
+/// Returns when interval `id` starts and ends.
+pub fn interval_bounds(id: u64) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = (id * INTERVAL_SECONDS) as i64;
+    let at = |secs| DateTime::from_timestamp(secs, 0).unwrap_or_default();
+    (at(start), at(start + INTERVAL_SECONDS as i64))
 }
@@ -5,14 +5,31 @@
 mod accessibility;
 mod api;
 mod app;
+mod bandwidth;
 mod banned_apps_window;
+mod cadence;
 mod command_palette;
+mod compression;
 mod content_filter;
+mod device;
+mod disk_guard;
+mod drafts_menu;
+mod highlight;
 mod idle;
+mod ingest_proto;
+mod integrity;
 mod interval;
 mod keyboard_tracker;
 mod logging;
 mod mouse_tracker;
+mod notifications;
+mod power;
+mod presentation;
+mod resume;
+mod scene;
+mod session;
+mod summary;
+mod throttle;
 mod workspace_tracker;
 
 use std::cell::{Cell, RefCell};
@@ -62,17 +79,31 @@ use url::Url;
 use image_hasher::{HashAlg, HasherConfig, ImageHash};
 
 use crate::accessibility::{ActiveWindowInfo, check_accessibility_trusted};
-use crate::api::{ActivityEntry, ActivityEvent, ApiClient, ApiError, ImageFormat, VideoFormat};
+use crate::api::{
+    ActivityEntry, ActivityEvent, ApiClient, ApiError, ImageFormat, Transport, VideoFormat,
+};
 use crate::app::{
-    App, MenuBuilder, MenuItemHandle, StatusItem, TerminateReply,
+    App, MenuBuilder, MenuItemHandle, StatusItem, SubmenuHandle, SubmenuItem, TerminateReply,
     reply_to_application_should_terminate, terminate,
 };
 use crate::banned_apps_window::BannedAppsWindow;
-use crate::command_palette::{CommandPalette, HotkeyTracker, PaletteCommand};
+use crate::cadence::{Cadence, CadenceSettings};
+use crate::command_palette::{
+    CommandPalette, DraftAction, HotkeyTracker, PaletteAction, PaletteCommand,
+};
+use crate::compression::Compression;
 use crate::content_filter::{ContentFilter, NoOpFilter, NsfwFilter};
+use crate::device::DeviceIdentity;
+use crate::disk_guard::{DiskLimits, DiskStatus};
+use crate::highlight::{Highlight, HighlightDetector, HighlightSettings};
 use crate::interval::current_interval_id;
 use crate::keyboard_tracker::KeyboardTracker;
 use crate::mouse_tracker::MouseTracker;
+use crate::notifications::NotificationAction;
+use crate::power::{PowerPolicy, PowerState, PowerThrottle};
+use crate::presentation::{Meeting, WindowInfo};
+use crate::scene::{SceneDetector, Thumbnail};
+use crate::summary::{Summarizer, SummaryEvent};
 use crate::workspace_tracker::WorkspaceTracker;
 
 const API_BASE_ENV: &str = "CLEO_CAPTURE_API_URL";
@@ -81,6 +112,10 @@ const DEFAULT_PENDING_ROOT_DIR: &str = ".cleo/captures";
 const PENDING_SCREENSHOTS_SUBDIR: &str = "screenshots";
 const PENDING_RECORDINGS_SUBDIR: &str = "recordings";
 const SCREENSHOT_INTERVAL_SECS: u64 = 5;
+const MIN_SCREENSHOT_INTERVAL_SECS: u64 = 2; // Interval during bursts of activity
+const MAX_SCREENSHOT_INTERVAL_SECS: u64 = 60; // Interval after a long quiet stretch
+const SCREENSHOT_QUIET_AFTER_SECS: u64 = 30; // Quiet time before the interval starts stretching
+const SCREENSHOT_TICK_MS: u64 = 1000; // How often to check whether a screenshot is due
 const BURST_WINDOW_SECS: u64 = 5;
 const BURST_THRESHOLD_WITH_SWITCH: usize = 3; // Require multiple app switches before auto-recording
 const BURST_THRESHOLD_ACTIONS_ONLY: usize = 5; // Actions without app switch need higher threshold
@@ -97,8 +132,13 @@ const RECORDING_BATCH_MAX_BYTES_ENV: &str = "CLEO_RECORDING_BATCH_MAX_BYTES";
 const RECORDING_BATCH_MAX_FILES_ENV: &str = "CLEO_RECORDING_BATCH_MAX_FILES";
 const RECORDING_SAMPLE_MAX_FRAMES_DEFAULT: u32 = 12;
 const IDLE_THRESHOLD_SECS: f64 = 60.0; // Skip screenshots if idle for 60+ seconds
+const SCENE_CHANGE_THRESHOLD: f64 = 0.005; // Fraction of the screen that must change before saving a screenshot
 const PHASH_DISTANCE_THRESHOLD: u32 = 10; // Max hamming distance to consider images similar (0 = identical)
-const LIMITS_REFRESH_INTERVAL_SECS: u64 = 5 * 60; // Refresh recording limits every 5 minutes
+const LIMITS_REFRESH_INTERVAL_SECS: u64 = 60; // Cheap to poll: unchanged limits come back as a 304
+const HIGHLIGHT_MIN_RECORDING_SECS: u64 = 2 * 60; // Highlights are recordings of at least 2 minutes
+const HIGHLIGHT_SPIKE_WINDOW_SECS: u64 = 60;
+const HIGHLIGHT_SPIKE_EVENTS: usize = 150; // Events within the spike window that count as a spike
+const HIGHLIGHT_COOLDOWN_SECS: u64 = 30 * 60; // At most one highlight per 30 minutes
 
 #[derive(Clone, Copy, Debug)]
 struct RecordingBatchConfig {
@@ -110,17 +150,41 @@ struct RecordingBatchConfig {
 struct RuntimeDaemonSettings {
     pending_root_path: PathBuf,
     screenshot_interval_secs: u64,
+    adaptive_screenshot_interval: bool,
+    min_screenshot_interval_secs: u64,
+    max_screenshot_interval_secs: u64,
+    screenshot_quiet_after_secs: u64,
+    scene_change_capture: bool,
+    scene_change_threshold: f64,
     idle_threshold_secs: f64,
     activity_window_secs: u64,
     burst_threshold_with_switch: usize,
     burst_threshold_actions_only: usize,
     auto_stop_tail_secs: u64,
+    min_free_disk_bytes: u64,
+    max_spool_bytes: u64,
     upload_batch_interval_secs: u64,
+    upload_max_kb_per_sec: u64,
+    upload_avoid_metered: bool,
+    upload_transport: Transport,
+    upload_compression: Compression,
     recording_batch_max_bytes: u64,
     recording_batch_max_files: usize,
     recording_sample_max_frames: u32,
     activity_flush_interval_secs: u64,
     limits_refresh_interval_secs: u64,
+    highlight_enabled: bool,
+    highlight_min_recording_secs: u64,
+    highlight_spike_window_secs: u64,
+    highlight_spike_events: usize,
+    highlight_cooldown_secs: u64,
+    pause_during_meetings: bool,
+    pause_when_mirroring: bool,
+    meeting_apps: Vec<String>,
+    power_throttling: bool,
+    battery_min_screenshot_interval_secs: u64,
+    saver_min_screenshot_interval_secs: u64,
+    auto_record_min_battery_percent: u8,
 }
 
 static RUNTIME_DAEMON_SETTINGS: OnceLock<RuntimeDaemonSettings> = OnceLock::new();
@@ -132,6 +196,9 @@ struct CleoConfig {
     /// CLEO_CAPTURE_API_URL env var, then http://localhost:3000.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     api_url: Option<String>,
+    /// Random per-install id sent as X-Device-ID. Generated on first use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    device_id: Option<String>,
     #[serde(default)]
     privacy: PrivacySettings,
     #[serde(default)]
@@ -161,6 +228,9 @@ struct DaemonSettings {
     capture: CaptureSettings,
     upload: UploadSettings,
     activity: ActivitySettings,
+    highlight: HighlightConfig,
+    meetings: MeetingSettings,
+    power: PowerSettings,
 }
 
 impl Default for DaemonSettings {
@@ -169,6 +239,9 @@ impl Default for DaemonSettings {
             capture: CaptureSettings::default(),
             upload: UploadSettings::default(),
             activity: ActivitySettings::default(),
+            highlight: HighlightConfig::default(),
+            meetings: MeetingSettings::default(),
+            power: PowerSettings::default(),
         }
     }
 }
@@ -179,11 +252,24 @@ struct CaptureSettings {
     #[serde(alias = "local_storage_path")]
     pending_root_path: Option<PathBuf>,
     screenshot_interval_secs: u64,
+    /// Speed up screenshots during bursts of activity and slow down when quiet
+    adaptive_screenshot_interval: bool,
+    min_screenshot_interval_secs: u64,
+    max_screenshot_interval_secs: u64,
+    screenshot_quiet_after_secs: u64,
+    /// Only save screenshots when the screen changed since the last one
+    scene_change_capture: bool,
+    /// Fraction of the screen (0.0-1.0) that must change to count as a new scene
+    scene_change_threshold: f64,
     idle_threshold_secs: f64,
     activity_window_secs: u64,
     burst_threshold_with_switch: usize,
     burst_threshold_actions_only: usize,
     auto_stop_tail_secs: u64,
+    /// Stop capturing while the spool's volume has less than this free
+    min_free_disk_bytes: u64,
+    /// Stop capturing while captures waiting to upload take up this much
+    max_spool_bytes: u64,
 }
 
 impl Default for CaptureSettings {
@@ -191,11 +277,19 @@ impl Default for CaptureSettings {
         Self {
             pending_root_path: None,
             screenshot_interval_secs: SCREENSHOT_INTERVAL_SECS,
+            adaptive_screenshot_interval: true,
+            min_screenshot_interval_secs: MIN_SCREENSHOT_INTERVAL_SECS,
+            max_screenshot_interval_secs: MAX_SCREENSHOT_INTERVAL_SECS,
+            screenshot_quiet_after_secs: SCREENSHOT_QUIET_AFTER_SECS,
+            scene_change_capture: true,
+            scene_change_threshold: SCENE_CHANGE_THRESHOLD,
             idle_threshold_secs: IDLE_THRESHOLD_SECS,
             activity_window_secs: BURST_WINDOW_SECS,
             burst_threshold_with_switch: BURST_THRESHOLD_WITH_SWITCH,
             burst_threshold_actions_only: BURST_THRESHOLD_ACTIONS_ONLY,
             auto_stop_tail_secs: AUTO_RECORDING_TAIL_SECS,
+            min_free_disk_bytes: disk_guard::MIN_FREE_BYTES_DEFAULT,
+            max_spool_bytes: disk_guard::MAX_SPOOL_BYTES_DEFAULT,
         }
     }
 }
@@ -204,6 +298,15 @@ impl Default for CaptureSettings {
 #[serde(default)]
 struct UploadSettings {
     batch_interval_secs: u64,
+    /// Cap on capture upload speed in KB/s; 0 means no limit
+    max_kb_per_sec: u64,
+    /// Hold recordings in the spool while on a cellular, Personal Hotspot
+    /// or Low Data Mode connection
+    avoid_metered: bool,
+    /// Encoding of activity uploads: "json" or the smaller "protobuf"
+    transport: Transport,
+    /// Compress activity and screenshot uploads: "none", "gzip" or "zstd"
+    compression: Compression,
     recording_batch_max_bytes: u64,
     recording_batch_max_files: usize,
     recording_sample_max_frames: u32,
@@ -213,6 +316,10 @@ impl Default for UploadSettings {
     fn default() -> Self {
         Self {
             batch_interval_secs: UPLOAD_BATCH_INTERVAL_SECS,
+            max_kb_per_sec: 0,
+            avoid_metered: false,
+            transport: Transport::Json,
+            compression: Compression::Off,
             recording_batch_max_bytes: RECORDING_BATCH_MAX_BYTES_DEFAULT,
             recording_batch_max_files: RECORDING_BATCH_MAX_FILES_DEFAULT,
             recording_sample_max_frames: RECORDING_SAMPLE_MAX_FRAMES_DEFAULT,
@@ -236,6 +343,75 @@ impl Default for ActivitySettings {
     }
 }
 
+/// Recordings that look like a highlight get an agent pass right away
+/// instead of waiting for the idle scheduler
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+struct HighlightConfig {
+    enabled: bool,
+    min_recording_secs: u64,
+    spike_window_secs: u64,
+    spike_events: usize,
+    cooldown_secs: u64,
+}
+
+impl Default for HighlightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_recording_secs: HIGHLIGHT_MIN_RECORDING_SECS,
+            spike_window_secs: HIGHLIGHT_SPIKE_WINDOW_SECS,
+            spike_events: HIGHLIGHT_SPIKE_EVENTS,
+            cooldown_secs: HIGHLIGHT_COOLDOWN_SECS,
+        }
+    }
+}
+
+/// Pause capturing while on a call, sharing the screen or presenting
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+struct MeetingSettings {
+    pause_capture: bool,
+    /// Count mirroring the screen to another display (a projector) as a meeting
+    pause_when_mirroring: bool,
+    /// Conferencing apps to watch for besides the built-in ones (name or bundle ID)
+    extra_apps: Vec<String>,
+}
+
+impl Default for MeetingSettings {
+    fn default() -> Self {
+        Self {
+            pause_capture: true,
+            pause_when_mirroring: true,
+            extra_apps: Vec::new(),
+        }
+    }
+}
+
+/// Take screenshots less often and skip auto-recording on battery, in Low
+/// Power Mode and under thermal pressure
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+struct PowerSettings {
+    enabled: bool,
+    battery_min_screenshot_interval_secs: u64,
+    /// Used in Low Power Mode and under serious thermal pressure
+    saver_min_screenshot_interval_secs: u64,
+    /// Below this charge on battery, activity bursts don't start recordings
+    auto_record_min_battery_percent: u8,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            battery_min_screenshot_interval_secs: power::BATTERY_MIN_INTERVAL_SECS_DEFAULT,
+            saver_min_screenshot_interval_secs: power::SAVER_MIN_INTERVAL_SECS_DEFAULT,
+            auto_record_min_battery_percent: power::AUTO_RECORD_MIN_BATTERY_PERCENT_DEFAULT,
+        }
+    }
+}
+
 impl PrivacySettings {
     /// Check if capture should be blocked for the given app/window
     fn should_block(&self, app_name: &str, bundle_id: &str, window_title: &str) -> bool {
@@ -299,11 +475,18 @@ enum AppMessage {
     AutoStopRecording,
     MaxDurationReached,
     RefreshLimits,
+    CheckDisk,
     FlushActivity,
     SetApiToken,
     PollHotkey,
     PaletteKey { key_code: u16 },
+    ShowCommandPalette,
     ManageBannedApps,
+    RefreshDrafts,
+    OpenDraft { position: usize },
+    CheckMeeting,
+    TogglePauseDuringMeetings,
+    PowerChanged { state: PowerState },
 }
 
 /// Dispatch a message to the main thread using GCD
@@ -327,6 +510,57 @@ fn dispatch_main(message: AppMessage) {
     }
 }
 
+/// Hand a drafts fetch result to the main thread
+fn dispatch_main_drafts(result: Result<api::DraftsPage, String>) {
+    let action = move || {
+        DAEMON.with(|d| {
+            if let Some(ref daemon) = *d.borrow() {
+                daemon.show_drafts(result);
+            }
+        });
+    };
+
+    if MainThreadMarker::new().is_some() {
+        action();
+    } else {
+        dispatch2::Queue::main().exec_async(action);
+    }
+}
+
+/// Hand the result of a palette publish or dismiss to the main thread
+fn dispatch_main_draft_result(action: DraftAction, id: i64, result: Result<(), String>) {
+    let action = move || {
+        DAEMON.with(|d| {
+            if let Some(ref daemon) = *d.borrow() {
+                daemon.finish_draft_action(action, id, result);
+            }
+        });
+    };
+
+    if MainThreadMarker::new().is_some() {
+        action();
+    } else {
+        dispatch2::Queue::main().exec_async(action);
+    }
+}
+
+/// Hand the result of a meeting check to the main thread
+fn dispatch_main_meeting(meeting: Option<Meeting>) {
+    let action = move || {
+        DAEMON.with(|d| {
+            if let Some(ref daemon) = *d.borrow() {
+                daemon.set_meeting(meeting);
+            }
+        });
+    };
+
+    if MainThreadMarker::new().is_some() {
+        action();
+    } else {
+        dispatch2::Queue::main().exec_async(action);
+    }
+}
+
 /// Dispatch a ban toggle action to the main thread
 fn dispatch_main_toggle_ban(app_name: String, should_ban: bool) {
     let action = move || {
@@ -369,6 +603,12 @@ fn main() {
                 info!("Accessibility permissions granted");
             }
 
+            // Verify our own signature before any API traffic carries the token
+            let integrity_status = integrity::check();
+            if !integrity_status.allows_credentials() {
+                show_integrity_warning(mtm, integrity_status);
+            }
+
             DAEMON.with(|d| {
                 let mut daemon = CleoDaemon::new();
                 daemon.initialize(mtm);
@@ -380,27 +620,38 @@ fn main() {
 
     app.on_should_terminate({
         move || {
-            let (api, pending) = DAEMON.with(|d| {
+            let (api, pending, summaries) = DAEMON.with(|d| {
                 if let Some(ref daemon) = *d.borrow() {
                     let pending = daemon.take_activity_events();
+                    let summaries = daemon.take_activity_summaries();
                     let api = daemon.api_client().ok();
-                    (api, pending)
+                    (api, pending, summaries)
                 } else {
-                    (None, Vec::new())
+                    (None, Vec::new(), Vec::new())
                 }
             });
 
             let Some(api) = api else {
                 return TerminateReply::Now;
             };
-            if pending.is_empty() {
+            let session_id = session::take_current_session();
+            if pending.is_empty() && summaries.is_empty() && session_id.is_none() {
                 return TerminateReply::Now;
             }
 
             let (tx, rx) = mpsc::channel();
             thread::spawn(move || {
-                let result = api.upload_activity(&pending);
-                let _ = tx.send(result.is_ok());
+                let mut ok = true;
+                if !pending.is_empty() {
+                    ok &= api.upload_activity(&pending).is_ok();
+                }
+                if !summaries.is_empty() {
+                    ok &= api.upload_activity_summaries(&summaries).is_ok();
+                }
+                if let Some(session_id) = session_id {
+                    ok &= api.end_session(session_id).is_ok();
+                }
+                let _ = tx.send(ok);
             });
 
             thread::spawn(move || {
@@ -460,15 +711,41 @@ struct CleoDaemon {
     hotkey_tracker: RefCell<Option<HotkeyTracker>>,
     hotkey_poll_task: RefCell<Option<RepeatingTask>>,
     screenshot_task: RefCell<Option<RepeatingTask>>,
+    /// Decides when the next screenshot is due from recent activity
+    screenshot_cadence: RefCell<Option<Cadence>>,
+    /// Compares sampled frames against the last saved screenshot
+    scene_detector: RefCell<SceneDetector>,
     activity_flush_task: RefCell<Option<RepeatingTask>>,
     auto_stop_task: RefCell<Option<DelayedTask>>,
     max_duration_task: RefCell<Option<DelayedTask>>,
     limits_refresh_task: RefCell<Option<RepeatingTask>>,
+    disk_check_task: RefCell<Option<RepeatingTask>>,
+    /// Result of the last spool check; capturing pauses while it isn't Ok
+    disk_status: Cell<DiskStatus>,
+    meeting_check_task: RefCell<Option<RepeatingTask>>,
+    meeting_check_pending: Cell<bool>,
+    /// Call, screen share, slide show or mirrored display seen by the last meeting check
+    meeting: RefCell<Option<Meeting>>,
+    /// Whether a detected meeting pauses capturing; starts from the config and
+    /// can be switched off from the status menu
+    pause_during_meetings: Cell<bool>,
+    power_check_task: RefCell<Option<RepeatingTask>>,
+    /// Power source, Low Power Mode and thermal state from the last power check
+    power_state: Cell<PowerState>,
     activity_window: RefCell<VecDeque<BurstAction>>,
     manual_recording: Cell<bool>,
     auto_capture_enabled: Cell<bool>,
     activity_events: RefCell<Vec<ActivityEntry>>,
+    /// Per-interval rollups of switches, clicks, keypresses and active time
+    activity_summarizer: RefCell<Summarizer>,
+    /// Flags recordings worth an immediate agent pass
+    highlight_detector: RefCell<HighlightDetector>,
     recording_limits: RefCell<Option<api::RecordingLimits>>,
+    /// Last list shown in the Drafts submenu
+    drafts: RefCell<Option<api::DraftsPage>>,
+    drafts_loading: Cell<bool>,
+    /// A publish or dismiss from the palette is waiting on the API
+    draft_action_pending: Cell<bool>,
     privacy_settings: RefCell<PrivacySettings>,
     /// The currently focused app name (for ban toggle in command palette)
     current_app_name: RefCell<Option<String>>,
@@ -495,15 +772,34 @@ impl CleoDaemon {
             hotkey_tracker: RefCell::new(None),
             hotkey_poll_task: RefCell::new(None),
             screenshot_task: RefCell::new(None),
+            screenshot_cadence: RefCell::new(None),
+            scene_detector: RefCell::new(SceneDetector::new(
+                daemon_runtime_settings().scene_change_threshold,
+            )),
             activity_flush_task: RefCell::new(None),
             auto_stop_task: RefCell::new(None),
             max_duration_task: RefCell::new(None),
             limits_refresh_task: RefCell::new(None),
+            disk_check_task: RefCell::new(None),
+            disk_status: Cell::new(DiskStatus::Ok),
+            meeting_check_task: RefCell::new(None),
+            meeting_check_pending: Cell::new(false),
+            meeting: RefCell::new(None),
+            pause_during_meetings: Cell::new(daemon_runtime_settings().pause_during_meetings),
+            power_check_task: RefCell::new(None),
+            power_state: Cell::new(PowerState::default()),
             activity_window: RefCell::new(VecDeque::new()),
             manual_recording: Cell::new(false),
             auto_capture_enabled: Cell::new(true),
             activity_events: RefCell::new(Vec::new()),
+            activity_summarizer: RefCell::new(Summarizer::new(chrono::Duration::milliseconds(
+                (daemon_runtime_settings().idle_threshold_secs * 1000.0) as i64,
+            ))),
+            highlight_detector: RefCell::new(HighlightDetector::new(highlight_settings())),
             recording_limits: RefCell::new(None),
+            drafts: RefCell::new(None),
+            drafts_loading: Cell::new(false),
+            draft_action_pending: Cell::new(false),
             privacy_settings: RefCell::new(PrivacySettings::default()),
             current_app_name: RefCell::new(None),
             banned_apps_window: RefCell::new(None),
@@ -513,6 +809,8 @@ impl CleoDaemon {
     fn initialize(&mut self, mtm: MainThreadMarker) {
         let (menu, handles, targets) = build_status_menu(mtm);
         handles.set_recording(false);
+        handles.set_drafts_placeholder("Loading...");
+        handles.set_pause_during_meetings(self.pause_during_meetings.get());
         self.menu_handles.replace(Some(handles));
         self.menu_targets.replace(targets);
 
@@ -524,17 +822,26 @@ impl CleoDaemon {
             menu,
         );
         self.status_item.replace(Some(status_item));
+        notifications::init(Box::new(handle_notification_click));
 
         self.logging_daemon.replace(Some(LoggingDaemon::start()));
+        self.resume_after_crash();
+        if daemon_runtime_settings().upload_avoid_metered {
+            bandwidth::start_network_monitor();
+        }
         self.batch_uploader.replace(Some(BatchUploader::start()));
         self.load_privacy_settings();
         self.ensure_api_client();
+        self.open_work_session();
         self.start_activity_tracking();
         self.start_mouse_tracking();
         self.start_keyboard_tracking();
         self.start_screenshot_timer();
         self.start_activity_flush_timer();
         self.start_limits_refresh_timer();
+        self.start_disk_check_timer();
+        self.start_meeting_check_timer();
+        self.start_power_check_timer();
         self.start_command_palette();
     }
 
@@ -551,6 +858,9 @@ impl CleoDaemon {
         self.stop_screenshot_timer();
         self.stop_activity_flush_timer();
         self.stop_limits_refresh_timer();
+        self.stop_disk_check_timer();
+        self.stop_meeting_check_timer();
+        self.stop_power_check_timer();
         self.flush_activity_events_async();
     }
 
@@ -565,6 +875,13 @@ impl CleoDaemon {
                     if self.recorder.borrow().is_some() {
                         self.manual_recording.set(true);
                         self.cancel_auto_stop();
+                    } else if let Some(meeting) = self.meeting_pause() {
+                        show_notification("Recording not started", &meeting.message());
+                    } else if !self.disk_status.get().is_ok() {
+                        show_notification(
+                            "Recording not started",
+                            &self.disk_status.get().message(),
+                        );
                     }
                 }
             }
@@ -574,11 +891,20 @@ impl CleoDaemon {
             AppMessage::AutoStopRecording => self.stop_recording_if_auto(),
             AppMessage::MaxDurationReached => self.stop_recording_max_duration(),
             AppMessage::RefreshLimits => self.fetch_recording_limits(),
+            AppMessage::CheckDisk => {
+                self.check_disk();
+            }
             AppMessage::FlushActivity => self.flush_activity_events(),
             AppMessage::SetApiToken => self.show_api_token_dialog(),
             AppMessage::PollHotkey => self.poll_hotkey(),
             AppMessage::PaletteKey { key_code } => self.handle_palette_key(key_code),
+            AppMessage::ShowCommandPalette => self.show_command_palette(),
             AppMessage::ManageBannedApps => self.show_banned_apps_window(),
+            AppMessage::RefreshDrafts => self.refresh_drafts(),
+            AppMessage::OpenDraft { position } => self.open_draft(position),
+            AppMessage::CheckMeeting => self.check_meeting(),
+            AppMessage::TogglePauseDuringMeetings => self.toggle_pause_during_meetings(),
+            AppMessage::PowerChanged { state } => self.set_power_state(state),
         }
     }
 
@@ -587,6 +913,15 @@ impl CleoDaemon {
             warn!("Recording already in progress");
             return;
         }
+        if let Some(meeting) = self.meeting_pause() {
+            warn!("Not starting recording: {}", meeting.message());
+            return;
+        }
+        let disk = self.check_disk();
+        if !disk.is_ok() {
+            warn!("Not starting recording: {}", disk.message());
+            return;
+        }
 
         let privacy = self.privacy_settings.borrow().clone();
         match ScreenRecorder::start_with_exclusions(&privacy) {
@@ -597,6 +932,9 @@ impl CleoDaemon {
                 );
                 self.update_menu_state(true);
                 self.recorder.replace(Some(recorder));
+                self.highlight_detector
+                    .borrow_mut()
+                    .recording_started(Instant::now(), Utc::now());
                 self.schedule_max_duration_stop();
             }
             Err(err) => error!("Failed to start recording: {err}"),
@@ -606,10 +944,17 @@ impl CleoDaemon {
     fn stop_recording(&self) {
         self.manual_recording.set(false);
         if let Some(recorder) = self.recorder.borrow_mut().take() {
+            let highlight = self
+                .highlight_detector
+                .borrow_mut()
+                .recording_stopped(Instant::now(), Utc::now());
             match recorder.stop() {
                 Ok(()) => {
                     eprintln!("[recording] Recording saved to pending folder");
                     info!("Recording saved to pending folder");
+                    if let Some(highlight) = highlight {
+                        self.report_highlight(highlight);
+                    }
                 }
                 Err(err) => {
                     eprintln!("[recording] Failed to stop recording: {err}");
@@ -622,11 +967,40 @@ impl CleoDaemon {
         self.cancel_max_duration_stop();
     }
 
+    /// Ask the API for a focused agent pass on a highlight recording, in the background
+    fn report_highlight(&self, highlight: Highlight) {
+        if !daemon_runtime_settings().highlight_enabled {
+            return;
+        }
+        let Ok(api) = self.api_client() else {
+            return;
+        };
+        eprintln!(
+            "[recording] Highlight detected ({} to {}), requesting an agent pass",
+            highlight.started_at, highlight.ended_at
+        );
+        thread::spawn(move || {
+            if let Err(err) = api.report_highlight(highlight.started_at, highlight.ended_at) {
+                warn!("Failed to report highlight: {err}");
+            }
+        });
+    }
+
     fn take_screenshot(&self) {
         if !self.auto_capture_enabled.get() {
             debug!("Skipping screenshot - auto capture disabled");
             return;
         }
+        if let Some(meeting) = self.meeting_pause() {
+            debug!("Skipping screenshot - {}", meeting.message());
+            return;
+        }
+        let now = Instant::now();
+        if let Some(cadence) = self.screenshot_cadence.borrow_mut().as_mut() {
+            if !cadence.is_due(now) {
+                return;
+            }
+        }
         // Skip screenshot if user is idle
         let idle_threshold = daemon_runtime_settings().idle_threshold_secs;
         if idle::is_idle(idle_threshold) {
@@ -643,9 +1017,37 @@ impl CleoDaemon {
                 return;
             }
         }
+        if let Some(cadence) = self.screenshot_cadence.borrow_mut().as_mut() {
+            cadence.record_capture(now);
+        }
         let privacy = self.privacy_settings.borrow().clone();
-        if let Err(err) = capture_screenshot_with_exclusions(&privacy) {
-            error!("Failed to capture screenshot: {err}");
+        let frame = match grab_screen_with_exclusions(&privacy) {
+            Ok(frame) => frame,
+            Err(err) => {
+                error!("Failed to capture screenshot: {err}");
+                return;
+            }
+        };
+        // Keep the frame only if the screen meaningfully changed since the last one
+        let thumbnail = Thumbnail::from_rgba(frame.width, frame.height, &frame.rgba);
+        if daemon_runtime_settings().scene_change_capture
+            && !self.scene_detector.borrow().is_changed(&thumbnail)
+        {
+            debug!("Skipping screenshot - no scene change");
+            return;
+        }
+        // Slow down (or stop) when the server says the capture quota is running out
+        if !throttle::try_capture() {
+            debug!("Skipping screenshot - throttled by capture quota");
+            return;
+        }
+        if !self.disk_status.get().is_ok() {
+            debug!("Skipping screenshot - {}", self.disk_status.get().message());
+            return;
+        }
+        match save_screenshot(&frame) {
+            Ok(()) => self.scene_detector.borrow_mut().set_reference(thumbnail),
+            Err(err) => error!("Failed to save screenshot: {err}"),
         }
     }
 
@@ -701,6 +1103,12 @@ impl CleoDaemon {
             palette.set_current_app(Some(info.app_name.clone()), is_banned);
         }
 
+        self.activity_summarizer
+            .borrow_mut()
+            .record(Utc::now(), SummaryEvent::AppSwitch(&info.app_name));
+        // Switching to a conferencing app pauses capture without waiting for the timer
+        self.check_meeting();
+
         let event = ActivityEvent::foreground_switch(info.app_name, info.window_title);
         let interval_id = current_interval_id();
         let entry = ActivityEntry::new(Utc::now(), interval_id, event);
@@ -830,10 +1238,10 @@ impl CleoDaemon {
 
     fn record_mouse_click(&self) {
         info!("Mouse click recorded");
-        let event = ActivityEvent::mouse_click();
-        let interval_id = current_interval_id();
-        let entry = ActivityEntry::new(Utc::now(), interval_id, event);
-        self.activity_events.borrow_mut().push(entry);
+        // Clicks are only counted in the interval summary, not uploaded one by one
+        self.activity_summarizer
+            .borrow_mut()
+            .record(Utc::now(), SummaryEvent::Click);
         self.handle_activity_event(BurstActionKind::Click);
     }
 
@@ -1024,7 +1432,9 @@ impl CleoDaemon {
             }
         };
 
-        if let Some(cmd) = command {
+        if let Some(PaletteAction::Draft(action, position)) = command {
+            self.act_on_draft(action, position);
+        } else if let Some(PaletteAction::Run(cmd)) = command {
             match cmd {
                 PaletteCommand::ToggleCapture => {
                     self.toggle_capture_mode();
@@ -1051,23 +1461,34 @@ impl CleoDaemon {
                 PaletteCommand::TakeScreenshot => {
                     // Take screenshot directly (bypass capture_enabled check for manual trigger)
                     let privacy = self.privacy_settings.borrow().clone();
-                    if let Err(err) = capture_screenshot_with_exclusions(&privacy) {
-                        error!("Failed to capture screenshot: {err}");
+                    let saved = grab_screen_with_exclusions(&privacy).and_then(|frame| {
+                        save_screenshot(&frame)?;
+                        Ok(Thumbnail::from_rgba(frame.width, frame.height, &frame.rgba))
+                    });
+                    match saved {
+                        Ok(thumbnail) => self.scene_detector.borrow_mut().set_reference(thumbnail),
+                        Err(err) => error!("Failed to capture screenshot: {err}"),
                     }
                 }
                 PaletteCommand::ToggleBanApp => {
                     self.toggle_ban_current_app();
                 }
+                PaletteCommand::ReviewDrafts => self.review_drafts_in_palette(),
             }
         }
     }
 
     fn record_keypress(&self) {
-        // Just track activity for recording triggers - don't log what was typed
+        // Just count keypresses and track activity for recording triggers - don't log what was typed
+        self.activity_summarizer
+            .borrow_mut()
+            .record(Utc::now(), SummaryEvent::Keypress);
         self.handle_activity_event(BurstActionKind::Keypress);
     }
 
     fn flush_activity_events(&self) {
+        self.flush_activity_summaries();
+
         let pending = {
             let buffer = self.activity_events.borrow();
             if buffer.is_empty() {
@@ -1086,15 +1507,48 @@ impl CleoDaemon {
 
         if let Err(err) = api.upload_activity(&pending) {
             error!("Failed to upload activity events: {err}");
+            resume::record_unsent_activity(&pending);
             return;
         }
+        resume::record_activity_uploaded(&pending);
 
         let mut buffer = self.activity_events.borrow_mut();
         let sent = pending.len().min(buffer.len());
         buffer.drain(0..sent);
+        resume::record_unsent_activity(&buffer);
         info!(target: "activity", "Flushed {sent} activity event(s) to API");
     }
 
+    fn flush_activity_summaries(&self) {
+        let summaries = self
+            .activity_summarizer
+            .borrow_mut()
+            .take_finished(Utc::now());
+        if summaries.is_empty() {
+            return;
+        }
+
+        let api = match self.api_client() {
+            Ok(client) => client,
+            Err(err) => {
+                error!("Cannot upload activity summaries: {err}");
+                self.activity_summarizer.borrow_mut().requeue(summaries);
+                return;
+            }
+        };
+
+        if let Err(err) = api.upload_activity_summaries(&summaries) {
+            error!("Failed to upload activity summaries: {err}");
+            self.activity_summarizer.borrow_mut().requeue(summaries);
+            return;
+        }
+        info!(target: "activity", "Flushed {} activity summary(s) to API", summaries.len());
+    }
+
+    fn take_activity_summaries(&self) -> Vec<api::ActivitySummary> {
+        self.activity_summarizer.borrow_mut().take_all(Utc::now())
+    }
+
     fn take_activity_events(&self) -> Vec<ActivityEntry> {
         let mut buffer = self.activity_events.borrow_mut();
         if buffer.is_empty() {
@@ -1104,13 +1558,15 @@ impl CleoDaemon {
     }
 
     fn flush_activity_events_async(&self) {
-        let pending = {
-            let buffer = self.activity_events.borrow();
-            if buffer.is_empty() {
-                return;
-            }
-            buffer.clone()
-        };
+        let pending = self.activity_events.borrow().clone();
+        let summaries = self.take_activity_summaries();
+        if pending.is_empty() && summaries.is_empty() {
+            return;
+        }
+
+        // The app may exit before the upload finishes; the next launch resends
+        // whatever the marker still lists
+        resume::record_unsent_activity(&pending);
 
         let api = match self.api_client() {
             Ok(client) => client,
@@ -1121,12 +1577,47 @@ impl CleoDaemon {
         };
 
         thread::spawn(move || {
-            if let Err(err) = api.upload_activity(&pending) {
-                error!("Failed to upload activity events during shutdown: {err}");
+            if !pending.is_empty() {
+                match api.upload_activity(&pending) {
+                    Ok(()) => resume::record_activity_uploaded(&pending),
+                    Err(err) => {
+                        error!("Failed to upload activity events during shutdown: {err}")
+                    }
+                }
+            }
+            if !summaries.is_empty() {
+                if let Err(err) = api.upload_activity_summaries(&summaries) {
+                    error!("Failed to upload activity summaries during shutdown: {err}");
+                }
             }
         });
     }
 
+    /// Pick up what a crashed run left behind: activity the API never
+    /// accepted and recordings still sitting in the temp directory.
+    fn resume_after_crash(&self) {
+        let marker_path = daemon_runtime_settings()
+            .pending_root_path
+            .join(resume::RESUME_FILE);
+        let previous = resume::load(marker_path);
+        if let Some(interval) = previous.last_uploaded_interval {
+            info!("Last activity upload before launch was interval {interval}");
+        }
+
+        let unsent = resume::unsent_after(&previous.unsent_activity, previous.last_uploaded_at);
+        if !unsent.is_empty() {
+            info!(
+                "Re-queueing {} activity event(s) from the previous run",
+                unsent.len()
+            );
+            let mut buffer = self.activity_events.borrow_mut();
+            buffer.splice(0..0, unsent);
+            resume::record_unsent_activity(&buffer);
+        }
+
+        recover_orphaned_recordings(&previous);
+    }
+
     fn ensure_api_client(&self) {
         if self.api.borrow().is_some() {
             return;
@@ -1151,12 +1642,34 @@ impl CleoDaemon {
             .ok_or(CaptureError::ApiUnavailable)
     }
 
+    /// Open a server-side work session in the background; uploads carry its id once set.
+    fn open_work_session(&self) {
+        if session::current_session_id().is_some() {
+            return;
+        }
+        let Ok(api) = self.api_client() else {
+            return;
+        };
+        thread::spawn(move || {
+            let device = device::device_name();
+            match api.open_session(device.as_deref()) {
+                Ok(id) => {
+                    session::set_current_session(id);
+                    info!("Opened work session {id}");
+                }
+                Err(err) => warn!("Failed to open work session: {err}"),
+            }
+        });
+    }
+
     fn start_screenshot_timer(&self) {
         if self.screenshot_task.borrow().is_some() {
             return;
         }
-        let interval_secs = daemon_runtime_settings().screenshot_interval_secs;
-        let task = RepeatingTask::start(Duration::from_secs(interval_secs), || {
+        // Tick often; the cadence decides whether a screenshot is actually due
+        self.screenshot_cadence
+            .replace(Some(Cadence::new(self.current_cadence_settings())));
+        let task = RepeatingTask::start(Duration::from_millis(SCREENSHOT_TICK_MS), || {
             dispatch_main(AppMessage::TakeScreenshot);
         });
         self.screenshot_task.replace(Some(task));
@@ -1164,6 +1677,7 @@ impl CleoDaemon {
 
     fn stop_screenshot_timer(&self) {
         self.screenshot_task.borrow_mut().take();
+        self.screenshot_cadence.borrow_mut().take();
     }
 
     fn start_activity_flush_timer(&self) {
@@ -1196,6 +1710,218 @@ impl CleoDaemon {
         self.limits_refresh_task.borrow_mut().take();
     }
 
+    fn start_disk_check_timer(&self) {
+        if self.disk_check_task.borrow().is_some() {
+            return;
+        }
+        self.check_disk();
+        let task =
+            RepeatingTask::start(Duration::from_secs(disk_guard::CHECK_INTERVAL_SECS), || {
+                dispatch_main(AppMessage::CheckDisk);
+            });
+        self.disk_check_task.replace(Some(task));
+    }
+
+    fn stop_disk_check_timer(&self) {
+        self.disk_check_task.borrow_mut().take();
+    }
+
+    /// Re-measure free space and the spool, show the spool size in the status
+    /// menu, and stop a running recording once the disk is too full. Notifies
+    /// when capturing pauses, not on every check while it stays paused.
+    fn check_disk(&self) -> DiskStatus {
+        let root = &daemon_runtime_settings().pending_root_path;
+        let spool_bytes = disk_guard::dir_size_bytes(root);
+        let free_bytes = match disk_guard::free_bytes(root) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!("Failed to read free disk space: {err}");
+                u64::MAX
+            }
+        };
+        let status = disk_guard::evaluate(disk_limits(), free_bytes, spool_bytes);
+        if let Some(handles) = self.menu_handles.borrow().as_ref() {
+            handles.set_spool(spool_bytes, status);
+        }
+
+        let previous = self.disk_status.replace(status);
+        if previous.is_ok() && !status.is_ok() {
+            warn!("Pausing capture: {}", status.message());
+            if self.recorder.borrow().is_some() {
+                self.stop_recording();
+            }
+            show_notification("Cleo paused capturing", &status.message());
+        } else if !previous.is_ok() && status.is_ok() {
+            info!("Disk space available again, capture resumed");
+        }
+        status
+    }
+
+    fn start_meeting_check_timer(&self) {
+        if self.meeting_check_task.borrow().is_some() {
+            return;
+        }
+        self.check_meeting();
+        let task = RepeatingTask::start(
+            Duration::from_secs(presentation::CHECK_INTERVAL_SECS),
+            || {
+                dispatch_main(AppMessage::CheckMeeting);
+            },
+        );
+        self.meeting_check_task.replace(Some(task));
+    }
+
+    fn stop_meeting_check_timer(&self) {
+        self.meeting_check_task.borrow_mut().take();
+    }
+
+    /// Look for a call, screen share, slide show or mirrored display in the
+    /// background; listing the on-screen windows can take a moment
+    fn check_meeting(&self) {
+        if self.meeting_check_pending.replace(true) {
+            return;
+        }
+        let foreground = self.current_app_name.borrow().clone();
+        thread::spawn(move || {
+            let windows = match list_on_screen_windows() {
+                Ok(windows) => windows,
+                Err(err) => {
+                    debug!("Failed to list windows for meeting detection: {err}");
+                    Vec::new()
+                }
+            };
+            let settings = daemon_runtime_settings();
+            let mirrored = settings.pause_when_mirroring && presentation::is_mirroring();
+            let meeting = presentation::detect(
+                foreground.as_deref(),
+                &windows,
+                mirrored,
+                &settings.meeting_apps,
+            );
+            dispatch_main_meeting(meeting);
+        });
+    }
+
+    /// Store the meeting check result. Stops a running recording and notifies
+    /// when capturing pauses, not on every check while the meeting goes on.
+    fn set_meeting(&self, meeting: Option<Meeting>) {
+        self.meeting_check_pending.set(false);
+        let previous = self.meeting.replace(meeting);
+        let current = self.meeting.borrow().clone();
+        if previous == current {
+            return;
+        }
+        match (&previous, &current) {
+            (None, Some(meeting)) => info!("Meeting detected: {}", meeting.message()),
+            (Some(_), None) => info!("Meeting ended"),
+            _ => {}
+        }
+        if previous.is_none()
+            && let Some(meeting) = self.meeting_pause()
+        {
+            warn!("Pausing capture: {}", meeting.message());
+            if self.recorder.borrow().is_some() {
+                self.stop_recording();
+            }
+            show_notification("Cleo paused capturing", &meeting.message());
+        }
+        self.update_meeting_badge();
+    }
+
+    /// The meeting capturing is paused for, if any
+    fn meeting_pause(&self) -> Option<Meeting> {
+        if !self.pause_during_meetings.get() {
+            return None;
+        }
+        self.meeting.borrow().clone()
+    }
+
+    /// The user's override from the status menu: keep capturing through meetings
+    fn toggle_pause_during_meetings(&self) {
+        let enabled = !self.pause_during_meetings.get();
+        self.pause_during_meetings.set(enabled);
+        info!(
+            "Pause during meetings {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        if let Some(handles) = self.menu_handles.borrow().as_ref() {
+            handles.set_pause_during_meetings(enabled);
+        }
+        if enabled
+            && let Some(meeting) = self.meeting_pause()
+            && self.recorder.borrow().is_some()
+        {
+            warn!("Pausing capture: {}", meeting.message());
+            self.stop_recording();
+        }
+        self.update_meeting_badge();
+    }
+
+    /// Badge the status icon while a meeting pauses capturing
+    fn update_meeting_badge(&self) {
+        let status_item = self.status_item.borrow();
+        let Some(status_item) = status_item.as_ref() else {
+            return;
+        };
+        match self.meeting_pause() {
+            Some(meeting) => status_item.set_badge(
+                Some("Paused"),
+                &format!(
+                    "Cleo Screen Recorder - {}; capturing paused",
+                    meeting.message()
+                ),
+            ),
+            None => status_item.set_badge(None, "Cleo Screen Recorder"),
+        }
+    }
+
+    fn start_power_check_timer(&self) {
+        if self.power_check_task.borrow().is_some() {
+            return;
+        }
+        // pmset takes a moment, so power checks run off the main thread
+        let check = || {
+            dispatch_main(AppMessage::PowerChanged {
+                state: power::read_state(),
+            });
+        };
+        thread::spawn(check);
+        let task = RepeatingTask::start(Duration::from_secs(power::CHECK_INTERVAL_SECS), check);
+        self.power_check_task.replace(Some(task));
+    }
+
+    fn stop_power_check_timer(&self) {
+        self.power_check_task.borrow_mut().take();
+    }
+
+    /// Store the power state and slow or restore the screenshot cadence when
+    /// what it allows changes
+    fn set_power_state(&self, state: PowerState) {
+        let before = self.power_throttle();
+        self.power_state.set(state);
+        let after = self.power_throttle();
+        if before == after {
+            return;
+        }
+        match after.min_interval {
+            Some(interval) => info!(
+                "Power: {}; screenshots at most every {}s, auto-recording {}",
+                state.describe(),
+                interval.as_secs(),
+                if after.auto_record { "on" } else { "off" }
+            ),
+            None => info!("Power: {}; capturing at the normal pace", state.describe()),
+        }
+        let settings = self.current_cadence_settings();
+        if let Some(cadence) = self.screenshot_cadence.borrow_mut().as_mut() {
+            cadence.set_settings(settings);
+        }
+    }
+
+    fn power_throttle(&self) -> PowerThrottle {
+        power::evaluate(power_policy(), self.power_state.get())
+    }
+
     fn handle_activity_event(&self, kind: BurstActionKind) {
         // Skip activity tracking if current app is banned
         if let Some(ref app_name) = *self.current_app_name.borrow() {
@@ -1204,7 +1930,13 @@ impl CleoDaemon {
             }
         }
 
+        if let Some(cadence) = self.screenshot_cadence.borrow_mut().as_mut() {
+            cadence.record_activity(Instant::now());
+        }
         self.track_activity_burst(kind);
+        self.highlight_detector
+            .borrow_mut()
+            .record_activity(Instant::now());
         self.schedule_auto_stop();
     }
 
@@ -1245,6 +1977,10 @@ impl CleoDaemon {
             || action_count >= burst_threshold_actions_only;
 
         if burst_triggered && self.recorder.borrow().is_none() && self.auto_capture_enabled.get() {
+            if !self.power_throttle().auto_record {
+                debug!("Not auto-recording - {}", self.power_state.get().describe());
+                return;
+            }
             eprintln!(
                 "[recording] Automatic recording triggered by activity burst ({} events in {}s window)",
                 window.len(),
@@ -1299,8 +2035,17 @@ impl CleoDaemon {
         if self.recorder.borrow().is_some() {
             eprintln!("[recording] Stopping recording: max duration reached");
             info!("Stopping recording: max duration reached");
-            self.manual_recording.set(false);
+            let was_manual = self.manual_recording.replace(false);
             self.stop_recording();
+            // Automatic recordings start again on the next burst; a manual one
+            // needs the user to restart it
+            if was_manual {
+                notifications::notify(
+                    "Recording auto-stopped",
+                    "Cleo stopped recording at the maximum length. Start a new one from the command palette.",
+                    Some(NotificationAction::OpenCommandPalette),
+                );
+            }
         }
     }
 
@@ -1348,7 +2093,29 @@ impl CleoDaemon {
                         storage_remaining,
                         storage_exceeded
                     );
+                    // Nothing to compare against on the first fetch after launch
+                    if let Some(previous) = self
+                        .recording_limits
+                        .borrow()
+                        .as_ref()
+                        .map(|l| l.drafts_ready)
+                        && limits.drafts_ready > previous
+                    {
+                        notify_new_drafts(
+                            limits.drafts_ready - previous,
+                            limits.app_url.as_deref(),
+                        );
+                    }
                     self.recording_limits.borrow_mut().replace(limits);
+                    // Keep the Drafts count in the status menu current
+                    self.refresh_drafts();
+
+                    // Launch mode starts and ends with these limits, so a
+                    // running cadence picks up the change on the next refresh
+                    let settings = self.current_cadence_settings();
+                    if let Some(cadence) = self.screenshot_cadence.borrow_mut().as_mut() {
+                        cadence.set_settings(settings);
+                    }
                 }
                 Err(err) => {
                     warn!("Failed to fetch recording limits: {}", err);
@@ -1357,6 +2124,191 @@ impl CleoDaemon {
         }
     }
 
+    /// Fetch the pending drafts in the background for the Drafts submenu
+    fn refresh_drafts(&self) {
+        let api = match self.api_client() {
+            Ok(client) => client,
+            Err(_) => {
+                if let Some(handles) = self.menu_handles.borrow().as_ref() {
+                    handles.set_drafts_placeholder("Set an API token to see drafts");
+                }
+                return;
+            }
+        };
+        // Creating the client above may already have started a fetch
+        if self.drafts_loading.replace(true) {
+            return;
+        }
+        thread::spawn(move || {
+            let result = api
+                .fetch_drafts(drafts_menu::MAX_MENU_DRAFTS)
+                .map_err(|err| err.to_string());
+            dispatch_main_drafts(result);
+        });
+    }
+
+    fn show_drafts(&self, result: Result<api::DraftsPage, String>) {
+        self.drafts_loading.set(false);
+        match result {
+            Ok(page) => {
+                self.drafts.replace(Some(page));
+                self.update_drafts_views();
+            }
+            Err(err) => {
+                warn!("Failed to fetch drafts: {err}");
+                // Keep showing the last list if there is one
+                if self.drafts.borrow().is_none()
+                    && let Some(handles) = self.menu_handles.borrow().as_ref()
+                {
+                    handles.set_drafts_placeholder("Couldn't load drafts");
+                }
+                if let Some(palette) = self.command_palette.borrow().as_ref() {
+                    palette.set_drafts_status(Some("Couldn't load drafts"));
+                }
+            }
+        }
+    }
+
+    /// Show the cached drafts in the status menu and the command palette
+    fn update_drafts_views(&self) {
+        let drafts = self.drafts.borrow();
+        let Some(page) = drafts.as_ref() else {
+            return;
+        };
+        if let Some(handles) = self.menu_handles.borrow().as_ref() {
+            handles.set_drafts(page);
+        }
+        if let Some(palette) = self.command_palette.borrow().as_ref() {
+            palette.set_drafts_waiting(page.total);
+            palette.show_drafts(
+                page.drafts
+                    .iter()
+                    .map(|draft| drafts_menu::item_title(&draft.first_line))
+                    .collect(),
+            );
+            if !self.draft_action_pending.get() {
+                palette.set_drafts_status(None);
+            }
+        }
+    }
+
+    /// The palette switched to its drafts list: show what's cached and fetch
+    /// the latest
+    fn review_drafts_in_palette(&self) {
+        if self.drafts.borrow().is_some() {
+            self.update_drafts_views();
+        } else if let Some(palette) = self.command_palette.borrow().as_ref() {
+            palette.set_drafts_status(Some("Loading drafts..."));
+        }
+        self.refresh_drafts();
+    }
+
+    /// Post, dismiss or open the draft picked in the palette
+    fn act_on_draft(&self, action: DraftAction, position: usize) {
+        let Some(draft) = self
+            .drafts
+            .borrow()
+            .as_ref()
+            .and_then(|page| page.drafts.get(position).cloned())
+        else {
+            return;
+        };
+        if action == DraftAction::Open {
+            open_with_default_app(draft.url.as_ref());
+            return;
+        }
+        // One request at a time, so a held key can't post the next draft too
+        if self.draft_action_pending.get() {
+            return;
+        }
+        let api = match self.api_client() {
+            Ok(client) => client,
+            Err(err) => {
+                warn!("Cannot review drafts: {err}");
+                return;
+            }
+        };
+
+        self.draft_action_pending.set(true);
+        if let Some(palette) = self.command_palette.borrow().as_ref() {
+            palette.set_drafts_status(Some(if action == DraftAction::Post {
+                "Posting..."
+            } else {
+                "Dismissing..."
+            }));
+        }
+        thread::spawn(move || {
+            let result = match action {
+                DraftAction::Post => api.publish_draft(draft.id),
+                _ => api.dismiss_draft(draft.id),
+            };
+            dispatch_main_draft_result(action, draft.id, result.map_err(|e| e.user_message()));
+        });
+    }
+
+    fn finish_draft_action(&self, action: DraftAction, id: i64, result: Result<(), String>) {
+        self.draft_action_pending.set(false);
+        let status = match result {
+            Ok(()) => {
+                info!("Draft {id}: {action:?} done from the command palette");
+                if let Some(page) = self.drafts.borrow_mut().as_mut() {
+                    let before = page.drafts.len();
+                    page.drafts.retain(|draft| draft.id != id);
+                    if page.drafts.len() < before {
+                        page.total = page.total.saturating_sub(1);
+                    }
+                }
+                self.update_drafts_views();
+                self.refresh_drafts();
+                if action == DraftAction::Post {
+                    "Posted to X".to_string()
+                } else {
+                    "Dismissed".to_string()
+                }
+            }
+            Err(err) => {
+                warn!("Draft {id}: {action:?} failed: {err}");
+                if action == DraftAction::Post {
+                    format!("Couldn't post: {err}")
+                } else {
+                    format!("Couldn't dismiss: {err}")
+                }
+            }
+        };
+        if let Some(palette) = self.command_palette.borrow().as_ref() {
+            palette.set_drafts_status(Some(&status));
+        }
+    }
+
+    /// Open the web review page for the clicked Drafts submenu item
+    fn open_draft(&self, position: usize) {
+        if let Some(page) = self.drafts.borrow().as_ref()
+            && let Some(url) = drafts_menu::url_at(page, position)
+        {
+            open_with_default_app(url.as_ref());
+        }
+    }
+
+    /// Screenshot cadence from the runtime settings, capped to the launch mode
+    /// interval while the user's launch mode is on. Power throttling applies
+    /// last, so a laptop on battery slows down even in launch mode.
+    fn current_cadence_settings(&self) -> CadenceSettings {
+        let settings = screenshot_cadence_settings();
+        let settings = match self
+            .recording_limits
+            .borrow()
+            .as_ref()
+            .and_then(|l| l.screenshot_ceiling(Utc::now()))
+        {
+            Some(ceiling) => settings.capped(ceiling),
+            None => settings,
+        };
+        match self.power_throttle().min_interval {
+            Some(floor) => settings.floored(floor),
+            None => settings,
+        }
+    }
+
     fn load_privacy_settings(&self) {
         match load_privacy_settings() {
             Ok(settings) => {
@@ -1391,11 +2343,32 @@ impl CleoDaemon {
         save_api_token(&api_key)?;
         let base = resolve_api_base();
         let client = ApiClient::new(base, Some(api_key)).map_err(CaptureError::from)?;
-        self.api.replace(Some(client));
+        let client = with_device_identity(client);
+        self.api.replace(Some(client.clone()));
         info!("API token saved from login link");
+        thread::spawn(move || match client.enroll_device() {
+            Ok(id) => info!("Enrolled this machine as device {id}"),
+            Err(err) => warn!("Failed to enroll device: {err}"),
+        });
+        self.open_work_session();
         Ok(())
     }
 
+    /// API tokens start with `cleo_`; anything else is an enrollment code
+    /// (e.g. `K7QM-2XWD`) to trade for a token first.
+    fn apply_token_or_enrollment_code(&self, input: String) -> Result<(), CaptureError> {
+        let input = input.trim();
+        if input.starts_with("cleo_") {
+            return self.apply_api_token(input.to_string());
+        }
+        let client = ApiClient::new(resolve_api_base(), None).map_err(CaptureError::from)?;
+        let api_key = client
+            .redeem_enrollment_code(input)
+            .map_err(CaptureError::from)?;
+        info!("Redeemed enrollment code for an API token");
+        self.apply_api_token(api_key)
+    }
+
     fn show_api_token_dialog(&self) {
         let Some(mtm) = MainThreadMarker::new() else {
             error!("show_api_token_dialog must be called on main thread");
@@ -1474,7 +2447,7 @@ impl CleoDaemon {
             let alert = NSAlert::new(mtm);
             let title = NSString::from_str("Set API Token");
             let message = NSString::from_str(
-                "Enter your Cleo API token.\n\nYou can get this from the web dashboard after logging in with Twitter.",
+                "Enter your Cleo API token, or the enrollment code shown after logging in to connect the recorder.\n\nYou can get a token from the web dashboard after logging in with Twitter.",
             );
             alert.setMessageText(&title);
             alert.setInformativeText(&message);
@@ -1543,7 +2516,7 @@ impl CleoDaemon {
                 let token = value.to_string();
 
                 if !token.trim().is_empty() {
-                    match self.apply_api_token(token) {
+                    match self.apply_token_or_enrollment_code(token) {
                         Ok(()) => {
                             info!("API token saved from dialog");
                             show_notification("Cleo", "API token saved successfully!");
@@ -1569,11 +2542,32 @@ fn build_status_menu(
             dispatch_main(AppMessage::ToggleRecording);
         });
 
-    let (menu, targets) = builder
+    let builder = builder
         .add_action_item("Take Screenshot", "", || {
             dispatch_main(AppMessage::TakeScreenshot);
         })
-        .add_separator()
+        .add_separator();
+
+    // Title refreshed by the disk check
+    let (builder, spool_handle) =
+        builder.add_action_item_with_handle("Spool: -", "", open_spool_folder);
+
+    // Filled in when the submenu opens
+    let (builder, drafts_handle) = builder.add_separator().add_submenu_with_handle(
+        "Drafts",
+        || dispatch_main(AppMessage::RefreshDrafts),
+        |position| dispatch_main(AppMessage::OpenDraft { position }),
+    );
+
+    // Unchecking keeps capturing through calls and screen shares
+    let (builder, meetings_handle) =
+        builder
+            .add_separator()
+            .add_action_item_with_handle("Pause During Meetings", "", || {
+                dispatch_main(AppMessage::TogglePauseDuringMeetings)
+            });
+
+    let (menu, targets) = builder
         .add_action_item("Manage Banned Apps...", "", || {
             dispatch_main(AppMessage::ManageBannedApps);
         })
@@ -1586,13 +2580,68 @@ fn build_status_menu(
         })
         .build();
 
-    (menu, MenuHandles::new(record_handle), targets)
+    (
+        menu,
+        MenuHandles::new(record_handle, spool_handle, drafts_handle, meetings_handle),
+        targets,
+    )
+}
+
+/// Show the captures waiting to upload in Finder
+fn open_spool_folder() {
+    let root = &daemon_runtime_settings().pending_root_path;
+    if let Err(err) = fs::create_dir_all(root) {
+        warn!("Spool folder unavailable: {err}");
+        return;
+    }
+    open_with_default_app(root.as_os_str());
 }
 
 fn build_api_client() -> Result<ApiClient, CaptureError> {
     let base = resolve_api_base();
     let auth_token = load_api_token()?;
-    ApiClient::new(base, Some(auth_token)).map_err(CaptureError::from)
+    let client = ApiClient::new(base, Some(auth_token))
+        .map_err(CaptureError::from)?
+        .with_upload_limit(daemon_runtime_settings().upload_max_kb_per_sec)
+        .with_transport(daemon_runtime_settings().upload_transport)
+        .with_compression(daemon_runtime_settings().upload_compression);
+    Ok(with_device_identity(client))
+}
+
+/// Attach this machine's identity to the client; uploads still work without it.
+fn with_device_identity(client: ApiClient) -> ApiClient {
+    match load_or_create_device_uid() {
+        Ok(uid) => client.with_device(DeviceIdentity {
+            uid,
+            name: device::device_name(),
+        }),
+        Err(err) => {
+            warn!("Device id unavailable, uploads will not be attributed: {err}");
+            client
+        }
+    }
+}
+
+/// Read the per-install device id from config, generating and saving one if missing.
+fn load_or_create_device_uid() -> Result<String, CaptureError> {
+    let mut config = load_config()?;
+    if let Some(uid) = config.device_id.as_ref().filter(|uid| !uid.is_empty()) {
+        return Ok(uid.clone());
+    }
+
+    let uid = device::generate_device_uid().map_err(CaptureError::from)?;
+    config.device_id = Some(uid.clone());
+
+    let path = cleo_config_path()?;
+    let payload = serde_json::to_string_pretty(&config).map_err(|err| {
+        CaptureError::Config(format!(
+            "Failed to serialize Cleo config at {}: {err}",
+            path.display()
+        ))
+    })?;
+    fs::write(&path, payload).map_err(CaptureError::from)?;
+    info!("Generated device id for this machine");
+    Ok(uid)
 }
 
 fn load_config() -> Result<CleoConfig, CaptureError> {
@@ -1623,6 +2672,7 @@ fn create_default_config(path: &Path) -> Result<CleoConfig, CaptureError> {
     let config = CleoConfig {
         api_token: String::new(),
         api_url: None,
+        device_id: None,
         privacy: PrivacySettings::default(),
         daemon: DaemonSettings::default(),
     };
@@ -1680,6 +2730,7 @@ fn save_api_token(token: &str) -> Result<(), CaptureError> {
     let config = CleoConfig {
         api_token,
         api_url: existing.as_ref().and_then(|c| c.api_url.clone()),
+        device_id: existing.as_ref().and_then(|c| c.device_id.clone()),
         privacy: existing
             .as_ref()
             .map(|c| c.privacy.clone())
@@ -1717,6 +2768,7 @@ fn save_privacy_settings(privacy: &PrivacySettings) -> Result<(), CaptureError>
     let config = CleoConfig {
         api_token,
         api_url: existing.as_ref().and_then(|c| c.api_url.clone()),
+        device_id: existing.as_ref().and_then(|c| c.device_id.clone()),
         privacy: privacy.clone(),
         daemon: existing.map(|c| c.daemon).unwrap_or_default(),
     };
@@ -1796,18 +2848,79 @@ impl CleoRoute {
 }
 
 fn show_notification(title: &str, message: &str) {
-    // NSUserNotificationCenter is deprecated and returns null for accessory apps
-    // Just log the notification for now
-    info!("[notification] {}: {}", title, message);
+    notifications::notify(title, message, None);
+}
+
+/// Tell the user new drafts are waiting; clicking opens the web app
+fn notify_new_drafts(count: u64, app_url: Option<&str>) {
+    let title = if count == 1 {
+        "1 new draft tweet ready".to_string()
+    } else {
+        format!("{count} new draft tweets ready")
+    };
+    notifications::notify(
+        &title,
+        "Review them in Cleo before they go out.",
+        app_url.map(|url| NotificationAction::OpenUrl(url.to_string())),
+    );
+}
+
+/// Run the action of a clicked notification
+fn handle_notification_click(action: NotificationAction) {
+    match action {
+        NotificationAction::OpenUrl(url) => open_with_default_app(url.as_ref()),
+        NotificationAction::OpenCommandPalette => dispatch_main(AppMessage::ShowCommandPalette),
+    }
+}
+
+/// Open a URL or folder the way Finder would
+fn open_with_default_app(target: &std::ffi::OsStr) {
+    if let Err(err) = std::process::Command::new("/usr/bin/open")
+        .arg(target)
+        .spawn()
+    {
+        warn!("Failed to open {}: {err}", target.display());
+    }
+}
+
+/// Warn the user that this build failed its integrity check and won't authenticate.
+fn show_integrity_warning(mtm: MainThreadMarker, status: &integrity::IntegrityStatus) {
+    unsafe {
+        let alert = NSAlert::new(mtm);
+        let title = NSString::from_str("Cleo could not verify this build");
+        let message = NSString::from_str(&format!(
+            "This copy of Cleo is not a verified release ({status}).\n\nYour API token will not be sent until you reinstall Cleo from an official download."
+        ));
+        alert.setMessageText(&title);
+        alert.setInformativeText(&message);
+        alert.setAlertStyle(NSAlertStyle::Critical);
+
+        let app = NSApplication::sharedApplication(mtm);
+        app.activateIgnoringOtherApps(true);
+        alert.runModal();
+    }
 }
 
 struct MenuHandles {
     recording: MenuItemHandle,
+    spool: MenuItemHandle,
+    drafts: SubmenuHandle,
+    meetings: MenuItemHandle,
 }
 
 impl MenuHandles {
-    fn new(recording: MenuItemHandle) -> Self {
-        Self { recording }
+    fn new(
+        recording: MenuItemHandle,
+        spool: MenuItemHandle,
+        drafts: SubmenuHandle,
+        meetings: MenuItemHandle,
+    ) -> Self {
+        Self {
+            recording,
+            spool,
+            drafts,
+            meetings,
+        }
     }
 
     fn set_recording(&self, recording: bool) {
@@ -1819,6 +2932,31 @@ impl MenuHandles {
         self.recording.set_title(title);
         self.recording.set_enabled(true);
     }
+
+    fn set_spool(&self, spool_bytes: u64, status: DiskStatus) {
+        let size = disk_guard::format_bytes(spool_bytes);
+        let title = if status.is_ok() {
+            format!("Spool: {size}")
+        } else {
+            format!("Spool: {size} (capture paused)")
+        };
+        self.spool.set_title(&title);
+    }
+
+    fn set_drafts(&self, page: &api::DraftsPage) {
+        self.drafts.set_title(&drafts_menu::menu_title(Some(page)));
+        self.drafts.set_items(&drafts_menu::items(page));
+    }
+
+    fn set_drafts_placeholder(&self, text: &str) {
+        self.drafts.set_title(&drafts_menu::menu_title(None));
+        self.drafts
+            .set_items(&[SubmenuItem::Label(text.to_string())]);
+    }
+
+    fn set_pause_during_meetings(&self, enabled: bool) {
+        self.meetings.set_checked(enabled);
+    }
 }
 
 struct LoggingDaemon {
@@ -1834,8 +2972,9 @@ impl LoggingDaemon {
             while !thread_flag.load(Ordering::Relaxed) {
                 info!(
                     target: "daemon",
-                    "heartbeat {}",
-                    Local::now().format("%H:%M:%S")
+                    "heartbeat {} integrity={}",
+                    Local::now().format("%H:%M:%S"),
+                    integrity::check().label()
                 );
                 if sleep_with_cancellation(&thread_flag, Duration::from_secs(1)) {
                     break;
@@ -2052,6 +3191,7 @@ impl ScreenRecorder {
             .add_recording_output(&recording_output)
             .map_err(CaptureError::from)?;
         stream.start_capture().map_err(CaptureError::from)?;
+        resume::recording_started(&file_path);
 
         Ok(Self {
             stream,
@@ -2119,6 +3259,7 @@ impl Drop for ScreenRecorder {
             error!("Failed to stop stream during drop: {err}");
         }
         let _ = fs::remove_file(&self.file_path);
+        resume::recording_finished(&self.file_path);
     }
 }
 
@@ -2184,10 +3325,48 @@ impl From<EncodingError> for CaptureError {
 fn recording_file_path() -> PathBuf {
     let mut path = env::temp_dir();
     let stamp = Local::now().format("%Y%m%d-%H%M%S");
-    path.push(format!("cleo-recording-{stamp}.mp4"));
+    path.push(format!(
+        "{}{stamp}.{}",
+        resume::RECORDING_PREFIX,
+        resume::RECORDING_EXTENSION
+    ));
     path
 }
 
+/// Move recordings a crashed run left in the temp directory into the pending
+/// folder so the batch uploader retries them.
+fn recover_orphaned_recordings(previous: &resume::ResumeMarker) {
+    let temp_dir = env::temp_dir();
+    let entries: Vec<PathBuf> = fs::read_dir(&temp_dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    let orphans = resume::orphaned_recordings(previous, &temp_dir, &entries);
+    if orphans.is_empty() {
+        return;
+    }
+
+    let pending_dir = pending_recordings_dir();
+    if let Err(err) = fs::create_dir_all(&pending_dir) {
+        error!("Cannot recover recordings, pending folder unavailable: {err}");
+        return;
+    }
+    for path in orphans {
+        let Some(filename) = path.file_name() else {
+            continue;
+        };
+        // Crashed before ScreenCaptureKit wrote anything
+        if recording_file_size_bytes(&path) == Some(0) {
+            let _ = fs::remove_file(&path);
+            continue;
+        }
+        match fs::rename(&path, pending_dir.join(filename)) {
+            Ok(()) => info!("Recovered recording {} from a previous run", path.display()),
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => warn!("Failed to recover recording {}: {err}", path.display()),
+        }
+    }
+}
+
 fn legacy_pending_root_path() -> PathBuf {
     dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("/tmp"))
@@ -2208,7 +3387,17 @@ fn daemon_runtime_settings() -> &'static RuntimeDaemonSettings {
             .filter(|p| !p.as_os_str().is_empty())
             .unwrap_or_else(default_pending_root_path);
 
-        let screenshot_interval_secs = daemon.capture.screenshot_interval_secs.max(1);
+        let min_screenshot_interval_secs = daemon.capture.min_screenshot_interval_secs.max(1);
+        let max_screenshot_interval_secs = daemon
+            .capture
+            .max_screenshot_interval_secs
+            .max(min_screenshot_interval_secs);
+        let screenshot_interval_secs = daemon
+            .capture
+            .screenshot_interval_secs
+            .clamp(min_screenshot_interval_secs, max_screenshot_interval_secs);
+        let screenshot_quiet_after_secs = daemon.capture.screenshot_quiet_after_secs;
+        let scene_change_threshold = daemon.capture.scene_change_threshold.clamp(0.0, 1.0);
         let idle_threshold_secs = if daemon.capture.idle_threshold_secs > 0.0 {
             daemon.capture.idle_threshold_secs
         } else {
@@ -2242,21 +3431,86 @@ fn daemon_runtime_settings() -> &'static RuntimeDaemonSettings {
         RuntimeDaemonSettings {
             pending_root_path,
             screenshot_interval_secs,
+            adaptive_screenshot_interval: daemon.capture.adaptive_screenshot_interval,
+            min_screenshot_interval_secs,
+            max_screenshot_interval_secs,
+            screenshot_quiet_after_secs,
+            scene_change_capture: daemon.capture.scene_change_capture,
+            scene_change_threshold,
             idle_threshold_secs,
             activity_window_secs,
             burst_threshold_with_switch,
             burst_threshold_actions_only,
             auto_stop_tail_secs,
+            min_free_disk_bytes: daemon.capture.min_free_disk_bytes,
+            max_spool_bytes: daemon.capture.max_spool_bytes.max(1),
             upload_batch_interval_secs,
+            upload_max_kb_per_sec: daemon.upload.max_kb_per_sec,
+            upload_avoid_metered: daemon.upload.avoid_metered,
+            upload_transport: daemon.upload.transport,
+            upload_compression: daemon.upload.compression,
             recording_batch_max_bytes,
             recording_batch_max_files,
             recording_sample_max_frames,
             activity_flush_interval_secs,
             limits_refresh_interval_secs,
+            highlight_enabled: daemon.highlight.enabled,
+            highlight_min_recording_secs: daemon.highlight.min_recording_secs,
+            highlight_spike_window_secs: daemon.highlight.spike_window_secs.max(1),
+            highlight_spike_events: daemon.highlight.spike_events.max(1),
+            highlight_cooldown_secs: daemon.highlight.cooldown_secs,
+            pause_during_meetings: daemon.meetings.pause_capture,
+            pause_when_mirroring: daemon.meetings.pause_when_mirroring,
+            meeting_apps: daemon.meetings.extra_apps,
+            power_throttling: daemon.power.enabled,
+            battery_min_screenshot_interval_secs: daemon.power.battery_min_screenshot_interval_secs,
+            saver_min_screenshot_interval_secs: daemon.power.saver_min_screenshot_interval_secs,
+            auto_record_min_battery_percent: daemon.power.auto_record_min_battery_percent.min(100),
         }
     })
 }
 
+fn screenshot_cadence_settings() -> CadenceSettings {
+    let settings = daemon_runtime_settings();
+    CadenceSettings {
+        min_interval: Duration::from_secs(settings.min_screenshot_interval_secs),
+        base_interval: Duration::from_secs(settings.screenshot_interval_secs),
+        max_interval: Duration::from_secs(settings.max_screenshot_interval_secs),
+        quiet_after: Duration::from_secs(settings.screenshot_quiet_after_secs),
+        burst_window: Duration::from_secs(settings.activity_window_secs),
+        burst_events: settings.burst_threshold_actions_only,
+        adaptive: settings.adaptive_screenshot_interval,
+    }
+}
+
+fn highlight_settings() -> HighlightSettings {
+    let settings = daemon_runtime_settings();
+    HighlightSettings {
+        min_recording: Duration::from_secs(settings.highlight_min_recording_secs),
+        spike_window: Duration::from_secs(settings.highlight_spike_window_secs),
+        spike_events: settings.highlight_spike_events,
+        cooldown: Duration::from_secs(settings.highlight_cooldown_secs),
+    }
+}
+
+fn power_policy() -> PowerPolicy {
+    let settings = daemon_runtime_settings();
+    PowerPolicy {
+        enabled: settings.power_throttling,
+        battery_min_interval: Duration::from_secs(settings.battery_min_screenshot_interval_secs),
+        saver_min_interval: Duration::from_secs(settings.saver_min_screenshot_interval_secs),
+        auto_record_min_battery_percent: settings.auto_record_min_battery_percent,
+    }
+}
+
+fn disk_limits() -> DiskLimits {
+    let settings = daemon_runtime_settings();
+    DiskLimits {
+        min_free_bytes: settings.min_free_disk_bytes,
+        max_spool_bytes: settings.max_spool_bytes,
+    }
+}
+
 fn pending_screenshots_dir() -> PathBuf {
     daemon_runtime_settings()
         .pending_root_path
@@ -2368,8 +3622,39 @@ fn video_format_from_path(path: &Path) -> Option<VideoFormat> {
     }
 }
 
-/// Save screenshot to local pending folder (no classification, no upload)
-fn capture_screenshot_with_exclusions(privacy: &PrivacySettings) -> Result<(), CaptureError> {
+/// A captured frame that hasn't been written to disk yet
+struct ScreenFrame {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+/// Windows currently on screen, for meeting detection
+fn list_on_screen_windows() -> Result<Vec<WindowInfo>, CaptureError> {
+    let content = SCShareableContent::get().map_err(CaptureError::from)?;
+    Ok(content
+        .windows()
+        .iter()
+        .filter(|w| w.is_on_screen())
+        .map(|w| {
+            let app = w.owning_application();
+            WindowInfo {
+                app_name: app
+                    .as_ref()
+                    .map(|a| a.application_name())
+                    .unwrap_or_default(),
+                bundle_id: app
+                    .as_ref()
+                    .map(|a| a.bundle_identifier())
+                    .unwrap_or_default(),
+                title: w.title().unwrap_or_default(),
+            }
+        })
+        .collect())
+}
+
+/// Capture the main display, leaving out blocked apps and windows
+fn grab_screen_with_exclusions(privacy: &PrivacySettings) -> Result<ScreenFrame, CaptureError> {
     let content = SCShareableContent::get().map_err(CaptureError::from)?;
     let display = content
         .displays()
@@ -2406,10 +3691,16 @@ fn capture_screenshot_with_exclusions(privacy: &PrivacySettings) -> Result<(), C
 
     let image = SCScreenshotManager::capture_image(&filter, &config).map_err(CaptureError::from)?;
     let rgba = image.rgba_data().map_err(CaptureError::from)?;
-    let width = image.width() as u32;
-    let height = image.height() as u32;
+    Ok(ScreenFrame {
+        width: image.width() as u32,
+        height: image.height() as u32,
+        rgba,
+    })
+}
 
-    let png = encode_png(width, height, &rgba)?;
+/// Save screenshot to local pending folder (no classification, no upload)
+fn save_screenshot(frame: &ScreenFrame) -> Result<(), CaptureError> {
+    let png = encode_png(frame.width, frame.height, &frame.rgba)?;
 
     // Save to local pending folder
     let dir = pending_screenshots_dir();
@@ -2444,6 +3735,23 @@ struct BatchUploader {
     stop: Arc<AtomicBool>,
 }
 
+/// Set while uploads keep failing, so an outage notifies once
+static UPLOADS_FAILING: AtomicBool = AtomicBool::new(false);
+
+fn note_upload_result(ok: bool) {
+    if ok {
+        if UPLOADS_FAILING.swap(false, Ordering::Relaxed) {
+            info!("Uploads recovered");
+        }
+    } else if !UPLOADS_FAILING.swap(true, Ordering::Relaxed) {
+        notifications::notify(
+            "Upload failed — retrying",
+            "Cleo couldn't reach the server. Captures stay on this Mac and upload once it's back.",
+            Some(NotificationAction::OpenCommandPalette),
+        );
+    }
+}
+
 impl BatchUploader {
     fn start() -> Self {
         eprintln!("[DEBUG] BatchUploader::start() called");
@@ -2535,6 +3843,11 @@ impl BatchUploader {
         if cancel_flag.load(Ordering::Relaxed) {
             return;
         }
+        // Pending files stay on disk until the quota clears
+        if throttle::uploads_paused() {
+            debug!("Skipping uploads - capture quota exceeded");
+            return;
+        }
         eprintln!("[DEBUG] process_pending() called");
         // Process screenshots - batch classify then upload, processing ALL files continuously
         let screenshot_dir = pending_screenshots_dir();
@@ -2561,6 +3874,12 @@ impl BatchUploader {
             return;
         }
 
+        // Recordings are the big uploads; they wait in the spool for an unmetered network
+        if daemon_runtime_settings().upload_avoid_metered && bandwidth::is_metered() {
+            debug!("Holding recordings - on a metered connection");
+            return;
+        }
+
         // Process recordings - batch classify then upload
         let recording_dir = pending_recordings_dir();
         if let Ok(entries) = fs::read_dir(&recording_dir) {
@@ -2589,6 +3908,16 @@ impl BatchUploader {
                         info!("Cancelling recording chunk processing");
                         return;
                     }
+                    if throttle::uploads_paused() {
+                        info!("Capture quota exceeded; holding remaining recording chunks");
+                        return;
+                    }
+                    if daemon_runtime_settings().upload_avoid_metered && bandwidth::is_metered() {
+                        info!(
+                            "Switched to a metered connection; holding remaining recording chunks"
+                        );
+                        return;
+                    }
 
                     let chunk_bytes: u64 = chunk
                         .iter()
@@ -2792,6 +4121,10 @@ impl BatchUploader {
                             result.uploaded, result.failed
                         );
                         total_processed += result.uploaded;
+                        if result.uploaded > 0 {
+                            resume::record_capture_upload();
+                            note_upload_result(true);
+                        }
                         // Delete only files confirmed as successfully uploaded.
                         if result.failed == 0 {
                             for path in &uploaded_paths {
@@ -2832,6 +4165,10 @@ impl BatchUploader {
                             e
                         );
                         error!("Batch upload failed: {}", e);
+                        if matches!(e, ApiError::QuotaExceeded { .. }) {
+                            return;
+                        }
+                        note_upload_result(false);
                     }
                 }
             }
@@ -3004,6 +4341,10 @@ impl BatchUploader {
                         "Batch upload complete: {} uploaded, {} failed",
                         result.uploaded, result.failed
                     );
+                    if result.uploaded > 0 {
+                        resume::record_capture_upload();
+                        note_upload_result(true);
+                    }
                     // Delete only files confirmed as successfully uploaded.
                     if result.failed == 0 {
                         for path in &uploaded_paths {
@@ -3044,6 +4385,9 @@ impl BatchUploader {
                         e
                     );
                     error!("Batch upload failed: {}", e);
+                    if !matches!(e, ApiError::QuotaExceeded { .. }) {
+                        note_upload_result(false);
+                    }
                 }
             }
         }
@@ -0,0 +1,248 @@
+//! Native macOS notifications through the UserNotifications framework.
+//!
+//! The daemon runs as an accessory app, where the deprecated
+//! NSUserNotificationCenter returns nil, so this uses UNUserNotificationCenter.
+//! That needs the app bundle (it raises outside one, e.g. under `cargo run`)
+//! and the user's permission, asked for on first launch; without either,
+//! notifications only reach the log.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use block2::{Block, RcBlock};
+use log::info;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Bool, ClassBuilder, Sel};
+use objc2::{ClassType, msg_send, sel};
+use objc2_foundation::{NSBundle, NSObject, NSString};
+
+#[link(name = "UserNotifications", kind = "framework")]
+unsafe extern "C" {}
+
+// UNAuthorizationOptions
+const AUTHORIZATION_SOUND: usize = 1 << 1;
+const AUTHORIZATION_ALERT: usize = 1 << 2;
+
+// UNNotificationPresentationOptions
+const PRESENT_SOUND: usize = 1 << 1;
+const PRESENT_LIST: usize = 1 << 3;
+const PRESENT_BANNER: usize = 1 << 4;
+
+/// Click actions kept for notifications still on screen; older ones are
+/// dropped first
+const MAX_PENDING_ACTIONS: usize = 50;
+
+/// What clicking a notification does
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationAction {
+    /// Open a web page, e.g. the drafts in the web app
+    OpenUrl(String),
+    OpenCommandPalette,
+}
+
+/// Called with the action of a clicked notification, on whatever thread
+/// UserNotifications delivers it
+pub type ClickHandler = Box<dyn Fn(NotificationAction) + Send + Sync + 'static>;
+
+static CLICK_HANDLER: OnceLock<ClickHandler> = OnceLock::new();
+static PENDING_ACTIONS: Mutex<Vec<(String, NotificationAction)>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+fn remember_action(
+    actions: &mut Vec<(String, NotificationAction)>,
+    id: String,
+    action: NotificationAction,
+) {
+    if actions.len() >= MAX_PENDING_ACTIONS {
+        actions.remove(0);
+    }
+    actions.push((id, action));
+}
+
+fn take_action(
+    actions: &mut Vec<(String, NotificationAction)>,
+    id: &str,
+) -> Option<NotificationAction> {
+    let idx = actions.iter().position(|(pending, _)| pending == id)?;
+    Some(actions.remove(idx).1)
+}
+
+/// The shared notification center, or None when not running from an app bundle
+fn notification_center() -> Option<Retained<AnyObject>> {
+    unsafe { NSBundle::mainBundle().bundleIdentifier() }?;
+    let cls = AnyClass::get(c"UNUserNotificationCenter")?;
+    let center: *mut AnyObject = unsafe { msg_send![cls, currentNotificationCenter] };
+    unsafe { Retained::retain(center) }
+}
+
+/// Delegate that shows banners while the daemon is active and runs the click action
+fn delegate_class() -> &'static AnyClass {
+    static CLASS: OnceLock<&'static AnyClass> = OnceLock::new();
+    CLASS.get_or_init(|| {
+        let superclass = NSObject::class();
+        let mut builder = ClassBuilder::new(c"CleoNotificationDelegate", superclass)
+            .expect("Failed to create NotificationDelegate class");
+
+        unsafe extern "C" fn will_present(
+            _this: *mut AnyObject,
+            _sel: Sel,
+            _center: *mut AnyObject,
+            _notification: *mut AnyObject,
+            completion: *mut Block<dyn Fn(usize)>,
+        ) {
+            if let Some(completion) = unsafe { completion.as_ref() } {
+                completion.call((PRESENT_BANNER | PRESENT_LIST | PRESENT_SOUND,));
+            }
+        }
+
+        unsafe extern "C" fn did_receive(
+            _this: *mut AnyObject,
+            _sel: Sel,
+            _center: *mut AnyObject,
+            response: *mut AnyObject,
+            completion: *mut Block<dyn Fn()>,
+        ) {
+            let identifier = unsafe {
+                let notification: *mut AnyObject = msg_send![response, notification];
+                let request: *mut AnyObject = msg_send![notification, request];
+                let identifier: *mut NSString = msg_send![request, identifier];
+                identifier.as_ref().map(|id| id.to_string())
+            };
+            let action = identifier.and_then(|id| {
+                let mut actions = PENDING_ACTIONS.lock().unwrap_or_else(|e| e.into_inner());
+                take_action(&mut actions, &id)
+            });
+            if let Some(action) = action
+                && let Some(handler) = CLICK_HANDLER.get()
+            {
+                handler(action);
+            }
+            if let Some(completion) = unsafe { completion.as_ref() } {
+                completion.call(());
+            }
+        }
+
+        unsafe {
+            builder.add_method(
+                sel!(userNotificationCenter:willPresentNotification:withCompletionHandler:),
+                will_present
+                    as unsafe extern "C" fn(
+                        *mut AnyObject,
+                        Sel,
+                        *mut AnyObject,
+                        *mut AnyObject,
+                        *mut Block<dyn Fn(usize)>,
+                    ),
+            );
+            builder.add_method(
+                sel!(userNotificationCenter:didReceiveNotificationResponse:withCompletionHandler:),
+                did_receive
+                    as unsafe extern "C" fn(
+                        *mut AnyObject,
+                        Sel,
+                        *mut AnyObject,
+                        *mut AnyObject,
+                        *mut Block<dyn Fn()>,
+                    ),
+            );
+        }
+
+        builder.register()
+    })
+}
+
+/// Install the click handler and delegate, and ask for permission to notify.
+/// macOS only prompts the first time.
+pub fn init(on_click: ClickHandler) {
+    let _ = CLICK_HANDLER.set(on_click);
+    let Some(center) = notification_center() else {
+        info!("Not running from an app bundle; notifications go to the log only");
+        return;
+    };
+
+    // The center holds its delegate weakly; this one is never released
+    let delegate: *mut AnyObject = unsafe { msg_send![delegate_class(), new] };
+    let _: () = unsafe { msg_send![&*center, setDelegate: delegate] };
+
+    let on_reply = RcBlock::new(|granted: Bool, _error: *mut AnyObject| {
+        if !granted.as_bool() {
+            info!("Notifications not allowed; they go to the log only");
+        }
+    });
+    let _: () = unsafe {
+        msg_send![
+            &*center,
+            requestAuthorizationWithOptions: AUTHORIZATION_ALERT | AUTHORIZATION_SOUND,
+            completionHandler: &*on_reply
+        ]
+    };
+}
+
+/// Post a notification; `action` runs when the user clicks it. Safe to call
+/// from any thread.
+pub fn notify(title: &str, body: &str, action: Option<NotificationAction>) {
+    info!("[notification] {title}: {body}");
+    let Some(center) = notification_center() else {
+        return;
+    };
+    let (Some(content_cls), Some(request_cls)) = (
+        AnyClass::get(c"UNMutableNotificationContent"),
+        AnyClass::get(c"UNNotificationRequest"),
+    ) else {
+        return;
+    };
+
+    let id = format!("cleo-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    if let Some(action) = action {
+        let mut actions = PENDING_ACTIONS.lock().unwrap_or_else(|e| e.into_inner());
+        remember_action(&mut actions, id.clone(), action);
+    }
+
+    unsafe {
+        let content: *mut AnyObject = msg_send![content_cls, new];
+        let Some(content) = Retained::from_raw(content) else {
+            return;
+        };
+        let _: () = msg_send![&*content, setTitle: &*NSString::from_str(title)];
+        let _: () = msg_send![&*content, setBody: &*NSString::from_str(body)];
+
+        let request: *mut AnyObject = msg_send![
+            request_cls,
+            requestWithIdentifier: &*NSString::from_str(&id),
+            content: &*content,
+            trigger: None::<&AnyObject>
+        ];
+        if request.is_null() {
+            return;
+        }
+        let _: () = msg_send![
+            &*center,
+            addNotificationRequest: request,
+            withCompletionHandler: None::<&Block<dyn Fn(*mut AnyObject)>>
+        ];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn click_actions_are_taken_once_and_bounded() {
+        let mut actions = Vec::new();
+        for i in 0..MAX_PENDING_ACTIONS + 1 {
+            remember_action(
+                &mut actions,
+                format!("cleo-{i}"),
+                NotificationAction::OpenCommandPalette,
+            );
+        }
+        assert_eq!(actions.len(), MAX_PENDING_ACTIONS);
+        assert_eq!(take_action(&mut actions, "cleo-0"), None);
+        assert_eq!(
+            take_action(&mut actions, "cleo-1"),
+            Some(NotificationAction::OpenCommandPalette)
+        );
+        assert_eq!(take_action(&mut actions, "cleo-1"), None);
+    }
+}
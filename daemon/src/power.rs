@@ -0,0 +1,245 @@
+//! Power-aware capture throttling.
+//!
+//! ScreenCaptureKit keeps the GPU and video encoder busy, which shows on a
+//! laptop's battery. On battery the daemon takes screenshots less often and
+//! stops starting recordings on its own once the charge runs low. Low Power
+//! Mode and thermal pressure slow screenshots further and turn auto-recording
+//! off until they clear.
+
+use std::process::Command;
+use std::time::Duration;
+
+use objc2::msg_send;
+use objc2::runtime::{AnyClass, AnyObject, Bool};
+
+/// How often the power source and thermal state are read
+pub const CHECK_INTERVAL_SECS: u64 = 60;
+pub const BATTERY_MIN_INTERVAL_SECS_DEFAULT: u64 = 15;
+pub const SAVER_MIN_INTERVAL_SECS_DEFAULT: u64 = 30;
+pub const AUTO_RECORD_MIN_BATTERY_PERCENT_DEFAULT: u8 = 30;
+
+/// NSProcessInfoThermalState
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Thermal {
+    Nominal,
+    Fair,
+    Serious,
+    Critical,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PowerState {
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>,
+    pub low_power_mode: bool,
+    pub thermal: Thermal,
+}
+
+impl Default for PowerState {
+    fn default() -> Self {
+        Self {
+            on_battery: false,
+            battery_percent: None,
+            low_power_mode: false,
+            thermal: Thermal::Nominal,
+        }
+    }
+}
+
+impl PowerState {
+    /// Short description for the log, e.g. "on battery (42%), Low Power Mode"
+    pub fn describe(&self) -> String {
+        let mut parts = vec![match (self.on_battery, self.battery_percent) {
+            (true, Some(percent)) => format!("on battery ({percent}%)"),
+            (true, None) => "on battery".to_string(),
+            (false, _) => "on AC power".to_string(),
+        }];
+        if self.low_power_mode {
+            parts.push("Low Power Mode".to_string());
+        }
+        if self.thermal >= Thermal::Serious {
+            parts.push(format!("thermal state {:?}", self.thermal));
+        }
+        parts.join(", ")
+    }
+}
+
+/// When to slow down, from `daemon.power` in the Cleo config
+#[derive(Clone, Copy, Debug)]
+pub struct PowerPolicy {
+    pub enabled: bool,
+    /// Shortest screenshot interval on battery
+    pub battery_min_interval: Duration,
+    /// Shortest screenshot interval in Low Power Mode or under serious thermal pressure
+    pub saver_min_interval: Duration,
+    /// On battery below this charge, activity bursts don't start recordings
+    pub auto_record_min_battery_percent: u8,
+}
+
+/// What the power state allows right now
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PowerThrottle {
+    /// No screenshot interval shorter than this
+    pub min_interval: Option<Duration>,
+    /// Whether activity bursts may start a recording
+    pub auto_record: bool,
+}
+
+impl PowerThrottle {
+    pub const NONE: Self = Self {
+        min_interval: None,
+        auto_record: true,
+    };
+}
+
+/// Apply the policy to a power state
+pub fn evaluate(policy: PowerPolicy, state: PowerState) -> PowerThrottle {
+    if !policy.enabled {
+        return PowerThrottle::NONE;
+    }
+    let saver = state.low_power_mode || state.thermal >= Thermal::Serious;
+    let min_interval = if saver {
+        Some(policy.saver_min_interval)
+    } else if state.on_battery {
+        Some(policy.battery_min_interval)
+    } else {
+        None
+    };
+    let low_battery = state.on_battery
+        && state
+            .battery_percent
+            .is_some_and(|percent| percent < policy.auto_record_min_battery_percent);
+    PowerThrottle {
+        min_interval,
+        auto_record: !saver && !low_battery,
+    }
+}
+
+/// Read the power source, Low Power Mode and thermal state. Safe to call
+/// from any thread; anything unreadable counts as AC power and nominal.
+pub fn read_state() -> PowerState {
+    let (on_battery, battery_percent) = Command::new("/usr/bin/pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_pmset_batt(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or((false, None));
+
+    let (low_power_mode, thermal) = match AnyClass::get(c"NSProcessInfo") {
+        Some(cls) => unsafe {
+            let info: *mut AnyObject = msg_send![cls, processInfo];
+            let low_power: Bool = msg_send![info, isLowPowerModeEnabled];
+            let thermal: isize = msg_send![info, thermalState];
+            (low_power.as_bool(), thermal_from_raw(thermal))
+        },
+        None => (false, Thermal::Nominal),
+    };
+
+    PowerState {
+        on_battery,
+        battery_percent,
+        low_power_mode,
+        thermal,
+    }
+}
+
+fn thermal_from_raw(raw: isize) -> Thermal {
+    match raw {
+        1 => Thermal::Fair,
+        2 => Thermal::Serious,
+        3 => Thermal::Critical,
+        _ => Thermal::Nominal,
+    }
+}
+
+/// Power source and internal battery charge from `pmset -g batt`, e.g.
+/// "Now drawing from 'Battery Power'" followed by a line with "85%;"
+pub fn parse_pmset_batt(output: &str) -> (bool, Option<u8>) {
+    let on_battery = output
+        .lines()
+        .next()
+        .is_some_and(|line| line.contains("'Battery Power'"));
+    let percent = output
+        .lines()
+        .filter(|line| line.contains("InternalBattery"))
+        .find_map(|line| {
+            let end = line.find('%')?;
+            let start = line[..end]
+                .rfind(|c: char| !c.is_ascii_digit())
+                .map_or(0, |i| i + 1);
+            line[start..end].parse::<u8>().ok()
+        });
+    (on_battery, percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> PowerPolicy {
+        PowerPolicy {
+            enabled: true,
+            battery_min_interval: Duration::from_secs(15),
+            saver_min_interval: Duration::from_secs(30),
+            auto_record_min_battery_percent: 30,
+        }
+    }
+
+    #[test]
+    fn pmset_output_gives_source_and_charge() {
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t42%; discharging; 3:10 remaining present: true\n";
+        assert_eq!(parse_pmset_batt(battery), (true, Some(42)));
+        let charging = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true\n";
+        assert_eq!(parse_pmset_batt(charging), (false, Some(100)));
+        assert_eq!(
+            parse_pmset_batt("Now drawing from 'AC Power'\n"),
+            (false, None)
+        );
+    }
+
+    #[test]
+    fn battery_and_pressure_slow_capture() {
+        assert_eq!(
+            evaluate(policy(), PowerState::default()),
+            PowerThrottle::NONE
+        );
+
+        let battery = PowerState {
+            on_battery: true,
+            battery_percent: Some(80),
+            ..PowerState::default()
+        };
+        assert_eq!(
+            evaluate(policy(), battery),
+            PowerThrottle {
+                min_interval: Some(Duration::from_secs(15)),
+                auto_record: true,
+            }
+        );
+
+        let low = PowerState {
+            battery_percent: Some(20),
+            ..battery
+        };
+        assert!(!evaluate(policy(), low).auto_record);
+
+        let hot = PowerState {
+            thermal: Thermal::Serious,
+            ..PowerState::default()
+        };
+        assert_eq!(
+            evaluate(policy(), hot),
+            PowerThrottle {
+                min_interval: Some(Duration::from_secs(30)),
+                auto_record: false,
+            }
+        );
+
+        let disabled = PowerPolicy {
+            enabled: false,
+            ..policy()
+        };
+        assert_eq!(evaluate(disabled, low), PowerThrottle::NONE);
+    }
+}
@@ -0,0 +1,251 @@
+//! Meeting and screen-share detection.
+//!
+//! Screenshots and recordings taken while the user is on a call or sharing
+//! their screen capture other people's faces and whatever is being presented,
+//! so the daemon pauses capturing while one is detected. Detection looks at the
+//! frontmost app and the windows on screen: a known conferencing app in front,
+//! a call window (Zoom meeting, a Meet tab), a "sharing your screen" indicator
+//! or a slide show. Mirroring the screen to another display counts too, since
+//! that's usually a projector or a TV in a meeting room.
+
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+    fn CGGetOnlineDisplayList(max_displays: u32, displays: *mut u32, count: *mut u32) -> i32;
+    fn CGDisplayIsInMirrorSet(display: u32) -> i32;
+}
+
+/// Most displays asked for from CoreGraphics
+const MAX_DISPLAYS: usize = 16;
+
+/// How often the on-screen windows are checked for a meeting
+pub const CHECK_INTERVAL_SECS: u64 = 5;
+
+/// Conferencing apps by name and bundle ID; having one in front counts as a call
+const CONFERENCING_APPS: &[(&str, &str)] = &[
+    ("zoom.us", "us.zoom.xos"),
+    ("Microsoft Teams", "com.microsoft.teams2"),
+    ("Microsoft Teams classic", "com.microsoft.teams"),
+    ("Webex", "com.cisco.webexmeetingsapp"),
+    ("FaceTime", "com.apple.FaceTime"),
+    (
+        "Google Meet",
+        "com.google.Chrome.app.kjgfgldnnfoeklkmfkjfagphfepbbdan",
+    ),
+];
+
+/// Window title prefixes of a call in progress, lowercased
+const CALL_WINDOW_PREFIXES: &[&str] = &["zoom meeting", "zoom webinar", "meet - "];
+
+/// Parts of the titles of the indicators apps show while sharing the screen, lowercased
+const SHARE_INDICATORS: &[&str] = &[
+    "is sharing your screen",
+    "is sharing a window",
+    "is sharing this tab",
+    "zoom share statusbar window",
+    "zoom share toolbar window",
+    "sharing control bar",
+];
+
+/// Window title prefixes of a slide show, lowercased
+const PRESENTATION_PREFIXES: &[&str] = &["powerpoint slide show", "powerpoint presenter view"];
+
+/// An on-screen window, as listed by ScreenCaptureKit
+#[derive(Clone, Debug, Default)]
+pub struct WindowInfo {
+    pub app_name: String,
+    pub bundle_id: String,
+    pub title: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MeetingKind {
+    Call,
+    ScreenShare,
+    Presentation,
+    /// The screen is mirrored to another display
+    Mirroring,
+}
+
+/// A detected meeting and the app it was seen in (empty for a mirrored display)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Meeting {
+    pub kind: MeetingKind,
+    pub app: String,
+}
+
+impl Meeting {
+    /// Why capturing is paused, for notifications and the status menu
+    pub fn message(&self) -> String {
+        match self.kind {
+            MeetingKind::Call => format!("On a call in {}", self.app),
+            MeetingKind::ScreenShare => format!("Sharing the screen in {}", self.app),
+            MeetingKind::Presentation => format!("Presenting in {}", self.app),
+            MeetingKind::Mirroring => "Mirroring the screen to another display".to_string(),
+        }
+    }
+}
+
+fn is_conferencing_app(app_name: &str, bundle_id: &str, extra_apps: &[String]) -> bool {
+    let known = CONFERENCING_APPS.iter().any(|(name, bundle)| {
+        app_name.eq_ignore_ascii_case(name) || bundle_id.eq_ignore_ascii_case(bundle)
+    });
+    known
+        || extra_apps.iter().any(|extra| {
+            app_name.eq_ignore_ascii_case(extra) || bundle_id.eq_ignore_ascii_case(extra)
+        })
+}
+
+/// Whether any online display is part of a mirror set, e.g. the screen is
+/// mirrored to a projector. False if the display list can't be read.
+pub fn is_mirroring() -> bool {
+    let mut displays = [0u32; MAX_DISPLAYS];
+    let mut count = 0u32;
+    let err =
+        unsafe { CGGetOnlineDisplayList(MAX_DISPLAYS as u32, displays.as_mut_ptr(), &mut count) };
+    if err != 0 {
+        return false;
+    }
+    displays[..(count as usize).min(MAX_DISPLAYS)]
+        .iter()
+        .any(|&display| unsafe { CGDisplayIsInMirrorSet(display) } != 0)
+}
+
+/// Look for a meeting in the frontmost app, the on-screen windows and
+/// whether the screen is `mirrored`. `extra_apps` are conferencing apps the
+/// user added by name or bundle ID. Screen sharing wins over mirroring, then
+/// a slide show, then a plain call.
+pub fn detect(
+    foreground_app: Option<&str>,
+    windows: &[WindowInfo],
+    mirrored: bool,
+    extra_apps: &[String],
+) -> Option<Meeting> {
+    let titled = |prefixes: &[&str], contains: &[&str]| {
+        windows.iter().find_map(|window| {
+            let title = window.title.to_lowercase();
+            let matched = prefixes.iter().any(|p| title.starts_with(p))
+                || contains.iter().any(|c| title.contains(c));
+            matched.then(|| window.app_name.clone())
+        })
+    };
+
+    if let Some(app) = titled(&[], SHARE_INDICATORS) {
+        return Some(Meeting {
+            kind: MeetingKind::ScreenShare,
+            app,
+        });
+    }
+    if mirrored {
+        return Some(Meeting {
+            kind: MeetingKind::Mirroring,
+            app: String::new(),
+        });
+    }
+    if let Some(app) = titled(PRESENTATION_PREFIXES, &[]) {
+        return Some(Meeting {
+            kind: MeetingKind::Presentation,
+            app,
+        });
+    }
+    if let Some(app) = titled(CALL_WINDOW_PREFIXES, &[]) {
+        return Some(Meeting {
+            kind: MeetingKind::Call,
+            app,
+        });
+    }
+    // The bundle ID of the frontmost app comes from its windows, if it has any
+    let app = foreground_app?;
+    let bundle_id = windows
+        .iter()
+        .find(|w| w.app_name == app)
+        .map_or("", |w| w.bundle_id.as_str());
+    is_conferencing_app(app, bundle_id, extra_apps).then(|| Meeting {
+        kind: MeetingKind::Call,
+        app: app.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(app_name: &str, bundle_id: &str, title: &str) -> WindowInfo {
+        WindowInfo {
+            app_name: app_name.to_string(),
+            bundle_id: bundle_id.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn detects_calls_shares_and_slide_shows() {
+        let editor = window("Code", "com.microsoft.VSCode", "main.rs — cleo");
+        assert_eq!(
+            detect(Some("Code"), std::slice::from_ref(&editor), false, &[]),
+            None
+        );
+
+        let meet = window("Google Chrome", "com.google.Chrome", "Meet - abc-defg-hij");
+        assert_eq!(
+            detect(Some("Code"), &[editor.clone(), meet.clone()], false, &[]),
+            Some(Meeting {
+                kind: MeetingKind::Call,
+                app: "Google Chrome".to_string(),
+            })
+        );
+
+        let indicator = window(
+            "Google Chrome",
+            "com.google.Chrome",
+            "meet.google.com is sharing your screen.",
+        );
+        assert_eq!(
+            detect(Some("Code"), &[meet, indicator], false, &[])
+                .unwrap()
+                .kind,
+            MeetingKind::ScreenShare
+        );
+
+        let slides = window(
+            "Microsoft PowerPoint",
+            "com.microsoft.Powerpoint",
+            "PowerPoint Slide Show - Roadmap.pptx",
+        );
+        assert_eq!(
+            detect(None, std::slice::from_ref(&slides), false, &[])
+                .unwrap()
+                .message(),
+            "Presenting in Microsoft PowerPoint"
+        );
+        assert_eq!(
+            detect(None, &[slides], true, &[]).unwrap().kind,
+            MeetingKind::Mirroring
+        );
+    }
+
+    #[test]
+    fn conferencing_apps_count_only_in_front() {
+        let zoom_home = window("zoom.us", "us.zoom.xos", "Zoom Workplace");
+        assert_eq!(
+            detect(Some("Code"), std::slice::from_ref(&zoom_home), false, &[]),
+            None
+        );
+        assert_eq!(
+            detect(Some("zoom.us"), &[zoom_home], false, &[])
+                .unwrap()
+                .kind,
+            MeetingKind::Call
+        );
+
+        let tuple = window("Tuple", "app.tuple.app", "Tuple");
+        assert_eq!(
+            detect(Some("Tuple"), std::slice::from_ref(&tuple), false, &[]),
+            None
+        );
+        let extra = vec!["app.tuple.app".to_string()];
+        assert_eq!(
+            detect(Some("Tuple"), &[tuple], false, &extra).unwrap().app,
+            "Tuple"
+        );
+    }
+}
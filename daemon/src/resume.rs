@@ -0,0 +1,242 @@
+//! Resume marker: a small state file that survives a crash.
+//!
+//! It records the newest activity event the API accepted, activity still
+//! waiting to be sent, and recordings being written to the temp directory.
+//! On launch the daemon re-queues the unsent activity and moves orphaned
+//! recordings into the pending folder so the batch uploader picks them up.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::api::ActivityEntry;
+
+/// File name of the marker inside the pending captures root.
+pub const RESUME_FILE: &str = "resume.json";
+
+/// Prefix and extension of recordings spooled to the temp directory.
+pub const RECORDING_PREFIX: &str = "cleo-recording-";
+pub const RECORDING_EXTENSION: &str = "mp4";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResumeMarker {
+    /// Interval of the newest activity event the API accepted
+    #[serde(default)]
+    pub last_uploaded_interval: Option<u64>,
+    /// Timestamp of that event; anything at or before it was sent
+    #[serde(default)]
+    pub last_uploaded_at: Option<DateTime<Utc>>,
+    /// When a screenshot or recording batch last uploaded
+    #[serde(default)]
+    pub last_capture_upload_at: Option<DateTime<Utc>>,
+    /// Recordings still being written in the temp directory
+    #[serde(default)]
+    pub pending_recordings: Vec<PathBuf>,
+    /// Activity buffered at the last flush that the API hadn't accepted
+    #[serde(default)]
+    pub unsent_activity: Vec<ActivityEntry>,
+}
+
+impl ResumeMarker {
+    /// Advance the upload mark past `events` and drop them from the unsent list.
+    fn mark_uploaded(&mut self, events: &[ActivityEntry]) {
+        if let Some(newest) = events.iter().max_by_key(|e| e.timestamp)
+            && self.last_uploaded_at.is_none_or(|at| newest.timestamp > at)
+        {
+            self.last_uploaded_at = Some(newest.timestamp);
+            self.last_uploaded_interval = Some(newest.interval_id);
+        }
+        self.unsent_activity = unsent_after(&self.unsent_activity, self.last_uploaded_at);
+    }
+}
+
+struct Store {
+    path: Option<PathBuf>,
+    marker: ResumeMarker,
+}
+
+static STORE: Mutex<Store> = Mutex::new(Store {
+    path: None,
+    marker: ResumeMarker {
+        last_uploaded_interval: None,
+        last_uploaded_at: None,
+        last_capture_upload_at: None,
+        pending_recordings: Vec::new(),
+        unsent_activity: Vec::new(),
+    },
+});
+
+/// Update the marker and write it back; no-op until `load` has been called.
+fn update(f: impl FnOnce(&mut ResumeMarker)) {
+    let mut store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut store.marker);
+    if let Some(path) = store.path.as_deref()
+        && let Err(err) = write_marker(path, &store.marker)
+    {
+        error!("Failed to save resume marker {}: {err}", path.display());
+    }
+}
+
+/// Write to a sibling file and rename, so a crash mid-write keeps the old marker.
+fn write_marker(path: &Path, marker: &ResumeMarker) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let payload = serde_json::to_vec(marker).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, payload)?;
+    fs::rename(&tmp, path)
+}
+
+/// Read the marker left by the previous run and keep writing to `path`.
+/// Returns what the previous run left behind; a missing or unreadable file
+/// counts as a clean start.
+pub fn load(path: PathBuf) -> ResumeMarker {
+    let previous = match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+            warn!(
+                "Ignoring unreadable resume marker {}: {err}",
+                path.display()
+            );
+            ResumeMarker::default()
+        }),
+        Err(_) => ResumeMarker::default(),
+    };
+
+    let mut store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    store.marker = ResumeMarker {
+        last_uploaded_interval: previous.last_uploaded_interval,
+        last_uploaded_at: previous.last_uploaded_at,
+        last_capture_upload_at: previous.last_capture_upload_at,
+        ..ResumeMarker::default()
+    };
+    store.path = Some(path);
+    previous
+}
+
+/// The API accepted these activity events.
+pub fn record_activity_uploaded(events: &[ActivityEntry]) {
+    update(|m| m.mark_uploaded(events));
+}
+
+/// Activity still buffered after a flush attempt.
+pub fn record_unsent_activity(events: &[ActivityEntry]) {
+    update(|m| m.unsent_activity = unsent_after(events, m.last_uploaded_at));
+}
+
+/// A screenshot or recording batch uploaded.
+pub fn record_capture_upload() {
+    update(|m| m.last_capture_upload_at = Some(Utc::now()));
+}
+
+/// A recording started writing to `path`.
+pub fn recording_started(path: &Path) {
+    update(|m| m.pending_recordings.push(path.to_path_buf()));
+}
+
+/// The recording at `path` was moved to the pending folder or discarded.
+pub fn recording_finished(path: &Path) {
+    update(|m| m.pending_recordings.retain(|p| p != path));
+}
+
+/// Events newer than the upload mark; everything else already reached the API.
+pub fn unsent_after(
+    events: &[ActivityEntry],
+    last_uploaded_at: Option<DateTime<Utc>>,
+) -> Vec<ActivityEntry> {
+    events
+        .iter()
+        .filter(|e| last_uploaded_at.is_none_or(|at| e.timestamp > at))
+        .cloned()
+        .collect()
+}
+
+/// Whether `path` looks like a recording the daemon spooled to the temp directory.
+pub fn is_spooled_recording(path: &Path) -> bool {
+    let named = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with(RECORDING_PREFIX));
+    let mp4 = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(RECORDING_EXTENSION));
+    named && mp4
+}
+
+/// Recordings a crashed run left in `temp_dir`: those the marker lists plus
+/// any other spooled recording there. Doesn't check that the files exist.
+pub fn orphaned_recordings(
+    marker: &ResumeMarker,
+    temp_dir: &Path,
+    temp_entries: &[PathBuf],
+) -> Vec<PathBuf> {
+    let mut orphans: Vec<PathBuf> = marker
+        .pending_recordings
+        .iter()
+        .filter(|p| p.parent() == Some(temp_dir))
+        .cloned()
+        .collect();
+    for path in temp_entries {
+        if is_spooled_recording(path) && !orphans.contains(path) {
+            orphans.push(path.clone());
+        }
+    }
+    orphans.sort();
+    orphans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ActivityEvent;
+
+    fn entry(secs: i64, interval_id: u64) -> ActivityEntry {
+        ActivityEntry::new(
+            DateTime::from_timestamp(secs, 0).unwrap(),
+            interval_id,
+            ActivityEvent::foreground_switch("Code", "main.rs — cleo"),
+        )
+    }
+
+    #[test]
+    fn uploads_advance_the_mark_and_clear_sent_events() {
+        let mut marker = ResumeMarker {
+            unsent_activity: vec![entry(100, 1), entry(200, 1), entry(300, 2)],
+            ..ResumeMarker::default()
+        };
+        marker.mark_uploaded(&[entry(100, 1), entry(200, 1)]);
+        assert_eq!(marker.last_uploaded_interval, Some(1));
+        assert_eq!(marker.unsent_activity.len(), 1);
+        assert_eq!(marker.unsent_activity[0].interval_id, 2);
+
+        // An older batch finishing late doesn't move the mark back
+        marker.mark_uploaded(&[entry(50, 0)]);
+        assert_eq!(marker.last_uploaded_at, DateTime::from_timestamp(200, 0));
+    }
+
+    #[test]
+    fn orphaned_recordings_come_from_the_marker_and_the_temp_dir() {
+        let temp = Path::new("/tmp/cleo");
+        let listed = temp.join("cleo-recording-20260302-090000.mp4");
+        let stray = temp.join("cleo-recording-20260302-091500.mp4");
+        let marker = ResumeMarker {
+            pending_recordings: vec![listed.clone(), PathBuf::from("/elsewhere/x.mp4")],
+            ..ResumeMarker::default()
+        };
+        let entries = vec![
+            listed.clone(),
+            stray.clone(),
+            temp.join("cleo-recording-20260302-091500.mov"),
+            temp.join("notes.mp4"),
+        ];
+        assert_eq!(
+            orphaned_recordings(&marker, temp, &entries),
+            vec![listed, stray]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+/// Side length of the luma grid frames are reduced to before comparing
+const GRID: usize = 64;
+/// Minimum brightness change (0-255) for a grid cell to count as changed
+const CELL_DELTA: u8 = 12;
+
+/// A frame reduced to a `GRID` x `GRID` grid of average brightness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    cells: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Downscales an RGBA frame by averaging the luma of each grid block.
+    pub fn from_rgba(width: u32, height: u32, rgba: &[u8]) -> Self {
+        let (width, height) = (width as usize, height as usize);
+        let mut sums = vec![0u64; GRID * GRID];
+        let mut counts = vec![0u64; GRID * GRID];
+
+        for (y, row) in rgba.chunks_exact(width * 4).take(height).enumerate() {
+            let gy = y * GRID / height.max(1);
+            for (x, px) in row.chunks_exact(4).enumerate() {
+                let gx = x * GRID / width.max(1);
+                // Integer Rec. 601 luma
+                let luma = (299 * px[0] as u64 + 587 * px[1] as u64 + 114 * px[2] as u64) / 1000;
+                sums[gy * GRID + gx] += luma;
+                counts[gy * GRID + gx] += 1;
+            }
+        }
+
+        let cells = sums
+            .iter()
+            .zip(&counts)
+            .map(|(&sum, &count)| (sum / count.max(1)) as u8)
+            .collect();
+        Self { cells }
+    }
+
+    /// Fraction of grid cells (0.0-1.0) whose brightness differs noticeably.
+    pub fn difference(&self, other: &Thumbnail) -> f64 {
+        let changed = self
+            .cells
+            .iter()
+            .zip(&other.cells)
+            .filter(|(a, b)| a.abs_diff(**b) >= CELL_DELTA)
+            .count();
+        changed as f64 / self.cells.len().max(1) as f64
+    }
+}
+
+/// Tracks the last saved screenshot so only frames that meaningfully differ
+/// from it are kept.
+///
+/// A cursor blink or clock tick touches a cell or two; switching documents,
+/// scrolling or typing a line moves well past `threshold`.
+#[derive(Debug)]
+pub struct SceneDetector {
+    threshold: f64,
+    reference: Option<Thumbnail>,
+}
+
+impl SceneDetector {
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            reference: None,
+        }
+    }
+
+    /// Returns true if `frame` differs enough from the last saved screenshot.
+    pub fn is_changed(&self, frame: &Thumbnail) -> bool {
+        match &self.reference {
+            Some(reference) => reference.difference(frame) >= self.threshold,
+            None => true,
+        }
+    }
+
+    /// Makes `frame` the screenshot later frames are compared against.
+    pub fn set_reference(&mut self, frame: Thumbnail) {
+        self.reference = Some(frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> Vec<u8> {
+        [value, value, value, 255].repeat((width * height) as usize)
+    }
+
+    #[test]
+    fn detects_only_meaningful_changes() {
+        let (width, height) = (256, 128);
+        let base = solid(width, height, 40);
+        let mut detector = SceneDetector::new(0.01);
+
+        let first = Thumbnail::from_rgba(width, height, &base);
+        assert!(detector.is_changed(&first), "first frame is always new");
+        detector.set_reference(first);
+
+        // A single bright pixel (e.g. a cursor) stays under the threshold
+        let mut cursor = base.clone();
+        cursor[..4].copy_from_slice(&[255, 255, 255, 255]);
+        assert!(!detector.is_changed(&Thumbnail::from_rgba(width, height, &cursor)));
+
+        // Repainting the top quarter of the screen is a scene change
+        let mut repainted = base.clone();
+        let quarter = (width * height) as usize;
+        for px in repainted[..quarter].chunks_exact_mut(4) {
+            px.copy_from_slice(&[220, 220, 220, 255]);
+        }
+        let frame = Thumbnail::from_rgba(width, height, &repainted);
+        assert!(detector.is_changed(&frame));
+        assert!((detector.reference.as_ref().unwrap().difference(&frame) - 0.25).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Sentinel meaning "no session open".
+const NO_SESSION: i64 = 0;
+
+static CURRENT_SESSION: AtomicI64 = AtomicI64::new(NO_SESSION);
+
+/// Returns the server-side id of the currently open work session, if any.
+pub fn current_session_id() -> Option<i64> {
+    match CURRENT_SESSION.load(Ordering::Relaxed) {
+        NO_SESSION => None,
+        id => Some(id),
+    }
+}
+
+/// Records the session the server opened for us.
+pub fn set_current_session(id: i64) {
+    CURRENT_SESSION.store(id, Ordering::Relaxed);
+}
+
+/// Clears and returns the current session id.
+pub fn take_current_session() -> Option<i64> {
+    match CURRENT_SESSION.swap(NO_SESSION, Ordering::Relaxed) {
+        NO_SESSION => None,
+        id => Some(id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_id_round_trips() {
+        set_current_session(42);
+        assert_eq!(current_session_id(), Some(42));
+        assert_eq!(take_current_session(), Some(42));
+        assert_eq!(current_session_id(), None);
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::api::ActivitySummary;
+use crate::interval::{interval_bounds, interval_id_at};
+
+/// Input the summarizer rolls up
+#[derive(Debug, Clone, Copy)]
+pub enum SummaryEvent<'a> {
+    /// A different app came to the foreground
+    AppSwitch(&'a str),
+    Click,
+    Keypress,
+}
+
+/// Counters for the interval in progress
+#[derive(Debug)]
+struct Rollup {
+    interval_id: u64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    foreground: HashMap<String, Duration>,
+    app_switches: u32,
+    clicks: u32,
+    keypresses: u32,
+    active: Duration,
+}
+
+impl Rollup {
+    fn new(interval_id: u64) -> Self {
+        let (start, end) = interval_bounds(interval_id);
+        Self {
+            interval_id,
+            start,
+            end,
+            foreground: HashMap::new(),
+            app_switches: 0,
+            clicks: 0,
+            keypresses: 0,
+            active: Duration::zero(),
+        }
+    }
+
+    /// Length of `[from, until)` that falls inside this interval
+    fn overlap(&self, from: DateTime<Utc>, until: DateTime<Utc>) -> Duration {
+        (until.min(self.end) - from.max(self.start)).max(Duration::zero())
+    }
+}
+
+/// Rolls raw activity up into one `ActivitySummary` per 5-minute interval.
+///
+/// Foreground time is credited to the app between switches; the user counts as
+/// active from each click, keypress or switch until `idle_grace` passes without
+/// another one.
+#[derive(Debug)]
+pub struct Summarizer {
+    idle_grace: Duration,
+    current: Option<Rollup>,
+    finished: Vec<ActivitySummary>,
+    /// Frontmost app and when it came to the front
+    foreground: Option<(String, DateTime<Utc>)>,
+    /// Span the user counts as active for, extended by each event
+    active: Option<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl Summarizer {
+    pub fn new(idle_grace: Duration) -> Self {
+        Self {
+            idle_grace,
+            current: None,
+            finished: Vec::new(),
+            foreground: None,
+            active: None,
+        }
+    }
+
+    /// Counts one event at `now`.
+    pub fn record(&mut self, now: DateTime<Utc>, event: SummaryEvent<'_>) {
+        self.roll_over(now);
+        let rollup = self
+            .current
+            .get_or_insert_with(|| Rollup::new(interval_id_at(now)));
+
+        match event {
+            SummaryEvent::AppSwitch(app) => {
+                if let Some((prev, since)) = self.foreground.take() {
+                    let spent = rollup.overlap(since, now);
+                    *rollup.foreground.entry(prev).or_insert_with(Duration::zero) += spent;
+                }
+                self.foreground = Some((app.to_string(), now));
+                rollup.app_switches += 1;
+            }
+            SummaryEvent::Click => rollup.clicks += 1,
+            SummaryEvent::Keypress => rollup.keypresses += 1,
+        }
+
+        let until = now + self.idle_grace;
+        self.active = match self.active {
+            Some((from, prev_until)) if now <= prev_until => Some((from, until)),
+            Some((from, prev_until)) => {
+                rollup.active += rollup.overlap(from, prev_until);
+                Some((now, until))
+            }
+            None => Some((now, until)),
+        };
+    }
+
+    /// Returns the summaries of intervals that ended by `now`.
+    pub fn take_finished(&mut self, now: DateTime<Utc>) -> Vec<ActivitySummary> {
+        self.roll_over(now);
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Ends the interval in progress at `now` and returns everything pending.
+    /// Used on shutdown, when the interval won't be continued.
+    pub fn take_all(&mut self, now: DateTime<Utc>) -> Vec<ActivitySummary> {
+        self.roll_over(now);
+        if let Some(mut rollup) = self.current.take() {
+            rollup.end = now.clamp(rollup.start, rollup.end);
+            self.close(rollup);
+        }
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Puts back summaries that failed to upload so the next flush retries them.
+    pub fn requeue(&mut self, mut summaries: Vec<ActivitySummary>) {
+        summaries.append(&mut self.finished);
+        self.finished = summaries;
+    }
+
+    fn roll_over(&mut self, now: DateTime<Utc>) {
+        if self.current.as_ref().is_some_and(|r| now >= r.end) {
+            let rollup = self.current.take().expect("checked above");
+            self.close(rollup);
+        }
+    }
+
+    fn close(&mut self, mut rollup: Rollup) {
+        if let Some((app, since)) = &self.foreground {
+            let spent = rollup.overlap(*since, rollup.end);
+            *rollup
+                .foreground
+                .entry(app.clone())
+                .or_insert_with(Duration::zero) += spent;
+        }
+        if let Some((from, until)) = self.active {
+            rollup.active += rollup.overlap(from, until);
+        }
+
+        let period = (rollup.end - rollup.start).num_seconds().max(0);
+        let top_app = rollup
+            .foreground
+            .iter()
+            .filter(|(_, spent)| **spent > Duration::zero())
+            .max_by(|(a_app, a), (b_app, b)| a.cmp(b).then_with(|| b_app.cmp(a_app)))
+            .map(|(app, _)| app.clone());
+
+        self.finished.push(ActivitySummary {
+            interval_id: rollup.interval_id,
+            session_id: crate::session::current_session_id(),
+            period_start: rollup.start,
+            period_end: rollup.end,
+            top_app,
+            app_switches: rollup.app_switches,
+            clicks: rollup.clicks,
+            keypresses: rollup.keypresses,
+            active_secs: rollup.active.num_seconds().clamp(0, period) as u32,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn rolls_up_each_interval() {
+        // 10:00 UTC is an interval boundary
+        let start = Utc.with_ymd_and_hms(2026, 2, 20, 10, 0, 0).unwrap();
+        let at = |secs| start + Duration::seconds(secs);
+        let mut summarizer = Summarizer::new(Duration::seconds(60));
+
+        summarizer.record(at(0), SummaryEvent::AppSwitch("Terminal"));
+        summarizer.record(at(10), SummaryEvent::Keypress);
+        summarizer.record(at(40), SummaryEvent::AppSwitch("Code"));
+        summarizer.record(at(50), SummaryEvent::Click);
+        // Idle from 1:50 until 4:30
+        summarizer.record(at(270), SummaryEvent::Click);
+
+        assert!(summarizer.take_finished(at(299)).is_empty());
+        let done = summarizer.take_finished(at(300));
+        assert_eq!(done.len(), 1);
+        let summary = &done[0];
+        assert_eq!(summary.interval_id, interval_id_at(start));
+        assert_eq!(summary.period_end, at(300));
+        assert_eq!(summary.top_app.as_deref(), Some("Code"));
+        assert_eq!(summary.app_switches, 2);
+        assert_eq!(summary.clicks, 2);
+        assert_eq!(summary.keypresses, 1);
+        // 0:00-1:50 plus 4:30-5:00
+        assert_eq!(summary.active_secs, 110 + 30);
+
+        // The next interval picks up the trailing active span and foreground app
+        summarizer.record(at(320), SummaryEvent::Keypress);
+        let rest = summarizer.take_all(at(350));
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].period_start, at(300));
+        assert_eq!(rest[0].period_end, at(350));
+        assert_eq!(rest[0].top_app.as_deref(), Some("Code"));
+        assert_eq!(rest[0].app_switches, 0);
+        assert_eq!(rest[0].active_secs, 50);
+    }
+}
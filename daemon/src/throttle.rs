@@ -0,0 +1,100 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+use crate::api::QuotaRemaining;
+
+/// How long to wait before retrying after a storage quota (402) rejection.
+/// Waiting doesn't clear it, so only check back occasionally.
+const STORAGE_FULL_RECHECK: Duration = Duration::from_secs(60 * 60);
+
+struct Throttle {
+    /// No captures or uploads until then (quota rejected by the server)
+    paused_until: Option<Instant>,
+    /// Minimum spacing between screenshots so the daily quota lasts until reset
+    min_capture_spacing: Option<Duration>,
+    last_capture: Option<Instant>,
+}
+
+static THROTTLE: Mutex<Throttle> = Mutex::new(Throttle {
+    paused_until: None,
+    min_capture_spacing: None,
+    last_capture: None,
+});
+
+fn with_throttle<T>(f: impl FnOnce(&mut Throttle) -> T) -> T {
+    let mut throttle = THROTTLE.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut throttle)
+}
+
+/// Spread the remaining daily captures evenly until the quota resets.
+fn spacing_for(quota: &QuotaRemaining) -> Option<Duration> {
+    let captures = quota.captures?;
+    let until_reset = (quota.resets_at - Utc::now()).to_std().ok()?;
+    if captures == 0 {
+        return Some(until_reset);
+    }
+    Some(until_reset / u32::try_from(captures).unwrap_or(u32::MAX))
+}
+
+/// Records the quota the server reported after a successful upload.
+pub fn record_quota(quota: &QuotaRemaining) {
+    let spacing = spacing_for(quota);
+    with_throttle(|t| {
+        t.paused_until = None;
+        t.min_capture_spacing = spacing;
+    });
+}
+
+/// Stops capturing and uploading after the server rejected a batch for quota.
+/// `retry_after` is None when waiting won't clear it (storage quota).
+pub fn pause_for_quota(retry_after: Option<Duration>) {
+    let wait = retry_after.unwrap_or(STORAGE_FULL_RECHECK);
+    with_throttle(|t| t.paused_until = Some(Instant::now() + wait));
+}
+
+/// Returns true while uploads should be held back.
+pub fn uploads_paused() -> bool {
+    with_throttle(|t| t.paused_until.is_some_and(|until| Instant::now() < until))
+}
+
+/// Returns true if a screenshot may be taken now, and counts it if so.
+pub fn try_capture() -> bool {
+    let now = Instant::now();
+    with_throttle(|t| {
+        if t.paused_until.is_some_and(|until| now < until) {
+            return false;
+        }
+        if let (Some(spacing), Some(last)) = (t.min_capture_spacing, t.last_capture) {
+            if now.duration_since(last) < spacing {
+                return false;
+            }
+        }
+        t.last_capture = Some(now);
+        true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quota_spacing_spreads_captures_until_reset() {
+        let quota = QuotaRemaining {
+            captures: Some(100),
+            bytes: None,
+            storage_bytes: None,
+            resets_at: Utc::now() + chrono::Duration::seconds(1000),
+        };
+        let spacing = spacing_for(&quota).expect("spacing");
+        assert!(spacing > Duration::from_secs(9) && spacing <= Duration::from_secs(10));
+
+        let unlimited = QuotaRemaining {
+            captures: None,
+            ..quota
+        };
+        assert_eq!(spacing_for(&unlimited), None);
+    }
+}
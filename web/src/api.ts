@@ -29,6 +29,12 @@ export const apiBaseForWs = {
 
 // OAuth state storage key for CSRF protection
 const OAUTH_STATE_KEY = 'cleo_oauth_state';
+// Which provider the pending OAuth redirect belongs to
+const OAUTH_PROVIDER_KEY = 'cleo_oauth_provider';
+const CSRF_COOKIE = 'csrf_token';
+const CSRF_HEADER = 'X-CSRF-Token';
+const SAFE_METHODS = new Set(['GET', 'HEAD', 'OPTIONS']);
+const THREAD_PUBLISH_POLL_MS = 2000;
 
 // ============== Zod Schemas for Runtime Validation ==============
 
@@ -43,6 +49,7 @@ const UserSchema = z.object({
   username: z.string(),
   display_name: z.string().nullable(),
   created_at: z.string(),
+  twitter_connection: z.enum(['connected', 'not_connected', 'needs_reconnect']),
 });
 
 const PendingTweetSchema = z.object({
@@ -107,10 +114,20 @@ const CreateThreadResponseSchema = z.object({
 
 const PostThreadResponseSchema = z.object({
   status: z.string(),
+  status_url: z.string(),
+});
+
+const ThreadPublishStatusSchema = z.object({
+  status: z.string(),
+  posted: z.number(),
+  total: z.number(),
   tweets: z.array(z.object({
     id: z.number(),
-    twitter_id: z.string(),
+    position: z.number().nullable(),
+    status: z.string(),
+    twitter_id: z.string().nullable(),
     reply_to: z.string().nullable(),
+    error: z.string().nullable(),
   })),
 });
 
@@ -121,6 +138,25 @@ const CaptureItemSchema = z.object({
   captured_at: z.string(),
   thumbnail_url: z.string().nullable(),
   thumbnail_ready: z.boolean(),
+  device_id: z.number().nullable().optional(),
+  session_id: z.number().nullable().optional(),
+  excluded_from_agent: z.boolean().optional(),
+  duration_secs: z.number().nullable().optional(),
+  width: z.number().nullable().optional(),
+  height: z.number().nullable().optional(),
+  codec: z.string().nullable().optional(),
+  file_size_bytes: z.number().nullable().optional(),
+});
+
+const BatchThumbnailSchema = z.object({
+  id: z.number(),
+  ready: z.boolean(),
+  url: z.string().nullable(),
+  preview: z.string().nullable(),
+});
+
+const BatchThumbnailsResponseSchema = z.object({
+  thumbnails: z.array(BatchThumbnailSchema),
 });
 
 const BrowseCapturesResponseSchema = z.object({
@@ -166,6 +202,12 @@ const ExchangeTokenResponseSchema = z.object({
   username: z.string(),
 });
 
+const LoginProviderSchema = z.enum(['twitter', 'github', 'google', 'email']);
+
+const LoginProvidersResponseSchema = z.object({
+  providers: z.array(LoginProviderSchema),
+});
+
 const ApiTokenResponseSchema = z.object({
   api_token: z.string(),
 });
@@ -193,10 +235,56 @@ const VapidPublicKeyResponseSchema = z.object({
   vapid_public_key: z.string(),
 });
 
+const WorkSessionSchema = z.object({
+  id: z.number(),
+  device: z.string().nullable(),
+  started_at: z.string(),
+  ended_at: z.string().nullable(),
+  app_summary: z.array(z.object({ application: z.string(), switches: z.number() })),
+  capture_count: z.number(),
+});
+
+const ListSessionsResponseSchema = z.object({
+  sessions: z.array(WorkSessionSchema),
+  total: z.number(),
+  has_more: z.boolean(),
+});
+
+const DeviceSchema = z.object({
+  id: z.number(),
+  name: z.string().nullable(),
+  created_at: z.string(),
+  last_seen_at: z.string(),
+  revoked_at: z.string().nullable(),
+  integrity: z.string().nullable(),
+  capture_count: z.number(),
+  last_capture_at: z.string().nullable(),
+});
+
+const ListDevicesResponseSchema = z.object({
+  devices: z.array(DeviceSchema),
+});
+
+const DataExportSchema = z.object({
+  id: z.number(),
+  status: z.enum(['pending', 'building', 'completed', 'failed', 'expired']),
+  size_bytes: z.number().nullable(),
+  error: z.string().nullable(),
+  requested_at: z.string(),
+  completed_at: z.string().nullable(),
+  expires_at: z.string().nullable(),
+  download_url: z.string().nullable(),
+});
+
+const ListExportsResponseSchema = z.object({
+  exports: z.array(DataExportSchema),
+});
+
 // ============== TypeScript Types (inferred from Zod) ==============
 
 export type VideoClip = z.infer<typeof VideoClipSchema>;
 export type User = z.infer<typeof UserSchema>;
+export type LoginProvider = z.infer<typeof LoginProviderSchema>;
 export type PendingTweet = z.infer<typeof PendingTweetSchema>;
 export type PostTweetResponse = z.infer<typeof PostTweetResponseSchema>;
 export type ThreadStatus = z.infer<typeof ThreadStatusSchema>;
@@ -204,9 +292,10 @@ export type TweetThread = z.infer<typeof TweetThreadSchema>;
 export type ThreadTweet = z.infer<typeof ThreadTweetSchema>;
 export type ThreadWithTweets = z.infer<typeof ThreadWithTweetsSchema>;
 export type CreateThreadResponse = z.infer<typeof CreateThreadResponseSchema>;
-export type PostThreadResponse = z.infer<typeof PostThreadResponseSchema>;
+export type ThreadPublishStatus = z.infer<typeof ThreadPublishStatusSchema>;
 export type CaptureItem = z.infer<typeof CaptureItemSchema>;
 export type BrowseCapturesResponse = z.infer<typeof BrowseCapturesResponseSchema>;
+export type BatchThumbnail = z.infer<typeof BatchThumbnailSchema>;
 export type ContentItem = z.infer<typeof ContentItemSchema>;
 export type ContentResponse = z.infer<typeof ContentResponseSchema>;
 export type PushSubscription = z.infer<typeof PushSubscriptionSchema>;
@@ -214,9 +303,14 @@ export type VapidPublicKeyResponse = z.infer<typeof VapidPublicKeyResponseSchema
 export type Persona = z.infer<typeof PersonaSchema>;
 export type UserPersona = z.infer<typeof UserPersonaSchema>;
 export type NudgesResponse = z.infer<typeof NudgesResponseSchema>;
+export type WorkSession = z.infer<typeof WorkSessionSchema>;
+export type ListSessionsResponse = z.infer<typeof ListSessionsResponseSchema>;
+export type Device = z.infer<typeof DeviceSchema>;
+export type DataExport = z.infer<typeof DataExportSchema>;
 
-// WebSocket publish progress messages
+// Publish job progress (GET /publish-jobs/:id/events, also sent on the publish WebSocket)
 const PublishProgressSchema = z.discriminatedUnion('type', [
+  z.object({ type: z.literal('queued') }),
   z.object({ type: z.literal('uploading'), segment: z.number(), total: z.number(), percent: z.number() }),
   z.object({ type: z.literal('processing') }),
   z.object({ type: z.literal('posting') }),
@@ -226,6 +320,14 @@ const PublishProgressSchema = z.discriminatedUnion('type', [
 
 export type PublishProgress = z.infer<typeof PublishProgressSchema>;
 
+const PublishJobResponseSchema = z.object({
+  job_id: z.string(),
+  events_url: z.string(),
+  status_url: z.string(),
+});
+
+const PUBLISH_EVENTS = ['queued', 'uploading', 'processing', 'posting', 'complete', 'error'] as const;
+
 export interface CreateThreadRequest {
   title?: string;
   tweet_ids: number[];
@@ -235,6 +337,7 @@ export interface BrowseCapturesParams {
   start?: string;
   end?: string;
   type?: string;
+  device_id?: number;
   limit?: number;
   offset?: number;
   include_ids?: number[];
@@ -247,12 +350,46 @@ export interface GetContentParams {
   status?: 'pending' | 'posted';
 }
 
+const ApiErrorBodySchema = z.object({
+  error: z.string(),
+  message: z.string(),
+});
+
+/** Error from an API call, carrying the server's machine-readable code when present */
+export class ApiRequestError extends Error {
+  constructor(
+    message: string,
+    readonly status: number,
+    readonly code: string | null,
+  ) {
+    super(message);
+    this.name = 'ApiRequestError';
+  }
+}
+
+/** Read the `{error, message}` body without consuming the response */
+async function readErrorBody(res: Response): Promise<z.infer<typeof ApiErrorBodySchema> | null> {
+  try {
+    return ApiErrorBodySchema.parse(await res.clone().json());
+  } catch {
+    return null;
+  }
+}
+
+async function toApiError(res: Response, fallback: string): Promise<ApiRequestError> {
+  const body = await readErrorBody(res);
+  return new ApiRequestError(body?.message ?? fallback, res.status, body?.error ?? null);
+}
+
 class ApiClient {
   private refreshTimer: number | null = null;
   private refreshPromise: Promise<boolean> | null = null;
   private onUnauthorized: (() => void) | null = null;
   private unauthorizedFired = false;
   private vapidPublicKey: string | null = null;
+  // CSRF token from the last login/refresh response, for when the API's cookies
+  // aren't readable from this origin
+  private csrfToken: string | null = null;
 
   // Cache for capture URLs (signed URLs expire in 15 minutes, cache for 10)
   private captureUrlCache = new Map<number, { data: { url: string; content_type: string }; expires: number }>();
@@ -300,6 +437,7 @@ class ApiClient {
         method: 'POST',
         credentials: 'include',
       });
+      this.storeCsrfToken(res);
 
       if (!res.ok) {
         // Refresh failed - session expired. Only fire callback once.
@@ -321,27 +459,54 @@ class ApiClient {
     }
   }
 
+  private storeCsrfToken(res: Response): void {
+    const token = res.headers.get(CSRF_HEADER);
+    if (token) this.csrfToken = token;
+  }
+
+  /**
+   * Header echoing the CSRF token for state-changing requests. Prefers the
+   * cookie (same-origin deployments) over the token from the last response.
+   */
+  private csrfHeaders(method: string | undefined): Record<string, string> {
+    if (SAFE_METHODS.has((method ?? 'GET').toUpperCase())) return {};
+    const fromCookie = document.cookie
+      .split('; ')
+      .find((c) => c.startsWith(`${CSRF_COOKIE}=`))
+      ?.slice(CSRF_COOKIE.length + 1);
+    const token = fromCookie || this.csrfToken;
+    return token ? { [CSRF_HEADER]: token } : {};
+  }
+
   /**
    * Wrapper for fetch that handles 401 errors by attempting refresh.
    * Multiple concurrent 401s will all wait for the same refresh operation.
    */
   private async fetchWithAuth(url: string, options: RequestInit = {}): Promise<Response> {
-    const opts: RequestInit = {
+    // Built per attempt: a refresh issues a new CSRF token
+    const opts = (): RequestInit => ({
       ...options,
       credentials: 'include',
       headers: {
         'Content-Type': 'application/json',
+        ...this.csrfHeaders(options.method),
         ...options.headers,
       },
-    };
+    });
 
-    let res = await fetch(url, opts);
+    let res = await fetch(url, opts());
 
-    // If unauthorized, try to refresh and retry once
-    if (res.status === 401) {
+    // If unauthorized, try to refresh and retry once. A Twitter reauth error
+    // means the session is fine, so refreshing it wouldn't help. A missing CSRF
+    // token (sessions from before it existed) is also fixed by a refresh.
+    const error = res.status === 401 || res.status === 403 ? (await readErrorBody(res))?.error : undefined;
+    const retry =
+      (res.status === 401 && error !== 'twitter_reauth_required') ||
+      (res.status === 403 && error === 'csrf_token_invalid');
+    if (retry) {
       const refreshed = await this.silentRefresh();
       if (refreshed) {
-        res = await fetch(url, opts);
+        res = await fetch(url, opts());
       }
     }
 
@@ -358,7 +523,7 @@ class ApiClient {
     schema: z.ZodType<T>
   ): Promise<T> {
     const res = await this.fetchWithAuth(url, options);
-    if (!res.ok) throw new Error(errorMessage);
+    if (!res.ok) throw await toApiError(res, errorMessage);
     const data = await res.json();
     return schema.parse(data);
   }
@@ -368,7 +533,7 @@ class ApiClient {
    */
   private async fetchJsonRaw<T>(url: string, options: RequestInit = {}, errorMessage: string): Promise<T> {
     const res = await this.fetchWithAuth(url, options);
-    if (!res.ok) throw new Error(errorMessage);
+    if (!res.ok) throw await toApiError(res, errorMessage);
     return res.json();
   }
 
@@ -377,7 +542,7 @@ class ApiClient {
    */
   private async fetchVoid(url: string, options: RequestInit = {}, errorMessage: string): Promise<void> {
     const res = await this.fetchWithAuth(url, options);
-    if (!res.ok) throw new Error(errorMessage);
+    if (!res.ok) throw await toApiError(res, errorMessage);
   }
 
   async logout(): Promise<void> {
@@ -400,11 +565,65 @@ class ApiClient {
     }
   }
 
-  async getAuthUrl(): Promise<{ url: string }> {
-    const res = await fetch(`${API_BASE}/auth/twitter`);
+  /** Login options the server offers; X is always first */
+  async getLoginProviders(): Promise<LoginProvider[]> {
+    const res = await fetch(`${API_BASE}/auth/providers`);
+    if (!res.ok) throw new Error('Failed to get login providers');
+    const data = await res.json();
+    return LoginProvidersResponseSchema.parse(data).providers;
+  }
+
+  /** Email a one-time sign-in link; resolves once it's sent */
+  async requestEmailLink(email: string): Promise<void> {
+    const res = await fetch(`${API_BASE}/auth/email/link`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ email }),
+    });
+    if (!res.ok) throw await toApiError(res, 'Failed to send sign-in link');
+  }
+
+  /** Trade the token from a sign-in link for a session (sets httpOnly cookies) */
+  async verifyEmailLink(token: string): Promise<{ username: string }> {
+    const res = await fetch(`${API_BASE}/auth/email/verify`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      credentials: 'include',
+      body: JSON.stringify({ token }),
+    });
+    if (!res.ok) throw await toApiError(res, 'Sign-in link is invalid or expired');
+    this.storeCsrfToken(res);
+    const data = await res.json();
+    return ExchangeTokenResponseSchema.parse(data);
+  }
+
+  async getAuthUrl(provider: Exclude<LoginProvider, 'email'> = 'twitter'): Promise<{ url: string }> {
+    const path = provider === 'twitter' ? '/auth/twitter' : `/auth/login/${provider}`;
+    const res = await fetch(`${API_BASE}${path}`);
     if (!res.ok) throw new Error('Failed to get auth URL');
     const data = await res.json();
-    const parsed = AuthUrlResponseSchema.parse(data);
+    return this.rememberOAuthRedirect(AuthUrlResponseSchema.parse(data), provider);
+  }
+
+  /**
+   * Start linking X to the signed-in account (also used to reconnect it).
+   * The redirect comes back through exchangeToken like a login.
+   */
+  async connectTwitter(): Promise<{ url: string }> {
+    const parsed = await this.fetchJson(
+      `${API_BASE}/me/integrations/twitter`,
+      { method: 'POST' },
+      'Failed to start connecting X',
+      AuthUrlResponseSchema
+    );
+    return this.rememberOAuthRedirect(parsed, 'twitter');
+  }
+
+  private rememberOAuthRedirect(
+    parsed: { url: string },
+    provider: LoginProvider
+  ): { url: string } {
+    sessionStorage.setItem(OAUTH_PROVIDER_KEY, provider);
 
     // Extract and store state from URL for CSRF validation on callback
     // The state parameter is in the URL returned by the backend
@@ -448,13 +667,18 @@ class ApiClient {
       throw new Error('OAuth state mismatch - possible CSRF attack');
     }
 
-    const res = await fetch(`${API_BASE}/auth/twitter/token`, {
+    const provider = sessionStorage.getItem(OAUTH_PROVIDER_KEY) ?? 'twitter';
+    sessionStorage.removeItem(OAUTH_PROVIDER_KEY);
+    const path = provider === 'twitter' ? '/auth/twitter/token' : `/auth/login/${provider}/token`;
+
+    const res = await fetch(`${API_BASE}${path}`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       credentials: 'include',
       body: JSON.stringify({ code, state }),
     });
     if (!res.ok) throw new Error('Failed to exchange token');
+    this.storeCsrfToken(res);
     const data = await res.json();
     return ExchangeTokenResponseSchema.parse(data);
   }
@@ -477,64 +701,63 @@ class ApiClient {
     return this.fetchJson(`${API_BASE}/tweets`, {}, 'Failed to get tweets', z.array(PendingTweetSchema));
   }
 
-  async postTweet(id: number): Promise<PostTweetResponse> {
-    return this.fetchJson(`${API_BASE}/tweets/${id}/publish`, { method: 'POST' }, 'Failed to post tweet', PostTweetResponseSchema);
+  /** Pass the same idempotencyKey when retrying so the tweet is only posted once */
+  async postTweet(id: number, idempotencyKey: string = crypto.randomUUID()): Promise<PostTweetResponse> {
+    return this.fetchJson(
+      `${API_BASE}/tweets/${id}/publish`,
+      { method: 'POST', headers: { 'Idempotency-Key': idempotencyKey } },
+      'Failed to post tweet',
+      PostTweetResponseSchema
+    );
   }
 
   /**
-   * Post a tweet via WebSocket with progress updates
+   * Publish a tweet as a background job and follow its progress over SSE
    * @param id Tweet ID
    * @param onProgress Callback for progress updates
    * @returns Promise that resolves with tweet_id and text on success
    */
-  postTweetWithProgress(
+  async postTweetWithProgress(
     id: number,
     onProgress: (progress: PublishProgress) => void
   ): Promise<PostTweetResponse> {
-    return new Promise((resolve, reject) => {
-      // Build WebSocket URL - uses cookies for auth
-      const wsPath = normalizeApiPath(`${API_BASE_PATH}/tweets/${id}/publish/ws`);
-      const wsUrl = `${apiBaseForWs.protocol}//${apiBaseForWs.host}${wsPath}`;
+    const job = await this.fetchJson(
+      `${API_BASE}/tweets/${id}/publish?async=true`,
+      { method: 'POST' },
+      'Failed to post tweet',
+      PublishJobResponseSchema
+    );
 
-      const ws = new WebSocket(wsUrl);
+    return new Promise((resolve, reject) => {
+      // Cookies authenticate the stream
+      const events = new EventSource(`${API_BASE}${job.events_url}`, { withCredentials: true });
+
+      const onMessage = (event: MessageEvent) => {
+        const result = PublishProgressSchema.safeParse(JSON.parse(event.data));
+        if (!result.success) {
+          console.error('Invalid publish progress event:', result.error);
+          return;
+        }
 
-      ws.onopen = () => {
-        // Connection opened, publish will start automatically
-      };
+        const msg = result.data;
+        onProgress(msg);
 
-      ws.onmessage = (event) => {
-        try {
-          const raw = JSON.parse(event.data);
-          const result = PublishProgressSchema.safeParse(raw);
-
-          if (!result.success) {
-            console.error('Invalid WebSocket message format:', result.error);
-            return;
-          }
-
-          const msg = result.data;
-          onProgress(msg);
-
-          if (msg.type === 'complete') {
-            ws.close();
-            resolve({ tweet_id: msg.tweet_id, text: msg.text });
-          } else if (msg.type === 'error') {
-            ws.close();
-            reject(new Error(msg.message));
-          }
-        } catch (e) {
-          console.error('Failed to parse WebSocket message:', e);
+        if (msg.type === 'complete') {
+          events.close();
+          resolve({ tweet_id: msg.tweet_id, text: msg.text });
+        } else if (msg.type === 'error') {
+          events.close();
+          reject(new Error(msg.message));
         }
       };
+      for (const name of PUBLISH_EVENTS) {
+        events.addEventListener(name, onMessage);
+      }
 
-      ws.onerror = () => {
-        reject(new Error('WebSocket connection failed'));
-      };
-
-      ws.onclose = (event) => {
-        if (!event.wasClean && event.code !== 1000) {
-          reject(new Error('WebSocket closed unexpectedly'));
-        }
+      events.onerror = () => {
+        // The server ends the stream after complete/error; anything else is a lost connection
+        if (events.readyState !== EventSource.CLOSED) events.close();
+        reject(new Error('Lost connection while publishing'));
       };
     });
   }
@@ -575,11 +798,31 @@ class ApiClient {
     return data;
   }
 
+  /** Thumbnail URLs (or inline previews) for up to 100 captures in one request */
+  async getThumbnails(ids: number[], inline = false): Promise<BatchThumbnail[]> {
+    const data = await this.fetchJson(
+      `${API_BASE}/captures/thumbnails`,
+      { method: 'POST', body: JSON.stringify({ ids, inline }) },
+      'Failed to load thumbnails',
+      BatchThumbnailsResponseSchema
+    );
+    return data.thumbnails;
+  }
+
+  async setCaptureExcluded(captureId: number, excluded: boolean): Promise<void> {
+    return this.fetchVoid(
+      `${API_BASE}/captures/${captureId}/exclude`,
+      { method: 'PUT', body: JSON.stringify({ excluded }) },
+      'Failed to update capture'
+    );
+  }
+
   async browseCaptures(params: BrowseCapturesParams = {}): Promise<BrowseCapturesResponse> {
     const query = new URLSearchParams();
     if (params.start) query.set('start', params.start);
     if (params.end) query.set('end', params.end);
     if (params.type) query.set('type', params.type);
+    if (params.device_id) query.set('device_id', params.device_id.toString());
     if (params.limit) query.set('limit', params.limit.toString());
     if (params.offset) query.set('offset', params.offset.toString());
     if (params.include_ids?.length) query.set('include_ids', params.include_ids.join(','));
@@ -588,6 +831,37 @@ class ApiClient {
     return this.fetchJson(url, {}, 'Failed to browse captures', BrowseCapturesResponseSchema);
   }
 
+  async getSessions(params: { limit?: number; offset?: number } = {}): Promise<ListSessionsResponse> {
+    const query = new URLSearchParams();
+    if (params.limit) query.set('limit', params.limit.toString());
+    if (params.offset) query.set('offset', params.offset.toString());
+
+    const url = `${API_BASE}/sessions${query.toString() ? '?' + query.toString() : ''}`;
+    return this.fetchJson(url, {}, 'Failed to load sessions', ListSessionsResponseSchema);
+  }
+
+  async getDevices(): Promise<Device[]> {
+    const data = await this.fetchJson(`${API_BASE}/me/devices`, {}, 'Failed to load devices', ListDevicesResponseSchema);
+    return data.devices;
+  }
+
+  async revokeDevice(id: number): Promise<void> {
+    return this.fetchVoid(`${API_BASE}/me/devices/${id}`, { method: 'DELETE' }, 'Failed to revoke device');
+  }
+
+  async requestDataExport(): Promise<DataExport> {
+    return this.fetchJson(`${API_BASE}/me/export`, { method: 'POST' }, 'Failed to request data export', DataExportSchema);
+  }
+
+  async getDataExports(): Promise<DataExport[]> {
+    const data = await this.fetchJson(`${API_BASE}/me/export`, {}, 'Failed to load data exports', ListExportsResponseSchema);
+    return data.exports;
+  }
+
+  async getDataExport(id: number): Promise<DataExport> {
+    return this.fetchJson(`${API_BASE}/me/export/${id}`, {}, 'Failed to load data export', DataExportSchema);
+  }
+
   async updateTweetCollateral(
     tweetId: number,
     collateral: { text?: string; image_capture_ids?: number[]; video_clip?: VideoClip | null }
@@ -649,13 +923,41 @@ class ApiClient {
     );
   }
 
-  async postThread(threadId: number): Promise<PostThreadResponse> {
-    return this.fetchJson(
+  /**
+   * Publish a thread and wait for the background job to finish.
+   * Pass the same idempotencyKey when retrying so the thread is only posted once.
+   */
+  async postThread(
+    threadId: number,
+    idempotencyKey: string = crypto.randomUUID(),
+    onProgress?: (status: ThreadPublishStatus) => void
+  ): Promise<ThreadPublishStatus> {
+    await this.fetchJson(
       `${API_BASE}/threads/${threadId}/publish`,
-      { method: 'POST' },
+      { method: 'POST', headers: { 'Idempotency-Key': idempotencyKey } },
       'Failed to post thread',
       PostThreadResponseSchema
     );
+
+    for (;;) {
+      const status = await this.getThreadPublishStatus(threadId);
+      onProgress?.(status);
+      if (status.status === 'partial_failed') {
+        const failed = status.tweets.find((tweet) => tweet.error);
+        throw new Error(failed?.error ?? 'Thread was only partly posted');
+      }
+      if (status.status !== 'posting') return status;
+      await new Promise((resolve) => setTimeout(resolve, THREAD_PUBLISH_POLL_MS));
+    }
+  }
+
+  async getThreadPublishStatus(threadId: number): Promise<ThreadPublishStatus> {
+    return this.fetchJson(
+      `${API_BASE}/threads/${threadId}/publish-status`,
+      {},
+      'Failed to get thread publish status',
+      ThreadPublishStatusSchema
+    );
   }
 
   // Content endpoint - unified view of all content
@@ -745,6 +1047,15 @@ class ApiClient {
     );
   }
 
+  /** Rewrite a draft in another language (a code from GET /me/language) */
+  async translateTweet(tweetId: number, language: string): Promise<{ text: string }> {
+    return this.fetchJsonRaw(
+      `${API_BASE}/tweets/${tweetId}/translate`,
+      { method: 'POST', body: JSON.stringify({ language }) },
+      'Failed to translate tweet'
+    );
+  }
+
   // Agent run
 
   async triggerAgentRun(): Promise<{ status: string; run_id: number | null }> {
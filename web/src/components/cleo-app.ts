@@ -42,6 +42,22 @@ export class CleoApp extends LitElement {
     const params = new URLSearchParams(window.location.search);
     const code = params.get('code');
     const stateParam = params.get('state');
+    const emailToken = params.get('email_token');
+
+    if (emailToken) {
+      // Emailed sign-in link
+      try {
+        await api.verifyEmailLink(emailToken);
+        this.isLoggedIn = true;
+        this.authError = null;
+      } catch (e) {
+        console.error('Email sign-in failed:', e);
+        this.authError = 'This sign-in link is invalid or has expired.';
+      }
+      window.history.replaceState({}, '', '/');
+      this.loading = false;
+      return;
+    }
 
     if (code && stateParam) {
       // OAuth callback - exchange code for session (sets httpOnly cookies)
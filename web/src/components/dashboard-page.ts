@@ -36,6 +36,8 @@ export class DashboardPage extends LitElement {
   private pushSetupInProgress = false;
   private queueItemKeys = new Set<string>();
   private queueInitialized = false;
+  // Draft to open on first load (?draft=<tweet id>, linked from the daemon's menu)
+  private requestedDraftId: number | null = null;
 
   async connectedCallback() {
     super.connectedCallback();
@@ -48,6 +50,10 @@ export class DashboardPage extends LitElement {
     if (requestedView === "queue" || requestedView === "sent") {
       this.viewMode = requestedView;
     }
+    const requestedDraft = Number(urlParams.get("draft"));
+    if (Number.isInteger(requestedDraft) && requestedDraft > 0) {
+      this.requestedDraftId = requestedDraft;
+    }
     await this.loadData();
     if (this.notificationPermission === "granted") {
       void this.setupPushNotifications();
@@ -106,6 +112,14 @@ export class DashboardPage extends LitElement {
     return `${item.type}-${item.thread.id}`;
   }
 
+  private takeRequestedDraftIndex(items: ContentItem[]): number {
+    const draftId = this.requestedDraftId;
+    if (draftId === null) return 0;
+    this.requestedDraftId = null;
+    const index = items.findIndex((item) => item.type === "tweet" && item.id === draftId);
+    return Math.max(0, index);
+  }
+
   private async processQueueNotifications(items: ContentItem[]) {
     if (this.viewMode !== "queue" || this.notificationPermission !== "granted" || !document.hidden) {
       this.queueItemKeys = new Set(items.map((item) => this.getItemKey(item)));
@@ -137,6 +151,16 @@ export class DashboardPage extends LitElement {
     this.queueItemKeys = nextKeys;
   }
 
+  private async reconnectTwitter() {
+    try {
+      const { url } = await api.connectTwitter();
+      window.location.href = url;
+    } catch (e) {
+      console.error("Failed to start reconnect:", e);
+      this.bulkActionError = e instanceof Error ? e.message : "Failed to start reconnect";
+    }
+  }
+
   private async requestNotificationPermission() {
     if (!("Notification" in window)) {
       this.notificationPermission = "unsupported";
@@ -349,7 +373,7 @@ export class DashboardPage extends LitElement {
         await this.processQueueNotifications(contentResponse.items);
       }
       this.content = contentResponse.items;
-      this.currentIndex = 0;
+      this.currentIndex = this.takeRequestedDraftIndex(contentResponse.items);
     } catch (e) {
       console.error("Failed to load data:", e);
       this.loadError = e instanceof Error ? e.message : "Failed to load data";
@@ -791,6 +815,28 @@ export class DashboardPage extends LitElement {
             `
           : ""}
 
+        ${this.user?.twitter_connection === "needs_reconnect"
+          ? html`
+              <div class="alert alert-warning mx-6 mt-2">
+                <span>X access was revoked or expired. Reconnect your account to keep publishing.</span>
+                <button class="btn btn-sm" @click=${this.reconnectTwitter}>
+                  Reconnect
+                </button>
+              </div>
+            `
+          : ""}
+
+        ${this.user?.twitter_connection === "not_connected"
+          ? html`
+              <div class="alert alert-info mx-6 mt-2">
+                <span>Connect your X account to publish from Cleo.</span>
+                <button class="btn btn-sm" @click=${this.reconnectTwitter}>
+                  Connect X
+                </button>
+              </div>
+            `
+          : ""}
+
         <!-- Stack-Based Layout (Grid + Scroll Container) -->
         <div class="flex flex-col lg:flex-row h-auto lg:h-[calc(100vh-65px)]">
           <!-- Left Sidebar - Toolbar -->
@@ -1,6 +1,6 @@
 import { LitElement, html } from 'lit';
 import { customElement, property, state } from 'lit/decorators.js';
-import { api } from '../api';
+import { api, type LoginProvider } from '../api';
 import { tailwindStyles } from '../styles/shared';
 
 @customElement('login-page')
@@ -10,12 +10,25 @@ export class LoginPage extends LitElement {
   @property({ type: String }) error: string | null = null;
   @state() loading = false;
   @state() authError: string | null = null;
+  @state() providers: LoginProvider[] = ['twitter'];
+  @state() email = '';
+  @state() emailSent = false;
 
-  async handleLogin() {
+  async connectedCallback() {
+    super.connectedCallback();
+    try {
+      this.providers = await api.getLoginProviders();
+    } catch (e) {
+      // X login still works without the list
+      console.error('Failed to get login providers:', e);
+    }
+  }
+
+  async handleLogin(provider: Exclude<LoginProvider, 'email'> = 'twitter') {
     this.loading = true;
     this.authError = null;
     try {
-      const { url } = await api.getAuthUrl();
+      const { url } = await api.getAuthUrl(provider);
       window.location.href = url;
     } catch (e) {
       console.error('Failed to get auth URL:', e);
@@ -24,6 +37,20 @@ export class LoginPage extends LitElement {
     }
   }
 
+  async handleEmailLogin(e: Event) {
+    e.preventDefault();
+    this.loading = true;
+    this.authError = null;
+    try {
+      await api.requestEmailLink(this.email);
+      this.emailSent = true;
+    } catch (e) {
+      console.error('Failed to send sign-in link:', e);
+      this.authError = e instanceof Error ? e.message : 'Failed to send sign-in link';
+    }
+    this.loading = false;
+  }
+
   render() {
     return html`
       <div class="hero min-h-screen">
@@ -44,7 +71,7 @@ export class LoginPage extends LitElement {
               : ''}
             <button
               class="btn btn-primary btn-lg"
-              @click=${this.handleLogin}
+              @click=${() => this.handleLogin('twitter')}
               ?disabled=${this.loading}
             >
               ${this.loading
@@ -56,6 +83,46 @@ export class LoginPage extends LitElement {
                     Sign in with X
                   `}
             </button>
+            ${this.providers.includes('github') || this.providers.includes('google')
+              ? html`<div class="flex flex-col gap-2 mt-4 items-center">
+                  ${this.providers.includes('github')
+                    ? html`<button
+                        class="btn btn-outline"
+                        @click=${() => this.handleLogin('github')}
+                        ?disabled=${this.loading}
+                      >
+                        Sign in with GitHub
+                      </button>`
+                    : ''}
+                  ${this.providers.includes('google')
+                    ? html`<button
+                        class="btn btn-outline"
+                        @click=${() => this.handleLogin('google')}
+                        ?disabled=${this.loading}
+                      >
+                        Sign in with Google
+                      </button>`
+                    : ''}
+                  <p class="text-sm opacity-70">Connect X afterwards to publish.</p>
+                </div>`
+              : ''}
+            ${this.providers.includes('email')
+              ? this.emailSent
+                ? html`<p class="mt-4">Check your inbox for a sign-in link for ${this.email}.</p>`
+                : html`<form class="join mt-4" @submit=${this.handleEmailLogin}>
+                    <input
+                      class="input input-bordered join-item"
+                      type="email"
+                      placeholder="you@example.com"
+                      required
+                      .value=${this.email}
+                      @input=${(e: Event) => (this.email = (e.target as HTMLInputElement).value)}
+                    />
+                    <button class="btn join-item" type="submit" ?disabled=${this.loading}>
+                      Email me a link
+                    </button>
+                  </form>`
+              : ''}
           </div>
         </div>
       </div>
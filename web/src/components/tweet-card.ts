@@ -53,7 +53,7 @@ export class TweetCard extends LitElement {
     this.error = null;
 
     try {
-      // Use WebSocket progress for media tweets (video or multiple images)
+      // Follow publish job progress for media tweets (video or multiple images)
       const hasMedia =
         this.tweet.video_clip || this.tweet.image_capture_ids.length > 0;
       if (hasMedia) {